@@ -3,6 +3,7 @@
 //! Benchmarks cover:
 //!   - Tool dispatch (XML parsing, native parsing)
 //!   - Memory store/recall cycles (SQLite backend)
+//!   - Recall re-ranking (time decay, boosts, MMR diversity)
 //!   - Agent turn cycle (full orchestration loop)
 //!
 //! Run: `cargo bench`
@@ -15,9 +16,10 @@ use std::sync::{Arc, Mutex};
 
 use zeroclaw::agent::agent::Agent;
 use zeroclaw::agent::dispatcher::{NativeToolDispatcher, ToolDispatcher, XmlToolDispatcher};
-use zeroclaw::config::MemoryConfig;
+use zeroclaw::config::{MemoryConfig, MemoryRankingConfig};
 use zeroclaw::memory;
-use zeroclaw::memory::{Memory, MemoryCategory};
+use zeroclaw::memory::ranking::{self, RecallContext};
+use zeroclaw::memory::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
 use zeroclaw::observability::{NoopObserver, Observer};
 use zeroclaw::providers::{ChatRequest, ChatResponse, Provider, ToolCall};
 use zeroclaw::tools::{Tool, ToolResult};
@@ -275,6 +277,95 @@ fn bench_memory_operations(c: &mut Criterion) {
     });
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Benchmark: Recall re-ranking
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn ranking_candidates(n: usize) -> Vec<MemoryEntry> {
+    let now = chrono::Utc::now();
+    (0..n)
+        .map(|i| MemoryEntry {
+            id: i.to_string(),
+            key: format!("key_{i}"),
+            // Every fourth entry repeats an earlier one so MMR has work to do.
+            content: format!(
+                "Content entry number {} about zeroclaw agent runtime",
+                if i % 4 == 0 { 0 } else { i }
+            ),
+            category: if i % 10 == 0 {
+                MemoryCategory::Core
+            } else {
+                MemoryCategory::Daily
+            },
+            timestamp: (now - chrono::Duration::days(i as i64 * 7)).to_rfc3339(),
+            session_id: None,
+            score: Some(1.0 - i as f64 / (n as f64 * 2.0)),
+            metadata: MemoryMetadata::default(),
+        })
+        .collect()
+}
+
+fn bench_memory_ranking(c: &mut Criterion) {
+    let config = MemoryRankingConfig::default();
+    let candidates = ranking_candidates(15);
+
+    c.bench_function("memory_rank_15_to_5", |b| {
+        b.iter(|| {
+            ranking::rank(
+                black_box(candidates.clone()),
+                &config,
+                &RecallContext::default(),
+                chrono::Utc::now(),
+                5,
+            )
+        });
+    });
+
+    let no_mmr = MemoryRankingConfig {
+        mmr_lambda: 1.0,
+        ..MemoryRankingConfig::default()
+    };
+    c.bench_function("memory_rank_15_to_5_without_mmr", |b| {
+        b.iter(|| {
+            ranking::rank(
+                black_box(candidates.clone()),
+                &no_mmr,
+                &RecallContext::default(),
+                chrono::Utc::now(),
+                5,
+            )
+        });
+    });
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mem = make_sqlite_memory(tmp.path());
+    rt.block_on(async {
+        for entry in ranking_candidates(100) {
+            mem.store(&entry.key, &entry.content, entry.category, None)
+                .await
+                .unwrap();
+        }
+    });
+
+    c.bench_function("memory_recall_ranked_top5", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                ranking::recall_ranked(
+                    mem.as_ref(),
+                    black_box("zeroclaw agent"),
+                    5,
+                    0.0,
+                    &config,
+                    &RecallContext::default(),
+                )
+                .await
+                .unwrap()
+            })
+        });
+    });
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Benchmark: Full agent turn cycle
 // ─────────────────────────────────────────────────────────────────────────────
@@ -324,6 +415,7 @@ criterion_group!(
     bench_xml_parsing,
     bench_native_parsing,
     bench_memory_operations,
+    bench_memory_ranking,
     bench_agent_turn,
 );
 criterion_main!(benches);
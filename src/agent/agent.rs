@@ -4,6 +4,7 @@ use crate::agent::dispatcher::{
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::agent::research;
+use crate::agent::shaping::{self, ContextBudget};
 use crate::config::{Config, ResearchPhaseConfig};
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
@@ -304,13 +305,12 @@ impl Agent {
             .unwrap_or("anthropic/claude-sonnet-4-20250514")
             .to_string();
 
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
+        let provider: Box<dyn Provider> = providers::create_routed_provider_for_config(
+            config,
             provider_name,
-            config.api_key.as_deref(),
-            config.api_url.as_deref(),
-            &config.reliability,
-            &config.model_routes,
             &model_name,
+            &providers::ProviderRuntimeOptions::default(),
+            Arc::clone(&observer),
         )?;
 
         let dispatcher_choice = config.agent.tool_dispatcher.as_str();
@@ -334,10 +334,10 @@ impl Agent {
             .memory(memory)
             .observer(observer)
             .tool_dispatcher(tool_dispatcher)
-            .memory_loader(Box::new(DefaultMemoryLoader::new(
-                5,
-                config.memory.min_relevance_score,
-            )))
+            .memory_loader(Box::new(
+                DefaultMemoryLoader::new(5, config.memory.min_relevance_score)
+                    .with_ranking(config.memory.ranking.clone()),
+            ))
             .prompt_builder(SystemPromptBuilder::with_defaults())
             .config(config.agent.clone())
             .model_name(model_name)
@@ -452,9 +452,13 @@ impl Agent {
         futures_util::future::join_all(futs).await
     }
 
-    fn classify_model(&self, user_message: &str) -> String {
-        if let Some(decision) =
-            super::classifier::classify_with_decision(&self.classification_config, user_message)
+    async fn classify_model(&self, user_message: &str) -> String {
+        if let Some(decision) = super::classifier::classify_with_fallback(
+            &self.classification_config,
+            self.provider.as_ref(),
+            user_message,
+        )
+        .await
         {
             if self.available_hints.contains(&decision.hint) {
                 let resolved_model = self
@@ -557,20 +561,40 @@ impl Agent {
         self.history
             .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
 
-        let effective_model = self.classify_model(user_message);
+        let effective_model = self.classify_model(user_message).await;
 
+        let budget = ContextBudget::new(self.provider.as_ref(), &effective_model);
+        let mut compact_tool_specs = None;
         for _ in 0..self.config.max_tool_iterations {
-            let messages = self.tool_dispatcher.to_provider_messages(&self.history);
+            let mut messages = self.tool_dispatcher.to_provider_messages(&self.history);
+            let mut tools = self
+                .tool_dispatcher
+                .should_send_tool_specs()
+                .then_some(self.tool_specs.as_slice());
+            if !budget.fits(&messages, tools) {
+                if tools.is_some() {
+                    tools = Some(
+                        compact_tool_specs
+                            .get_or_insert_with(|| shaping::compact_tool_specs(&self.tool_specs))
+                            .as_slice(),
+                    );
+                }
+                let fit = shaping::fit_messages(&budget, &mut messages, tools);
+                tracing::info!(
+                    tokens = fit.tokens,
+                    budget = budget.limit(),
+                    dropped = fit.dropped,
+                    truncated = fit.truncated,
+                    "Shaped request to fit the model context window"
+                );
+            }
             let response = match self
                 .provider
                 .chat(
                     ChatRequest {
                         messages: &messages,
-                        tools: if self.tool_dispatcher.should_send_tool_specs() {
-                            Some(&self.tool_specs)
-                        } else {
-                            None
-                        },
+                        tools,
+                        response_format: None,
                     },
                     &effective_model,
                     self.temperature,
@@ -942,6 +966,7 @@ mod tests {
                     max_length: None,
                     priority: 10,
                 }],
+                fallback_model: None,
             })
             .available_hints(vec!["fast".to_string()])
             .route_model_by_hint(route_model_by_hint)
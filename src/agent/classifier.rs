@@ -1,4 +1,8 @@
 use crate::config::schema::QueryClassificationConfig;
+use crate::providers::{structured, ChatMessage, ChatRequest, Provider, ResponseFormat};
+
+/// Reply value meaning that none of the hints fits the message.
+const NO_HINT: &str = "none";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ClassificationDecision {
@@ -29,7 +33,7 @@ pub fn classify_with_decision(
     let len = message.len();
 
     let mut rules: Vec<_> = config.rules.iter().collect();
-    rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+    rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
 
     for rule in rules {
         // Length constraints
@@ -65,13 +69,88 @@ pub fn classify_with_decision(
     None
 }
 
+/// Response format for model classification: `{"hint": <hint or "none">}`.
+pub fn hint_choice_format(hints: &[&str]) -> ResponseFormat {
+    let mut choices: Vec<&str> = hints.to_vec();
+    choices.push(NO_HINT);
+    ResponseFormat::json_schema(
+        "route_hint",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "hint": {"type": "string", "enum": choices}
+            },
+            "required": ["hint"]
+        }),
+    )
+}
+
+/// Classify with the rules, then ask `config.fallback_model` on `provider`
+/// to pick one of the rules' hints when none matches.
+///
+/// Model failures and replies outside the schema count as no match, so the
+/// message keeps its default route.
+pub async fn classify_with_fallback(
+    config: &QueryClassificationConfig,
+    provider: &dyn Provider,
+    message: &str,
+) -> Option<ClassificationDecision> {
+    if let Some(decision) = classify_with_decision(config, message) {
+        return Some(decision);
+    }
+    let model = config
+        .fallback_model
+        .as_deref()
+        .filter(|_| config.enabled)?;
+    let mut hints: Vec<&str> = config.rules.iter().map(|rule| rule.hint.as_str()).collect();
+    hints.sort_unstable();
+    hints.dedup();
+    if hints.is_empty() {
+        return None;
+    }
+
+    let format = hint_choice_format(&hints);
+    let messages = [
+        ChatMessage::system(format!(
+            "Pick the model route best suited to the user's message. \
+             Routes: {}. Answer \"{NO_HINT}\" when none clearly fits.",
+            hints.join(", ")
+        )),
+        ChatMessage::user(message),
+    ];
+    let request = ChatRequest {
+        messages: &messages,
+        tools: None,
+        response_format: Some(&format),
+    };
+    let reply = match provider.chat(request, model, 0.0).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::warn!(model, "Query classification model failed: {e}");
+            return None;
+        }
+    };
+    let hint = match structured::parse_reply(reply.text_or_empty(), &format) {
+        Ok(value) => value["hint"].as_str().map(str::to_string)?,
+        Err(e) => {
+            tracing::warn!(model, "Ignoring invalid query classification reply: {e}");
+            return None;
+        }
+    };
+    (hint != NO_HINT).then_some(ClassificationDecision { hint, priority: 0 })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::schema::{ClassificationRule, QueryClassificationConfig};
 
     fn make_config(enabled: bool, rules: Vec<ClassificationRule>) -> QueryClassificationConfig {
-        QueryClassificationConfig { enabled, rules }
+        QueryClassificationConfig {
+            enabled,
+            rules,
+            fallback_model: None,
+        }
     }
 
     #[test]
@@ -188,6 +267,98 @@ mod tests {
         assert_eq!(classify(&config, "something completely different"), None);
     }
 
+    /// Replies with a fixed text and records the requested format name.
+    struct HintModel {
+        reply: &'static str,
+        format: parking_lot::Mutex<Option<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for HintModel {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(self.reply.to_string())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<crate::providers::ChatResponse> {
+            *self.format.lock() = request.response_format.map(|f| f.name().to_string());
+            Ok(crate::providers::ChatResponse {
+                text: Some(self.reply.to_string()),
+                tool_calls: vec![],
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    fn fallback_config() -> QueryClassificationConfig {
+        QueryClassificationConfig {
+            fallback_model: Some("classifier-model".into()),
+            ..make_config(
+                true,
+                vec![ClassificationRule {
+                    hint: "code".into(),
+                    patterns: vec!["fn ".into()],
+                    ..Default::default()
+                }],
+            )
+        }
+    }
+
+    fn hint_model(reply: &'static str) -> HintModel {
+        HintModel {
+            reply,
+            format: parking_lot::Mutex::new(None),
+        }
+    }
+
+    #[tokio::test]
+    async fn fallback_model_picks_hint_through_json_schema() {
+        let config = fallback_config();
+        let model = hint_model(r#"{"hint": "code"}"#);
+
+        let decision = classify_with_fallback(&config, &model, "my build is broken")
+            .await
+            .expect("model should pick a hint");
+
+        assert_eq!(decision.hint, "code");
+        assert_eq!(model.format.lock().as_deref(), Some("route_hint"));
+    }
+
+    #[tokio::test]
+    async fn fallback_model_replies_outside_the_schema_do_not_route() {
+        let config = fallback_config();
+        for reply in [r#"{"hint": "none"}"#, r#"{"hint": "vision"}"#, "code"] {
+            let model = hint_model(reply);
+            assert_eq!(
+                classify_with_fallback(&config, &model, "my build is broken").await,
+                None,
+                "reply {reply:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn fallback_model_is_not_asked_when_a_rule_matches() {
+        let config = fallback_config();
+        let model = hint_model(r#"{"hint": "none"}"#);
+
+        let decision = classify_with_fallback(&config, &model, "fn main() {}").await;
+
+        assert_eq!(decision.map(|d| d.hint).as_deref(), Some("code"));
+        assert!(model.format.lock().is_none());
+    }
+
     #[test]
     fn classify_with_decision_exposes_priority_of_matched_rule() {
         let config = make_config(
@@ -1,16 +1,20 @@
+use crate::agent::shaping;
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::multimodal;
 use crate::observability::{self, runtime_trace, Observer, ObserverEvent};
+use crate::providers::streaming::StreamAccumulator;
 use crate::providers::{
-    self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
+    self, ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilityError, StreamEvent,
+    ToolCall,
 };
 use crate::runtime;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
 use regex::{Regex, RegexSet};
 use std::collections::{BTreeSet, HashSet};
 use std::fmt::Write;
@@ -296,7 +300,7 @@ pub(crate) async fn agent_turn(
     multimodal_config: &crate::config::MultimodalConfig,
     max_tool_iterations: usize,
 ) -> Result<String> {
-    run_tool_call_loop(
+    Box::pin(run_tool_call_loop(
         provider,
         history,
         tools_registry,
@@ -313,10 +317,53 @@ pub(crate) async fn agent_turn(
         None,
         None,
         &[],
-    )
+    ))
     .await
 }
 
+/// Stream one provider round into the draft channel and return the assembled
+/// response plus whether any answer text was forwarded live.
+///
+/// The first text delta clears accumulated progress lines; tool-call starts
+/// surface as progress lines so the draft shows which tool is being prepared.
+async fn stream_chat_to_draft(
+    provider: &dyn Provider,
+    request: ChatRequest<'_>,
+    model: &str,
+    temperature: f64,
+    tx: &tokio::sync::mpsc::Sender<String>,
+) -> Result<(ChatResponse, bool)> {
+    let mut events = provider.stream_chat(request, model, temperature).await?;
+    let mut accumulator = StreamAccumulator::new();
+    let mut streamed_text = false;
+
+    while let Some(event) = events.next().await {
+        let event = event?;
+        match &event {
+            StreamEvent::TextDelta(delta) if !delta.is_empty() => {
+                if !streamed_text {
+                    streamed_text = true;
+                    let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
+                }
+                let _ = tx.send(delta.clone()).await;
+            }
+            StreamEvent::ToolCallDelta {
+                name: Some(name), ..
+            } => {
+                let _ = tx
+                    .send(format!(
+                        "{DRAFT_PROGRESS_SENTINEL}\u{1f527} Preparing {name}...\n"
+                    ))
+                    .await;
+            }
+            _ => {}
+        }
+        accumulator.push(event);
+    }
+
+    Ok((accumulator.finish(), streamed_text))
+}
+
 // ── Agent Tool-Call Loop ──────────────────────────────────────────────────
 // Core agentic iteration: send conversation to the LLM, parse any tool
 // calls from the response, execute them, append results to history, and
@@ -362,6 +409,9 @@ pub(crate) async fn run_tool_call_loop(
         .map(|tool| tool.spec())
         .collect();
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
+    let context_budget = shaping::ContextBudget::new(provider, model);
+    let mut compact_tool_specs: Option<Vec<crate::tools::ToolSpec>> = None;
+    let mut history_compacted = false;
     let turn_id = Uuid::new_v4().to_string();
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
     let bypass_non_cli_approval_for_turn =
@@ -399,8 +449,68 @@ pub(crate) async fn run_tool_call_loop(
             .into());
         }
 
-        let prepared_messages =
+        let mut prepared_messages =
             multimodal::prepare_messages_for_provider(history, multimodal_config).await?;
+        let mut request_tools = use_native_tools.then_some(tool_specs.as_slice());
+
+        // ── Context-window shaping ───────────────────────────
+        // Shrink oversized requests before sending rather than after a
+        // context-length error: compact tool specs, summarize older history
+        // (once per turn), then drop or truncate what still does not fit.
+        if !context_budget.fits(&prepared_messages.messages, request_tools) {
+            let tokens_before =
+                context_budget.request_tokens(&prepared_messages.messages, request_tools);
+            if use_native_tools {
+                let compact = compact_tool_specs
+                    .get_or_insert_with(|| shaping::compact_tool_specs(&tool_specs));
+                request_tools = Some(compact.as_slice());
+            }
+            if !history_compacted
+                && !context_budget.fits(&prepared_messages.messages, request_tools)
+            {
+                history_compacted = true;
+                if auto_compact_history(history, provider, model, 0)
+                    .await
+                    .unwrap_or(false)
+                {
+                    prepared_messages =
+                        multimodal::prepare_messages_for_provider(history, multimodal_config)
+                            .await?;
+                }
+            }
+            let fit = shaping::fit_messages(
+                &context_budget,
+                &mut prepared_messages.messages,
+                request_tools,
+            );
+            tracing::info!(
+                tokens_before,
+                tokens_after = fit.tokens,
+                budget = context_budget.limit(),
+                dropped = fit.dropped,
+                truncated = fit.truncated,
+                "Shaped request to fit the model context window"
+            );
+            runtime_trace::record_event(
+                "context_shaped",
+                Some(channel_name),
+                Some(provider_name),
+                Some(model),
+                Some(&turn_id),
+                Some(fit.tokens <= context_budget.limit()),
+                None,
+                serde_json::json!({
+                    "iteration": iteration + 1,
+                    "tokens_before": tokens_before,
+                    "tokens_after": fit.tokens,
+                    "budget": context_budget.limit(),
+                    "compact_tool_specs": use_native_tools,
+                    "history_compacted": history_compacted,
+                    "dropped_messages": fit.dropped,
+                    "truncated_messages": fit.truncated,
+                }),
+            );
+        }
 
         // ── Progress: LLM thinking ────────────────────────────
         if let Some(ref tx) = on_delta {
@@ -440,20 +550,26 @@ pub(crate) async fn run_tool_call_loop(
 
         // Unified path via Provider::chat so provider-specific native tool logic
         // (OpenAI/Anthropic/OpenRouter/compatible adapters) is honored.
-        let request_tools = if use_native_tools {
-            Some(tool_specs.as_slice())
-        } else {
-            None
+        let request = ChatRequest {
+            messages: &prepared_messages.messages,
+            tools: request_tools,
+            response_format: None,
+        };
+        // Stream tokens into the draft when the caller wants live updates.
+        // Prompt-guided tool calls are embedded in the text, so only stream
+        // when tools are native (or absent) to avoid leaking call markup.
+        let stream_tx = on_delta.as_ref().filter(|_| {
+            provider.supports_streaming() && (use_native_tools || tool_specs.is_empty())
+        });
+        let chat_future = async {
+            match stream_tx {
+                Some(tx) => stream_chat_to_draft(provider, request, model, temperature, tx).await,
+                None => provider
+                    .chat(request, model, temperature)
+                    .await
+                    .map(|resp| (resp, false)),
+            }
         };
-
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: &prepared_messages.messages,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
 
         let chat_result = if let Some(token) = cancellation_token.as_ref() {
             tokio::select! {
@@ -463,6 +579,10 @@ pub(crate) async fn run_tool_call_loop(
         } else {
             chat_future.await
         };
+        let (chat_result, streamed_text) = match chat_result {
+            Ok((resp, streamed)) => (Ok(resp), streamed),
+            Err(e) => (Err(e), false),
+        };
 
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
@@ -633,7 +753,9 @@ pub(crate) async fn run_tool_call_loop(
             // No tool calls — this is the final response.
             // If a streaming sender is provided, relay the text in small chunks
             // so the channel can progressively update the draft message.
-            if let Some(ref tx) = on_delta {
+            // Skip the replay when the provider already streamed this exact text.
+            let already_streamed = streamed_text && display_text == response_text;
+            if let Some(tx) = on_delta.as_ref().filter(|_| !already_streamed) {
                 // Clear accumulated progress lines before streaming the final answer.
                 let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
                 // Split on whitespace boundaries, accumulating chunks of at least
@@ -902,7 +1024,7 @@ pub(crate) async fn run_tool_call_loop(
         for ((idx, call), outcome) in executable_indices
             .iter()
             .zip(executable_calls.iter())
-            .zip(executed_outcomes.into_iter())
+            .zip(executed_outcomes)
         {
             runtime_trace::record_event(
                 "tool_call_result",
@@ -1220,16 +1342,15 @@ pub async fn run(
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        model_context_window: config.model_context_window,
     };
 
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
+    let provider: Box<dyn Provider> = providers::create_routed_provider_for_config(
+        &config,
         provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
         model_name,
         &provider_runtime_options,
+        Arc::clone(&observer),
     )?;
 
     observer.record_event(&ObserverEvent::AgentStart {
@@ -1413,8 +1534,13 @@ pub async fn run(
         }
 
         // Inject memory + hardware RAG context into user message
-        let mem_context =
-            build_context(mem.as_ref(), &msg, config.memory.min_relevance_score).await;
+        let mem_context = build_context(
+            mem.as_ref(),
+            &msg,
+            config.memory.min_relevance_score,
+            &config.memory.ranking,
+        )
+        .await;
         let rag_limit = if config.agent.compact_context { 2 } else { 5 };
         let hw_context = hardware_rag
             .as_ref()
@@ -1433,7 +1559,7 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let response = run_tool_call_loop(
+        let response = Box::pin(run_tool_call_loop(
             provider.as_ref(),
             &mut history,
             &tools_registry,
@@ -1450,7 +1576,7 @@ pub async fn run(
             None,
             None,
             &[],
-        )
+        ))
         .await?;
         final_output = response.clone();
         println!("{response}");
@@ -1538,8 +1664,13 @@ pub async fn run(
             }
 
             // Inject memory + hardware RAG context into user message
-            let mem_context =
-                build_context(mem.as_ref(), &user_input, config.memory.min_relevance_score).await;
+            let mem_context = build_context(
+                mem.as_ref(),
+                &user_input,
+                config.memory.min_relevance_score,
+                &config.memory.ranking,
+            )
+            .await;
             let rag_limit = if config.agent.compact_context { 2 } else { 5 };
             let hw_context = hardware_rag
                 .as_ref()
@@ -1555,7 +1686,7 @@ pub async fn run(
 
             history.push(ChatMessage::user(&enriched));
 
-            let response = match run_tool_call_loop(
+            let response = match Box::pin(run_tool_call_loop(
                 provider.as_ref(),
                 &mut history,
                 &tools_registry,
@@ -1572,7 +1703,7 @@ pub async fn run(
                 None,
                 None,
                 &[],
-            )
+            ))
             .await
             {
                 Ok(resp) => resp,
@@ -1694,15 +1825,14 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        model_context_window: config.model_context_window,
     };
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
+    let provider: Box<dyn Provider> = providers::create_routed_provider_for_config(
+        &config,
         provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
         &model_name,
         &provider_runtime_options,
+        Arc::clone(&observer),
     )?;
 
     let hardware_rag: Option<crate::rag::HardwareRag> = config
@@ -1789,7 +1919,13 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     }
     system_prompt.push_str(&build_shell_policy_instructions(&config.autonomy));
 
-    let mem_context = build_context(mem.as_ref(), message, config.memory.min_relevance_score).await;
+    let mem_context = build_context(
+        mem.as_ref(),
+        message,
+        config.memory.min_relevance_score,
+        &config.memory.ranking,
+    )
+    .await;
     let rag_limit = if config.agent.compact_context { 2 } else { 5 };
     let hw_context = hardware_rag
         .as_ref()
@@ -1885,6 +2021,7 @@ mod tests {
             ProviderCapabilities {
                 native_tool_calling: false,
                 vision: true,
+                structured_output: false,
             }
         }
 
@@ -1927,6 +2064,7 @@ mod tests {
     struct ScriptedProvider {
         responses: Arc<Mutex<VecDeque<ChatResponse>>>,
         capabilities: ProviderCapabilities,
+        streaming: bool,
     }
 
     impl ScriptedProvider {
@@ -1943,6 +2081,7 @@ mod tests {
             Self {
                 responses: Arc::new(Mutex::new(scripted)),
                 capabilities: ProviderCapabilities::default(),
+                streaming: false,
             }
         }
 
@@ -1950,6 +2089,11 @@ mod tests {
             self.capabilities.native_tool_calling = true;
             self
         }
+
+        fn with_streaming(mut self) -> Self {
+            self.streaming = true;
+            self
+        }
     }
 
     #[async_trait]
@@ -1958,6 +2102,10 @@ mod tests {
             self.capabilities.clone()
         }
 
+        fn supports_streaming(&self) -> bool {
+            self.streaming
+        }
+
         async fn chat_with_system(
             &self,
             _system_prompt: Option<&str>,
@@ -2667,6 +2815,86 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_streams_deltas_on_tool_turns() {
+        let provider = ScriptedProvider::from_text_responses(vec!["All done"])
+            .with_native_tool_support()
+            .with_streaming();
+        provider
+            .responses
+            .lock()
+            .expect("responses lock should be valid")
+            .push_front(ChatResponse {
+                text: Some("Checking first".to_string()),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "count_tool".to_string(),
+                    arguments: r#"{"value":"X"}"#.to_string(),
+                }],
+                usage: None,
+                reasoning_content: None,
+            });
+
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let observer = NoopObserver;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            Some(tx),
+            None,
+            &[],
+        )
+        .await
+        .expect("streaming tool loop should complete");
+
+        assert_eq!(result, "All done");
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+
+        let mut deltas = Vec::new();
+        while let Ok(delta) = rx.try_recv() {
+            deltas.push(delta);
+        }
+        let answer: Vec<&str> = deltas
+            .iter()
+            .map(String::as_str)
+            .filter(|d| !d.starts_with(DRAFT_PROGRESS_SENTINEL))
+            .collect();
+        assert_eq!(
+            answer,
+            vec![
+                DRAFT_CLEAR_SENTINEL,
+                "Checking first",
+                DRAFT_CLEAR_SENTINEL,
+                "All done"
+            ],
+            "tool-turn text should stream live and the final answer should not be replayed"
+        );
+        assert!(deltas
+            .iter()
+            .any(|d| d.starts_with(DRAFT_PROGRESS_SENTINEL) && d.contains("count_tool")));
+    }
+
     #[test]
     fn parse_tool_calls_extracts_single_call() {
         let response = r#"Let me check that.
@@ -3398,7 +3626,13 @@ Tail"#;
         .await
         .unwrap();
 
-        let context = build_context(&mem, "status updates", 0.0).await;
+        let context = build_context(
+            &mem,
+            "status updates",
+            0.0,
+            &crate::config::MemoryRankingConfig::default(),
+        )
+        .await;
         assert!(context.contains("user_msg_real"));
         assert!(!context.contains("assistant_resp_poisoned"));
         assert!(!context.contains("fabricated event"));
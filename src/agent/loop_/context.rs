@@ -1,26 +1,31 @@
+use crate::config::MemoryRankingConfig;
+use crate::memory::ranking::{recall_ranked, RecallContext};
 use crate::memory::{self, Memory};
 use std::fmt::Write;
 
 /// Build context preamble by searching memory for relevant entries.
 /// Entries with a hybrid score below `min_relevance_score` are dropped to
-/// prevent unrelated memories from bleeding into the conversation.
+/// prevent unrelated memories from bleeding into the conversation; the rest
+/// are re-ranked per `[memory.ranking]`.
 pub(super) async fn build_context(
     mem: &dyn Memory,
     user_msg: &str,
     min_relevance_score: f64,
+    ranking: &MemoryRankingConfig,
 ) -> String {
     let mut context = String::new();
 
     // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
-        let relevant: Vec<_> = entries
-            .iter()
-            .filter(|e| match e.score {
-                Some(score) => score >= min_relevance_score,
-                None => true,
-            })
-            .collect();
-
+    if let Ok(relevant) = recall_ranked(
+        mem,
+        user_msg,
+        5,
+        min_relevance_score,
+        ranking,
+        &RecallContext::default(),
+    )
+    .await
+    {
         if !relevant.is_empty() {
             context.push_str("[Memory context]\n");
             for entry in &relevant {
@@ -80,7 +80,11 @@ pub(super) async fn auto_compact_history(
         return Ok(false);
     }
 
-    let compact_end = start + compact_count;
+    // Never split a tool call from its results.
+    let mut compact_end = start + compact_count;
+    while compact_end < history.len() && history[compact_end].role == "tool" {
+        compact_end += 1;
+    }
     let to_compact: Vec<ChatMessage> = history[start..compact_end].to_vec();
     let transcript = build_compaction_transcript(&to_compact);
 
@@ -1,3 +1,5 @@
+use crate::config::MemoryRankingConfig;
+use crate::memory::ranking::{recall_ranked, RecallContext};
 use crate::memory::{self, Memory};
 use async_trait::async_trait;
 use std::fmt::Write;
@@ -11,6 +13,7 @@ pub trait MemoryLoader: Send + Sync {
 pub struct DefaultMemoryLoader {
     limit: usize,
     min_relevance_score: f64,
+    ranking: MemoryRankingConfig,
 }
 
 impl Default for DefaultMemoryLoader {
@@ -18,6 +21,7 @@ impl Default for DefaultMemoryLoader {
         Self {
             limit: 5,
             min_relevance_score: 0.4,
+            ranking: MemoryRankingConfig::default(),
         }
     }
 }
@@ -27,8 +31,15 @@ impl DefaultMemoryLoader {
         Self {
             limit: limit.max(1),
             min_relevance_score,
+            ranking: MemoryRankingConfig::default(),
         }
     }
+
+    /// Override the recall re-ranking settings (`[memory.ranking]`).
+    pub fn with_ranking(mut self, ranking: MemoryRankingConfig) -> Self {
+        self.ranking = ranking;
+        self
+    }
 }
 
 #[async_trait]
@@ -38,7 +49,15 @@ impl MemoryLoader for DefaultMemoryLoader {
         memory: &dyn Memory,
         user_message: &str,
     ) -> anyhow::Result<String> {
-        let entries = memory.recall(user_message, self.limit, None).await?;
+        let entries = recall_ranked(
+            memory,
+            user_message,
+            self.limit,
+            self.min_relevance_score,
+            &self.ranking,
+            &RecallContext::default(),
+        )
+        .await?;
         if entries.is_empty() {
             return Ok(String::new());
         }
@@ -48,11 +67,6 @@ impl MemoryLoader for DefaultMemoryLoader {
             if memory::is_assistant_autosave_key(&entry.key) {
                 continue;
             }
-            if let Some(score) = entry.score {
-                if score < self.min_relevance_score {
-                    continue;
-                }
-            }
             let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
         }
 
@@ -69,7 +83,7 @@ impl MemoryLoader for DefaultMemoryLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::memory::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
     use std::sync::Arc;
 
     struct MockMemory;
@@ -106,6 +120,7 @@ mod tests {
                 timestamp: "now".into(),
                 session_id: None,
                 score: None,
+                metadata: MemoryMetadata::default(),
             }])
         }
 
@@ -209,6 +224,7 @@ mod tests {
                     timestamp: "now".into(),
                     session_id: None,
                     score: Some(0.95),
+                    metadata: MemoryMetadata::default(),
                 },
                 MemoryEntry {
                     id: "2".into(),
@@ -218,6 +234,7 @@ mod tests {
                     timestamp: "now".into(),
                     session_id: None,
                     score: Some(0.9),
+                    metadata: MemoryMetadata::default(),
                 },
             ]),
         };
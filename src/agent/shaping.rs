@@ -0,0 +1,337 @@
+//! Context-window aware request shaping.
+//!
+//! Requests are measured with the provider's token counter against the
+//! model's context window before they are sent, so long sessions shrink
+//! instead of failing with a context-length error. Callers apply the steps
+//! from least to most lossy: [`compact_tool_specs`], history compaction
+//! (`auto_compact_history` in the agent loop), then [`fit_messages`], which
+//! drops the oldest turns and finally truncates oversized messages.
+
+use crate::providers::tokenizer::TokenCounter;
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::ToolSpec;
+use crate::util::truncate_with_ellipsis;
+
+/// Share of the input budget used when token counts are estimates.
+const ESTIMATE_HEADROOM_PERCENT: usize = 90;
+/// Longest tool description kept by [`compact_tool_specs`].
+const COMPACT_TOOL_DESCRIPTION_CHARS: usize = 160;
+/// JSON Schema keys that only document parameters.
+const SCHEMA_DOC_KEYS: &[&str] = &["description", "examples", "example", "title"];
+/// Messages are never truncated below this many characters.
+const MIN_TRUNCATED_CHARS: usize = 200;
+
+/// Token budget for requests to one model.
+pub(crate) struct ContextBudget {
+    counter: Box<dyn TokenCounter>,
+    limit: usize,
+}
+
+impl ContextBudget {
+    pub(crate) fn new(provider: &dyn Provider, model: &str) -> Self {
+        let counter = provider.token_counter(model);
+        let limit = provider.model_limits(model).input_budget();
+        let limit = if counter.is_exact() {
+            limit
+        } else {
+            limit * ESTIMATE_HEADROOM_PERCENT / 100
+        };
+        Self { counter, limit }
+    }
+
+    /// Prompt tokens a request may use.
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub(crate) fn request_tokens(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[ToolSpec]>,
+    ) -> usize {
+        self.counter.count_messages(messages) + tools.map_or(0, |t| self.counter.count_tools(t))
+    }
+
+    pub(crate) fn fits(&self, messages: &[ChatMessage], tools: Option<&[ToolSpec]>) -> bool {
+        self.request_tokens(messages, tools) <= self.limit
+    }
+
+    /// Tokens `message` adds to a conversation.
+    fn message_tokens(&self, message: &ChatMessage) -> usize {
+        self.counter
+            .count_messages(std::slice::from_ref(message))
+            .saturating_sub(self.counter.count_messages(&[]))
+    }
+}
+
+/// What [`fit_messages`] had to do.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FitOutcome {
+    /// Oldest messages dropped from the request.
+    pub dropped: usize,
+    /// Messages shortened in place.
+    pub truncated: usize,
+    /// Estimated prompt tokens after shaping.
+    pub tokens: usize,
+}
+
+/// Tool specs with descriptions cut to their first sentence and parameter
+/// documentation removed from the schemas. Names, types and required fields
+/// are kept, so calls stay valid.
+pub(crate) fn compact_tool_specs(tools: &[ToolSpec]) -> Vec<ToolSpec> {
+    tools
+        .iter()
+        .map(|tool| {
+            let mut parameters = tool.parameters.clone();
+            strip_schema_docs(&mut parameters);
+            ToolSpec {
+                name: tool.name.clone(),
+                description: first_sentence(&tool.description),
+                parameters,
+            }
+        })
+        .collect()
+}
+
+fn first_sentence(text: &str) -> String {
+    let text = text.trim();
+    let end = text
+        .find(". ")
+        .map(|idx| idx + 1)
+        .into_iter()
+        .chain(text.find('\n'))
+        .min()
+        .unwrap_or(text.len());
+    truncate_with_ellipsis(text[..end].trim(), COMPACT_TOOL_DESCRIPTION_CHARS)
+}
+
+fn strip_schema_docs(schema: &mut serde_json::Value) {
+    match schema {
+        serde_json::Value::Object(map) => {
+            map.retain(|key, _| !SCHEMA_DOC_KEYS.contains(&key.as_str()));
+            for (key, child) in map.iter_mut() {
+                match (key.as_str(), child) {
+                    // Keys under `properties` are parameter names, not schema keywords.
+                    ("properties", serde_json::Value::Object(properties)) => {
+                        properties.values_mut().for_each(strip_schema_docs);
+                    }
+                    (_, child) => strip_schema_docs(child),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_schema_docs),
+        _ => {}
+    }
+}
+
+/// Drop the oldest turns, then truncate the largest messages, until the
+/// request fits `budget`. Leading system messages and the latest user
+/// request (with everything after it) are never dropped; the result always
+/// starts its conversation with a user message so tool results are not
+/// orphaned from their calls.
+pub(crate) fn fit_messages(
+    budget: &ContextBudget,
+    messages: &mut Vec<ChatMessage>,
+    tools: Option<&[ToolSpec]>,
+) -> FitOutcome {
+    let mut outcome = FitOutcome {
+        tokens: budget.request_tokens(messages, tools),
+        ..FitOutcome::default()
+    };
+    let start = messages.iter().take_while(|m| m.role == "system").count();
+    let mut tail = messages
+        .iter()
+        .rposition(|m| m.role == "user" && !m.content.starts_with("[Tool results]"))
+        .filter(|idx| *idx >= start)
+        .unwrap_or(messages.len().saturating_sub(1).max(start));
+
+    while outcome.tokens > budget.limit() && start < tail {
+        loop {
+            let removed = messages.remove(start);
+            outcome.tokens = outcome
+                .tokens
+                .saturating_sub(budget.message_tokens(&removed));
+            outcome.dropped += 1;
+            tail -= 1;
+            if start >= tail || messages[start].role == "user" {
+                break;
+            }
+        }
+    }
+
+    let mut exhausted = vec![false; messages.len()];
+    let mut touched = vec![false; messages.len()];
+    while outcome.tokens > budget.limit() {
+        let Some((idx, tokens)) = (start..messages.len())
+            .filter(|idx| !exhausted[*idx])
+            .map(|idx| (idx, budget.message_tokens(&messages[idx])))
+            .max_by_key(|(_, tokens)| *tokens)
+        else {
+            break;
+        };
+        // Cut a little past the excess so one pass usually suffices.
+        let excess = outcome.tokens - budget.limit() + tokens / 50;
+        let chars = messages[idx].content.chars().count();
+        let keep = (chars * tokens.saturating_sub(excess) / tokens.max(1)).max(MIN_TRUNCATED_CHARS);
+        if truncate_message(&mut messages[idx], keep) {
+            let after = budget.message_tokens(&messages[idx]);
+            outcome.tokens = outcome.tokens.saturating_sub(tokens.saturating_sub(after));
+            touched[idx] = true;
+        }
+        exhausted[idx] =
+            keep == MIN_TRUNCATED_CHARS || messages[idx].content.chars().count() >= chars;
+    }
+    outcome.truncated = touched.iter().filter(|t| **t).count();
+    outcome
+}
+
+/// Shorten a message to about `max_chars`, keeping native tool-call JSON
+/// well-formed and leaving image markers intact. Returns whether it changed.
+fn truncate_message(message: &mut ChatMessage, max_chars: usize) -> bool {
+    if !crate::multimodal::parse_image_markers(&message.content)
+        .1
+        .is_empty()
+    {
+        return false;
+    }
+    if matches!(message.role.as_str(), "tool" | "assistant") {
+        if let Ok(serde_json::Value::Object(mut object)) =
+            serde_json::from_str::<serde_json::Value>(&message.content)
+        {
+            let Some(serde_json::Value::String(inner)) = object.get("content") else {
+                return false;
+            };
+            if inner.chars().count() <= max_chars {
+                return false;
+            }
+            let shortened = truncate_with_ellipsis(inner, max_chars);
+            object.insert("content".into(), serde_json::Value::String(shortened));
+            message.content = serde_json::Value::Object(object).to_string();
+            return true;
+        }
+    }
+    if message.content.chars().count() <= max_chars {
+        return false;
+    }
+    message.content = truncate_with_ellipsis(&message.content, max_chars);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::model_limits::ModelLimits;
+    use async_trait::async_trait;
+
+    /// Provider with a fixed context window and no reply reserve.
+    struct SmallWindow(usize);
+
+    #[async_trait]
+    impl Provider for SmallWindow {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+
+        fn model_limits(&self, _model: &str) -> ModelLimits {
+            ModelLimits::new(self.0, 0)
+        }
+    }
+
+    fn budget(window: usize) -> ContextBudget {
+        ContextBudget::new(&SmallWindow(window), "generic-model")
+    }
+
+    #[test]
+    fn budget_keeps_headroom_for_estimates() {
+        assert_eq!(budget(10_000).limit(), 9_000);
+        assert!(budget(10_000).fits(&[ChatMessage::user("hi")], None));
+    }
+
+    #[test]
+    fn compact_tool_specs_keeps_structure_and_param_names() {
+        let tool = ToolSpec {
+            name: "memory_store".into(),
+            description: "Store a fact. Facts are recalled later by similarity.\nMore.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "description": "arguments",
+                "properties": {
+                    "description": {"type": "string", "description": "What to store"},
+                    "tags": {"type": "array", "items": {"type": "string", "title": "tag"}},
+                },
+                "required": ["description"],
+            }),
+        };
+        let compact = compact_tool_specs(std::slice::from_ref(&tool));
+        assert_eq!(compact[0].description, "Store a fact.");
+        assert_eq!(
+            compact[0].parameters,
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "description": {"type": "string"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                },
+                "required": ["description"],
+            })
+        );
+    }
+
+    #[test]
+    fn fit_messages_drops_oldest_turns_without_orphaning_tool_results() {
+        let filler = "word ".repeat(400);
+        let mut messages = vec![
+            ChatMessage::system("system prompt"),
+            ChatMessage::user(filler.clone()),
+            ChatMessage::assistant(r#"{"content":null,"tool_calls":[{"id":"1"}]}"#),
+            ChatMessage::tool(format!(r#"{{"tool_call_id":"1","content":"{filler}"}}"#)),
+            ChatMessage::assistant(filler.clone()),
+            ChatMessage::user("latest question"),
+        ];
+        let budget = budget(1_000);
+        let outcome = fit_messages(&budget, &mut messages, None);
+
+        assert_eq!(outcome.dropped, 4);
+        assert_eq!(outcome.truncated, 0);
+        assert!(outcome.tokens <= budget.limit());
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(messages[1].content, "latest question");
+    }
+
+    #[test]
+    fn fit_messages_truncates_oversized_current_turn() {
+        let mut messages = vec![
+            ChatMessage::system("system prompt"),
+            ChatMessage::user("summarize this"),
+            ChatMessage::assistant(r#"{"content":null,"tool_calls":[{"id":"1"}]}"#),
+            ChatMessage::tool(
+                serde_json::json!({"tool_call_id": "1", "content": "log line ".repeat(2_000)})
+                    .to_string(),
+            ),
+        ];
+        let budget = budget(2_000);
+        let outcome = fit_messages(&budget, &mut messages, None);
+
+        assert_eq!(outcome.dropped, 0);
+        assert_eq!(outcome.truncated, 1);
+        assert!(budget.fits(&messages, None), "{outcome:?}");
+        let tool: serde_json::Value = serde_json::from_str(&messages[3].content).unwrap();
+        assert_eq!(tool["tool_call_id"], "1");
+        assert!(tool["content"].as_str().unwrap().ends_with("..."));
+    }
+
+    #[test]
+    fn fit_messages_is_a_noop_when_request_fits() {
+        let mut messages = vec![ChatMessage::user("hi")];
+        let outcome = fit_messages(&budget(10_000), &mut messages, None);
+        assert_eq!((outcome.dropped, outcome.truncated), (0, 0));
+        assert_eq!(messages.len(), 1);
+    }
+}
@@ -0,0 +1,589 @@
+//! Typed media attachments for channel messages.
+//!
+//! Agents reference media with `[IMAGE:<path-or-url>]`-style markers. Replies
+//! are parsed into [`Attachment`]s once, checked against the configured
+//! [`AttachmentPolicy`], and handed to channels that can upload them; other
+//! channels receive them as links. Inbound media is carried the same way on
+//! [`ChannelMessage`](super::traits::ChannelMessage) and rendered back into
+//! markers for the agent.
+
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+
+/// Default `[channels_config.attachments].max_size_mb`.
+pub const DEFAULT_MAX_ATTACHMENT_MB: u64 = 25;
+
+/// What an attachment is, which decides how channels present it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    Image,
+    Document,
+    Video,
+    Audio,
+    /// Audio meant to play as a voice note.
+    Voice,
+}
+
+impl AttachmentKind {
+    /// Parse a marker prefix (`IMAGE`, `PHOTO`, `FILE`, ...), case-insensitively.
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker.trim().to_ascii_uppercase().as_str() {
+            "IMAGE" | "PHOTO" => Some(Self::Image),
+            "DOCUMENT" | "FILE" => Some(Self::Document),
+            "VIDEO" => Some(Self::Video),
+            "AUDIO" => Some(Self::Audio),
+            "VOICE" => Some(Self::Voice),
+            _ => None,
+        }
+    }
+
+    pub fn marker_name(self) -> &'static str {
+        match self {
+            Self::Image => "IMAGE",
+            Self::Document => "DOCUMENT",
+            Self::Video => "VIDEO",
+            Self::Audio => "AUDIO",
+            Self::Voice => "VOICE",
+        }
+    }
+
+    /// Kind matching a MIME type; anything not image, video or audio is a document.
+    pub fn from_mime_type(mime_type: &str) -> Self {
+        let top_level = mime_type.split('/').next().unwrap_or_default();
+        match top_level.trim().to_ascii_lowercase().as_str() {
+            "image" => Self::Image,
+            "video" => Self::Video,
+            "audio" => Self::Audio,
+            _ => Self::Document,
+        }
+    }
+}
+
+/// Where the attachment's bytes live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentSource {
+    /// A local file.
+    Path(PathBuf),
+    /// A remote or inline resource (`https:`, `data:`, `mxc:`).
+    Url(String),
+}
+
+impl AttachmentSource {
+    /// Classify a marker target. Anything with a URL scheme is a URL;
+    /// `file://` targets and bare paths are local files.
+    pub fn parse(target: &str) -> Self {
+        let target = target.trim();
+        if let Some(path) = target.strip_prefix("file://") {
+            return Self::Path(PathBuf::from(path));
+        }
+        if has_url_scheme(target) {
+            Self::Url(target.to_string())
+        } else {
+            Self::Path(PathBuf::from(target))
+        }
+    }
+}
+
+fn has_url_scheme(target: &str) -> bool {
+    let Some((scheme, _)) = target.split_once(':') else {
+        return false;
+    };
+    // A single letter is a Windows drive (`C:\...`), not a scheme.
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// A media file attached to an inbound or outbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    /// MIME type, when known (e.g. `image/png`).
+    pub mime_type: Option<String>,
+    /// Size in bytes, when known.
+    pub size_bytes: Option<u64>,
+    pub source: AttachmentSource,
+    /// Text shown alongside the media.
+    pub caption: Option<String>,
+}
+
+impl Attachment {
+    pub fn new(kind: AttachmentKind, source: AttachmentSource) -> Self {
+        Self {
+            kind,
+            mime_type: None,
+            size_bytes: None,
+            source,
+            caption: None,
+        }
+    }
+
+    pub fn from_path(kind: AttachmentKind, path: impl Into<PathBuf>) -> Self {
+        Self::new(kind, AttachmentSource::Path(path.into()))
+    }
+
+    pub fn from_url(kind: AttachmentKind, url: impl Into<String>) -> Self {
+        Self::new(kind, AttachmentSource::Url(url.into()))
+    }
+
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        let mime_type = mime_type.into();
+        self.mime_type = (!mime_type.trim().is_empty()).then_some(mime_type);
+        self
+    }
+
+    pub fn with_size(mut self, size_bytes: u64) -> Self {
+        self.size_bytes = Some(size_bytes);
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        let caption = caption.into();
+        self.caption = (!caption.trim().is_empty()).then_some(caption);
+        self
+    }
+
+    /// The path or URL, as written in a marker.
+    pub fn target(&self) -> String {
+        match &self.source {
+            AttachmentSource::Path(path) => path.display().to_string(),
+            AttachmentSource::Url(url) => url.clone(),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match &self.source {
+            AttachmentSource::Path(path) => Some(path),
+            AttachmentSource::Url(_) => None,
+        }
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        match &self.source {
+            AttachmentSource::Path(_) => None,
+            AttachmentSource::Url(url) => Some(url),
+        }
+    }
+
+    /// Last path segment of the path or URL, without query or fragment.
+    pub fn file_name(&self) -> Option<String> {
+        let name = match &self.source {
+            AttachmentSource::Path(path) => path.file_name()?.to_str()?.to_string(),
+            AttachmentSource::Url(url) if url.starts_with("data:") => return None,
+            AttachmentSource::Url(url) => {
+                let url = url.split(['?', '#']).next().unwrap_or(url);
+                url.rsplit('/').next()?.to_string()
+            }
+        };
+        (!name.is_empty() && !name.contains(':')).then_some(name)
+    }
+
+    /// The MIME type, guessed from the file name when unset.
+    pub fn effective_mime_type(&self) -> String {
+        self.mime_type
+            .clone()
+            .or_else(|| {
+                let name = self.file_name()?;
+                mime_guess::from_path(name).first_raw().map(String::from)
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string())
+    }
+
+    /// The `[KIND:target]` marker understood by the agent and by channels.
+    pub fn marker(&self) -> String {
+        format!("[{}:{}]", self.kind.marker_name(), self.target())
+    }
+
+    /// Plain-text stand-in for channels that cannot upload: the URL for
+    /// remote media, the file name for local files, prefixed by the caption.
+    pub fn link_text(&self) -> String {
+        let label = match &self.source {
+            AttachmentSource::Url(url) => url.clone(),
+            AttachmentSource::Path(_) => {
+                format!("📎 {}", self.file_name().unwrap_or_else(|| self.target()))
+            }
+        };
+        match &self.caption {
+            Some(caption) => format!("{caption}: {label}"),
+            None => label,
+        }
+    }
+
+    /// Fill in the size and MIME type of a local file from the filesystem.
+    #[must_use]
+    pub fn with_local_metadata(mut self) -> Self {
+        if let AttachmentSource::Path(path) = &self.source {
+            if self.size_bytes.is_none() {
+                self.size_bytes = std::fs::metadata(path)
+                    .ok()
+                    .filter(std::fs::Metadata::is_file)
+                    .map(|meta| meta.len());
+            }
+            if self.mime_type.is_none() {
+                self.mime_type = mime_guess::from_path(path).first_raw().map(String::from);
+            }
+        }
+        self
+    }
+}
+
+fn find_matching_close(s: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split `[KIND:target]` markers out of `message`. Returns the remaining
+/// text (trimmed) and the attachments in order; brackets that are not media
+/// markers are kept as text.
+pub fn parse_markers(message: &str) -> (String, Vec<Attachment>) {
+    let mut cleaned = String::with_capacity(message.len());
+    let mut attachments = Vec::new();
+    let mut cursor = 0;
+
+    while cursor < message.len() {
+        let Some(open_rel) = message[cursor..].find('[') else {
+            cleaned.push_str(&message[cursor..]);
+            break;
+        };
+
+        let open = cursor + open_rel;
+        cleaned.push_str(&message[cursor..open]);
+
+        let Some(close_rel) = find_matching_close(&message[open + 1..]) else {
+            cleaned.push_str(&message[open..]);
+            break;
+        };
+
+        let close = open + 1 + close_rel;
+        let marker = &message[open + 1..close];
+
+        let parsed = marker.split_once(':').and_then(|(kind, target)| {
+            let kind = AttachmentKind::from_marker(kind)?;
+            let target = target.trim();
+            if target.is_empty() {
+                return None;
+            }
+            Some(Attachment::new(kind, AttachmentSource::parse(target)))
+        });
+
+        if let Some(attachment) = parsed {
+            attachments.push(attachment);
+        } else {
+            cleaned.push_str(&message[open..=close]);
+        }
+
+        cursor = close + 1;
+    }
+
+    (cleaned.trim().to_string(), attachments)
+}
+
+fn append_lines(content: &str, lines: impl IntoIterator<Item = String>) -> String {
+    let mut out = content.trim_end().to_string();
+    for line in lines {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+/// `content` followed by markers for attachments it does not already reference.
+pub fn append_markers(content: &str, attachments: &[Attachment]) -> String {
+    append_lines(
+        content,
+        attachments
+            .iter()
+            .filter(|attachment| !content.contains(&attachment.target()))
+            .map(Attachment::marker),
+    )
+}
+
+/// `content` followed by [`Attachment::link_text`] for each attachment.
+pub fn append_links(content: &str, attachments: &[Attachment]) -> String {
+    append_lines(content, attachments.iter().map(Attachment::link_text))
+}
+
+/// Resolve a local attachment target (`/workspace/...`, relative, or
+/// absolute) to a file inside `workspace`.
+pub fn resolve_workspace_path(workspace: &Path, target: &str) -> anyhow::Result<PathBuf> {
+    if target.contains('\0') {
+        anyhow::bail!("attachment path contains null byte");
+    }
+    let workspace_root = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+
+    let target_path = if let Some(rel) = target.strip_prefix("/workspace/") {
+        workspace.join(rel)
+    } else if target == "/workspace" {
+        workspace.to_path_buf()
+    } else {
+        let path = Path::new(target);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            workspace.join(path)
+        }
+    };
+
+    let resolved = target_path
+        .canonicalize()
+        .with_context(|| format!("attachment path not found: {target}"))?;
+
+    if !resolved.starts_with(&workspace_root) {
+        anyhow::bail!("attachment path escapes workspace: {target}");
+    }
+
+    if !resolved.is_file() {
+        anyhow::bail!("attachment path is not a file: {}", resolved.display());
+    }
+
+    Ok(resolved)
+}
+
+/// Save inbound media under `<workspace>/<dir>/`, keeping only a safe
+/// version of `file_name`. Returns the written path.
+pub async fn save_inbound(
+    workspace: &Path,
+    dir: &str,
+    file_name: &str,
+    bytes: &[u8],
+) -> anyhow::Result<PathBuf> {
+    let base = Path::new(file_name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let safe: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let safe = safe.trim_start_matches('.');
+    let safe = if safe.is_empty() {
+        "attachment.bin"
+    } else {
+        safe
+    };
+
+    let save_dir = workspace.join(dir);
+    tokio::fs::create_dir_all(&save_dir)
+        .await
+        .with_context(|| format!("failed to create {}", save_dir.display()))?;
+    let path = save_dir.join(safe);
+    tokio::fs::write(&path, bytes)
+        .await
+        .with_context(|| format!("failed to save attachment to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Size and MIME limits applied to every attachment, in both directions
+/// (`[channels_config.attachments]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentPolicy {
+    /// Largest accepted attachment; `0` disables the limit.
+    pub max_bytes: u64,
+    /// Accepted MIME types (`image/png`, `image/*`); empty accepts all.
+    pub allowed_mime_types: Vec<String>,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_ATTACHMENT_MB * 1024 * 1024,
+            allowed_mime_types: Vec::new(),
+        }
+    }
+}
+
+impl AttachmentPolicy {
+    pub fn from_config(config: &crate::config::ChannelAttachmentsConfig) -> Self {
+        Self {
+            max_bytes: config.max_size_mb.saturating_mul(1024 * 1024),
+            allowed_mime_types: config
+                .allowed_mime_types
+                .iter()
+                .map(|mime| mime.trim().to_ascii_lowercase())
+                .filter(|mime| !mime.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Reject attachments over the size limit or of a disallowed MIME type.
+    /// Unknown sizes pass; channels enforce their own upload limits.
+    pub fn check(&self, attachment: &Attachment) -> anyhow::Result<()> {
+        if let Some(size) = attachment.size_bytes {
+            if self.max_bytes > 0 && size > self.max_bytes {
+                anyhow::bail!(
+                    "{} is {size} bytes, over the {} byte attachment limit",
+                    attachment.target(),
+                    self.max_bytes
+                );
+            }
+        }
+        if !self.allowed_mime_types.is_empty() {
+            let mime_type = attachment.effective_mime_type().to_ascii_lowercase();
+            if !self
+                .allowed_mime_types
+                .iter()
+                .any(|pattern| mime_matches(pattern, &mime_type))
+            {
+                anyhow::bail!(
+                    "{} has MIME type {mime_type}, which is not allowed",
+                    attachment.target()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Split `attachments` into accepted and rejected, logging each rejection.
+    pub fn partition(&self, attachments: Vec<Attachment>) -> (Vec<Attachment>, Vec<Attachment>) {
+        attachments.into_iter().partition(|attachment| {
+            self.check(attachment)
+                .map_err(|error| tracing::warn!("Attachment rejected by policy: {error}"))
+                .is_ok()
+        })
+    }
+}
+
+fn mime_matches(pattern: &str, mime_type: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(top_level) => mime_type
+            .split_once('/')
+            .is_some_and(|(top, _)| top == top_level),
+        None => pattern == "*" || pattern == mime_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_markers_extracts_typed_attachments() {
+        let (text, attachments) = parse_markers(
+            "Here [IMAGE:/tmp/a.png] and [file: https://example.com/r.pdf?x=1] [note] [VIDEO:]",
+        );
+        assert_eq!(text, "Here  and  [note] [VIDEO:]");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(attachments[0].path(), Some(Path::new("/tmp/a.png")));
+        assert_eq!(attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(attachments[1].url(), Some("https://example.com/r.pdf?x=1"));
+        assert_eq!(attachments[1].file_name().as_deref(), Some("r.pdf"));
+        assert_eq!(attachments[1].effective_mime_type(), "application/pdf");
+
+        let (_, nested) = parse_markers("[DOCUMENT:/tmp/report [final].pdf]");
+        assert_eq!(nested[0].target(), "/tmp/report [final].pdf");
+    }
+
+    #[test]
+    fn source_parse_distinguishes_urls_from_paths() {
+        assert!(matches!(
+            AttachmentSource::parse("mxc://matrix.org/abc"),
+            AttachmentSource::Url(_)
+        ));
+        assert!(matches!(
+            AttachmentSource::parse("data:image/png;base64,AAAA"),
+            AttachmentSource::Url(_)
+        ));
+        assert_eq!(
+            AttachmentSource::parse("file:///tmp/a.txt"),
+            AttachmentSource::Path(PathBuf::from("/tmp/a.txt"))
+        );
+        assert!(matches!(
+            AttachmentSource::parse(r"C:\files\a.txt"),
+            AttachmentSource::Path(_)
+        ));
+    }
+
+    #[test]
+    fn markers_and_links_render_for_the_agent_and_link_only_channels() {
+        let image = Attachment::from_path(AttachmentKind::Image, "/ws/photo.jpg");
+        let doc = Attachment::from_url(AttachmentKind::Document, "https://example.com/a.pdf")
+            .with_caption("Report");
+
+        assert_eq!(
+            append_markers("see [IMAGE:/ws/photo.jpg]", &[image.clone(), doc.clone()]),
+            "see [IMAGE:/ws/photo.jpg]\n[DOCUMENT:https://example.com/a.pdf]"
+        );
+        assert_eq!(
+            append_links("", &[image, doc]),
+            "📎 photo.jpg\nReport: https://example.com/a.pdf"
+        );
+    }
+
+    #[test]
+    fn policy_enforces_size_and_mime_limits() {
+        let policy = AttachmentPolicy::from_config(&crate::config::ChannelAttachmentsConfig {
+            max_size_mb: 1,
+            allowed_mime_types: vec!["image/*".into(), "application/pdf".into()],
+        });
+        let png = Attachment::from_path(AttachmentKind::Image, "/tmp/a.png").with_size(1_000);
+        let big = png.clone().with_size(2 * 1024 * 1024);
+        let zip = Attachment::from_url(AttachmentKind::Document, "https://x.test/a.zip");
+
+        assert!(policy.check(&png).is_ok());
+        assert!(policy.check(&big).is_err());
+        assert!(policy.check(&zip).is_err());
+
+        let (accepted, rejected) = policy.partition(vec![png, big, zip]);
+        assert_eq!((accepted.len(), rejected.len()), (1, 2));
+        assert!(AttachmentPolicy {
+            max_bytes: 0,
+            allowed_mime_types: Vec::new()
+        }
+        .check(&rejected[0])
+        .is_ok());
+    }
+
+    #[test]
+    fn resolve_workspace_path_blocks_escape() {
+        let temp = tempfile::tempdir().unwrap();
+        let workspace = temp.path().join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::write(workspace.join("ok.txt"), b"ok").unwrap();
+        std::fs::write(temp.path().join("outside.txt"), b"no").unwrap();
+
+        assert!(resolve_workspace_path(&workspace, "ok.txt").is_ok());
+        assert!(resolve_workspace_path(&workspace, "/workspace/ok.txt").is_ok());
+        assert!(resolve_workspace_path(&workspace, "../outside.txt").is_err());
+
+        let saved = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(save_inbound(
+                &workspace,
+                "inbox",
+                "../../x/.evil name?.txt",
+                b"hi",
+            ))
+            .unwrap();
+        assert_eq!(saved, workspace.join("inbox").join("evil_name_.txt"));
+
+        let local = Attachment::from_path(AttachmentKind::Document, workspace.join("ok.txt"))
+            .with_local_metadata();
+        assert_eq!(local.size_bytes, Some(2));
+        assert_eq!(local.mime_type.as_deref(), Some("text/plain"));
+    }
+}
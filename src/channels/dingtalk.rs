@@ -278,6 +278,8 @@ impl Channel for DingTalkChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
+                        transcribed_voice: false,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -1,12 +1,12 @@
+use super::attachments::{self, Attachment, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
-use anyhow::Context;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::Mutex;
 use reqwest::multipart::{Form, Part};
 use serde_json::json;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
@@ -85,36 +85,7 @@ impl DiscordChannel {
         let workspace = self.workspace_dir.as_ref().ok_or_else(|| {
             anyhow::anyhow!("workspace_dir is not configured; local file attachments are disabled")
         })?;
-        let workspace_root = workspace
-            .canonicalize()
-            .unwrap_or_else(|_| workspace.to_path_buf());
-
-        let target_path = if let Some(rel) = target.strip_prefix("/workspace/") {
-            workspace.join(rel)
-        } else if target == "/workspace" {
-            workspace.to_path_buf()
-        } else {
-            let path = Path::new(target);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                workspace.join(path)
-            }
-        };
-
-        let resolved = target_path
-            .canonicalize()
-            .with_context(|| format!("attachment path not found: {target}"))?;
-
-        if !resolved.starts_with(&workspace_root) {
-            anyhow::bail!("attachment path escapes workspace: {target}");
-        }
-
-        if !resolved.is_file() {
-            anyhow::bail!("attachment path is not a file: {}", resolved.display());
-        }
-
-        Ok(resolved)
+        attachments::resolve_workspace_path(workspace, target)
     }
 }
 
@@ -177,107 +148,48 @@ async fn process_attachments(
     parts.join("\n---\n")
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum DiscordAttachmentKind {
-    Image,
-    Document,
-    Video,
-    Audio,
-    Voice,
-}
-
-impl DiscordAttachmentKind {
-    fn from_marker(kind: &str) -> Option<Self> {
-        match kind.trim().to_ascii_uppercase().as_str() {
-            "IMAGE" | "PHOTO" => Some(Self::Image),
-            "DOCUMENT" | "FILE" => Some(Self::Document),
-            "VIDEO" => Some(Self::Video),
-            "AUDIO" => Some(Self::Audio),
-            "VOICE" => Some(Self::Voice),
-            _ => None,
-        }
-    }
-
-    fn marker_name(&self) -> &'static str {
-        match self {
-            Self::Image => "IMAGE",
-            Self::Document => "DOCUMENT",
-            Self::Video => "VIDEO",
-            Self::Audio => "AUDIO",
-            Self::Voice => "VOICE",
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct DiscordAttachment {
-    kind: DiscordAttachmentKind,
-    target: String,
-}
-
-fn parse_attachment_markers(message: &str) -> (String, Vec<DiscordAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0usize;
-
-    while let Some(rel_start) = message[cursor..].find('[') {
-        let start = cursor + rel_start;
-        cleaned.push_str(&message[cursor..start]);
-
-        let Some(rel_end) = message[start..].find(']') else {
-            cleaned.push_str(&message[start..]);
-            cursor = message.len();
-            break;
-        };
-        let end = start + rel_end;
-        let marker_text = &message[start + 1..end];
-
-        let parsed = marker_text.split_once(':').and_then(|(kind, target)| {
-            let kind = DiscordAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
+/// Media from an inbound Discord message. Text attachments are inlined by
+/// [`process_attachments`] instead.
+fn inbound_attachments(attachments: &[serde_json::Value]) -> Vec<Attachment> {
+    attachments
+        .iter()
+        .filter_map(|att| {
+            let url = att.get("url").and_then(|v| v.as_str())?;
+            let content_type = att
+                .get("content_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("application/octet-stream");
+            if content_type.starts_with("text/") {
                 return None;
             }
-            Some(DiscordAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
-
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            cleaned.push_str(&message[start..=end]);
-        }
-
-        cursor = end + 1;
-    }
-
-    if cursor < message.len() {
-        cleaned.push_str(&message[cursor..]);
-    }
-
-    (cleaned.trim().to_string(), attachments)
+            let mut attachment =
+                Attachment::from_url(AttachmentKind::from_mime_type(content_type), url)
+                    .with_mime_type(content_type);
+            if let Some(size) = att.get("size").and_then(serde_json::Value::as_u64) {
+                attachment = attachment.with_size(size);
+            }
+            if let Some(description) = att.get("description").and_then(|v| v.as_str()) {
+                attachment = attachment.with_caption(description);
+            }
+            Some(attachment)
+        })
+        .collect()
 }
 
-fn classify_outgoing_attachments(
-    attachments: &[DiscordAttachment],
-) -> (Vec<DiscordAttachment>, Vec<String>, Vec<String>) {
+/// Split outgoing attachments into local files to upload and remote URLs
+/// to post inline.
+fn classify_outgoing_attachments(attachments: &[Attachment]) -> (Vec<Attachment>, Vec<String>) {
     let mut local_files = Vec::new();
     let mut remote_urls = Vec::new();
-    let unresolved_markers = Vec::new();
 
     for attachment in attachments {
-        let target = attachment.target.trim();
-        if target.starts_with("https://") || target.starts_with("http://") {
-            remote_urls.push(target.to_string());
-            continue;
+        match attachment.url() {
+            Some(url) => remote_urls.push(url.to_string()),
+            None => local_files.push(attachment.clone()),
         }
-
-        local_files.push(attachment.clone());
     }
 
-    (local_files, remote_urls, unresolved_markers)
+    (local_files, remote_urls)
 }
 
 fn with_inline_attachment_urls(
@@ -559,14 +471,16 @@ impl Channel for DiscordChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         let raw_content = super::strip_tool_call_tags(&message.content);
-        let (cleaned_content, parsed_attachments) = parse_attachment_markers(&raw_content);
-        let (local_attachment_targets, remote_urls, mut unresolved_markers) =
-            classify_outgoing_attachments(&parsed_attachments);
+        let (cleaned_content, mut outgoing_attachments) = attachments::parse_markers(&raw_content);
+        outgoing_attachments.splice(0..0, message.attachments.iter().cloned());
+        let (local_attachment_targets, remote_urls) =
+            classify_outgoing_attachments(&outgoing_attachments);
         let mut local_files = Vec::new();
+        let mut unresolved_markers = Vec::new();
 
         for attachment in &local_attachment_targets {
-            let target = attachment.target.trim();
-            match self.resolve_local_attachment_path(target) {
+            let target = attachment.target();
+            match self.resolve_local_attachment_path(&target) {
                 Ok(path) => local_files.push(path),
                 Err(error) => {
                     tracing::warn!(
@@ -574,11 +488,7 @@ impl Channel for DiscordChannel {
                         error = %error,
                         "discord: local attachment rejected by workspace policy"
                     );
-                    unresolved_markers.push(format!(
-                        "[{}:{}]",
-                        attachment.kind.marker_name(),
-                        target
-                    ));
+                    unresolved_markers.push(attachment.marker());
                 }
             }
         }
@@ -628,6 +538,10 @@ impl Channel for DiscordChannel {
     }
 
     #[allow(clippy::too_many_lines)]
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = Self::bot_user_id_from_token(&self.bot_token).unwrap_or_default();
 
@@ -795,20 +709,21 @@ impl Channel for DiscordChannel {
                         is_group_message && self.is_group_sender_trigger_enabled(author_id);
                     let require_mention =
                         self.mention_only && is_group_message && !allow_sender_without_mention;
-                    let Some(clean_content) =
-                        normalize_incoming_content(content, require_mention, &bot_user_id)
-                    else {
-                        continue;
-                    };
-
-                    let attachment_text = {
-                        let atts = d
-                            .get("attachments")
-                            .and_then(|a| a.as_array())
-                            .cloned()
-                            .unwrap_or_default();
-                        process_attachments(&atts, &self.http_client()).await
-                    };
+                    let atts = d
+                        .get("attachments")
+                        .and_then(|a| a.as_array())
+                        .cloned()
+                        .unwrap_or_default();
+                    let media = inbound_attachments(&atts);
+                    let clean_content =
+                        match normalize_incoming_content(content, require_mention, &bot_user_id) {
+                            Some(clean_content) => clean_content,
+                            // Media-only messages carry no text to normalize.
+                            None if !require_mention && !media.is_empty() => String::new(),
+                            None => continue,
+                        };
+
+                    let attachment_text = process_attachments(&atts, &self.http_client()).await;
                     let final_content = if attachment_text.is_empty() {
                         clean_content
                     } else {
@@ -868,6 +783,8 @@ impl Channel for DiscordChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: media,
+                        transcribed_voice: false,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -1559,54 +1476,46 @@ mod tests {
     }
 
     #[test]
-    fn parse_attachment_markers_extracts_supported_markers() {
-        let input = "Report\n[IMAGE:https://example.com/a.png]\n[DOCUMENT:/tmp/a.pdf]";
-        let (cleaned, attachments) = parse_attachment_markers(input);
-
-        assert_eq!(cleaned, "Report");
-        assert_eq!(attachments.len(), 2);
-        assert_eq!(attachments[0].kind, DiscordAttachmentKind::Image);
-        assert_eq!(attachments[0].target, "https://example.com/a.png");
-        assert_eq!(attachments[1].kind, DiscordAttachmentKind::Document);
-        assert_eq!(attachments[1].target, "/tmp/a.pdf");
-    }
-
-    #[test]
-    fn parse_attachment_markers_keeps_invalid_marker_text() {
-        let input = "Hello [NOT_A_MARKER:foo] world";
-        let (cleaned, attachments) = parse_attachment_markers(input);
+    fn inbound_attachments_skip_inlined_text_files() {
+        let attachments = vec![
+            serde_json::json!({
+                "url": "https://cdn.discordapp.com/attachments/1/2/cat.png",
+                "filename": "cat.png",
+                "content_type": "image/png",
+                "size": 2048,
+                "description": "a cat"
+            }),
+            serde_json::json!({
+                "url": "https://cdn.discordapp.com/attachments/1/3/notes.txt",
+                "filename": "notes.txt",
+                "content_type": "text/plain"
+            }),
+        ];
 
-        assert_eq!(cleaned, input);
-        assert!(attachments.is_empty());
+        let media = inbound_attachments(&attachments);
+        assert_eq!(media.len(), 1);
+        assert_eq!(media[0].kind, AttachmentKind::Image);
+        assert_eq!(media[0].size_bytes, Some(2048));
+        assert_eq!(media[0].caption.as_deref(), Some("a cat"));
+        assert_eq!(
+            media[0].url(),
+            Some("https://cdn.discordapp.com/attachments/1/2/cat.png")
+        );
     }
 
     #[test]
-    fn classify_outgoing_attachments_splits_local_remote_and_unresolved() {
-        let temp = tempfile::tempdir().expect("tempdir");
-        let file_path = temp.path().join("image.png");
-        std::fs::write(&file_path, b"fake").expect("write fixture");
-
+    fn classify_outgoing_attachments_splits_local_and_remote() {
         let attachments = vec![
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Image,
-                target: file_path.to_string_lossy().to_string(),
-            },
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Image,
-                target: "https://example.com/remote.png".to_string(),
-            },
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Video,
-                target: "/tmp/does-not-exist.mp4".to_string(),
-            },
+            Attachment::from_path(AttachmentKind::Image, "/tmp/image.png"),
+            Attachment::from_url(AttachmentKind::Image, "https://example.com/remote.png"),
+            Attachment::from_path(AttachmentKind::Video, "/tmp/does-not-exist.mp4"),
         ];
 
-        let (locals, remotes, unresolved) = classify_outgoing_attachments(&attachments);
+        let (locals, remotes) = classify_outgoing_attachments(&attachments);
         assert_eq!(locals.len(), 2);
-        assert_eq!(locals[0].target, file_path.to_string_lossy());
-        assert_eq!(locals[1].target, "/tmp/does-not-exist.mp4");
+        assert_eq!(locals[0].target(), "/tmp/image.png");
+        assert_eq!(locals[1].target(), "/tmp/does-not-exist.mp4");
         assert_eq!(remotes, vec!["https://example.com/remote.png".to_string()]);
-        assert!(unresolved.is_empty());
     }
 
     #[test]
@@ -1628,7 +1537,7 @@ mod tests {
             .with_workspace_dir(PathBuf::from("/tmp/discord-workspace"));
         assert_eq!(
             channel.workspace_dir.as_deref(),
-            Some(Path::new("/tmp/discord-workspace"))
+            Some(std::path::Path::new("/tmp/discord-workspace"))
         );
     }
 
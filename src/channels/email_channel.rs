@@ -14,7 +14,8 @@ use async_imap::types::Fetch;
 use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
-use lettre::message::SinglePart;
+use lettre::message::header::ContentType;
+use lettre::message::{MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
@@ -23,6 +24,7 @@ use rustls_pki_types::DnsName;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
@@ -33,6 +35,7 @@ use tokio_rustls::TlsConnector;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use super::attachments::{self, Attachment, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 
 /// Email channel configuration
@@ -118,6 +121,7 @@ type ImapSession = Session<TlsStream<TcpStream>>;
 pub struct EmailChannel {
     pub config: EmailConfig,
     seen_messages: Arc<Mutex<HashSet<String>>>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl EmailChannel {
@@ -125,9 +129,17 @@ impl EmailChannel {
         Self {
             config,
             seen_messages: Arc::new(Mutex::new(HashSet::new())),
+            workspace_dir: None,
         }
     }
 
+    /// Configure the workspace used to store received attachments and to
+    /// validate outgoing attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     /// Check if a sender email is in the allowlist
     pub fn is_sender_allowed(&self, email: &str) -> bool {
         if self.config.allowed_senders.is_empty() {
@@ -205,6 +217,67 @@ impl EmailChannel {
         "(no readable content)".to_string()
     }
 
+    /// Collect non-text attachment parts as (file name, MIME type, bytes)
+    fn extract_attachments(parsed: &mail_parser::Message) -> Vec<(String, String, Vec<u8>)> {
+        parsed
+            .attachments()
+            .filter_map(|part| {
+                let ct = MimeHeaders::content_type(part)?;
+                if ct.ctype() == "text" {
+                    return None;
+                }
+                let mime_type = match ct.subtype() {
+                    Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+                    None => ct.ctype().to_string(),
+                };
+                let name = MimeHeaders::attachment_name(part).unwrap_or("attachment");
+                Some((name.to_string(), mime_type, part.contents().to_vec()))
+            })
+            .collect()
+    }
+
+    /// Save received attachment parts to `<workspace>/email_attachments/`.
+    /// Without a workspace there is nowhere to keep them, so they are dropped.
+    async fn save_attachments(&self, parts: Vec<(String, String, Vec<u8>)>) -> Vec<Attachment> {
+        let Some(workspace) = self.workspace_dir.as_ref() else {
+            if !parts.is_empty() {
+                debug!("Dropping {} email attachments: no workspace", parts.len());
+            }
+            return Vec::new();
+        };
+        let mut saved = Vec::with_capacity(parts.len());
+        for (name, mime_type, bytes) in parts {
+            match attachments::save_inbound(workspace, "email_attachments", &name, &bytes).await {
+                Ok(path) => saved.push(
+                    Attachment::from_path(AttachmentKind::from_mime_type(&mime_type), path)
+                        .with_mime_type(mime_type)
+                        .with_size(bytes.len() as u64),
+                ),
+                Err(e) => warn!("Failed to save email attachment {name}: {e}"),
+            }
+        }
+        saved
+    }
+
+    /// Build a MIME part for an outgoing attachment, reading it from the workspace
+    async fn attachment_part(&self, attachment: &Attachment) -> Result<SinglePart> {
+        let path = attachment
+            .path()
+            .ok_or_else(|| anyhow!("attachment is not a local file"))?;
+        let workspace = self
+            .workspace_dir
+            .as_ref()
+            .ok_or_else(|| anyhow!("workspace_dir is not configured"))?;
+        let path = attachments::resolve_workspace_path(workspace, &path.to_string_lossy())?;
+        let bytes = tokio::fs::read(&path).await?;
+        let file_name = attachment
+            .file_name()
+            .unwrap_or_else(|| "attachment".to_string());
+        let content_type = ContentType::parse(&attachment.effective_mime_type())
+            .unwrap_or(ContentType::parse("application/octet-stream")?);
+        Ok(lettre::message::Attachment::new(file_name).body(bytes, content_type))
+    }
+
     /// Connect to IMAP server with TLS and authenticate
     async fn connect_imap(&self) -> Result<ImapSession> {
         let addr = format!("{}:{}", self.config.imap_host, self.config.imap_port);
@@ -265,6 +338,7 @@ impl EmailChannel {
                     let sender = Self::extract_sender(&parsed);
                     let subject = parsed.subject().unwrap_or("(no subject)").to_string();
                     let body_text = Self::extract_text(&parsed);
+                    let attachments = Self::extract_attachments(&parsed);
                     let content = format!("Subject: {}\n\n{}", subject, body_text);
                     let msg_id = parsed
                         .message_id()
@@ -302,6 +376,7 @@ impl EmailChannel {
                         sender,
                         content,
                         timestamp: ts,
+                        attachments,
                     });
                 }
             }
@@ -460,6 +535,8 @@ impl EmailChannel {
                 channel: "email".to_string(),
                 timestamp: email.timestamp,
                 thread_ts: None,
+                attachments: self.save_attachments(email.attachments).await,
+                transcribed_voice: false,
             };
 
             if tx.send(msg).await.is_err() {
@@ -495,6 +572,7 @@ struct ParsedEmail {
     sender: String,
     content: String,
     timestamp: u64,
+    attachments: Vec<(String, String, Vec<u8>)>,
 }
 
 /// Result from waiting on IDLE
@@ -524,11 +602,39 @@ impl Channel for EmailChannel {
             ("ZeroClaw Message", message.content.as_str())
         };
 
-        let email = Message::builder()
+        // Local workspace files become MIME parts; remote media stays a link.
+        let (body, mut outgoing) = attachments::parse_markers(body);
+        outgoing.splice(0..0, message.attachments.iter().cloned());
+        let mut parts = Vec::new();
+        let mut links = Vec::new();
+        for attachment in outgoing {
+            if attachment.url().is_some() {
+                links.push(attachment);
+                continue;
+            }
+            match self.attachment_part(&attachment).await {
+                Ok(part) => parts.push(part),
+                Err(e) => {
+                    warn!("Email attachment {} not sent: {e}", attachment.target());
+                    links.push(attachment);
+                }
+            }
+        }
+        let body = attachments::append_links(&body, &links);
+
+        let builder = Message::builder()
             .from(self.config.from_address.parse()?)
             .to(message.recipient.parse()?)
-            .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
+            .subject(subject);
+        let email = if parts.is_empty() {
+            builder.singlepart(SinglePart::plain(body))?
+        } else {
+            let multipart = parts.into_iter().fold(
+                MultiPart::mixed().singlepart(SinglePart::plain(body)),
+                |mp, part| mp.singlepart(part),
+            );
+            builder.multipart(multipart)?
+        };
 
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
@@ -536,6 +642,10 @@ impl Channel for EmailChannel {
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> Result<()> {
         info!(
             "Starting email channel with IDLE support on {}",
@@ -232,6 +232,8 @@ end tell"#
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: None,
+                            attachments: Vec::new(),
+                            transcribed_voice: false,
                         };
 
                         if tx.send(msg).await.is_err() {
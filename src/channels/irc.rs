@@ -304,7 +304,7 @@ impl IrcChannel {
 
 /// Certificate verifier that accepts any certificate (for `verify_tls=false`).
 #[derive(Debug)]
-struct NoVerify;
+pub(crate) struct NoVerify;
 
 impl rustls::client::danger::ServerCertVerifier for NoVerify {
     fn verify_server_cert(
@@ -435,48 +435,42 @@ impl Channel for IrcChannel {
                 }
 
                 // CAP responses for SASL
-                "CAP" => {
-                    if sasl_pending && msg.params.iter().any(|p| p.contains("sasl")) {
-                        if msg.params.iter().any(|p| p.contains("ACK")) {
-                            // CAP * ACK :sasl — server accepted, start SASL auth
-                            let mut guard = self.writer.lock().await;
-                            if let Some(ref mut w) = *guard {
-                                Self::send_raw(w, "AUTHENTICATE PLAIN").await?;
-                            }
-                        } else if msg.params.iter().any(|p| p.contains("NAK")) {
-                            // CAP * NAK :sasl — server rejected SASL, proceed without it
-                            tracing::warn!(
-                                "IRC server does not support SASL, continuing without it"
-                            );
-                            sasl_pending = false;
-                            let mut guard = self.writer.lock().await;
-                            if let Some(ref mut w) = *guard {
-                                Self::send_raw(w, "CAP END").await?;
-                            }
+                "CAP" if sasl_pending && msg.params.iter().any(|p| p.contains("sasl")) => {
+                    if msg.params.iter().any(|p| p.contains("ACK")) {
+                        // CAP * ACK :sasl — server accepted, start SASL auth
+                        let mut guard = self.writer.lock().await;
+                        if let Some(ref mut w) = *guard {
+                            Self::send_raw(w, "AUTHENTICATE PLAIN").await?;
+                        }
+                    } else if msg.params.iter().any(|p| p.contains("NAK")) {
+                        // CAP * NAK :sasl — server rejected SASL, proceed without it
+                        tracing::warn!("IRC server does not support SASL, continuing without it");
+                        sasl_pending = false;
+                        let mut guard = self.writer.lock().await;
+                        if let Some(ref mut w) = *guard {
+                            Self::send_raw(w, "CAP END").await?;
                         }
                     }
                 }
 
-                "AUTHENTICATE" => {
-                    // Server sends "AUTHENTICATE +" to request credentials
-                    if sasl_pending && msg.params.first().is_some_and(|p| p == "+") {
-                        // sasl_password is loaded from runtime config, not hard-coded
-                        if let Some(password) = self.sasl_password.as_deref() {
-                            let encoded = encode_sasl_plain(&current_nick, password);
-                            let mut guard = self.writer.lock().await;
-                            if let Some(ref mut w) = *guard {
-                                Self::send_raw(w, &format!("AUTHENTICATE {encoded}")).await?;
-                            }
-                        } else {
-                            // SASL was requested but no password is configured; abort SASL
-                            tracing::warn!(
-                                "SASL authentication requested but no SASL password is configured; aborting SASL"
-                            );
-                            sasl_pending = false;
-                            let mut guard = self.writer.lock().await;
-                            if let Some(ref mut w) = *guard {
-                                Self::send_raw(w, "CAP END").await?;
-                            }
+                // Server sends "AUTHENTICATE +" to request credentials
+                "AUTHENTICATE" if sasl_pending && msg.params.first().is_some_and(|p| p == "+") => {
+                    // sasl_password is loaded from runtime config, not hard-coded
+                    if let Some(password) = self.sasl_password.as_deref() {
+                        let encoded = encode_sasl_plain(&current_nick, password);
+                        let mut guard = self.writer.lock().await;
+                        if let Some(ref mut w) = *guard {
+                            Self::send_raw(w, &format!("AUTHENTICATE {encoded}")).await?;
+                        }
+                    } else {
+                        // SASL was requested but no password is configured; abort SASL
+                        tracing::warn!(
+                            "SASL authentication requested but no SASL password is configured; aborting SASL"
+                        );
+                        sasl_pending = false;
+                        let mut guard = self.writer.lock().await;
+                        if let Some(ref mut w) = *guard {
+                            Self::send_raw(w, "CAP END").await?;
                         }
                     }
                 }
@@ -580,6 +574,8 @@ impl Channel for IrcChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
+                        transcribed_voice: false,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
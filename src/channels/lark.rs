@@ -927,6 +927,8 @@ impl LarkChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
+                        transcribed_voice: false,
                     };
 
                     tracing::debug!("Lark WS: message in {}", lark_msg.chat_id);
@@ -1235,6 +1237,8 @@ impl LarkChannel {
             channel: self.channel_name().to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         });
 
         messages
@@ -1373,6 +1377,8 @@ impl LarkChannel {
             channel: self.channel_name().to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         });
 
         messages
@@ -250,6 +250,8 @@ impl LinqChannel {
             channel: "linq".to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         });
 
         messages
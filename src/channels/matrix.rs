@@ -1,12 +1,15 @@
+use crate::channels::attachments::{self, Attachment, AttachmentKind};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use matrix_sdk::{
+    attachment::AttachmentConfig,
     authentication::matrix::MatrixSession,
     config::SyncSettings,
     ruma::{
         events::room::message::{
             MessageType, OriginalSyncRoomMessageEvent, Relation, RoomMessageEventContent,
         },
+        events::room::MediaSource,
         events::Mentions,
         OwnedRoomId, OwnedUserId,
     },
@@ -30,6 +33,7 @@ pub struct MatrixChannel {
     session_owner_hint: Option<String>,
     session_device_id_hint: Option<String>,
     zeroclaw_dir: Option<PathBuf>,
+    workspace_dir: Option<PathBuf>,
     resolved_room_id_cache: Arc<RwLock<Option<String>>>,
     sdk_client: Arc<OnceCell<MatrixSdkClient>>,
     http_client: Client,
@@ -169,6 +173,7 @@ impl MatrixChannel {
             session_owner_hint: Self::normalize_optional_field(owner_hint),
             session_device_id_hint: Self::normalize_optional_field(device_id_hint),
             zeroclaw_dir,
+            workspace_dir: None,
             resolved_room_id_cache: Arc::new(RwLock::new(None)),
             sdk_client: Arc::new(OnceCell::new()),
             http_client: Client::new(),
@@ -180,6 +185,12 @@ impl MatrixChannel {
         self
     }
 
+    /// Configure the workspace used to validate local upload paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn encode_path_segment(value: &str) -> String {
         fn should_encode(byte: u8) -> bool {
             !matches!(
@@ -231,6 +242,63 @@ impl MatrixChannel {
         !body.trim().is_empty()
     }
 
+    /// Map an image/file/audio/video event to an attachment pointing at its
+    /// `mxc://` URI. The body is a caption only when it differs from the
+    /// file name.
+    fn media_attachment(msgtype: &MessageType) -> Option<Attachment> {
+        let (kind, body, filename, source, mimetype, size) = match msgtype {
+            MessageType::Image(c) => (
+                AttachmentKind::Image,
+                &c.body,
+                c.filename.as_deref(),
+                &c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+                c.info.as_ref().and_then(|i| i.size),
+            ),
+            MessageType::File(c) => (
+                AttachmentKind::Document,
+                &c.body,
+                c.filename.as_deref(),
+                &c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+                c.info.as_ref().and_then(|i| i.size),
+            ),
+            MessageType::Audio(c) => (
+                AttachmentKind::Audio,
+                &c.body,
+                c.filename.as_deref(),
+                &c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+                c.info.as_ref().and_then(|i| i.size),
+            ),
+            MessageType::Video(c) => (
+                AttachmentKind::Video,
+                &c.body,
+                c.filename.as_deref(),
+                &c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+                c.info.as_ref().and_then(|i| i.size),
+            ),
+            _ => return None,
+        };
+
+        let uri = match source {
+            MediaSource::Plain(uri) => uri.to_string(),
+            MediaSource::Encrypted(file) => file.url.to_string(),
+        };
+        let mut attachment = Attachment::from_url(kind, uri);
+        if let Some(mimetype) = mimetype {
+            attachment = attachment.with_mime_type(mimetype);
+        }
+        if let Some(size) = size {
+            attachment = attachment.with_size(u64::from(size));
+        }
+        if filename.is_some_and(|name| name != body) {
+            attachment = attachment.with_caption(body.clone());
+        }
+        Some(attachment)
+    }
+
     fn is_matrix_identifier_char(ch: char) -> bool {
         ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.')
     }
@@ -692,12 +760,56 @@ impl Channel for MatrixChannel {
             anyhow::bail!("Matrix room '{}' is not in joined state", target_room_id);
         }
 
-        room.send(RoomMessageEventContent::text_markdown(&message.content))
-            .await?;
+        let (text, outgoing) = message.split_attachments();
+        if outgoing.is_empty() {
+            room.send(RoomMessageEventContent::text_markdown(&message.content))
+                .await?;
+            return Ok(());
+        }
+
+        // Local workspace files are uploaded; remote media is sent as links.
+        let mut uploads = Vec::new();
+        let mut links = Vec::new();
+        for attachment in outgoing {
+            let resolved = match (attachment.path(), self.workspace_dir.as_ref()) {
+                (Some(path), Some(workspace)) => {
+                    attachments::resolve_workspace_path(workspace, &path.to_string_lossy())
+                        .map_err(|e| {
+                            tracing::warn!("Matrix: local attachment rejected: {e}");
+                        })
+                        .ok()
+                }
+                _ => None,
+            };
+            match resolved {
+                Some(path) => uploads.push((path, attachment)),
+                None => links.push(attachment),
+            }
+        }
+
+        let text = attachments::append_links(&text, &links);
+        if !text.is_empty() {
+            room.send(RoomMessageEventContent::text_markdown(&text))
+                .await?;
+        }
+        for (path, attachment) in uploads {
+            let bytes = tokio::fs::read(&path).await?;
+            let file_name = attachment.file_name().unwrap_or_else(|| "file".to_string());
+            let content_type: mime_guess::mime::Mime = attachment
+                .effective_mime_type()
+                .parse()
+                .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM);
+            room.send_attachment(file_name, &content_type, bytes, AttachmentConfig::new())
+                .await?;
+        }
 
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let target_room_id = self.target_room_id().await?;
         self.ensure_room_supported(&target_room_id).await?;
@@ -773,13 +885,21 @@ impl Channel for MatrixChannel {
                     return;
                 }
 
+                let mut media = Vec::new();
                 let body = match &event.content.msgtype {
                     MessageType::Text(content) => content.body.clone(),
                     MessageType::Notice(content) => content.body.clone(),
-                    _ => return,
+                    other => match MatrixChannel::media_attachment(other) {
+                        Some(attachment) => {
+                            let caption = attachment.caption.clone().unwrap_or_default();
+                            media.push(attachment);
+                            caption
+                        }
+                        None => return,
+                    },
                 };
 
-                if !MatrixChannel::has_non_empty_body(&body) {
+                if !MatrixChannel::has_non_empty_body(&body) && media.is_empty() {
                     return;
                 }
 
@@ -831,6 +951,8 @@ impl Channel for MatrixChannel {
                         .unwrap_or_default()
                         .as_secs(),
                     thread_ts: None,
+                    attachments: media,
+                    transcribed_voice: false,
                 };
 
                 let _ = tx.send(msg).await;
@@ -344,6 +344,8 @@ impl MattermostChannel {
             #[allow(clippy::cast_sign_loss)]
             timestamp: (create_at / 1000) as u64,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         })
     }
 }
@@ -384,7 +386,7 @@ fn is_mattermost_username_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'
 }
 
-fn find_bot_mention_spans(text: &str, bot_username: &str) -> Vec<(usize, usize)> {
+pub(super) fn find_bot_mention_spans(text: &str, bot_username: &str) -> Vec<(usize, usize)> {
     if bot_username.is_empty() {
         return Vec::new();
     }
@@ -470,7 +472,7 @@ fn normalize_mattermost_content(
     Some(cleaned)
 }
 
-fn normalize_group_reply_allowed_sender_ids(sender_ids: Vec<String>) -> Vec<String> {
+pub(super) fn normalize_group_reply_allowed_sender_ids(sender_ids: Vec<String>) -> Vec<String> {
     let mut normalized = sender_ids
         .into_iter()
         .map(|entry| entry.trim().to_string())
@@ -14,6 +14,7 @@
 //! To add a new channel, implement [`Channel`] in a new submodule and wire it into
 //! [`start_channels`]. See `AGENTS.md` §7.2 for the full change playbook.
 
+pub mod attachments;
 pub mod clawdtalk;
 pub mod cli;
 pub mod dingtalk;
@@ -30,17 +31,23 @@ pub mod mattermost;
 pub mod nextcloud_talk;
 pub mod nostr;
 pub mod qq;
+pub mod rocketchat;
+pub mod session_store;
 pub mod signal;
 pub mod slack;
+pub mod teams;
 pub mod telegram;
 pub mod traits;
 pub mod transcription;
+pub mod tts;
 pub mod wati;
 pub mod whatsapp;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_storage;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_web;
+pub mod xmpp;
+pub mod zulip;
 
 pub use clawdtalk::ClawdTalkChannel;
 pub use cli::CliChannel;
@@ -58,14 +65,18 @@ pub use mattermost::MattermostChannel;
 pub use nextcloud_talk::NextcloudTalkChannel;
 pub use nostr::NostrChannel;
 pub use qq::QQChannel;
+pub use rocketchat::RocketChatChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
+pub use teams::TeamsChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
 pub use wati::WatiChannel;
 pub use whatsapp::WhatsAppChannel;
 #[cfg(feature = "whatsapp-web")]
 pub use whatsapp_web::WhatsAppWebChannel;
+pub use xmpp::XmppChannel;
+pub use zulip::ZulipChannel;
 
 use crate::agent::loop_::{
     build_shell_policy_instructions, build_tool_instructions_from_specs, run_tool_call_loop,
@@ -124,6 +135,8 @@ const MEMORY_CONTEXT_ENTRY_MAX_CHARS: usize = 800;
 const MEMORY_CONTEXT_MAX_CHARS: usize = 4_000;
 const CHANNEL_HISTORY_COMPACT_KEEP_MESSAGES: usize = 12;
 const CHANNEL_HISTORY_COMPACT_CONTENT_CHARS: usize = 600;
+/// How often a running daemon enforces session retention.
+const CHANNEL_SESSION_PRUNE_INTERVAL_SECS: u64 = 3600;
 /// Guardrail for hook-modified outbound channel content.
 const CHANNEL_HOOK_MAX_OUTBOUND_CHARS: usize = 20_000;
 
@@ -235,7 +248,12 @@ struct ChannelRuntimeContext {
     auto_save_memory: bool,
     max_tool_iterations: usize,
     min_relevance_score: f64,
+    memory_ranking: crate::config::MemoryRankingConfig,
+    memory_namespaces: crate::config::MemoryNamespaceConfig,
+    attachment_policy: attachments::AttachmentPolicy,
+    tts: Option<Arc<tts::TtsService>>,
     conversation_histories: ConversationHistoryMap,
+    session_writer: Option<Arc<session_store::SessionWriter>>,
     provider_cache: ProviderCacheMap,
     route_overrides: RouteSelectionMap,
     api_key: Option<String>,
@@ -725,7 +743,6 @@ fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRun
                 Some(ChannelRuntimeCommand::SetModel(model))
             }
         }
-        "/new" => Some(ChannelRuntimeCommand::NewSession),
         _ => None,
     }
 }
@@ -1360,7 +1377,7 @@ async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Resul
 
     let (next_defaults, next_autonomy_policy) =
         load_runtime_defaults_from_config_file(&config_path).await?;
-    let next_default_provider = providers::create_resilient_provider_with_options(
+    let next_default_provider = providers::create_channel_provider_with_options(
         &next_defaults.default_provider,
         next_defaults.api_key.as_deref(),
         next_defaults.api_url.as_deref(),
@@ -1446,13 +1463,29 @@ fn get_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str) -> Channel
 }
 
 /// Classify a user message and return the appropriate route selection with logging.
-/// Returns None if classification is disabled or no rules match.
-fn classify_message_route(
+/// Returns None if classification is disabled or neither the rules nor the
+/// fallback model (asked through the default provider) pick a route.
+async fn classify_message_route(
     ctx: &ChannelRuntimeContext,
     message: &str,
 ) -> Option<ChannelRouteSelection> {
-    let decision =
-        crate::agent::classifier::classify_with_decision(&ctx.query_classification, message)?;
+    let config = &ctx.query_classification;
+    let decision = match crate::agent::classifier::classify_with_decision(config, message) {
+        Some(decision) => decision,
+        None if config.enabled && config.fallback_model.is_some() => {
+            let provider = default_route_selection(ctx).provider;
+            let provider = match get_or_create_provider(ctx, &provider).await {
+                Ok(provider) => provider,
+                Err(e) => {
+                    tracing::warn!("Query classification provider unavailable: {e}");
+                    return None;
+                }
+            };
+            crate::agent::classifier::classify_with_fallback(config, provider.as_ref(), message)
+                .await?
+        }
+        None => return None,
+    };
 
     // Find the matching model route
     let route = ctx.model_routes.iter().find(|r| r.hint == decision.hint)?;
@@ -1485,14 +1518,124 @@ fn set_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str, next: Chan
     }
 }
 
+/// Queue the current in-memory turns for `sender_key` for the durable session
+/// store (if enabled). The write happens on the session writer thread;
+/// failures are logged there and never block a reply.
+fn persist_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
+    let Some(writer) = ctx.session_writer.as_ref() else {
+        return;
+    };
+
+    let channel = session_store::channel_for_history_key(
+        sender_key,
+        ctx.channels_by_name.keys().map(String::as_str),
+    )
+    .unwrap_or_else(|| "unknown".to_string());
+    let histories = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let turns = histories.get(sender_key).cloned().unwrap_or_default();
+    // Queue before releasing the lock so snapshots reach the writer in the
+    // order the history changed.
+    writer.save(sender_key, &channel, turns);
+}
+
+/// Evict histories deleted from outside the runtime (CLI, gateway API,
+/// retention pruning) so the next turn does not write them back.
+async fn apply_session_tombstones(ctx: &ChannelRuntimeContext) {
+    let Some(store) = ctx.session_writer.as_ref().map(|w| Arc::clone(w.store())) else {
+        return;
+    };
+    let tombstones = match tokio::task::spawn_blocking(move || store.take_tombstones()).await {
+        Ok(Ok(tombstones)) => tombstones,
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to read channel session tombstones: {e:#}");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("Channel session tombstone read panicked: {e}");
+            return;
+        }
+    };
+    if tombstones.is_empty() {
+        return;
+    }
+
+    let evicted: Vec<String> = {
+        let mut histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let evicted: Vec<String> = histories
+            .keys()
+            .filter(|key| {
+                tombstones.iter().any(|tombstone| match tombstone {
+                    session_store::SessionTombstone::All => true,
+                    session_store::SessionTombstone::Session(deleted) => deleted == *key,
+                    session_store::SessionTombstone::Channel(channel) => {
+                        session_store::channel_for_history_key(
+                            key,
+                            ctx.channels_by_name.keys().map(String::as_str),
+                        )
+                        .is_some_and(|name| name == *channel)
+                    }
+                })
+            })
+            .cloned()
+            .collect();
+        for key in &evicted {
+            histories.remove(key);
+        }
+        evicted
+    };
+
+    // A turn that finished after the delete may have re-saved the row.
+    for key in &evicted {
+        persist_sender_history(ctx, key);
+    }
+    if !evicted.is_empty() {
+        tracing::info!("Evicted {} deleted channel session(s)", evicted.len());
+    }
+}
+
+/// Periodically prune sessions past their retention while the daemon runs.
+/// Pruned sessions are tombstoned and evicted before the next message.
+fn spawn_session_prune_task(
+    store: Arc<session_store::SessionStore>,
+    config: crate::config::ChannelSessionsConfig,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let period = Duration::from_secs(CHANNEL_SESSION_PRUNE_INTERVAL_SECS);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let (store, config) = (Arc::clone(&store), config.clone());
+            match tokio::task::spawn_blocking(move || store.prune_expired(&config)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(pruned)) => tracing::info!("Pruned {pruned} expired channel session(s)"),
+                Ok(Err(e)) => tracing::warn!("Failed to prune expired channel sessions: {e:#}"),
+                Err(e) => tracing::warn!("Channel session pruning panicked: {e}"),
+            }
+        }
+    })
+}
+
 fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
     ctx.conversation_histories
         .lock()
         .unwrap_or_else(|e| e.into_inner())
         .remove(sender_key);
+    persist_sender_history(ctx, sender_key);
 }
 
 fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool {
+    let compacted = compact_sender_history_in_memory(ctx, sender_key);
+    persist_sender_history(ctx, sender_key);
+    compacted
+}
+
+fn compact_sender_history_in_memory(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool {
     let mut histories = ctx
         .conversation_histories
         .lock()
@@ -1537,6 +1680,8 @@ fn append_sender_turn(ctx: &ChannelRuntimeContext, sender_key: &str, turn: ChatM
     while turns.len() > MAX_CHANNEL_HISTORY {
         turns.remove(0);
     }
+    drop(histories);
+    persist_sender_history(ctx, sender_key);
 }
 
 fn rollback_orphan_user_turn(
@@ -1563,6 +1708,8 @@ fn rollback_orphan_user_turn(
     if turns.is_empty() {
         histories.remove(sender_key);
     }
+    drop(histories);
+    persist_sender_history(ctx, sender_key);
     true
 }
 
@@ -1676,7 +1823,7 @@ async fn create_resilient_provider_nonblocking(
 ) -> anyhow::Result<Box<dyn Provider>> {
     let provider_name = provider_name.to_string();
     tokio::task::spawn_blocking(move || {
-        providers::create_resilient_provider_with_options(
+        providers::create_channel_provider_with_options(
             &provider_name,
             api_key.as_deref(),
             api_url.as_deref(),
@@ -2288,22 +2435,32 @@ async fn build_memory_context(
     mem: &dyn Memory,
     user_msg: &str,
     min_relevance_score: f64,
+    ranking: &crate::config::MemoryRankingConfig,
+    recall_context: &crate::memory::ranking::RecallContext<'_>,
 ) -> String {
     let mut context = String::new();
 
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    // Entries without a score (e.g. non-vector backends) are kept.
+    if let Ok(entries) = crate::memory::ranking::recall_ranked(
+        mem,
+        user_msg,
+        5,
+        min_relevance_score,
+        ranking,
+        recall_context,
+    )
+    .await
+    {
         let mut included = 0usize;
         let mut used_chars = 0usize;
 
-        for entry in entries.iter().filter(|e| match e.score {
-            Some(score) => score >= min_relevance_score,
-            None => true, // keep entries without a score (e.g. non-vector backends)
-        }) {
+        for entry in &entries {
             if included >= MEMORY_CONTEXT_MAX_ENTRIES {
                 break;
             }
 
-            if should_skip_memory_context_entry(&entry.key, &entry.content) {
+            let key = crate::memory::namespace::MemoryNamespace::of(entry).display_key(&entry.key);
+            if should_skip_memory_context_entry(key, &entry.content) {
                 continue;
             }
 
@@ -2313,7 +2470,7 @@ async fn build_memory_context(
                 entry.content.clone()
             };
 
-            let line = format!("- {}: {}\n", entry.key, content);
+            let line = format!("- {key}: {content}\n");
             let line_chars = line.chars().count();
             if used_chars + line_chars > MEMORY_CONTEXT_MAX_CHARS {
                 break;
@@ -2669,6 +2826,61 @@ fn spawn_supervised_listener_with_health_interval(
     })
 }
 
+/// Open the durable session store, prune expired sessions and return its
+/// writer with the histories to seed the runtime cache with. Persistence problems degrade to
+/// an in-memory-only runtime instead of refusing to start channels.
+fn open_session_store(
+    workspace_dir: &Path,
+    config: &crate::config::ChannelSessionsConfig,
+) -> (
+    Option<Arc<session_store::SessionWriter>>,
+    HashMap<String, Vec<ChatMessage>>,
+) {
+    if !config.persist {
+        return (None, HashMap::new());
+    }
+
+    let store = match session_store::SessionStore::open(workspace_dir) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("Channel session persistence disabled: {e:#}");
+            return (None, HashMap::new());
+        }
+    };
+
+    match store.prune_expired(config) {
+        Ok(0) => {}
+        Ok(pruned) => tracing::info!("Pruned {pruned} expired channel session(s)"),
+        Err(e) => tracing::warn!("Failed to prune expired channel sessions: {e:#}"),
+    }
+    // Deletes made while no runtime was running are already reflected below.
+    if let Err(e) = store.take_tombstones() {
+        tracing::warn!("Failed to reset channel session tombstones: {e:#}");
+    }
+
+    let histories = store.load_histories().unwrap_or_else(|e| {
+        tracing::warn!("Failed to restore channel sessions: {e:#}");
+        HashMap::new()
+    });
+    let histories = histories
+        .into_iter()
+        .map(|(key, mut turns)| {
+            if turns.len() > MAX_CHANNEL_HISTORY {
+                turns.drain(..turns.len() - MAX_CHANNEL_HISTORY);
+            }
+            (key, turns)
+        })
+        .collect();
+
+    match session_store::SessionWriter::spawn(Arc::new(store)) {
+        Ok(writer) => (Some(Arc::new(writer)), histories),
+        Err(e) => {
+            tracing::warn!("Channel session persistence disabled: {e:#}");
+            (None, histories)
+        }
+    }
+}
+
 fn compute_max_in_flight_messages(channel_count: usize) -> usize {
     channel_count
         .saturating_mul(CHANNEL_PARALLELISM_PER_CHANNEL)
@@ -2714,6 +2926,127 @@ fn spawn_scoped_typing_task(
     handle
 }
 
+/// Drop inbound attachments the policy rejects and reference the rest in the
+/// message text, which is how the agent reads media.
+fn apply_inbound_attachment_policy(
+    policy: &attachments::AttachmentPolicy,
+    mut msg: traits::ChannelMessage,
+) -> traits::ChannelMessage {
+    if msg.attachments.is_empty() {
+        return msg;
+    }
+    let (accepted, rejected) = policy.partition(std::mem::take(&mut msg.attachments));
+    for attachment in &rejected {
+        let name = attachment
+            .file_name()
+            .unwrap_or_else(|| attachment.kind.marker_name().to_ascii_lowercase());
+        msg.content = msg
+            .content
+            .replace(&attachment.marker(), "")
+            .replace(&attachment.target(), "");
+        let _ = write!(
+            msg.content,
+            "\n[Attachment {name} was not accepted: it exceeds the size or type limits]"
+        );
+    }
+    msg.attachments = accepted;
+    msg.content = msg.content_with_markers().trim().to_string();
+    msg
+}
+
+/// Build the reply to `msg`. Media markers in `content` become typed
+/// attachments checked against the attachment policy; attachments the policy
+/// rejects, or that `channel` cannot upload, are sent as links instead.
+fn build_reply_message(
+    channel: &dyn Channel,
+    policy: &attachments::AttachmentPolicy,
+    content: &str,
+    msg: &traits::ChannelMessage,
+) -> SendMessage {
+    let reply = SendMessage::new(content, &msg.reply_target).in_thread(msg.thread_ts.clone());
+    let (text, parsed) = attachments::parse_markers(content);
+    if parsed.is_empty() {
+        return reply;
+    }
+
+    let parsed = parsed
+        .into_iter()
+        .map(attachments::Attachment::with_local_metadata)
+        .collect();
+    let (accepted, mut rejected) = policy.partition(parsed);
+    if channel.supports_attachments() {
+        SendMessage {
+            content: attachments::append_links(&text, &rejected),
+            ..reply
+        }
+        .with_attachments(accepted)
+    } else {
+        rejected.splice(0..0, accepted);
+        SendMessage {
+            content: attachments::append_links(&text, &rejected),
+            ..reply
+        }
+    }
+}
+
+/// Synthesize a voice note for the reply to `msg` when `[tts]` asks for one
+/// and `channel` can upload it. Failures leave the reply text-only.
+async fn synthesize_voice_reply(
+    tts: Option<&tts::TtsService>,
+    channel: &dyn Channel,
+    msg: &traits::ChannelMessage,
+    response: &str,
+) -> Option<attachments::Attachment> {
+    let tts = tts.filter(|tts| tts.wants_voice_reply(msg))?;
+    if !channel.supports_attachments() {
+        return None;
+    }
+    match tts.synthesize_reply(response).await {
+        Ok(voice) => voice,
+        Err(e) => {
+            tracing::warn!("Voice reply synthesis failed on {}: {e}", channel.name());
+            None
+        }
+    }
+}
+
+/// Send the final reply to `msg`, finalizing the streamed draft when there is
+/// one, with a voice note attached when `[tts]` asks for it.
+async fn deliver_reply(
+    ctx: &ChannelRuntimeContext,
+    channel: &dyn Channel,
+    msg: &traits::ChannelMessage,
+    response: &str,
+    draft_message_id: Option<&str>,
+) {
+    let voice = synthesize_voice_reply(ctx.tts.as_deref(), channel, msg, response).await;
+    let mut reply = build_reply_message(channel, &ctx.attachment_policy, response, msg);
+    if let Some(draft_id) = draft_message_id {
+        if let Err(e) = channel
+            .finalize_draft(&msg.reply_target, draft_id, response)
+            .await
+        {
+            tracing::warn!("Failed to finalize draft: {e}; sending as new message");
+            reply.attachments.extend(voice);
+            let _ = channel.send(&reply).await;
+        } else if let Some(voice) = voice {
+            // The draft already shows the text; follow it with the voice note.
+            let _ = channel
+                .send(
+                    &SendMessage::new("", &msg.reply_target)
+                        .in_thread(msg.thread_ts.clone())
+                        .with_attachments(vec![voice]),
+                )
+                .await;
+        }
+    } else {
+        reply.attachments.extend(voice);
+        if let Err(e) = channel.send(&reply).await {
+            eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+        }
+    }
+}
+
 async fn process_channel_message(
     ctx: Arc<ChannelRuntimeContext>,
     msg: traits::ChannelMessage,
@@ -2757,19 +3090,30 @@ async fn process_channel_message(
     } else {
         msg
     };
+    let msg = apply_inbound_attachment_policy(&ctx.attachment_policy, msg);
+    apply_session_tombstones(ctx.as_ref()).await;
 
     let target_channel = ctx.channels_by_name.get(&msg.channel).cloned();
     if let Err(err) = maybe_apply_runtime_config_update(ctx.as_ref()).await {
         tracing::warn!("Failed to apply runtime config update: {err}");
     }
-    if handle_runtime_command_if_needed(ctx.as_ref(), &msg, target_channel.as_ref()).await {
+    // Boxed: runtime commands may hold a whole `Config` across an await.
+    if Box::pin(handle_runtime_command_if_needed(
+        ctx.as_ref(),
+        &msg,
+        target_channel.as_ref(),
+    ))
+    .await
+    {
         return;
     }
 
     let history_key = conversation_history_key(&msg);
     // Try classification first, fall back to sender/default route
-    let route = classify_message_route(ctx.as_ref(), &msg.content)
-        .unwrap_or_else(|| get_route_selection(ctx.as_ref(), &history_key));
+    let route = match classify_message_route(ctx.as_ref(), &msg.content).await {
+        Some(route) => route,
+        None => get_route_selection(ctx.as_ref(), &history_key),
+    };
     let runtime_defaults = runtime_defaults_snapshot(ctx.as_ref());
     let active_provider = match get_or_create_provider(ctx.as_ref(), &route.provider).await {
         Ok(provider) => provider,
@@ -2790,15 +3134,27 @@ async fn process_channel_message(
             return;
         }
     };
+    let memory_namespace = crate::memory::namespace::MemoryNamespace::for_message(
+        ctx.memory_namespaces.isolation,
+        &msg.channel,
+        &msg.sender,
+    );
     if ctx.auto_save_memory && msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
         let autosave_key = conversation_memory_key(&msg);
+        let source = crate::memory::MemorySource {
+            channel: Some(msg.channel.clone()),
+            sender: Some(msg.sender.clone()),
+            tool: None,
+        };
         let _ = ctx
             .memory
-            .store(
+            .store_in_namespace(
+                &memory_namespace,
                 &autosave_key,
                 &msg.content,
                 crate::memory::MemoryCategory::Conversation,
                 None,
+                crate::memory::MemoryMetadata::default().with_source(source),
             )
             .await;
     }
@@ -2829,8 +3185,19 @@ async fn process_channel_message(
     // Only enrich with memory context when there is no prior conversation
     // history. Follow-up turns already include context from previous messages.
     if !had_prior_history {
-        let memory_context =
-            build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
+        let recall_scope = crate::memory::namespace::RecallScope::for_namespace(
+            memory_namespace.clone(),
+            &ctx.memory_namespaces,
+        );
+        let memory_context = build_memory_context(
+            ctx.memory.as_ref(),
+            &msg.content,
+            ctx.min_relevance_score,
+            &ctx.memory_ranking,
+            &crate::memory::ranking::RecallContext::for_sender(&msg.channel, &msg.sender)
+                .with_scope(&recall_scope),
+        )
+        .await;
         if let Some(last_turn) = prior_turns.last_mut() {
             if last_turn.role == "user" && !memory_context.is_empty() {
                 last_turn.content = format!("{memory_context}{}", msg.content);
@@ -2962,11 +3329,16 @@ async fn process_channel_message(
 
     let timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
+    // Memory tools called during this turn act on the sender's namespace.
+    let namespace_context = crate::memory::namespace::NamespaceContext {
+        namespace: memory_namespace,
+        policy: ctx.memory_namespaces.clone(),
+    };
     let llm_result = tokio::select! {
         () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
         result = tokio::time::timeout(
             Duration::from_secs(timeout_budget_secs),
-            run_tool_call_loop(
+            crate::memory::namespace::with_namespace(namespace_context, run_tool_call_loop(
                 active_provider.as_ref(),
                 &mut history,
                 ctx.tools_registry.as_ref(),
@@ -2983,7 +3355,7 @@ async fn process_channel_message(
                 delta_tx,
                 ctx.hooks.as_deref(),
                 &excluded_tools_snapshot,
-            ),
+            )),
         ) => LlmExecutionResult::Completed(result),
     };
 
@@ -3135,28 +3507,14 @@ async fn process_channel_message(
                 truncate_with_ellipsis(&delivered_response, 80)
             );
             if let Some(channel) = target_channel.as_ref() {
-                if let Some(ref draft_id) = draft_message_id {
-                    if let Err(e) = channel
-                        .finalize_draft(&msg.reply_target, draft_id, &delivered_response)
-                        .await
-                    {
-                        tracing::warn!("Failed to finalize draft: {e}; sending as new message");
-                        let _ = channel
-                            .send(
-                                &SendMessage::new(&delivered_response, &msg.reply_target)
-                                    .in_thread(msg.thread_ts.clone()),
-                            )
-                            .await;
-                    }
-                } else if let Err(e) = channel
-                    .send(
-                        &SendMessage::new(delivered_response, &msg.reply_target)
-                            .in_thread(msg.thread_ts.clone()),
-                    )
-                    .await
-                {
-                    eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
-                }
+                deliver_reply(
+                    ctx.as_ref(),
+                    channel.as_ref(),
+                    &msg,
+                    &delivered_response,
+                    draft_message_id.as_deref(),
+                )
+                .await;
             }
         }
         LlmExecutionResult::Completed(Ok(Err(e))) => {
@@ -4008,7 +4366,8 @@ fn collect_configured_channels(
                 .with_group_reply_policy(
                     sl.effective_group_reply_mode().requires_mention(),
                     sl.group_reply_allowed_sender_ids(),
-                ),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
             ),
         });
     }
@@ -4051,7 +4410,8 @@ fn collect_configured_channels(
                     mx.device_id.clone(),
                     config.config_path.parent().map(|path| path.to_path_buf()),
                 )
-                .with_mention_only(mx.mention_only),
+                .with_mention_only(mx.mention_only)
+                .with_workspace_dir(config.workspace_dir.clone()),
             ),
         });
     }
@@ -4067,14 +4427,17 @@ fn collect_configured_channels(
     if let Some(ref sig) = config.channels_config.signal {
         channels.push(ConfiguredChannel {
             display_name: "Signal",
-            channel: Arc::new(SignalChannel::new(
-                sig.http_url.clone(),
-                sig.account.clone(),
-                sig.group_id.clone(),
-                sig.allowed_from.clone(),
-                sig.ignore_attachments,
-                sig.ignore_stories,
-            )),
+            channel: Arc::new(
+                SignalChannel::new(
+                    sig.http_url.clone(),
+                    sig.account.clone(),
+                    sig.group_id.clone(),
+                    sig.allowed_from.clone(),
+                    sig.ignore_attachments,
+                    sig.ignore_stories,
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            ),
         });
     }
 
@@ -4091,12 +4454,15 @@ fn collect_configured_channels(
                 if wa.is_cloud_config() {
                     channels.push(ConfiguredChannel {
                         display_name: "WhatsApp",
-                        channel: Arc::new(WhatsAppChannel::new(
-                            wa.access_token.clone().unwrap_or_default(),
-                            wa.phone_number_id.clone().unwrap_or_default(),
-                            wa.verify_token.clone().unwrap_or_default(),
-                            wa.allowed_numbers.clone(),
-                        )),
+                        channel: Arc::new(
+                            WhatsAppChannel::new(
+                                wa.access_token.clone().unwrap_or_default(),
+                                wa.phone_number_id.clone().unwrap_or_default(),
+                                wa.verify_token.clone().unwrap_or_default(),
+                                wa.allowed_numbers.clone(),
+                            )
+                            .with_workspace_dir(config.workspace_dir.clone()),
+                        ),
                     });
                 } else {
                     tracing::warn!("WhatsApp Cloud API configured but missing required fields (phone_number_id, access_token, verify_token)");
@@ -4163,10 +4529,28 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(ref teams_cfg) = config.channels_config.teams {
+        channels.push(ConfiguredChannel {
+            display_name: "Teams",
+            channel: Arc::new(
+                TeamsChannel::new(
+                    teams_cfg.app_id.clone(),
+                    teams_cfg.app_password.clone(),
+                    teams_cfg.tenant_id.clone(),
+                    teams_cfg.allowed_users.clone(),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            ),
+        });
+    }
+
     if let Some(ref email_cfg) = config.channels_config.email {
         channels.push(ConfiguredChannel {
             display_name: "Email",
-            channel: Arc::new(EmailChannel::new(email_cfg.clone())),
+            channel: Arc::new(
+                EmailChannel::new(email_cfg.clone())
+                    .with_workspace_dir(config.workspace_dir.clone()),
+            ),
         });
     }
 
@@ -4188,6 +4572,61 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(ref xmpp) = config.channels_config.xmpp {
+        channels.push(ConfiguredChannel {
+            display_name: "XMPP",
+            channel: Arc::new(XmppChannel::new(xmpp::XmppChannelConfig {
+                jid: xmpp.jid.clone(),
+                password: xmpp.password.clone(),
+                server: xmpp.server.clone(),
+                port: xmpp.port,
+                tls: xmpp.tls,
+                verify_tls: xmpp.verify_tls.unwrap_or(true),
+                resource: xmpp.resource.clone(),
+                nickname: xmpp.nickname.clone(),
+                rooms: xmpp.rooms.clone(),
+                allowed_users: xmpp.allowed_users.clone(),
+                mention_only: xmpp.effective_group_reply_mode().requires_mention(),
+                group_reply_allowed_sender_ids: xmpp.group_reply_allowed_sender_ids(),
+            })),
+        });
+    }
+
+    if let Some(ref zl) = config.channels_config.zulip {
+        channels.push(ConfiguredChannel {
+            display_name: "Zulip",
+            channel: Arc::new(
+                ZulipChannel::new(
+                    zl.site_url.clone(),
+                    zl.bot_email.clone(),
+                    zl.api_key.clone(),
+                    zl.allowed_users.clone(),
+                    zl.effective_group_reply_mode().requires_mention(),
+                )
+                .with_group_reply_allowed_senders(zl.group_reply_allowed_sender_ids())
+                .with_streaming(zl.stream_mode, zl.draft_update_interval_ms),
+            ),
+        });
+    }
+
+    if let Some(ref rc) = config.channels_config.rocketchat {
+        channels.push(ConfiguredChannel {
+            display_name: "Rocket.Chat",
+            channel: Arc::new(
+                RocketChatChannel::new(
+                    rc.server_url.clone(),
+                    rc.user_id.clone(),
+                    rc.auth_token.clone(),
+                    rc.allowed_users.clone(),
+                    rc.thread_replies.unwrap_or(true),
+                    rc.effective_group_reply_mode().requires_mention(),
+                )
+                .with_group_reply_allowed_senders(rc.group_reply_allowed_sender_ids())
+                .with_streaming(rc.stream_mode, rc.draft_update_interval_ms),
+            ),
+        });
+    }
+
     #[cfg(feature = "channel-lark")]
     if let Some(ref lk) = config.channels_config.lark {
         if lk.use_feishu {
@@ -4367,6 +4806,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        model_context_window: config.model_context_window,
     };
     let provider: Arc<dyn Provider> = Arc::from(
         create_resilient_provider_nonblocking(
@@ -4654,6 +5094,29 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .as_ref()
         .is_some_and(|tg| tg.interrupt_on_new_message);
 
+    let (session_writer, restored_histories) =
+        open_session_store(&config.workspace_dir, &config.channels_config.sessions);
+    if !restored_histories.is_empty() {
+        println!(
+            "  💬 Restored {} conversation session(s)",
+            restored_histories.len()
+        );
+    }
+    let session_prune_task = session_writer.as_ref().map(|writer| {
+        spawn_session_prune_task(
+            Arc::clone(writer.store()),
+            config.channels_config.sessions.clone(),
+        )
+    });
+
+    let tts_service = match tts::TtsService::from_config(&config.tts, &config.workspace_dir) {
+        Ok(service) => service.map(Arc::new),
+        Err(e) => {
+            tracing::warn!("Voice replies disabled: {e}");
+            None
+        }
+    };
+
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
         provider: Arc::clone(&provider),
@@ -4667,7 +5130,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
         auto_save_memory: config.memory.auto_save,
         max_tool_iterations: config.agent.max_tool_iterations,
         min_relevance_score: config.memory.min_relevance_score,
-        conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+        memory_ranking: config.memory.ranking.clone(),
+        memory_namespaces: config.memory.namespaces.clone(),
+        attachment_policy: attachments::AttachmentPolicy::from_config(
+            &config.channels_config.attachments,
+        ),
+        tts: tts_service,
+        conversation_histories: Arc::new(Mutex::new(restored_histories)),
+        session_writer,
         provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
         route_overrides: Arc::new(Mutex::new(HashMap::new())),
         api_key: config.api_key.clone(),
@@ -4696,6 +5166,9 @@ pub async fn start_channels(config: Config) -> Result<()> {
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
+    if let Some(task) = session_prune_task {
+        task.abort();
+    }
 
     // Wait for all channel tasks
     for h in handles {
@@ -4989,7 +5462,12 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5043,7 +5521,12 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5075,6 +5558,101 @@ mod tests {
         assert_eq!(turns[0].content, "hello");
     }
 
+    #[tokio::test]
+    async fn sender_history_mutations_write_through_to_session_store() {
+        let workspace = make_workspace();
+        let sessions_config = crate::config::ChannelSessionsConfig::default();
+        let (writer, restored) = open_session_store(workspace.path(), &sessions_config);
+        assert!(restored.is_empty());
+
+        let sender = "telegram_u9".to_string();
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: writer.clone(),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(workspace.path().to_path_buf()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
+            query_classification: crate::config::QueryClassificationConfig::default(),
+            model_routes: Vec::new(),
+            approval_manager: Arc::new(ApprovalManager::from_config(
+                &crate::config::AutonomyConfig::default(),
+            )),
+        };
+
+        append_sender_turn(&ctx, &sender, ChatMessage::user("remember me"));
+        append_sender_turn(&ctx, &sender, ChatMessage::assistant("noted"));
+        let writer = writer.expect("persistence is enabled by default");
+        writer.flush();
+
+        // A fresh runtime (daemon restart) hydrates the same turns.
+        let (_, restored) = open_session_store(workspace.path(), &sessions_config);
+        let turns = restored.get(&sender).expect("session should be restored");
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].content, "remember me");
+        assert_eq!(turns[1].content, "noted");
+
+        clear_sender_history(&ctx, &sender);
+        writer.flush();
+        let (_, restored) = open_session_store(workspace.path(), &sessions_config);
+        assert!(!restored.contains_key(&sender));
+
+        // A delete from the CLI or gateway is applied before the next turn
+        // instead of being written back from the in-memory cache.
+        append_sender_turn(&ctx, &sender, ChatMessage::user("old context"));
+        writer.flush();
+        let external = session_store::SessionStore::open(workspace.path()).unwrap();
+        assert!(external.delete(&sender).unwrap());
+        apply_session_tombstones(&ctx).await;
+        assert!(!ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&sender));
+        append_sender_turn(&ctx, &sender, ChatMessage::user("fresh start"));
+        writer.flush();
+        let record = external.get(&sender).unwrap().expect("new session saved");
+        assert_eq!(record.turns.len(), 1);
+        assert_eq!(record.turns[0].content, "fresh start");
+    }
+
+    #[test]
+    fn open_session_store_respects_persist_toggle() {
+        let workspace = make_workspace();
+        let sessions_config = crate::config::ChannelSessionsConfig {
+            persist: false,
+            ..crate::config::ChannelSessionsConfig::default()
+        };
+        let (store, restored) = open_session_store(workspace.path(), &sessions_config);
+        assert!(store.is_none());
+        assert!(restored.is_empty());
+        assert!(!session_store::SessionStore::db_path(workspace.path()).exists());
+    }
+
     #[test]
     fn rollback_orphan_user_turn_removes_only_latest_matching_user_turn() {
         let sender = "telegram_u3".to_string();
@@ -5100,7 +5678,12 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5698,7 +6281,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5728,6 +6316,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -5775,7 +6365,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5805,6 +6400,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -5839,7 +6436,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5869,6 +6471,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -5917,7 +6521,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5947,6 +6556,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -5994,7 +6605,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6024,6 +6640,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6063,7 +6681,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6093,6 +6716,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6127,7 +6752,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6157,6 +6787,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6200,7 +6832,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6230,6 +6867,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6300,7 +6939,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6343,6 +6987,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6435,7 +7081,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6472,6 +7123,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6545,7 +7198,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6576,6 +7234,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6650,7 +7310,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6681,6 +7346,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6745,7 +7412,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6776,6 +7448,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6810,6 +7484,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6884,7 +7560,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6915,6 +7596,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -6949,6 +7632,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7029,7 +7714,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7060,6 +7750,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7144,7 +7836,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7175,6 +7872,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7239,7 +7938,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7270,6 +7974,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7299,6 +8005,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7356,7 +8064,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7384,6 +8097,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7412,6 +8127,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7471,7 +8188,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(route_overrides)),
             api_key: None,
@@ -7501,6 +8223,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7547,7 +8271,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7577,6 +8306,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7635,7 +8366,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7668,6 +8404,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 4,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7775,7 +8513,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: Some("http://127.0.0.1:11434".to_string()),
@@ -7875,7 +8618,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 12,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7905,6 +8653,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -7940,7 +8690,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 3,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7970,6 +8725,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -8066,6 +8823,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 timestamp: "2026-02-20T00:00:00Z".to_string(),
                 session_id: None,
                 score: Some(0.9),
+                metadata: crate::memory::MemoryMetadata::default(),
             }])
         }
 
@@ -8117,7 +8875,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8146,6 +8909,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         })
         .await
         .unwrap();
@@ -8157,6 +8922,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         })
         .await
         .unwrap();
@@ -8202,7 +8969,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8232,6 +9004,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             })
             .await
             .unwrap();
@@ -8244,6 +9018,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             })
             .await
             .unwrap();
@@ -8299,7 +9075,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8329,6 +9110,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             })
             .await
             .unwrap();
@@ -8341,6 +9124,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             })
             .await
             .unwrap();
@@ -8378,7 +9163,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8408,6 +9198,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -8442,7 +9234,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8472,6 +9269,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -8848,6 +9647,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         assert_eq!(conversation_memory_key(&msg), "slack_U123_msg_abc123");
@@ -8863,6 +9664,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -8872,6 +9675,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         assert_ne!(
@@ -8893,6 +9698,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -8902,6 +9709,8 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         mem.store(
@@ -8935,11 +9744,54 @@ BTC is currently around $65,000 based on latest tool output."#
             .await
             .unwrap();
 
-        let context = build_memory_context(&mem, "age", 0.0).await;
+        let context = build_memory_context(
+            &mem,
+            "age",
+            0.0,
+            &crate::config::MemoryRankingConfig::default(),
+            &crate::memory::ranking::RecallContext::default(),
+        )
+        .await;
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("Age is 45"));
     }
 
+    #[tokio::test]
+    async fn build_memory_context_hides_other_senders_namespaces() {
+        use crate::memory::namespace::{MemoryNamespace, RecallScope};
+
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let bob = MemoryNamespace::sender("telegram", "bob");
+        for (ns, content) in [(&alice, "Age is 45"), (&bob, "Age is 31")] {
+            mem.store_in_namespace(
+                ns,
+                "age_fact",
+                content,
+                MemoryCategory::Conversation,
+                None,
+                crate::memory::MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let scope =
+            RecallScope::for_namespace(alice, &crate::config::MemoryNamespaceConfig::default());
+        let context = build_memory_context(
+            &mem,
+            "age",
+            0.0,
+            &crate::config::MemoryRankingConfig::default(),
+            &crate::memory::ranking::RecallContext::for_sender("telegram", "alice")
+                .with_scope(&scope),
+        )
+        .await;
+        assert!(context.contains("- age_fact: Age is 45"));
+        assert!(!context.contains("Age is 31"));
+    }
+
     #[tokio::test]
     async fn process_channel_message_restores_per_sender_history_on_follow_ups() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -8963,7 +9815,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8993,6 +9850,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9008,6 +9867,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9053,7 +9914,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9083,6 +9949,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9143,7 +10011,12 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9173,6 +10046,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9774,7 +10649,12 @@ BTC is currently around $65,000 based on latest tool output."#;
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9805,6 +10685,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9845,7 +10727,12 @@ BTC is currently around $65,000 based on latest tool output."#;
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_ranking: crate::config::MemoryRankingConfig::default(),
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            attachment_policy: attachments::AttachmentPolicy::default(),
+            tts: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_writer: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9875,6 +10762,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -9890,6 +10779,8 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             },
             CancellationToken::new(),
         )
@@ -1,3 +1,4 @@
+use super::attachments::{self, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
@@ -144,6 +145,8 @@ fn build_channel_message(
         channel: "qq".to_string(),
         timestamp: current_unix_timestamp_secs(),
         thread_ts: (!msg_id.is_empty()).then(|| msg_id.to_string()),
+        attachments: Vec::new(),
+        transcribed_voice: false,
     }
 }
 
@@ -588,7 +591,15 @@ impl Channel for QQChannel {
             .filter(|value| !value.is_empty());
         let mut msg_seq: u64 = 1;
 
-        let (text_content, image_urls) = parse_outgoing_content(&message.content);
+        // QQ can only send remote images; anything else degrades to a link.
+        let (text, outgoing) = message.split_attachments();
+        let (images, others): (Vec<_>, Vec<_>) = outgoing.into_iter().partition(|attachment| {
+            attachment.kind == AttachmentKind::Image
+                && attachment.url().is_some_and(is_remote_media_url)
+        });
+        let (text_content, mut image_urls) =
+            parse_outgoing_content(&attachments::append_links(&text, &others));
+        image_urls.extend(images.iter().filter_map(|a| a.url().map(str::to_string)));
 
         if let Some(body) = build_text_message_body(&text_content, passive_msg_id, msg_seq) {
             self.post_json(&token, &message_url, &body, "send message")
@@ -614,6 +625,10 @@ impl Channel for QQChannel {
     }
 
     #[allow(clippy::too_many_lines)]
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         tracing::info!("QQ: authenticating...");
         let token = self.get_token().await?;
@@ -0,0 +1,773 @@
+use super::mattermost::{find_bot_mention_spans, normalize_group_reply_allowed_sender_ids};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+use tokio_tungstenite::tungstenite::Message as WsMsg;
+
+/// DDP subscription covering every room the bot belongs to, direct messages included.
+const MY_MESSAGES: &str = "__my_messages__";
+
+/// Rocket.Chat channel — receives over the realtime (DDP) websocket and
+/// replies via REST API v1.
+///
+/// Replies go to the room ID; the thread ID (`tmid`) travels in `thread_ts`.
+pub struct RocketChatChannel {
+    base_url: String, // e.g., https://chat.example.com
+    user_id: String,
+    auth_token: String,
+    allowed_users: Vec<String>,
+    /// When true (default), top-level room messages are answered in a thread.
+    thread_replies: bool,
+    /// When true, only respond to room messages that @-mention the bot.
+    mention_only: bool,
+    /// Usernames or user IDs that bypass mention gating in rooms.
+    group_reply_allowed_sender_ids: Vec<String>,
+    stream_mode: StreamMode,
+    draft_update_interval_ms: u64,
+    last_draft_edit: Mutex<HashMap<String, std::time::Instant>>,
+    /// Thread of each open draft, for the fallback send in `finalize_draft`.
+    draft_threads: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl RocketChatChannel {
+    pub fn new(
+        base_url: String,
+        user_id: String,
+        auth_token: String,
+        allowed_users: Vec<String>,
+        thread_replies: bool,
+        mention_only: bool,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            base_url,
+            user_id,
+            auth_token,
+            allowed_users,
+            thread_replies,
+            mention_only,
+            group_reply_allowed_sender_ids: Vec::new(),
+            stream_mode: StreamMode::Off,
+            draft_update_interval_ms: 1000,
+            last_draft_edit: Mutex::new(HashMap::new()),
+            draft_threads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Configure sender IDs that bypass mention gating in rooms.
+    pub fn with_group_reply_allowed_senders(mut self, sender_ids: Vec<String>) -> Self {
+        self.group_reply_allowed_sender_ids = normalize_group_reply_allowed_sender_ids(sender_ids);
+        self
+    }
+
+    /// Configure progressive replies through message edits.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_update_interval_ms = draft_update_interval_ms;
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.rocketchat")
+    }
+
+    fn api_url(&self, method: &str) -> String {
+        format!("{}/api/v1/{method}", self.base_url)
+    }
+
+    /// Realtime endpoint derived from the server URL.
+    fn websocket_url(&self) -> String {
+        let base = if let Some(rest) = self.base_url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = self.base_url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            self.base_url.clone()
+        };
+        format!("{base}/websocket")
+    }
+
+    /// Check a sender against the allowlist by username (case-insensitive) or user ID.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, username: &str, user_id: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(username) || u == user_id)
+    }
+
+    fn is_group_sender_trigger_enabled(&self, username: &str, user_id: &str) -> bool {
+        self.group_reply_allowed_sender_ids
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(username) || entry == user_id)
+    }
+
+    /// POST a REST method and return the JSON body, failing on API errors.
+    async fn call(&self, method: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+        let resp = self
+            .http_client()
+            .post(self.api_url(method))
+            .header("X-User-Id", &self.user_id)
+            .header("X-Auth-Token", &self.auth_token)
+            .json(body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            bail!("Rocket.Chat {method} failed ({status}): {sanitized}");
+        }
+        Ok(resp.json().await.unwrap_or_default())
+    }
+
+    /// Post a message and return its ID.
+    async fn post_message(
+        &self,
+        room_id: &str,
+        tmid: Option<&str>,
+        text: &str,
+    ) -> Result<Option<String>> {
+        let mut body = json!({ "roomId": room_id, "text": text });
+        if let Some(tmid) = tmid.filter(|t| !t.is_empty()) {
+            body["tmid"] = json!(tmid);
+        }
+        let data = self.call("chat.postMessage", &body).await?;
+        Ok(data
+            .get("message")
+            .and_then(|m| m.get("_id"))
+            .and_then(|id| id.as_str())
+            .map(str::to_string))
+    }
+
+    /// The bot's own user ID and username, used to skip its own messages
+    /// and detect @-mentions.
+    async fn get_bot_identity(&self) -> Result<(String, String)> {
+        let resp: serde_json::Value = self
+            .http_client()
+            .get(self.api_url("me"))
+            .header("X-User-Id", &self.user_id)
+            .header("X-Auth-Token", &self.auth_token)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let Some(id) = resp.get("_id").and_then(|i| i.as_str()) else {
+            bail!("Rocket.Chat /api/v1/me failed; check user_id and auth_token");
+        };
+        let username = resp
+            .get("username")
+            .and_then(|u| u.as_str())
+            .unwrap_or("")
+            .to_string();
+        Ok((id.to_string(), username))
+    }
+
+    async fn react(&self, message_id: &str, emoji: &str, should_react: bool) -> Result<()> {
+        let Some(shortcode) = rocketchat_emoji_shortcode(emoji) else {
+            tracing::debug!("Rocket.Chat: no shortcode for reaction {emoji:?}; skipping");
+            return Ok(());
+        };
+        let raw_id = message_id.strip_prefix("rocketchat_").unwrap_or(message_id);
+        self.call(
+            "chat.react",
+            &json!({ "messageId": raw_id, "emoji": shortcode, "shouldReact": should_react }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for RocketChatChannel {
+    fn name(&self) -> &str {
+        "rocketchat"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        self.post_message(
+            &message.recipient,
+            message.thread_ts.as_deref(),
+            &message.content,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let (bot_user_id, bot_username) = self.get_bot_identity().await?;
+
+        let (ws_stream, _) = tokio_tungstenite::connect_async(&self.websocket_url()).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        for frame in [
+            json!({ "msg": "connect", "version": "1", "support": ["1"] }),
+            json!({
+                "msg": "method",
+                "method": "login",
+                "id": "login",
+                "params": [{ "resume": self.auth_token }]
+            }),
+            json!({
+                "msg": "sub",
+                "id": "messages",
+                "name": "stream-room-messages",
+                "params": [MY_MESSAGES, false]
+            }),
+        ] {
+            write.send(WsMsg::Text(frame.to_string().into())).await?;
+        }
+
+        tracing::info!("Rocket.Chat channel listening as {bot_username}...");
+
+        while let Some(frame) = read.next().await {
+            let text = match frame? {
+                WsMsg::Text(t) => t,
+                WsMsg::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(text.as_ref()) else {
+                continue;
+            };
+
+            match event.get("msg").and_then(|m| m.as_str()) {
+                Some("ping") => {
+                    write
+                        .send(WsMsg::Text(json!({ "msg": "pong" }).to_string().into()))
+                        .await?;
+                }
+                Some("result") if event.get("id").and_then(|i| i.as_str()) == Some("login") => {
+                    if let Some(error) = event.get("error") {
+                        let reason = error
+                            .get("reason")
+                            .and_then(|r| r.as_str())
+                            .unwrap_or("unknown error");
+                        bail!("Rocket.Chat realtime login failed: {reason}");
+                    }
+                }
+                Some("nosub") => {
+                    bail!("Rocket.Chat rejected the message subscription");
+                }
+                Some("changed")
+                    if event.get("collection").and_then(|c| c.as_str())
+                        == Some("stream-room-messages") =>
+                {
+                    let args = event
+                        .get("fields")
+                        .and_then(|f| f.get("args"))
+                        .and_then(|a| a.as_array());
+                    let Some(message) = args.and_then(|a| a.first()) else {
+                        continue;
+                    };
+                    let room = args.and_then(|a| a.get(1));
+                    if let Some(msg) =
+                        self.parse_rocketchat_message(message, room, &bot_user_id, &bot_username)
+                    {
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        bail!("Rocket.Chat websocket closed")
+    }
+
+    async fn health_check(&self) -> bool {
+        self.http_client()
+            .get(self.api_url("me"))
+            .header("X-User-Id", &self.user_id)
+            .header("X-Auth-Token", &self.auth_token)
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let initial_text = if message.content.is_empty() {
+            "..."
+        } else {
+            message.content.as_str()
+        };
+        let id = self
+            .post_message(
+                &message.recipient,
+                message.thread_ts.as_deref(),
+                initial_text,
+            )
+            .await?;
+
+        if let Some(ref id) = id {
+            self.draft_threads
+                .lock()
+                .insert(id.clone(), message.thread_ts.clone());
+        }
+        self.last_draft_edit
+            .lock()
+            .insert(message.recipient.clone(), std::time::Instant::now());
+        Ok(id)
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<Option<String>> {
+        {
+            let last_edits = self.last_draft_edit.lock();
+            if let Some(last_time) = last_edits.get(recipient) {
+                let elapsed = u64::try_from(last_time.elapsed().as_millis()).unwrap_or(u64::MAX);
+                if elapsed < self.draft_update_interval_ms {
+                    return Ok(None);
+                }
+            }
+        }
+
+        let body = json!({ "roomId": recipient, "msgId": message_id, "text": text });
+        match self.call("chat.update", &body).await {
+            Ok(_) => {
+                self.last_draft_edit
+                    .lock()
+                    .insert(recipient.to_string(), std::time::Instant::now());
+            }
+            Err(e) => tracing::debug!("{e}"),
+        }
+        Ok(None)
+    }
+
+    async fn finalize_draft(&self, recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        let tmid = self.draft_threads.lock().remove(message_id).flatten();
+
+        let body = json!({ "roomId": recipient, "msgId": message_id, "text": text });
+        if let Err(e) = self.call("chat.update", &body).await {
+            tracing::warn!("Rocket.Chat finalize_draft edit failed ({e}); sending a new message");
+            self.post_message(recipient, tmid.as_deref(), text).await?;
+        }
+        Ok(())
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        self.draft_threads.lock().remove(message_id);
+
+        let body = json!({ "roomId": recipient, "msgId": message_id });
+        if let Err(e) = self.call("chat.delete", &body).await {
+            tracing::debug!("{e}");
+        }
+        Ok(())
+    }
+
+    async fn add_reaction(&self, _channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.react(message_id, emoji, true).await
+    }
+
+    async fn remove_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<()> {
+        self.react(message_id, emoji, false).await
+    }
+}
+
+impl RocketChatChannel {
+    /// Parse a `stream-room-messages` payload. `room` is the second event
+    /// argument, which carries the room type (`d` for direct messages).
+    fn parse_rocketchat_message(
+        &self,
+        message: &serde_json::Value,
+        room: Option<&serde_json::Value>,
+        bot_user_id: &str,
+        bot_username: &str,
+    ) -> Option<ChannelMessage> {
+        let id = message.get("_id").and_then(|i| i.as_str())?;
+        let room_id = message.get("rid").and_then(|r| r.as_str())?;
+        let text = message.get("msg").and_then(|m| m.as_str()).unwrap_or("");
+        let user = message.get("u");
+        let user_id = user
+            .and_then(|u| u.get("_id"))
+            .and_then(|i| i.as_str())
+            .unwrap_or("");
+        let username = user
+            .and_then(|u| u.get("username"))
+            .and_then(|n| n.as_str())
+            .unwrap_or("");
+
+        // System messages (`t`) and edits (`editedAt`) are not new input.
+        if user_id == bot_user_id
+            || text.trim().is_empty()
+            || message.get("t").is_some()
+            || message.get("editedAt").is_some()
+        {
+            return None;
+        }
+
+        if !self.is_user_allowed(username, user_id) {
+            tracing::warn!("Rocket.Chat: ignoring message from unauthorized user: {username}");
+            return None;
+        }
+
+        let is_direct = room
+            .and_then(|r| r.get("roomType"))
+            .and_then(|t| t.as_str())
+            == Some("d");
+        let require_mention = !is_direct
+            && self.mention_only
+            && !self.is_group_sender_trigger_enabled(username, user_id);
+        let content = if require_mention {
+            normalize_rocketchat_content(text, bot_user_id, bot_username, message)?
+        } else {
+            text.to_string()
+        };
+
+        // Replies stay in an existing thread; top-level room messages start
+        // one when thread_replies is enabled. Direct messages are never threaded.
+        let thread_ts = message
+            .get("tmid")
+            .and_then(|t| t.as_str())
+            .map(str::to_string)
+            .or_else(|| (!is_direct && self.thread_replies).then(|| id.to_string()));
+
+        Some(ChannelMessage {
+            id: format!("rocketchat_{id}"),
+            sender: username.to_string(),
+            reply_target: room_id.to_string(),
+            content,
+            channel: "rocketchat".to_string(),
+            timestamp: message
+                .get("ts")
+                .and_then(|ts| ts.get("$date"))
+                .and_then(serde_json::Value::as_u64)
+                .map_or(0, |ms| ms / 1000),
+            thread_ts,
+            attachments: Vec::new(),
+            transcribed_voice: false,
+        })
+    }
+}
+
+/// Normalize a room message when `mention_only` is enabled.
+///
+/// Returns `None` unless the message mentions the bot, either as `@username`
+/// in the text or through the message's `mentions` list.
+/// Returns `Some(cleaned)` with the @-mention stripped and text trimmed.
+fn normalize_rocketchat_content(
+    text: &str,
+    bot_user_id: &str,
+    bot_username: &str,
+    message: &serde_json::Value,
+) -> Option<String> {
+    let mention_spans = find_bot_mention_spans(text, bot_username);
+    let listed = !bot_user_id.is_empty()
+        && message
+            .get("mentions")
+            .and_then(|m| m.as_array())
+            .is_some_and(|mentions| {
+                mentions
+                    .iter()
+                    .any(|m| m.get("_id").and_then(|i| i.as_str()) == Some(bot_user_id))
+            });
+
+    if mention_spans.is_empty() && !listed {
+        return None;
+    }
+
+    let mut cleaned = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end) in mention_spans {
+        cleaned.push_str(&text[cursor..start]);
+        cleaned.push(' ');
+        cursor = end;
+    }
+    cleaned.push_str(&text[cursor..]);
+
+    let cleaned = cleaned.trim().to_string();
+    if cleaned.is_empty() {
+        return None;
+    }
+    Some(cleaned)
+}
+
+/// Rocket.Chat shortcode for the reactions the runtime uses. Plain names
+/// such as `thumbsup` are wrapped in colons.
+fn rocketchat_emoji_shortcode(emoji: &str) -> Option<String> {
+    let emoji = emoji.trim();
+    let name = match emoji {
+        "\u{1F440}" => "eyes",
+        "\u{2705}" => "white_check_mark",
+        "\u{26A0}\u{FE0F}" | "\u{26A0}" => "warning",
+        "\u{274C}" => "x",
+        "\u{1F44D}" => "thumbsup",
+        _ => {
+            let name = emoji.trim_matches(':');
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+            {
+                return None;
+            }
+            name
+        }
+    };
+    Some(format!(":{name}:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_channel(
+        allowed: Vec<String>,
+        thread_replies: bool,
+        mention_only: bool,
+    ) -> RocketChatChannel {
+        RocketChatChannel::new(
+            "https://chat.example.com/".into(),
+            "bot-id".into(),
+            "token".into(),
+            allowed,
+            thread_replies,
+            mention_only,
+        )
+    }
+
+    fn room_message(user_id: &str, username: &str, text: &str) -> serde_json::Value {
+        json!({
+            "_id": "m1",
+            "rid": "GENERAL",
+            "msg": text,
+            "ts": { "$date": 1_700_000_000_123_u64 },
+            "u": { "_id": user_id, "username": username }
+        })
+    }
+
+    fn channel_room() -> serde_json::Value {
+        json!({ "roomParticipant": true, "roomType": "c" })
+    }
+
+    #[test]
+    fn rocketchat_urls_derive_from_base_url() {
+        let ch = make_channel(vec![], true, false);
+        assert_eq!(ch.api_url("me"), "https://chat.example.com/api/v1/me");
+        assert_eq!(ch.websocket_url(), "wss://chat.example.com/websocket");
+
+        let local = RocketChatChannel::new(
+            "http://localhost:3000".into(),
+            "id".into(),
+            "token".into(),
+            vec![],
+            true,
+            false,
+        );
+        assert_eq!(local.websocket_url(), "ws://localhost:3000/websocket");
+    }
+
+    #[test]
+    fn rocketchat_allowlist_matches_username_or_id() {
+        let ch = make_channel(vec!["Alice".into(), "u-bob".into()], true, false);
+        assert!(ch.is_user_allowed("alice", "u-alice"));
+        assert!(ch.is_user_allowed("bob", "u-bob"));
+        assert!(!ch.is_user_allowed("mallory", "u-mallory"));
+        assert!(!make_channel(vec![], true, false).is_user_allowed("alice", "u-alice"));
+    }
+
+    #[test]
+    fn rocketchat_room_message_starts_thread_when_enabled() {
+        let ch = make_channel(vec!["*".into()], true, false);
+        let room = channel_room();
+        let msg = ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "alice", "hello"),
+                Some(&room),
+                "bot-id",
+                "zeroclaw",
+            )
+            .unwrap();
+        assert_eq!(msg.id, "rocketchat_m1");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "GENERAL");
+        assert_eq!(msg.thread_ts.as_deref(), Some("m1"));
+        assert_eq!(msg.timestamp, 1_700_000_000);
+
+        let ch = make_channel(vec!["*".into()], false, false);
+        let msg = ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "alice", "hello"),
+                Some(&room),
+                "bot-id",
+                "zeroclaw",
+            )
+            .unwrap();
+        assert!(msg.thread_ts.is_none());
+    }
+
+    #[test]
+    fn rocketchat_thread_reply_keeps_existing_thread() {
+        let ch = make_channel(vec!["*".into()], false, false);
+        let mut message = room_message("u-alice", "alice", "follow-up");
+        message["tmid"] = json!("root-1");
+        let msg = ch
+            .parse_rocketchat_message(&message, Some(&channel_room()), "bot-id", "zeroclaw")
+            .unwrap();
+        assert_eq!(msg.thread_ts.as_deref(), Some("root-1"));
+    }
+
+    #[test]
+    fn rocketchat_skips_own_system_and_edited_messages() {
+        let ch = make_channel(vec!["*".into()], true, false);
+        let room = channel_room();
+        assert!(ch
+            .parse_rocketchat_message(
+                &room_message("bot-id", "zeroclaw", "echo"),
+                Some(&room),
+                "bot-id",
+                "zeroclaw"
+            )
+            .is_none());
+
+        let mut joined = room_message("u-alice", "alice", "alice");
+        joined["t"] = json!("uj");
+        assert!(ch
+            .parse_rocketchat_message(&joined, Some(&room), "bot-id", "zeroclaw")
+            .is_none());
+
+        let mut edited = room_message("u-alice", "alice", "fixed typo");
+        edited["editedAt"] = json!({ "$date": 1 });
+        assert!(ch
+            .parse_rocketchat_message(&edited, Some(&room), "bot-id", "zeroclaw")
+            .is_none());
+    }
+
+    #[test]
+    fn rocketchat_mention_only_gates_rooms_but_not_direct_messages() {
+        let ch = make_channel(vec!["*".into()], true, true);
+        let room = channel_room();
+        assert!(ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "alice", "no mention"),
+                Some(&room),
+                "bot-id",
+                "zeroclaw"
+            )
+            .is_none());
+
+        let msg = ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "alice", "@ZeroClaw status?"),
+                Some(&room),
+                "bot-id",
+                "zeroclaw",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "status?");
+
+        let direct = json!({ "roomParticipant": true, "roomType": "d" });
+        let msg = ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "alice", "no mention"),
+                Some(&direct),
+                "bot-id",
+                "zeroclaw",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "no mention");
+        assert!(msg.thread_ts.is_none());
+    }
+
+    #[test]
+    fn rocketchat_group_sender_override_bypasses_mention() {
+        let ch = make_channel(vec!["*".into()], true, true)
+            .with_group_reply_allowed_senders(vec!["alice".into()]);
+        let msg = ch
+            .parse_rocketchat_message(
+                &room_message("u-alice", "Alice", "no mention"),
+                Some(&channel_room()),
+                "bot-id",
+                "zeroclaw",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "no mention");
+    }
+
+    #[test]
+    fn rocketchat_mentions_list_counts_as_mention() {
+        let mut message = room_message("u-alice", "alice", "@here deploy?");
+        message["mentions"] = json!([{ "_id": "bot-id", "username": "zeroclaw" }]);
+        assert_eq!(
+            normalize_rocketchat_content("@here deploy?", "bot-id", "zeroclaw", &message),
+            Some("@here deploy?".to_string())
+        );
+        assert_eq!(
+            normalize_rocketchat_content("@zeroclaw", "bot-id", "zeroclaw", &message),
+            None
+        );
+    }
+
+    #[test]
+    fn rocketchat_shortcodes_cover_runtime_reactions() {
+        assert_eq!(
+            rocketchat_emoji_shortcode("\u{1F440}").as_deref(),
+            Some(":eyes:")
+        );
+        assert_eq!(
+            rocketchat_emoji_shortcode("\u{2705}").as_deref(),
+            Some(":white_check_mark:")
+        );
+        assert_eq!(
+            rocketchat_emoji_shortcode("\u{26A0}\u{FE0F}").as_deref(),
+            Some(":warning:")
+        );
+        assert_eq!(
+            rocketchat_emoji_shortcode("tada").as_deref(),
+            Some(":tada:")
+        );
+        assert_eq!(rocketchat_emoji_shortcode("\u{1F9EA}"), None);
+    }
+
+    #[test]
+    fn rocketchat_supports_draft_updates_respects_stream_mode() {
+        assert!(!make_channel(vec![], true, false).supports_draft_updates());
+        let partial = make_channel(vec![], true, false).with_streaming(StreamMode::Partial, 500);
+        assert!(partial.supports_draft_updates());
+        assert_eq!(partial.draft_update_interval_ms, 500);
+    }
+
+    #[tokio::test]
+    async fn rocketchat_update_draft_rate_limit_short_circuits_network() {
+        let ch = make_channel(vec![], true, false).with_streaming(StreamMode::Partial, 60_000);
+        ch.last_draft_edit
+            .lock()
+            .insert("GENERAL".to_string(), std::time::Instant::now());
+
+        let result = ch.update_draft("GENERAL", "m1", "delta text").await;
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -0,0 +1,744 @@
+//! Durable per-sender conversation history for channel sessions.
+//!
+//! The channel runtime keeps an in-memory map of recent turns per sender so
+//! follow-up messages carry context. This store mirrors that map into SQLite
+//! (`<workspace>/sessions/sessions.db`) so a daemon restart or crash does not
+//! wipe every Telegram/Slack/Discord conversation. The runtime hydrates its
+//! cache from here on startup and writes through on every history mutation,
+//! via a [`SessionWriter`] thread so message handling never waits on SQLite.
+//!
+//! Deletes from outside the runtime (`zeroclaw sessions`, the gateway API,
+//! retention pruning) also leave a tombstone. A running daemon consumes the
+//! tombstones before handling its next message and evicts the matching
+//! in-memory histories, so deleted sessions are not written back.
+
+use crate::config::{ChannelSessionsConfig, Config};
+use crate::providers::ChatMessage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+
+/// Database file name inside `<workspace>/sessions/`.
+const DB_FILE_NAME: &str = "sessions.db";
+
+/// Summary row for a persisted channel session (no turn contents).
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub key: String,
+    pub channel: String,
+    pub message_count: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A pending delete the channel runtime still has to apply to its cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionTombstone {
+    /// One session, by history key.
+    Session(String),
+    /// Every session of one channel.
+    Channel(String),
+    /// Every session.
+    All,
+}
+
+impl SessionTombstone {
+    fn scope(&self) -> String {
+        match self {
+            Self::Session(key) => format!("session:{key}"),
+            Self::Channel(channel) => format!("channel:{channel}"),
+            Self::All => "all".to_string(),
+        }
+    }
+
+    fn from_scope(scope: &str) -> Option<Self> {
+        if scope == "all" {
+            return Some(Self::All);
+        }
+        if let Some(key) = scope.strip_prefix("session:") {
+            return Some(Self::Session(key.to_string()));
+        }
+        scope
+            .strip_prefix("channel:")
+            .map(|channel| Self::Channel(channel.to_string()))
+    }
+}
+
+/// A persisted channel session including its conversation turns.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRecord {
+    #[serde(flatten)]
+    pub summary: SessionSummary,
+    pub turns: Vec<ChatMessage>,
+}
+
+/// SQLite-backed store for channel conversation histories.
+pub struct SessionStore {
+    conn: Mutex<Connection>,
+    db_path: PathBuf,
+}
+
+impl SessionStore {
+    /// Open (or create) the session database under `workspace_dir/sessions/`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = Self::db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create sessions directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open sessions DB: {}", db_path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             CREATE TABLE IF NOT EXISTS channel_sessions (
+                session_key TEXT PRIMARY KEY,
+                channel     TEXT NOT NULL,
+                turns       TEXT NOT NULL,
+                created_at  TEXT NOT NULL,
+                updated_at  TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_channel_sessions_channel ON channel_sessions(channel);
+             CREATE INDEX IF NOT EXISTS idx_channel_sessions_updated ON channel_sessions(updated_at);
+             CREATE TABLE IF NOT EXISTS channel_session_tombstones (
+                scope      TEXT PRIMARY KEY,
+                deleted_at TEXT NOT NULL
+             );",
+        )
+        .context("Failed to initialize sessions schema")?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+        })
+    }
+
+    /// Location of the session database for a workspace.
+    pub fn db_path(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join("sessions").join(DB_FILE_NAME)
+    }
+
+    /// Whether `filename` in the sessions directory belongs to the store
+    /// (the database itself or its `-wal` / `-shm` / `-journal` companions).
+    pub(crate) fn is_store_file(filename: &str) -> bool {
+        filename.starts_with(DB_FILE_NAME)
+    }
+
+    /// Path of the opened database file.
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Replace the stored turns for `key`. An empty slice deletes the session,
+    /// mirroring how the in-memory cache drops senders with no turns.
+    pub fn save(&self, key: &str, channel: &str, turns: &[ChatMessage]) -> Result<()> {
+        if turns.is_empty() {
+            self.remove_row(key)?;
+            return Ok(());
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let turns_json = serde_json::to_string(turns)?;
+        self.conn
+            .lock()
+            .execute(
+                "INSERT INTO channel_sessions (session_key, channel, turns, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT(session_key) DO UPDATE SET
+                    channel = excluded.channel,
+                    turns = excluded.turns,
+                    updated_at = excluded.updated_at",
+                params![key, channel, turns_json, now],
+            )
+            .with_context(|| format!("Failed to persist channel session '{key}'"))?;
+        Ok(())
+    }
+
+    /// Delete a single session and tombstone it for a running daemon.
+    /// Returns whether a row was removed.
+    pub fn delete(&self, key: &str) -> Result<bool> {
+        let removed = self.remove_row(key)?;
+        self.add_tombstone(&SessionTombstone::Session(key.to_string()))?;
+        Ok(removed)
+    }
+
+    /// Delete every session, optionally limited to one channel, and
+    /// tombstone the scope for a running daemon.
+    pub fn clear(&self, channel: Option<&str>) -> Result<usize> {
+        let removed = {
+            let conn = self.conn.lock();
+            match channel {
+                Some(channel) => conn.execute(
+                    "DELETE FROM channel_sessions WHERE channel = ?1",
+                    params![channel],
+                )?,
+                None => conn.execute("DELETE FROM channel_sessions", [])?,
+            }
+        };
+        let tombstone = channel.map_or(SessionTombstone::All, |channel| {
+            SessionTombstone::Channel(channel.to_string())
+        });
+        self.add_tombstone(&tombstone)?;
+        Ok(removed)
+    }
+
+    /// Return and forget every pending tombstone.
+    pub fn take_tombstones(&self) -> Result<Vec<SessionTombstone>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let scopes = {
+            let mut stmt = tx.prepare("SELECT scope FROM channel_session_tombstones")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        if !scopes.is_empty() {
+            tx.execute("DELETE FROM channel_session_tombstones", [])?;
+        }
+        tx.commit()?;
+        Ok(scopes
+            .iter()
+            .filter_map(|scope| SessionTombstone::from_scope(scope))
+            .collect())
+    }
+
+    fn remove_row(&self, key: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .lock()
+            .execute(
+                "DELETE FROM channel_sessions WHERE session_key = ?1",
+                params![key],
+            )
+            .with_context(|| format!("Failed to delete channel session '{key}'"))?;
+        Ok(removed > 0)
+    }
+
+    fn add_tombstone(&self, tombstone: &SessionTombstone) -> Result<()> {
+        self.conn
+            .lock()
+            .execute(
+                "INSERT INTO channel_session_tombstones (scope, deleted_at) VALUES (?1, ?2)
+                 ON CONFLICT(scope) DO UPDATE SET deleted_at = excluded.deleted_at",
+                params![tombstone.scope(), Utc::now().to_rfc3339()],
+            )
+            .context("Failed to record channel session tombstone")?;
+        Ok(())
+    }
+
+    /// Fetch a session with its turns.
+    pub fn get(&self, key: &str) -> Result<Option<SessionRecord>> {
+        let conn = self.conn.lock();
+        let row = conn
+            .query_row(
+                "SELECT session_key, channel, turns, created_at, updated_at
+                 FROM channel_sessions WHERE session_key = ?1",
+                params![key],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        row.map(|(key, channel, turns, created_at, updated_at)| {
+            let turns: Vec<ChatMessage> = serde_json::from_str(&turns)
+                .with_context(|| format!("Corrupt turns payload for session '{key}'"))?;
+            Ok(SessionRecord {
+                summary: SessionSummary {
+                    key,
+                    channel,
+                    message_count: turns.len(),
+                    created_at: parse_timestamp(&created_at),
+                    updated_at: parse_timestamp(&updated_at),
+                },
+                turns,
+            })
+        })
+        .transpose()
+    }
+
+    /// List sessions, most recently updated first. Turns are counted by
+    /// SQLite, so listing does not decode them.
+    pub fn list(&self, channel: Option<&str>) -> Result<Vec<SessionSummary>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT session_key, channel,
+                    CASE WHEN json_valid(turns) THEN json_array_length(turns) END,
+                    created_at, updated_at
+             FROM channel_sessions
+             WHERE ?1 IS NULL OR channel = ?1
+             ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map(params![channel], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            let (key, channel, message_count, created_at, updated_at) = row?;
+            let Some(message_count) = message_count else {
+                tracing::warn!("Skipping corrupt channel session '{key}'");
+                continue;
+            };
+            summaries.push(SessionSummary {
+                key,
+                channel,
+                message_count: usize::try_from(message_count).unwrap_or_default(),
+                created_at: parse_timestamp(&created_at),
+                updated_at: parse_timestamp(&updated_at),
+            });
+        }
+        Ok(summaries)
+    }
+
+    /// Load every session as a history map keyed by session key.
+    ///
+    /// Sessions whose payload cannot be decoded are skipped with a warning so
+    /// one corrupt row does not prevent the runtime from starting.
+    pub fn load_histories(&self) -> Result<HashMap<String, Vec<ChatMessage>>> {
+        Ok(self
+            .load_records()?
+            .into_iter()
+            .map(|record| (record.summary.key, record.turns))
+            .collect())
+    }
+
+    /// Remove sessions idle for longer than the configured retention of their
+    /// channel. Returns the number of sessions removed.
+    pub fn prune_expired(&self, config: &ChannelSessionsConfig) -> Result<usize> {
+        let now = Utc::now();
+        let expired: Vec<String> = self
+            .list(None)?
+            .into_iter()
+            .filter(|summary| {
+                // Retentions too long to represent never expire.
+                let retention = match config.retention_hours_for(&summary.channel) {
+                    0 => None,
+                    hours => i64::try_from(hours).ok().and_then(Duration::try_hours),
+                };
+                retention.is_some_and(|retention| {
+                    now.signed_duration_since(summary.updated_at) > retention
+                })
+            })
+            .map(|summary| summary.key)
+            .collect();
+
+        let mut removed = 0;
+        for key in &expired {
+            if self.delete(key)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn load_records(&self) -> Result<Vec<SessionRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT session_key, channel, turns, created_at, updated_at
+             FROM channel_sessions
+             ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (key, channel, turns, created_at, updated_at) = row?;
+            let turns: Vec<ChatMessage> = match serde_json::from_str(&turns) {
+                Ok(turns) => turns,
+                Err(e) => {
+                    tracing::warn!("Skipping corrupt channel session '{key}': {e}");
+                    continue;
+                }
+            };
+            records.push(SessionRecord {
+                summary: SessionSummary {
+                    key,
+                    channel,
+                    message_count: turns.len(),
+                    created_at: parse_timestamp(&created_at),
+                    updated_at: parse_timestamp(&updated_at),
+                },
+                turns,
+            });
+        }
+        Ok(records)
+    }
+}
+
+enum WriterMessage {
+    Save {
+        key: String,
+        channel: String,
+        turns: Vec<ChatMessage>,
+    },
+    Flush(mpsc::Sender<()>),
+}
+
+/// Writes history snapshots to a [`SessionStore`] from a dedicated thread.
+///
+/// Async tasks only queue a snapshot, so they never wait on SQLite. The single
+/// writer applies snapshots in the order they were queued; callers queue
+/// while still holding the history lock, so a session's saves land in the
+/// order its history changed.
+pub struct SessionWriter {
+    store: Arc<SessionStore>,
+    queue: mpsc::Sender<WriterMessage>,
+}
+
+impl SessionWriter {
+    /// Start the writer thread for `store`. It exits once the writer is
+    /// dropped and the queued snapshots are written.
+    pub fn spawn(store: Arc<SessionStore>) -> Result<Self> {
+        let (queue, pending) = mpsc::channel();
+        let thread_store = Arc::clone(&store);
+        std::thread::Builder::new()
+            .name("channel-session-writer".into())
+            .spawn(move || {
+                for message in pending {
+                    match message {
+                        WriterMessage::Save {
+                            key,
+                            channel,
+                            turns,
+                        } => {
+                            if let Err(e) = thread_store.save(&key, &channel, &turns) {
+                                tracing::warn!("Failed to persist channel session {key}: {e:#}");
+                            }
+                        }
+                        WriterMessage::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .context("Failed to start channel session writer")?;
+        Ok(Self { store, queue })
+    }
+
+    /// The underlying store, for reads and deletes.
+    pub fn store(&self) -> &Arc<SessionStore> {
+        &self.store
+    }
+
+    /// Queue `turns` as the new history of `key` (see [`SessionStore::save`]).
+    pub fn save(&self, key: &str, channel: &str, turns: Vec<ChatMessage>) {
+        let message = WriterMessage::Save {
+            key: key.to_string(),
+            channel: channel.to_string(),
+            turns,
+        };
+        if self.queue.send(message).is_err() {
+            tracing::warn!("Channel session writer stopped; not persisting {key}");
+        }
+    }
+
+    /// Block until every snapshot queued so far has been written.
+    pub fn flush(&self) {
+        let (done, written) = mpsc::channel();
+        if self.queue.send(WriterMessage::Flush(done)).is_ok() {
+            let _ = written.recv();
+        }
+    }
+}
+
+fn parse_timestamp(raw: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|ts| ts.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// Resolve the channel name encoded at the start of a history key
+/// (`<channel>_<sender>` or `<channel>_<thread>_<sender>`). Channel names may
+/// themselves contain underscores, so the longest known name wins.
+pub(crate) fn channel_for_history_key<'a>(
+    key: &str,
+    channel_names: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    channel_names
+        .into_iter()
+        .filter(|name| {
+            key.strip_prefix(name)
+                .is_some_and(|rest| rest.starts_with('_'))
+        })
+        .max_by_key(|name| name.len())
+        .map(str::to_string)
+}
+
+pub fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    let store = SessionStore::open(&config.workspace_dir)?;
+    match command {
+        crate::SessionCommands::List { channel } => {
+            let sessions = store.list(channel.as_deref())?;
+            if sessions.is_empty() {
+                println!("No persisted channel sessions.");
+                return Ok(());
+            }
+
+            println!("💬 Channel sessions ({}):", sessions.len());
+            for session in sessions {
+                println!(
+                    "- {} | {} | {} message(s) | updated {}",
+                    session.key,
+                    session.channel,
+                    session.message_count,
+                    session.updated_at.to_rfc3339(),
+                );
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Show { key } => {
+            let Some(record) = store.get(&key)? else {
+                anyhow::bail!("No channel session found for '{key}'");
+            };
+            println!("💬 Session {}", record.summary.key);
+            println!("  Channel: {}", record.summary.channel);
+            println!("  Created: {}", record.summary.created_at.to_rfc3339());
+            println!("  Updated: {}", record.summary.updated_at.to_rfc3339());
+            println!();
+            for turn in &record.turns {
+                println!("[{}] {}", turn.role, turn.content);
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Delete { key } => {
+            if store.delete(&key)? {
+                println!("✅ Deleted channel session {key}");
+            } else {
+                println!("No channel session found for '{key}'");
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Clear { channel, yes } => {
+            if !yes {
+                let scope = channel
+                    .as_deref()
+                    .map_or_else(|| "all channels".to_string(), |c| format!("channel '{c}'"));
+                let confirmed = dialoguer::Confirm::new()
+                    .with_prompt(format!("Delete every persisted session for {scope}?"))
+                    .default(false)
+                    .interact()?;
+                if !confirmed {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+            let removed = store.clear(channel.as_deref())?;
+            println!("✅ Deleted {removed} channel session(s)");
+            Ok(())
+        }
+        crate::SessionCommands::Prune => {
+            let removed = store.prune_expired(&config.channels_config.sessions)?;
+            println!("✅ Pruned {removed} expired channel session(s)");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_and_load_round_trips_turns() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        let turns = vec![ChatMessage::user("hello"), ChatMessage::assistant("hi!")];
+        store.save("telegram_alice", "telegram", &turns).unwrap();
+
+        let reopened = SessionStore::open(tmp.path()).unwrap();
+        let histories = reopened.load_histories().unwrap();
+        let restored = histories.get("telegram_alice").unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].role, "user");
+        assert_eq!(restored[1].content, "hi!");
+    }
+
+    #[test]
+    fn writer_applies_snapshots_in_queue_order() {
+        let tmp = TempDir::new().unwrap();
+        let store = Arc::new(SessionStore::open(tmp.path()).unwrap());
+        let writer = SessionWriter::spawn(Arc::clone(&store)).unwrap();
+
+        for n in 1..=20 {
+            let turns = (0..n)
+                .map(|i| ChatMessage::user(format!("turn {i}")))
+                .collect();
+            writer.save("telegram_alice", "telegram", turns);
+        }
+        writer.flush();
+
+        let record = store.get("telegram_alice").unwrap().unwrap();
+        assert_eq!(record.turns.len(), 20);
+    }
+
+    #[test]
+    fn save_empty_turns_deletes_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        store
+            .save("slack_bob", "slack", &[ChatMessage::user("hey")])
+            .unwrap();
+        store.save("slack_bob", "slack", &[]).unwrap();
+
+        assert!(store.get("slack_bob").unwrap().is_none());
+        assert!(store.take_tombstones().unwrap().is_empty());
+    }
+
+    #[test]
+    fn delete_and_clear_leave_tombstones_for_the_runtime() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        store
+            .save("telegram_alice", "telegram", &[ChatMessage::user("a")])
+            .unwrap();
+        assert!(store.delete("telegram_alice").unwrap());
+        store.clear(Some("discord")).unwrap();
+        store.clear(None).unwrap();
+
+        let mut tombstones = store.take_tombstones().unwrap();
+        tombstones.sort_by_key(|t| format!("{t:?}"));
+        assert_eq!(
+            tombstones,
+            vec![
+                SessionTombstone::All,
+                SessionTombstone::Channel("discord".into()),
+                SessionTombstone::Session("telegram_alice".into()),
+            ]
+        );
+        assert!(store.take_tombstones().unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_filters_by_channel() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        store
+            .save("telegram_alice", "telegram", &[ChatMessage::user("a")])
+            .unwrap();
+        store
+            .save("discord_bob", "discord", &[ChatMessage::user("b")])
+            .unwrap();
+
+        let discord = store.list(Some("discord")).unwrap();
+        assert_eq!(discord.len(), 1);
+        assert_eq!(discord[0].key, "discord_bob");
+        assert_eq!(discord[0].message_count, 1);
+        assert_eq!(store.list(None).unwrap().len(), 2);
+
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE channel_sessions SET turns = 'not json' WHERE session_key = 'discord_bob'",
+                [],
+            )
+            .unwrap();
+        assert_eq!(store.list(None).unwrap().len(), 1);
+        store
+            .save("discord_bob", "discord", &[ChatMessage::user("b")])
+            .unwrap();
+        assert_eq!(store.clear(Some("telegram")).unwrap(), 1);
+        assert_eq!(store.list(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prune_expired_respects_per_channel_retention() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        store
+            .save("telegram_alice", "telegram", &[ChatMessage::user("a")])
+            .unwrap();
+        store
+            .save("discord_bob", "discord", &[ChatMessage::user("b")])
+            .unwrap();
+
+        let stale = (Utc::now() - Duration::hours(48)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE channel_sessions SET updated_at = ?1",
+                params![stale],
+            )
+            .unwrap();
+
+        let mut config = ChannelSessionsConfig {
+            retention_hours: 0,
+            ..ChannelSessionsConfig::default()
+        };
+        config.channel_retention_hours.insert("discord".into(), 24);
+
+        assert_eq!(store.prune_expired(&config).unwrap(), 1);
+        let remaining = store.list(None).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].channel, "telegram");
+        assert_eq!(
+            store.take_tombstones().unwrap(),
+            vec![SessionTombstone::Session("discord_bob".into())]
+        );
+    }
+
+    #[test]
+    fn prune_expired_keeps_sessions_with_unrepresentable_retention() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        store
+            .save("telegram_alice", "telegram", &[ChatMessage::user("a")])
+            .unwrap();
+
+        let config = ChannelSessionsConfig {
+            retention_hours: u64::MAX,
+            ..ChannelSessionsConfig::default()
+        };
+
+        assert_eq!(store.prune_expired(&config).unwrap(), 0);
+        assert_eq!(store.list(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn channel_for_history_key_prefers_longest_match() {
+        let names = ["nextcloud", "nextcloud_talk", "telegram"];
+        assert_eq!(
+            channel_for_history_key("nextcloud_talk_alice", names).as_deref(),
+            Some("nextcloud_talk")
+        );
+        assert_eq!(
+            channel_for_history_key("telegram_42_alice", names).as_deref(),
+            Some("telegram")
+        );
+        assert!(channel_for_history_key("slack_bob", names).is_none());
+    }
+}
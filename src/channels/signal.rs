@@ -1,14 +1,21 @@
+use crate::channels::attachments::{self, Attachment, AttachmentKind, AttachmentSource};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use base64::Engine;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::Deserialize;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 const GROUP_TARGET_PREFIX: &str = "group:";
 
+/// Placeholder source for inbound attachments until `getAttachment` has
+/// fetched them into the workspace; never reaches the agent.
+const PENDING_ATTACHMENT_PREFIX: &str = "signal-attachment:";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum RecipientTarget {
     Direct(String),
@@ -28,6 +35,7 @@ pub struct SignalChannel {
     allowed_from: Vec<String>,
     ignore_attachments: bool,
     ignore_stories: bool,
+    workspace_dir: Option<PathBuf>,
 }
 
 // ── signal-cli SSE event JSON shapes ────────────────────────────
@@ -87,9 +95,17 @@ impl SignalChannel {
             allowed_from,
             ignore_attachments,
             ignore_stories,
+            workspace_dir: None,
         }
     }
 
+    /// Configure the workspace used to store received attachments and to
+    /// validate outgoing attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn http_client(&self) -> Client {
         let builder = Client::builder().connect_timeout(Duration::from_secs(10));
         let builder = crate::config::apply_runtime_proxy_to_builder(builder, "channel.signal");
@@ -232,7 +248,16 @@ impl SignalChannel {
             }
         }
 
-        let text = data_msg.message.as_deref().filter(|t| !t.is_empty())?;
+        // Attachments need a workspace to be downloaded into.
+        let media = if self.workspace_dir.is_some() {
+            Self::pending_attachments(data_msg)
+        } else {
+            Vec::new()
+        };
+        let text = data_msg.message.as_deref().unwrap_or("");
+        if text.is_empty() && media.is_empty() {
+            return None;
+        }
         let sender = Self::sender(envelope)?;
 
         if !self.is_sender_allowed(&sender) {
@@ -266,8 +291,124 @@ impl SignalChannel {
             channel: "signal".to_string(),
             timestamp: timestamp / 1000, // millis → secs
             thread_ts: None,
+            attachments: media,
+            transcribed_voice: false,
         })
     }
+
+    /// Attachments listed in a data message, keyed by their signal-cli id.
+    /// Audio is treated as a voice note, which is how Signal sends speech.
+    fn pending_attachments(data_msg: &DataMessage) -> Vec<Attachment> {
+        data_msg
+            .attachments
+            .iter()
+            .flatten()
+            .filter_map(|attachment| {
+                let id = attachment.get("id").and_then(|i| i.as_str())?;
+                let mime_type = attachment
+                    .get("contentType")
+                    .and_then(|c| c.as_str())
+                    .unwrap_or("application/octet-stream");
+                let kind = match AttachmentKind::from_mime_type(mime_type) {
+                    AttachmentKind::Audio => AttachmentKind::Voice,
+                    kind => kind,
+                };
+                let mut pending =
+                    Attachment::from_url(kind, format!("{PENDING_ATTACHMENT_PREFIX}{id}"))
+                        .with_mime_type(mime_type);
+                if let Some(size) = attachment.get("size").and_then(serde_json::Value::as_u64) {
+                    pending = pending.with_size(size);
+                }
+                if let Some(caption) = attachment.get("caption").and_then(|c| c.as_str()) {
+                    pending = pending.with_caption(caption);
+                }
+                Some(pending)
+            })
+            .collect()
+    }
+
+    /// Fetch pending attachments with `getAttachment` into
+    /// `<workspace>/signal_files/`. Attachments that cannot be fetched are
+    /// dropped; a message left with neither text nor media is discarded.
+    async fn download_attachments(&self, mut msg: ChannelMessage) -> Option<ChannelMessage> {
+        let Some(workspace) = self.workspace_dir.as_ref() else {
+            return Some(msg);
+        };
+        let mut fetched = Vec::with_capacity(msg.attachments.len());
+        for mut attachment in std::mem::take(&mut msg.attachments) {
+            let Some(id) = attachment
+                .url()
+                .and_then(|url| url.strip_prefix(PENDING_ATTACHMENT_PREFIX))
+                .map(str::to_string)
+            else {
+                fetched.push(attachment);
+                continue;
+            };
+            let mut params = match Self::parse_recipient_target(&msg.reply_target) {
+                RecipientTarget::Direct(number) => serde_json::json!({ "recipient": number }),
+                RecipientTarget::Group(group_id) => serde_json::json!({ "groupId": group_id }),
+            };
+            params["id"] = serde_json::json!(id);
+            params["account"] = serde_json::json!(self.account);
+
+            let saved = async {
+                let result = self
+                    .rpc_request("getAttachment", params)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("empty getAttachment response"))?;
+                let data = result
+                    .get("data")
+                    .and_then(|d| d.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("getAttachment returned no data"))?;
+                let bytes = base64::engine::general_purpose::STANDARD.decode(data)?;
+                let extension = mime_guess::get_mime_extensions_str(
+                    attachment.mime_type.as_deref().unwrap_or_default(),
+                )
+                .and_then(|exts| exts.first())
+                .unwrap_or(&"bin");
+                attachments::save_inbound(
+                    workspace,
+                    "signal_files",
+                    &format!("{id}.{extension}"),
+                    &bytes,
+                )
+                .await
+            }
+            .await;
+            match saved {
+                Ok(path) => {
+                    attachment.source = AttachmentSource::Path(path);
+                    fetched.push(attachment);
+                }
+                Err(e) => tracing::warn!("Signal: failed to fetch attachment {id}: {e}"),
+            }
+        }
+        msg.attachments = fetched;
+        (!msg.content.is_empty() || !msg.attachments.is_empty()).then_some(msg)
+    }
+
+    /// Encode a local workspace file as the data URI signal-cli accepts in
+    /// `attachments`, so the daemon need not share our filesystem.
+    async fn attachment_data_uri(&self, attachment: &Attachment) -> anyhow::Result<String> {
+        let path = attachment
+            .path()
+            .ok_or_else(|| anyhow::anyhow!("attachment is not a local file"))?;
+        let workspace = self
+            .workspace_dir
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("workspace_dir is not configured"))?;
+        let path = attachments::resolve_workspace_path(workspace, &path.to_string_lossy())?;
+        let bytes = tokio::fs::read(&path).await?;
+        let file_name = attachment
+            .file_name()
+            .unwrap_or_else(|| "attachment".to_string());
+        Ok(format!(
+            "data:{};filename={};base64,{}",
+            attachment.effective_mime_type(),
+            file_name,
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ))
+    }
 }
 
 #[async_trait]
@@ -277,23 +418,52 @@ impl Channel for SignalChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let params = match Self::parse_recipient_target(&message.recipient) {
+        let (text, outgoing) = message.split_attachments();
+        let mut files = Vec::new();
+        let mut links = Vec::new();
+        for attachment in outgoing {
+            if attachment.url().is_some() {
+                links.push(attachment);
+                continue;
+            }
+            match self.attachment_data_uri(&attachment).await {
+                Ok(uri) => files.push(uri),
+                Err(e) => {
+                    tracing::warn!("Signal: attachment not sent: {e}");
+                    links.push(attachment);
+                }
+            }
+        }
+        let content = if files.is_empty() && links.is_empty() {
+            message.content.clone()
+        } else {
+            attachments::append_links(&text, &links)
+        };
+
+        let mut params = match Self::parse_recipient_target(&message.recipient) {
             RecipientTarget::Direct(number) => serde_json::json!({
                 "recipient": [number],
-                "message": &message.content,
+                "message": content,
                 "account": &self.account,
             }),
             RecipientTarget::Group(group_id) => serde_json::json!({
                 "groupId": group_id,
-                "message": &message.content,
+                "message": content,
                 "account": &self.account,
             }),
         };
+        if !files.is_empty() {
+            params["attachments"] = serde_json::json!(files);
+        }
 
         self.rpc_request("send", params).await?;
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let mut url = reqwest::Url::parse(&format!("{}/api/v1/events", self.http_url))?;
         url.query_pairs_mut().append_pair("account", &self.account);
@@ -370,7 +540,11 @@ impl Channel for SignalChannel {
                             match serde_json::from_str::<SseEnvelope>(&current_data) {
                                 Ok(sse) => {
                                     if let Some(ref envelope) = sse.envelope {
-                                        if let Some(msg) = self.process_envelope(envelope) {
+                                        let msg = match self.process_envelope(envelope) {
+                                            Some(msg) => self.download_attachments(msg).await,
+                                            None => None,
+                                        };
+                                        if let Some(msg) = msg {
                                             if tx.send(msg).await.is_err() {
                                                 return Ok(());
                                             }
@@ -398,7 +572,9 @@ impl Channel for SignalChannel {
                     Ok(sse) => {
                         if let Some(ref envelope) = sse.envelope {
                             if let Some(msg) = self.process_envelope(envelope) {
-                                let _ = tx.send(msg).await;
+                                if let Some(msg) = self.download_attachments(msg).await {
+                                    let _ = tx.send(msg).await;
+                                }
                             }
                         }
                     }
@@ -1,8 +1,13 @@
+use super::attachments::{self, Attachment, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+/// Slack files larger than this stay links instead of being downloaded.
+const SLACK_MAX_FILE_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
+
 /// Slack channel — polls conversations.history via Web API
 pub struct SlackChannel {
     bot_token: String,
@@ -10,6 +15,7 @@ pub struct SlackChannel {
     allowed_users: Vec<String>,
     mention_only: bool,
     group_reply_allowed_sender_ids: Vec<String>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl SlackChannel {
@@ -20,9 +26,17 @@ impl SlackChannel {
             allowed_users,
             mention_only: false,
             group_reply_allowed_sender_ids: Vec::new(),
+            workspace_dir: None,
         }
     }
 
+    /// Configure the workspace used to store downloaded files and to
+    /// validate local upload paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     /// Configure group-chat trigger policy.
     pub fn with_group_reply_policy(
         mut self,
@@ -242,6 +256,198 @@ impl SlackChannel {
         Ok(channels)
     }
 
+    /// Files shared in a Slack message. `url_private` needs the bot token,
+    /// so [`Self::download_attachments`] fetches them into the workspace.
+    fn inbound_attachments(msg: &serde_json::Value) -> Vec<(String, Attachment)> {
+        msg.get("files")
+            .and_then(|f| f.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|file| {
+                let url = file.get("url_private").and_then(|u| u.as_str())?;
+                let name = file
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or("file")
+                    .to_string();
+                let mime_type = file
+                    .get("mimetype")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("application/octet-stream");
+                let mut attachment =
+                    Attachment::from_url(AttachmentKind::from_mime_type(mime_type), url)
+                        .with_mime_type(mime_type);
+                if let Some(size) = file.get("size").and_then(serde_json::Value::as_u64) {
+                    attachment = attachment.with_size(size);
+                }
+                if let Some(title) = file.get("title").and_then(|t| t.as_str()) {
+                    if title != name {
+                        attachment = attachment.with_caption(title);
+                    }
+                }
+                Some((name, attachment))
+            })
+            .collect()
+    }
+
+    /// Download private Slack files to `<workspace>/slack_files/` so the
+    /// agent can read them. Files that cannot be fetched keep their URL.
+    async fn download_attachments(&self, files: Vec<(String, Attachment)>) -> Vec<Attachment> {
+        let mut out = Vec::with_capacity(files.len());
+        for (name, attachment) in files {
+            let (Some(workspace), Some(url)) = (self.workspace_dir.as_ref(), attachment.url())
+            else {
+                out.push(attachment);
+                continue;
+            };
+            if attachment
+                .size_bytes
+                .is_some_and(|size| size > SLACK_MAX_FILE_DOWNLOAD_BYTES)
+            {
+                out.push(attachment);
+                continue;
+            }
+            let downloaded = async {
+                let resp = self
+                    .http_client()
+                    .get(url)
+                    .bearer_auth(&self.bot_token)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                let bytes = resp.bytes().await?;
+                attachments::save_inbound(workspace, "slack_files", &name, &bytes).await
+            }
+            .await;
+            match downloaded {
+                Ok(path) => out.push(Attachment {
+                    source: attachments::AttachmentSource::Path(path),
+                    ..attachment
+                }),
+                Err(e) => {
+                    tracing::warn!("Slack: failed to download file {name}: {e}");
+                    out.push(attachment);
+                }
+            }
+        }
+        out
+    }
+
+    /// Parse a Slack Web API response, failing on HTTP errors and `ok: false`.
+    async fn api_result(
+        method: &str,
+        resp: reqwest::Response,
+    ) -> anyhow::Result<serde_json::Value> {
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+
+        if !status.is_success() {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Slack {method} failed ({status}): {sanitized}");
+        }
+
+        // Slack returns 200 for most app-level errors; check JSON "ok" field
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack {method} failed: {err}");
+        }
+
+        Ok(parsed)
+    }
+
+    async fn post_message(
+        &self,
+        channel: &str,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut body = serde_json::json!({
+            "channel": channel,
+            "text": text
+        });
+
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        Self::api_result("chat.postMessage", resp).await?;
+        Ok(())
+    }
+
+    /// Upload a local file with the external upload flow:
+    /// `files.getUploadURLExternal`, a raw POST, then `files.completeUploadExternal`.
+    async fn upload_file(
+        &self,
+        channel: &str,
+        thread_ts: Option<&str>,
+        path: &Path,
+        title: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let bytes = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/files.getUploadURLExternal")
+            .bearer_auth(&self.bot_token)
+            .form(&[
+                ("filename", file_name.clone()),
+                ("length", bytes.len().to_string()),
+            ])
+            .send()
+            .await?;
+        let ticket = Self::api_result("files.getUploadURLExternal", resp).await?;
+        let (Some(upload_url), Some(file_id)) = (
+            ticket.get("upload_url").and_then(|u| u.as_str()),
+            ticket.get("file_id").and_then(|f| f.as_str()),
+        ) else {
+            anyhow::bail!("Slack files.getUploadURLExternal returned no upload_url/file_id");
+        };
+
+        self.http_client()
+            .post(upload_url)
+            .body(bytes)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let mut body = serde_json::json!({
+            "files": [{"id": file_id, "title": title.unwrap_or(&file_name)}],
+            "channel_id": channel,
+        });
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/files.completeUploadExternal")
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+        Self::api_result("files.completeUploadExternal", resp).await?;
+        Ok(())
+    }
+
     fn slack_now_ts() -> String {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -268,47 +474,51 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "channel": message.recipient,
-            "text": message.content
-        });
-
-        if let Some(ref ts) = message.thread_ts {
-            body["thread_ts"] = serde_json::json!(ts);
+        let thread_ts = message.thread_ts.as_deref();
+        let (text, outgoing) = message.split_attachments();
+        if outgoing.is_empty() {
+            return self
+                .post_message(&message.recipient, &message.content, thread_ts)
+                .await;
         }
 
-        let resp = self
-            .http_client()
-            .post("https://slack.com/api/chat.postMessage")
-            .bearer_auth(&self.bot_token)
-            .json(&body)
-            .send()
-            .await?;
-
-        let status = resp.status();
-        let body = resp
-            .text()
-            .await
-            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-
-        if !status.is_success() {
-            let sanitized = crate::providers::sanitize_api_error(&body);
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {sanitized}");
+        // Local files are uploaded; remote media is linked, and Slack unfurls it.
+        let mut uploads = Vec::new();
+        let mut links = Vec::new();
+        for attachment in outgoing {
+            let resolved = match (attachment.path(), self.workspace_dir.as_ref()) {
+                (Some(path), Some(workspace)) => {
+                    attachments::resolve_workspace_path(workspace, &path.to_string_lossy())
+                        .map_err(|e| {
+                            tracing::warn!("Slack: local attachment rejected: {e}");
+                        })
+                        .ok()
+                }
+                _ => None,
+            };
+            match resolved {
+                Some(path) => uploads.push((path, attachment.caption)),
+                None => links.push(attachment),
+            }
         }
 
-        // Slack returns 200 for most app-level errors; check JSON "ok" field
-        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
-        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
-            let err = parsed
-                .get("error")
-                .and_then(|e| e.as_str())
-                .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
+        let text = attachments::append_links(&text, &links);
+        if !text.is_empty() {
+            self.post_message(&message.recipient, &text, thread_ts)
+                .await?;
+        }
+        for (path, caption) in uploads {
+            self.upload_file(&message.recipient, thread_ts, &path, caption.as_deref())
+                .await?;
         }
 
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = self.get_bot_user_id().await.unwrap_or_default();
         let scoped_channel = self.configured_channel_id();
@@ -435,8 +645,10 @@ impl Channel for SlackChannel {
                             continue;
                         }
 
+                        let files = Self::inbound_attachments(msg);
+
                         // Skip empty or already-seen
-                        if text.is_empty() || ts <= last_ts {
+                        if (text.is_empty() && files.is_empty()) || ts <= last_ts {
                             continue;
                         }
 
@@ -445,13 +657,19 @@ impl Channel for SlackChannel {
                             is_group_message && self.is_group_sender_trigger_enabled(user);
                         let require_mention =
                             self.mention_only && is_group_message && !allow_sender_without_mention;
-                        let Some(normalized_text) =
-                            Self::normalize_incoming_content(text, require_mention, &bot_user_id)
-                        else {
-                            continue;
+                        let normalized_text = match Self::normalize_incoming_content(
+                            text,
+                            require_mention,
+                            &bot_user_id,
+                        ) {
+                            Some(normalized_text) => normalized_text,
+                            // File-only messages carry no text to normalize.
+                            None if !require_mention && !files.is_empty() => String::new(),
+                            None => continue,
                         };
 
                         last_ts_by_channel.insert(channel_id.clone(), ts.to_string());
+                        let media = self.download_attachments(files).await;
 
                         let channel_msg = ChannelMessage {
                             id: format!("slack_{channel_id}_{ts}"),
@@ -464,6 +682,8 @@ impl Channel for SlackChannel {
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: Self::inbound_thread_ts(msg, ts),
+                            attachments: media,
+                            transcribed_voice: false,
                         };
 
                         if tx.send(channel_msg).await.is_err() {
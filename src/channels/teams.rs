@@ -0,0 +1,963 @@
+use super::attachments::{self, Attachment, AttachmentKind};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use base64::Engine;
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, RwLock};
+
+const BOT_FRAMEWORK_OPENID_CONFIG_URL: &str =
+    "https://login.botframework.com/v1/.well-known/openidconfiguration";
+const BOT_FRAMEWORK_ISSUER: &str = "https://api.botframework.com";
+const BOT_FRAMEWORK_SCOPE: &str = "https://api.botframework.com/.default";
+const DEFAULT_TOKEN_TENANT: &str = "botframework.com";
+/// Microsoft recommends refreshing the signing keys once a day.
+const SIGNING_KEYS_TTL_SECS: u64 = 24 * 60 * 60;
+/// Minimum age of the key cache before an unknown `kid` triggers a refetch.
+const SIGNING_KEYS_MIN_REFRESH_SECS: u64 = 5 * 60;
+/// Tolerated clock skew when checking token lifetimes.
+const CLOCK_SKEW_SECS: u64 = 5 * 60;
+/// `Action.Submit` data field carrying a runtime command back from a card.
+const CARD_COMMAND_FIELD: &str = "zeroclaw_command";
+/// Teams only renders inline (base64) images up to this size.
+const MAX_INLINE_IMAGE_BYTES: u64 = 1024 * 1024;
+
+/// Sender of the running Teams listener. The gateway receives activities,
+/// so it hands verified messages over here to reach the channel runtime.
+static LISTENER: LazyLock<parking_lot::Mutex<Option<mpsc::Sender<ChannelMessage>>>> =
+    LazyLock::new(|| parking_lot::Mutex::new(None));
+
+/// Pass a verified inbound message to the channel runtime so it gets
+/// history, runtime commands and approvals like polling channels do.
+/// Gives the message back when no Teams listener runs in this process.
+pub async fn forward_to_listener(msg: ChannelMessage) -> Result<(), ChannelMessage> {
+    let Some(tx) = LISTENER.lock().clone() else {
+        return Err(msg);
+    };
+    tx.send(msg).await.map_err(|e| e.0)
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// `service_url|conversation_id` — both are needed to reply, and service
+/// URLs never contain `|`.
+fn format_reply_target(service_url: &str, conversation_id: &str) -> String {
+    format!("{}|{conversation_id}", service_url.trim_end_matches('/'))
+}
+
+fn parse_reply_target(target: &str) -> anyhow::Result<(&str, &str)> {
+    let (service_url, conversation_id) = target
+        .split_once('|')
+        .filter(|(url, id)| !url.is_empty() && !id.is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!("Teams recipient must be `<service_url>|<conversation_id>`")
+        })?;
+    if !service_url.starts_with("https://") {
+        anyhow::bail!("Refusing to send Teams activity to non-HTTPS service URL");
+    }
+    Ok((service_url, conversation_id))
+}
+
+/// Remove `<at>Bot</at>` mention tags Teams puts in channel messages.
+fn strip_mentions(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<at>") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find("</at>") {
+            Some(end) => rest = &rest[start + end + "</at>".len()..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+/// The request ID of a non-CLI approval prompt, taken from its
+/// ``/approve-confirm <id>`` instruction.
+fn approval_request_id(text: &str) -> Option<&str> {
+    text.match_indices("/approve-confirm ")
+        .find_map(|(i, pat)| {
+            let tail = &text[i + pat.len()..];
+            let end = tail
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                .unwrap_or(tail.len());
+            let id = &tail[..end];
+            id.starts_with("apr-").then_some(id)
+        })
+}
+
+/// Adaptive card that shows an approval prompt with a button confirming it.
+/// The submit comes back as a message activity carrying the command.
+fn approval_card(text: &str, request_id: &str) -> Value {
+    json!({
+        "contentType": "application/vnd.microsoft.card.adaptive",
+        "content": {
+            "type": "AdaptiveCard",
+            "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+            "version": "1.4",
+            "body": [{ "type": "TextBlock", "text": text, "wrap": true }],
+            "actions": [{
+                "type": "Action.Submit",
+                "title": "Approve",
+                "data": { CARD_COMMAND_FIELD: format!("/approve-confirm {request_id}") },
+            }],
+        },
+    })
+}
+
+fn inbound_attachments(activity: &Value) -> Vec<Attachment> {
+    let Some(items) = activity.get("attachments").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let content_type = item.get("contentType").and_then(Value::as_str)?;
+            let name = item.get("name").and_then(Value::as_str);
+            // Files shared in Teams arrive as download-info cards; inline
+            // images carry a direct content URL.
+            let (url, mime_type) =
+                if content_type == "application/vnd.microsoft.teams.file.download.info" {
+                    let url = item
+                        .pointer("/content/downloadUrl")
+                        .and_then(Value::as_str)?;
+                    let mime = name
+                        .and_then(|name| mime_guess::from_path(name).first_raw())
+                        .unwrap_or("application/octet-stream");
+                    (url, mime)
+                } else if content_type.starts_with("image/")
+                    || content_type.starts_with("audio/")
+                    || content_type.starts_with("video/")
+                {
+                    (
+                        item.get("contentUrl").and_then(Value::as_str)?,
+                        content_type,
+                    )
+                } else {
+                    return None;
+                };
+            let mut attachment =
+                Attachment::from_url(AttachmentKind::from_mime_type(mime_type), url)
+                    .with_mime_type(mime_type);
+            if let Some(name) = name {
+                attachment = attachment.with_caption(name);
+            }
+            Some(attachment)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SigningKey {
+    kid: String,
+    n: String,
+    e: String,
+    #[serde(default)]
+    endorsements: Vec<String>,
+}
+
+struct SigningKeyCache {
+    keys: Vec<SigningKey>,
+    fetched_at: u64,
+}
+
+fn decode_base64url(segment: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(segment.trim_end_matches('='))?)
+}
+
+/// The `kid` header of a JWT, naming the key that signed it.
+fn token_key_id(token: &str) -> Option<String> {
+    let header = decode_base64url(token.split('.').next()?).ok()?;
+    let header: Value = serde_json::from_slice(&header).ok()?;
+    Some(header.get("kid")?.as_str()?.to_string())
+}
+
+/// Check the RS256 signature of a Bot Framework token against `keys` and
+/// return its claims. The signing key must be endorsed for `channel_id`.
+fn verify_token_signature(
+    token: &str,
+    keys: &[SigningKey],
+    channel_id: &str,
+) -> anyhow::Result<Value> {
+    let mut parts = token.split('.');
+    let (Some(header_b64), Some(payload_b64), Some(signature_b64), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("malformed token");
+    };
+
+    let header: Value = serde_json::from_slice(&decode_base64url(header_b64)?)?;
+    if header.get("alg").and_then(Value::as_str) != Some("RS256") {
+        anyhow::bail!("unsupported token algorithm");
+    }
+    let kid = header
+        .get("kid")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("token has no key ID"))?;
+    let key = keys
+        .iter()
+        .find(|key| key.kid == kid)
+        .ok_or_else(|| anyhow::anyhow!("unknown signing key `{kid}`"))?;
+    if !key.endorsements.is_empty() && !key.endorsements.iter().any(|e| e == channel_id) {
+        anyhow::bail!("signing key is not endorsed for channel `{channel_id}`");
+    }
+
+    let public_key = RsaPublicKeyComponents {
+        n: decode_base64url(&key.n)?,
+        e: decode_base64url(&key.e)?,
+    };
+    let signed = &token[..header_b64.len() + 1 + payload_b64.len()];
+    public_key
+        .verify(
+            &RSA_PKCS1_2048_8192_SHA256,
+            signed.as_bytes(),
+            &decode_base64url(signature_b64)?,
+        )
+        .map_err(|_| anyhow::anyhow!("invalid token signature"))?;
+
+    Ok(serde_json::from_slice(&decode_base64url(payload_b64)?)?)
+}
+
+/// Check issuer, audience, lifetime and service URL of verified claims.
+fn validate_claims(
+    claims: &Value,
+    app_id: &str,
+    service_url: &str,
+    now: u64,
+) -> anyhow::Result<()> {
+    if claims.get("iss").and_then(Value::as_str) != Some(BOT_FRAMEWORK_ISSUER) {
+        anyhow::bail!("unexpected token issuer");
+    }
+    if claims.get("aud").and_then(Value::as_str) != Some(app_id) {
+        anyhow::bail!("token audience does not match app_id");
+    }
+    let expires = claims
+        .get("exp")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow::anyhow!("token has no expiry"))?;
+    if expires + CLOCK_SKEW_SECS < now {
+        anyhow::bail!("token expired");
+    }
+    if let Some(not_before) = claims.get("nbf").and_then(Value::as_u64) {
+        if not_before > now + CLOCK_SKEW_SECS {
+            anyhow::bail!("token not yet valid");
+        }
+    }
+    // Replies carry the Connector token to the activity's serviceUrl, so a
+    // token that does not pin it must not be trusted.
+    let claimed = claims
+        .get("serviceurl")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("token has no service URL"))?;
+    if claimed.trim_end_matches('/') != service_url.trim_end_matches('/') {
+        anyhow::bail!("token service URL does not match the activity");
+    }
+    Ok(())
+}
+
+/// Microsoft Teams channel via the Bot Framework.
+///
+/// Incoming activities are received by the gateway endpoint `/teams` and
+/// forwarded to the listener; replies go through the Bot Connector API.
+pub struct TeamsChannel {
+    app_id: String,
+    app_password: String,
+    tenant_id: Option<String>,
+    allowed_users: Vec<String>,
+    workspace_dir: Option<PathBuf>,
+    token_cache: Arc<RwLock<Option<(String, u64)>>>,
+    signing_keys: Arc<RwLock<Option<SigningKeyCache>>>,
+}
+
+impl TeamsChannel {
+    pub fn new(
+        app_id: String,
+        app_password: String,
+        tenant_id: Option<String>,
+        allowed_users: Vec<String>,
+    ) -> Self {
+        Self {
+            app_id,
+            app_password,
+            tenant_id: tenant_id.filter(|id| !id.trim().is_empty()),
+            allowed_users,
+            workspace_dir: None,
+            token_cache: Arc::new(RwLock::new(None)),
+            signing_keys: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Resolve local attachment paths against this workspace.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.teams")
+    }
+
+    fn is_user_allowed(&self, ids: &[&str]) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || ids.iter().any(|id| u.eq_ignore_ascii_case(id)))
+    }
+
+    /// Verify the `Authorization` header of an incoming activity.
+    pub async fn verify_request(
+        &self,
+        authorization: Option<&str>,
+        activity: &Value,
+    ) -> anyhow::Result<()> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("missing bearer token"))?;
+        let service_url = activity
+            .get("serviceUrl")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let channel_id = activity
+            .get("channelId")
+            .and_then(Value::as_str)
+            .unwrap_or("msteams");
+
+        let mut keys = self.signing_keys(false).await?;
+        if !token_key_id(token).is_some_and(|kid| keys.iter().any(|key| key.kid == kid)) {
+            // Keys may have rotated since the last fetch.
+            keys = self.signing_keys(true).await?;
+        }
+        let claims = verify_token_signature(token, &keys, channel_id)?;
+        validate_claims(&claims, &self.app_id, service_url, now_unix_secs())
+    }
+
+    /// Cached Bot Framework signing keys; `refresh` refetches them unless
+    /// they were fetched moments ago.
+    async fn signing_keys(&self, refresh: bool) -> anyhow::Result<Vec<SigningKey>> {
+        let now = now_unix_secs();
+        {
+            let cache = self.signing_keys.read().await;
+            if let Some(cache) = cache.as_ref() {
+                let age = now.saturating_sub(cache.fetched_at);
+                let fresh = if refresh {
+                    age < SIGNING_KEYS_MIN_REFRESH_SECS
+                } else {
+                    age < SIGNING_KEYS_TTL_SECS
+                };
+                if fresh {
+                    return Ok(cache.keys.clone());
+                }
+            }
+        }
+
+        let client = self.http_client();
+        let openid: Value = client
+            .get(BOT_FRAMEWORK_OPENID_CONFIG_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let jwks_uri = openid
+            .get("jwks_uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Bot Framework OpenID config has no jwks_uri"))?;
+        let jwks: Value = client
+            .get(jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let keys: Vec<SigningKey> =
+            serde_json::from_value(jwks.get("keys").cloned().unwrap_or_else(|| json!([])))?;
+
+        *self.signing_keys.write().await = Some(SigningKeyCache {
+            keys: keys.clone(),
+            fetched_at: now,
+        });
+        Ok(keys)
+    }
+
+    /// Parse a Bot Framework activity into a channel message.
+    ///
+    /// Handles `message` activities, including `Action.Submit` payloads from
+    /// approval cards, which carry their command in `value`.
+    pub fn parse_activity(&self, activity: &Value) -> Option<ChannelMessage> {
+        if activity.get("type").and_then(Value::as_str) != Some("message") {
+            return None;
+        }
+
+        if let Some(required) = self.tenant_id.as_deref() {
+            let tenant = activity
+                .pointer("/channelData/tenant/id")
+                .or_else(|| activity.pointer("/conversation/tenantId"))
+                .and_then(Value::as_str);
+            if tenant != Some(required) {
+                tracing::warn!("Teams: ignoring activity from another tenant");
+                return None;
+            }
+        }
+
+        let service_url = activity.get("serviceUrl").and_then(Value::as_str)?;
+        let conversation_id = activity
+            .pointer("/conversation/id")
+            .and_then(Value::as_str)?;
+        let from_id = activity.pointer("/from/id").and_then(Value::as_str)?;
+        let aad_object_id = activity
+            .pointer("/from/aadObjectId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        if !self.is_user_allowed(&[from_id, aad_object_id]) {
+            tracing::warn!(
+                "Teams: ignoring message from unauthorized user: {}. \
+                Add to channels.teams.allowed_users in config.toml, \
+                or run `zeroclaw onboard --channels-only` to configure interactively.",
+                if aad_object_id.is_empty() {
+                    from_id
+                } else {
+                    aad_object_id
+                }
+            );
+            return None;
+        }
+
+        let text = activity
+            .get("text")
+            .and_then(Value::as_str)
+            .map(strip_mentions)
+            .unwrap_or_default();
+        let content = if text.is_empty() {
+            activity
+                .pointer(&format!("/value/{CARD_COMMAND_FIELD}"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_default()
+        } else {
+            text
+        };
+        let attachments = inbound_attachments(activity);
+        if content.is_empty() && attachments.is_empty() {
+            return None;
+        }
+
+        let id = activity
+            .get("id")
+            .and_then(Value::as_str)
+            .map_or_else(|| uuid::Uuid::new_v4().to_string(), str::to_string);
+        // Channel posts are answered in their thread; chats have no threads.
+        let thread_ts = (activity.pointer("/conversation/conversationType")
+            == Some(&json!("channel")))
+        .then(|| id.clone());
+        let timestamp = activity
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .and_then(|ts| u64::try_from(ts.timestamp()).ok())
+            .unwrap_or_else(now_unix_secs);
+
+        Some(ChannelMessage {
+            id,
+            sender: if aad_object_id.is_empty() {
+                from_id.to_string()
+            } else {
+                aad_object_id.to_string()
+            },
+            reply_target: format_reply_target(service_url, conversation_id),
+            content,
+            channel: "teams".to_string(),
+            timestamp,
+            thread_ts,
+            attachments,
+            transcribed_voice: false,
+        })
+    }
+
+    /// Fetch a connector token with the client-credentials flow.
+    async fn fetch_access_token(&self) -> anyhow::Result<(String, u64)> {
+        let tenant = self.tenant_id.as_deref().unwrap_or(DEFAULT_TOKEN_TENANT);
+        let url = format!("https://login.microsoftonline.com/{tenant}/oauth2/v2.0/token");
+        let resp = self
+            .http_client()
+            .post(&url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.app_id.as_str()),
+                ("client_secret", self.app_password.as_str()),
+                ("scope", BOT_FRAMEWORK_SCOPE),
+            ])
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&err);
+            anyhow::bail!("Teams token request failed ({status}): {sanitized}");
+        }
+
+        let data: Value = resp.json().await?;
+        let token = data
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing access_token in Teams token response"))?
+            .to_string();
+        let expires_in = data
+            .get("expires_in")
+            .and_then(Value::as_u64)
+            .unwrap_or(3600);
+
+        // Expire 60 seconds early to avoid edge cases
+        Ok((token, now_unix_secs() + expires_in.saturating_sub(60)))
+    }
+
+    /// Get a valid connector token, refreshing if expired.
+    async fn get_token(&self) -> anyhow::Result<String> {
+        {
+            let cache = self.token_cache.read().await;
+            if let Some((ref token, expiry)) = *cache {
+                if now_unix_secs() < expiry {
+                    return Ok(token.clone());
+                }
+            }
+        }
+
+        let (token, expiry) = self.fetch_access_token().await?;
+        *self.token_cache.write().await = Some((token.clone(), expiry));
+        Ok(token)
+    }
+
+    /// Post an activity to a conversation, as a reply to `reply_to_id` when set.
+    async fn post_activity(
+        &self,
+        target: &str,
+        reply_to_id: Option<&str>,
+        mut activity: Value,
+    ) -> anyhow::Result<()> {
+        let (service_url, conversation_id) = parse_reply_target(target)?;
+        let mut url = format!(
+            "{service_url}/v3/conversations/{}/activities",
+            urlencoding::encode(conversation_id)
+        );
+        if let Some(reply_to_id) = reply_to_id {
+            url.push('/');
+            url.push_str(&urlencoding::encode(reply_to_id));
+            activity["replyToId"] = json!(reply_to_id);
+        }
+        activity["from"] = json!({ "id": format!("28:{}", self.app_id) });
+        activity["conversation"] = json!({ "id": conversation_id });
+
+        let token = self.get_token().await?;
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(token)
+            .json(&activity)
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            return Ok(());
+        }
+
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        let sanitized = crate::providers::sanitize_api_error(&body);
+        tracing::error!("Teams send failed: {status} — {sanitized}");
+        anyhow::bail!("Teams connector API error: {status}");
+    }
+
+    /// Bot Framework attachment for outgoing media: remote URLs are linked
+    /// by `contentUrl`, small local images are inlined as data URIs.
+    async fn outgoing_attachment(&self, attachment: &Attachment) -> Option<Value> {
+        let mime_type = attachment.effective_mime_type();
+        if let Some(url) = attachment.url() {
+            return Some(json!({
+                "contentType": mime_type,
+                "contentUrl": url,
+                "name": attachment.caption.clone().or_else(|| attachment.file_name()),
+            }));
+        }
+
+        if attachment.kind != AttachmentKind::Image {
+            return None;
+        }
+        let path = attachments::resolve_workspace_path(
+            self.workspace_dir.as_ref()?,
+            &attachment.path()?.to_string_lossy(),
+        )
+        .map_err(|e| tracing::warn!("Teams: local attachment rejected: {e}"))
+        .ok()?;
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| tracing::warn!("Teams: failed to read {}: {e}", path.display()))
+            .ok()?;
+        if bytes.len() as u64 > MAX_INLINE_IMAGE_BYTES {
+            return None;
+        }
+        Some(json!({
+            "contentType": mime_type,
+            "contentUrl": format!(
+                "data:{mime_type};base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            ),
+            "name": attachment.file_name(),
+        }))
+    }
+}
+
+#[async_trait]
+impl Channel for TeamsChannel {
+    fn name(&self) -> &str {
+        "teams"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, outgoing) = message.split_attachments();
+        let mut cards = Vec::new();
+        let mut links = Vec::new();
+        for attachment in outgoing {
+            match self.outgoing_attachment(&attachment).await {
+                Some(card) => cards.push(card),
+                None => links.push(attachment),
+            }
+        }
+        let text = attachments::append_links(&text, &links);
+
+        let activity = match approval_request_id(&text) {
+            Some(request_id) => {
+                cards.insert(0, approval_card(&text, request_id));
+                json!({ "type": "message", "attachments": cards })
+            }
+            None => json!({
+                "type": "message",
+                "text": text,
+                "textFormat": "markdown",
+                "attachments": cards,
+            }),
+        };
+
+        self.post_activity(&message.recipient, message.thread_ts.as_deref(), activity)
+            .await
+    }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Teams channel active (webhook mode). \
+            Set the Azure Bot messaging endpoint to your gateway's /teams endpoint."
+        );
+        *LISTENER.lock() = Some(tx.clone());
+
+        // Keep the task alive; activities arrive through the gateway webhook.
+        tx.closed().await;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.get_token().await.is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.post_activity(recipient, None, json!({ "type": "typing" }))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+
+    const APP_ID: &str = "00000000-0000-0000-0000-00000000b07f";
+    const SERVICE_URL: &str = "https://smba.trafficmanager.net/amer/";
+
+    /// 2048-bit PKCS#1 RSA key used only to sign test tokens.
+    const TEST_SIGNING_KEY_DER: &str = concat!(
+        "MIIEogIBAAKCAQEAkgwk/Y9BQOK3wpylB4fELN9UlvaPaDBut8aDl9gAF0zD9fjrn0WnMM+PAOCSymDV",
+        "UYAYszmkWDEOx5F3bxyMw73w7mtOrmDWQgpZucDCx31jPIg5/YOK+QmBxE0oAYkiQryAa5j1VTNWler4",
+        "iGRGhZcBTLnxqElRwcddtar5EzgxV0s5d2MN8lPCm0c/u7AIHBfjWT8kEzu+LTlmyphJvAGjH+v8OQSd",
+        "T/9puK0RFkrBgc6QHsW7DzBYBMtGBc3j1kPNRf+fd63Zw6hHbAviKtwgjP4A6siWgu/XrGrQSyp4PXjO",
+        "Qmclfztm0OTQIha4skenQrRaCkIhSaVin1PqmQIDAQABAoIBAAIItsQsSFefwLjuZxL/hlEsrBgtJ2qn",
+        "UAdxtZf6C2hHqqwEkz2E4hL5NQ/pUMoORrJLoyxt11beGgtYAI6U2nYIcXBUoMQnd5x0WfMPFvdhzO3F",
+        "JO+dsyQMSJkkDPRWhVpiFzTIbNRKsoBEhUEbCmtmYr9/kHjMSp31CZ5T2wO0PYF/3/G4xHdrfDRbOdUJ",
+        "0YotjTUC5v1dfKLgQ1zyQ9dqELSsSrdsopL3gwPp3bMCdR6Iq0/+Ls9hU8lf4lOFM9HSXuGtQadMxRyo",
+        "GAdtvmlUJ6UQ3wbv7DDJLAtrM39FY/qC/1cJmgVLxMqVdBFPRbGuZYD4zg6GOdLZ0nwkYOECgYEAyiJ/",
+        "2FwNai4K+8A83K+8vtnPc1syL8BDiWSRRrdBbWpzR8QUB0tFJboZ4JcZ3mWVExzzZjyBNn6mDti+LsrT",
+        "r+Xo3/68TTO5ap/5O9iL8VA3OfsXzMsAAiu+UKNaI9HXilus6OcN/sFKIhyTv8CRu9yK23gHkCkuqFzB",
+        "iNV1XMkCgYEAuPdnnhUI4Mlqm9kG1ACefesZ12YBLJ+vWnm7exmLOl3IYXZZSPB0Oy+AJn8TRYRH+xrw",
+        "8Xa1Uo2OhCeV+XUG3nVipyW1PX6qNELUFeCNhLZN9kjU/6jgu/gDFkK0AGmmKLz9yo/iAUH2kqWF9d9t",
+        "AZ2wH3qnc5yzu++5VdY/l1ECgYAUp8vapyVzyTq0GSPfBVJLSmSpjBMn0ATWwGEcEdPsw2j+V2oYiLeL",
+        "MGY+jhJFqKnAJ0A0HVCy6eQ8NifRDkbtfZf7c8N4PCXhYHGOjQVX5nOOSDtZYREiJ6guA1NNv76/07hq",
+        "oFnMw6G4tKTgfmQmB1ZlMPHM6HhtGfVo7y8sCQKBgEcq6jNo5IGY/ev5FSd9FX5ddxPpq4n/5vho3jn/",
+        "GhLbAr59St1Fp3MeU7B8NG4K96cmNo6xnhKlZLB0ir/wxzPQ9qRZXeLLWbHCBNhmDg2CF30riMjVsaKh",
+        "So7GG3wNKmGCViQaOFtDhhvmlCSGUurMQXfbOZLCOGWXVzgvt0gBAoGAPMv/Pdssos/UNWCs1J0lSRVL",
+        "f4a+8rxvg+BIAY86ecnPfe6a86qW47o+lEr+aZr0gOkrK5IJY39a/HshTKtP4dzXs3Qi8qWzG9ae32nG",
+        "RtzQk/UrfcsOp8dH7DKHbrHBORYzo9L5tuhsiaB2xduJ2ZmxCGKDnQ+y3jkc7RGE3PM=",
+    );
+
+    fn channel(allowed_users: Vec<String>) -> TeamsChannel {
+        TeamsChannel::new(APP_ID.into(), "secret".into(), None, allowed_users)
+    }
+
+    fn test_key() -> (RsaKeyPair, SigningKey) {
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(TEST_SIGNING_KEY_DER)
+            .unwrap();
+        let pair = RsaKeyPair::from_der(&der).unwrap();
+        let public = RsaPublicKeyComponents::<Vec<u8>>::from(pair.public());
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        let key = SigningKey {
+            kid: "test-kid".into(),
+            n: encode(&public.n),
+            e: encode(&public.e),
+            endorsements: vec!["msteams".into()],
+        };
+        (pair, key)
+    }
+
+    fn sign(pair: &RsaKeyPair, header: &Value, claims: &Value) -> String {
+        let encode = |v: &Value| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(v.to_string().as_bytes())
+        };
+        let signed = format!("{}.{}", encode(header), encode(claims));
+        let mut signature = vec![0; pair.public().modulus_len()];
+        pair.sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            signed.as_bytes(),
+            &mut signature,
+        )
+        .unwrap();
+        format!(
+            "{signed}.{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    fn valid_claims(now: u64) -> Value {
+        json!({
+            "iss": BOT_FRAMEWORK_ISSUER,
+            "aud": APP_ID,
+            "exp": now + 3600,
+            "nbf": now - 10,
+            "serviceurl": SERVICE_URL,
+        })
+    }
+
+    fn message_activity() -> Value {
+        json!({
+            "type": "message",
+            "id": "1700000000000",
+            "timestamp": "2026-01-05T10:00:00.000Z",
+            "serviceUrl": SERVICE_URL,
+            "channelId": "msteams",
+            "from": { "id": "29:abc", "aadObjectId": "aad-user-1", "name": "Alice" },
+            "conversation": { "id": "a:conv-1", "conversationType": "personal" },
+            "text": "hello there",
+        })
+    }
+
+    #[test]
+    fn verify_token_signature_accepts_valid_token() {
+        let (pair, key) = test_key();
+        let claims = valid_claims(now_unix_secs());
+        let token = sign(&pair, &json!({"alg": "RS256", "kid": "test-kid"}), &claims);
+
+        let verified = verify_token_signature(&token, &[key], "msteams").unwrap();
+        assert_eq!(verified, claims);
+        validate_claims(&verified, APP_ID, SERVICE_URL, now_unix_secs()).unwrap();
+    }
+
+    #[test]
+    fn verify_token_signature_rejects_tampering_and_unknown_keys() {
+        let (pair, key) = test_key();
+        let header = json!({"alg": "RS256", "kid": "test-kid"});
+        let token = sign(&pair, &header, &valid_claims(now_unix_secs()));
+
+        let (signed, signature) = token.rsplit_once('.').unwrap();
+        let (head, _) = signed.split_once('.').unwrap();
+        let forged_claims = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(json!({"aud": APP_ID, "iss": "attacker"}).to_string());
+        let forged = format!("{head}.{forged_claims}.{signature}");
+        assert!(verify_token_signature(&forged, std::slice::from_ref(&key), "msteams").is_err());
+
+        let other_kid = sign(
+            &pair,
+            &json!({"alg": "RS256", "kid": "rotated"}),
+            &valid_claims(now_unix_secs()),
+        );
+        let err =
+            verify_token_signature(&other_kid, std::slice::from_ref(&key), "msteams").unwrap_err();
+        assert!(err.to_string().starts_with("unknown signing key"));
+
+        let unsigned = sign(
+            &pair,
+            &json!({"alg": "none", "kid": "test-kid"}),
+            &json!({}),
+        );
+        assert!(verify_token_signature(&unsigned, std::slice::from_ref(&key), "msteams").is_err());
+
+        assert!(verify_token_signature(&token, &[key], "skype").is_err());
+    }
+
+    #[test]
+    fn validate_claims_checks_audience_lifetime_and_service_url() {
+        let now = now_unix_secs();
+        let mut claims = valid_claims(now);
+        claims["aud"] = json!("another-bot");
+        assert!(validate_claims(&claims, APP_ID, SERVICE_URL, now).is_err());
+
+        let mut claims = valid_claims(now);
+        claims["exp"] = json!(now - CLOCK_SKEW_SECS - 1);
+        assert!(validate_claims(&claims, APP_ID, SERVICE_URL, now).is_err());
+
+        let claims = valid_claims(now);
+        assert!(validate_claims(&claims, APP_ID, "https://evil.example.com/", now).is_err());
+        assert!(validate_claims(&claims, APP_ID, SERVICE_URL.trim_end_matches('/'), now).is_ok());
+    }
+
+    #[test]
+    fn validate_claims_rejects_token_without_service_url() {
+        let now = now_unix_secs();
+        let mut claims = valid_claims(now);
+        claims.as_object_mut().unwrap().remove("serviceurl");
+
+        let err = validate_claims(&claims, APP_ID, SERVICE_URL, now).unwrap_err();
+        assert_eq!(err.to_string(), "token has no service URL");
+    }
+
+    #[test]
+    fn parse_activity_builds_reply_target_and_sender() {
+        let ch = channel(vec!["aad-user-1".into()]);
+        let msg = ch.parse_activity(&message_activity()).unwrap();
+
+        assert_eq!(msg.channel, "teams");
+        assert_eq!(msg.sender, "aad-user-1");
+        assert_eq!(msg.content, "hello there");
+        assert_eq!(
+            msg.reply_target,
+            "https://smba.trafficmanager.net/amer|a:conv-1"
+        );
+        assert_eq!(msg.thread_ts, None);
+        assert_eq!(msg.timestamp, 1_767_607_200);
+        assert_eq!(
+            parse_reply_target(&msg.reply_target).unwrap(),
+            ("https://smba.trafficmanager.net/amer", "a:conv-1")
+        );
+    }
+
+    #[test]
+    fn parse_activity_filters_users_tenants_and_non_messages() {
+        assert!(channel(vec![])
+            .parse_activity(&message_activity())
+            .is_none());
+        assert!(channel(vec!["29:abc".into()])
+            .parse_activity(&message_activity())
+            .is_some());
+
+        let mut typing = message_activity();
+        typing["type"] = json!("typing");
+        assert!(channel(vec!["*".into()]).parse_activity(&typing).is_none());
+
+        let single_tenant = TeamsChannel::new(
+            APP_ID.into(),
+            "secret".into(),
+            Some("tenant-a".into()),
+            vec!["*".into()],
+        );
+        let mut activity = message_activity();
+        activity["channelData"] = json!({ "tenant": { "id": "tenant-b" } });
+        assert!(single_tenant.parse_activity(&activity).is_none());
+        activity["channelData"] = json!({ "tenant": { "id": "tenant-a" } });
+        assert!(single_tenant.parse_activity(&activity).is_some());
+    }
+
+    #[test]
+    fn parse_activity_strips_mentions_and_threads_channel_posts() {
+        let mut activity = message_activity();
+        activity["text"] = json!("<at>ZeroClaw</at> run the report");
+        activity["conversation"] = json!({
+            "id": "19:chan@thread.tacv2;messageid=1699999999999",
+            "conversationType": "channel",
+        });
+
+        let msg = channel(vec!["*".into()]).parse_activity(&activity).unwrap();
+        assert_eq!(msg.content, "run the report");
+        assert_eq!(msg.thread_ts.as_deref(), Some("1700000000000"));
+    }
+
+    #[test]
+    fn parse_activity_maps_card_submit_to_command() {
+        let mut activity = message_activity();
+        activity.as_object_mut().unwrap().remove("text");
+        activity["value"] = json!({ CARD_COMMAND_FIELD: "/approve-confirm apr-1a2b3c4d" });
+
+        let msg = channel(vec!["*".into()]).parse_activity(&activity).unwrap();
+        assert_eq!(msg.content, "/approve-confirm apr-1a2b3c4d");
+    }
+
+    #[test]
+    fn parse_activity_collects_shared_files() {
+        let mut activity = message_activity();
+        activity["text"] = json!("");
+        activity["attachments"] = json!([
+            { "contentType": "text/html", "content": "<p>ignored</p>" },
+            {
+                "contentType": "application/vnd.microsoft.teams.file.download.info",
+                "name": "report.pdf",
+                "content": { "downloadUrl": "https://contoso.sharepoint.com/report.pdf" },
+            },
+        ]);
+
+        let msg = channel(vec!["*".into()]).parse_activity(&activity).unwrap();
+        assert_eq!(msg.attachments.len(), 1);
+        assert_eq!(msg.attachments[0].kind, AttachmentKind::Document);
+        assert_eq!(
+            msg.attachments[0].url(),
+            Some("https://contoso.sharepoint.com/report.pdf")
+        );
+    }
+
+    #[test]
+    fn approval_prompts_become_adaptive_cards() {
+        let prompt = "Approval request created.\nRequest ID: `apr-1a2b3c4d`\nTool: `shell`\nConfirm with `/approve-confirm apr-1a2b3c4d` (must be the same sender in this chat/channel).";
+        assert_eq!(approval_request_id(prompt), Some("apr-1a2b3c4d"));
+        assert_eq!(
+            approval_request_id("Confirm approval with `/approve-confirm <request-id>`."),
+            None
+        );
+
+        let card = approval_card(prompt, "apr-1a2b3c4d");
+        assert_eq!(
+            card.pointer("/content/actions/0/data/zeroclaw_command")
+                .and_then(Value::as_str),
+            Some("/approve-confirm apr-1a2b3c4d")
+        );
+    }
+
+    #[test]
+    fn parse_reply_target_rejects_plain_http() {
+        assert!(parse_reply_target("http://example.com|conv").is_err());
+        assert!(parse_reply_target("conv-only").is_err());
+    }
+}
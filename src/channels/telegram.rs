@@ -1,3 +1,4 @@
+use super::attachments::{self, Attachment, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
@@ -135,30 +136,19 @@ fn build_telegram_ack_reaction_request(
     })
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum TelegramAttachmentKind {
-    Image,
-    Document,
-    Video,
-    Audio,
-    Voice,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct TelegramAttachment {
-    kind: TelegramAttachmentKind,
+    kind: AttachmentKind,
     target: String,
+    caption: Option<String>,
 }
 
-impl TelegramAttachmentKind {
-    fn from_marker(marker: &str) -> Option<Self> {
-        match marker.trim().to_ascii_uppercase().as_str() {
-            "IMAGE" | "PHOTO" => Some(Self::Image),
-            "DOCUMENT" | "FILE" => Some(Self::Document),
-            "VIDEO" => Some(Self::Video),
-            "AUDIO" => Some(Self::Audio),
-            "VOICE" => Some(Self::Voice),
-            _ => None,
+impl From<&Attachment> for TelegramAttachment {
+    fn from(attachment: &Attachment) -> Self {
+        Self {
+            kind: attachment.kind,
+            target: attachment.target(),
+            caption: attachment.caption.clone(),
         }
     }
 }
@@ -324,7 +314,7 @@ async fn resolve_workspace_attachment_output_path(
     Ok(output_path)
 }
 
-fn infer_attachment_kind_from_target(target: &str) -> Option<TelegramAttachmentKind> {
+fn infer_attachment_kind_from_target(target: &str) -> Option<AttachmentKind> {
     let normalized = target
         .split('?')
         .next()
@@ -339,12 +329,12 @@ fn infer_attachment_kind_from_target(target: &str) -> Option<TelegramAttachmentK
         .to_ascii_lowercase();
 
     match extension.as_str() {
-        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Some(TelegramAttachmentKind::Image),
-        "mp4" | "mov" | "mkv" | "avi" | "webm" => Some(TelegramAttachmentKind::Video),
-        "mp3" | "m4a" | "wav" | "flac" => Some(TelegramAttachmentKind::Audio),
-        "ogg" | "oga" | "opus" => Some(TelegramAttachmentKind::Voice),
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Some(AttachmentKind::Image),
+        "mp4" | "mov" | "mkv" | "avi" | "webm" => Some(AttachmentKind::Video),
+        "mp3" | "m4a" | "wav" | "flac" => Some(AttachmentKind::Audio),
+        "ogg" | "oga" | "opus" => Some(AttachmentKind::Voice),
         "pdf" | "txt" | "md" | "csv" | "json" | "zip" | "tar" | "gz" | "doc" | "docx" | "xls"
-        | "xlsx" | "ppt" | "pptx" => Some(TelegramAttachmentKind::Document),
+        | "xlsx" | "ppt" | "pptx" => Some(AttachmentKind::Document),
         _ => None,
     }
 }
@@ -370,6 +360,7 @@ fn parse_path_only_attachment(message: &str) -> Option<TelegramAttachment> {
     Some(TelegramAttachment {
         kind,
         target: candidate.to_string(),
+        caption: None,
     })
 }
 
@@ -378,67 +369,12 @@ fn strip_tool_call_tags(message: &str) -> String {
     super::strip_tool_call_tags(message)
 }
 
-fn find_matching_close(s: &str) -> Option<usize> {
-    let mut depth = 1usize;
-    for (i, ch) in s.char_indices() {
-        match ch {
-            '[' => depth += 1,
-            ']' => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(i);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
-}
-
 fn parse_attachment_markers(message: &str) -> (String, Vec<TelegramAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0;
-
-    while cursor < message.len() {
-        let Some(open_rel) = message[cursor..].find('[') else {
-            cleaned.push_str(&message[cursor..]);
-            break;
-        };
-
-        let open = cursor + open_rel;
-        cleaned.push_str(&message[cursor..open]);
-
-        let Some(close_rel) = find_matching_close(&message[open + 1..]) else {
-            cleaned.push_str(&message[open..]);
-            break;
-        };
-
-        let close = open + 1 + close_rel;
-        let marker = &message[open + 1..close];
-
-        let parsed = marker.split_once(':').and_then(|(kind, target)| {
-            let kind = TelegramAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
-                return None;
-            }
-            Some(TelegramAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
-
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            cleaned.push_str(&message[open..=close]);
-        }
-
-        cursor = close + 1;
-    }
-
-    (cleaned.trim().to_string(), attachments)
+    let (cleaned, parsed) = attachments::parse_markers(message);
+    (
+        cleaned,
+        parsed.iter().map(TelegramAttachment::from).collect(),
+    )
 }
 
 /// Telegram Bot API maximum file download size (20 MB).
@@ -1318,6 +1254,16 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             return None;
         }
 
+        let media_kind = if is_image_extension(&local_path) {
+            AttachmentKind::Image
+        } else {
+            AttachmentKind::Document
+        };
+        let mut media = Attachment::from_path(media_kind, &local_path).with_local_metadata();
+        if let Some(caption) = &attachment.caption {
+            media = media.with_caption(caption.as_str());
+        }
+
         // Build message content.
         // Photos with image extensions use [IMAGE:] marker so the multimodal
         // pipeline validates vision capability. Non-image files always get
@@ -1346,6 +1292,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: vec![media],
+            transcribed_voice: false,
         })
     }
 
@@ -1489,10 +1437,11 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             chat_id
         );
 
+        let voice_prefix = super::transcription::VOICE_CONTENT_PREFIX;
         let content = if let Some(quote) = self.extract_reply_context(message) {
-            format!("{quote}\n\n[Voice] {text}")
+            format!("{quote}\n\n{voice_prefix} {text}")
         } else {
-            format!("[Voice] {text}")
+            format!("{voice_prefix} {text}")
         };
 
         Some(ChannelMessage {
@@ -1506,6 +1455,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
+            transcribed_voice: true,
         })
     }
 
@@ -1665,6 +1616,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         })
     }
 
@@ -2004,27 +1957,28 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         attachment: &TelegramAttachment,
     ) -> anyhow::Result<()> {
         let target = attachment.target.trim();
+        let caption = attachment.caption.as_deref();
 
         if is_http_url(target) {
             let result = match attachment.kind {
-                TelegramAttachmentKind::Image => {
-                    self.send_photo_by_url(chat_id, thread_id, target, None)
+                AttachmentKind::Image => {
+                    self.send_photo_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
-                TelegramAttachmentKind::Document => {
-                    self.send_document_by_url(chat_id, thread_id, target, None)
+                AttachmentKind::Document => {
+                    self.send_document_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
-                TelegramAttachmentKind::Video => {
-                    self.send_video_by_url(chat_id, thread_id, target, None)
+                AttachmentKind::Video => {
+                    self.send_video_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
-                TelegramAttachmentKind::Audio => {
-                    self.send_audio_by_url(chat_id, thread_id, target, None)
+                AttachmentKind::Audio => {
+                    self.send_audio_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
-                TelegramAttachmentKind::Voice => {
-                    self.send_voice_by_url(chat_id, thread_id, target, None)
+                AttachmentKind::Voice => {
+                    self.send_voice_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
             };
@@ -2039,13 +1993,16 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                     "Telegram send media by URL failed; falling back to text link"
                 );
                 let kind_label = match attachment.kind {
-                    TelegramAttachmentKind::Image => "Image",
-                    TelegramAttachmentKind::Document => "Document",
-                    TelegramAttachmentKind::Video => "Video",
-                    TelegramAttachmentKind::Audio => "Audio",
-                    TelegramAttachmentKind::Voice => "Voice",
+                    AttachmentKind::Image => "Image",
+                    AttachmentKind::Document => "Document",
+                    AttachmentKind::Video => "Video",
+                    AttachmentKind::Audio => "Audio",
+                    AttachmentKind::Voice => "Voice",
+                };
+                let fallback_text = match caption {
+                    Some(caption) => format!("{kind_label}: {caption}\n{target}"),
+                    None => format!("{kind_label}: {target}"),
                 };
-                let fallback_text = format!("{kind_label}: {target}");
                 self.send_text_chunks(&fallback_text, chat_id, thread_id)
                     .await?;
             }
@@ -2059,13 +2016,13 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         let path = resolve_workspace_attachment_path(workspace, target)?;
 
         match attachment.kind {
-            TelegramAttachmentKind::Image => self.send_photo(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Document => {
-                self.send_document(chat_id, thread_id, &path, None).await
+            AttachmentKind::Image => self.send_photo(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Document => {
+                self.send_document(chat_id, thread_id, &path, caption).await
             }
-            TelegramAttachmentKind::Video => self.send_video(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Audio => self.send_audio(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Voice => self.send_voice(chat_id, thread_id, &path, None).await,
+            AttachmentKind::Video => self.send_video(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Audio => self.send_audio(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Voice => self.send_voice(chat_id, thread_id, &path, caption).await,
         }
     }
 
@@ -2779,7 +2736,11 @@ impl Channel for TelegramChannel {
             None => (message.recipient.as_str(), None),
         };
 
-        let (text_without_markers, attachments) = parse_attachment_markers(&content);
+        let (text_without_markers, mut attachments) = parse_attachment_markers(&content);
+        attachments.splice(
+            0..0,
+            message.attachments.iter().map(TelegramAttachment::from),
+        );
 
         if !attachments.is_empty() {
             if !text_without_markers.is_empty() {
@@ -2803,6 +2764,10 @@ impl Channel for TelegramChannel {
         self.send_text_chunks(&content, chat_id, thread_id).await
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let mut offset: i64 = 0;
 
@@ -3380,9 +3345,9 @@ mod tests {
 
         assert_eq!(cleaned, "Here are files  and");
         assert_eq!(attachments.len(), 2);
-        assert_eq!(attachments[0].kind, TelegramAttachmentKind::Image);
+        assert_eq!(attachments[0].kind, AttachmentKind::Image);
         assert_eq!(attachments[0].target, "/tmp/a.png");
-        assert_eq!(attachments[1].kind, TelegramAttachmentKind::Document);
+        assert_eq!(attachments[1].kind, AttachmentKind::Document);
         assert_eq!(attachments[1].target, "https://example.com/a.pdf");
     }
 
@@ -3402,7 +3367,7 @@ mod tests {
 
         assert_eq!(cleaned, "Here it is");
         assert_eq!(attachments.len(), 1);
-        assert_eq!(attachments[0].kind, TelegramAttachmentKind::Video);
+        assert_eq!(attachments[0].kind, AttachmentKind::Video);
         assert_eq!(
             attachments[0].target,
             "/mnt/clips/Butters - What What [G4PvTrTp7Tc].mp4"
@@ -3426,7 +3391,7 @@ mod tests {
         let parsed = parse_path_only_attachment(image_path.to_string_lossy().as_ref())
             .expect("expected attachment");
 
-        assert_eq!(parsed.kind, TelegramAttachmentKind::Image);
+        assert_eq!(parsed.kind, AttachmentKind::Image);
         assert_eq!(parsed.target, image_path.to_string_lossy());
     }
 
@@ -3527,7 +3492,7 @@ mod tests {
     fn infer_attachment_kind_from_target_detects_document_extension() {
         assert_eq!(
             infer_attachment_kind_from_target("https://example.com/files/specs.pdf?download=1"),
-            Some(TelegramAttachmentKind::Document)
+            Some(AttachmentKind::Document)
         );
     }
 
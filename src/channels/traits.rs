@@ -1,3 +1,4 @@
+use super::attachments::{self, Attachment};
 use async_trait::async_trait;
 
 /// A message received from or sent to a channel
@@ -12,6 +13,19 @@ pub struct ChannelMessage {
     /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
     /// When set, replies should be posted as threaded responses.
     pub thread_ts: Option<String>,
+    /// Media received with the message. Channels may also reference it in
+    /// `content`; see [`ChannelMessage::content_with_markers`].
+    pub attachments: Vec<Attachment>,
+    /// Set by the channel when `content` is a transcript of a voice note.
+    pub transcribed_voice: bool,
+}
+
+impl ChannelMessage {
+    /// `content` with a marker appended for each attachment it does not
+    /// already reference, which is the form the agent understands.
+    pub fn content_with_markers(&self) -> String {
+        attachments::append_markers(&self.content, &self.attachments)
+    }
 }
 
 /// Message to send through a channel
@@ -22,6 +36,9 @@ pub struct SendMessage {
     pub subject: Option<String>,
     /// Platform thread identifier for threaded replies (e.g. Slack `thread_ts`).
     pub thread_ts: Option<String>,
+    /// Media to upload with the message. Only read by channels whose
+    /// [`Channel::supports_attachments`] is `true`.
+    pub attachments: Vec<Attachment>,
 }
 
 impl SendMessage {
@@ -32,6 +49,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: None,
             thread_ts: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -46,6 +64,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: Some(subject.into()),
             thread_ts: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -54,6 +73,27 @@ impl SendMessage {
         self.thread_ts = thread_ts;
         self
     }
+
+    /// Attach media to the message.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Text and attachments to deliver: markers left in `content` are parsed
+    /// out and follow the typed attachments.
+    pub fn split_attachments(&self) -> (String, Vec<Attachment>) {
+        let (text, parsed) = attachments::parse_markers(&self.content);
+        let mut all = self.attachments.clone();
+        all.extend(parsed);
+        (text, all)
+    }
+
+    /// `content` with typed attachments rendered back into markers, for
+    /// channels that parse markers themselves.
+    pub fn content_with_markers(&self) -> String {
+        attachments::append_markers(&self.content, &self.attachments)
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -65,6 +105,12 @@ pub trait Channel: Send + Sync {
     /// Send a message through this channel
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()>;
 
+    /// Whether `send` uploads [`SendMessage::attachments`]. Replies to
+    /// channels that return `false` carry their attachments as links.
+    fn supports_attachments(&self) -> bool {
+        false
+    }
+
     /// Start listening for incoming messages (long-running)
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()>;
 
@@ -176,6 +222,8 @@ mod tests {
                 channel: "dummy".into(),
                 timestamp: 123,
                 thread_ts: None,
+                attachments: Vec::new(),
+                transcribed_voice: false,
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -192,6 +240,8 @@ mod tests {
             channel: "dummy".into(),
             timestamp: 999,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         let cloned = message.clone();
@@ -6,6 +6,10 @@ use crate::config::TranscriptionConfig;
 /// Maximum upload size accepted by the Groq Whisper API (25 MB).
 const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
 
+/// Prefix for transcribed voice-note text, which tells the agent the sender
+/// spoke rather than typed.
+pub const VOICE_CONTENT_PREFIX: &str = "[Voice]";
+
 /// Map file extension to MIME type for Whisper-compatible transcription APIs.
 fn mime_for_audio(extension: &str) -> Option<&'static str> {
     match extension.to_ascii_lowercase().as_str() {
@@ -0,0 +1,551 @@
+//! Voice-note replies for channels.
+//!
+//! [`TtsService`] synthesizes a reply with the OpenAI speech API or a local
+//! command, caches the audio under `<workspace>/tts_cache` by content hash,
+//! and hands it back as a voice [`Attachment`]. The `[tts.channels]` reply
+//! mode decides per channel whether a reply gets one.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+
+use super::attachments::{self, Attachment, AttachmentKind};
+use super::traits::ChannelMessage;
+use crate::config::{TtsConfig, TtsProvider, TtsReplyMode};
+
+/// Upper bound on a single synthesis run, for both HTTP and local backends.
+const SYNTHESIS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Workspace subdirectory holding synthesized audio, keyed by content hash.
+const TTS_CACHE_DIR: &str = "tts_cache";
+
+/// Marker in the names of audio files still being synthesized.
+const PARTIAL_MARKER: &str = ".partial.";
+
+/// Remove cached audio last used more than `max_age` ago (if set), then the
+/// least recently used files until the cache fits in `max_bytes` (if set).
+/// Partial files younger than [`SYNTHESIS_TIMEOUT`] belong to a synthesis in
+/// flight and are left alone. Returns the number of files removed.
+fn prune_cache(dir: &Path, max_bytes: Option<u64>, max_age: Option<Duration>) -> Result<usize> {
+    let now = SystemTime::now();
+    let age_of = |modified: SystemTime| now.duration_since(modified).unwrap_or_default();
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified = metadata.modified()?;
+        let partial = entry.file_name().to_string_lossy().contains(PARTIAL_MARKER);
+        if partial && age_of(modified) < SYNTHESIS_TIMEOUT {
+            continue;
+        }
+        files.push((modified, metadata.len(), partial, entry.path()));
+    }
+    files.sort_by_key(|(modified, ..)| *modified);
+
+    let mut total: u64 = files.iter().map(|(_, len, ..)| len).sum();
+    let mut removed = 0;
+    for (modified, len, partial, path) in files {
+        let expired = max_age.is_some_and(|max| age_of(modified) > max);
+        let over_size = max_bytes.is_some_and(|max| total > max);
+        if !(partial || expired || over_size) {
+            continue;
+        }
+        std::fs::remove_file(&path)?;
+        total -= len;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// A speech synthesis backend.
+#[async_trait]
+pub trait TtsBackend: Send + Sync {
+    /// Identifies the voice for cache keys, so different backends, models or
+    /// voices never share cached audio.
+    fn cache_key(&self) -> String;
+
+    /// Model name to bill in `CostTracker`; `None` for local backends.
+    fn billed_model(&self) -> Option<&str>;
+
+    /// Synthesize `text` into the audio file at `output`.
+    async fn synthesize(&self, text: &str, output: &Path) -> Result<()>;
+}
+
+/// OpenAI-compatible `/audio/speech` backend.
+pub struct OpenAiTtsBackend {
+    api_url: String,
+    model: String,
+    voice: String,
+    format: String,
+}
+
+impl OpenAiTtsBackend {
+    pub fn new(config: &TtsConfig) -> Self {
+        Self {
+            api_url: config.api_url.clone(),
+            model: config.model.clone(),
+            voice: config.voice.clone(),
+            format: config.format.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsBackend for OpenAiTtsBackend {
+    fn cache_key(&self) -> String {
+        format!("openai:{}:{}:{}", self.api_url, self.model, self.voice)
+    }
+
+    fn billed_model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    async fn synthesize(&self, text: &str, output: &Path) -> Result<()> {
+        let api_key = std::env::var("TTS_API_KEY")
+            .or_else(|_| std::env::var("OPENAI_API_KEY"))
+            .context(
+                "TTS_API_KEY or OPENAI_API_KEY environment variable is not set — required for voice replies",
+            )?;
+
+        let client = crate::config::build_runtime_proxy_client("tts.openai");
+        let resp = client
+            .post(&self.api_url)
+            .bearer_auth(&api_key)
+            .timeout(SYNTHESIS_TIMEOUT)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "input": text,
+                "voice": self.voice,
+                "response_format": self.format,
+            }))
+            .send()
+            .await
+            .context("Failed to send speech synthesis request")?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            bail!("Speech API error ({status}): {sanitized}");
+        }
+
+        let audio = resp.bytes().await?;
+        tokio::fs::write(output, &audio).await?;
+        Ok(())
+    }
+}
+
+/// Local command backend (`piper`, `espeak-ng`, ...). The reply text is
+/// written to stdin; `{output}` in `args` is replaced with the audio path,
+/// and without it the command's stdout is taken as the audio.
+pub struct CommandTtsBackend {
+    command: String,
+    args: Vec<String>,
+}
+
+impl CommandTtsBackend {
+    pub fn new(command: String, args: Vec<String>) -> Self {
+        Self { command, args }
+    }
+}
+
+#[async_trait]
+impl TtsBackend for CommandTtsBackend {
+    fn cache_key(&self) -> String {
+        format!("command:{}:{}", self.command, self.args.join(" "))
+    }
+
+    fn billed_model(&self) -> Option<&str> {
+        None
+    }
+
+    async fn synthesize(&self, text: &str, output: &Path) -> Result<()> {
+        let output_str = output.to_string_lossy();
+        let writes_file = self.args.iter().any(|arg| arg.contains("{output}"));
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.replace("{output}", &output_str))
+            .collect();
+
+        let mut child = tokio::process::Command::new(&self.command)
+            .args(&args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start TTS command '{}'", self.command))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+
+        let result = tokio::time::timeout(SYNTHESIS_TIMEOUT, child.wait_with_output())
+            .await
+            .context("TTS command timed out")??;
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            bail!(
+                "TTS command '{}' failed ({}): {}",
+                self.command,
+                result.status,
+                stderr.trim()
+            );
+        }
+
+        if !writes_file {
+            tokio::fs::write(output, &result.stdout).await?;
+        }
+        if tokio::fs::metadata(output).await?.len() == 0 {
+            bail!("TTS command '{}' produced no audio", self.command);
+        }
+        Ok(())
+    }
+}
+
+/// Whether `msg` was a voice note: either it carries a voice attachment or the
+/// channel transcribed one into its text. Typed text is never a voice note,
+/// whatever it starts with.
+pub fn is_voice_message(msg: &ChannelMessage) -> bool {
+    msg.transcribed_voice
+        || msg
+            .attachments
+            .iter()
+            .any(|attachment| attachment.kind == AttachmentKind::Voice)
+}
+
+/// File extension and MIME type for a backend output format. OpenAI's `opus`
+/// output is Ogg-wrapped, which is what voice-note APIs expect.
+fn audio_file_type(format: &str) -> (&str, String) {
+    match format.to_ascii_lowercase().as_str() {
+        "opus" | "ogg" | "oga" => ("ogg", "audio/ogg".to_string()),
+        "mp3" => ("mp3", "audio/mpeg".to_string()),
+        "wav" => ("wav", "audio/wav".to_string()),
+        "aac" => ("aac", "audio/aac".to_string()),
+        "flac" => ("flac", "audio/flac".to_string()),
+        _ => (
+            format,
+            mime_guess::from_ext(format)
+                .first_or_octet_stream()
+                .to_string(),
+        ),
+    }
+}
+
+/// Voice reply synthesis with a workspace cache and cost accounting.
+pub struct TtsService {
+    config: TtsConfig,
+    backend: Box<dyn TtsBackend>,
+    cache_dir: PathBuf,
+}
+
+impl TtsService {
+    /// Build the service from `[tts]`; `None` when voice replies are disabled.
+    pub fn from_config(config: &TtsConfig, workspace_dir: &Path) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let backend: Box<dyn TtsBackend> = match config.provider {
+            TtsProvider::Openai => Box::new(OpenAiTtsBackend::new(config)),
+            TtsProvider::Command => {
+                let command = config
+                    .command
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|command| !command.is_empty())
+                    .context("[tts] provider = \"command\" requires `command`")?;
+                Box::new(CommandTtsBackend::new(
+                    command.to_string(),
+                    config.args.clone(),
+                ))
+            }
+        };
+        Ok(Some(Self::with_backend(
+            config.clone(),
+            backend,
+            workspace_dir,
+        )))
+    }
+
+    pub fn with_backend(
+        config: TtsConfig,
+        backend: Box<dyn TtsBackend>,
+        workspace_dir: &Path,
+    ) -> Self {
+        Self {
+            config,
+            backend,
+            cache_dir: workspace_dir.join(TTS_CACHE_DIR),
+        }
+    }
+
+    /// Whether the reply to `msg` should carry a voice note.
+    pub fn wants_voice_reply(&self, msg: &ChannelMessage) -> bool {
+        match self.config.reply_mode(&msg.channel) {
+            TtsReplyMode::Off => false,
+            TtsReplyMode::InKind => is_voice_message(msg),
+            TtsReplyMode::Always => true,
+        }
+    }
+
+    /// Apply the cache limits from `[tts]`. Failures are only logged.
+    async fn prune_cache(&self) {
+        let dir = self.cache_dir.clone();
+        let max_bytes = (self.config.cache_max_mb > 0)
+            .then(|| self.config.cache_max_mb.saturating_mul(1 << 20));
+        let max_age = (self.config.cache_max_age_days > 0)
+            .then(|| Duration::from_secs(self.config.cache_max_age_days.saturating_mul(86_400)));
+        match tokio::task::spawn_blocking(move || prune_cache(&dir, max_bytes, max_age)).await {
+            Ok(Ok(0)) => {}
+            Ok(Ok(removed)) => tracing::debug!("Pruned {removed} TTS cache file(s)"),
+            Ok(Err(e)) => tracing::warn!("Failed to prune TTS cache: {e:#}"),
+            Err(e) => tracing::warn!("TTS cache pruning panicked: {e}"),
+        }
+    }
+
+    /// Synthesize `reply` into a voice attachment. Media markers are not
+    /// spoken; empty or over-long replies return `None`. Audio is cached by
+    /// voice and text, and only fresh synthesis is billed.
+    pub async fn synthesize_reply(&self, reply: &str) -> Result<Option<Attachment>> {
+        let (text, _) = attachments::parse_markers(reply);
+        let characters = text.chars().count();
+        if characters == 0 || characters > self.config.max_chars {
+            return Ok(None);
+        }
+
+        let (extension, mime_type) = audio_file_type(&self.config.format);
+        let mut hasher = Sha256::new();
+        hasher.update(self.backend.cache_key().as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.config.format.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(text.as_bytes());
+        let digest = hex::encode(hasher.finalize());
+        let path = self.cache_dir.join(format!("{digest}.{extension}"));
+
+        if tokio::fs::metadata(&path).await.is_ok() {
+            // Mark the entry as recently used so pruning keeps it.
+            let used = path.clone();
+            let touched = tokio::task::spawn_blocking(move || {
+                std::fs::File::options()
+                    .append(true)
+                    .open(used)?
+                    .set_modified(SystemTime::now())
+            })
+            .await;
+            if let Ok(Err(e)) = touched {
+                tracing::debug!("Failed to refresh TTS cache entry: {e}");
+            }
+        } else {
+            tokio::fs::create_dir_all(&self.cache_dir).await?;
+            // Synthesize next to the final path so a failed run never leaves
+            // a truncated file that later hits the cache. The name is unique
+            // so concurrent syntheses of the same text do not collide.
+            let partial = self.cache_dir.join(format!(
+                "{digest}.{}{PARTIAL_MARKER}{extension}",
+                uuid::Uuid::new_v4().simple()
+            ));
+            if let Err(e) = self.backend.synthesize(&text, &partial).await {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e);
+            }
+            tokio::fs::rename(&partial, &path).await?;
+
+            if let (Some(model), Some(tracker)) =
+                (self.backend.billed_model(), crate::cost::tracker::shared())
+            {
+                if let Err(e) = tracker.record_tts_usage(model, characters as u64) {
+                    tracing::warn!("Failed to record TTS cost: {e}");
+                }
+            }
+            self.prune_cache().await;
+        }
+
+        Ok(Some(
+            Attachment::from_path(AttachmentKind::Voice, path).with_mime_type(mime_type),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingBackend {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl TtsBackend for CountingBackend {
+        fn cache_key(&self) -> String {
+            "counting".into()
+        }
+
+        fn billed_model(&self) -> Option<&str> {
+            None
+        }
+
+        async fn synthesize(&self, text: &str, output: &Path) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::fs::write(output, text.as_bytes()).await?;
+            Ok(())
+        }
+    }
+
+    fn message(channel: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: "alice".into(),
+            content: content.into(),
+            channel: channel.into(),
+            timestamp: 0,
+            thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
+        }
+    }
+
+    fn service(workspace: &Path, calls: Arc<AtomicUsize>) -> TtsService {
+        let mut config = TtsConfig {
+            enabled: true,
+            max_chars: 20,
+            ..TtsConfig::default()
+        };
+        config
+            .channels
+            .insert("telegram".into(), TtsReplyMode::InKind);
+        config
+            .channels
+            .insert("signal".into(), TtsReplyMode::Always);
+        TtsService::with_backend(config, Box::new(CountingBackend { calls }), workspace)
+    }
+
+    #[test]
+    fn voice_reply_follows_channel_mode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tts = service(tmp.path(), Arc::new(AtomicUsize::new(0)));
+
+        let mut transcribed = message("telegram", "[Voice] hello");
+        transcribed.transcribed_voice = true;
+        assert!(tts.wants_voice_reply(&transcribed));
+        assert!(!tts.wants_voice_reply(&message("telegram", "hello")));
+        assert!(
+            !tts.wants_voice_reply(&message("telegram", "[Voice] typed by hand")),
+            "typed text is not a voice note"
+        );
+        assert!(tts.wants_voice_reply(&message("signal", "hello")));
+        transcribed.channel = "discord".into();
+        assert!(!tts.wants_voice_reply(&transcribed));
+
+        let mut voice_note = message("whatsapp", "");
+        voice_note.attachments.push(Attachment::from_url(
+            AttachmentKind::Voice,
+            "https://example.com/v.ogg",
+        ));
+        assert!(is_voice_message(&voice_note));
+    }
+
+    #[tokio::test]
+    async fn synthesize_reply_caches_audio_and_skips_long_text() {
+        let tmp = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tts = service(tmp.path(), Arc::clone(&calls));
+
+        let first = tts
+            .synthesize_reply("hi [IMAGE:/tmp/x.png]")
+            .await
+            .unwrap()
+            .unwrap();
+        let second = tts.synthesize_reply("hi").await.unwrap().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.kind, AttachmentKind::Voice);
+        assert_eq!(first.mime_type.as_deref(), Some("audio/ogg"));
+        assert!(first
+            .path()
+            .unwrap()
+            .starts_with(tmp.path().join(TTS_CACHE_DIR)));
+        assert_eq!(std::fs::read(first.path().unwrap()).unwrap(), b"hi");
+
+        let long = "x".repeat(21);
+        assert!(tts.synthesize_reply(&long).await.unwrap().is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_syntheses_of_the_same_text_do_not_collide() {
+        let tmp = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tts = service(tmp.path(), Arc::clone(&calls));
+
+        let (a, b) = tokio::join!(tts.synthesize_reply("hello"), tts.synthesize_reply("hello"));
+        let (a, b) = (a.unwrap().unwrap(), b.unwrap().unwrap());
+
+        assert_eq!(a, b);
+        assert_eq!(std::fs::read(a.path().unwrap()).unwrap(), b"hello");
+        let leftovers = std::fs::read_dir(tmp.path().join(TTS_CACHE_DIR))
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .contains(PARTIAL_MARKER)
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn prune_cache_drops_expired_then_least_recently_used_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let day = Duration::from_secs(86_400);
+        let write = |name: &str, age: Duration| {
+            let path = tmp.path().join(name);
+            std::fs::write(&path, [0u8; 10]).unwrap();
+            std::fs::File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - age)
+                .unwrap();
+        };
+        write("expired.opus", day * 40);
+        write("old.opus", day * 3);
+        write("recent.opus", day);
+        write("new.opus", Duration::ZERO);
+        write("inflight.partial.opus", Duration::ZERO);
+
+        let removed = prune_cache(tmp.path(), Some(20), Some(day * 30)).unwrap();
+
+        assert_eq!(removed, 2);
+        for name in ["recent.opus", "new.opus", "inflight.partial.opus"] {
+            assert!(tmp.path().join(name).exists(), "{name} should be kept");
+        }
+    }
+
+    #[test]
+    fn command_provider_requires_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = TtsConfig {
+            enabled: true,
+            provider: TtsProvider::Command,
+            ..TtsConfig::default()
+        };
+        assert!(TtsService::from_config(&config, tmp.path()).is_err());
+        assert!(TtsService::from_config(&TtsConfig::default(), tmp.path())
+            .unwrap()
+            .is_none());
+    }
+}
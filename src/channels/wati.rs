@@ -163,6 +163,8 @@ impl WatiChannel {
             channel: "wati".to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         });
 
         messages
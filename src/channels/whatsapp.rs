@@ -1,7 +1,11 @@
+use super::attachments::{self, Attachment, AttachmentKind, AttachmentSource};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use std::path::PathBuf;
 use uuid::Uuid;
 
+const GRAPH_API_BASE: &str = "https://graph.facebook.com/v18.0";
+
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -27,6 +31,7 @@ pub struct WhatsAppChannel {
     endpoint_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl WhatsAppChannel {
@@ -41,9 +46,17 @@ impl WhatsAppChannel {
             endpoint_id,
             verify_token,
             allowed_numbers,
+            workspace_dir: None,
         }
     }
 
+    /// Configure the workspace used to store downloaded media and to
+    /// validate local upload paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.whatsapp")
     }
@@ -105,20 +118,25 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
+                    // Text messages carry a body; media messages carry a
+                    // Graph media id and an optional caption.
+                    let mut media = Vec::new();
                     let content = if let Some(text_obj) = msg.get("text") {
                         text_obj
                             .get("body")
                             .and_then(|b| b.as_str())
                             .unwrap_or("")
                             .to_string()
+                    } else if let Some(attachment) = Self::parse_media(msg) {
+                        let caption = attachment.caption.clone().unwrap_or_default();
+                        media.push(attachment);
+                        caption
                     } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
+                        tracing::debug!("WhatsApp: skipping unsupported message from {from}");
                         continue;
                     };
 
-                    if content.is_empty() {
+                    if content.is_empty() && media.is_empty() {
                         continue;
                     }
 
@@ -142,6 +160,8 @@ impl WhatsAppChannel {
                         channel: "whatsapp".to_string(),
                         timestamp,
                         thread_ts: None,
+                        attachments: media,
+                        transcribed_voice: false,
                     });
                 }
             }
@@ -149,37 +169,197 @@ impl WhatsAppChannel {
 
         messages
     }
-}
 
-#[async_trait]
-impl Channel for WhatsAppChannel {
-    fn name(&self) -> &str {
-        "whatsapp"
+    /// Map an image/document/audio/video message to an attachment that
+    /// points at its Graph API media object.
+    fn parse_media(msg: &serde_json::Value) -> Option<Attachment> {
+        let (field, kind) = match msg.get("type").and_then(|t| t.as_str())? {
+            "image" => ("image", AttachmentKind::Image),
+            "document" => ("document", AttachmentKind::Document),
+            "video" => ("video", AttachmentKind::Video),
+            "audio" => ("audio", AttachmentKind::Audio),
+            _ => return None,
+        };
+        let media = msg.get(field)?;
+        let id = media.get("id").and_then(|i| i.as_str())?;
+        let kind = if media.get("voice").and_then(serde_json::Value::as_bool) == Some(true) {
+            AttachmentKind::Voice
+        } else {
+            kind
+        };
+
+        let mut attachment = Attachment::from_url(kind, format!("{GRAPH_API_BASE}/{id}"));
+        if let Some(mime_type) = media.get("mime_type").and_then(|m| m.as_str()) {
+            attachment = attachment.with_mime_type(mime_type);
+        }
+        if let Some(caption) = media.get("caption").and_then(|c| c.as_str()) {
+            attachment = attachment.with_caption(caption);
+        }
+        Some(attachment)
     }
 
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.endpoint_id
-        );
+    /// Download Graph API media referenced by `msg` into
+    /// `<workspace>/whatsapp_files/`. Media that cannot be fetched keeps its
+    /// Graph URL so the agent still sees that something was sent.
+    pub async fn download_media(&self, msg: &mut ChannelMessage) {
+        let Some(workspace) = self.workspace_dir.clone() else {
+            return;
+        };
+        for attachment in &mut msg.attachments {
+            let Some(media_url) = attachment.url().map(str::to_string) else {
+                continue;
+            };
+            let Some(media_id) = media_url
+                .strip_prefix(GRAPH_API_BASE)
+                .and_then(|rest| rest.strip_prefix('/'))
+            else {
+                continue;
+            };
+            match self.fetch_media(&workspace, media_id, attachment).await {
+                Ok(path) => attachment.source = AttachmentSource::Path(path),
+                Err(e) => tracing::warn!("WhatsApp: failed to download media {media_id}: {e}"),
+            }
+        }
+    }
 
-        // Normalize recipient (remove leading + if present for API)
-        let to = message
-            .recipient
-            .strip_prefix('+')
-            .unwrap_or(&message.recipient);
+    async fn fetch_media(
+        &self,
+        workspace: &std::path::Path,
+        media_id: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<PathBuf> {
+        // The media object returns a short-lived download URL that still
+        // requires the access token.
+        let meta: serde_json::Value = self
+            .http_client()
+            .get(format!("{GRAPH_API_BASE}/{media_id}"))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let url = meta
+            .get("url")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("media object has no download url"))?;
+        ensure_https(url)?;
+
+        let bytes = self
+            .http_client()
+            .get(url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        let mime_type = meta
+            .get("mime_type")
+            .and_then(|m| m.as_str())
+            .or(attachment.mime_type.as_deref())
+            .unwrap_or("application/octet-stream");
+        let extension = mime_guess::get_mime_extensions_str(mime_type)
+            .and_then(|exts| exts.first())
+            .unwrap_or(&"bin");
+        attachments::save_inbound(
+            workspace,
+            "whatsapp_files",
+            &format!("{media_id}.{extension}"),
+            &bytes,
+        )
+        .await
+    }
 
-        let body = serde_json::json!({
+    /// Upload a local file to the Graph API and return its media id.
+    async fn upload_media(&self, attachment: &Attachment) -> anyhow::Result<String> {
+        let path = attachment
+            .path()
+            .ok_or_else(|| anyhow::anyhow!("attachment is not a local file"))?;
+        let workspace = self
+            .workspace_dir
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("workspace_dir is not configured"))?;
+        let path = attachments::resolve_workspace_path(workspace, &path.to_string_lossy())?;
+        let bytes = tokio::fs::read(&path).await?;
+        let mime_type = attachment.effective_mime_type();
+        let file_name = attachment.file_name().unwrap_or_else(|| "file".to_string());
+
+        let form = reqwest::multipart::Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime_type.clone())
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(bytes)
+                    .file_name(file_name)
+                    .mime_str(&mime_type)?,
+            );
+
+        let url = format!("{GRAPH_API_BASE}/{}/media", self.endpoint_id);
+        ensure_https(&url)?;
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&error_body);
+            tracing::error!("WhatsApp media upload failed: {status} — {sanitized}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+        let body: serde_json::Value = resp.json().await?;
+        body.get("id")
+            .and_then(|i| i.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media upload returned no id"))
+    }
+
+    /// Build the media message body for an attachment, uploading local files.
+    async fn media_body(
+        &self,
+        to: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<serde_json::Value> {
+        let media_type = match attachment.kind {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Video => "video",
+            AttachmentKind::Audio | AttachmentKind::Voice => "audio",
+            AttachmentKind::Document => "document",
+        };
+        let mut media = match attachment.url() {
+            Some(url) => serde_json::json!({ "link": url }),
+            None => serde_json::json!({ "id": self.upload_media(attachment).await? }),
+        };
+        // Audio messages do not accept captions.
+        if media_type != "audio" {
+            if let Some(caption) = &attachment.caption {
+                media["caption"] = serde_json::json!(caption);
+            }
+        }
+        if media_type == "document" {
+            if let Some(name) = attachment.file_name() {
+                media["filename"] = serde_json::json!(name);
+            }
+        }
+
+        let mut body = serde_json::json!({
             "messaging_product": "whatsapp",
             "recipient_type": "individual",
             "to": to,
-            "type": "text",
-            "text": {
-                "preview_url": false,
-                "body": message.content
-            }
+            "type": media_type,
         });
+        body[media_type] = media;
+        Ok(body)
+    }
+
+    async fn post_message(&self, body: &serde_json::Value) -> anyhow::Result<()> {
+        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
+        let url = format!("{GRAPH_API_BASE}/{}/messages", self.endpoint_id);
 
         ensure_https(&url)?;
 
@@ -188,7 +368,7 @@ impl Channel for WhatsAppChannel {
             .post(&url)
             .bearer_auth(&self.access_token)
             .header("Content-Type", "application/json")
-            .json(&body)
+            .json(body)
             .send()
             .await?;
 
@@ -202,6 +382,69 @@ impl Channel for WhatsAppChannel {
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl Channel for WhatsAppChannel {
+    fn name(&self) -> &str {
+        "whatsapp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        // Normalize recipient (remove leading + if present for API)
+        let to = message
+            .recipient
+            .strip_prefix('+')
+            .unwrap_or(&message.recipient);
+
+        let (text, outgoing) = message.split_attachments();
+
+        if !text.is_empty() || outgoing.is_empty() {
+            let body = serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "text",
+                "text": {
+                    "preview_url": false,
+                    "body": if outgoing.is_empty() { &message.content } else { &text }
+                }
+            });
+            self.post_message(&body).await?;
+        }
+
+        let mut failed = Vec::new();
+        for attachment in outgoing {
+            let sent = match self.media_body(to, &attachment).await {
+                Ok(body) => self.post_message(&body).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = sent {
+                tracing::warn!("WhatsApp: attachment send failed, falling back to link: {e}");
+                failed.push(attachment);
+            }
+        }
+
+        if !failed.is_empty() {
+            let body = serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "text",
+                "text": {
+                    "preview_url": false,
+                    "body": attachments::append_links("", &failed)
+                }
+            });
+            self.post_message(&body).await?;
+        }
+
+        Ok(())
+    }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
 
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         // WhatsApp uses webhooks (push-based), not polling.
@@ -220,7 +463,7 @@ impl Channel for WhatsAppChannel {
 
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
-        let url = format!("https://graph.facebook.com/v18.0/{}", self.endpoint_id);
+        let url = format!("{GRAPH_API_BASE}/{}", self.endpoint_id);
 
         if ensure_https(&url).is_err() {
             return false;
@@ -350,7 +593,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_non_text_message_skipped() {
+    fn whatsapp_parse_image_message_as_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -360,7 +603,7 @@ mod tests {
                             "from": "1234567890",
                             "timestamp": "1699999999",
                             "type": "image",
-                            "image": { "id": "img123" }
+                            "image": { "id": "img123", "mime_type": "image/jpeg", "caption": "look" }
                         }]
                     }
                 }]
@@ -368,7 +611,12 @@ mod tests {
         });
 
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty(), "Non-text messages should be skipped");
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "look");
+        let media = &msgs[0].attachments[0];
+        assert_eq!(media.kind, AttachmentKind::Image);
+        assert_eq!(media.url(), Some("https://graph.facebook.com/v18.0/img123"));
+        assert_eq!(media.mime_type.as_deref(), Some("image/jpeg"));
     }
 
     #[test]
@@ -737,7 +985,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_audio_message_skipped() {
+    fn whatsapp_parse_audio_message_as_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -754,11 +1002,16 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].content.is_empty());
+        assert_eq!(msgs[0].attachments[0].kind, AttachmentKind::Audio);
+        assert!(msgs[0].attachments[0]
+            .url()
+            .is_some_and(|url| url.ends_with("/audio123")));
     }
 
     #[test]
-    fn whatsapp_parse_video_message_skipped() {
+    fn whatsapp_parse_video_message_as_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -775,11 +1028,16 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].content.is_empty());
+        assert_eq!(msgs[0].attachments[0].kind, AttachmentKind::Video);
+        assert!(msgs[0].attachments[0]
+            .url()
+            .is_some_and(|url| url.ends_with("/video123")));
     }
 
     #[test]
-    fn whatsapp_parse_document_message_skipped() {
+    fn whatsapp_parse_document_message_as_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -796,7 +1054,12 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].content.is_empty());
+        assert_eq!(msgs[0].attachments[0].kind, AttachmentKind::Document);
+        assert!(msgs[0].attachments[0]
+            .url()
+            .is_some_and(|url| url.ends_with("/doc123")));
     }
 
     #[test]
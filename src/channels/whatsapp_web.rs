@@ -485,8 +485,10 @@ impl Channel for WhatsAppWebChannel {
 
         let to = self.recipient_to_jid(&message.recipient)?;
 
-        // Parse media attachment markers from the response text.
-        let (text_without_markers, attachments) = parse_wa_attachment_markers(&message.content);
+        // Parse media attachment markers from the response text; typed
+        // attachments are rendered as markers so both paths share one parser.
+        let (text_without_markers, attachments) =
+            parse_wa_attachment_markers(&message.content_with_markers());
 
         // Send any text portion first.
         if !text_without_markers.is_empty() {
@@ -540,6 +542,10 @@ impl Channel for WhatsAppWebChannel {
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
         // Store the sender channel for incoming messages
         *self.tx.lock() = Some(tx.clone());
@@ -651,6 +657,8 @@ impl Channel for WhatsAppWebChannel {
                                         content: trimmed.to_string(),
                                         timestamp: chrono::Utc::now().timestamp() as u64,
                                         thread_ts: None,
+                                        attachments: Vec::new(),
+                                        transcribed_voice: false,
                                     })
                                     .await
                                 {
@@ -0,0 +1,1550 @@
+//! XMPP channel over a direct client-to-server stream (RFC 6120/6121).
+//!
+//! Handles 1:1 chats and multi-user chat rooms (XEP-0045, without OMEMO),
+//! chat state notifications (XEP-0085) for typing, and server pings
+//! (XEP-0199). Stanzas are read with a small streaming parser: XMPP only
+//! needs elements, attributes and text, not a general XML library.
+
+use crate::channels::attachments::{Attachment, AttachmentKind};
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::XmppTlsMode;
+use async_trait::async_trait;
+use base64::Engine;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, Mutex};
+use tokio_rustls::rustls;
+
+const NS_CLIENT: &str = "jabber:client";
+const NS_STREAM: &str = "http://etherx.jabber.org/streams";
+const NS_TLS: &str = "urn:ietf:params:xml:ns:xmpp-tls";
+const NS_SASL: &str = "urn:ietf:params:xml:ns:xmpp-sasl";
+const NS_BIND: &str = "urn:ietf:params:xml:ns:xmpp-bind";
+const NS_STANZAS: &str = "urn:ietf:params:xml:ns:xmpp-stanzas";
+const NS_MUC: &str = "http://jabber.org/protocol/muc";
+const NS_CHAT_STATES: &str = "http://jabber.org/protocol/chatstates";
+const NS_PING: &str = "urn:xmpp:ping";
+const NS_OOB: &str = "jabber:x:oob";
+
+/// Drop the connection when the server has been silent this long.
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Interval between whitespace keepalives (RFC 6120 §4.6.1).
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+const INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(120);
+
+/// Upper bound on a single buffered stanza.
+const MAX_STANZA_BYTES: usize = 1024 * 1024;
+
+/// Maximum element nesting accepted from the server.
+const MAX_DEPTH: usize = 32;
+
+/// Any byte stream the session can run over: plain TCP or TLS.
+trait XmppIo: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> XmppIo for T {}
+
+type WriteHalf = tokio::io::WriteHalf<Box<dyn XmppIo>>;
+
+/// XMPP channel settings, resolved from `[channels_config.xmpp]`.
+pub struct XmppChannelConfig {
+    pub jid: String,
+    pub password: String,
+    pub server: Option<String>,
+    pub port: Option<u16>,
+    pub tls: XmppTlsMode,
+    pub verify_tls: bool,
+    pub resource: String,
+    pub nickname: Option<String>,
+    pub rooms: Vec<String>,
+    pub allowed_users: Vec<String>,
+    pub mention_only: bool,
+    pub group_reply_allowed_sender_ids: Vec<String>,
+}
+
+/// XMPP client channel.
+pub struct XmppChannel {
+    bare_jid: String,
+    localpart: String,
+    domain: String,
+    password: String,
+    host: String,
+    port: u16,
+    tls: XmppTlsMode,
+    verify_tls: bool,
+    resource: String,
+    nickname: String,
+    rooms: Vec<String>,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+    writer: Arc<Mutex<Option<WriteHalf>>>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl XmppChannel {
+    pub fn new(cfg: XmppChannelConfig) -> Self {
+        let (bare_jid, _) = split_jid(cfg.jid.trim());
+        let (localpart, domain) = bare_jid.split_once('@').unwrap_or(("", bare_jid));
+        let (localpart, domain) = (localpart.to_string(), domain.to_ascii_lowercase());
+        let host = cfg
+            .server
+            .map(|server| server.trim().to_string())
+            .filter(|server| !server.is_empty())
+            .unwrap_or_else(|| domain.clone());
+        let port = cfg.port.unwrap_or(match cfg.tls {
+            XmppTlsMode::Direct => 5223,
+            XmppTlsMode::Starttls | XmppTlsMode::None => 5222,
+        });
+        let nickname = cfg
+            .nickname
+            .map(|nick| nick.trim().to_string())
+            .filter(|nick| !nick.is_empty())
+            .unwrap_or_else(|| localpart.clone());
+        let rooms = cfg
+            .rooms
+            .iter()
+            .map(|room| split_jid(room.trim()).0.to_ascii_lowercase())
+            .filter(|room| !room.is_empty())
+            .collect();
+        let mut group_reply_allowed_sender_ids: Vec<String> = Vec::new();
+        for id in cfg.group_reply_allowed_sender_ids {
+            let id = id.trim();
+            if !id.is_empty() && !group_reply_allowed_sender_ids.iter().any(|e| e == id) {
+                group_reply_allowed_sender_ids.push(id.to_string());
+            }
+        }
+
+        Self {
+            bare_jid: bare_jid.to_string(),
+            localpart,
+            domain,
+            password: cfg.password,
+            host,
+            port,
+            tls: cfg.tls,
+            verify_tls: cfg.verify_tls,
+            resource: cfg.resource,
+            nickname,
+            rooms,
+            allowed_users: cfg.allowed_users,
+            mention_only: cfg.mention_only,
+            group_reply_allowed_sender_ids,
+            writer: Arc::new(Mutex::new(None)),
+            initial_backoff: INITIAL_RECONNECT_BACKOFF,
+            max_backoff: MAX_RECONNECT_BACKOFF,
+        }
+    }
+
+    fn is_user_allowed(&self, id: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(id))
+    }
+
+    /// Whether a room occupant may trigger replies without a mention.
+    fn is_group_sender_trigger_enabled(&self, nick: &str, occupant: &str) -> bool {
+        self.group_reply_allowed_sender_ids.iter().any(|id| {
+            id == "*" || id.eq_ignore_ascii_case(nick) || id.eq_ignore_ascii_case(occupant)
+        })
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        let (bare, _) = split_jid(jid.trim());
+        self.rooms
+            .iter()
+            .any(|room| room.eq_ignore_ascii_case(bare))
+    }
+
+    fn message_type(&self, to: &str) -> &'static str {
+        if self.is_room(to) {
+            "groupchat"
+        } else {
+            "chat"
+        }
+    }
+
+    /// Open the transport, upgrading via STARTTLS when configured.
+    async fn connect(&self) -> anyhow::Result<Box<dyn XmppIo>> {
+        let tcp = tokio::net::TcpStream::connect((self.host.as_str(), self.port)).await?;
+
+        match self.tls {
+            XmppTlsMode::Direct => Ok(Box::new(self.wrap_tls(tcp).await?)),
+            XmppTlsMode::Starttls => {
+                let mut tcp = tcp;
+                let mut reader = StanzaReader::default();
+                let features = open_stream(&mut tcp, &mut reader, &self.domain).await?;
+                if features.child("starttls").is_none() {
+                    anyhow::bail!("XMPP server {} does not offer STARTTLS", self.host);
+                }
+                write_str(&mut tcp, &format!("<starttls xmlns='{NS_TLS}'/>")).await?;
+                let reply = read_stanza(&mut tcp, &mut reader).await?;
+                if reply.local_name() != "proceed" {
+                    anyhow::bail!("XMPP server refused STARTTLS");
+                }
+                Ok(Box::new(self.wrap_tls(tcp).await?))
+            }
+            XmppTlsMode::None => {
+                if !is_loopback_host(&self.host) {
+                    anyhow::bail!(
+                        "XMPP tls = \"none\" is only allowed for loopback servers, not {}",
+                        self.host
+                    );
+                }
+                Ok(Box::new(tcp))
+            }
+        }
+    }
+
+    /// TLS handshake; the certificate is checked against the JID domain.
+    async fn wrap_tls(
+        &self,
+        tcp: tokio::net::TcpStream,
+    ) -> anyhow::Result<tokio_rustls::client::TlsStream<tokio::net::TcpStream>> {
+        let tls_config = if self.verify_tls {
+            let root_store: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            rustls::ClientConfig::builder()
+                .with_root_certificates(root_store)
+                .with_no_client_auth()
+        } else {
+            rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(super::irc::NoVerify))
+                .with_no_client_auth()
+        };
+
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+        let domain = rustls::pki_types::ServerName::try_from(self.domain.clone())?;
+        Ok(connector.connect(domain, tcp).await?)
+    }
+
+    /// Authenticate with SASL PLAIN and bind a resource. Returns the bound
+    /// full JID and the reader holding anything buffered after it.
+    async fn establish(&self, io: &mut Box<dyn XmppIo>) -> anyhow::Result<(String, StanzaReader)> {
+        let mut reader = StanzaReader::default();
+        let features = open_stream(io, &mut reader, &self.domain).await?;
+        let offers_plain = features.child("mechanisms").is_some_and(|mechanisms| {
+            mechanisms.children.iter().any(|mechanism| {
+                mechanism.local_name() == "mechanism"
+                    && mechanism.text.trim().eq_ignore_ascii_case("PLAIN")
+            })
+        });
+        if !offers_plain {
+            anyhow::bail!("XMPP server does not offer SASL PLAIN");
+        }
+
+        let auth = XmlElement::new("auth")
+            .with_attr("xmlns", NS_SASL)
+            .with_attr("mechanism", "PLAIN")
+            .with_text(&encode_sasl_plain(&self.localpart, &self.password));
+        write_str(io, &auth.to_xml()).await?;
+        let reply = read_stanza(io, &mut reader).await?;
+        match reply.local_name() {
+            "success" => {}
+            "failure" => anyhow::bail!(
+                "XMPP authentication failed: {}",
+                reply
+                    .children
+                    .first()
+                    .map_or("unknown", XmlElement::local_name)
+            ),
+            other => anyhow::bail!("unexpected SASL reply <{other}>"),
+        }
+
+        let features = open_stream(io, &mut reader, &self.domain).await?;
+        if features.child("bind").is_none() {
+            anyhow::bail!("XMPP server does not offer resource binding");
+        }
+        let bind_id = new_stanza_id();
+        let bind = XmlElement::new("iq")
+            .with_attr("type", "set")
+            .with_attr("id", &bind_id)
+            .with_child(
+                XmlElement::new("bind")
+                    .with_attr("xmlns", NS_BIND)
+                    .with_child(XmlElement::new("resource").with_text(&self.resource)),
+            );
+        write_str(io, &bind.to_xml()).await?;
+
+        loop {
+            let stanza = read_stanza(io, &mut reader).await?;
+            if stanza.local_name() != "iq" || stanza.attr("id") != Some(bind_id.as_str()) {
+                continue;
+            }
+            if stanza.attr("type") != Some("result") {
+                anyhow::bail!("XMPP resource binding failed");
+            }
+            let jid = stanza
+                .child("bind")
+                .and_then(|bind| bind.child("jid"))
+                .map(|jid| jid.text.trim().to_string())
+                .filter(|jid| !jid.is_empty())
+                .unwrap_or_else(|| format!("{}/{}", self.bare_jid, self.resource));
+            return Ok((jid, reader));
+        }
+    }
+
+    fn join_room_stanza(&self, room: &str) -> XmlElement {
+        XmlElement::new("presence")
+            .with_attr("to", &format!("{room}/{}", self.nickname))
+            .with_child(
+                XmlElement::new("x")
+                    .with_attr("xmlns", NS_MUC)
+                    .with_child(XmlElement::new("history").with_attr("maxstanzas", "0")),
+            )
+    }
+
+    fn message_stanza(&self, to: &str, body: &str) -> XmlElement {
+        XmlElement::new("message")
+            .with_attr("to", to)
+            .with_attr("type", self.message_type(to))
+            .with_attr("id", &new_stanza_id())
+            .with_child(XmlElement::new("body").with_text(body))
+            .with_child(XmlElement::new("active").with_attr("xmlns", NS_CHAT_STATES))
+    }
+
+    fn chat_state_stanza(&self, to: &str, state: &str) -> XmlElement {
+        XmlElement::new("message")
+            .with_attr("to", to)
+            .with_attr("type", self.message_type(to))
+            .with_child(XmlElement::new(state).with_attr("xmlns", NS_CHAT_STATES))
+    }
+
+    async fn write_stanza(&self, stanza: &XmlElement) -> anyhow::Result<()> {
+        self.write_raw(&stanza.to_xml()).await
+    }
+
+    async fn write_raw(&self, data: &str) -> anyhow::Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XMPP not connected"))?;
+        write_str(writer, data).await
+    }
+
+    /// Turn an inbound `<message>` into a channel message, applying the
+    /// allowlist and, in rooms, the mention policy.
+    fn parse_message(&self, stanza: &XmlElement) -> Option<ChannelMessage> {
+        let from = stanza.attr("from")?;
+        let (bare, resource) = split_jid(from);
+        let body = stanza
+            .child("body")
+            .map(|body| body.text.trim().to_string())
+            .unwrap_or_default();
+        let attachments = inbound_attachments(stanza);
+        if body.is_empty() && attachments.is_empty() {
+            // Chat states, receipts and other payload-only messages.
+            return None;
+        }
+
+        let (sender, reply_target, content) = match stanza.attr("type").unwrap_or("normal") {
+            "groupchat" => {
+                let nick = resource.filter(|nick| !nick.is_empty())?;
+                if !self.is_room(bare)
+                    || nick.eq_ignore_ascii_case(&self.nickname)
+                    || stanza.child("delay").is_some()
+                {
+                    // Unknown rooms, our own echo and joined-room history.
+                    return None;
+                }
+                let occupant = format!("{bare}/{nick}");
+                if !self.is_user_allowed(nick) && !self.is_user_allowed(&occupant) {
+                    tracing::debug!("XMPP: ignoring message from unauthorized occupant {occupant}");
+                    return None;
+                }
+                let content = match strip_mention(&body, &self.nickname) {
+                    Some(content) => content,
+                    None if self.mention_only
+                        && !self.is_group_sender_trigger_enabled(nick, &occupant) =>
+                    {
+                        return None;
+                    }
+                    None => body,
+                };
+                (occupant, bare.to_string(), content)
+            }
+            "chat" | "normal" => {
+                if !self.is_user_allowed(bare) {
+                    tracing::debug!("XMPP: ignoring message from unauthorized user {bare}");
+                    return None;
+                }
+                (bare.to_string(), bare.to_string(), body)
+            }
+            _ => return None,
+        };
+        if content.is_empty() && attachments.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: stanza.attr("id").map_or_else(new_stanza_id, str::to_string),
+            sender,
+            reply_target,
+            content,
+            channel: "xmpp".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            attachments,
+            transcribed_voice: false,
+        })
+    }
+
+    /// Answer pings and refuse other requests, as RFC 6120 requires.
+    async fn answer_iq(&self, stanza: &XmlElement) -> anyhow::Result<()> {
+        let Some(kind @ ("get" | "set")) = stanza.attr("type") else {
+            return Ok(());
+        };
+        let mut reply =
+            XmlElement::new("iq").with_attr("id", stanza.attr("id").unwrap_or_default());
+        if let Some(from) = stanza.attr("from") {
+            reply = reply.with_attr("to", from);
+        }
+        let is_ping = stanza
+            .child("ping")
+            .is_some_and(|ping| ping.attr("xmlns") == Some(NS_PING));
+        reply = if is_ping && kind == "get" {
+            reply.with_attr("type", "result")
+        } else {
+            reply.with_attr("type", "error").with_child(
+                XmlElement::new("error")
+                    .with_attr("type", "cancel")
+                    .with_child(
+                        XmlElement::new("service-unavailable").with_attr("xmlns", NS_STANZAS),
+                    ),
+            )
+        };
+        self.write_stanza(&reply).await
+    }
+
+    /// Handle one stanza; returns `false` once the receiver is gone.
+    async fn handle_stanza(
+        &self,
+        stanza: &XmlElement,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<bool> {
+        if stanza.name == "stream:error" {
+            anyhow::bail!("XMPP stream error: {}", stream_error_condition(stanza));
+        }
+        match stanza.local_name() {
+            "message" => {
+                if let Some(msg) = self.parse_message(stanza) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(false);
+                    }
+                }
+            }
+            "iq" => self.answer_iq(stanza).await?,
+            "presence" if stanza.attr("type") == Some("error") => {
+                tracing::warn!(
+                    "XMPP presence error from {}: {}",
+                    stanza.attr("from").unwrap_or_default(),
+                    stream_error_condition(stanza)
+                );
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// One connection: log in, join rooms and relay stanzas until the
+    /// stream fails or the receiver is dropped. `online` is set once the
+    /// session is fully established.
+    async fn run_session(
+        &self,
+        tx: &mpsc::Sender<ChannelMessage>,
+        online: &mut bool,
+    ) -> anyhow::Result<()> {
+        if self.localpart.is_empty() || self.domain.is_empty() {
+            anyhow::bail!("invalid XMPP JID {:?}; expected user@domain", self.bare_jid);
+        }
+        tracing::info!(
+            "XMPP channel connecting to {}:{} as {}...",
+            self.host,
+            self.port,
+            self.bare_jid
+        );
+
+        let mut io = self.connect().await?;
+        let (bound_jid, mut reader) = self.establish(&mut io).await?;
+        let (mut read_half, write_half) = tokio::io::split(io);
+        *self.writer.lock().await = Some(write_half);
+
+        self.write_stanza(&XmlElement::new("presence")).await?;
+        for room in &self.rooms {
+            self.write_stanza(&self.join_room_stanza(room)).await?;
+        }
+        *online = true;
+        tracing::info!(
+            "XMPP channel online as {bound_jid} ({} room(s))",
+            self.rooms.len()
+        );
+
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await;
+        let mut last_read = Instant::now();
+        let mut buf = vec![0u8; 8192];
+
+        loop {
+            while let Some(event) = reader.next_event()? {
+                match event {
+                    StreamEvent::Stanza(stanza) => {
+                        if !self.handle_stanza(&stanza, tx).await? {
+                            return Ok(());
+                        }
+                    }
+                    StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+                    StreamEvent::Open(_) => {}
+                }
+            }
+
+            tokio::select! {
+                _ = keepalive.tick() => {
+                    if last_read.elapsed() > READ_TIMEOUT {
+                        anyhow::bail!("XMPP read timed out (no data for {READ_TIMEOUT:?})");
+                    }
+                    self.write_raw(" ").await?;
+                }
+                read = read_half.read(&mut buf) => {
+                    let n = read?;
+                    if n == 0 {
+                        anyhow::bail!("XMPP connection closed by server");
+                    }
+                    last_read = Instant::now();
+                    reader.push(&buf[..n])?;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for XmppChannel {
+    fn name(&self) -> &str {
+        "xmpp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let to = split_jid(message.recipient.trim()).0;
+        self.write_stanza(&self.message_stanza(to, &message.content))
+            .await
+    }
+
+    /// Keeps a session up, reconnecting with exponential backoff. The
+    /// backoff resets once a session gets online again.
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let mut backoff = self.initial_backoff;
+        loop {
+            let mut online = false;
+            let result = tokio::select! {
+                () = tx.closed() => Ok(()),
+                result = self.run_session(&tx, &mut online) => result,
+            };
+            *self.writer.lock().await = None;
+            if tx.is_closed() {
+                return Ok(());
+            }
+            if let Err(e) = result {
+                if online {
+                    backoff = self.initial_backoff;
+                }
+                tracing::warn!("XMPP connection lost: {e}; reconnecting in {backoff:?}");
+            }
+            tokio::select! {
+                () = tx.closed() => return Ok(()),
+                () = tokio::time::sleep(backoff) => {}
+            }
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.writer.lock().await.is_some()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        let to = split_jid(recipient.trim()).0;
+        self.write_stanza(&self.chat_state_stanza(to, "composing"))
+            .await
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        let to = split_jid(recipient.trim()).0;
+        self.write_stanza(&self.chat_state_stanza(to, "active"))
+            .await
+    }
+}
+
+/// Split a JID into its bare part and optional resource.
+fn split_jid(jid: &str) -> (&str, Option<&str>) {
+    match jid.split_once('/') {
+        Some((bare, resource)) => (bare, Some(resource)),
+        None => (jid, None),
+    }
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+fn new_stanza_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// SASL PLAIN payload: `\0authcid\0password`, base64-encoded.
+fn encode_sasl_plain(username: &str, password: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("\0{username}\0{password}"))
+}
+
+/// Name of the first defined condition in a stream or stanza error.
+fn stream_error_condition(stanza: &XmlElement) -> String {
+    let error = if stanza.local_name() == "error" {
+        Some(stanza)
+    } else {
+        stanza.child("error")
+    };
+    error
+        .and_then(|error| error.children.iter().find(|c| c.local_name() != "text"))
+        .map_or_else(|| "unknown".to_string(), |c| c.local_name().to_string())
+}
+
+/// Content of a room message addressed to `nickname`, or `None` when it
+/// does not mention it. A leading `nick:` or `nick,` is stripped.
+fn strip_mention(body: &str, nickname: &str) -> Option<String> {
+    let nick = nickname.trim().to_ascii_lowercase();
+    if nick.is_empty() {
+        return None;
+    }
+    let lower = body.to_ascii_lowercase();
+    let is_boundary =
+        |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != '_' && c != '-');
+
+    let mut search = 0;
+    while let Some(offset) = lower[search..].find(&nick) {
+        let start = search + offset;
+        let end = start + nick.len();
+        if is_boundary(body[..start].chars().next_back()) && is_boundary(body[end..].chars().next())
+        {
+            if body[..start].trim().is_empty() {
+                let rest = body[end..].trim_start();
+                let rest = rest.strip_prefix([':', ',']).unwrap_or(rest);
+                return Some(rest.trim().to_string());
+            }
+            return Some(body.trim().to_string());
+        }
+        search = end;
+    }
+    None
+}
+
+/// Out-of-band file links (XEP-0066) carried by a message.
+fn inbound_attachments(stanza: &XmlElement) -> Vec<Attachment> {
+    stanza
+        .children
+        .iter()
+        .filter(|child| child.local_name() == "x" && child.attr("xmlns") == Some(NS_OOB))
+        .filter_map(|oob| {
+            let url = oob.child("url")?.text.trim();
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return None;
+            }
+            let mut attachment = Attachment::from_url(AttachmentKind::Document, url);
+            attachment.kind = AttachmentKind::from_mime_type(&attachment.effective_mime_type());
+            if let Some(desc) = oob.child("desc").map(|d| d.text.trim()) {
+                if !desc.is_empty() {
+                    attachment = attachment.with_caption(desc);
+                }
+            }
+            Some(attachment)
+        })
+        .collect()
+}
+
+// ── Stream plumbing ──────────────────────────────────────────
+
+async fn write_str<S: AsyncWrite + Unpin + ?Sized>(io: &mut S, data: &str) -> anyhow::Result<()> {
+    io.write_all(data.as_bytes()).await?;
+    io.flush().await?;
+    Ok(())
+}
+
+async fn read_event<S: AsyncRead + Unpin + ?Sized>(
+    io: &mut S,
+    reader: &mut StanzaReader,
+) -> anyhow::Result<StreamEvent> {
+    let mut buf = [0u8; 4096];
+    loop {
+        if let Some(event) = reader.next_event()? {
+            return Ok(event);
+        }
+        let n = tokio::time::timeout(READ_TIMEOUT, io.read(&mut buf))
+            .await
+            .map_err(|_| anyhow::anyhow!("XMPP read timed out (no data for {READ_TIMEOUT:?})"))??;
+        if n == 0 {
+            anyhow::bail!("XMPP connection closed by server");
+        }
+        reader.push(&buf[..n])?;
+    }
+}
+
+async fn read_stanza<S: AsyncRead + Unpin + ?Sized>(
+    io: &mut S,
+    reader: &mut StanzaReader,
+) -> anyhow::Result<XmlElement> {
+    match read_event(io, reader).await? {
+        StreamEvent::Stanza(stanza) if stanza.name == "stream:error" => {
+            anyhow::bail!("XMPP stream error: {}", stream_error_condition(&stanza))
+        }
+        StreamEvent::Stanza(stanza) => Ok(stanza),
+        StreamEvent::Open(_) => anyhow::bail!("unexpected XMPP stream header"),
+        StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+    }
+}
+
+/// Send a stream header and wait for the server's features.
+async fn open_stream<S: AsyncRead + AsyncWrite + Unpin + ?Sized>(
+    io: &mut S,
+    reader: &mut StanzaReader,
+    domain: &str,
+) -> anyhow::Result<XmlElement> {
+    reader.reset();
+    let header = format!(
+        "<?xml version='1.0'?><stream:stream to='{}' version='1.0' xml:lang='en' \
+         xmlns='{NS_CLIENT}' xmlns:stream='{NS_STREAM}'>",
+        escape_xml(domain)
+    );
+    write_str(io, &header).await?;
+
+    loop {
+        match read_event(io, reader).await? {
+            StreamEvent::Open(_) => break,
+            StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+            StreamEvent::Stanza(stanza) if stanza.name == "stream:error" => {
+                anyhow::bail!("XMPP stream error: {}", stream_error_condition(&stanza))
+            }
+            StreamEvent::Stanza(_) => {}
+        }
+    }
+    let features = read_stanza(io, reader).await?;
+    if features.name != "stream:features" {
+        anyhow::bail!("expected stream features, got <{}>", features.name);
+    }
+    Ok(features)
+}
+
+// ── Minimal XML ──────────────────────────────────────────────
+
+/// An XML element with its attributes, child elements and concatenated text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct XmlElement {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn with_attr(mut self, name: &str, value: &str) -> Self {
+        self.attrs.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn with_text(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    /// Element name without its namespace prefix.
+    fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First child with the given local name.
+    fn child(&self, local_name: &str) -> Option<&XmlElement> {
+        self.children
+            .iter()
+            .find(|child| child.local_name() == local_name)
+    }
+
+    fn to_xml(&self) -> String {
+        let mut out = format!("<{}", self.name);
+        for (key, value) in &self.attrs {
+            let _ = write!(out, " {key}='{}'", escape_xml(value));
+        }
+        if self.children.is_empty() && self.text.is_empty() {
+            out.push_str("/>");
+            return out;
+        }
+        out.push('>');
+        out.push_str(&escape_xml(&self.text));
+        for child in &self.children {
+            out.push_str(&child.to_xml());
+        }
+        let _ = write!(out, "</{}>", self.name);
+        out
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StreamEvent {
+    /// `<stream:stream …>` header; only the attributes are meaningful.
+    Open(XmlElement),
+    /// A complete top-level stanza.
+    Stanza(XmlElement),
+    /// `</stream:stream>`.
+    Close,
+}
+
+/// Incremental splitter turning stream bytes into top-level stanzas.
+#[derive(Debug, Default)]
+struct StanzaReader {
+    buf: Vec<u8>,
+}
+
+impl StanzaReader {
+    fn push(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() > MAX_STANZA_BYTES {
+            anyhow::bail!("XMPP stanza exceeds {MAX_STANZA_BYTES} bytes");
+        }
+        Ok(())
+    }
+
+    /// Discard buffered input; used when the stream restarts.
+    fn reset(&mut self) {
+        self.buf.clear();
+    }
+
+    /// Next complete event, or `None` until more input arrives.
+    fn next_event(&mut self) -> anyhow::Result<Option<StreamEvent>> {
+        loop {
+            let text = match std::str::from_utf8(&self.buf) {
+                Ok(text) => text,
+                // A multi-byte character split across reads.
+                Err(e) if e.error_len().is_none() => {
+                    std::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap_or_default()
+                }
+                Err(_) => anyhow::bail!("invalid UTF-8 in XMPP stream"),
+            };
+            let rest = text.trim_start();
+            let skipped = text.len() - rest.len();
+            if rest.is_empty() {
+                self.buf.drain(..skipped);
+                return Ok(None);
+            }
+
+            let (event, consumed) = if rest.starts_with("<?") || rest.starts_with("<!--") {
+                let terminator = if rest.starts_with("<?") { "?>" } else { "-->" };
+                let Some(end) = rest.find(terminator) else {
+                    return Ok(None);
+                };
+                (None, end + terminator.len())
+            } else if rest.starts_with("</stream:stream") {
+                let Some(end) = rest.find('>') else {
+                    return Ok(None);
+                };
+                (Some(StreamEvent::Close), end + 1)
+            } else if rest.starts_with("<stream:stream") {
+                let Some((header, _, len)) = parse_start_tag(rest)? else {
+                    return Ok(None);
+                };
+                (Some(StreamEvent::Open(header)), len)
+            } else if rest.starts_with('<') {
+                let Some((stanza, len)) = parse_element(rest, 0)? else {
+                    return Ok(None);
+                };
+                (Some(StreamEvent::Stanza(stanza)), len)
+            } else {
+                anyhow::bail!("unexpected text between XMPP stanzas");
+            };
+
+            self.buf.drain(..skipped + consumed);
+            if let Some(event) = event {
+                return Ok(Some(event));
+            }
+        }
+    }
+}
+
+/// Parse `<name attr='v' …>` at the start of `input`. Returns the element,
+/// whether it was self-closing and the bytes consumed, or `None` when the
+/// tag is incomplete.
+fn parse_start_tag(input: &str) -> anyhow::Result<Option<(XmlElement, bool, usize)>> {
+    let name_end = input[1..]
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .map(|i| i + 1);
+    let Some(name_end) = name_end else {
+        return Ok(None);
+    };
+    let name = &input[1..name_end];
+    if name.is_empty() {
+        anyhow::bail!("malformed XML tag");
+    }
+    let mut element = XmlElement::new(name);
+    let mut pos = name_end;
+
+    loop {
+        let rest = &input[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+        if trimmed.starts_with("/>") {
+            return Ok(Some((element, true, pos + 2)));
+        }
+        if trimmed.starts_with('/') {
+            return Ok(None);
+        }
+        if trimmed.starts_with('>') {
+            return Ok(Some((element, false, pos + 1)));
+        }
+
+        let Some(eq) = trimmed.find('=') else {
+            return Ok(None);
+        };
+        let key = trimmed[..eq].trim();
+        if key.is_empty() || key.contains(['<', '>', '/']) {
+            anyhow::bail!("malformed XML attribute in <{name}>");
+        }
+        let after_eq = trimmed[eq + 1..].trim_start();
+        let value_start = pos + (trimmed.len() - after_eq.len());
+        let Some(quote) = after_eq.chars().next() else {
+            return Ok(None);
+        };
+        if quote != '\'' && quote != '"' {
+            anyhow::bail!("unquoted XML attribute {key} in <{name}>");
+        }
+        let Some(value_len) = after_eq[1..].find(quote) else {
+            return Ok(None);
+        };
+        let value = unescape_xml(&after_eq[1..=value_len]);
+        element.attrs.push((key.to_string(), value));
+        pos = value_start + value_len + 2;
+    }
+}
+
+/// Parse one complete element, or `None` when more input is needed.
+fn parse_element(input: &str, depth: usize) -> anyhow::Result<Option<(XmlElement, usize)>> {
+    if depth > MAX_DEPTH {
+        anyhow::bail!("XMPP stanza nested deeper than {MAX_DEPTH} levels");
+    }
+    let Some((mut element, self_closing, mut pos)) = parse_start_tag(input)? else {
+        return Ok(None);
+    };
+    if self_closing {
+        return Ok(Some((element, pos)));
+    }
+
+    loop {
+        let rest = &input[pos..];
+        if rest.is_empty() {
+            return Ok(None);
+        }
+        if let Some(close) = rest.strip_prefix("</") {
+            let Some(end) = close.find('>') else {
+                return Ok(None);
+            };
+            if close[..end].trim() != element.name {
+                anyhow::bail!(
+                    "mismatched XML end tag </{}> for <{}>",
+                    close[..end].trim(),
+                    element.name
+                );
+            }
+            return Ok(Some((element, pos + 2 + end + 1)));
+        }
+        if rest.starts_with("<!--") {
+            let Some(end) = rest.find("-->") else {
+                return Ok(None);
+            };
+            pos += end + 3;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let Some(end) = cdata.find("]]>") else {
+                return Ok(None);
+            };
+            element.text.push_str(&cdata[..end]);
+            pos += "<![CDATA[".len() + end + 3;
+        } else if rest.starts_with('<') {
+            let Some((child, len)) = parse_element(rest, depth + 1)? else {
+                return Ok(None);
+            };
+            element.children.push(child);
+            pos += len;
+        } else {
+            // Text runs until the next tag; without one it may be incomplete.
+            let Some(end) = rest.find('<') else {
+                return Ok(None);
+            };
+            element.text.push_str(&unescape_xml(&rest[..end]));
+            pos += end;
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\'' => out.push_str("&apos;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Resolve the predefined and numeric entities; unknown ones are kept as-is.
+fn unescape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let resolved = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "apos" => Some('\''),
+            "quot" => Some('"'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        if let Some(c) = resolved {
+            out.push(c);
+        } else {
+            out.push_str(&rest[..=semi]);
+        }
+        rest = &rest[semi + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::{TcpListener, TcpStream};
+
+    const ROOM: &str = "lounge@conference.localhost";
+
+    fn test_config(port: u16) -> XmppChannelConfig {
+        XmppChannelConfig {
+            jid: "zc@localhost".into(),
+            password: "secret".into(),
+            server: Some("127.0.0.1".into()),
+            port: Some(port),
+            tls: XmppTlsMode::None,
+            verify_tls: true,
+            resource: "zeroclaw".into(),
+            nickname: None,
+            rooms: vec![ROOM.into()],
+            allowed_users: vec!["*".into()],
+            mention_only: true,
+            group_reply_allowed_sender_ids: vec![],
+        }
+    }
+
+    fn parse_one(xml: &str) -> XmlElement {
+        let mut reader = StanzaReader::default();
+        reader.push(xml.as_bytes()).unwrap();
+        match reader.next_event().unwrap() {
+            Some(StreamEvent::Stanza(stanza)) => stanza,
+            other => panic!("expected stanza, got {other:?}"),
+        }
+    }
+
+    /// A scripted XMPP server speaking plain TCP on loopback.
+    struct StubServer {
+        io: TcpStream,
+        reader: StanzaReader,
+    }
+
+    impl StubServer {
+        async fn accept(listener: &TcpListener) -> Self {
+            let (io, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+                .await
+                .expect("client did not connect")
+                .unwrap();
+            Self {
+                io,
+                reader: StanzaReader::default(),
+            }
+        }
+
+        async fn send(&mut self, xml: &str) {
+            write_str(&mut self.io, xml).await.unwrap();
+        }
+
+        async fn next(&mut self) -> XmlElement {
+            tokio::time::timeout(
+                Duration::from_secs(5),
+                read_stanza(&mut self.io, &mut self.reader),
+            )
+            .await
+            .expect("client went quiet")
+            .unwrap()
+        }
+
+        async fn expect_stream_open(&mut self) {
+            let event = read_event(&mut self.io, &mut self.reader).await.unwrap();
+            assert!(matches!(event, StreamEvent::Open(_)), "got {event:?}");
+            self.send(
+                "<?xml version='1.0'?><stream:stream from='localhost' id='s1' version='1.0' \
+                 xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'>",
+            )
+            .await;
+        }
+
+        /// Authenticate and bind the client; returns the presences it sent.
+        async fn handshake(&mut self) -> Vec<XmlElement> {
+            self.expect_stream_open().await;
+            self.send(
+                "<stream:features><mechanisms xmlns='urn:ietf:params:xml:ns:xmpp-sasl'>\
+                 <mechanism>SCRAM-SHA-1</mechanism><mechanism>PLAIN</mechanism>\
+                 </mechanisms></stream:features>",
+            )
+            .await;
+
+            let auth = self.next().await;
+            assert_eq!(auth.local_name(), "auth");
+            assert_eq!(auth.attr("mechanism"), Some("PLAIN"));
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(auth.text.trim())
+                .unwrap();
+            assert_eq!(decoded, b"\0zc\0secret");
+            self.send("<success xmlns='urn:ietf:params:xml:ns:xmpp-sasl'/>")
+                .await;
+
+            self.expect_stream_open().await;
+            self.send(
+                "<stream:features><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'/>\
+                 </stream:features>",
+            )
+            .await;
+            let bind = self.next().await;
+            assert_eq!(bind.attr("type"), Some("set"));
+            let resource = bind
+                .child("bind")
+                .and_then(|b| b.child("resource"))
+                .unwrap();
+            assert_eq!(resource.text, "zeroclaw");
+            let id = bind.attr("id").unwrap().to_string();
+            self.send(&format!(
+                "<iq type='result' id='{id}'><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'>\
+                 <jid>zc@localhost/zeroclaw</jid></bind></iq>"
+            ))
+            .await;
+
+            vec![self.next().await, self.next().await]
+        }
+    }
+
+    #[test]
+    fn reader_handles_stream_header_split_input_and_close() {
+        let mut reader = StanzaReader::default();
+        reader
+            .push(b"<?xml version='1.0'?><stream:stream from='localhost' version='1.0'>")
+            .unwrap();
+        let Some(StreamEvent::Open(header)) = reader.next_event().unwrap() else {
+            panic!("expected stream header");
+        };
+        assert_eq!(header.attr("from"), Some("localhost"));
+
+        let stanza = "<message from='a@b/c'><body>caf\u{e9} &amp; &#x263A;</body></message> ";
+        let (first, second) = stanza.as_bytes().split_at(32);
+        reader.push(first).unwrap();
+        assert!(reader.next_event().unwrap().is_none());
+        reader.push(second).unwrap();
+        let Some(StreamEvent::Stanza(message)) = reader.next_event().unwrap() else {
+            panic!("expected stanza");
+        };
+        assert_eq!(message.child("body").unwrap().text, "caf\u{e9} & \u{263A}");
+
+        reader.push(b"</stream:stream>").unwrap();
+        assert_eq!(reader.next_event().unwrap(), Some(StreamEvent::Close));
+    }
+
+    #[test]
+    fn reader_waits_for_split_multibyte_characters() {
+        let mut reader = StanzaReader::default();
+        let bytes = "<body>\u{e9}</body>".as_bytes();
+        reader.push(&bytes[..7]).unwrap();
+        assert!(reader.next_event().unwrap().is_none());
+        reader.push(&bytes[7..]).unwrap();
+        let Some(StreamEvent::Stanza(body)) = reader.next_event().unwrap() else {
+            panic!("expected stanza");
+        };
+        assert_eq!(body.text, "\u{e9}");
+    }
+
+    #[test]
+    fn reader_rejects_mismatched_tags_and_deep_nesting() {
+        let mut reader = StanzaReader::default();
+        reader.push(b"<a><b></a>").unwrap();
+        assert!(reader.next_event().is_err());
+
+        let deep = format!(
+            "{}{}",
+            "<x>".repeat(MAX_DEPTH + 2),
+            "</x>".repeat(MAX_DEPTH + 2)
+        );
+        let mut reader = StanzaReader::default();
+        reader.push(deep.as_bytes()).unwrap();
+        assert!(reader.next_event().is_err());
+    }
+
+    #[test]
+    fn element_serialization_escapes_text_and_attributes() {
+        let xml = XmlElement::new("message")
+            .with_attr("to", "o'hara@example.org")
+            .with_child(XmlElement::new("body").with_text("1 < 2 & \"ok\""))
+            .to_xml();
+        assert_eq!(
+            xml,
+            "<message to='o&apos;hara@example.org'><body>1 &lt; 2 &amp; &quot;ok&quot;</body></message>"
+        );
+        assert_eq!(
+            parse_one(&xml).child("body").unwrap().text,
+            "1 < 2 & \"ok\""
+        );
+    }
+
+    #[test]
+    fn new_derives_host_port_and_nickname_from_jid() {
+        let mut cfg = test_config(0);
+        cfg.server = None;
+        cfg.port = None;
+        cfg.tls = XmppTlsMode::Direct;
+        cfg.jid = "Bot@Example.org/laptop".into();
+        let ch = XmppChannel::new(cfg);
+        assert_eq!(ch.bare_jid, "Bot@Example.org");
+        assert_eq!(ch.domain, "example.org");
+        assert_eq!(ch.host, "example.org");
+        assert_eq!(ch.port, 5223);
+        assert_eq!(ch.nickname, "Bot");
+    }
+
+    #[test]
+    fn parse_message_accepts_allowed_direct_chat() {
+        let mut cfg = test_config(0);
+        cfg.allowed_users = vec!["Alice@localhost".into()];
+        let ch = XmppChannel::new(cfg);
+
+        let msg = ch
+            .parse_message(&parse_one(
+                "<message type='chat' from='alice@localhost/phone' id='m1'><body> hi </body></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.id, "m1");
+        assert_eq!(msg.sender, "alice@localhost");
+        assert_eq!(msg.reply_target, "alice@localhost");
+        assert_eq!(msg.content, "hi");
+        assert_eq!(msg.channel, "xmpp");
+
+        assert!(ch
+            .parse_message(&parse_one(
+                "<message type='chat' from='mallory@localhost/x'><body>hi</body></message>",
+            ))
+            .is_none());
+        // Chat states without a body are not messages.
+        assert!(ch
+            .parse_message(&parse_one(
+                "<message type='chat' from='alice@localhost/phone'>\
+                 <composing xmlns='http://jabber.org/protocol/chatstates'/></message>",
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn parse_message_gates_room_messages_on_mentions() {
+        let ch = XmppChannel::new(test_config(0));
+        let room_message = |from: &str, body: &str| {
+            parse_one(&format!(
+                "<message type='groupchat' from='{ROOM}/{from}'><body>{body}</body></message>"
+            ))
+        };
+
+        assert!(ch
+            .parse_message(&room_message("alice", "hello all"))
+            .is_none());
+        let msg = ch
+            .parse_message(&room_message("alice", "ZC: what time is it?"))
+            .unwrap();
+        assert_eq!(msg.content, "what time is it?");
+        assert_eq!(msg.sender, format!("{ROOM}/alice"));
+        assert_eq!(msg.reply_target, ROOM);
+
+        let msg = ch
+            .parse_message(&room_message("alice", "ask zc about it"))
+            .unwrap();
+        assert_eq!(msg.content, "ask zc about it");
+        // Nickname inside another word is not a mention.
+        assert!(ch
+            .parse_message(&room_message("alice", "zcash rocks"))
+            .is_none());
+        // Own echo and rooms we did not join are ignored.
+        assert!(ch.parse_message(&room_message("zc", "zc: loop")).is_none());
+        assert!(ch
+            .parse_message(&parse_one(
+                "<message type='groupchat' from='other@conference.localhost/alice'>\
+                 <body>zc: hi</body></message>",
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn parse_message_skips_room_history_and_honours_sender_overrides() {
+        let mut cfg = test_config(0);
+        cfg.group_reply_allowed_sender_ids = vec![" alice ".into(), "alice".into()];
+        let ch = XmppChannel::new(cfg);
+        assert_eq!(ch.group_reply_allowed_sender_ids, vec!["alice".to_string()]);
+
+        let msg = ch
+            .parse_message(&parse_one(&format!(
+                "<message type='groupchat' from='{ROOM}/alice'><body>no mention</body></message>"
+            )))
+            .unwrap();
+        assert_eq!(msg.content, "no mention");
+        assert!(ch
+            .parse_message(&parse_one(&format!(
+                "<message type='groupchat' from='{ROOM}/bob'><body>no mention</body></message>"
+            )))
+            .is_none());
+        assert!(ch
+            .parse_message(&parse_one(&format!(
+                "<message type='groupchat' from='{ROOM}/alice'><body>old</body>\
+                 <delay xmlns='urn:xmpp:delay' stamp='2024-01-01T00:00:00Z'/></message>"
+            )))
+            .is_none());
+
+        let mut cfg = test_config(0);
+        cfg.mention_only = false;
+        let ch = XmppChannel::new(cfg);
+        assert!(ch
+            .parse_message(&parse_one(&format!(
+                "<message type='groupchat' from='{ROOM}/bob'><body>anyone?</body></message>"
+            )))
+            .is_some());
+    }
+
+    #[test]
+    fn parse_message_collects_out_of_band_attachments() {
+        let ch = XmppChannel::new(test_config(0));
+        let msg = ch
+            .parse_message(&parse_one(
+                "<message type='chat' from='alice@localhost/phone'>\
+                 <body>https://files.localhost/cat.png</body>\
+                 <x xmlns='jabber:x:oob'><url>https://files.localhost/cat.png</url></x></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.attachments.len(), 1);
+        assert_eq!(msg.attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(
+            msg.attachments[0].url(),
+            Some("https://files.localhost/cat.png")
+        );
+    }
+
+    #[test]
+    fn chat_state_stanzas_use_room_message_type() {
+        let ch = XmppChannel::new(test_config(0));
+        let composing = ch.chat_state_stanza(ROOM, "composing");
+        assert_eq!(composing.attr("type"), Some("groupchat"));
+        assert_eq!(
+            composing.child("composing").unwrap().attr("xmlns"),
+            Some(NS_CHAT_STATES)
+        );
+        assert_eq!(
+            ch.chat_state_stanza("alice@localhost", "active")
+                .attr("type"),
+            Some("chat")
+        );
+    }
+
+    #[test]
+    fn plaintext_is_refused_for_remote_hosts() {
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]"));
+        assert!(!is_loopback_host("xmpp.example.org"));
+    }
+
+    #[tokio::test]
+    async fn listen_logs_in_joins_rooms_and_relays_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let channel = Arc::new(XmppChannel::new(test_config(port)));
+        let (tx, mut rx) = mpsc::channel(8);
+        let listen = tokio::spawn({
+            let channel = Arc::clone(&channel);
+            async move { channel.listen(tx).await }
+        });
+
+        let mut server = StubServer::accept(&listener).await;
+        let presences = server.handshake().await;
+        assert_eq!(presences[0].attr("to"), None);
+        assert_eq!(presences[1].attr("to"), Some(&*format!("{ROOM}/zc")));
+        assert_eq!(presences[1].child("x").unwrap().attr("xmlns"), Some(NS_MUC));
+
+        server
+            .send(
+                "<message type='chat' from='alice@localhost/phone' id='m1'>\
+                 <body>hello &amp; welcome</body></message>",
+            )
+            .await;
+        let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(msg.content, "hello & welcome");
+        assert_eq!(msg.reply_target, "alice@localhost");
+        assert!(channel.health_check().await);
+
+        server
+            .send("<iq type='get' id='p1' from='localhost'><ping xmlns='urn:xmpp:ping'/></iq>")
+            .await;
+        let pong = server.next().await;
+        assert_eq!(pong.attr("type"), Some("result"));
+        assert_eq!(pong.attr("id"), Some("p1"));
+
+        channel.start_typing(ROOM).await.unwrap();
+        let typing = server.next().await;
+        assert_eq!(typing.attr("to"), Some(ROOM));
+        assert!(typing.child("composing").is_some());
+
+        channel
+            .send(&SendMessage::new("hi <alice>", "alice@localhost"))
+            .await
+            .unwrap();
+        let reply = server.next().await;
+        assert_eq!(reply.attr("to"), Some("alice@localhost"));
+        assert_eq!(reply.attr("type"), Some("chat"));
+        assert_eq!(reply.child("body").unwrap().text, "hi <alice>");
+
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(5), listen)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(!channel.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn listen_reconnects_after_connection_loss() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut channel = XmppChannel::new(test_config(port));
+        channel.initial_backoff = Duration::from_millis(10);
+        channel.max_backoff = Duration::from_millis(40);
+        let channel = Arc::new(channel);
+        let (tx, mut rx) = mpsc::channel(8);
+        let listen = tokio::spawn({
+            let channel = Arc::clone(&channel);
+            async move { channel.listen(tx).await }
+        });
+
+        let mut first = StubServer::accept(&listener).await;
+        first.handshake().await;
+        drop(first);
+
+        let mut second = StubServer::accept(&listener).await;
+        second.handshake().await;
+        second
+            .send("<message type='chat' from='bob@localhost/pc'><body>back?</body></message>")
+            .await;
+        let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(msg.sender, "bob@localhost");
+
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(5), listen)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn listen_retries_when_authentication_fails() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut channel = XmppChannel::new(test_config(port));
+        channel.initial_backoff = Duration::from_millis(10);
+        let channel = Arc::new(channel);
+        let (tx, rx) = mpsc::channel(8);
+        let listen = tokio::spawn({
+            let channel = Arc::clone(&channel);
+            async move { channel.listen(tx).await }
+        });
+
+        let mut server = StubServer::accept(&listener).await;
+        server.expect_stream_open().await;
+        server
+            .send(
+                "<stream:features><mechanisms xmlns='urn:ietf:params:xml:ns:xmpp-sasl'>\
+                 <mechanism>PLAIN</mechanism></mechanisms></stream:features>",
+            )
+            .await;
+        server.next().await;
+        server
+            .send("<failure xmlns='urn:ietf:params:xml:ns:xmpp-sasl'><not-authorized/></failure>")
+            .await;
+
+        // The channel keeps trying instead of giving up.
+        let _retry = StubServer::accept(&listener).await;
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(5), listen)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,895 @@
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Topic used when a stream message is sent without one.
+const DEFAULT_TOPIC: &str = "zeroclaw";
+
+/// Zulip holds event long-polls open for up to ~90s.
+const LONG_POLL_TIMEOUT_SECS: u64 = 120;
+
+/// Zulip channel — receives through the real-time events API and replies via REST.
+///
+/// Stream messages reply to `stream:<stream_id>` with the topic carried in
+/// `thread_ts`; direct messages reply to a comma-separated list of emails.
+pub struct ZulipChannel {
+    base_url: String, // e.g., https://chat.example.com
+    bot_email: String,
+    api_key: String,
+    allowed_users: Vec<String>,
+    /// When true, only respond to stream messages that @-mention the bot.
+    mention_only: bool,
+    /// Sender emails or IDs that bypass mention gating in streams.
+    group_reply_allowed_sender_ids: Vec<String>,
+    stream_mode: StreamMode,
+    draft_update_interval_ms: u64,
+    last_draft_edit: Mutex<HashMap<String, std::time::Instant>>,
+    /// Topic of each open draft, for the fallback send in `finalize_draft`.
+    draft_topics: Mutex<HashMap<String, Option<String>>>,
+}
+
+/// Where a reply goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ZulipTarget {
+    /// Stream ID or name.
+    Stream(String),
+    /// Direct message recipients (emails or user IDs).
+    Direct(Vec<String>),
+}
+
+impl ZulipChannel {
+    pub fn new(
+        base_url: String,
+        bot_email: String,
+        api_key: String,
+        allowed_users: Vec<String>,
+        mention_only: bool,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            base_url,
+            bot_email,
+            api_key,
+            allowed_users,
+            mention_only,
+            group_reply_allowed_sender_ids: Vec::new(),
+            stream_mode: StreamMode::Off,
+            draft_update_interval_ms: 1000,
+            last_draft_edit: Mutex::new(HashMap::new()),
+            draft_topics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Configure sender IDs that bypass mention gating in streams.
+    pub fn with_group_reply_allowed_senders(mut self, sender_ids: Vec<String>) -> Self {
+        self.group_reply_allowed_sender_ids =
+            super::mattermost::normalize_group_reply_allowed_sender_ids(sender_ids);
+        self
+    }
+
+    /// Configure progressive replies through message edits.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_update_interval_ms = draft_update_interval_ms;
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.zulip",
+            LONG_POLL_TIMEOUT_SECS,
+            10,
+        )
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api/v1/{path}", self.base_url)
+    }
+
+    /// Check a sender against the allowlist by email (case-insensitive) or user ID.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, email: &str, user_id: i64) -> bool {
+        let user_id = user_id.to_string();
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(email) || *u == user_id)
+    }
+
+    fn is_group_sender_trigger_enabled(&self, email: &str, user_id: i64) -> bool {
+        let user_id = user_id.to_string();
+        self.group_reply_allowed_sender_ids
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(email) || *entry == user_id)
+    }
+
+    /// Parse `stream:<id-or-name>` or a comma-separated recipient list.
+    fn parse_reply_target(recipient: &str) -> ZulipTarget {
+        if let Some(stream) = recipient.strip_prefix("stream:") {
+            return ZulipTarget::Stream(stream.trim().to_string());
+        }
+        ZulipTarget::Direct(
+            recipient
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+        )
+    }
+
+    /// Form fields addressing a message to `recipient`.
+    fn message_form(recipient: &str, topic: Option<&str>, content: &str) -> Vec<(String, String)> {
+        let mut form = Vec::with_capacity(4);
+        match Self::parse_reply_target(recipient) {
+            ZulipTarget::Stream(stream) => {
+                form.push(("type".to_string(), "stream".to_string()));
+                form.push(("to".to_string(), stream));
+                let topic = topic
+                    .filter(|t| !t.trim().is_empty())
+                    .unwrap_or(DEFAULT_TOPIC);
+                form.push(("topic".to_string(), topic.to_string()));
+            }
+            ZulipTarget::Direct(recipients) => {
+                let to: Vec<serde_json::Value> = recipients
+                    .into_iter()
+                    .map(|r| {
+                        r.parse::<i64>()
+                            .map_or_else(|_| serde_json::json!(r), |id| serde_json::json!(id))
+                    })
+                    .collect();
+                form.push(("type".to_string(), "private".to_string()));
+                form.push(("to".to_string(), serde_json::Value::from(to).to_string()));
+            }
+        }
+        form.push(("content".to_string(), content.to_string()));
+        form
+    }
+
+    /// Send a message and return its ID.
+    async fn post_message(
+        &self,
+        recipient: &str,
+        topic: Option<&str>,
+        content: &str,
+    ) -> Result<Option<i64>> {
+        let resp = self
+            .http_client()
+            .post(self.api_url("messages"))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(&Self::message_form(recipient, topic, content))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            bail!("Zulip send failed ({status}): {sanitized}");
+        }
+
+        let data: serde_json::Value = resp.json().await.unwrap_or_default();
+        Ok(data.get("id").and_then(serde_json::Value::as_i64))
+    }
+
+    /// Replace the content of a message the bot sent.
+    async fn edit_message(&self, message_id: &str, content: &str) -> Result<bool> {
+        let resp = self
+            .http_client()
+            .patch(self.api_url(&format!("messages/{message_id}")))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(&[("content", content)])
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            return Ok(true);
+        }
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        let sanitized = crate::providers::sanitize_api_error(&body);
+        tracing::debug!("Zulip message edit failed ({status}): {sanitized}");
+        Ok(false)
+    }
+
+    /// The bot's own user ID and full name, used to skip its own messages
+    /// and detect @-mentions.
+    async fn get_bot_identity(&self) -> Result<(i64, String)> {
+        let resp: serde_json::Value = self
+            .http_client()
+            .get(self.api_url("users/me"))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let Some(user_id) = resp.get("user_id").and_then(serde_json::Value::as_i64) else {
+            let msg = resp
+                .get("msg")
+                .and_then(|m| m.as_str())
+                .unwrap_or("no user_id");
+            bail!("Zulip users/me failed: {msg}");
+        };
+        let full_name = resp
+            .get("full_name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("")
+            .to_string();
+        Ok((user_id, full_name))
+    }
+
+    /// Register a message event queue; returns `(queue_id, last_event_id)`.
+    async fn register_queue(&self) -> Result<(String, i64)> {
+        let resp: serde_json::Value = self
+            .http_client()
+            .post(self.api_url("register"))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(&[
+                ("event_types", r#"["message"]"#),
+                ("apply_markdown", "false"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let Some(queue_id) = resp.get("queue_id").and_then(|q| q.as_str()) else {
+            let msg = resp
+                .get("msg")
+                .and_then(|m| m.as_str())
+                .unwrap_or("no queue_id");
+            bail!("Zulip event queue registration failed: {msg}");
+        };
+        let last_event_id = resp
+            .get("last_event_id")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(-1);
+        Ok((queue_id.to_string(), last_event_id))
+    }
+}
+
+#[async_trait]
+impl Channel for ZulipChannel {
+    fn name(&self) -> &str {
+        "zulip"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        self.post_message(
+            &message.recipient,
+            message.thread_ts.as_deref(),
+            &message.content,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let (bot_user_id, bot_full_name) = self.get_bot_identity().await?;
+        let (mut queue_id, mut last_event_id) = self.register_queue().await?;
+
+        tracing::info!("Zulip channel listening as {bot_full_name}...");
+
+        loop {
+            let resp = match self
+                .http_client()
+                .get(self.api_url("events"))
+                .basic_auth(&self.bot_email, Some(&self.api_key))
+                .query(&[
+                    ("queue_id", queue_id.clone()),
+                    ("last_event_id", last_event_id.to_string()),
+                ])
+                .send()
+                .await
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::warn!("Zulip poll error: {e}");
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                    continue;
+                }
+            };
+
+            let data: serde_json::Value = match resp.json().await {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!("Zulip parse error: {e}");
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                    continue;
+                }
+            };
+
+            if data.get("result").and_then(|r| r.as_str()) == Some("error") {
+                if data.get("code").and_then(|c| c.as_str()) == Some("BAD_EVENT_QUEUE_ID") {
+                    tracing::info!("Zulip event queue expired; registering a new one");
+                    (queue_id, last_event_id) = self.register_queue().await?;
+                } else {
+                    let msg = data.get("msg").and_then(|m| m.as_str()).unwrap_or("");
+                    tracing::warn!("Zulip poll error: {msg}");
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                }
+                continue;
+            }
+
+            let Some(events) = data.get("events").and_then(|e| e.as_array()) else {
+                continue;
+            };
+            for event in events {
+                if let Some(id) = event.get("id").and_then(serde_json::Value::as_i64) {
+                    last_event_id = last_event_id.max(id);
+                }
+                if event.get("type").and_then(|t| t.as_str()) != Some("message") {
+                    continue;
+                }
+                if let Some(msg) = self.parse_zulip_event(event, bot_user_id, &bot_full_name) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.http_client()
+            .get(self.api_url("users/me"))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let initial_text = if message.content.is_empty() {
+            "..."
+        } else {
+            message.content.as_str()
+        };
+        let id = self
+            .post_message(
+                &message.recipient,
+                message.thread_ts.as_deref(),
+                initial_text,
+            )
+            .await?
+            .map(|id| id.to_string());
+
+        if let Some(ref id) = id {
+            self.draft_topics
+                .lock()
+                .insert(id.clone(), message.thread_ts.clone());
+        }
+        self.last_draft_edit
+            .lock()
+            .insert(message.recipient.clone(), std::time::Instant::now());
+        Ok(id)
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<Option<String>> {
+        {
+            let last_edits = self.last_draft_edit.lock();
+            if let Some(last_time) = last_edits.get(recipient) {
+                let elapsed = u64::try_from(last_time.elapsed().as_millis()).unwrap_or(u64::MAX);
+                if elapsed < self.draft_update_interval_ms {
+                    return Ok(None);
+                }
+            }
+        }
+
+        if self.edit_message(message_id, text).await? {
+            self.last_draft_edit
+                .lock()
+                .insert(recipient.to_string(), std::time::Instant::now());
+        }
+        Ok(None)
+    }
+
+    async fn finalize_draft(&self, recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        let topic = self.draft_topics.lock().remove(message_id).flatten();
+
+        if self.edit_message(message_id, text).await? {
+            return Ok(());
+        }
+
+        tracing::warn!("Zulip finalize_draft edit failed; sending a new message");
+        self.post_message(recipient, topic.as_deref(), text).await?;
+        Ok(())
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        self.draft_topics.lock().remove(message_id);
+
+        let resp = self
+            .http_client()
+            .delete(self.api_url(&format!("messages/{message_id}")))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            tracing::debug!("Zulip message delete failed ({status}): {sanitized}");
+        }
+        Ok(())
+    }
+
+    async fn add_reaction(&self, _channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        let Some(name) = zulip_emoji_name(emoji) else {
+            tracing::debug!("Zulip: no emoji name for reaction {emoji:?}; skipping");
+            return Ok(());
+        };
+        let raw_id = message_id.strip_prefix("zulip_").unwrap_or(message_id);
+
+        let resp = self
+            .http_client()
+            .post(self.api_url(&format!("messages/{raw_id}/reactions")))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(&[("emoji_name", name)])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            bail!("Zulip add reaction failed ({status}): {sanitized}");
+        }
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<()> {
+        let Some(name) = zulip_emoji_name(emoji) else {
+            return Ok(());
+        };
+        let raw_id = message_id.strip_prefix("zulip_").unwrap_or(message_id);
+
+        let resp = self
+            .http_client()
+            .delete(self.api_url(&format!("messages/{raw_id}/reactions")))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .query(&[("emoji_name", name)])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            bail!("Zulip remove reaction failed ({status}): {sanitized}");
+        }
+        Ok(())
+    }
+}
+
+impl ZulipChannel {
+    fn parse_zulip_event(
+        &self,
+        event: &serde_json::Value,
+        bot_user_id: i64,
+        bot_full_name: &str,
+    ) -> Option<ChannelMessage> {
+        let message = event.get("message")?;
+        let id = message.get("id").and_then(serde_json::Value::as_i64)?;
+        let sender_id = message
+            .get("sender_id")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(0);
+        let sender_email = message
+            .get("sender_email")
+            .and_then(|e| e.as_str())
+            .unwrap_or("");
+        let text = message
+            .get("content")
+            .and_then(|c| c.as_str())
+            .unwrap_or("");
+
+        if sender_id == bot_user_id || text.trim().is_empty() {
+            return None;
+        }
+
+        if !self.is_user_allowed(sender_email, sender_id) {
+            tracing::warn!("Zulip: ignoring message from unauthorized user: {sender_email}");
+            return None;
+        }
+
+        let (reply_target, thread_ts, content) = match message.get("type").and_then(|t| t.as_str())
+        {
+            Some("stream") => {
+                let stream_id = message
+                    .get("stream_id")
+                    .and_then(serde_json::Value::as_i64)?;
+                let topic = message
+                    .get("subject")
+                    .and_then(|s| s.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let mentioned = event
+                    .get("flags")
+                    .and_then(|f| f.as_array())
+                    .is_some_and(|flags| flags.iter().any(|f| f.as_str() == Some("mentioned")));
+                let require_mention = self.mention_only
+                    && !self.is_group_sender_trigger_enabled(sender_email, sender_id);
+                let content = if require_mention {
+                    normalize_zulip_content(text, bot_full_name, mentioned)?
+                } else {
+                    text.to_string()
+                };
+                (format!("stream:{stream_id}"), Some(topic), content)
+            }
+            Some("private") => {
+                // Reply to everyone in the conversation except the bot.
+                let recipients: Vec<&str> = message
+                    .get("display_recipient")
+                    .and_then(|r| r.as_array())
+                    .map(|users| {
+                        users
+                            .iter()
+                            .filter(|u| {
+                                u.get("id").and_then(serde_json::Value::as_i64) != Some(bot_user_id)
+                            })
+                            .filter_map(|u| u.get("email").and_then(|e| e.as_str()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let reply_target = if recipients.is_empty() {
+                    sender_email.to_string()
+                } else {
+                    recipients.join(",")
+                };
+                (reply_target, None, text.to_string())
+            }
+            _ => return None,
+        };
+
+        Some(ChannelMessage {
+            id: format!("zulip_{id}"),
+            sender: sender_email.to_string(),
+            reply_target,
+            content,
+            channel: "zulip".to_string(),
+            timestamp: message
+                .get("timestamp")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0),
+            thread_ts,
+            attachments: Vec::new(),
+            transcribed_voice: false,
+        })
+    }
+}
+
+/// Normalize a stream message when `mention_only` is enabled.
+///
+/// Returns `None` unless the message mentions the bot, either by text
+/// (`@**Full Name**` or `@**Full Name|id**`) or through the `mentioned` flag.
+/// Returns `Some(cleaned)` with the mentions stripped and text trimmed.
+fn normalize_zulip_content(text: &str, bot_full_name: &str, mentioned: bool) -> Option<String> {
+    let mut cleaned = String::with_capacity(text.len());
+    let mut found = false;
+    let mut rest = text;
+
+    while let Some(start) = rest.find("@**") {
+        let after = &rest[start + 3..];
+        let Some(end) = after.find("**") else {
+            break;
+        };
+        let name = after[..end].split('|').next().unwrap_or("");
+        if !bot_full_name.is_empty() && name.trim().eq_ignore_ascii_case(bot_full_name.trim()) {
+            cleaned.push_str(&rest[..start]);
+            cleaned.push(' ');
+            found = true;
+        } else {
+            cleaned.push_str(&rest[..start + 3 + end + 2]);
+        }
+        rest = &after[end + 2..];
+    }
+    cleaned.push_str(rest);
+
+    if !found && !mentioned {
+        return None;
+    }
+
+    let cleaned = cleaned.trim().to_string();
+    if cleaned.is_empty() {
+        return None;
+    }
+    Some(cleaned)
+}
+
+/// Zulip emoji name for the reactions the runtime uses. Plain names such as
+/// `thumbs_up` are passed through.
+fn zulip_emoji_name(emoji: &str) -> Option<&str> {
+    let emoji = emoji.trim();
+    match emoji {
+        "\u{1F440}" => Some("eyes"),
+        "\u{2705}" => Some("check"),
+        "\u{26A0}\u{FE0F}" | "\u{26A0}" => Some("warning"),
+        "\u{274C}" => Some("cross_mark"),
+        "\u{1F44D}" => Some("+1"),
+        _ => {
+            let name = emoji.trim_matches(':');
+            (!name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+')))
+            .then_some(name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_channel(allowed: Vec<String>, mention_only: bool) -> ZulipChannel {
+        ZulipChannel::new(
+            "https://chat.example.com/".into(),
+            "bot@example.com".into(),
+            "key".into(),
+            allowed,
+            mention_only,
+        )
+    }
+
+    fn stream_event(
+        sender_id: i64,
+        email: &str,
+        content: &str,
+        flags: &[&str],
+    ) -> serde_json::Value {
+        json!({
+            "type": "message",
+            "id": 7,
+            "flags": flags,
+            "message": {
+                "id": 1234,
+                "sender_id": sender_id,
+                "sender_email": email,
+                "content": content,
+                "type": "stream",
+                "stream_id": 42,
+                "display_recipient": "general",
+                "subject": "deploys",
+                "timestamp": 1_700_000_000
+            }
+        })
+    }
+
+    #[test]
+    fn zulip_url_trimming_and_api_url() {
+        let ch = make_channel(vec![], false);
+        assert_eq!(ch.base_url, "https://chat.example.com");
+        assert_eq!(
+            ch.api_url("messages"),
+            "https://chat.example.com/api/v1/messages"
+        );
+    }
+
+    #[test]
+    fn zulip_allowlist_matches_email_or_id() {
+        let ch = make_channel(vec!["Alice@Example.com".into(), "99".into()], false);
+        assert!(ch.is_user_allowed("alice@example.com", 1));
+        assert!(ch.is_user_allowed("other@example.com", 99));
+        assert!(!ch.is_user_allowed("mallory@example.com", 2));
+        assert!(!make_channel(vec![], false).is_user_allowed("alice@example.com", 1));
+        assert!(make_channel(vec!["*".into()], false).is_user_allowed("x@y.z", 3));
+    }
+
+    #[test]
+    fn zulip_stream_message_maps_topic_to_thread_ts() {
+        let ch = make_channel(vec!["*".into()], false);
+        let msg = ch
+            .parse_zulip_event(
+                &stream_event(5, "alice@example.com", "hello", &[]),
+                1,
+                "ZeroClaw",
+            )
+            .unwrap();
+        assert_eq!(msg.id, "zulip_1234");
+        assert_eq!(msg.sender, "alice@example.com");
+        assert_eq!(msg.reply_target, "stream:42");
+        assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+        assert_eq!(msg.content, "hello");
+        assert_eq!(msg.channel, "zulip");
+        assert_eq!(msg.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn zulip_skips_own_and_unauthorized_messages() {
+        let ch = make_channel(vec!["alice@example.com".into()], false);
+        assert!(ch
+            .parse_zulip_event(
+                &stream_event(1, "bot@example.com", "hi", &[]),
+                1,
+                "ZeroClaw"
+            )
+            .is_none());
+        assert!(ch
+            .parse_zulip_event(
+                &stream_event(6, "mallory@example.com", "hi", &[]),
+                1,
+                "ZeroClaw"
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn zulip_mention_only_requires_mention() {
+        let ch = make_channel(vec!["*".into()], true);
+        assert!(ch
+            .parse_zulip_event(
+                &stream_event(5, "alice@example.com", "no mention", &[]),
+                1,
+                "ZeroClaw"
+            )
+            .is_none());
+
+        let msg = ch
+            .parse_zulip_event(
+                &stream_event(
+                    5,
+                    "alice@example.com",
+                    "@**ZeroClaw|1** what broke?",
+                    &["mentioned"],
+                ),
+                1,
+                "ZeroClaw",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "what broke?");
+    }
+
+    #[test]
+    fn zulip_group_sender_override_bypasses_mention() {
+        let ch = make_channel(vec!["*".into()], true)
+            .with_group_reply_allowed_senders(vec![" alice@example.com ".into()]);
+        let msg = ch
+            .parse_zulip_event(
+                &stream_event(5, "Alice@example.com", "no mention", &[]),
+                1,
+                "ZeroClaw",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "no mention");
+    }
+
+    #[test]
+    fn zulip_private_message_replies_to_other_participants() {
+        let ch = make_channel(vec!["*".into()], true);
+        let event = json!({
+            "type": "message",
+            "id": 8,
+            "flags": [],
+            "message": {
+                "id": 55,
+                "sender_id": 5,
+                "sender_email": "alice@example.com",
+                "content": "hi bot",
+                "type": "private",
+                "display_recipient": [
+                    {"id": 1, "email": "bot@example.com"},
+                    {"id": 5, "email": "alice@example.com"},
+                    {"id": 6, "email": "bob@example.com"}
+                ],
+                "timestamp": 1
+            }
+        });
+        let msg = ch.parse_zulip_event(&event, 1, "ZeroClaw").unwrap();
+        assert_eq!(msg.reply_target, "alice@example.com,bob@example.com");
+        assert!(msg.thread_ts.is_none());
+        // Mention gating only applies to streams.
+        assert_eq!(msg.content, "hi bot");
+    }
+
+    #[test]
+    fn zulip_normalize_content_strips_only_bot_mentions() {
+        assert_eq!(
+            normalize_zulip_content("@**ZeroClaw** ping @**Alice**", "ZeroClaw", false),
+            Some("ping @**Alice**".to_string())
+        );
+        assert_eq!(
+            normalize_zulip_content("hey @**Alice**", "ZeroClaw", false),
+            None
+        );
+        // Mentioned through a user group: flag set, text left as is.
+        assert_eq!(
+            normalize_zulip_content("@*ops* deploy?", "ZeroClaw", true),
+            Some("@*ops* deploy?".to_string())
+        );
+        assert_eq!(
+            normalize_zulip_content("@**zeroclaw**", "ZeroClaw", true),
+            None
+        );
+    }
+
+    #[test]
+    fn zulip_message_form_addresses_streams_and_direct_messages() {
+        let form = ZulipChannel::message_form("stream:42", Some("deploys"), "done");
+        assert_eq!(
+            form,
+            vec![
+                ("type".to_string(), "stream".to_string()),
+                ("to".to_string(), "42".to_string()),
+                ("topic".to_string(), "deploys".to_string()),
+                ("content".to_string(), "done".to_string()),
+            ]
+        );
+
+        let form = ZulipChannel::message_form("stream:general", None, "x");
+        assert_eq!(form[2].1, DEFAULT_TOPIC);
+
+        let form = ZulipChannel::message_form("alice@example.com, 17", None, "x");
+        assert_eq!(form[0].1, "private");
+        assert_eq!(form[1].1, r#"["alice@example.com",17]"#);
+    }
+
+    #[test]
+    fn zulip_emoji_names_cover_runtime_reactions() {
+        assert_eq!(zulip_emoji_name("\u{1F440}"), Some("eyes"));
+        assert_eq!(zulip_emoji_name("\u{2705}"), Some("check"));
+        assert_eq!(zulip_emoji_name("\u{26A0}\u{FE0F}"), Some("warning"));
+        assert_eq!(zulip_emoji_name(":thumbs_up:"), Some("thumbs_up"));
+        assert_eq!(zulip_emoji_name("\u{1F9EA}"), None);
+    }
+
+    #[test]
+    fn zulip_supports_draft_updates_respects_stream_mode() {
+        let off = make_channel(vec![], false);
+        assert!(!off.supports_draft_updates());
+
+        let partial = make_channel(vec![], false).with_streaming(StreamMode::Partial, 750);
+        assert!(partial.supports_draft_updates());
+        assert_eq!(partial.draft_update_interval_ms, 750);
+    }
+
+    #[tokio::test]
+    async fn zulip_send_draft_returns_none_when_stream_mode_off() {
+        let ch = make_channel(vec![], false);
+        let id = ch
+            .send_draft(&SendMessage::new("draft", "stream:42"))
+            .await
+            .unwrap();
+        assert!(id.is_none());
+    }
+
+    #[tokio::test]
+    async fn zulip_update_draft_rate_limit_short_circuits_network() {
+        let ch = make_channel(vec![], false).with_streaming(StreamMode::Partial, 60_000);
+        ch.last_draft_edit
+            .lock()
+            .insert("stream:42".to_string(), std::time::Instant::now());
+
+        let result = ch.update_draft("stream:42", "1234", "delta text").await;
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -5,11 +5,7 @@ use anyhow::{Context, Result};
 use directories::UserDirs;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-<<<<<<< HEAD
-use std::collections::{HashMap, HashSet};
-=======
-use std::collections::{BTreeMap, HashMap};
->>>>>>> upstream/main
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 #[cfg(unix)]
@@ -34,11 +30,14 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "channel.mattermost",
     "channel.nextcloud_talk",
     "channel.qq",
+    "channel.rocketchat",
     "channel.signal",
     "channel.slack",
+    "channel.teams",
     "channel.telegram",
     "channel.wati",
     "channel.whatsapp",
+    "channel.zulip",
     "tool.browser",
     "tool.composio",
     "tool.http_request",
@@ -46,6 +45,7 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "memory.embeddings",
     "tunnel.custom",
     "transcription.groq",
+    "tts.openai",
 ];
 
 const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
@@ -55,6 +55,7 @@ const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
     "memory.*",
     "tunnel.*",
     "transcription.*",
+    "tts.*",
 ];
 
 static RUNTIME_PROXY_CONFIG: OnceLock<RwLock<ProxyConfig>> = OnceLock::new();
@@ -160,6 +161,10 @@ pub struct Config {
     #[serde(default)]
     pub model_routes: Vec<ModelRouteConfig>,
 
+    /// Adaptive selection among model routes that share a hint (`[model_routing]`).
+    #[serde(default)]
+    pub model_routing: ModelRoutingConfig,
+
     /// Embedding routing rules — route `hint:<name>` to specific provider+model combos.
     #[serde(default)]
     pub embedding_routes: Vec<EmbeddingRouteConfig>,
@@ -188,6 +193,10 @@ pub struct Config {
     #[serde(default)]
     pub memory: MemoryConfig,
 
+    /// Workspace document ingestion for `knowledge_search` (`[knowledge]`).
+    #[serde(default)]
+    pub knowledge: KnowledgeConfig,
+
     /// Persistent storage provider configuration (`[storage]`).
     #[serde(default)]
     pub storage: StorageConfig,
@@ -268,6 +277,10 @@ pub struct Config {
     #[serde(default)]
     pub transcription: TranscriptionConfig,
 
+    /// Text-to-speech voice replies (`[tts]`).
+    #[serde(default)]
+    pub tts: TtsConfig,
+
     /// Inter-process agent communication (`[agents_ipc]`).
     #[serde(default)]
     pub agents_ipc: AgentsIpcConfig,
@@ -278,6 +291,13 @@ pub struct Config {
     /// - `Some(false)`: force vision support off
     #[serde(default)]
     pub model_support_vision: Option<bool>,
+
+    /// Context window override (tokens) for the active provider/model.
+    /// - `None` (default): use the built-in model registry
+    /// - `Some(n)`: size requests for an `n`-token window (e.g. a local model
+    ///   served with a smaller `num_ctx`)
+    #[serde(default)]
+    pub model_context_window: Option<usize>,
 }
 
 /// Named provider profile definition compatible with Codex app-server style config.
@@ -356,6 +376,7 @@ impl std::fmt::Debug for DelegateAgentConfig {
             .field("model", &self.model)
             .field("system_prompt", &self.system_prompt)
             .field("api_key_configured", &self.api_key.is_some())
+            .field("api_url", &self.api_url)
             .field("temperature", &self.temperature)
             .field("max_depth", &self.max_depth)
             .field("agentic", &self.agentic)
@@ -383,8 +404,12 @@ impl std::fmt::Debug for Config {
             self.channels_config.linq.is_some(),
             self.channels_config.wati.is_some(),
             self.channels_config.nextcloud_talk.is_some(),
+            self.channels_config.teams.is_some(),
             self.channels_config.email.is_some(),
             self.channels_config.irc.is_some(),
+            self.channels_config.xmpp.is_some(),
+            self.channels_config.zulip.is_some(),
+            self.channels_config.rocketchat.is_some(),
             self.channels_config.lark.is_some(),
             self.channels_config.feishu.is_some(),
             self.channels_config.dingtalk.is_some(),
@@ -532,6 +557,132 @@ impl Default for TranscriptionConfig {
     }
 }
 
+// ── Text-to-speech ───────────────────────────────────────────────
+
+fn default_tts_api_url() -> String {
+    "https://api.openai.com/v1/audio/speech".into()
+}
+
+fn default_tts_model() -> String {
+    "tts-1".into()
+}
+
+fn default_tts_voice() -> String {
+    "alloy".into()
+}
+
+fn default_tts_format() -> String {
+    "opus".into()
+}
+
+fn default_tts_max_chars() -> usize {
+    1000
+}
+
+fn default_tts_cache_max_mb() -> u64 {
+    100
+}
+
+fn default_tts_cache_max_age_days() -> u64 {
+    30
+}
+
+/// Speech synthesis backend (`[tts] provider`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsProvider {
+    /// OpenAI-compatible `/audio/speech` endpoint.
+    #[default]
+    Openai,
+    /// Local command such as `piper` or `espeak-ng`.
+    Command,
+}
+
+/// When a channel answers with a voice note (`[tts.channels]`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsReplyMode {
+    /// Text replies only.
+    #[default]
+    Off,
+    /// Reply with a voice note when the inbound message was a voice note.
+    InKind,
+    /// Attach a voice note to every reply.
+    Always,
+}
+
+/// Text-to-speech configuration for voice replies (`[tts]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TtsConfig {
+    /// Enable voice replies on channels listed in `channels`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Synthesis backend.
+    #[serde(default)]
+    pub provider: TtsProvider,
+    /// Speech endpoint for the `openai` provider. The key is read from
+    /// `TTS_API_KEY`, falling back to `OPENAI_API_KEY`.
+    #[serde(default = "default_tts_api_url")]
+    pub api_url: String,
+    /// Speech model; also the `[cost.prices]` key used to price synthesis.
+    #[serde(default = "default_tts_model")]
+    pub model: String,
+    /// Voice name passed to the `openai` provider.
+    #[serde(default = "default_tts_voice")]
+    pub voice: String,
+    /// Audio format (file extension) produced by the backend.
+    #[serde(default = "default_tts_format")]
+    pub format: String,
+    /// Program run by the `command` provider; reply text is written to its stdin.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments for `command`; `{output}` is replaced with the audio file path.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Replies longer than this many characters stay text-only.
+    #[serde(default = "default_tts_max_chars")]
+    pub max_chars: usize,
+    /// Size limit of the audio cache in MiB; least recently used files are
+    /// removed beyond it. `0` disables the limit.
+    #[serde(default = "default_tts_cache_max_mb")]
+    pub cache_max_mb: u64,
+    /// Cached audio unused for this many days is removed. `0` disables expiry.
+    #[serde(default = "default_tts_cache_max_age_days")]
+    pub cache_max_age_days: u64,
+    /// Reply mode per channel name; unlisted channels are `off`.
+    #[serde(default)]
+    pub channels: HashMap<String, TtsReplyMode>,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TtsProvider::default(),
+            api_url: default_tts_api_url(),
+            model: default_tts_model(),
+            voice: default_tts_voice(),
+            format: default_tts_format(),
+            command: None,
+            args: Vec::new(),
+            max_chars: default_tts_max_chars(),
+            cache_max_mb: default_tts_cache_max_mb(),
+            cache_max_age_days: default_tts_cache_max_age_days(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+impl TtsConfig {
+    /// Reply mode for `channel`, honoring `enabled`.
+    pub fn reply_mode(&self, channel: &str) -> TtsReplyMode {
+        if !self.enabled {
+            return TtsReplyMode::Off;
+        }
+        self.channels.get(channel).copied().unwrap_or_default()
+    }
+}
+
 // ── Agents IPC ──────────────────────────────────────────────────
 
 fn default_agents_ipc_db_path() -> String {
@@ -815,6 +966,10 @@ pub struct CostConfig {
     /// Per-model pricing (USD per 1M tokens)
     #[serde(default)]
     pub prices: std::collections::HashMap<String, ModelPricing>,
+
+    /// Discount applied to usage from provider batch APIs, in percent (default: 50)
+    #[serde(default = "default_batch_discount_percent")]
+    pub batch_discount_percent: u8,
 }
 
 /// Per-model pricing entry (USD per 1M tokens).
@@ -827,6 +982,14 @@ pub struct ModelPricing {
     /// Output price per 1M tokens
     #[serde(default)]
     pub output: f64,
+
+    /// Price per 1M input tokens read from a prompt cache (default: `input`)
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+
+    /// Price per 1M input tokens written to a prompt cache (default: `input`)
+    #[serde(default)]
+    pub cache_write: Option<f64>,
 }
 
 fn default_daily_limit() -> f64 {
@@ -841,6 +1004,10 @@ fn default_warn_percent() -> u8 {
     80
 }
 
+fn default_batch_discount_percent() -> u8 {
+    50
+}
+
 impl Default for CostConfig {
     fn default() -> Self {
         Self {
@@ -850,6 +1017,7 @@ impl Default for CostConfig {
             warn_at_percent: default_warn_percent(),
             allow_override: false,
             prices: get_default_pricing(),
+            batch_discount_percent: default_batch_discount_percent(),
         }
     }
 }
@@ -864,6 +1032,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cached_input: Some(0.30),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -871,6 +1041,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 75.0,
+            cached_input: Some(1.50),
+            cache_write: Some(18.75),
         },
     );
     prices.insert(
@@ -878,6 +1050,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cached_input: Some(0.30),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -885,6 +1059,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.25,
             output: 1.25,
+            cached_input: Some(0.03),
+            cache_write: Some(0.30),
         },
     );
 
@@ -894,6 +1070,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 5.0,
             output: 15.0,
+            cached_input: Some(2.50),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -901,6 +1079,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.15,
             output: 0.60,
+            cached_input: Some(0.075),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -908,6 +1088,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 60.0,
+            cached_input: Some(7.50),
+            cache_write: None,
         },
     );
 
@@ -917,6 +1099,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.10,
             output: 0.40,
+            cached_input: Some(0.025),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -924,6 +1108,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 1.25,
             output: 5.0,
+            cached_input: Some(0.3125),
+            cache_write: None,
         },
     );
 
@@ -1897,6 +2083,52 @@ fn parse_proxy_enabled(raw: &str) -> Option<bool> {
         _ => None,
     }
 }
+// ── Knowledge ────────────────────────────────────────────────
+
+/// Workspace document ingestion for the `knowledge_search` tool (`[knowledge]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KnowledgeConfig {
+    /// Register the `knowledge_search` tool once documents have been ingested.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Folders (workspace-relative or absolute) ingested by `zeroclaw ingest` when no path is given.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Approximate token budget per chunk.
+    #[serde(default = "default_knowledge_chunk_max_tokens")]
+    pub chunk_max_tokens: usize,
+    /// Poll interval for `zeroclaw ingest --watch`.
+    #[serde(default = "default_knowledge_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+    /// Files larger than this are skipped.
+    #[serde(default = "default_knowledge_max_file_bytes")]
+    pub max_file_bytes: u64,
+}
+
+fn default_knowledge_chunk_max_tokens() -> usize {
+    512
+}
+
+fn default_knowledge_watch_interval_secs() -> u64 {
+    30
+}
+
+fn default_knowledge_max_file_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            paths: Vec::new(),
+            chunk_max_tokens: default_knowledge_chunk_max_tokens(),
+            watch_interval_secs: default_knowledge_watch_interval_secs(),
+            max_file_bytes: default_knowledge_max_file_bytes(),
+        }
+    }
+}
+
 // ── Memory ───────────────────────────────────────────────────
 
 /// Persistent storage configuration (`[storage]` section).
@@ -2010,10 +2242,198 @@ impl Default for QdrantConfig {
     }
 }
 
+/// Recall re-ranking applied before memories are injected into context
+/// (`[memory.ranking]`). Shared by every memory backend.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryRankingConfig {
+    /// Re-rank recalled memories (decay, boosts, diversity). Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Age in days at which a non-core entry's score is halved (0 = no decay). Default: 30
+    #[serde(default = "default_ranking_half_life_days")]
+    pub half_life_days: f64,
+    /// Lowest fraction of its score a decayed entry keeps (0.0–1.0). Default: 0.25
+    #[serde(default = "default_ranking_decay_floor")]
+    pub decay_floor: f64,
+    /// Score multiplier for `core` entries, which also never decay. Default: 1.2
+    #[serde(default = "default_ranking_core_boost")]
+    pub core_boost: f64,
+    /// Score multiplier for entries from the current session or channel sender. Default: 1.15
+    #[serde(default = "default_ranking_session_boost")]
+    pub session_boost: f64,
+    /// MMR trade-off between relevance (1.0) and diversity (0.0). Default: 0.7
+    #[serde(default = "default_ranking_mmr_lambda")]
+    pub mmr_lambda: f64,
+    /// Candidates recalled per returned entry, giving re-ranking room to work. Default: 3
+    #[serde(default = "default_ranking_candidate_multiplier")]
+    pub candidate_multiplier: usize,
+}
+
+fn default_ranking_half_life_days() -> f64 {
+    30.0
+}
+fn default_ranking_decay_floor() -> f64 {
+    0.25
+}
+fn default_ranking_core_boost() -> f64 {
+    1.2
+}
+fn default_ranking_session_boost() -> f64 {
+    1.15
+}
+fn default_ranking_mmr_lambda() -> f64 {
+    0.7
+}
+fn default_ranking_candidate_multiplier() -> usize {
+    3
+}
+
+impl Default for MemoryRankingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            half_life_days: default_ranking_half_life_days(),
+            decay_floor: default_ranking_decay_floor(),
+            core_boost: default_ranking_core_boost(),
+            session_boost: default_ranking_session_boost(),
+            mmr_lambda: default_ranking_mmr_lambda(),
+            candidate_multiplier: default_ranking_candidate_multiplier(),
+        }
+    }
+}
+
+/// Knowledge-graph memory backend settings (`[memory.graph]`).
+///
+/// Only used when `backend = "graph"`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryGraphConfig {
+    /// Relation hops followed from entities named in the query. Default: 2
+    #[serde(default = "default_graph_max_hops")]
+    pub max_hops: usize,
+    /// Weight of graph-neighbourhood evidence combined with the hybrid search score (0.0–1.0). Default: 0.5
+    #[serde(default = "default_graph_weight")]
+    pub graph_weight: f64,
+    /// Max relations traversed per recall. Default: 200
+    #[serde(default = "default_graph_max_relations")]
+    pub max_relations: usize,
+}
+
+fn default_graph_max_hops() -> usize {
+    2
+}
+fn default_graph_weight() -> f64 {
+    0.5
+}
+fn default_graph_max_relations() -> usize {
+    200
+}
+
+impl Default for MemoryGraphConfig {
+    fn default() -> Self {
+        Self {
+            max_hops: default_graph_max_hops(),
+            graph_weight: default_graph_weight(),
+            max_relations: default_graph_max_relations(),
+        }
+    }
+}
+
+/// How channel messages are split into memory namespaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryIsolation {
+    /// Every channel and sender shares one memory.
+    None,
+    /// One namespace per channel.
+    Channel,
+    /// One namespace per sender on each channel.
+    #[default]
+    Sender,
+}
+
+/// Multi-tenant memory namespaces (`[memory.namespaces]`).
+///
+/// Applies to channel messages; the CLI agent and gateway are unscoped.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryNamespaceConfig {
+    /// Namespace granularity: `none`, `channel` or `sender`. Default: `sender`
+    #[serde(default)]
+    pub isolation: MemoryIsolation,
+    /// Include the shared namespace when recalling for a sender. Default: true
+    #[serde(default = "default_true")]
+    pub recall_shared: bool,
+    /// Let the agent write to the shared namespace from a channel message. Default: false
+    #[serde(default)]
+    pub allow_shared_writes: bool,
+    /// Let the agent recall from every namespace (`scope = "all"`). Default: false
+    #[serde(default)]
+    pub allow_cross_namespace_recall: bool,
+}
+
+impl Default for MemoryNamespaceConfig {
+    fn default() -> Self {
+        Self {
+            isolation: MemoryIsolation::default(),
+            recall_shared: true,
+            allow_shared_writes: false,
+            allow_cross_namespace_recall: false,
+        }
+    }
+}
+
+/// Near-duplicate memory consolidation (`[memory.consolidation]`).
+///
+/// Used by `zeroclaw memory consolidate` and the scheduled consolidation job.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryConsolidationConfig {
+    /// Cosine similarity (0.0–1.0) at or above which `daily`/`conversation`
+    /// entries are treated as near-duplicates and merged. Default: 0.88
+    #[serde(default = "default_consolidation_similarity")]
+    pub similarity_threshold: f64,
+    /// Max entries scanned per pass (most recent first). Default: 500
+    #[serde(default = "default_consolidation_max_entries")]
+    pub max_entries: usize,
+    /// Max entries merged into a single consolidated entry. Default: 8
+    #[serde(default = "default_consolidation_max_cluster_size")]
+    pub max_cluster_size: usize,
+    /// Promote a merged entry to `core` when its sources span at least this many
+    /// distinct days (0 = only when the model marks it as a stable fact). Default: 3
+    #[serde(default = "default_consolidation_promote_after_days")]
+    pub promote_after_days: u32,
+    /// Model used for merge summaries. Defaults to `default_model`.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn default_consolidation_similarity() -> f64 {
+    0.88
+}
+fn default_consolidation_max_entries() -> usize {
+    500
+}
+fn default_consolidation_max_cluster_size() -> usize {
+    8
+}
+fn default_consolidation_promote_after_days() -> u32 {
+    3
+}
+
+impl Default for MemoryConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: default_consolidation_similarity(),
+            max_entries: default_consolidation_max_entries(),
+            max_cluster_size: default_consolidation_max_cluster_size(),
+            promote_after_days: default_consolidation_promote_after_days(),
+            model: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct MemoryConfig {
-    /// "sqlite" | "lucid" | "postgres" | "qdrant" | "markdown" | "none" (`none` = explicit no-op memory)
+    /// "sqlite" | "lucid" | "postgres" | "qdrant" | "graph" | "markdown" | "none" (`none` = explicit no-op memory)
     ///
     /// `postgres` requires `[storage.provider.config]` with `db_url` (`dbURL` alias supported).
     /// `qdrant` uses `[memory.qdrant]` config or `QDRANT_URL` env var.
@@ -2032,7 +2452,10 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "local" | "local:PATH" | "custom:URL"
+    ///
+    /// `local` embeds on-device with hashed n-grams (`embedding_dimensions` buckets);
+    /// `local:PATH` averages word vectors from a `.vec`/GloVe text file.
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
     /// Embedding model name (e.g. "text-embedding-3-small")
@@ -2092,6 +2515,26 @@ pub struct MemoryConfig {
     /// Only used when `backend = "qdrant"`.
     #[serde(default)]
     pub qdrant: QdrantConfig,
+
+    // ── Knowledge graph backend options ───────────────────────
+    /// Entity/relation graph settings. Only used when `backend = "graph"`.
+    #[serde(default)]
+    pub graph: MemoryGraphConfig,
+
+    // ── Recall ranking ─────────────────────────────────────────
+    /// Time decay, category/session boosts and MMR diversity for recall.
+    #[serde(default)]
+    pub ranking: MemoryRankingConfig,
+
+    // ── Namespaces ─────────────────────────────────────────────
+    /// Per-channel / per-sender memory isolation for channel messages.
+    #[serde(default)]
+    pub namespaces: MemoryNamespaceConfig,
+
+    // ── Consolidation ──────────────────────────────────────────
+    /// Near-duplicate clustering and merge settings.
+    #[serde(default)]
+    pub consolidation: MemoryConsolidationConfig,
 }
 
 fn default_embedding_provider() -> String {
@@ -2162,6 +2605,10 @@ impl Default for MemoryConfig {
             auto_hydrate: true,
             sqlite_open_timeout_secs: None,
             qdrant: QdrantConfig::default(),
+            graph: MemoryGraphConfig::default(),
+            ranking: MemoryRankingConfig::default(),
+            namespaces: MemoryNamespaceConfig::default(),
+            consolidation: MemoryConsolidationConfig::default(),
         }
     }
 }
@@ -2853,6 +3300,24 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Consecutive failed calls that open a provider's circuit breaker.
+    /// While open, the provider is tried only after healthy fallbacks. `0` disables breakers.
+    #[serde(default = "default_circuit_failure_threshold")]
+    pub circuit_failure_threshold: u32,
+    /// Seconds an open breaker waits before letting a half-open probe through.
+    #[serde(default = "default_circuit_cooldown_secs")]
+    pub circuit_cooldown_secs: u64,
+    /// Successful half-open probes needed to close the breaker again.
+    #[serde(default = "default_circuit_half_open_successes")]
+    pub circuit_half_open_successes: u32,
+    /// Hedge channel replies: if the primary provider has not answered after
+    /// this many milliseconds, also ask the next fallback provider and keep
+    /// whichever answers first. `0` disables hedging.
+    ///
+    /// Streamed replies race on their first stream event; non-streamed replies
+    /// race on the whole completion.
+    #[serde(default)]
+    pub channel_hedge_delay_ms: u64,
 }
 
 fn default_provider_retries() -> u32 {
@@ -2879,6 +3344,18 @@ fn default_scheduler_retries() -> u32 {
     2
 }
 
+fn default_circuit_failure_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_circuit_half_open_successes() -> u32 {
+    1
+}
+
 impl Default for ReliabilityConfig {
     fn default() -> Self {
         Self {
@@ -2891,6 +3368,10 @@ impl Default for ReliabilityConfig {
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            circuit_failure_threshold: default_circuit_failure_threshold(),
+            circuit_cooldown_secs: default_circuit_cooldown_secs(),
+            circuit_half_open_successes: default_circuit_half_open_successes(),
+            channel_hedge_delay_ms: 0,
         }
     }
 }
@@ -2909,6 +3390,42 @@ pub struct SchedulerConfig {
     /// Maximum tasks executed per scheduler polling cycle.
     #[serde(default = "default_scheduler_max_concurrent")]
     pub max_concurrent: usize,
+    /// Batch API execution for agent jobs (`[scheduler.batch]`).
+    #[serde(default)]
+    pub batch: SchedulerBatchConfig,
+}
+
+/// Batch API execution for non-interactive agent jobs (`[scheduler.batch]`).
+///
+/// Listed jobs are submitted to the provider's discounted batch endpoint
+/// (OpenAI, Anthropic, Bedrock) as single completions of the job prompt,
+/// without system prompt, tools or memory, polled until done, and delivered
+/// like any other run.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SchedulerBatchConfig {
+    /// Submit eligible agent jobs through the provider batch API.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between status checks of a submitted batch.
+    #[serde(default = "default_scheduler_batch_poll_secs")]
+    pub poll_secs: u64,
+    /// Job IDs or names to batch. Empty batches nothing.
+    #[serde(default)]
+    pub jobs: Vec<String>,
+}
+
+fn default_scheduler_batch_poll_secs() -> u64 {
+    300
+}
+
+impl Default for SchedulerBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_secs: default_scheduler_batch_poll_secs(),
+            jobs: Vec::new(),
+        }
+    }
 }
 
 fn default_scheduler_enabled() -> bool {
@@ -2929,6 +3446,7 @@ impl Default for SchedulerConfig {
             enabled: default_scheduler_enabled(),
             max_tasks: default_scheduler_max_tasks(),
             max_concurrent: default_scheduler_max_concurrent(),
+            batch: SchedulerBatchConfig::default(),
         }
     }
 }
@@ -2967,6 +3485,77 @@ pub struct ModelRouteConfig {
     pub api_key: Option<String>,
 }
 
+/// Adaptive route selection (`[model_routing]` section).
+///
+/// With `adaptive = true`, several `[[model_routes]]` entries may share one
+/// hint. The router keeps rolling latency and error stats per route and sends
+/// each `hint:` request to the cheapest route (per `[cost.prices]`) whose p95
+/// latency and error rate stay within the limits below.
+///
+/// ```toml
+/// [model_routing]
+/// adaptive = true
+/// latency_slo_ms = 8000
+///
+/// [[model_routes]]
+/// hint = "fast"
+/// provider = "groq"
+/// model = "llama-3.3-70b-versatile"
+///
+/// [[model_routes]]
+/// hint = "fast"
+/// provider = "openai"
+/// model = "gpt-4o-mini"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelRoutingConfig {
+    /// Pick among same-hint routes by latency, error rate and price (default: false).
+    /// When disabled, the last route declared for a hint wins.
+    #[serde(default)]
+    pub adaptive: bool,
+    /// Target p95 latency per request in milliseconds (default: 20000).
+    #[serde(default = "default_model_routing_latency_slo_ms")]
+    pub latency_slo_ms: u64,
+    /// Highest recent error rate (0.0-1.0) a route may have to stay eligible (default: 0.25).
+    #[serde(default = "default_model_routing_max_error_rate")]
+    pub max_error_rate: f64,
+    /// Number of recent calls kept per route for the rolling stats (default: 50).
+    #[serde(default = "default_model_routing_window")]
+    pub window: usize,
+    /// Calls a route needs before its stats are trusted (default: 5).
+    /// Routes below this count stay eligible so they can be measured.
+    #[serde(default = "default_model_routing_min_samples")]
+    pub min_samples: usize,
+}
+
+fn default_model_routing_latency_slo_ms() -> u64 {
+    20_000
+}
+
+fn default_model_routing_max_error_rate() -> f64 {
+    0.25
+}
+
+fn default_model_routing_window() -> usize {
+    50
+}
+
+fn default_model_routing_min_samples() -> usize {
+    5
+}
+
+impl Default for ModelRoutingConfig {
+    fn default() -> Self {
+        Self {
+            adaptive: false,
+            latency_slo_ms: default_model_routing_latency_slo_ms(),
+            max_error_rate: default_model_routing_max_error_rate(),
+            window: default_model_routing_window(),
+            min_samples: default_model_routing_min_samples(),
+        }
+    }
+}
+
 // ── Embedding routing ───────────────────────────────────────────
 
 /// Route an embedding hint to a specific provider + model.
@@ -3009,6 +3598,10 @@ pub struct QueryClassificationConfig {
     /// Classification rules evaluated in priority order.
     #[serde(default)]
     pub rules: Vec<ClassificationRule>,
+    /// Model on the default provider asked to pick one of the rules' hints
+    /// when no rule matches. Default: unset (rules only).
+    #[serde(default)]
+    pub fallback_model: Option<String>,
 }
 
 /// A single classification rule mapping message patterns to a model hint.
@@ -3249,10 +3842,18 @@ pub struct ChannelsConfig {
     pub wati: Option<WatiConfig>,
     /// Nextcloud Talk bot channel configuration.
     pub nextcloud_talk: Option<NextcloudTalkConfig>,
+    /// Microsoft Teams bot channel configuration.
+    pub teams: Option<TeamsConfig>,
     /// Email channel configuration.
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
     pub irc: Option<IrcConfig>,
+    /// XMPP channel configuration.
+    pub xmpp: Option<XmppConfig>,
+    /// Zulip channel configuration.
+    pub zulip: Option<ZulipConfig>,
+    /// Rocket.Chat channel configuration.
+    pub rocketchat: Option<RocketChatConfig>,
     /// Lark channel configuration.
     pub lark: Option<LarkConfig>,
     /// Feishu channel configuration.
@@ -3271,6 +3872,12 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
+    /// Conversation history persistence and retention.
+    #[serde(default)]
+    pub sessions: ChannelSessionsConfig,
+    /// Size and MIME limits for media attachments.
+    #[serde(default)]
+    pub attachments: ChannelAttachmentsConfig,
 }
 
 impl ChannelsConfig {
@@ -3322,6 +3929,10 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(self.nextcloud_talk.as_ref())),
                 self.nextcloud_talk.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(self.teams.as_ref())),
+                self.teams.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(self.email.as_ref())),
                 self.email.is_some(),
@@ -3330,6 +3941,18 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(self.irc.as_ref())),
                 self.irc.is_some()
             ),
+            (
+                Box::new(ConfigWrapper::new(self.xmpp.as_ref())),
+                self.xmpp.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(self.zulip.as_ref())),
+                self.zulip.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(self.rocketchat.as_ref())),
+                self.rocketchat.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(self.lark.as_ref())),
                 self.lark.is_some(),
@@ -3389,8 +4012,12 @@ impl Default for ChannelsConfig {
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            teams: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            rocketchat: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -3398,6 +4025,82 @@ impl Default for ChannelsConfig {
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
+            sessions: ChannelSessionsConfig::default(),
+            attachments: ChannelAttachmentsConfig::default(),
+        }
+    }
+}
+
+/// Persistence and retention of channel conversation histories
+/// (`[channels_config.sessions]` section).
+///
+/// Per-sender histories are mirrored to `<workspace>/sessions/sessions.db`
+/// and restored when channels start, so a daemon restart keeps context.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelSessionsConfig {
+    /// Persist channel conversation histories across restarts. Default: `true`.
+    #[serde(default = "default_true")]
+    pub persist: bool,
+    /// Drop sessions idle for longer than this many hours. `0` keeps them
+    /// forever. Default: `168` (7 days).
+    #[serde(default = "default_channel_session_retention_hours")]
+    pub retention_hours: u64,
+    /// Per-channel retention overrides in hours, keyed by channel name
+    /// (e.g. `telegram = 24`). `0` keeps that channel's sessions forever.
+    #[serde(default)]
+    pub channel_retention_hours: HashMap<String, u64>,
+}
+
+fn default_channel_session_retention_hours() -> u64 {
+    168
+}
+
+impl ChannelSessionsConfig {
+    /// Effective retention for `channel`, honoring per-channel overrides.
+    pub fn retention_hours_for(&self, channel: &str) -> u64 {
+        self.channel_retention_hours
+            .get(channel)
+            .copied()
+            .unwrap_or(self.retention_hours)
+    }
+}
+
+impl Default for ChannelSessionsConfig {
+    fn default() -> Self {
+        Self {
+            persist: true,
+            retention_hours: default_channel_session_retention_hours(),
+            channel_retention_hours: HashMap::new(),
+        }
+    }
+}
+
+/// Media attachment policy for all channels
+/// (`[channels_config.attachments]`).
+///
+/// Applies to inbound media and to attachments in replies. Rejected
+/// attachments are dropped from inbound messages and sent as links instead
+/// of uploads.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelAttachmentsConfig {
+    /// Largest attachment in megabytes. `0` disables the limit. Default: `25`.
+    #[serde(default = "default_channel_attachment_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Accepted MIME types, exact (`application/pdf`) or by prefix
+    /// (`image/*`). Empty accepts every type. Default: `[]`.
+    #[serde(default)]
+    pub allowed_mime_types: Vec<String>,
+}
+
+fn default_channel_attachment_max_size_mb() -> u64 {
+    crate::channels::attachments::DEFAULT_MAX_ATTACHMENT_MB
+}
+
+impl Default for ChannelAttachmentsConfig {
+    fn default() -> Self {
+        Self {
+            max_size_mb: default_channel_attachment_max_size_mb(),
+            allowed_mime_types: Vec::new(),
         }
     }
 }
@@ -3889,6 +4592,31 @@ impl ChannelConfig for NextcloudTalkConfig {
     }
 }
 
+/// Microsoft Teams bot configuration (Bot Framework webhook receive + connector send).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TeamsConfig {
+    /// Microsoft App ID of the Azure Bot registration.
+    pub app_id: String,
+    /// Client secret of the App ID, used to obtain connector API tokens.
+    pub app_password: String,
+    /// Azure AD tenant ID for single-tenant bots. When set, connector tokens
+    /// are requested from this tenant and activities from other tenants are ignored.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Allowed AAD object IDs or Teams user IDs (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+impl ChannelConfig for TeamsConfig {
+    fn name() -> &'static str {
+        "Microsoft Teams"
+    }
+    fn desc() -> &'static str {
+        "Teams via Azure Bot Service"
+    }
+}
+
 impl WhatsAppConfig {
     /// Detect which backend to use based on config fields.
     /// Returns "cloud" if phone_number_id is set, "web" if session_path is set.
@@ -3962,6 +4690,172 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// How the XMPP channel secures its connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum XmppTlsMode {
+    /// Plain connection upgraded with STARTTLS (port 5222).
+    #[default]
+    Starttls,
+    /// TLS from the first byte, per XEP-0368 (port 5223).
+    Direct,
+    /// No TLS. Only accepted for servers on a loopback address.
+    None,
+}
+
+/// XMPP channel configuration (direct client-to-server stream).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct XmppConfig {
+    /// Bot account JID (`user@domain`)
+    pub jid: String,
+    /// Account password, sent with SASL PLAIN over TLS
+    pub password: String,
+    /// Server hostname (defaults to the JID domain)
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Server port (default: 5222, or 5223 for direct TLS)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Connection security: `starttls` (default), `direct` or `none`
+    #[serde(default)]
+    pub tls: XmppTlsMode,
+    /// Verify TLS certificate (default: true)
+    #[serde(default)]
+    pub verify_tls: Option<bool>,
+    /// Resource bound for the session (default: "zeroclaw")
+    #[serde(default = "default_xmpp_resource")]
+    pub resource: String,
+    /// Nickname used in rooms (defaults to the JID localpart)
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Multi-user chat rooms to join (`room@conference.example.org`)
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Allowed bare JIDs, room nicknames or occupant JIDs (`room@service/nick`),
+    /// case-insensitive, or "*" for all
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Room trigger controls. Rooms default to mention-only.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+}
+
+impl XmppConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::MentionOnly)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+impl ChannelConfig for XmppConfig {
+    fn name() -> &'static str {
+        "XMPP"
+    }
+    fn desc() -> &'static str {
+        "XMPP/Jabber with MUC rooms"
+    }
+}
+
+fn default_xmpp_resource() -> String {
+    "zeroclaw".into()
+}
+
+/// Zulip channel configuration (bot account, events API).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ZulipConfig {
+    /// Zulip server URL (e.g. "https://chat.zulip.org")
+    pub site_url: String,
+    /// Bot account email
+    pub bot_email: String,
+    /// Bot API key
+    pub api_key: String,
+    /// Allowed sender emails or user IDs (case-insensitive), or "*" for all
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Stream trigger controls. Streams default to mention-only.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+}
+
+impl ZulipConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::MentionOnly)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+impl ChannelConfig for ZulipConfig {
+    fn name() -> &'static str {
+        "Zulip"
+    }
+    fn desc() -> &'static str {
+        "Zulip streams and topics"
+    }
+}
+
+/// Rocket.Chat channel configuration (realtime API plus REST replies).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RocketChatConfig {
+    /// Rocket.Chat server URL (e.g. "https://chat.example.com")
+    pub server_url: String,
+    /// Bot user ID (`X-User-Id`)
+    pub user_id: String,
+    /// Personal access token (`X-Auth-Token`)
+    pub auth_token: String,
+    /// Allowed usernames (case-insensitive) or user IDs, or "*" for all
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// When true (default), top-level room messages are answered in a thread.
+    #[serde(default)]
+    pub thread_replies: Option<bool>,
+    /// Room trigger controls. Rooms default to mention-only.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+}
+
+impl RocketChatConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::MentionOnly)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+impl ChannelConfig for RocketChatConfig {
+    fn name() -> &'static str {
+        "Rocket.Chat"
+    }
+    fn desc() -> &'static str {
+        "Rocket.Chat rooms and threads"
+    }
+}
+
 /// How ZeroClaw receives events from Feishu / Lark.
 ///
 /// - `websocket` (default) — persistent WSS long-connection; no public URL required.
@@ -4630,12 +5524,14 @@ impl Default for Config {
             agent: AgentConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
+            model_routing: ModelRoutingConfig::default(),
             embedding_routes: Vec::new(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
             goal_loop: GoalLoopConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
+            knowledge: KnowledgeConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
@@ -4657,8 +5553,10 @@ impl Default for Config {
             hardware: HardwareConfig::default(),
             query_classification: QueryClassificationConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             model_support_vision: None,
+            model_context_window: None,
         }
     }
 }
@@ -5097,6 +5995,13 @@ fn decrypt_channel_secrets(
             "config.channels_config.nextcloud_talk.webhook_secret",
         )?;
     }
+    if let Some(ref mut teams) = channels.teams {
+        decrypt_secret(
+            store,
+            &mut teams.app_password,
+            "config.channels_config.teams.app_password",
+        )?;
+    }
     if let Some(ref mut irc) = channels.irc {
         decrypt_optional_secret(
             store,
@@ -5114,6 +6019,27 @@ fn decrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        decrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        decrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
+    if let Some(ref mut rocketchat) = channels.rocketchat {
+        decrypt_secret(
+            store,
+            &mut rocketchat.auth_token,
+            "config.channels_config.rocketchat.auth_token",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         decrypt_secret(
             store,
@@ -5259,6 +6185,13 @@ fn encrypt_channel_secrets(
             "config.channels_config.nextcloud_talk.webhook_secret",
         )?;
     }
+    if let Some(ref mut teams) = channels.teams {
+        encrypt_secret(
+            store,
+            &mut teams.app_password,
+            "config.channels_config.teams.app_password",
+        )?;
+    }
     if let Some(ref mut irc) = channels.irc {
         encrypt_optional_secret(
             store,
@@ -5276,6 +6209,27 @@ fn encrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        encrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        encrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
+    if let Some(ref mut rocketchat) = channels.rocketchat {
+        encrypt_secret(
+            store,
+            &mut rocketchat.auth_token,
+            "config.channels_config.rocketchat.auth_token",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         encrypt_secret(
             store,
@@ -5878,6 +6832,15 @@ impl Config {
                 anyhow::bail!("model_routes[{i}].max_tokens must be greater than 0");
             }
         }
+        if self.model_routing.latency_slo_ms == 0 {
+            anyhow::bail!("model_routing.latency_slo_ms must be greater than 0");
+        }
+        if !(0.0..=1.0).contains(&self.model_routing.max_error_rate) {
+            anyhow::bail!("model_routing.max_error_rate must be between 0.0 and 1.0");
+        }
+        if self.model_routing.window == 0 {
+            anyhow::bail!("model_routing.window must be greater than 0");
+        }
 
         if self.provider_api.is_some()
             && !self
@@ -6174,6 +7137,17 @@ impl Config {
             }
         }
 
+        // Context window override: ZEROCLAW_MODEL_CONTEXT_WINDOW or MODEL_CONTEXT_WINDOW
+        if let Ok(tokens) = std::env::var("ZEROCLAW_MODEL_CONTEXT_WINDOW")
+            .or_else(|_| std::env::var("MODEL_CONTEXT_WINDOW"))
+        {
+            if let Ok(tokens) = tokens.trim().parse::<usize>() {
+                if tokens > 0 {
+                    self.model_context_window = Some(tokens);
+                }
+            }
+        }
+
         // Web search enabled: ZEROCLAW_WEB_SEARCH_ENABLED or WEB_SEARCH_ENABLED
         if let Ok(enabled) = std::env::var("ZEROCLAW_WEB_SEARCH_ENABLED")
             .or_else(|_| std::env::var("WEB_SEARCH_ENABLED"))
@@ -6625,6 +7599,7 @@ mod tests {
                 model: "model-test".into(),
                 system_prompt: None,
                 api_key: Some("agent-credential".into()),
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -6949,6 +7924,7 @@ default_temperature = 0.7
             coordination: CoordinationConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
+            model_routing: ModelRoutingConfig::default(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig {
@@ -6983,8 +7959,12 @@ default_temperature = 0.7
                 linq: None,
                 wati: None,
                 nextcloud_talk: None,
+                teams: None,
                 email: None,
                 irc: None,
+                xmpp: None,
+                zulip: None,
+                rocketchat: None,
                 lark: None,
                 feishu: None,
                 dingtalk: None,
@@ -6992,8 +7972,11 @@ default_temperature = 0.7
                 nostr: None,
                 clawdtalk: None,
                 message_timeout_secs: 300,
+                sessions: ChannelSessionsConfig::default(),
+                attachments: ChannelAttachmentsConfig::default(),
             },
             memory: MemoryConfig::default(),
+            knowledge: KnowledgeConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
@@ -7009,12 +7992,15 @@ default_temperature = 0.7
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
             peripherals: PeripheralsConfig::default(),
+            skillforge: SkillForgeConfig::default(),
             agents: HashMap::new(),
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             model_support_vision: None,
+            model_context_window: None,
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -7355,6 +8341,7 @@ tool_dispatcher = "xml"
             coordination: CoordinationConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
+            model_routing: ModelRoutingConfig::default(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig::default(),
@@ -7362,6 +8349,7 @@ tool_dispatcher = "xml"
             goal_loop: GoalLoopConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
+            knowledge: KnowledgeConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
@@ -7377,12 +8365,15 @@ tool_dispatcher = "xml"
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
             peripherals: PeripheralsConfig::default(),
+            skillforge: SkillForgeConfig::default(),
             agents: HashMap::new(),
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
             model_support_vision: None,
+            model_context_window: None,
         };
 
         config.save().await.unwrap();
@@ -7442,6 +8433,7 @@ tool_dispatcher = "xml"
                 model: "model-test".into(),
                 system_prompt: None,
                 api_key: Some("agent-credential".into()),
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -7875,8 +8867,12 @@ allowed_users = ["@ops:matrix.org"]
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            teams: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            rocketchat: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -7884,6 +8880,8 @@ allowed_users = ["@ops:matrix.org"]
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            sessions: ChannelSessionsConfig::default(),
+            attachments: ChannelAttachmentsConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -8153,8 +9151,12 @@ channel_id = "C123"
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            teams: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            rocketchat: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -8162,6 +9164,8 @@ channel_id = "C123"
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            sessions: ChannelSessionsConfig::default(),
+            attachments: ChannelAttachmentsConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -8745,6 +9749,33 @@ provider_api = "not-a-real-mode"
             .contains("model_routes[0].max_tokens must be greater than 0"));
     }
 
+    #[test]
+    async fn model_routing_defaults_and_rejects_bad_error_rate() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[model_routing]
+adaptive = true
+latency_slo_ms = 8000
+"#,
+        )
+        .expect("model_routing section should parse");
+        assert!(parsed.model_routing.adaptive);
+        assert_eq!(parsed.model_routing.latency_slo_ms, 8000);
+        assert_eq!(parsed.model_routing.window, 50);
+        assert_eq!(parsed.model_routing.min_samples, 5);
+
+        let mut config = Config::default();
+        config.model_routing.max_error_rate = 1.5;
+        let err = config
+            .validate()
+            .expect_err("max_error_rate above 1.0 should be rejected");
+        assert!(err
+            .to_string()
+            .contains("model_routing.max_error_rate must be between 0.0 and 1.0"));
+    }
+
     #[test]
     async fn env_override_glm_api_key_for_regional_aliases() {
         let _env_guard = env_override_lock().await;
@@ -9407,6 +10438,23 @@ default_model = "legacy-model"
         std::env::remove_var("ZEROCLAW_MODEL_SUPPORT_VISION");
     }
 
+    #[test]
+    async fn env_override_model_context_window() {
+        let _env_guard = env_override_lock().await;
+        let mut config = Config::default();
+        assert_eq!(config.model_context_window, None);
+
+        std::env::set_var("ZEROCLAW_MODEL_CONTEXT_WINDOW", "8192");
+        config.apply_env_overrides();
+        assert_eq!(config.model_context_window, Some(8192));
+
+        std::env::set_var("ZEROCLAW_MODEL_CONTEXT_WINDOW", "lots");
+        config.apply_env_overrides();
+        assert_eq!(config.model_context_window, Some(8192));
+
+        std::env::remove_var("ZEROCLAW_MODEL_CONTEXT_WINDOW");
+    }
+
     #[test]
     async fn env_override_invalid_port_ignored() {
         let _env_guard = env_override_lock().await;
@@ -9901,6 +10949,87 @@ default_model = "legacy-model"
         assert!(parsed.allowed_users.is_empty());
     }
 
+    #[test]
+    async fn teams_config_defaults_optional_fields() {
+        let toml_str = r#"
+app_id = "00000000-0000-0000-0000-000000000001"
+app_password = "secret"
+"#;
+        let parsed: TeamsConfig = toml::from_str(toml_str).unwrap();
+        assert!(parsed.tenant_id.is_none());
+        assert!(parsed.allowed_users.is_empty());
+    }
+
+    #[test]
+    async fn xmpp_config_defaults_to_starttls_and_mention_only_rooms() {
+        let toml_str = r#"
+jid = "bot@example.org"
+password = "secret"
+rooms = ["ops@conference.example.org"]
+"#;
+        let parsed: XmppConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(parsed.tls, XmppTlsMode::Starttls);
+        assert_eq!(parsed.resource, "zeroclaw");
+        assert!(parsed.server.is_none());
+        assert!(parsed.port.is_none());
+        assert_eq!(
+            parsed.effective_group_reply_mode(),
+            GroupReplyMode::MentionOnly
+        );
+
+        let parsed: XmppConfig = toml::from_str(
+            r#"
+jid = "bot@localhost"
+password = "secret"
+tls = "none"
+group_reply = { mode = "all_messages" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.tls, XmppTlsMode::None);
+        assert_eq!(
+            parsed.effective_group_reply_mode(),
+            GroupReplyMode::AllMessages
+        );
+    }
+
+    #[test]
+    async fn zulip_and_rocketchat_configs_default_to_mention_only_without_streaming() {
+        let zulip: ZulipConfig = toml::from_str(
+            r#"
+site_url = "https://zulip.example.com"
+bot_email = "zeroclaw-bot@zulip.example.com"
+api_key = "key"
+"#,
+        )
+        .unwrap();
+        assert!(zulip.allowed_users.is_empty());
+        assert_eq!(zulip.stream_mode, StreamMode::Off);
+        assert_eq!(zulip.draft_update_interval_ms, 1000);
+        assert_eq!(
+            zulip.effective_group_reply_mode(),
+            GroupReplyMode::MentionOnly
+        );
+
+        let rocketchat: RocketChatConfig = toml::from_str(
+            r#"
+server_url = "https://chat.example.com"
+user_id = "bot-id"
+auth_token = "token"
+stream_mode = "partial"
+group_reply = { mode = "all_messages", allowed_sender_ids = ["alice"] }
+"#,
+        )
+        .unwrap();
+        assert!(rocketchat.thread_replies.is_none());
+        assert_eq!(rocketchat.stream_mode, StreamMode::Partial);
+        assert_eq!(
+            rocketchat.effective_group_reply_mode(),
+            GroupReplyMode::AllMessages
+        );
+        assert_eq!(rocketchat.group_reply_allowed_sender_ids(), vec!["alice"]);
+    }
+
     // ── Config file permission hardening (Unix only) ───────────────
 
     #[cfg(unix)]
@@ -10014,6 +11143,33 @@ default_model = "legacy-model"
         assert_eq!(parsed.transcription.max_duration_secs, 120);
     }
 
+    #[test]
+    async fn tts_config_parses_channel_modes() {
+        let toml_str = r#"
+            default_temperature = 0.7
+            [tts]
+            enabled = true
+            provider = "command"
+            command = "piper"
+            args = ["--output_file", "{output}"]
+            [tts.channels]
+            telegram = "in_kind"
+            signal = "always"
+        "#;
+        let parsed: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(parsed.tts.provider, TtsProvider::Command);
+        assert_eq!(parsed.tts.reply_mode("telegram"), TtsReplyMode::InKind);
+        assert_eq!(parsed.tts.reply_mode("signal"), TtsReplyMode::Always);
+        assert_eq!(parsed.tts.reply_mode("discord"), TtsReplyMode::Off);
+        assert_eq!(parsed.tts.format, "opus");
+
+        let disabled = TtsConfig {
+            enabled: false,
+            ..parsed.tts
+        };
+        assert_eq!(disabled.reply_mode("telegram"), TtsReplyMode::Off);
+    }
+
     #[test]
     async fn security_defaults_are_backward_compatible() {
         let parsed: Config = toml::from_str(
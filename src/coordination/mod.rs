@@ -161,9 +161,7 @@ impl CoordinationEnvelope {
                 if self
                     .correlation_id
                     .as_deref()
-                    .map(str::trim)
-                    .filter(|value| !value.is_empty())
-                    .is_none()
+                    .is_none_or(|value| value.trim().is_empty())
                 {
                     return Err(CoordinationError::MissingCorrelationId {
                         message_id: self.id.clone(),
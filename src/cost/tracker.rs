@@ -1,5 +1,5 @@
 use super::types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
-use crate::config::schema::CostConfig;
+use crate::config::schema::{CostConfig, ModelPricing};
 use anyhow::{anyhow, Context, Result};
 use chrono::{Datelike, NaiveDate, Utc};
 use parking_lot::{Mutex, MutexGuard};
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 /// Cost tracker for API usage monitoring and budget enforcement.
 pub struct CostTracker {
@@ -133,6 +133,81 @@ impl CostTracker {
         Ok(())
     }
 
+    /// Record usage reported by a provider response, priced from
+    /// `[cost.prices]` with prompt-cache reads and writes at their own rates.
+    pub fn record_provider_usage(
+        &self,
+        model: &str,
+        usage: &crate::providers::traits::TokenUsage,
+    ) -> Result<()> {
+        let usage = TokenUsage::with_cache(
+            model,
+            usage.input_tokens.unwrap_or(0),
+            usage.output_tokens.unwrap_or(0),
+            usage.cached_input_tokens.unwrap_or(0),
+            usage.cache_write_tokens.unwrap_or(0),
+            &self.pricing_for(model),
+        );
+        self.record_usage(usage)
+    }
+
+    /// Record usage from a provider batch API, priced like
+    /// [`Self::record_provider_usage`] less `[cost] batch_discount_percent`.
+    pub fn record_batch_usage(
+        &self,
+        model: &str,
+        usage: &crate::providers::traits::TokenUsage,
+    ) -> Result<()> {
+        let mut usage = TokenUsage::with_cache(
+            model,
+            usage.input_tokens.unwrap_or(0),
+            usage.output_tokens.unwrap_or(0),
+            usage.cached_input_tokens.unwrap_or(0),
+            usage.cache_write_tokens.unwrap_or(0),
+            &self.pricing_for(model),
+        );
+        let discount = f64::from(self.config.batch_discount_percent.min(100)) / 100.0;
+        usage.cost_usd *= 1.0 - discount;
+        self.record_usage(usage)
+    }
+
+    /// Record speech synthesis for `characters` of text. TTS is billed per
+    /// character, so the model's `[cost.prices]` input rate is read as USD per
+    /// million characters.
+    pub fn record_tts_usage(&self, model: &str, characters: u64) -> Result<()> {
+        let pricing = self.pricing_for(model);
+        self.record_usage(TokenUsage::new(model, characters, 0, pricing.input, 0.0))
+    }
+
+    /// Look up pricing for a model. Keys may carry a provider prefix
+    /// (`anthropic/claude-...`) that the runtime model name lacks.
+    fn pricing_for(&self, model: &str) -> ModelPricing {
+        let bare = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+        self.config
+            .prices
+            .get(model)
+            .or_else(|| {
+                self.config
+                    .prices
+                    .iter()
+                    .find(|(key, _)| bare(key) == bare(model))
+                    .map(|(_, pricing)| pricing)
+            })
+            .cloned()
+            .unwrap_or_else(|| {
+                tracing::debug!(
+                    model,
+                    "No pricing configured for model; recording zero cost"
+                );
+                ModelPricing {
+                    input: 0.0,
+                    output: 0.0,
+                    cached_input: None,
+                    cache_write: None,
+                }
+            })
+    }
+
     /// Get the current cost summary.
     pub fn get_summary(&self) -> Result<CostSummary> {
         let (daily_cost, monthly_cost) = {
@@ -175,6 +250,29 @@ impl CostTracker {
     }
 }
 
+static SHARED_TRACKER: OnceLock<Arc<CostTracker>> = OnceLock::new();
+
+/// Create the process-wide tracker when cost tracking is enabled.
+///
+/// Long-running runtimes (gateway, channels, provider hedging) share it so
+/// their records land in one session instead of racing separate trackers.
+pub fn init_shared(config: &CostConfig, workspace_dir: &Path) {
+    if !config.enabled || SHARED_TRACKER.get().is_some() {
+        return;
+    }
+    match CostTracker::new(config.clone(), workspace_dir) {
+        Ok(tracker) => {
+            let _ = SHARED_TRACKER.set(Arc::new(tracker));
+        }
+        Err(e) => tracing::warn!("Failed to initialize cost tracker: {e}"),
+    }
+}
+
+/// The process-wide tracker, if [`init_shared`] created one.
+pub fn shared() -> Option<Arc<CostTracker>> {
+    SHARED_TRACKER.get().cloned()
+}
+
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
     let storage_path = workspace_dir.join("state").join("costs.jsonl");
     let legacy_path = workspace_dir.join(".zeroclaw").join("costs.db");
@@ -447,6 +545,54 @@ mod tests {
         assert_eq!(summary.by_model.len(), 1);
     }
 
+    #[test]
+    fn record_batch_usage_applies_discount() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = enabled_config();
+        config.prices.insert(
+            "batch/model".into(),
+            ModelPricing {
+                input: 2.0,
+                output: 4.0,
+                cached_input: None,
+                cache_write: None,
+            },
+        );
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+
+        let usage = crate::providers::traits::TokenUsage {
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(500_000),
+            ..Default::default()
+        };
+        tracker.record_batch_usage("batch/model", &usage).unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert!((summary.session_cost_usd - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn record_tts_usage_prices_per_million_characters() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = enabled_config();
+        config.prices.insert(
+            "tts-1".into(),
+            ModelPricing {
+                input: 15.0,
+                output: 0.0,
+                cached_input: None,
+                cache_write: None,
+            },
+        );
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+
+        tracker.record_tts_usage("tts-1", 2_000).unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert!((summary.session_cost_usd - 0.03).abs() < 1e-9);
+        assert_eq!(summary.by_model["tts-1"].request_count, 1);
+    }
+
     #[test]
     fn budget_exceeded_daily_limit() {
         let tmp = TempDir::new().unwrap();
@@ -523,6 +669,27 @@ mod tests {
         assert!((today_cost - valid_usage.cost_usd).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn provider_usage_prices_cached_tokens_at_discount() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let cached = crate::providers::traits::TokenUsage {
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(0),
+            cached_input_tokens: Some(1_000_000),
+            cache_write_tokens: None,
+        };
+        tracker
+            .record_provider_usage("claude-sonnet-4-20250514", &cached)
+            .unwrap();
+
+        // Default pricing: $3.00/M input, $0.30/M cache reads.
+        let summary = tracker.get_summary().unwrap();
+        assert!((summary.session_cost_usd - 0.30).abs() < 1e-9);
+        assert!(summary.by_model.contains_key("claude-sonnet-4-20250514"));
+    }
+
     #[test]
     fn invalid_budget_estimate_is_rejected() {
         let tmp = TempDir::new().unwrap();
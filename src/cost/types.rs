@@ -1,3 +1,4 @@
+use crate::config::schema::ModelPricing;
 use serde::{Deserialize, Serialize};
 
 /// Token usage information from a single API call.
@@ -9,6 +10,12 @@ pub struct TokenUsage {
     pub input_tokens: u64,
     /// Output/completion tokens
     pub output_tokens: u64,
+    /// Input tokens read from a prompt cache (included in `input_tokens`)
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+    /// Input tokens written to a prompt cache (included in `input_tokens`)
+    #[serde(default)]
+    pub cache_write_tokens: u64,
     /// Total tokens
     pub total_tokens: u64,
     /// Calculated cost in USD
@@ -48,6 +55,54 @@ impl TokenUsage {
             model,
             input_tokens,
             output_tokens,
+            cached_input_tokens: 0,
+            cache_write_tokens: 0,
+            total_tokens,
+            cost_usd,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create a usage record priced with cache-aware rates.
+    ///
+    /// `input_tokens` is the full prompt; cache reads and writes are carved
+    /// out of it and billed at `pricing.cached_input` / `pricing.cache_write`
+    /// (falling back to the regular input price when unset).
+    pub fn with_cache(
+        model: impl Into<String>,
+        input_tokens: u64,
+        output_tokens: u64,
+        cached_input_tokens: u64,
+        cache_write_tokens: u64,
+        pricing: &ModelPricing,
+    ) -> Self {
+        let model = model.into();
+        let input_price = Self::sanitize_price(pricing.input);
+        let output_price = Self::sanitize_price(pricing.output);
+        let cached_price = pricing
+            .cached_input
+            .map_or(input_price, Self::sanitize_price);
+        let write_price = pricing
+            .cache_write
+            .map_or(input_price, Self::sanitize_price);
+
+        let cached_input_tokens = cached_input_tokens.min(input_tokens);
+        let cache_write_tokens = cache_write_tokens.min(input_tokens - cached_input_tokens);
+        let uncached_tokens = input_tokens - cached_input_tokens - cache_write_tokens;
+        let total_tokens = input_tokens.saturating_add(output_tokens);
+
+        let per_million = |tokens: u64, price: f64| (tokens as f64 / 1_000_000.0) * price;
+        let cost_usd = per_million(uncached_tokens, input_price)
+            + per_million(cached_input_tokens, cached_price)
+            + per_million(cache_write_tokens, write_price)
+            + per_million(output_tokens, output_price);
+
+        Self {
+            model,
+            input_tokens,
+            output_tokens,
+            cached_input_tokens,
+            cache_write_tokens,
             total_tokens,
             cost_usd,
             timestamp: chrono::Utc::now(),
@@ -181,6 +236,44 @@ mod tests {
         assert_eq!(usage.total_tokens, 2000);
     }
 
+    #[test]
+    fn token_usage_prices_cache_reads_and_writes_separately() {
+        let pricing = ModelPricing {
+            input: 3.0,
+            output: 15.0,
+            cached_input: Some(0.3),
+            cache_write: Some(3.75),
+        };
+        let usage = TokenUsage::with_cache("test/model", 100_000, 1000, 80_000, 10_000, &pricing);
+
+        // 10K uncached * 3 + 80K cached * 0.3 + 10K written * 3.75 + 1K out * 15
+        let expected = 0.03 + 0.024 + 0.0375 + 0.015;
+        assert!((usage.cost_usd - expected).abs() < 1e-9);
+        assert_eq!(usage.cached_input_tokens, 80_000);
+        assert_eq!(usage.total_tokens, 101_000);
+    }
+
+    #[test]
+    fn token_usage_cache_rates_default_to_input_price() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            output: 0.0,
+            cached_input: None,
+            cache_write: None,
+        };
+        let cached = TokenUsage::with_cache("test/model", 1000, 0, 900, 0, &pricing);
+        let plain = TokenUsage::new("test/model", 1000, 0, 2.0, 0.0);
+        assert!((cached.cost_usd - plain.cost_usd).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn legacy_records_without_cache_fields_deserialize() {
+        let json = r#"{"model":"m","input_tokens":1,"output_tokens":2,"total_tokens":3,"cost_usd":0.1,"timestamp":"2026-01-01T00:00:00Z"}"#;
+        let usage: TokenUsage = serde_json::from_str(json).unwrap();
+        assert_eq!(usage.cached_input_tokens, 0);
+        assert_eq!(usage.cache_write_tokens, 0);
+    }
+
     #[test]
     fn cost_record_creation() {
         let usage = TokenUsage::new("test/model", 100, 50, 1.0, 2.0);
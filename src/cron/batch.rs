@@ -0,0 +1,449 @@
+//! Batch API execution for agent jobs (`[scheduler.batch]`).
+//!
+//! Eligible due jobs are grouped by model and submitted to the default
+//! provider's discounted batch endpoint instead of running the agent loop.
+//! Submitted batches are recorded in the cron DB and polled on later ticks;
+//! each finished job is then delivered, recorded and rescheduled like an
+//! interactive run. Batched jobs are single completions of the job prompt,
+//! without the agent's system prompt, tools or memory, so only jobs listed in
+//! `[scheduler.batch] jobs` are eligible.
+
+use super::scheduler::{agent_job_prompt, persist_job_result};
+use crate::config::Config;
+use crate::cron::{
+    add_batch, get_job, list_batches, mark_batch_polled, remove_batch, CronBatch, CronJob, JobType,
+    SessionTarget,
+};
+use crate::providers::batch::{BatchRequest, BatchStatus};
+use crate::providers::traits::ChatMessage;
+use crate::providers::{self, Provider};
+use crate::security::SecurityPolicy;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashSet};
+
+const MIN_BATCH_POLL_SECS: u64 = 30;
+/// Providers finish within 24 hours; give up on batches well past that.
+const BATCH_TIMEOUT_HOURS: i64 = 48;
+
+/// Poll submitted batches, then submit eligible jobs from `jobs`. Returns
+/// the jobs the interactive path should still run.
+pub(super) async fn dispatch(
+    config: &Config,
+    security: &SecurityPolicy,
+    jobs: Vec<CronJob>,
+) -> Vec<CronJob> {
+    let batches = match list_batches(config) {
+        Ok(batches) => batches,
+        Err(e) => {
+            tracing::warn!("Failed to load cron batches: {e}");
+            Vec::new()
+        }
+    };
+
+    // Jobs in a batch stay due until their results arrive; skip them this
+    // tick even if the batch finishes below, since `jobs` predates that.
+    let batched: HashSet<&str> = batches
+        .iter()
+        .flat_map(|batch| batch.job_ids.iter().map(String::as_str))
+        .collect();
+    let jobs: Vec<CronJob> = jobs
+        .into_iter()
+        .filter(|job| !batched.contains(job.id.as_str()))
+        .collect();
+
+    for batch in &batches {
+        poll(config, batch).await;
+    }
+
+    if !config.scheduler.batch.enabled {
+        return jobs;
+    }
+    let (eligible, mut interactive): (Vec<_>, Vec<_>) =
+        jobs.into_iter().partition(|job| is_eligible(config, job));
+    if eligible.is_empty() || !security.can_act() {
+        // The interactive path reports read-only blocks per job.
+        interactive.extend(eligible);
+        return interactive;
+    }
+
+    let provider_name = default_provider_name(config);
+    match create_provider(config, provider_name) {
+        Ok(provider) if provider.supports_batch() => {
+            let fallback =
+                submit(config, security, provider.as_ref(), provider_name, eligible).await;
+            interactive.extend(fallback);
+        }
+        Ok(_) => interactive.extend(eligible),
+        Err(e) => {
+            tracing::warn!("Cron batch provider '{provider_name}' unavailable: {e}");
+            interactive.extend(eligible);
+        }
+    }
+    interactive
+}
+
+/// Only jobs listed in `[scheduler.batch] jobs` are batched: a batched run
+/// drops the system prompt, tools and memory, so it has to be opted into.
+fn is_eligible(config: &Config, job: &CronJob) -> bool {
+    matches!(job.job_type, JobType::Agent)
+        && job.session_target == SessionTarget::Isolated
+        && config
+            .scheduler
+            .batch
+            .jobs
+            .iter()
+            .any(|entry| *entry == job.id || job.name.as_deref() == Some(entry.as_str()))
+}
+
+fn default_provider_name(config: &Config) -> &str {
+    config.default_provider.as_deref().unwrap_or("openrouter")
+}
+
+fn create_provider(config: &Config, name: &str) -> anyhow::Result<Box<dyn Provider>> {
+    providers::create_provider_with_url(name, config.api_key.as_deref(), config.api_url.as_deref())
+}
+
+/// Submit one batch per model. Returns jobs that could not be submitted.
+async fn submit(
+    config: &Config,
+    security: &SecurityPolicy,
+    provider: &dyn Provider,
+    provider_name: &str,
+    jobs: Vec<CronJob>,
+) -> Vec<CronJob> {
+    let mut by_model: BTreeMap<String, Vec<CronJob>> = BTreeMap::new();
+    for job in jobs {
+        let model = job
+            .model
+            .clone()
+            .or_else(|| config.default_model.clone())
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4".to_string());
+        by_model.entry(model).or_default().push(job);
+    }
+
+    let mut fallback = Vec::new();
+    for (model, jobs) in by_model {
+        // Batched jobs count against the action budget like agent runs.
+        let (admitted, blocked): (Vec<_>, Vec<_>) = jobs
+            .into_iter()
+            .partition(|_| !security.is_rate_limited() && security.record_action());
+        fallback.extend(blocked);
+        if admitted.is_empty() {
+            continue;
+        }
+
+        let requests: Vec<BatchRequest> = admitted
+            .iter()
+            .map(|job| BatchRequest {
+                custom_id: job.id.clone(),
+                messages: vec![ChatMessage::user(agent_job_prompt(job))],
+            })
+            .collect();
+        match provider
+            .submit_batch(&requests, &model, config.default_temperature)
+            .await
+        {
+            Ok(batch_id) => {
+                tracing::info!(
+                    "Submitted {} cron job(s) to {provider_name} batch {batch_id}",
+                    admitted.len()
+                );
+                let batch = CronBatch {
+                    id: batch_id,
+                    provider: provider_name.to_string(),
+                    model,
+                    job_ids: admitted.into_iter().map(|job| job.id).collect(),
+                    submitted_at: Utc::now(),
+                    polled_at: None,
+                };
+                if let Err(e) = add_batch(config, &batch) {
+                    tracing::warn!("Failed to record cron batch {}: {e}", batch.id);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Cron batch submission failed, running jobs interactively: {e}");
+                fallback.extend(admitted);
+            }
+        }
+    }
+    fallback
+}
+
+async fn poll(config: &Config, batch: &CronBatch) {
+    let now = Utc::now();
+    let interval = config.scheduler.batch.poll_secs.max(MIN_BATCH_POLL_SECS);
+    if batch
+        .polled_at
+        .is_some_and(|at| now - at < chrono::Duration::seconds(interval as i64))
+    {
+        return;
+    }
+
+    let status = match create_provider(config, &batch.provider) {
+        Ok(provider) => provider.poll_batch(&batch.id).await,
+        Err(e) => Err(e),
+    };
+    match status {
+        Ok(BatchStatus::Completed(outcomes)) => finish(config, batch, Ok(outcomes), now).await,
+        Ok(BatchStatus::Failed(reason)) => finish(config, batch, Err(reason), now).await,
+        Ok(BatchStatus::Pending) if !timed_out(batch, now) => {
+            let _ = mark_batch_polled(config, &batch.id, now);
+        }
+        Err(e) if !timed_out(batch, now) => {
+            tracing::warn!("Failed to poll cron batch {}: {e}", batch.id);
+            let _ = mark_batch_polled(config, &batch.id, now);
+        }
+        _ => {
+            let reason = format!("no result after {BATCH_TIMEOUT_HOURS}h");
+            finish(config, batch, Err(reason), now).await;
+        }
+    }
+}
+
+fn timed_out(batch: &CronBatch, now: DateTime<Utc>) -> bool {
+    now - batch.submitted_at > chrono::Duration::hours(BATCH_TIMEOUT_HOURS)
+}
+
+/// Deliver and record each job of a finished batch, then forget the batch.
+async fn finish(
+    config: &Config,
+    batch: &CronBatch,
+    outcomes: Result<Vec<crate::providers::batch::BatchOutcome>, String>,
+    finished_at: DateTime<Utc>,
+) {
+    let tracker = crate::cost::tracker::shared();
+    for job_id in &batch.job_ids {
+        let Ok(job) = get_job(config, job_id) else {
+            // Removed while the batch was running.
+            continue;
+        };
+        let (success, output) = match &outcomes {
+            Err(reason) => (
+                false,
+                format!("agent job failed: batch {} {reason}", batch.id),
+            ),
+            Ok(outcomes) => match outcomes.iter().find(|o| o.custom_id == *job_id) {
+                Some(outcome) => match &outcome.result {
+                    Ok(output) => {
+                        if let (Some(tracker), Some(usage)) = (&tracker, &output.usage) {
+                            if let Err(e) = tracker.record_batch_usage(&batch.model, usage) {
+                                tracing::warn!("Failed to record batch usage: {e}");
+                            }
+                        }
+                        let text = if output.text.trim().is_empty() {
+                            "agent job executed".to_string()
+                        } else {
+                            output.text.clone()
+                        };
+                        (true, text)
+                    }
+                    Err(e) => (false, format!("agent job failed: {e}")),
+                },
+                None => (
+                    false,
+                    format!("agent job failed: no result in batch {}", batch.id),
+                ),
+            },
+        };
+        persist_job_result(
+            config,
+            &job,
+            success,
+            &output,
+            batch.submitted_at,
+            finished_at,
+        )
+        .await;
+    }
+
+    if let Err(e) = remove_batch(config, &batch.id) {
+        tracing::warn!("Failed to remove finished cron batch {}: {e}", batch.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::{add_agent_job, list_runs, Schedule};
+    use crate::providers::batch::BatchOutcome;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct RecordingProvider {
+        submitted: Mutex<Vec<(String, Vec<BatchRequest>)>>,
+    }
+
+    #[async_trait]
+    impl Provider for RecordingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("interactive call in batch test")
+        }
+
+        fn supports_batch(&self) -> bool {
+            true
+        }
+
+        async fn submit_batch(
+            &self,
+            requests: &[BatchRequest],
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            let mut submitted = self.submitted.lock();
+            submitted.push((model.to_string(), requests.to_vec()));
+            Ok(format!("batch-{}", submitted.len()))
+        }
+    }
+
+    fn test_config(tmp: &TempDir) -> Config {
+        let mut config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        config.scheduler.batch.enabled = true;
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn agent_job(config: &Config, name: &str, model: Option<&str>) -> CronJob {
+        add_agent_job(
+            config,
+            Some(name.to_string()),
+            Schedule::Every { every_ms: 60_000 },
+            "summarize the news",
+            SessionTarget::Isolated,
+            model.map(ToString::to_string),
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn eligibility_respects_session_target_and_allow_list() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        let mut job = agent_job(&config, "digest", None);
+        assert!(!is_eligible(&config, &job));
+
+        config.scheduler.batch.jobs = vec!["other".into()];
+        assert!(!is_eligible(&config, &job));
+        config.scheduler.batch.jobs = vec!["digest".into()];
+        assert!(is_eligible(&config, &job));
+        config.scheduler.batch.jobs = vec![job.id.clone()];
+        assert!(is_eligible(&config, &job));
+
+        job.session_target = SessionTarget::Main;
+        assert!(!is_eligible(&config, &job));
+        job.session_target = SessionTarget::Isolated;
+        job.job_type = JobType::Shell;
+        assert!(!is_eligible(&config, &job));
+    }
+
+    #[tokio::test]
+    async fn submit_groups_jobs_by_model_and_records_batches() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let provider = RecordingProvider::default();
+        let jobs = vec![
+            agent_job(&config, "a", Some("model-x")),
+            agent_job(&config, "b", Some("model-y")),
+            agent_job(&config, "c", Some("model-x")),
+        ];
+
+        let fallback = submit(&config, &security, &provider, "openai", jobs).await;
+        assert!(fallback.is_empty());
+
+        let submitted = provider.submitted.lock();
+        assert_eq!(submitted.len(), 2);
+        assert_eq!(submitted[0].0, "model-x");
+        assert_eq!(submitted[0].1.len(), 2);
+        assert!(submitted[0].1[0].messages[0]
+            .content
+            .ends_with("] summarize the news"));
+
+        let batches = list_batches(&config).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|b| b.provider == "openai"));
+    }
+
+    #[tokio::test]
+    async fn finish_records_runs_and_removes_batch() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let ok = agent_job(&config, "ok", None);
+        let failed = agent_job(&config, "failed", None);
+        let missing = agent_job(&config, "missing", None);
+        let batch = CronBatch {
+            id: "batch-1".into(),
+            provider: "openai".into(),
+            model: "gpt-4o-mini".into(),
+            job_ids: vec![ok.id.clone(), failed.id.clone(), missing.id.clone()],
+            submitted_at: Utc::now(),
+            polled_at: None,
+        };
+        add_batch(&config, &batch).unwrap();
+
+        let outcomes = vec![
+            BatchOutcome::success(ok.id.clone(), "all quiet".into(), None),
+            BatchOutcome::failure(failed.id.clone(), "context too long"),
+        ];
+        finish(&config, &batch, Ok(outcomes), Utc::now()).await;
+
+        let ok = get_job(&config, &ok.id).unwrap();
+        assert_eq!(ok.last_status.as_deref(), Some("ok"));
+        assert_eq!(ok.last_output.as_deref(), Some("all quiet"));
+        let runs = list_runs(&config, &failed.id, 5).unwrap();
+        assert_eq!(runs[0].status, "error");
+        assert!(runs[0]
+            .output
+            .as_deref()
+            .unwrap()
+            .contains("context too long"));
+        let missing = get_job(&config, &missing.id).unwrap();
+        assert!(missing
+            .last_output
+            .as_deref()
+            .unwrap()
+            .contains("no result in batch"));
+        assert!(list_batches(&config).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dispatch_skips_jobs_already_in_a_batch() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.scheduler.batch.enabled = false;
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let pending = agent_job(&config, "pending", None);
+        let free = agent_job(&config, "free", None);
+        add_batch(
+            &config,
+            &CronBatch {
+                id: "batch-1".into(),
+                provider: "openai".into(),
+                model: "gpt-4o-mini".into(),
+                job_ids: vec![pending.id.clone()],
+                submitted_at: Utc::now(),
+                // Recently polled, so this tick does not contact the provider.
+                polled_at: Some(Utc::now()),
+            },
+        )
+        .unwrap();
+
+        let remaining = dispatch(&config, &security, vec![pending, free.clone()]).await;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, free.id);
+        assert_eq!(list_batches(&config).unwrap().len(), 1);
+    }
+}
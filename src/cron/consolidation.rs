@@ -1,13 +1,28 @@
 use crate::config::Config;
-use crate::cron::{add_agent_job, CronJob, Schedule, SessionTarget};
+use crate::cron::{
+    add_agent_job, add_memory_job, list_jobs, remove_job, CronJob, JobType, Schedule, SessionTarget,
+};
 use anyhow::Result;
 
 /// Default cron expression: 3:00 AM daily.
 const DEFAULT_SCHEDULE_EXPR: &str = "0 3 * * *";
 
+/// Default cron expression for memory deduplication: 3:30 AM daily, after the
+/// nightly summary has been stored.
+pub const MEMORY_CONSOLIDATION_SCHEDULE_EXPR: &str = "30 3 * * *";
+
 /// Job name marker used to identify consolidation jobs.
 pub const CONSOLIDATION_JOB_NAME: &str = "__consolidate_nightly";
 
+/// Display name of the built-in memory deduplication job. The scheduler keys
+/// off [`JobType::Memory`], not this name, and runs
+/// [`crate::memory::consolidation::run`] instead of an agent.
+pub const MEMORY_CONSOLIDATION_JOB_NAME: &str = "__consolidate_memory";
+
+const MEMORY_CONSOLIDATION_PROMPT: &str = "\
+Built-in memory consolidation: cluster near-duplicate daily and conversation \
+memories, merge each cluster, and promote recurring facts to core.";
+
 /// The prompt instructs the agent to perform memory consolidation using
 /// existing tools (cron_runs, memory_recall, memory_store, file_write).
 const CONSOLIDATION_PROMPT: &str = "\
@@ -80,6 +95,34 @@ pub fn create_consolidation_job_with_schedule(
     )
 }
 
+/// Install (or replace) the scheduled memory deduplication job.
+pub fn create_memory_consolidation_job_with_schedule(
+    config: &Config,
+    cron_expr: &str,
+    tz: Option<String>,
+) -> Result<CronJob> {
+    for job in list_jobs(config)? {
+        if is_memory_consolidation_job(&job) {
+            remove_job(config, &job.id)?;
+        }
+    }
+
+    add_memory_job(
+        config,
+        Some(MEMORY_CONSOLIDATION_JOB_NAME.into()),
+        Schedule::Cron {
+            expr: cron_expr.into(),
+            tz,
+        },
+        MEMORY_CONSOLIDATION_PROMPT,
+    )
+}
+
+/// Whether `job` is the built-in memory deduplication job.
+pub fn is_memory_consolidation_job(job: &CronJob) -> bool {
+    job.job_type == JobType::Memory
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +203,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn memory_consolidation_job_replaces_previous_install() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let first = create_memory_consolidation_job_with_schedule(
+            &config,
+            MEMORY_CONSOLIDATION_SCHEDULE_EXPR,
+            None,
+        )
+        .unwrap();
+        let second =
+            create_memory_consolidation_job_with_schedule(&config, "0 5 * * *", None).unwrap();
+
+        assert!(is_memory_consolidation_job(&second));
+        assert_eq!(second.job_type, JobType::Memory);
+        let jobs = list_jobs(&config).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, second.id);
+        assert_ne!(first.id, second.id);
+        assert!(!is_memory_consolidation_job(
+            &create_consolidation_job(&config).unwrap()
+        ));
+    }
+
     #[test]
     fn create_consolidation_job_with_custom_schedule_applies_tz() {
         let tmp = TempDir::new().unwrap();
@@ -2,6 +2,7 @@ use crate::config::Config;
 use crate::security::SecurityPolicy;
 use anyhow::{bail, Result};
 
+mod batch;
 pub mod consolidation;
 mod schedule;
 mod store;
@@ -15,10 +16,13 @@ pub use schedule::{
 };
 #[allow(unused_imports)]
 pub use store::{
-    add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    add_agent_job, add_batch, add_job, add_memory_job, add_shell_job, due_jobs, get_job,
+    list_batches, list_jobs, list_runs, mark_batch_polled, record_last_run, record_run,
+    remove_batch, remove_job, reschedule_after_run, update_job,
+};
+pub use types::{
+    CronBatch, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
-pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
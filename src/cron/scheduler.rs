@@ -3,8 +3,9 @@ use crate::channels::{
 };
 use crate::config::Config;
 use crate::cron::{
-    due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    batch, due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job,
+    reschedule_after_run, update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule,
+    SessionTarget,
 };
 use crate::security::SecurityPolicy;
 use anyhow::Result;
@@ -44,6 +45,7 @@ pub async fn run(config: Config) -> Result<()> {
             }
         };
 
+        let jobs = batch::dispatch(&config, &security, jobs).await;
         process_due_jobs(&config, &security, jobs, SCHEDULER_COMPONENT).await;
     }
 }
@@ -66,6 +68,7 @@ async fn execute_job_with_retry(
         let (success, output) = match job.job_type {
             JobType::Shell => run_job_command(config, security, job).await,
             JobType::Agent => run_agent_job(config, security, job).await,
+            JobType::Memory => run_memory_consolidation_job(config, security).await,
         };
         last_output = output;
 
@@ -160,14 +163,12 @@ async fn run_agent_job(
             "blocked by security policy: action budget exhausted".to_string(),
         );
     }
-    let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
-    let prompt = job.prompt.clone().unwrap_or_default();
-    let prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
+    let prefixed_prompt = agent_job_prompt(job);
     let model_override = job.model.clone();
 
     let run_result = match job.session_target {
         SessionTarget::Main | SessionTarget::Isolated => {
-            crate::agent::run(
+            Box::pin(crate::agent::run(
                 config.clone(),
                 Some(prefixed_prompt),
                 None,
@@ -175,7 +176,7 @@ async fn run_agent_job(
                 config.default_temperature,
                 vec![],
                 false,
-            )
+            ))
             .await
         }
     };
@@ -193,7 +194,31 @@ async fn run_agent_job(
     }
 }
 
-async fn persist_job_result(
+/// Prompt sent for an agent job, tagged with the job id and name.
+pub(super) fn agent_job_prompt(job: &CronJob) -> String {
+    let name = job.name.as_deref().unwrap_or("cron-job");
+    let prompt = job.prompt.as_deref().unwrap_or_default();
+    format!("[cron:{} {name}] {prompt}", job.id)
+}
+
+async fn run_memory_consolidation_job(
+    config: &Config,
+    security: &SecurityPolicy,
+) -> (bool, String) {
+    if !security.can_act() {
+        return (
+            false,
+            "blocked by security policy: autonomy is read-only".to_string(),
+        );
+    }
+
+    match crate::memory::consolidation::run(config, false).await {
+        Ok(report) => (true, format!("memory consolidation: {}", report.summary())),
+        Err(e) => (false, format!("memory consolidation failed: {e}")),
+    }
+}
+
+pub(super) async fn persist_job_result(
     config: &Config,
     job: &CronJob,
     mut success: bool,
@@ -346,7 +371,8 @@ pub(crate) async fn deliver_announcement(
                 sl.bot_token.clone(),
                 sl.channel_id.clone(),
                 sl.allowed_users.clone(),
-            );
+            )
+            .with_workspace_dir(config.workspace_dir.clone());
             channel.send(&SendMessage::new(output, target)).await?;
         }
         "mattermost" => {
@@ -754,6 +780,27 @@ mod tests {
         assert!(output.contains("agent job failed:"));
     }
 
+    #[tokio::test]
+    async fn memory_consolidation_job_runs_builtin_pass() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        let job = crate::cron::consolidation::create_memory_consolidation_job_with_schedule(
+            &config,
+            crate::cron::consolidation::MEMORY_CONSOLIDATION_SCHEDULE_EXPR,
+            None,
+        )
+        .unwrap();
+
+        let (success, output) = execute_job_now(&config, &job).await;
+        assert!(success, "{output}");
+        assert!(output.contains("memory consolidation: scanned 0 entries"));
+
+        config.autonomy.level = crate::security::AutonomyLevel::ReadOnly;
+        let (success, output) = execute_job_now(&config, &job).await;
+        assert!(!success);
+        assert!(output.contains("read-only"));
+    }
+
     #[tokio::test]
     async fn run_agent_job_blocks_readonly_mode() {
         let tmp = TempDir::new().unwrap();
@@ -1,7 +1,7 @@
 use crate::config::Config;
 use crate::cron::{
-    next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
-    CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    next_run_for_schedule, schedule_cron_expression, validate_schedule, CronBatch, CronJob,
+    CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -67,6 +67,45 @@ pub fn add_shell_job(
     get_job(config, &id)
 }
 
+/// Add a built-in memory consolidation job. `description` is shown in
+/// listings only; the scheduler never hands it to an agent.
+pub fn add_memory_job(
+    config: &Config,
+    name: Option<String>,
+    schedule: Schedule,
+    description: &str,
+) -> Result<CronJob> {
+    let now = Utc::now();
+    validate_schedule(&schedule, now)?;
+    let next_run = next_run_for_schedule(&schedule, now)?;
+    let id = Uuid::new_v4().to_string();
+    let expression = schedule_cron_expression(&schedule).unwrap_or_default();
+    let schedule_json = serde_json::to_string(&schedule)?;
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_jobs (
+                id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                enabled, delivery, delete_after_run, created_at, next_run
+             ) VALUES (?1, ?2, '', ?3, 'memory', ?4, ?5, 'isolated', NULL, 1, ?6, 0, ?7, ?8)",
+            params![
+                id,
+                expression,
+                schedule_json,
+                description,
+                name,
+                serde_json::to_string(&DeliveryConfig::default())?,
+                now.to_rfc3339(),
+                next_run.to_rfc3339(),
+            ],
+        )
+        .context("Failed to insert cron memory job")?;
+        Ok(())
+    })?;
+
+    get_job(config, &id)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn add_agent_job(
     config: &Config,
@@ -406,6 +445,77 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
     })
 }
 
+pub fn add_batch(config: &Config, batch: &CronBatch) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_batches (id, provider, model, job_ids, submitted_at, polled_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                batch.id,
+                batch.provider,
+                batch.model,
+                serde_json::to_string(&batch.job_ids)?,
+                batch.submitted_at.to_rfc3339(),
+                batch.polled_at.map(|at| at.to_rfc3339()),
+            ],
+        )
+        .context("Failed to insert cron batch")?;
+        Ok(())
+    })
+}
+
+pub fn list_batches(config: &Config) -> Result<Vec<CronBatch>> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, provider, model, job_ids, submitted_at, polled_at
+             FROM cron_batches ORDER BY submitted_at ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let job_ids: String = row.get(3)?;
+            let polled_at: Option<String> = row.get(5)?;
+            Ok(CronBatch {
+                id: row.get(0)?,
+                provider: row.get(1)?,
+                model: row.get(2)?,
+                job_ids: serde_json::from_str(&job_ids)
+                    .map_err(|e| sql_conversion_error(e.into()))?,
+                submitted_at: parse_rfc3339(&row.get::<_, String>(4)?)
+                    .map_err(sql_conversion_error)?,
+                polled_at: match polled_at {
+                    Some(raw) => Some(parse_rfc3339(&raw).map_err(sql_conversion_error)?),
+                    None => None,
+                },
+            })
+        })?;
+
+        let mut batches = Vec::new();
+        for row in rows {
+            batches.push(row?);
+        }
+        Ok(batches)
+    })
+}
+
+pub fn mark_batch_polled(config: &Config, batch_id: &str, polled_at: DateTime<Utc>) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_batches SET polled_at = ?1 WHERE id = ?2",
+            params![polled_at.to_rfc3339(), batch_id],
+        )
+        .context("Failed to update cron batch poll time")?;
+        Ok(())
+    })
+}
+
+pub fn remove_batch(config: &Config, batch_id: &str) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute("DELETE FROM cron_batches WHERE id = ?1", params![batch_id])
+            .context("Failed to delete cron batch")?;
+        Ok(())
+    })
+}
+
 fn parse_rfc3339(raw: &str) -> Result<DateTime<Utc>> {
     let parsed = DateTime::parse_from_rfc3339(raw)
         .with_context(|| format!("Invalid RFC3339 timestamp in cron DB: {raw}"))?;
@@ -558,7 +668,16 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
         );
         CREATE INDEX IF NOT EXISTS idx_cron_runs_job_id ON cron_runs(job_id);
         CREATE INDEX IF NOT EXISTS idx_cron_runs_started_at ON cron_runs(started_at);
-        CREATE INDEX IF NOT EXISTS idx_cron_runs_job_started ON cron_runs(job_id, started_at);",
+        CREATE INDEX IF NOT EXISTS idx_cron_runs_job_started ON cron_runs(job_id, started_at);
+
+        CREATE TABLE IF NOT EXISTS cron_batches (
+            id           TEXT PRIMARY KEY,
+            provider     TEXT NOT NULL,
+            model        TEXT NOT NULL,
+            job_ids      TEXT NOT NULL,
+            submitted_at TEXT NOT NULL,
+            polled_at    TEXT
+        );",
     )
     .context("Failed to initialize cron schema")?;
 
@@ -7,6 +7,8 @@ pub enum JobType {
     #[default]
     Shell,
     Agent,
+    /// Built-in memory consolidation pass; runs without an agent turn
+    Memory,
 }
 
 impl From<JobType> for &'static str {
@@ -14,6 +16,7 @@ impl From<JobType> for &'static str {
         match value {
             JobType::Shell => "shell",
             JobType::Agent => "agent",
+            JobType::Memory => "memory",
         }
     }
 }
@@ -25,8 +28,9 @@ impl TryFrom<&str> for JobType {
         match value.to_lowercase().as_str() {
             "shell" => Ok(JobType::Shell),
             "agent" => Ok(JobType::Agent),
+            "memory" => Ok(JobType::Memory),
             _ => Err(format!(
-                "Invalid job type '{}'. Expected one of: 'shell', 'agent'",
+                "Invalid job type '{}'. Expected one of: 'shell', 'agent', 'memory'",
                 value
             )),
         }
@@ -133,6 +137,17 @@ pub struct CronRun {
     pub duration_ms: Option<i64>,
 }
 
+/// Agent jobs submitted together to a provider batch API, awaiting results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronBatch {
+    pub id: String,
+    pub provider: String,
+    pub model: String,
+    pub job_ids: Vec<String>,
+    pub submitted_at: DateTime<Utc>,
+    pub polled_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CronJobPatch {
     pub schedule: Option<Schedule>,
@@ -156,6 +171,7 @@ mod tests {
         assert_eq!(JobType::try_from("SHELL").unwrap(), JobType::Shell);
         assert_eq!(JobType::try_from("agent").unwrap(), JobType::Agent);
         assert_eq!(JobType::try_from("AgEnT").unwrap(), JobType::Agent);
+        assert_eq!(JobType::try_from("memory").unwrap(), JobType::Memory);
     }
 
     #[test]
@@ -53,7 +53,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
                 max_backoff,
                 move || {
                     let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    async move { Box::pin(crate::channels::start_channels(cfg)).await }
                 },
             ));
         } else {
@@ -83,7 +83,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
+                async move { Box::pin(crate::cron::scheduler::run(cfg)).await }
             },
         ));
     } else {
@@ -117,10 +117,15 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let mut tools_vec: Vec<_> = tools.into_iter().collect();
     tools_vec.sort();
     let tools_list = tools_vec.join(", ");
-    
+
     let notifier_startup = notifier.clone();
     tokio::spawn(async move {
-        let _ = notifier_startup.notify_telegram(&format!("🚀 *Ezra is online!* \nActive tools: `{}`", tools_list)).await;
+        let _ = notifier_startup
+            .notify_telegram(&format!(
+                "🚀 *Ezra is online!* \nActive tools: `{}`",
+                tools_list
+            ))
+            .await;
     });
 
     println!("🧠 ZeroClaw daemon started");
@@ -234,7 +239,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
-            match crate::agent::run(
+            match Box::pin(crate::agent::run(
                 config.clone(),
                 Some(prompt),
                 None,
@@ -242,7 +247,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                 temp,
                 vec![],
                 false,
-            )
+            ))
             .await
             {
                 Ok(output) => {
@@ -278,8 +283,10 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     }
 }
 
-<<<<<<< HEAD
-async fn run_skillforge_worker(config: Config, notifier: Option<Arc<SystemNotifier>>) -> Result<()> {
+async fn run_skillforge_worker(
+    config: Config,
+    notifier: Option<Arc<SystemNotifier>>,
+) -> Result<()> {
     let forge = crate::skillforge::SkillForge::new(config.skillforge.clone(), notifier);
     let interval_hours = config.skillforge.scan_interval_hours.max(1);
     let mut interval = tokio::time::interval(Duration::from_secs(interval_hours * 3600));
@@ -290,7 +297,7 @@ async fn run_skillforge_worker(config: Config, notifier: Option<Arc<SystemNotifi
             Ok(_) => crate::health::mark_component_ok("skillforge"),
             Err(e) => {
                 tracing::warn!("SkillForge pipeline failed: {e}");
-                crate::health::mark_component_error("skillforge", &e.to_string());
+                crate::health::mark_component_error("skillforge", e.to_string());
             }
         }
     }
@@ -308,14 +315,14 @@ fn spawn_config_watcher(initial_config: Config, notifier: Arc<SystemNotifier>) -
             match Config::load_or_init().await {
                 Ok(new_config) => {
                     let new_tools = new_config.get_enabled_tools();
-                    
+
                     // Find tools that were just enabled
                     for tool in &new_tools {
                         if !current_tools.contains(tool) {
                             let _ = notifier.notify_new_tool(tool).await;
                         }
                     }
-                    
+
                     current_tools = new_tools;
                 }
                 Err(e) => {
@@ -326,28 +333,6 @@ fn spawn_config_watcher(initial_config: Config, notifier: Arc<SystemNotifier>) -
     })
 }
 
-fn has_supervised_channels(config: &Config) -> bool {
-    let crate::config::ChannelsConfig {
-        cli: _,     // `cli` is used only when running the CLI manually
-        webhook: _, // Managed by the gateway
-        telegram,
-        discord,
-        slack,
-        mattermost,
-        imessage,
-        matrix,
-        signal,
-        whatsapp,
-        email,
-        irc,
-        lark,
-        dingtalk,
-        linq,
-        nextcloud_talk,
-        qq,
-        ..
-    } = &config.channels_config;
-=======
 fn heartbeat_tasks_for_tick(
     file_tasks: Vec<String>,
     fallback_message: Option<&str>,
@@ -355,7 +340,6 @@ fn heartbeat_tasks_for_tick(
     if !file_tasks.is_empty() {
         return file_tasks;
     }
->>>>>>> upstream/main
 
     fallback_message
         .map(str::trim)
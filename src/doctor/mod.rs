@@ -82,6 +82,7 @@ pub fn diagnose(config: &Config) -> Vec<DiagResult> {
     check_config_semantics(config, &mut items);
     check_workspace(config, &mut items);
     check_daemon_state(config, &mut items);
+    check_provider_circuits(config, &mut items);
     check_environment(&mut items);
     check_cli_tools(&mut items);
 
@@ -325,6 +326,8 @@ pub async fn run_models(
         );
     }
 
+    print_adaptive_routing(config)?;
+
     if provider_override.is_some() && ok_count == 0 {
         anyhow::bail!("Model probe failed for target provider")
     }
@@ -332,6 +335,103 @@ pub async fn run_models(
     Ok(())
 }
 
+/// Show the latest adaptive route decision per hint from the runtime trace.
+fn print_adaptive_routing(config: &Config) -> Result<()> {
+    if !config.model_routing.adaptive || config.model_routes.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  Adaptive routing (p95 SLO {}ms, max error rate {:.0}%):",
+        config.model_routing.latency_slo_ms,
+        config.model_routing.max_error_rate * 100.0
+    );
+
+    let path = crate::observability::runtime_trace::resolve_trace_path(
+        &config.observability,
+        &config.workspace_dir,
+    );
+    let events =
+        crate::observability::runtime_trace::load_events(&path, 500, Some("route_decision"), None)?;
+
+    let mut seen_hints = std::collections::HashSet::new();
+    for event in events {
+        let hint = event
+            .payload
+            .get("hint")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("?")
+            .to_string();
+        if !seen_hints.insert(hint.clone()) {
+            continue;
+        }
+
+        println!(
+            "    hint:{} → {} / {} ({})",
+            hint,
+            event.provider.as_deref().unwrap_or("-"),
+            event.model.as_deref().unwrap_or("-"),
+            event.timestamp
+        );
+        if let Some(reason) = &event.message {
+            println!("      {}", truncate_for_display(reason, 160));
+        }
+        let candidates = event
+            .payload
+            .get("candidates")
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        for candidate in candidates {
+            let field = |key: &str| {
+                candidate
+                    .get(key)
+                    .filter(|value| !value.is_null())
+                    .map_or_else(|| "-".to_string(), ToString::to_string)
+            };
+            let error_rate = candidate
+                .get("error_rate")
+                .and_then(serde_json::Value::as_f64)
+                .unwrap_or(0.0);
+            println!(
+                "      {:<28} p50={:<7} p95={:<7} errors={:<5} samples={:<4} $/1M={:<7} $/call={:<9} quota={}",
+                format!(
+                    "{}/{}",
+                    candidate
+                        .get("provider")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or("-"),
+                    candidate
+                        .get("model")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or("-")
+                ),
+                field("p50_ms"),
+                field("p95_ms"),
+                format!("{:.0}%", error_rate * 100.0),
+                field("samples"),
+                field("price_per_million"),
+                candidate
+                    .get("billed_usd_per_call")
+                    .and_then(serde_json::Value::as_f64)
+                    .map_or_else(|| "-".to_string(), |usd| format!("{usd:.4}")),
+                field("quota_remaining")
+            );
+        }
+    }
+
+    if seen_hints.is_empty() {
+        println!(
+            "    No route decisions recorded yet (trace: {}).\n    \
+             Enable [observability] runtime_trace_mode = \"rolling\" or \"full\" to persist them.",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
 pub fn run_traces(
     config: &Config,
     id: Option<&str>,
@@ -564,6 +664,16 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         }
     }
 
+    if let Some(reason) = embedding_provider_validation_error(&config.memory.embedding_provider) {
+        items.push(DiagItem::warn(
+            cat,
+            format!(
+                "memory.embedding_provider \"{}\" is invalid: {}",
+                config.memory.embedding_provider, reason
+            ),
+        ));
+    }
+
     if let Some(hint) = config
         .memory
         .embedding_model
@@ -585,6 +695,9 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         }
     }
 
+    // Memory: qdrant needs a reachable URL and real embeddings
+    check_qdrant_memory(config, std::env::var("QDRANT_URL").ok(), items);
+
     // Channel: at least one configured
     let cc = &config.channels_config;
     let has_channel = cc.channels().iter().any(|(_, ok)| *ok);
@@ -615,6 +728,37 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
     }
 }
 
+/// Qdrant backend checks, with the `QDRANT_URL` fallback passed in so tests
+/// do not depend on the process environment.
+fn check_qdrant_memory(config: &Config, env_url: Option<String>, items: &mut Vec<DiagItem>) {
+    let cat = "config";
+    if !matches!(
+        crate::memory::classify_memory_backend(&config.memory.backend),
+        crate::memory::MemoryBackendKind::Qdrant
+    ) {
+        return;
+    }
+
+    match crate::memory::resolve_qdrant_url(&config.memory, env_url) {
+        Some(url) => items.push(DiagItem::ok(
+            cat,
+            format!("qdrant memory backend configured ({url})"),
+        )),
+        None => items.push(DiagItem::error(
+            cat,
+            "memory backend \"qdrant\" requires [memory.qdrant].url or QDRANT_URL",
+        )),
+    }
+    if config.memory.embedding_provider.trim() == "none"
+        && !config.memory.embedding_model.starts_with("hint:")
+    {
+        items.push(DiagItem::warn(
+            cat,
+            "memory backend \"qdrant\" has embedding_provider = \"none\"; stores will fail and recall is keyword-only",
+        ));
+    }
+}
+
 fn provider_validation_error(name: &str) -> Option<String> {
     match crate::providers::create_provider(name, None) {
         Ok(_) => None,
@@ -630,12 +774,33 @@ fn provider_validation_error(name: &str) -> Option<String> {
 
 fn embedding_provider_validation_error(name: &str) -> Option<String> {
     let normalized = name.trim();
-    if normalized.eq_ignore_ascii_case("none") || normalized.eq_ignore_ascii_case("openai") {
+    if normalized.eq_ignore_ascii_case("none")
+        || normalized.eq_ignore_ascii_case("openai")
+        || normalized.eq_ignore_ascii_case("openrouter")
+        || normalized.eq_ignore_ascii_case("local")
+    {
         return None;
     }
 
+    if let Some(path) = normalized.strip_prefix("local:") {
+        let path = path.trim();
+        if path.is_empty() {
+            return Some("local provider requires a model file path after 'local:'".into());
+        }
+        if !Path::new(path).is_file() {
+            return Some(format!(
+                "local embedding model file not found: {path} (memory will fail to start)"
+            ));
+        }
+        return crate::memory::embeddings::StaticVectorEmbedding::load(Path::new(path))
+            .err()
+            .map(|e| format!("local embedding model failed to load: {e}"));
+    }
+
     let Some(url) = normalized.strip_prefix("custom:") else {
-        return Some("supported values: none, openai, custom:<url>".into());
+        return Some(
+            "supported values: none, openai, openrouter, local, local:<path>, custom:<url>".into(),
+        );
     };
 
     let url = url.trim();
@@ -902,6 +1067,65 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
 
 // ── Environment checks ───────────────────────────────────────────
 
+// ── Provider circuit breakers ───────────────────────────────────
+
+fn check_provider_circuits(config: &Config, items: &mut Vec<DiagItem>) {
+    use crate::providers::circuit_breaker::{self, CircuitState};
+
+    let cat = "providers";
+    if config.reliability.circuit_failure_threshold == 0 {
+        items.push(DiagItem::ok(cat, "circuit breakers disabled"));
+        return;
+    }
+
+    let circuits = match circuit_breaker::load_persisted(&config.workspace_dir) {
+        Ok(circuits) => circuits,
+        Err(e) => {
+            items.push(DiagItem::warn(
+                cat,
+                format!("cannot read provider circuit state: {e}"),
+            ));
+            return;
+        }
+    };
+
+    let mut open_count = 0usize;
+    for (provider, circuit) in &circuits {
+        let last_error = circuit
+            .last_error
+            .as_deref()
+            .map(|e| format!(": {}", truncate_for_display(e, 80)))
+            .unwrap_or_default();
+        match circuit.state {
+            CircuitState::Open => {
+                open_count += 1;
+                items.push(DiagItem::warn(
+                    cat,
+                    format!(
+                        "{provider} circuit open ({} consecutive failures, {} trips){last_error}",
+                        circuit.consecutive_failures, circuit.trips
+                    ),
+                ));
+            }
+            CircuitState::HalfOpen => {
+                open_count += 1;
+                items.push(DiagItem::warn(
+                    cat,
+                    format!("{provider} circuit half-open, probing for recovery{last_error}"),
+                ));
+            }
+            CircuitState::Closed => {}
+        }
+    }
+
+    if open_count == 0 {
+        items.push(DiagItem::ok(
+            cat,
+            format!("all provider circuits closed ({} tracked)", circuits.len()),
+        ));
+    }
+}
+
 fn check_environment(items: &mut Vec<DiagItem>) {
     let cat = "environment";
 
@@ -1231,6 +1455,50 @@ mod tests {
         assert_eq!(route_item.unwrap().severity, Severity::Warn);
     }
 
+    #[test]
+    fn embedding_provider_validation_accepts_local_variants() {
+        assert!(embedding_provider_validation_error("local").is_none());
+        assert!(embedding_provider_validation_error("local:")
+            .unwrap()
+            .contains("model file path"));
+        assert!(
+            embedding_provider_validation_error("local:/nonexistent/model.vec")
+                .unwrap()
+                .contains("not found")
+        );
+
+        let tmp = TempDir::new().unwrap();
+        let model = tmp.path().join("vectors.vec");
+        std::fs::write(&model, "cat 1 0\n").unwrap();
+        assert!(
+            embedding_provider_validation_error(&format!("local:{}", model.display())).is_none()
+        );
+    }
+
+    #[test]
+    fn config_validation_flags_qdrant_without_url_or_embeddings() {
+        let mut config = Config::default();
+        config.memory.backend = "qdrant".into();
+        config.memory.embedding_provider = "none".into();
+
+        let mut items = Vec::new();
+        check_qdrant_memory(&config, None, &mut items);
+        let url_item = items
+            .iter()
+            .find(|item| item.message.contains("requires [memory.qdrant].url"));
+        assert_eq!(url_item.unwrap().severity, Severity::Error);
+        let embed_item = items
+            .iter()
+            .find(|item| item.message.contains("embedding_provider = \"none\""));
+        assert_eq!(embed_item.unwrap().severity, Severity::Warn);
+
+        let mut items = Vec::new();
+        check_qdrant_memory(&config, Some("http://qdrant:6333".into()), &mut items);
+        assert!(items.iter().any(
+            |item| item.severity == Severity::Ok && item.message.contains("http://qdrant:6333")
+        ));
+    }
+
     #[test]
     fn environment_check_finds_git() {
         let mut items = Vec::new();
@@ -1277,6 +1545,7 @@ mod tests {
                 model: "model-z".into(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -1291,6 +1560,7 @@ mod tests {
                 model: "model-a".into(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
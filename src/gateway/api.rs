@@ -50,6 +50,8 @@ fn require_auth(
 pub struct MemoryQuery {
     pub query: Option<String>,
     pub category: Option<String>,
+    /// Comma-separated tags; entries must carry all of them
+    pub tags: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -57,6 +59,14 @@ pub struct MemoryStoreBody {
     pub key: String,
     pub content: String,
     pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionQuery {
+    pub channel: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -382,9 +392,15 @@ pub async fn handle_api_memory_list(
         return e.into_response();
     }
 
+    let tags: Vec<String> = params
+        .tags
+        .as_deref()
+        .map(|raw| raw.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+
     if let Some(ref query) = params.query {
         // Search mode
-        match state.mem.recall(query, 50, None).await {
+        match state.mem.recall_tagged(query, 50, None, &tags).await {
             Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -401,7 +417,7 @@ pub async fn handle_api_memory_list(
             other => crate::memory::MemoryCategory::Custom(other.to_string()),
         });
 
-        match state.mem.list(category.as_ref(), None).await {
+        match state.mem.list_tagged(category.as_ref(), None, &tags).await {
             Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -433,9 +449,28 @@ pub async fn handle_api_memory_store(
         })
         .unwrap_or(crate::memory::MemoryCategory::Core);
 
+    // Provenance is stamped server-side; a client-supplied source would be spoofable
+    let mut metadata = crate::memory::MemoryMetadata::default()
+        .with_tags(&body.tags)
+        .with_source(crate::memory::MemorySource {
+            channel: Some("gateway".into()),
+            ..crate::memory::MemorySource::default()
+        });
+    match body.ttl_seconds {
+        Some(0) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "ttl_seconds must be greater than zero"})),
+            )
+                .into_response();
+        }
+        Some(secs) => metadata = metadata.with_ttl(std::time::Duration::from_secs(secs)),
+        None => {}
+    }
+
     match state
         .mem
-        .store(&body.key, &body.content, category, None)
+        .store_with_metadata(&body.key, &body.content, category, None, metadata)
         .await
     {
         Ok(()) => Json(serde_json::json!({"status": "ok"})).into_response(),
@@ -469,6 +504,94 @@ pub async fn handle_api_memory_delete(
     }
 }
 
+/// Run `op` against the channel session store on the blocking pool, so
+/// SQLite work never runs on a runtime worker.
+async fn with_session_store<T: Send + 'static>(
+    state: &AppState,
+    op: impl FnOnce(&crate::channels::session_store::SessionStore) -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    tokio::task::spawn_blocking(move || {
+        op(&crate::channels::session_store::SessionStore::open(
+            &workspace_dir,
+        )?)
+    })
+    .await?
+}
+
+/// GET /api/sessions — list persisted channel sessions
+pub async fn handle_api_sessions_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SessionQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let channel = params.channel;
+    let result = with_session_store(&state, move |store| store.list(channel.as_deref())).await;
+    match result {
+        Ok(sessions) => Json(serde_json::json!({"sessions": sessions})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to list sessions: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/:key — inspect a persisted channel session
+pub async fn handle_api_sessions_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let lookup = key.clone();
+    let result = with_session_store(&state, move |store| store.get(&lookup)).await;
+    match result {
+        Ok(Some(session)) => Json(serde_json::json!({"session": session})).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("Session '{key}' not found")})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to load session: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE /api/sessions/:key — delete a persisted channel session
+pub async fn handle_api_sessions_delete(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let lookup = key.clone();
+    let result = with_session_store(&state, move |store| store.delete(&lookup)).await;
+    match result {
+        Ok(deleted) => {
+            Json(serde_json::json!({"status": "ok", "deleted": deleted})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to delete session: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 /// GET /api/cost — cost summary
 pub async fn handle_api_cost(
     State(state): State<AppState>,
@@ -516,7 +639,7 @@ pub async fn handle_api_cli_tools(
     Json(serde_json::json!({"cli_tools": tools})).into_response()
 }
 
-/// GET /api/health — component health snapshot
+/// GET /api/health — component health and provider circuit breaker snapshot
 pub async fn handle_api_health(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -526,7 +649,8 @@ pub async fn handle_api_health(
     }
 
     let snapshot = crate::health::snapshot();
-    Json(serde_json::json!({"health": snapshot})).into_response()
+    let circuits = crate::providers::circuit_breaker::snapshot();
+    Json(serde_json::json!({"health": snapshot, "provider_circuits": circuits})).into_response()
 }
 
 // ── Helpers ─────────────────────────────────────────────────────
@@ -658,6 +782,9 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
         mask_required_secret(&mut nextcloud.app_token);
         mask_optional_secret(&mut nextcloud.webhook_secret);
     }
+    if let Some(teams) = masked.channels_config.teams.as_mut() {
+        mask_required_secret(&mut teams.app_password);
+    }
     if let Some(email) = masked.channels_config.email.as_mut() {
         mask_required_secret(&mut email.password);
     }
@@ -666,6 +793,15 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
         mask_optional_secret(&mut irc.nickserv_password);
         mask_optional_secret(&mut irc.sasl_password);
     }
+    if let Some(xmpp) = masked.channels_config.xmpp.as_mut() {
+        mask_required_secret(&mut xmpp.password);
+    }
+    if let Some(zulip) = masked.channels_config.zulip.as_mut() {
+        mask_required_secret(&mut zulip.api_key);
+    }
+    if let Some(rocketchat) = masked.channels_config.rocketchat.as_mut() {
+        mask_required_secret(&mut rocketchat.auth_token);
+    }
     if let Some(lark) = masked.channels_config.lark.as_mut() {
         mask_required_secret(&mut lark.app_secret);
         mask_optional_secret(&mut lark.encrypt_key);
@@ -806,6 +942,12 @@ fn restore_masked_sensitive_fields(
         restore_required_secret(&mut incoming_ch.app_token, &current_ch.app_token);
         restore_optional_secret(&mut incoming_ch.webhook_secret, &current_ch.webhook_secret);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.teams.as_mut(),
+        current.channels_config.teams.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.app_password, &current_ch.app_password);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.email.as_mut(),
         current.channels_config.email.as_ref(),
@@ -826,6 +968,24 @@ fn restore_masked_sensitive_fields(
         );
         restore_optional_secret(&mut incoming_ch.sasl_password, &current_ch.sasl_password);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.xmpp.as_mut(),
+        current.channels_config.xmpp.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.password, &current_ch.password);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.zulip.as_mut(),
+        current.channels_config.zulip.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.api_key, &current_ch.api_key);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.rocketchat.as_mut(),
+        current.channels_config.rocketchat.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.auth_token, &current_ch.auth_token);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.lark.as_mut(),
         current.channels_config.lark.as_ref(),
@@ -14,7 +14,7 @@ pub mod static_files;
 pub mod ws;
 
 use crate::channels::{
-    Channel, LinqChannel, NextcloudTalkChannel, QQChannel, SendMessage, WatiChannel,
+    Channel, LinqChannel, NextcloudTalkChannel, QQChannel, SendMessage, TeamsChannel, WatiChannel,
     WhatsAppChannel,
 };
 use crate::config::Config;
@@ -76,6 +76,10 @@ fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> S
     format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
 }
 
+fn teams_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("teams_{}_{}", msg.sender, msg.id)
+}
+
 fn qq_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
     format!("qq_{}_{}", msg.sender, msg.id)
 }
@@ -307,6 +311,7 @@ pub struct AppState {
     pub nextcloud_talk: Option<Arc<NextcloudTalkChannel>>,
     /// Nextcloud Talk webhook secret for signature verification
     pub nextcloud_talk_webhook_secret: Option<Arc<str>>,
+    pub teams: Option<Arc<TeamsChannel>>,
     pub wati: Option<Arc<WatiChannel>>,
     pub qq: Option<Arc<QQChannel>>,
     pub qq_webhook_enabled: bool,
@@ -367,6 +372,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
             max_tokens_override: None,
             model_support_vision: config.model_support_vision,
+            model_context_window: config.model_context_window,
         },
     )?);
     let model = config
@@ -418,7 +424,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let multimodal_config = config.multimodal.clone();
 
     // Cost tracker (optional)
-    let cost_tracker = if config.cost.enabled {
+    let cost_tracker = if let Some(shared) = crate::cost::tracker::shared() {
+        Some(shared)
+    } else if config.cost.enabled {
         match CostTracker::new(config.cost.clone(), &config.workspace_dir) {
             Ok(ct) => Some(Arc::new(ct)),
             Err(e) => {
@@ -449,12 +457,15 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .as_ref()
         .filter(|wa| wa.is_cloud_config())
         .map(|wa| {
-            Arc::new(WhatsAppChannel::new(
-                wa.access_token.clone().unwrap_or_default(),
-                wa.phone_number_id.clone().unwrap_or_default(),
-                wa.verify_token.clone().unwrap_or_default(),
-                wa.allowed_numbers.clone(),
-            ))
+            Arc::new(
+                WhatsAppChannel::new(
+                    wa.access_token.clone().unwrap_or_default(),
+                    wa.phone_number_id.clone().unwrap_or_default(),
+                    wa.verify_token.clone().unwrap_or_default(),
+                    wa.allowed_numbers.clone(),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            )
         });
 
     // WhatsApp app secret for webhook signature verification
@@ -539,6 +550,20 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             ))
         });
 
+    // Teams channel (if configured)
+    let teams_channel: Option<Arc<TeamsChannel>> =
+        config.channels_config.teams.as_ref().map(|teams_cfg| {
+            Arc::new(
+                TeamsChannel::new(
+                    teams_cfg.app_id.clone(),
+                    teams_cfg.app_password.clone(),
+                    teams_cfg.tenant_id.clone(),
+                    teams_cfg.allowed_users.clone(),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            )
+        });
+
     // Nextcloud Talk webhook secret for signature verification
     // Priority: environment variable > config file
     let nextcloud_talk_webhook_secret: Option<Arc<str>> =
@@ -625,6 +650,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     if nextcloud_talk_channel.is_some() {
         println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
     }
+    if teams_channel.is_some() {
+        println!("  POST /teams     — Microsoft Teams bot activities");
+    }
     if qq_webhook_enabled {
         println!("  POST /qq        — QQ Bot webhook (validation + events)");
     }
@@ -683,6 +711,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         linq_signing_secret,
         nextcloud_talk: nextcloud_talk_channel,
         nextcloud_talk_webhook_secret,
+        teams: teams_channel,
         wati: wati_channel,
         qq: qq_channel,
         qq_webhook_enabled,
@@ -725,6 +754,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/wati", get(handle_wati_verify))
         .route("/wati", post(handle_wati_webhook))
         .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
+        .route("/teams", post(handle_teams_activity))
         .route("/qq", post(handle_qq_webhook))
         // ── OpenAI-compatible endpoints ──
         .route("/v1/models", get(openai_compat::handle_v1_models))
@@ -744,6 +774,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/memory", get(api::handle_api_memory_list))
         .route("/api/memory", post(api::handle_api_memory_store))
         .route("/api/memory/{key}", delete(api::handle_api_memory_delete))
+        .route("/api/sessions", get(api::handle_api_sessions_list))
+        .route(
+            "/api/sessions/{key}",
+            get(api::handle_api_sessions_get).delete(api::handle_api_sessions_delete),
+        )
         .route("/api/cost", get(api::handle_api_cost))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
@@ -956,7 +991,7 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
 /// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk).
 async fn run_gateway_chat_with_tools(state: &AppState, message: &str) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    Box::pin(crate::agent::process_message(config, message)).await
 }
 
 fn sanitize_gateway_response(response: &str, tools: &[Box<dyn Tool>]) -> String {
@@ -1469,7 +1504,7 @@ async fn handle_whatsapp_message(
     };
 
     // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
+    let mut messages = wa.parse_webhook_payload(&payload);
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status updates)
@@ -1477,7 +1512,9 @@ async fn handle_whatsapp_message(
     }
 
     // Process each message
-    for msg in &messages {
+    for msg in &mut messages {
+        wa.download_media(msg).await;
+        let msg = &*msg;
         tracing::info!(
             "WhatsApp message from {}: {}",
             msg.sender,
@@ -1493,7 +1530,7 @@ async fn handle_whatsapp_message(
                 .await;
         }
 
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        match run_gateway_chat_with_tools(&state, &msg.content_with_markers()).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -1838,6 +1875,87 @@ async fn handle_nextcloud_talk_webhook(
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
 }
 
+/// POST /teams — incoming Bot Framework activity (Microsoft Teams)
+async fn handle_teams_activity(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref teams) = state.teams else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Teams not configured"})),
+        );
+    };
+
+    let Ok(activity) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    // ── Security: Verify the Bot Framework JWT ──
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if let Err(e) = teams.verify_request(authorization, &activity).await {
+        tracing::warn!("Teams activity authentication failed: {e}");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid token"})),
+        );
+    }
+
+    let Some(msg) = teams.parse_activity(&activity) else {
+        // Acknowledge conversation updates, typing and other non-message activities.
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    };
+
+    tracing::info!(
+        "Teams message from {}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    // With the channel runtime running, it owns the conversation (history,
+    // runtime commands, approval cards).
+    let Err(msg) = crate::channels::teams::forward_to_listener(msg).await else {
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    };
+
+    // Otherwise answer here. Bot Framework retries activities that take
+    // longer than 15 seconds to acknowledge, so reply in the background.
+    let teams = Arc::clone(teams);
+    tokio::spawn(async move {
+        if state.auto_save {
+            let key = teams_memory_key(&msg);
+            let _ = state
+                .mem
+                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        let reply = match Box::pin(run_gateway_chat_with_tools(&state, &msg.content)).await {
+            Ok(response) => {
+                sanitize_gateway_response(&response, state.tools_registry_exec.as_ref())
+            }
+            Err(e) => {
+                tracing::error!("LLM error for Teams message: {e:#}");
+                "Sorry, I couldn't process your message right now.".to_string()
+            }
+        };
+        if let Err(e) = teams
+            .send(&SendMessage::new(reply, &msg.reply_target).in_thread(msg.thread_ts.clone()))
+            .await
+        {
+            tracing::error!("Failed to send Teams reply: {e}");
+        }
+    });
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+}
+
 /// POST /qq — incoming QQ Bot webhook (validation + events)
 async fn handle_qq_webhook(
     State(state): State<AppState>,
@@ -2024,6 +2142,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2080,6 +2199,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2122,6 +2242,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2165,6 +2286,7 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2385,6 +2507,8 @@ mod tests {
             channel: "whatsapp".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         let key = whatsapp_memory_key(&msg);
@@ -2401,6 +2525,8 @@ mod tests {
             channel: "qq".into(),
             timestamp: 1,
             thread_ts: Some("msg-123".into()),
+            attachments: Vec::new(),
+            transcribed_voice: false,
         };
 
         let key = qq_memory_key(&msg);
@@ -2634,6 +2760,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2701,6 +2828,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2750,6 +2878,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2804,6 +2933,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2863,6 +2993,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2944,6 +3075,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -2997,6 +3129,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3055,6 +3188,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3118,6 +3252,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3177,6 +3312,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: Some(channel),
             nextcloud_talk_webhook_secret: Some(Arc::from(secret)),
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3206,6 +3342,58 @@ Reminder set successfully."#;
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn teams_webhook_rejects_missing_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(TeamsChannel::new(
+            "app-id".into(),
+            "app-password".into(),
+            None,
+            vec!["*".into()],
+        ));
+        let body = r#"{"type":"message","id":"1","serviceUrl":"https://smba.trafficmanager.net/amer/","from":{"id":"29:abc"},"conversation":{"id":"a:conv"},"text":"hello"}"#;
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            teams: Some(channel),
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let response = handle_teams_activity(State(state), HeaderMap::new(), Bytes::from(body))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
     #[tokio::test]
     async fn qq_webhook_returns_not_found_when_not_configured() {
         let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
@@ -3229,6 +3417,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
@@ -3280,6 +3469,7 @@ Reminder set successfully."#;
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
             wati: None,
             qq: Some(qq),
             qq_webhook_enabled: true,
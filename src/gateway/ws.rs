@@ -4,13 +4,18 @@
 //! ```text
 //! Client -> Server: {"type":"message","content":"Hello"}
 //! Server -> Client: {"type":"chunk","content":"Hi! "}
+//! Server -> Client: {"type":"chunk_reset"}
 //! Server -> Client: {"type":"tool_call","name":"shell","args":{...}}
 //! Server -> Client: {"type":"tool_result","name":"shell","output":"..."}
 //! Server -> Client: {"type":"done","full_response":"..."}
 //! ```
+//!
+//! `chunk` frames carry answer tokens as the provider streams them, including
+//! text produced on tool-call rounds. `chunk_reset` tells the client to discard
+//! the chunks received so far, e.g. before the next round's text begins.
 
 use super::AppState;
-use crate::agent::loop_::run_tool_call_loop;
+use crate::agent::loop_::{run_tool_call_loop, DRAFT_CLEAR_SENTINEL, DRAFT_PROGRESS_SENTINEL};
 use crate::approval::ApprovalManager;
 use crate::providers::ChatMessage;
 use axum::{
@@ -54,6 +59,18 @@ pub async fn handle_ws_chat(
         .into_response()
 }
 
+/// Map a draft delta from the agent loop to a client frame. Progress lines
+/// are internal trace output and are not forwarded.
+fn delta_frame(delta: &str) -> Option<serde_json::Value> {
+    if delta == DRAFT_CLEAR_SENTINEL {
+        Some(serde_json::json!({"type": "chunk_reset"}))
+    } else if delta.starts_with(DRAFT_PROGRESS_SENTINEL) || delta.is_empty() {
+        None
+    } else {
+        Some(serde_json::json!({"type": "chunk", "content": delta}))
+    }
+}
+
 async fn handle_socket(mut socket: WebSocket, state: AppState) {
     // Maintain conversation history for this WebSocket session
     let mut history: Vec<ChatMessage> = Vec::new();
@@ -124,26 +141,45 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
             "model": state.model,
         }));
 
-        // Run the agent loop with tool execution
-        let result = run_tool_call_loop(
-            state.provider.as_ref(),
-            &mut history,
-            state.tools_registry_exec.as_ref(),
-            state.observer.as_ref(),
-            &provider_label,
-            &state.model,
-            state.temperature,
-            true, // silent - no console output
-            Some(&approval_manager),
-            "webchat",
-            &state.multimodal,
-            state.max_tool_iterations,
-            None, // cancellation token
-            None, // delta streaming
-            None, // hooks
-            &[],  // excluded tools
-        )
-        .await;
+        // Run the agent loop with tool execution, relaying deltas as chunks
+        let (delta_tx, mut delta_rx) = tokio::sync::mpsc::channel::<String>(64);
+        let result = {
+            let agent_loop = run_tool_call_loop(
+                state.provider.as_ref(),
+                &mut history,
+                state.tools_registry_exec.as_ref(),
+                state.observer.as_ref(),
+                &provider_label,
+                &state.model,
+                state.temperature,
+                true, // silent - no console output
+                Some(&approval_manager),
+                "webchat",
+                &state.multimodal,
+                state.max_tool_iterations,
+                None, // cancellation token
+                Some(delta_tx),
+                None, // hooks
+                &[],  // excluded tools
+            );
+            tokio::pin!(agent_loop);
+
+            loop {
+                tokio::select! {
+                    result = &mut agent_loop => break result,
+                    Some(delta) = delta_rx.recv() => {
+                        if let Some(frame) = delta_frame(&delta) {
+                            let _ = socket.send(Message::Text(frame.to_string().into())).await;
+                        }
+                    }
+                }
+            }
+        };
+        while let Ok(delta) = delta_rx.try_recv() {
+            if let Some(frame) = delta_frame(&delta) {
+                let _ = socket.send(Message::Text(frame.to_string().into())).await;
+            }
+        }
 
         match result {
             Ok(response) => {
@@ -297,6 +333,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn delta_frame_maps_chunks_and_resets_and_skips_progress() {
+        assert_eq!(
+            delta_frame("Hi "),
+            Some(serde_json::json!({"type": "chunk", "content": "Hi "}))
+        );
+        assert_eq!(
+            delta_frame(DRAFT_CLEAR_SENTINEL),
+            Some(serde_json::json!({"type": "chunk_reset"}))
+        );
+        assert_eq!(
+            delta_frame(&format!("{DRAFT_PROGRESS_SENTINEL}Thinking...\n")),
+            None
+        );
+    }
+
     #[test]
     fn sanitize_ws_response_removes_tool_call_tags() {
         let input = r#"Before
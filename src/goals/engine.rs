@@ -1,3 +1,4 @@
+use crate::providers::{structured, ChatMessage, ChatRequest, Provider, ResponseFormat};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
@@ -120,6 +121,13 @@ impl<'de> Deserialize<'de> for StepStatus {
     }
 }
 
+/// Outcome of one executed step, parsed from a [`GoalEngine::step_result_format`] reply.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StepOutcome {
+    pub success: bool,
+    pub summary: String,
+}
+
 // ── GoalEngine ──────────────────────────────────────────────────
 
 pub struct GoalEngine {
@@ -241,7 +249,91 @@ impl GoalEngine {
         prompt
     }
 
-    /// Simple heuristic: output containing error indicators → failure.
+    /// Response format for step execution: `{"success": bool, "summary": string}`.
+    pub fn step_result_format() -> ResponseFormat {
+        ResponseFormat::json_schema(
+            "step_result",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "success": {"type": "boolean"},
+                    "summary": {"type": "string"}
+                },
+                "required": ["success", "summary"]
+            }),
+        )
+    }
+
+    /// Parse a step reply strictly against [`Self::step_result_format`].
+    ///
+    /// Replies that are not JSON matching the schema are errors; there is no
+    /// free-text fallback.
+    pub fn parse_step_result(output: &str) -> Result<StepOutcome> {
+        let value = structured::parse_reply(output, &Self::step_result_format())
+            .map_err(|e| anyhow::anyhow!("invalid step result: {e}"))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Execute one step through `provider`, requesting [`Self::step_result_format`]
+    /// and parsing the reply with [`Self::parse_step_result`].
+    pub async fn execute_step(
+        provider: &dyn Provider,
+        model: &str,
+        temperature: f64,
+        goal: &Goal,
+        step: &Step,
+    ) -> Result<StepOutcome> {
+        let format = Self::step_result_format();
+        let messages = [ChatMessage::user(Self::build_step_prompt(goal, step))];
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                    response_format: Some(&format),
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        Self::parse_step_result(response.text_or_empty())
+    }
+
+    /// Record `outcome` for the step at `(goal_idx, step_idx)`.
+    ///
+    /// A failed step stays pending for another attempt until it reaches
+    /// `MAX_STEP_ATTEMPTS`, after which it is marked failed.
+    pub fn apply_step_outcome(
+        state: &mut GoalState,
+        goal_idx: usize,
+        step_idx: usize,
+        outcome: &StepOutcome,
+    ) {
+        let Some(goal) = state.goals.get_mut(goal_idx) else {
+            return;
+        };
+        let Some(step) = goal.steps.get_mut(step_idx) else {
+            return;
+        };
+        step.attempts += 1;
+        if outcome.success {
+            step.status = StepStatus::Completed;
+            step.result = Some(outcome.summary.clone());
+            let _ = writeln!(goal.context, "{}: {}", step.description, outcome.summary);
+            goal.last_error = None;
+        } else {
+            step.status = if step.attempts >= MAX_STEP_ATTEMPTS {
+                StepStatus::Failed
+            } else {
+                StepStatus::Pending
+            };
+            goal.last_error = Some(outcome.summary.clone());
+        }
+    }
+
+    /// Simple heuristic for free-form output: error indicators → failure.
+    ///
+    /// Step replies are parsed strictly by [`Self::parse_step_result`] instead.
     pub fn interpret_result(output: &str) -> bool {
         let lower = output.to_ascii_lowercase();
         let failure_indicators = [
@@ -504,6 +596,98 @@ target = "oc_test"
         assert!(!GoalEngine::interpret_result("Fatal: repository not found"));
     }
 
+    #[test]
+    fn parse_step_result_is_strict() {
+        let outcome =
+            GoalEngine::parse_step_result(r#"{"success": false, "summary": "Tests pass locally"}"#)
+                .unwrap();
+        assert!(!outcome.success);
+        assert_eq!(outcome.summary, "Tests pass locally");
+
+        assert!(GoalEngine::parse_step_result("Done, everything worked.").is_err());
+        assert!(GoalEngine::parse_step_result(r#"{"success": "yes", "summary": "ok"}"#).is_err());
+        assert!(GoalEngine::parse_step_result(r#"{"summary": "ok"}"#).is_err());
+    }
+
+    struct StepProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for StepProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            assert!(message.contains("Current step: Deploy"));
+            Ok(r#"{"success": true, "summary": "Deployed v2"}"#.into())
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            model: &str,
+            temperature: f64,
+        ) -> Result<String> {
+            assert!(messages
+                .iter()
+                .any(|m| m.role == "system" && m.content.contains("\"summary\"")));
+            let last = messages.iter().rfind(|m| m.role == "user").unwrap();
+            self.chat_with_system(None, &last.content, model, temperature)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_step_requests_and_applies_structured_result() {
+        let mut state = sample_goal_state();
+        let (gi, si) = GoalEngine::select_next_actionable(&state).unwrap();
+        state.goals[gi].steps[si].description = "Deploy".into();
+
+        let outcome = GoalEngine::execute_step(
+            &StepProvider,
+            "model",
+            0.0,
+            &state.goals[gi],
+            &state.goals[gi].steps[si],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            outcome,
+            StepOutcome {
+                success: true,
+                summary: "Deployed v2".into()
+            }
+        );
+
+        GoalEngine::apply_step_outcome(&mut state, gi, si, &outcome);
+        let step = &state.goals[gi].steps[si];
+        assert_eq!(step.status, StepStatus::Completed);
+        assert_eq!(step.result.as_deref(), Some("Deployed v2"));
+        assert!(state.goals[gi].context.contains("Deployed v2"));
+    }
+
+    #[test]
+    fn failed_outcomes_retry_until_max_attempts() {
+        let mut state = sample_goal_state();
+        let (gi, si) = GoalEngine::select_next_actionable(&state).unwrap();
+        let failure = StepOutcome {
+            success: false,
+            summary: "missing credentials".into(),
+        };
+        let start = state.goals[gi].steps[si].attempts;
+        for _ in start..MAX_STEP_ATTEMPTS {
+            GoalEngine::apply_step_outcome(&mut state, gi, si, &failure);
+        }
+        assert_eq!(state.goals[gi].steps[si].status, StepStatus::Failed);
+        assert_eq!(
+            state.goals[gi].last_error.as_deref(),
+            Some("missing credentials")
+        );
+    }
+
     #[tokio::test]
     async fn load_save_state_roundtrip() {
         let tmp = TempDir::new().unwrap();
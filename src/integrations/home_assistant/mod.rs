@@ -68,14 +68,16 @@ pub async fn get_ha_insights(client: &HomeAssistantClient) -> anyhow::Result<Str
     let states = client.fetch_states().await?;
     let analyzer = analytics::HomeHabitAnalyzer::new();
     let summary = analyzer.analyze(&states);
-    
+
     let mut output = summary.message;
     if !summary.suggestions.is_empty() {
         output.push_str("\n\nSuggestions:\n");
         for suggestion in summary.suggestions {
-            output.push_str(&format!("- {suggestion}\n"));
+            output.push_str("- ");
+            output.push_str(&suggestion);
+            output.push('\n');
         }
     }
-    
+
     Ok(output)
 }
@@ -2,10 +2,8 @@ pub mod home_assistant;
 pub mod registry;
 
 use crate::config::Config;
+use anyhow::Result;
 
-<<<<<<< HEAD
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-=======
 /// Integration status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum IntegrationStatus {
@@ -19,7 +17,6 @@ pub enum IntegrationStatus {
 
 /// Integration category
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
->>>>>>> upstream/main
 pub enum IntegrationCategory {
     Chat,
     AiModel,
@@ -33,6 +30,20 @@ pub enum IntegrationCategory {
 }
 
 impl IntegrationCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Chat => "Chat Providers",
+            Self::AiModel => "AI Models",
+            Self::Productivity => "Productivity",
+            Self::MusicAudio => "Music & Audio",
+            Self::SmartHome => "Smart Home",
+            Self::ToolsAutomation => "Tools & Automation",
+            Self::MediaCreative => "Media & Creative",
+            Self::Social => "Social",
+            Self::Platform => "Platforms",
+        }
+    }
+
     pub fn all() -> &'static [Self] {
         &[
             Self::Chat,
@@ -48,13 +59,6 @@ impl IntegrationCategory {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum IntegrationStatus {
-    Active,
-    Available,
-    ComingSoon,
-}
-
 pub struct IntegrationEntry {
     pub name: &'static str,
     pub description: &'static str,
@@ -64,19 +68,6 @@ pub struct IntegrationEntry {
 
 pub fn handle_command(command: crate::IntegrationCommands, config: &Config) -> anyhow::Result<()> {
     match command {
-<<<<<<< HEAD
-        crate::IntegrationCommands::Info { name } => {
-            let all = registry::all_integrations();
-            let entry = all.iter().find(|e| e.name.eq_ignore_ascii_case(&name));
-
-            if let Some(entry) = entry {
-                let status = (entry.status_fn)(config);
-                let (icon, label) = match status {
-                    IntegrationStatus::Active => ("✅", "Active"),
-                    IntegrationStatus::Available => ("⚪", "Available"),
-                    IntegrationStatus::ComingSoon => ("🔜", "Coming Soon"),
-                };
-=======
         crate::IntegrationCommands::List { category, status } => {
             list_integrations(config, category.as_deref(), status.as_deref())
         }
@@ -225,56 +216,13 @@ fn search_integrations(config: &Config, query: &str) -> Result<()> {
 fn show_integration_info(config: &Config, name: &str) -> Result<()> {
     let entries = registry::all_integrations();
     let name_lower = name.to_lowercase();
->>>>>>> upstream/main
-
-                println!();
-                println!(
-                    "  {} {} — {}",
-                    icon,
-                    console::style(entry.name).white().bold(),
-                    entry.description
-                );
-                println!("  Category: {:?}", entry.category);
-                println!("  Status:   {label}");
-                println!();
-
-<<<<<<< HEAD
-                // Show setup hints based on integration
-                match entry.name {
-                    "Telegram" => {
-                        println!("  Setup:");
-                        println!("    1. Message @BotFather on Telegram");
-                        println!("    2. Create a bot and copy the token");
-                        println!("    3. Run: zeroclaw channel add telegram '{{\"bot_token\":\"...\"}}'");
-                    }
-                    "Discord" => {
-                        println!("  Setup:");
-                        println!("    1. Go to https://discord.com/developers/applications");
-                        println!("    2. Create app → Bot → Copy token");
-                        println!("    3. Enable MESSAGE CONTENT intent");
-                        println!("    4. Run: zeroclaw channel add discord '{{\"bot_token\":\"...\"}}'");
-                    }
-                    "Home Assistant" => {
-                        println!("  Details:");
-                        println!("    ZeroClaw connects to Home Assistant for read-only analytics.");
-                        println!("    Status is currently '{label}'.");
-                    }
-                    "Matrix" => {
-                        println!("  Setup:");
-                        println!("    1. Create a Matrix account for the bot");
-                        println!("    2. Get homeserver URL and access token");
-                        println!("    3. Run: zeroclaw channel add matrix '{{\"homeserver\":\"...\",\"access_token\":\"...\",\"room_id\":\"...\"}}'");
-                    }
-                    _ => {
-                        if status == IntegrationStatus::ComingSoon {
-                            println!("  This integration is planned. Stay tuned!");
-                        }
-                    }
-                }
-                println!();
-            } else {
-                anyhow::bail!("Integration '{}' not found.", name);
-=======
+
+    let Some(entry) = entries.iter().find(|e| e.name.to_lowercase() == name_lower) else {
+        anyhow::bail!(
+            "Unknown integration: {name}. Check README for supported integrations or run `zeroclaw onboard --interactive` to configure channels/providers."
+        );
+    };
+
     let status = (entry.status_fn)(config);
     let icon = status_icon(status);
     let label = match status {
@@ -310,6 +258,11 @@ fn show_integration_info(config: &Config, name: &str) -> Result<()> {
             println!("    3. Enable MESSAGE CONTENT intent");
             println!("    4. Run: zeroclaw onboard --channels-only");
         }
+        "Home Assistant" => {
+            println!("  Details:");
+            println!("    ZeroClaw connects to Home Assistant for read-only analytics.");
+            println!("    Status is currently '{label}'.");
+        }
         "Slack" => {
             println!("  Setup:");
             println!("    1. Go to https://api.slack.com/apps");
@@ -357,11 +310,12 @@ fn show_integration_info(config: &Config, name: &str) -> Result<()> {
             if status == IntegrationStatus::ComingSoon {
                 println!("  This integration is planned. Stay tuned!");
                 println!("  Track progress: https://github.com/theonlyhennygod/zeroclaw");
->>>>>>> upstream/main
             }
-            Ok(())
         }
     }
+    println!();
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -373,7 +327,9 @@ mod tests {
     fn handle_command_info_is_case_insensitive() {
         let config = Config::default();
         let result = handle_command(
-            crate::IntegrationCommands::Info { name: "TELEGRAM".into() },
+            crate::IntegrationCommands::Info {
+                name: "TELEGRAM".into(),
+            },
             &config,
         );
         assert!(result.is_ok());
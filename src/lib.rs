@@ -332,6 +332,80 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Rebuild the keyword index and re-embed memories with the configured embedding provider
+    Reindex,
+    /// Export every memory entry to a versioned, checksummed archive
+    Export {
+        /// `archive` keeps embeddings; `jsonl` writes entries only
+        #[arg(long, value_enum, default_value_t = crate::memory::archive::ArchiveFormat::Archive)]
+        format: crate::memory::archive::ArchiveFormat,
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import entries from an archive written by `memory export`
+    Import {
+        /// Archive file to read
+        path: std::path::PathBuf,
+        /// Verify the archive and report what would be imported without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge near-duplicate daily/conversation memories and promote recurring facts to core
+    Consolidate {
+        /// Show the near-duplicate groups that would be merged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Install a recurring cron job with this expression instead of running now
+        #[arg(long)]
+        schedule: Option<String>,
+        /// Timezone for `--schedule` (IANA name, e.g. Europe/Berlin)
+        #[arg(long, requires = "schedule")]
+        tz: Option<String>,
+    },
+    /// List recent consolidation merges
+    Merges {
+        /// Maximum number of merges to display
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Undo a consolidation merge, restoring its source entries
+    Unmerge {
+        /// Merge ID (or unique prefix) as printed by `memory merges`
+        id: String,
+    },
+}
+
+/// Channel session subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List persisted channel conversation sessions
+    List {
+        /// Filter by channel name (e.g. telegram, slack)
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Show the stored conversation turns for a session
+    Show {
+        /// Session key (as printed by `sessions list`)
+        key: String,
+    },
+    /// Delete a single persisted session
+    Delete {
+        /// Session key to delete
+        key: String,
+    },
+    /// Delete all persisted sessions, optionally for one channel
+    Clear {
+        /// Only clear sessions for this channel
+        #[arg(long)]
+        channel: Option<String>,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Remove sessions older than the configured retention
+    Prune,
 }
 
 /// Integration subcommands
@@ -53,9 +53,6 @@ mod agent;
 mod approval;
 mod auth;
 mod channels;
-mod rag {
-    pub use zeroclaw::rag::*;
-}
 mod config;
 mod coordination;
 mod cost;
@@ -77,6 +74,7 @@ mod observability;
 mod onboard;
 mod peripherals;
 mod providers;
+mod rag;
 mod runtime;
 mod security;
 mod service;
@@ -92,7 +90,7 @@ use config::Config;
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
     ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, MigrateCommands,
-    PeripheralCommands, ServiceCommands, SkillCommands,
+    PeripheralCommands, ServiceCommands, SessionCommands, SkillCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -376,7 +374,10 @@ Examples:
     },
 
     /// List supported AI providers
-    Providers,
+    Providers {
+        #[command(subcommand)]
+        provider_command: Option<ProviderCommands>,
+    },
 
     /// Manage channels (telegram, discord, slack)
     #[command(long_about = "\
@@ -397,6 +398,26 @@ Examples:
         channel_command: ChannelCommands,
     },
 
+    /// Manage persisted channel conversation sessions
+    #[command(long_about = "\
+Manage persisted channel conversation sessions.
+
+Channel conversation histories are stored in the workspace so they \
+survive daemon restarts. List, inspect, delete, or prune them here. \
+Changes take effect for a running daemon after it restarts.
+
+Examples:
+  zeroclaw sessions list
+  zeroclaw sessions list --channel telegram
+  zeroclaw sessions show telegram_alice
+  zeroclaw sessions delete telegram_alice
+  zeroclaw sessions clear --channel discord --yes
+  zeroclaw sessions prune")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
     /// Browse 50+ integrations
     Integrations {
         #[command(subcommand)]
@@ -457,25 +478,57 @@ Examples:
         peripheral_command: zeroclaw::PeripheralCommands,
     },
 
-    /// Manage agent memory (list, get, stats, clear)
+    /// Manage agent memory (list, get, stats, clear, export, import, consolidate)
     #[command(long_about = "\
 Manage agent memory entries.
 
 List, inspect, and clear memory entries stored by the agent. \
-Supports filtering by category and session, pagination, and \
-batch clearing with confirmation.
+Supports filtering by category and session, pagination, \
+batch clearing with confirmation, export/import through \
+portable archives, and consolidation of near-duplicate entries.
 
 Examples:
   zeroclaw memory stats
   zeroclaw memory list
   zeroclaw memory list --category core --limit 10
   zeroclaw memory get <key>
-  zeroclaw memory clear --category conversation --yes")]
+  zeroclaw memory clear --category conversation --yes
+  zeroclaw memory reindex
+  zeroclaw memory export --output memory.zcmem
+  zeroclaw memory import memory.zcmem
+  zeroclaw memory consolidate --dry-run
+  zeroclaw memory consolidate --schedule '30 3 * * *'")]
     Memory {
         #[command(subcommand)]
         memory_command: MemoryCommands,
     },
 
+    /// Ingest workspace documents for the knowledge_search tool
+    #[command(long_about = "\
+Ingest workspace documents into the knowledge index.
+
+Markdown, text, HTML and (with the rag-pdf feature) PDF files are \
+chunked, embedded with the [memory] embedding provider and stored \
+with file/line citations for the knowledge_search tool. Unchanged \
+files are skipped by content hash; files deleted from an ingested \
+folder are dropped from the index.
+
+Examples:
+  zeroclaw ingest docs/
+  zeroclaw ingest notes/runbook.md
+  zeroclaw ingest --watch              # folders from [knowledge] paths
+  zeroclaw ingest --status")]
+    Ingest {
+        /// Files or folders to ingest (defaults to `[knowledge] paths`)
+        paths: Vec<std::path::PathBuf>,
+        /// Keep running and re-ingest changed files every `watch_interval_secs`
+        #[arg(long)]
+        watch: bool,
+        /// Show index statistics and exit
+        #[arg(long, conflicts_with = "watch")]
+        status: bool,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
@@ -650,6 +703,23 @@ enum ModelCommands {
     Status,
 }
 
+#[derive(Subcommand, Debug)]
+enum ProviderCommands {
+    /// Run the provider conformance suite live against a provider
+    Verify {
+        /// Provider name (e.g. openai, anthropic, custom:https://host/v1)
+        name: String,
+
+        /// Model to verify with (defaults to the configured default model for the default provider)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Record the run to a cassette file for offline replay in tests
+        #[arg(long)]
+        record: Option<std::path::PathBuf>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum DoctorCommands {
     /// Probe model catalogs across providers and report availability
@@ -707,6 +777,48 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Rebuild the keyword index and re-embed memories with the configured embedding provider
+    Reindex,
+    /// Export every memory entry to a versioned, checksummed archive
+    Export {
+        /// `archive` keeps embeddings; `jsonl` writes entries only
+        #[arg(long, value_enum, default_value_t = crate::memory::archive::ArchiveFormat::Archive)]
+        format: crate::memory::archive::ArchiveFormat,
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import entries from an archive written by `memory export`
+    Import {
+        /// Archive file to read
+        path: std::path::PathBuf,
+        /// Verify the archive and report what would be imported without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge near-duplicate daily/conversation memories and promote recurring facts to core
+    Consolidate {
+        /// Show the near-duplicate groups that would be merged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Install a recurring cron job with this expression instead of running now
+        #[arg(long)]
+        schedule: Option<String>,
+        /// Timezone for `--schedule` (IANA name, e.g. Europe/Berlin)
+        #[arg(long, requires = "schedule")]
+        tz: Option<String>,
+    },
+    /// List recent consolidation merges
+    Merges {
+        /// Maximum number of merges to display
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Undo a consolidation merge, restoring its source entries
+    Unmerge {
+        /// Merge ID (or unique prefix) as printed by `memory merges`
+        id: String,
+    },
 }
 
 #[tokio::main]
@@ -780,9 +892,9 @@ async fn main() -> Result<()> {
             bail!("--channels-only does not accept --force");
         }
         let config = if channels_only {
-            onboard::run_channels_repair_wizard().await
+            Box::pin(onboard::run_channels_repair_wizard()).await
         } else if interactive {
-            onboard::run_wizard(force).await
+            Box::pin(onboard::run_wizard(force)).await
         } else {
             onboard::run_quick_setup(
                 api_key.as_deref(),
@@ -795,7 +907,7 @@ async fn main() -> Result<()> {
         }?;
         // Auto-start channels if user said yes during wizard
         if std::env::var("ZEROCLAW_AUTOSTART_CHANNELS").as_deref() == Ok("1") {
-            channels::start_channels(config).await?;
+            Box::pin(channels::start_channels(config)).await?;
         }
         return Ok(());
     }
@@ -804,6 +916,8 @@ async fn main() -> Result<()> {
     let mut config = Config::load_or_init().await?;
     config.apply_env_overrides();
     observability::runtime_trace::init_from_config(&config.observability, &config.workspace_dir);
+    providers::circuit_breaker::init_persistence(&config.workspace_dir);
+    cost::tracker::init_shared(&config.cost, &config.workspace_dir);
     if config.security.otp.enabled {
         let config_dir = config
             .config_path
@@ -852,7 +966,7 @@ async fn main() -> Result<()> {
             if let Some(ref backend) = memory_backend {
                 config.memory.backend = backend.clone();
             }
-            agent::run(
+            Box::pin(agent::run(
                 config,
                 message,
                 provider,
@@ -860,7 +974,7 @@ async fn main() -> Result<()> {
                 temperature,
                 peripheral,
                 true,
-            )
+            ))
             .await
             .map(|_| ())
         }
@@ -1017,7 +1131,21 @@ async fn main() -> Result<()> {
             ModelCommands::Status => onboard::run_models_status(&config).await,
         },
 
-        Commands::Providers => {
+        Commands::Providers {
+            provider_command:
+                Some(ProviderCommands::Verify {
+                    name,
+                    model,
+                    record,
+                }),
+        } => {
+            providers::conformance::verify(&config, &name, model.as_deref(), record.as_deref())
+                .await
+        }
+
+        Commands::Providers {
+            provider_command: None,
+        } => {
             let providers = providers::list_providers();
             let current = config
                 .default_provider
@@ -1079,11 +1207,15 @@ async fn main() -> Result<()> {
         },
 
         Commands::Channel { channel_command } => match channel_command {
-            ChannelCommands::Start => channels::start_channels(config).await,
-            ChannelCommands::Doctor => channels::doctor_channels(config).await,
+            ChannelCommands::Start => Box::pin(channels::start_channels(config)).await,
+            ChannelCommands::Doctor => Box::pin(channels::doctor_channels(config)).await,
             other => channels::handle_command(other, &config).await,
         },
 
+        Commands::Sessions { session_command } => {
+            channels::session_store::handle_command(session_command, &config)
+        }
+
         Commands::Integrations {
             integration_command,
         } => integrations::handle_command(integration_command, &config),
@@ -1098,6 +1230,12 @@ async fn main() -> Result<()> {
             memory::cli::handle_command(memory_command, &config).await
         }
 
+        Commands::Ingest {
+            paths,
+            watch,
+            status,
+        } => rag::ingest::handle_command(&config, paths, watch, status).await,
+
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
         Commands::Hardware { hardware_command } => {
@@ -0,0 +1,454 @@
+//! Portable memory archives — move an agent's memory between machines or backends.
+//!
+//! An archive is line-oriented UTF-8:
+//!
+//! ```text
+//! {"format":"zeroclaw-memory","version":1,"layout":"archive",...,"checksum":"sha256:..."}
+//! {"key":"user_lang","content":"Prefers Rust","category":"core",...}
+//! {"key":"standup","content":"Standup at 9","category":"daily",...}
+//! ```
+//!
+//! The first line is an [`ArchiveManifest`]; every following line is one record.
+//! The manifest checksum covers the record lines byte-for-byte, so truncated or
+//! hand-edited files are rejected on import.
+//!
+//! - `jsonl` records carry entries only; the target backend re-embeds on import.
+//! - `archive` records also carry the stored vector (base64, little-endian `f32`)
+//!   tagged with its embedding fingerprint, so a target in the same embedding
+//!   space skips re-embedding.
+
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord, StoredEmbedding,
+};
+use super::vector;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, Write};
+
+/// Format tag written into every manifest.
+pub const ARCHIVE_FORMAT: &str = "zeroclaw-memory";
+/// Current archive version. Readers reject anything newer.
+pub const ARCHIVE_VERSION: u32 = 1;
+/// Upper bound on records preallocated from the (untrusted) manifest count.
+const MAX_PREALLOCATED_RECORDS: usize = 1024;
+
+/// Record layout of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// Entries only (readable, target re-embeds)
+    Jsonl,
+    /// Entries plus stored embeddings (full fidelity)
+    Archive,
+}
+
+impl std::fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jsonl => write!(f, "jsonl"),
+            Self::Archive => write!(f, "archive"),
+        }
+    }
+}
+
+/// First line of every archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    pub layout: ArchiveFormat,
+    pub exported_at: String,
+    /// Backend the entries were exported from (e.g. `sqlite`)
+    pub source_backend: String,
+    pub entries: usize,
+    /// Records that carry an embedding
+    pub embeddings: usize,
+    /// `sha256:<hex>` over the record lines, each terminated by `\n`
+    pub checksum: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedRecord {
+    key: String,
+    content: String,
+    category: MemoryCategory,
+    timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "MemoryMetadata::is_empty")]
+    metadata: MemoryMetadata,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    embedding: Option<ArchivedEmbedding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedEmbedding {
+    fingerprint: String,
+    dimensions: usize,
+    /// Base64 of the little-endian `f32` bytes
+    vector: String,
+}
+
+impl ArchivedRecord {
+    fn from_record(record: MemoryRecord, format: ArchiveFormat) -> Self {
+        let MemoryRecord { entry, embedding } = record;
+        let embedding = match format {
+            ArchiveFormat::Jsonl => None,
+            ArchiveFormat::Archive => embedding.map(|stored| ArchivedEmbedding {
+                fingerprint: stored.fingerprint,
+                dimensions: stored.vector.len(),
+                vector: base64::engine::general_purpose::STANDARD
+                    .encode(vector::vec_to_bytes(&stored.vector)),
+            }),
+        };
+        Self {
+            key: entry.key,
+            content: entry.content,
+            category: entry.category,
+            timestamp: entry.timestamp,
+            session_id: entry.session_id,
+            metadata: entry.metadata,
+            embedding,
+        }
+    }
+
+    fn into_record(self) -> Result<MemoryRecord> {
+        let embedding = match self.embedding {
+            Some(archived) => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(&archived.vector)
+                    .with_context(|| format!("invalid embedding for key '{}'", self.key))?;
+                let vector = vector::bytes_to_vec(&bytes);
+                if vector.len() != archived.dimensions {
+                    bail!(
+                        "embedding for key '{}' has {} dimensions, manifest says {}",
+                        self.key,
+                        vector.len(),
+                        archived.dimensions
+                    );
+                }
+                Some(StoredEmbedding {
+                    fingerprint: archived.fingerprint,
+                    vector,
+                })
+            }
+            None => None,
+        };
+
+        Ok(MemoryRecord {
+            entry: MemoryEntry {
+                id: String::new(),
+                key: self.key,
+                content: self.content,
+                category: self.category,
+                timestamp: self.timestamp,
+                session_id: self.session_id,
+                score: None,
+                metadata: self.metadata,
+            },
+            embedding,
+        })
+    }
+}
+
+fn checksum_of<'a>(lines: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    for line in lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("sha256:{}", hex::encode(hasher.finalize()))
+}
+
+/// Serialize `records` as an archive into `writer`.
+pub fn write_archive<W: Write>(
+    writer: &mut W,
+    records: Vec<MemoryRecord>,
+    format: ArchiveFormat,
+    source_backend: &str,
+) -> Result<ArchiveManifest> {
+    let archived: Vec<ArchivedRecord> = records
+        .into_iter()
+        .map(|record| ArchivedRecord::from_record(record, format))
+        .collect();
+    let embeddings = archived.iter().filter(|r| r.embedding.is_some()).count();
+    let lines = archived
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<String>>>()?;
+
+    let manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        layout: format,
+        exported_at: Utc::now().to_rfc3339(),
+        source_backend: source_backend.to_string(),
+        entries: lines.len(),
+        embeddings,
+        checksum: checksum_of(lines.iter().map(String::as_str)),
+    };
+
+    writeln!(writer, "{}", serde_json::to_string(&manifest)?)?;
+    for line in &lines {
+        writeln!(writer, "{line}")?;
+    }
+    writer.flush()?;
+    Ok(manifest)
+}
+
+/// Parse and verify an archive. Fails on unknown formats, newer versions,
+/// entry-count mismatches, or a checksum mismatch.
+pub fn read_archive<R: BufRead>(reader: R) -> Result<(ArchiveManifest, Vec<MemoryRecord>)> {
+    let mut lines = reader.lines();
+    let header = lines
+        .next()
+        .context("archive is empty")?
+        .context("failed to read archive manifest")?;
+    let manifest: ArchiveManifest = serde_json::from_str(header.trim_end_matches('\r'))
+        .context("first line is not a zeroclaw memory archive manifest")?;
+
+    if manifest.format != ARCHIVE_FORMAT {
+        bail!("unsupported archive format '{}'", manifest.format);
+    }
+    if manifest.version > ARCHIVE_VERSION {
+        bail!(
+            "archive version {} is newer than supported version {ARCHIVE_VERSION}; upgrade zeroclaw",
+            manifest.version
+        );
+    }
+
+    let mut record_lines = Vec::with_capacity(manifest.entries.min(MAX_PREALLOCATED_RECORDS));
+    for line in lines {
+        let line = line.context("failed to read archive record")?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        if record_lines.len() == manifest.entries {
+            bail!(
+                "archive holds more records than the {} its manifest declares",
+                manifest.entries
+            );
+        }
+        record_lines.push(line.to_string());
+    }
+
+    if record_lines.len() != manifest.entries {
+        bail!(
+            "archive holds {} records but manifest declares {}",
+            record_lines.len(),
+            manifest.entries
+        );
+    }
+    let actual = checksum_of(record_lines.iter().map(String::as_str));
+    if actual != manifest.checksum {
+        bail!(
+            "archive checksum mismatch (expected {}, got {actual}); the file is corrupt or was edited",
+            manifest.checksum
+        );
+    }
+
+    let records = record_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str::<ArchivedRecord>(line)
+                .with_context(|| format!("invalid record on line {}", i + 2))?
+                .into_record()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((manifest, records))
+}
+
+/// Export every live entry of `memory` into `writer`.
+pub async fn export_memory<W: Write>(
+    memory: &dyn Memory,
+    writer: &mut W,
+    format: ArchiveFormat,
+) -> Result<ArchiveManifest> {
+    let records = memory.export_records().await?;
+    write_archive(writer, records, format, memory.name())
+}
+
+/// Upsert every record into `memory`, returning how many were written.
+pub async fn import_memory(memory: &dyn Memory, records: Vec<MemoryRecord>) -> Result<usize> {
+    let mut imported = 0;
+    for record in records {
+        let key = record.entry.key.clone();
+        memory
+            .import_record(record)
+            .await
+            .with_context(|| format!("failed to import memory '{key}'"))?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::{EmbeddingProvider, HashedNgramEmbedding};
+    use crate::memory::SqliteMemory;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn sqlite_with_embedder(dir: &TempDir) -> SqliteMemory {
+        let embedder: Arc<dyn EmbeddingProvider> = Arc::new(HashedNgramEmbedding::new(64));
+        SqliteMemory::with_embedder(dir.path(), embedder, 0.7, 0.3, 100, None).unwrap()
+    }
+
+    async fn seed(mem: &dyn Memory) {
+        mem.store("lang", "Prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store_with_metadata(
+            "standup",
+            "Standup moved to 10:30",
+            MemoryCategory::Custom("work".into()),
+            Some("sess-1"),
+            MemoryMetadata::default().with_tags(["schedule"]),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn archive_roundtrip_preserves_entries_and_embeddings() {
+        let src_dir = TempDir::new().unwrap();
+        let src = sqlite_with_embedder(&src_dir);
+        seed(&src).await;
+
+        let mut buf = Vec::new();
+        let manifest = export_memory(&src, &mut buf, ArchiveFormat::Archive)
+            .await
+            .unwrap();
+        assert_eq!(manifest.entries, 2);
+        assert_eq!(manifest.embeddings, 2);
+        assert_eq!(manifest.source_backend, "sqlite");
+
+        let (parsed, records) = read_archive(buf.as_slice()).unwrap();
+        assert_eq!(parsed, manifest);
+        let original = src.export_records().await.unwrap();
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = SqliteMemory::new(dst_dir.path()).unwrap();
+        assert_eq!(import_memory(&dst, records).await.unwrap(), 2);
+
+        let standup = dst.get("standup").await.unwrap().unwrap();
+        assert_eq!(standup.category, MemoryCategory::Custom("work".into()));
+        assert_eq!(standup.session_id.as_deref(), Some("sess-1"));
+        assert_eq!(standup.metadata.tags, vec!["schedule"]);
+        let src_standup = src.get("standup").await.unwrap().unwrap();
+        assert_eq!(standup.timestamp, src_standup.timestamp);
+
+        // Vectors survive even though the target has no embedder configured
+        let copied = dst.export_records().await.unwrap();
+        for record in &original {
+            let twin = copied
+                .iter()
+                .find(|r| r.entry.key == record.entry.key)
+                .unwrap();
+            assert_eq!(twin.embedding, record.embedding);
+        }
+        assert_eq!(
+            dst.embedding_fingerprint().unwrap(),
+            src.embedding_fingerprint().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn jsonl_export_omits_embeddings() {
+        let src_dir = TempDir::new().unwrap();
+        let src = sqlite_with_embedder(&src_dir);
+        seed(&src).await;
+
+        let mut buf = Vec::new();
+        let manifest = export_memory(&src, &mut buf, ArchiveFormat::Jsonl)
+            .await
+            .unwrap();
+        assert_eq!(manifest.layout, ArchiveFormat::Jsonl);
+        assert_eq!(manifest.embeddings, 0);
+
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(!text.contains("\"embedding\""));
+        let (_, records) = read_archive(text.as_bytes()).unwrap();
+        assert!(records.iter().all(|r| r.embedding.is_none()));
+    }
+
+    #[tokio::test]
+    async fn tampered_archive_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(dir.path()).unwrap();
+        seed(&mem).await;
+
+        let mut buf = Vec::new();
+        export_memory(&mem, &mut buf, ArchiveFormat::Jsonl)
+            .await
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        let edited = text.replace("Prefers Rust", "Prefers Go");
+        let err = read_archive(edited.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        let truncated = text.lines().take(2).collect::<Vec<_>>().join("\n");
+        let err = read_archive(truncated.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("manifest declares 2"));
+    }
+
+    #[test]
+    fn newer_archive_version_is_rejected() {
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.into(),
+            version: ARCHIVE_VERSION + 1,
+            layout: ArchiveFormat::Jsonl,
+            exported_at: "2026-01-01T00:00:00Z".into(),
+            source_backend: "sqlite".into(),
+            entries: 0,
+            embeddings: 0,
+            checksum: checksum_of([]),
+        };
+        let text = serde_json::to_string(&manifest).unwrap();
+        let err = read_archive(text.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+    }
+
+    #[test]
+    fn manifest_entry_count_is_not_trusted() {
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.into(),
+            version: ARCHIVE_VERSION,
+            layout: ArchiveFormat::Jsonl,
+            exported_at: "2026-01-01T00:00:00Z".into(),
+            source_backend: "sqlite".into(),
+            entries: usize::MAX,
+            embeddings: 0,
+            checksum: checksum_of([]),
+        };
+        let text = serde_json::to_string(&manifest).unwrap();
+        let err = read_archive(text.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("holds 0 records"));
+
+        let manifest = ArchiveManifest {
+            entries: 1,
+            ..manifest
+        };
+        let text = format!(
+            "{}\n{{}}\n{{}}\n",
+            serde_json::to_string(&manifest).unwrap()
+        );
+        let err = read_archive(text.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("more records than the 1"));
+    }
+
+    #[test]
+    fn non_archive_input_is_rejected() {
+        assert!(read_archive("".as_bytes()).is_err());
+        assert!(read_archive("# MEMORY.md\n".as_bytes()).is_err());
+    }
+}
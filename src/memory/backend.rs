@@ -2,6 +2,7 @@
 pub enum MemoryBackendKind {
     Sqlite,
     Lucid,
+    Graph,
     Postgres,
     Qdrant,
     Markdown,
@@ -38,6 +39,15 @@ const LUCID_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
     optional_dependency: true,
 };
 
+const GRAPH_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
+    key: "graph",
+    label: "Knowledge Graph — SQLite hybrid search plus entity/relation recall",
+    auto_save_default: true,
+    uses_sqlite_hygiene: true,
+    sqlite_based: true,
+    optional_dependency: false,
+};
+
 const MARKDOWN_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
     key: "markdown",
     label: "Markdown Files — simple, human-readable, no dependencies",
@@ -102,6 +112,7 @@ pub fn classify_memory_backend(backend: &str) -> MemoryBackendKind {
     match backend {
         "sqlite" => MemoryBackendKind::Sqlite,
         "lucid" => MemoryBackendKind::Lucid,
+        "graph" => MemoryBackendKind::Graph,
         "postgres" => MemoryBackendKind::Postgres,
         "qdrant" => MemoryBackendKind::Qdrant,
         "markdown" => MemoryBackendKind::Markdown,
@@ -114,6 +125,7 @@ pub fn memory_backend_profile(backend: &str) -> MemoryBackendProfile {
     match classify_memory_backend(backend) {
         MemoryBackendKind::Sqlite => SQLITE_PROFILE,
         MemoryBackendKind::Lucid => LUCID_PROFILE,
+        MemoryBackendKind::Graph => GRAPH_PROFILE,
         MemoryBackendKind::Postgres => POSTGRES_PROFILE,
         MemoryBackendKind::Qdrant => QDRANT_PROFILE,
         MemoryBackendKind::Markdown => MARKDOWN_PROFILE,
@@ -130,6 +142,7 @@ mod tests {
     fn classify_known_backends() {
         assert_eq!(classify_memory_backend("sqlite"), MemoryBackendKind::Sqlite);
         assert_eq!(classify_memory_backend("lucid"), MemoryBackendKind::Lucid);
+        assert_eq!(classify_memory_backend("graph"), MemoryBackendKind::Graph);
         assert_eq!(
             classify_memory_backend("postgres"),
             MemoryBackendKind::Postgres
@@ -1,13 +1,14 @@
+use super::archive::{self, ArchiveFormat};
+use super::consolidation;
 use super::traits::{Memory, MemoryCategory};
 use super::{
     classify_memory_backend, create_memory_for_migration, effective_memory_backend_name,
     MemoryBackendKind,
 };
 use crate::config::Config;
-#[cfg(feature = "memory-postgres")]
-use anyhow::Context;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use console::style;
+use std::path::{Path, PathBuf};
 
 /// Handle `zeroclaw memory <subcommand>` CLI commands.
 pub async fn handle_command(command: crate::MemoryCommands, config: &Config) -> Result<()> {
@@ -23,6 +24,20 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
         crate::MemoryCommands::Clear { key, category, yes } => {
             handle_clear(config, key, category, yes).await
         }
+        crate::MemoryCommands::Reindex => handle_reindex(config).await,
+        crate::MemoryCommands::Export { format, output } => {
+            handle_export(config, format, output).await
+        }
+        crate::MemoryCommands::Import { path, dry_run } => {
+            handle_import(config, &path, dry_run).await
+        }
+        crate::MemoryCommands::Consolidate {
+            dry_run,
+            schedule,
+            tz,
+        } => handle_consolidate(config, dry_run, schedule, tz).await,
+        crate::MemoryCommands::Merges { limit } => handle_merges(config, limit),
+        crate::MemoryCommands::Unmerge { id } => handle_unmerge(config, &id).await,
     }
 }
 
@@ -30,7 +45,8 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
 ///
 /// CLI commands (list/get/stats/clear) never use vector search, so we skip
 /// embedding provider initialisation for local backends by using the
-/// migration factory.  Postgres still needs its full connection config.
+/// migration factory.  Qdrant is built from `[memory.qdrant]` there as well;
+/// Postgres still needs its full connection config.
 fn create_cli_memory(config: &Config) -> Result<Box<dyn Memory>> {
     let backend = effective_memory_backend_name(
         &config.memory.backend,
@@ -72,7 +88,7 @@ fn create_cli_memory(config: &Config) -> Result<Box<dyn Memory>> {
         MemoryBackendKind::Postgres => {
             bail!("memory backend 'postgres' requires the 'memory-postgres' feature to be enabled");
         }
-        _ => create_memory_for_migration(&backend, &config.workspace_dir),
+        _ => create_memory_for_migration(&backend, config),
     }
 }
 
@@ -190,7 +206,7 @@ async fn handle_stats(config: &Config) -> Result<()> {
 
         println!("\n  By category:");
         let mut sorted: Vec<_> = counts.into_iter().collect();
-        sorted.sort_by(|a, b| b.1.cmp(&a.1));
+        sorted.sort_by_key(|entry| std::cmp::Reverse(entry.1));
         for (cat, count) in sorted {
             println!("    {cat:<20} {count}");
         }
@@ -251,6 +267,263 @@ async fn handle_clear(
     Ok(())
 }
 
+async fn handle_reindex(config: &Config) -> Result<()> {
+    let backend = effective_memory_backend_name(
+        &config.memory.backend,
+        Some(&config.storage.provider.config),
+    );
+    let kind = classify_memory_backend(&backend);
+    if !matches!(
+        kind,
+        MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid | MemoryBackendKind::Graph
+    ) {
+        bail!("`memory reindex` requires a sqlite-based backend (current: '{backend}')");
+    }
+
+    let mem = super::create_sqlite_memory_with_embedder(config)?;
+    let previous = mem.embedding_fingerprint()?;
+    let reembedded = mem.reindex().await?;
+    let current = mem.embedding_fingerprint()?;
+
+    println!(
+        "{} Rebuilt keyword index; re-embedded {reembedded} entries.",
+        style("✓").green().bold()
+    );
+    match (previous, current) {
+        (Some(prev), Some(cur)) if prev != cur => println!("  Embeddings: {prev} → {cur}"),
+        (_, Some(cur)) => println!("  Embeddings: {cur}"),
+        (_, None) => println!("  Embeddings: disabled (embedding_provider = \"none\")"),
+    }
+
+    if kind == MemoryBackendKind::Graph {
+        let graph =
+            super::GraphMemory::new(&config.workspace_dir, mem, config.memory.graph.clone())?;
+        graph.rebuild().await?;
+        let (entities, relations) = graph.graph().counts()?;
+        println!("  Knowledge graph: {entities} entities, {relations} relations");
+    }
+
+    Ok(())
+}
+
+async fn handle_export(
+    config: &Config,
+    format: ArchiveFormat,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let mem = create_cli_memory(config)?;
+
+    let Some(path) = output else {
+        // Archive goes to stdout, so keep the summary on stderr
+        let records = mem.export_records().await?;
+        let manifest =
+            archive::write_archive(&mut std::io::stdout().lock(), records, format, mem.name())?;
+        eprintln!(
+            "Exported {} entries ({} with embeddings) from {}.",
+            manifest.entries, manifest.embeddings, manifest.source_backend
+        );
+        return Ok(());
+    };
+
+    let file = std::fs::File::create(&path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+    let manifest = archive::export_memory(&*mem, &mut writer, format).await?;
+
+    println!(
+        "{} Exported {} entries ({} with embeddings) from {} to {}",
+        style("✓").green().bold(),
+        manifest.entries,
+        manifest.embeddings,
+        manifest.source_backend,
+        path.display(),
+    );
+    println!("  Format:   {format} v{}", manifest.version);
+    println!("  Checksum: {}", manifest.checksum);
+
+    Ok(())
+}
+
+async fn handle_import(config: &Config, path: &Path, dry_run: bool) -> Result<()> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let (manifest, records) = archive::read_archive(std::io::BufReader::new(file))?;
+
+    println!(
+        "Archive: {} entries ({} with embeddings) exported from {} at {}",
+        manifest.entries, manifest.embeddings, manifest.source_backend, manifest.exported_at,
+    );
+    println!("  Format:   {} v{}", manifest.layout, manifest.version);
+    println!("  Checksum: {} (verified)", manifest.checksum);
+
+    if dry_run {
+        println!("\nDry run: nothing was written.");
+        return Ok(());
+    }
+
+    let mem = create_import_memory(config)?;
+    let imported = archive::import_memory(&*mem, records).await?;
+    println!(
+        "{} Imported {imported} entries into {}.",
+        style("✓").green().bold(),
+        mem.name()
+    );
+    if manifest.embeddings < manifest.entries {
+        println!(
+            "  Entries without a reusable embedding were re-embedded with the configured provider."
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_consolidate(
+    config: &Config,
+    dry_run: bool,
+    schedule: Option<String>,
+    tz: Option<String>,
+) -> Result<()> {
+    if let Some(expr) = schedule {
+        let job = crate::cron::consolidation::create_memory_consolidation_job_with_schedule(
+            config, &expr, tz,
+        )?;
+        println!(
+            "{} Scheduled memory consolidation (job {}, next run {}).",
+            style("✓").green().bold(),
+            job.id,
+            job.next_run.to_rfc3339(),
+        );
+        return Ok(());
+    }
+
+    let report = consolidation::run(config, dry_run).await?;
+
+    if report.clusters.is_empty() {
+        println!(
+            "No near-duplicate memories found ({} scanned).",
+            report.scanned
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "Found {} near-duplicate groups in {} entries:\n",
+            report.clusters.len(),
+            report.scanned,
+        );
+        for (index, cluster) in report.clusters.iter().enumerate() {
+            println!("Group {} ({} entries):", index + 1, cluster.len());
+            for entry in cluster {
+                println!(
+                    "  - {} [{}] {}",
+                    style(&entry.key).white().bold(),
+                    entry.category,
+                    truncate_content(&entry.content, 60),
+                );
+            }
+        }
+        println!("\nDry run: nothing was merged.");
+        return Ok(());
+    }
+
+    for merge in &report.merges {
+        println!(
+            "- {} [{}] ← {} entries (merge {})",
+            style(&merge.merged_key).white().bold(),
+            merge.category,
+            merge.sources.len(),
+            &merge.id[..8],
+        );
+        println!("    {}", truncate_content(&merge.content, 80));
+    }
+    println!(
+        "\n{} Consolidation: {}.",
+        style("✓").green().bold(),
+        report.summary()
+    );
+    if !report.merges.is_empty() {
+        println!(
+            "  Review with `zeroclaw memory merges`; revert with `zeroclaw memory unmerge <id>`."
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_merges(config: &Config, limit: usize) -> Result<()> {
+    let journal = consolidation::ConsolidationJournal::open(&config.workspace_dir)?;
+    let merges = journal.list(limit)?;
+
+    if merges.is_empty() {
+        println!("No consolidation merges recorded.");
+        return Ok(());
+    }
+
+    println!("Consolidation merges ({} shown):\n", merges.len());
+    for merge in &merges {
+        let status = match &merge.undone_at {
+            Some(at) => format!("undone {at}"),
+            None => "active".into(),
+        };
+        println!(
+            "- {} {} [{}] {} ({status})",
+            style(&merge.id).white().bold(),
+            merge.merged_key,
+            merge.category,
+            merge.created_at,
+        );
+        println!("    {}", truncate_content(&merge.content, 80));
+        for source in &merge.sources {
+            println!(
+                "      ← {} [{}] {}",
+                source.key,
+                source.category,
+                truncate_content(&source.content, 50),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_unmerge(config: &Config, id: &str) -> Result<()> {
+    let journal = consolidation::ConsolidationJournal::open(&config.workspace_dir)?;
+    let mem = create_import_memory(config)?;
+    let merge = consolidation::undo_merge(&*mem, &journal, id).await?;
+
+    println!(
+        "{} Restored {} entries and removed {}.",
+        style("✓").green().bold(),
+        merge.sources.len(),
+        merge.merged_key,
+    );
+
+    Ok(())
+}
+
+/// Memory used as an import target.
+///
+/// SQLite-based backends get the configured embedder so entries whose archived
+/// vector belongs to a different embedding space are re-embedded on the way in.
+fn create_import_memory(config: &Config) -> Result<Box<dyn Memory>> {
+    let backend = effective_memory_backend_name(
+        &config.memory.backend,
+        Some(&config.storage.provider.config),
+    );
+    match classify_memory_backend(&backend) {
+        MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid => {
+            Ok(Box::new(super::create_sqlite_memory_with_embedder(config)?))
+        }
+        MemoryBackendKind::Graph => Ok(Box::new(super::GraphMemory::new(
+            &config.workspace_dir,
+            super::create_sqlite_memory_with_embedder(config)?,
+            config.memory.graph.clone(),
+        )?)),
+        _ => create_cli_memory(config),
+    }
+}
+
 /// Delete a single entry by exact key or prefix match.
 async fn handle_clear_key(mem: &dyn Memory, key: &str, yes: bool) -> Result<()> {
     // Resolve the target key (exact match or unique prefix).
@@ -0,0 +1,930 @@
+//! Near-duplicate memory consolidation.
+//!
+//! Long-running agents accumulate many `daily` and `conversation` entries that
+//! restate the same thing. A consolidation pass embeds those entries, groups
+//! near-duplicates by cosine similarity, asks the configured provider to merge
+//! each group into a single entry, and replaces the originals with it. Groups
+//! that keep recurring across days (or that the model marks as a stable fact)
+//! are promoted to `core`.
+//!
+//! Every merge is journaled with full copies of its source entries in
+//! `<workspace>/memory/consolidation.db`, so it can be reviewed with
+//! `zeroclaw memory merges` and reverted with `zeroclaw memory unmerge <id>`.
+
+use super::embeddings::{EmbeddingProvider, HashedNgramEmbedding};
+use super::namespace::MemoryNamespace;
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord};
+use super::vector::cosine_similarity;
+use crate::config::{Config, MemoryConsolidationConfig};
+use crate::providers::{self, structured, ChatMessage, ChatRequest, Provider, ResponseFormat};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Journal database file name inside `<workspace>/memory/`.
+const JOURNAL_FILE_NAME: &str = "consolidation.db";
+
+/// Tag added to every consolidated entry.
+pub const CONSOLIDATED_TAG: &str = "consolidated";
+
+/// Key prefix of consolidated entries.
+const CONSOLIDATED_KEY_PREFIX: &str = "consolidated_";
+
+/// Dimensions of the on-device fallback used when no embedder is configured.
+const FALLBACK_EMBEDDING_DIMS: usize = 256;
+
+/// Texts per embedding request.
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// Model used when neither `[memory.consolidation].model` nor `default_model` is set.
+const DEFAULT_MODEL: &str = "anthropic/claude-sonnet-4";
+
+const MERGE_TEMPERATURE: f64 = 0.2;
+
+const MERGE_SYSTEM_PROMPT: &str = "\
+You merge near-duplicate memory entries kept by an AI assistant. Combine the \
+numbered entries into one concise memory that keeps every distinct fact, \
+prefers the most recent value when entries conflict, and drops repetition. \
+Do not add information that is not in the entries.
+
+Reply with JSON only, no prose and no code fences:
+{\"content\": \"<merged memory>\", \"stable_fact\": true|false}
+
+Set stable_fact to true only when the merged memory is a durable fact about \
+the user, their preferences, or their environment rather than a one-off event.";
+
+/// Tuning for a consolidation pass.
+#[derive(Debug, Clone)]
+pub struct ConsolidationOptions {
+    pub similarity_threshold: f32,
+    pub max_entries: usize,
+    pub max_cluster_size: usize,
+    pub promote_after_days: usize,
+    pub dry_run: bool,
+}
+
+impl ConsolidationOptions {
+    pub fn from_config(config: &MemoryConsolidationConfig) -> Self {
+        #[allow(clippy::cast_possible_truncation)]
+        Self {
+            similarity_threshold: config.similarity_threshold.clamp(0.0, 1.0) as f32,
+            max_entries: config.max_entries,
+            max_cluster_size: config.max_cluster_size.max(2),
+            promote_after_days: config.promote_after_days as usize,
+            dry_run: false,
+        }
+    }
+}
+
+impl Default for ConsolidationOptions {
+    fn default() -> Self {
+        Self::from_config(&MemoryConsolidationConfig::default())
+    }
+}
+
+/// One applied merge: the consolidated entry and the entries it replaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRecord {
+    pub id: String,
+    pub merged_key: String,
+    pub category: MemoryCategory,
+    pub content: String,
+    pub sources: Vec<MemoryEntry>,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+/// Outcome of a consolidation pass.
+#[derive(Debug, Default)]
+pub struct ConsolidationReport {
+    /// Candidate entries examined.
+    pub scanned: usize,
+    /// Near-duplicate groups found (in dry-run mode nothing else happens).
+    pub clusters: Vec<Vec<MemoryEntry>>,
+    /// Merges applied and journaled.
+    pub merges: Vec<MergeRecord>,
+    /// Groups skipped because the merge call failed or returned nothing usable.
+    pub failed: usize,
+}
+
+impl ConsolidationReport {
+    /// Merges whose result was promoted to `core`.
+    pub fn promoted(&self) -> usize {
+        self.merges
+            .iter()
+            .filter(|merge| merge.category == MemoryCategory::Core)
+            .count()
+    }
+
+    /// One-line summary suitable for logs and cron run output.
+    pub fn summary(&self) -> String {
+        let replaced: usize = self.merges.iter().map(|merge| merge.sources.len()).sum();
+        format!(
+            "scanned {} entries, found {} near-duplicate groups, merged {} ({} entries replaced, {} promoted to core), {} failed",
+            self.scanned,
+            self.clusters.len(),
+            self.merges.len(),
+            replaced,
+            self.promoted(),
+            self.failed,
+        )
+    }
+}
+
+/// SQLite journal of applied merges.
+pub struct ConsolidationJournal {
+    conn: Mutex<Connection>,
+}
+
+impl ConsolidationJournal {
+    /// Open (or create) the journal under `workspace_dir/memory/`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let dir = workspace_dir.join("memory");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create memory directory: {}", dir.display()))?;
+        let db_path = dir.join(JOURNAL_FILE_NAME);
+        let conn = Connection::open(&db_path).with_context(|| {
+            format!(
+                "Failed to open consolidation journal: {}",
+                db_path.display()
+            )
+        })?;
+        Self::init(conn)
+    }
+
+    /// In-memory journal (tests and dry runs).
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS memory_merges (
+                id          TEXT PRIMARY KEY,
+                merged_key  TEXT NOT NULL,
+                category    TEXT NOT NULL,
+                content     TEXT NOT NULL,
+                sources     TEXT NOT NULL,
+                created_at  TEXT NOT NULL,
+                undone_at   TEXT
+             );
+             CREATE INDEX IF NOT EXISTS idx_memory_merges_created ON memory_merges(created_at);",
+        )
+        .context("Failed to initialize consolidation journal schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn record(&self, merge: &MergeRecord) -> Result<()> {
+        self.conn.lock().execute(
+            "INSERT INTO memory_merges (id, merged_key, category, content, sources, created_at, undone_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL)",
+            params![
+                merge.id,
+                merge.merged_key,
+                serde_json::to_string(&merge.category)?,
+                merge.content,
+                serde_json::to_string(&merge.sources)?,
+                merge.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent merges first.
+    pub fn list(&self, limit: usize) -> Result<Vec<MergeRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, merged_key, category, content, sources, created_at, undone_at
+             FROM memory_merges ORDER BY created_at DESC, id LIMIT ?1",
+        )?;
+        #[allow(clippy::cast_possible_wrap)]
+        let rows = stmt.query_map(params![limit as i64], Self::row_to_record)?;
+        rows.map(|row| row?).collect()
+    }
+
+    /// Look up a merge by id (or unique id prefix).
+    pub fn get(&self, id: &str) -> Result<Option<MergeRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, merged_key, category, content, sources, created_at, undone_at
+             FROM memory_merges WHERE id = ?1 OR id LIKE ?2 || '%' LIMIT 2",
+        )?;
+        let matches = stmt
+            .query_map(params![id, id], Self::row_to_record)?
+            .map(|row| row?)
+            .collect::<Result<Vec<_>>>()?;
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(matches.into_iter().next()),
+            _ => matches
+                .into_iter()
+                .find(|merge| merge.id == id)
+                .map_or_else(
+                    || bail!("Merge id prefix '{id}' is ambiguous"),
+                    |m| Ok(Some(m)),
+                ),
+        }
+    }
+
+    fn mark_undone(&self, id: &str) -> Result<()> {
+        self.conn.lock().execute(
+            "UPDATE memory_merges SET undone_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<Result<MergeRecord>> {
+        let category: String = row.get(2)?;
+        let sources: String = row.get(4)?;
+        let decoded = serde_json::from_str::<MemoryCategory>(&category)
+            .and_then(|category| Ok((category, serde_json::from_str(&sources)?)))
+            .context("Corrupt entry in consolidation journal");
+        let (category, sources) = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        Ok(Ok(MergeRecord {
+            id: row.get(0)?,
+            merged_key: row.get(1)?,
+            category,
+            content: row.get(3)?,
+            sources,
+            created_at: row.get(5)?,
+            undone_at: row.get(6)?,
+        }))
+    }
+}
+
+/// Group entries whose embeddings are within `threshold` cosine similarity.
+///
+/// Greedy leader clustering in the given order: each unassigned entry seeds a
+/// group and pulls in every later unassigned entry similar to it, up to
+/// `max_cluster_size`. Only groups with at least two members are returned.
+pub fn cluster_by_similarity(
+    vectors: &[Vec<f32>],
+    threshold: f32,
+    max_cluster_size: usize,
+) -> Vec<Vec<usize>> {
+    let mut assigned = vec![false; vectors.len()];
+    let mut clusters = Vec::new();
+
+    for seed in 0..vectors.len() {
+        if assigned[seed] || vectors[seed].is_empty() {
+            continue;
+        }
+        let mut members = vec![seed];
+        for candidate in seed + 1..vectors.len() {
+            if members.len() >= max_cluster_size {
+                break;
+            }
+            if !assigned[candidate]
+                && cosine_similarity(&vectors[seed], &vectors[candidate]) >= threshold
+            {
+                members.push(candidate);
+            }
+        }
+        if members.len() > 1 {
+            for &index in &members {
+                assigned[index] = true;
+            }
+            clusters.push(members);
+        }
+    }
+
+    clusters
+}
+
+/// Cluster each namespace separately so merges never mix tenants' entries.
+fn cluster_within_namespaces(
+    candidates: &[MemoryEntry],
+    vectors: &[Vec<f32>],
+    options: &ConsolidationOptions,
+) -> Vec<Vec<usize>> {
+    let mut by_namespace: BTreeMap<MemoryNamespace, Vec<usize>> = BTreeMap::new();
+    for (index, entry) in candidates.iter().enumerate() {
+        by_namespace
+            .entry(MemoryNamespace::of(entry))
+            .or_default()
+            .push(index);
+    }
+
+    let mut groups = Vec::new();
+    for indices in by_namespace.values() {
+        let subset: Vec<Vec<f32>> = indices.iter().map(|&i| vectors[i].clone()).collect();
+        for group in cluster_by_similarity(
+            &subset,
+            options.similarity_threshold,
+            options.max_cluster_size,
+        ) {
+            groups.push(group.into_iter().map(|j| indices[j]).collect());
+        }
+    }
+    groups
+}
+
+/// Run a consolidation pass over `memory`.
+///
+/// `provider` is only called when merges are applied, so dry runs need no
+/// working provider.
+pub async fn consolidate(
+    memory: &dyn Memory,
+    embedder: &dyn EmbeddingProvider,
+    provider: Option<(&dyn Provider, &str)>,
+    journal: &ConsolidationJournal,
+    options: &ConsolidationOptions,
+) -> Result<ConsolidationReport> {
+    let candidates = load_candidates(memory, options.max_entries).await?;
+    let vectors = embed_entries(embedder, &candidates).await?;
+    let groups = cluster_within_namespaces(&candidates, &vectors, options);
+
+    let mut report = ConsolidationReport {
+        scanned: candidates.len(),
+        clusters: groups
+            .iter()
+            .map(|group| group.iter().map(|&i| candidates[i].clone()).collect())
+            .collect(),
+        ..ConsolidationReport::default()
+    };
+
+    if options.dry_run || report.clusters.is_empty() {
+        return Ok(report);
+    }
+    let Some((provider, model)) = provider else {
+        bail!("Memory consolidation needs a provider to merge entries");
+    };
+
+    for cluster in report.clusters.clone() {
+        match merge_cluster(memory, provider, model, journal, cluster, options).await {
+            Ok(Some(merge)) => report.merges.push(merge),
+            Ok(None) => report.failed += 1,
+            Err(e) => {
+                tracing::warn!("memory consolidation: merge failed: {e}");
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Revert a journaled merge: restore its source entries and remove the
+/// consolidated entry.
+pub async fn undo_merge(
+    memory: &dyn Memory,
+    journal: &ConsolidationJournal,
+    id: &str,
+) -> Result<MergeRecord> {
+    let Some(merge) = journal.get(id)? else {
+        bail!("No consolidation merge with id '{id}'");
+    };
+    if let Some(undone_at) = &merge.undone_at {
+        bail!("Merge {} was already undone at {undone_at}", merge.id);
+    }
+
+    for source in &merge.sources {
+        memory
+            .import_record(MemoryRecord {
+                entry: source.clone(),
+                embedding: None,
+            })
+            .await
+            .with_context(|| format!("Failed to restore memory '{}'", source.key))?;
+    }
+    if !merge
+        .sources
+        .iter()
+        .any(|source| source.key == merge.merged_key)
+    {
+        memory.forget(&merge.merged_key).await?;
+    }
+    journal.mark_undone(&merge.id)?;
+
+    Ok(merge)
+}
+
+/// Run a consolidation pass against the configured memory backend, embedder
+/// and provider. Used by `zeroclaw memory consolidate` and the cron job.
+pub async fn run(config: &Config, dry_run: bool) -> Result<ConsolidationReport> {
+    let memory = super::create_memory_with_storage(
+        &config.memory,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?;
+    let embedder = super::create_embedder(config)?;
+    let journal = ConsolidationJournal::open(&config.workspace_dir)?;
+    let mut options = ConsolidationOptions::from_config(&config.memory.consolidation);
+    options.dry_run = dry_run;
+
+    let model = config
+        .memory
+        .consolidation
+        .model
+        .as_deref()
+        .or(config.default_model.as_deref())
+        .unwrap_or(DEFAULT_MODEL);
+    // Only needed once near-duplicates are found; consolidate() reports that case.
+    let provider = if dry_run {
+        None
+    } else {
+        create_provider(config, model)
+            .map_err(|e| tracing::warn!("memory consolidation: provider unavailable: {e}"))
+            .ok()
+    };
+
+    consolidate(
+        memory.as_ref(),
+        embedder.as_ref(),
+        provider.as_deref().map(|provider| (provider, model)),
+        &journal,
+        &options,
+    )
+    .await
+}
+
+fn create_provider(config: &Config, model: &str) -> Result<Box<dyn Provider>> {
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning_level: config.effective_provider_reasoning_level(),
+        custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+        max_tokens_override: None,
+        model_support_vision: config.model_support_vision,
+        model_context_window: config.model_context_window,
+    };
+    providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model,
+        &options,
+    )
+}
+
+/// Most recent `daily`/`conversation` entries, oldest first.
+async fn load_candidates(memory: &dyn Memory, max_entries: usize) -> Result<Vec<MemoryEntry>> {
+    let mut entries = memory.list(Some(&MemoryCategory::Daily), None).await?;
+    entries.extend(
+        memory
+            .list(Some(&MemoryCategory::Conversation), None)
+            .await?,
+    );
+    entries.retain(|entry| !entry.content.trim().is_empty());
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    entries.truncate(max_entries);
+    entries.reverse();
+    Ok(entries)
+}
+
+async fn embed_entries(
+    embedder: &dyn EmbeddingProvider,
+    entries: &[MemoryEntry],
+) -> Result<Vec<Vec<f32>>> {
+    // Keyword-only setups still get near-duplicate detection on-device.
+    let fallback;
+    let embedder = if embedder.dimensions() == 0 {
+        fallback = HashedNgramEmbedding::new(FALLBACK_EMBEDDING_DIMS);
+        &fallback as &dyn EmbeddingProvider
+    } else {
+        embedder
+    };
+
+    let mut vectors = Vec::with_capacity(entries.len());
+    for batch in entries.chunks(EMBED_BATCH_SIZE) {
+        let texts: Vec<&str> = batch.iter().map(|entry| entry.content.as_str()).collect();
+        let embedded = embedder.embed(&texts).await?;
+        if embedded.len() != texts.len() {
+            bail!(
+                "Embedding provider returned {} vectors for {} entries",
+                embedded.len(),
+                texts.len()
+            );
+        }
+        vectors.extend(embedded);
+    }
+    Ok(vectors)
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeReply {
+    content: String,
+    #[serde(default)]
+    stable_fact: bool,
+}
+
+/// Reply schema for a merge: `{"content": string, "stable_fact": bool}`.
+fn merge_reply_format() -> ResponseFormat {
+    ResponseFormat::json_schema(
+        "memory_merge",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "content": {"type": "string", "minLength": 1},
+                "stable_fact": {"type": "boolean"}
+            },
+            "required": ["content"]
+        }),
+    )
+}
+
+/// Parse the model's merge reply against [`merge_reply_format`], tolerating
+/// code fences or surrounding prose. Anything else is a failed merge.
+fn parse_merge_reply(raw: &str) -> Option<MergeReply> {
+    let value = structured::parse_reply(raw, &merge_reply_format()).ok()?;
+    let reply = serde_json::from_value::<MergeReply>(value).ok()?;
+    let content = reply.content.trim();
+    (!content.is_empty()).then(|| MergeReply {
+        content: content.to_string(),
+        stable_fact: reply.stable_fact,
+    })
+}
+
+fn merge_prompt(cluster: &[MemoryEntry]) -> String {
+    let mut prompt = String::from("Merge these memory entries:\n\n");
+    for (index, entry) in cluster.iter().enumerate() {
+        let _ = writeln!(
+            prompt,
+            "{}. [{}] ({}) {}",
+            index + 1,
+            entry.timestamp,
+            entry.category,
+            entry.content.trim()
+        );
+    }
+    prompt
+}
+
+/// Stable key for a group: hash of its sorted source keys.
+fn merged_key(cluster: &[MemoryEntry]) -> String {
+    let keys: BTreeSet<&str> = cluster.iter().map(|entry| entry.key.as_str()).collect();
+    let mut hasher = Sha256::new();
+    for key in keys {
+        hasher.update(key.as_bytes());
+        hasher.update([0]);
+    }
+    let digest = hex::encode(hasher.finalize());
+    format!("{CONSOLIDATED_KEY_PREFIX}{}", &digest[..16])
+}
+
+fn distinct_days(cluster: &[MemoryEntry]) -> usize {
+    cluster
+        .iter()
+        .map(|entry| entry.timestamp.get(..10).unwrap_or(&entry.timestamp))
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// Tags of every source plus [`CONSOLIDATED_TAG`]; expires only if every source did.
+fn merged_metadata(cluster: &[MemoryEntry]) -> MemoryMetadata {
+    let tags = cluster
+        .iter()
+        .flat_map(|entry| entry.metadata.tags.iter().cloned())
+        .chain(std::iter::once(CONSOLIDATED_TAG.to_string()))
+        .collect::<Vec<_>>();
+    let expires_at = cluster
+        .iter()
+        .map(|entry| entry.metadata.expires_at.clone())
+        .collect::<Option<Vec<_>>>()
+        .and_then(|expiries| expiries.into_iter().max());
+
+    MemoryMetadata {
+        expires_at,
+        ..MemoryMetadata::default()
+            .with_tags(tags)
+            .with_source(super::traits::MemorySource {
+                tool: Some("memory_consolidation".into()),
+                ..super::traits::MemorySource::default()
+            })
+    }
+}
+
+async fn merge_cluster(
+    memory: &dyn Memory,
+    provider: &dyn Provider,
+    model: &str,
+    journal: &ConsolidationJournal,
+    cluster: Vec<MemoryEntry>,
+    options: &ConsolidationOptions,
+) -> Result<Option<MergeRecord>> {
+    let format = merge_reply_format();
+    let messages = [
+        ChatMessage::system(MERGE_SYSTEM_PROMPT),
+        ChatMessage::user(merge_prompt(&cluster)),
+    ];
+    let response = provider
+        .chat(
+            ChatRequest {
+                messages: &messages,
+                tools: None,
+                response_format: Some(&format),
+            },
+            model,
+            MERGE_TEMPERATURE,
+        )
+        .await?;
+    let Some(reply) = parse_merge_reply(response.text_or_empty()) else {
+        tracing::warn!("memory consolidation: provider returned no valid merge");
+        return Ok(None);
+    };
+
+    let promote = reply.stable_fact
+        || (options.promote_after_days > 0
+            && distinct_days(&cluster) >= options.promote_after_days);
+    let category = if promote {
+        MemoryCategory::Core
+    } else {
+        // Keep the category of the newest source.
+        cluster
+            .last()
+            .map_or(MemoryCategory::Daily, |entry| entry.category.clone())
+    };
+    let session_id = cluster
+        .first()
+        .and_then(|first| first.session_id.clone())
+        .filter(|session| {
+            cluster
+                .iter()
+                .all(|entry| entry.session_id.as_deref() == Some(session))
+        });
+
+    // Clusters never span namespaces, so the first source decides.
+    let namespace = cluster
+        .first()
+        .map_or_else(MemoryNamespace::shared, MemoryNamespace::of);
+    let key = merged_key(&cluster);
+    memory
+        .store_in_namespace(
+            &namespace,
+            &key,
+            &reply.content,
+            category.clone(),
+            session_id.as_deref(),
+            merged_metadata(&cluster),
+        )
+        .await?;
+
+    let merge = MergeRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        merged_key: namespace.qualify_key(&key),
+        category,
+        content: reply.content,
+        sources: cluster,
+        created_at: Utc::now().to_rfc3339(),
+        undone_at: None,
+    };
+    // Journal before deleting so a crash never loses the sources.
+    journal.record(&merge)?;
+    for source in &merge.sources {
+        if source.key != merge.merged_key {
+            memory.forget(&source.key).await?;
+        }
+    }
+
+    Ok(Some(merge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    struct ScriptedProvider {
+        reply: String,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(self.reply.clone())
+        }
+    }
+
+    async fn seeded_memory(tmp: &TempDir) -> SqliteMemory {
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store(
+            "daily_1",
+            "User prefers dark mode in the editor",
+            MemoryCategory::Daily,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "daily_2",
+            "User prefers dark mode in the editor!",
+            MemoryCategory::Daily,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "conv_1",
+            "Deployment pipeline failed on the staging cluster",
+            MemoryCategory::Conversation,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "core_1",
+            "User prefers dark mode in the editor",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem
+    }
+
+    #[test]
+    fn cluster_by_similarity_groups_close_vectors_only() {
+        let vectors = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.99, 0.05],
+            vec![0.98, 0.1],
+        ];
+        assert_eq!(
+            cluster_by_similarity(&vectors, 0.95, 8),
+            vec![vec![0, 2, 3]]
+        );
+        assert_eq!(cluster_by_similarity(&vectors, 0.95, 2), vec![vec![0, 2]]);
+        assert!(cluster_by_similarity(&vectors, 0.9999, 8).is_empty());
+    }
+
+    #[test]
+    fn parse_merge_reply_accepts_fenced_json_and_rejects_everything_else() {
+        let reply =
+            parse_merge_reply("```json\n{\"content\": \"Likes tea\", \"stable_fact\": true}\n```")
+                .unwrap();
+        assert_eq!(reply.content, "Likes tea");
+        assert!(reply.stable_fact);
+
+        let reply = parse_merge_reply("{\"content\": \"Likes tea\"}").unwrap();
+        assert!(!reply.stable_fact);
+
+        assert!(parse_merge_reply("Likes tea").is_none());
+        assert!(parse_merge_reply("{\"summary\": \"Likes tea\"}").is_none());
+        assert!(parse_merge_reply("{\"content\": \"  \"}").is_none());
+        assert!(parse_merge_reply("  ").is_none());
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_clusters_without_touching_memory() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let journal = ConsolidationJournal::open_in_memory().unwrap();
+        let options = ConsolidationOptions {
+            dry_run: true,
+            ..ConsolidationOptions::default()
+        };
+
+        let report = consolidate(
+            &mem,
+            &HashedNgramEmbedding::new(256),
+            None,
+            &journal,
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.scanned, 3, "core entries are not candidates");
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].len(), 2);
+        assert!(report.merges.is_empty());
+        assert_eq!(mem.count().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn merge_replaces_sources_and_undo_restores_them() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let journal = ConsolidationJournal::open_in_memory().unwrap();
+        let provider = ScriptedProvider {
+            reply: r#"{"content": "User prefers dark mode in the editor", "stable_fact": true}"#
+                .into(),
+        };
+
+        let report = consolidate(
+            &mem,
+            &HashedNgramEmbedding::new(256),
+            Some((&provider, "test-model")),
+            &journal,
+            &ConsolidationOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.merges.len(), 1);
+        assert_eq!(report.promoted(), 1);
+        let merge = &report.merges[0];
+        assert!(mem.get("daily_1").await.unwrap().is_none());
+        assert!(mem.get("daily_2").await.unwrap().is_none());
+        let merged = mem.get(&merge.merged_key).await.unwrap().unwrap();
+        assert_eq!(merged.category, MemoryCategory::Core);
+        assert!(merged.metadata.tags.contains(&CONSOLIDATED_TAG.to_string()));
+        assert_eq!(journal.list(10).unwrap().len(), 1);
+
+        let undone = undo_merge(&mem, &journal, &merge.id[..8]).await.unwrap();
+        assert_eq!(undone.sources.len(), 2);
+        assert!(mem.get(&merge.merged_key).await.unwrap().is_none());
+        let restored = mem.get("daily_1").await.unwrap().unwrap();
+        assert_eq!(restored.category, MemoryCategory::Daily);
+        assert_eq!(restored.timestamp, merge.sources[0].timestamp);
+        assert!(journal.get(&merge.id).unwrap().unwrap().undone_at.is_some());
+        assert!(undo_merge(&mem, &journal, &merge.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn prose_merge_reply_counts_as_failed_and_keeps_sources() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let journal = ConsolidationJournal::open_in_memory().unwrap();
+        let provider = ScriptedProvider {
+            reply: "Sure! The user prefers dark mode.".into(),
+        };
+
+        let report = consolidate(
+            &mem,
+            &HashedNgramEmbedding::new(256),
+            Some((&provider, "test-model")),
+            &journal,
+            &ConsolidationOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(report.merges.is_empty());
+        assert_eq!(report.failed, 1);
+        assert!(mem.get("daily_1").await.unwrap().is_some());
+        assert!(mem.get("daily_2").await.unwrap().is_some());
+        assert!(journal.list(10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recurring_entries_are_promoted_by_day_count() {
+        let cluster: Vec<MemoryEntry> = ["2026-01-01", "2026-01-02", "2026-01-04"]
+            .iter()
+            .enumerate()
+            .map(|(i, day)| MemoryEntry {
+                id: i.to_string(),
+                key: format!("k{i}"),
+                content: "Standup is at 9".into(),
+                category: MemoryCategory::Daily,
+                timestamp: format!("{day}T09:00:00Z"),
+                session_id: Some("s1".into()),
+                score: None,
+                metadata: MemoryMetadata::default(),
+            })
+            .collect();
+        assert_eq!(distinct_days(&cluster), 3);
+
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let journal = ConsolidationJournal::open_in_memory().unwrap();
+        let provider = ScriptedProvider {
+            reply: r#"{"content": "Standup is at 9", "stable_fact": false}"#.into(),
+        };
+        let merge = merge_cluster(
+            &mem,
+            &provider,
+            "test-model",
+            &journal,
+            cluster,
+            &ConsolidationOptions::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(merge.category, MemoryCategory::Core);
+        let stored = mem.get(&merge.merged_key).await.unwrap().unwrap();
+        assert_eq!(stored.session_id.as_deref(), Some("s1"));
+    }
+}
@@ -1,4 +1,7 @@
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -9,6 +12,12 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Embedding dimensions
     fn dimensions(&self) -> usize;
 
+    /// Identity of the vector space this provider produces. Stored vectors are
+    /// only comparable with queries embedded under the same fingerprint.
+    fn fingerprint(&self) -> String {
+        format!("{}:{}", self.name(), self.dimensions())
+    }
+
     /// Embed a batch of texts into vectors
     async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>>;
 
@@ -103,6 +112,10 @@ impl EmbeddingProvider for OpenAiEmbedding {
         self.dims
     }
 
+    fn fingerprint(&self) -> String {
+        format!("openai:{}:{}", self.model, self.dims)
+    }
+
     async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(Vec::new());
@@ -154,6 +167,197 @@ impl EmbeddingProvider for OpenAiEmbedding {
     }
 }
 
+// ── Local hashed n-gram provider (offline floor) ─────────────
+
+/// Deterministic, CPU-only embedder using signed feature hashing.
+///
+/// Word unigrams and character trigrams are hashed into a fixed number of
+/// buckets and L2-normalized. No model file or network access is needed, so
+/// it works in air-gapped deployments; quality sits between pure keyword
+/// search and a trained sentence-embedding model.
+pub struct HashedNgramEmbedding {
+    dims: usize,
+}
+
+impl HashedNgramEmbedding {
+    pub fn new(dims: usize) -> Self {
+        Self { dims: dims.max(1) }
+    }
+
+    /// FNV-1a: stable across platforms and Rust versions, unlike `DefaultHasher`.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for b in bytes {
+            hash ^= u64::from(*b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = Self::fnv1a(feature.as_bytes());
+        #[allow(clippy::cast_possible_truncation)]
+        let bucket = (hash % self.dims as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0_f32; self.dims];
+        for token in tokenize(text) {
+            self.add_feature(&mut vector, &format!("w:{token}"), 1.0);
+
+            let padded: Vec<char> = format!("<{token}>").chars().collect();
+            for gram in padded.windows(3) {
+                let gram: String = gram.iter().collect();
+                self.add_feature(&mut vector, &format!("c:{gram}"), 0.5);
+            }
+        }
+        l2_normalize(&mut vector);
+        vector
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashedNgramEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    fn fingerprint(&self) -> String {
+        format!("local:hashed-ngram-v1:{}", self.dims)
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+// ── Local static word-vector provider ────────────────────────
+
+/// Sentence embeddings from a local word-vector file, averaged over tokens.
+///
+/// Accepts the plain-text `.vec` / GloVe layout: one `word v1 v2 ... vN`
+/// entry per line, with an optional `<count> <dims>` header line.
+pub struct StaticVectorEmbedding {
+    source: String,
+    dims: usize,
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+impl StaticVectorEmbedding {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read word vectors {}: {e}", path.display()))?;
+        let mut model = Self::parse(&raw)?;
+        // The content hash keeps two different files with the same name
+        // from sharing a fingerprint (and skipping a needed reindex).
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        let digest = hex::encode(Sha256::digest(raw.as_bytes()));
+        model.source = format!("{name}@{}", &digest[..16]);
+        Ok(model)
+    }
+
+    fn parse(raw: &str) -> anyhow::Result<Self> {
+        let mut dims = 0;
+        let mut vectors = HashMap::new();
+
+        for (line_no, line) in raw.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else {
+                continue;
+            };
+            let values: Vec<f32> =
+                parts
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| {
+                        anyhow::anyhow!("invalid vector value on line {}: {e}", line_no + 1)
+                    })?;
+
+            // fastText/word2vec header: "<count> <dims>"
+            if line_no == 0 && values.len() == 1 && word.parse::<usize>().is_ok() {
+                continue;
+            }
+            if values.is_empty() {
+                continue;
+            }
+            if dims == 0 {
+                dims = values.len();
+            } else if values.len() != dims {
+                anyhow::bail!(
+                    "word vector on line {} has {} dimensions, expected {dims}",
+                    line_no + 1,
+                    values.len()
+                );
+            }
+            vectors.insert(word.to_lowercase(), values);
+        }
+
+        if vectors.is_empty() {
+            anyhow::bail!("word vector file contains no vectors");
+        }
+
+        Ok(Self {
+            source: String::new(),
+            dims,
+            vectors,
+        })
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut sum = vec![0.0_f32; self.dims];
+        for vector in tokenize(text).filter_map(|token| self.vectors.get(&token)) {
+            for (acc, value) in sum.iter_mut().zip(vector) {
+                *acc += value;
+            }
+        }
+        l2_normalize(&mut sum);
+        sum
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for StaticVectorEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    fn fingerprint(&self) -> String {
+        format!("local:{}:{}", self.source, self.dims)
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
 // ── Factory ──────────────────────────────────────────────────
 
 pub fn create_embedding_provider(
@@ -161,8 +365,8 @@ pub fn create_embedding_provider(
     api_key: Option<&str>,
     model: &str,
     dims: usize,
-) -> Box<dyn EmbeddingProvider> {
-    match provider {
+) -> anyhow::Result<Box<dyn EmbeddingProvider>> {
+    let provider: Box<dyn EmbeddingProvider> = match provider {
         "openai" => {
             let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(
@@ -186,8 +390,17 @@ pub fn create_embedding_provider(
             let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
         }
+        "local" => Box::new(HashedNgramEmbedding::new(dims)),
+        name if name.starts_with("local:") => {
+            let path = name.strip_prefix("local:").unwrap_or("").trim();
+            // No silent fallback: a different embedder would quietly change recall.
+            let provider = StaticVectorEmbedding::load(Path::new(path))
+                .map_err(|e| anyhow::anyhow!("local embedding model unavailable: {e}"))?;
+            Box::new(provider)
+        }
         _ => Box::new(NoopEmbedding),
-    }
+    };
+    Ok(provider)
 }
 
 #[cfg(test)]
@@ -210,13 +423,14 @@ mod tests {
 
     #[test]
     fn factory_none() {
-        let p = create_embedding_provider("none", None, "model", 1536);
+        let p = create_embedding_provider("none", None, "model", 1536).unwrap();
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_openai() {
-        let p = create_embedding_provider("openai", Some("key"), "text-embedding-3-small", 1536);
+        let p = create_embedding_provider("openai", Some("key"), "text-embedding-3-small", 1536)
+            .unwrap();
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
@@ -228,14 +442,16 @@ mod tests {
             Some("sk-or-test"),
             "openai/text-embedding-3-small",
             1536,
-        );
+        )
+        .unwrap();
         assert_eq!(p.name(), "openai"); // uses OpenAiEmbedding internally
         assert_eq!(p.dimensions(), 1536);
     }
 
     #[test]
     fn factory_custom_url() {
-        let p = create_embedding_provider("custom:http://localhost:1234", None, "model", 768);
+        let p =
+            create_embedding_provider("custom:http://localhost:1234", None, "model", 768).unwrap();
         assert_eq!(p.name(), "openai"); // uses OpenAiEmbedding internally
         assert_eq!(p.dimensions(), 768);
     }
@@ -266,30 +482,124 @@ mod tests {
 
     #[test]
     fn factory_empty_string_returns_noop() {
-        let p = create_embedding_provider("", None, "model", 1536);
+        let p = create_embedding_provider("", None, "model", 1536).unwrap();
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_unknown_provider_returns_noop() {
-        let p = create_embedding_provider("cohere", None, "model", 1536);
+        let p = create_embedding_provider("cohere", None, "model", 1536).unwrap();
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_custom_empty_url() {
         // "custom:" with no URL — should still construct without panic
-        let p = create_embedding_provider("custom:", None, "model", 768);
+        let p = create_embedding_provider("custom:", None, "model", 768).unwrap();
         assert_eq!(p.name(), "openai");
     }
 
     #[test]
     fn factory_openai_no_api_key() {
-        let p = create_embedding_provider("openai", None, "text-embedding-3-small", 1536);
+        let p = create_embedding_provider("openai", None, "text-embedding-3-small", 1536).unwrap();
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
 
+    #[test]
+    fn factory_local_uses_hashed_ngrams() {
+        let p = create_embedding_provider("local", None, "ignored", 256).unwrap();
+        assert_eq!(p.name(), "local");
+        assert_eq!(p.dimensions(), 256);
+        assert_eq!(p.fingerprint(), "local:hashed-ngram-v1:256");
+    }
+
+    #[test]
+    fn factory_local_missing_file_is_an_error() {
+        let err = create_embedding_provider("local:/nonexistent/vectors.vec", None, "m", 64)
+            .err()
+            .expect("missing model must not fall back silently");
+        assert!(err
+            .to_string()
+            .contains("local embedding model unavailable"));
+    }
+
+    #[test]
+    fn factory_local_loads_word_vector_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("tiny.vec");
+        std::fs::write(&path, "2 3\ncat 1 0 0\ndog 0.9 0.1 0\n").unwrap();
+
+        let p = create_embedding_provider(&format!("local:{}", path.display()), None, "m", 1536)
+            .unwrap();
+        assert_eq!(p.dimensions(), 3);
+        assert!(p.fingerprint().starts_with("local:tiny.vec@"));
+
+        // Same file name, different vectors: the fingerprint must change.
+        let other = tmp.path().join("other");
+        std::fs::create_dir(&other).unwrap();
+        let other_path = other.join("tiny.vec");
+        std::fs::write(&other_path, "2 3\ncat 0 1 0\ndog 0.1 0.9 0\n").unwrap();
+        let q =
+            create_embedding_provider(&format!("local:{}", other_path.display()), None, "m", 1536)
+                .unwrap();
+        assert_ne!(p.fingerprint(), q.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_distinguishes_models() {
+        let a = OpenAiEmbedding::new("https://api.openai.com", "k", "model-a", 1536);
+        let b = OpenAiEmbedding::new("https://api.openai.com", "k", "model-b", 1536);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        assert_eq!(NoopEmbedding.fingerprint(), "none:0");
+    }
+
+    #[tokio::test]
+    async fn hashed_embedding_is_deterministic_and_normalized() {
+        let p = HashedNgramEmbedding::new(128);
+        let a = p.embed_one("The quick brown fox").await.unwrap();
+        let b = p.embed_one("the QUICK brown fox!").await.unwrap();
+        assert_eq!(a, b);
+        let norm: f32 = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+
+    #[tokio::test]
+    async fn hashed_embedding_ranks_related_text_higher() {
+        let p = HashedNgramEmbedding::new(512);
+        let query = p.embed_one("deploy the rust service").await.unwrap();
+        let related = p.embed_one("rust service deployment notes").await.unwrap();
+        let unrelated = p.embed_one("grandma's lasagna recipe").await.unwrap();
+        assert!(
+            super::super::vector::cosine_similarity(&query, &related)
+                > super::super::vector::cosine_similarity(&query, &unrelated)
+        );
+    }
+
+    #[tokio::test]
+    async fn hashed_embedding_of_empty_text_is_zero() {
+        let p = HashedNgramEmbedding::new(16);
+        let v = p.embed_one("  ...  ").await.unwrap();
+        assert_eq!(v.len(), 16);
+        assert!(v.iter().all(|x| *x == 0.0));
+    }
+
+    #[tokio::test]
+    async fn static_vectors_average_known_tokens() {
+        let p = StaticVectorEmbedding::parse("cat 1 0\ndog 0 1\n").unwrap();
+        let v = p.embed_one("Cat and dog").await.unwrap();
+        assert!((v[0] - v[1]).abs() < 1e-6);
+        assert!(v[0] > 0.0);
+    }
+
+    #[test]
+    fn static_vectors_reject_ragged_rows() {
+        let err = StaticVectorEmbedding::parse("cat 1 0\ndog 0 1 2\n")
+            .err()
+            .expect("ragged rows should be rejected");
+        assert!(err.to_string().contains("expected 2"));
+    }
+
     #[test]
     fn openai_trailing_slash_stripped() {
         let p = OpenAiEmbedding::new("https://api.openai.com/", "key", "model", 1536);
@@ -0,0 +1,1068 @@
+//! Knowledge-graph memory backend.
+//!
+//! Wraps the SQLite store and, on every write, extracts `(subject, predicate,
+//! object)` triples from the stored content into an entity/relation graph in
+//! `<workspace>/memory/graph.db`. Recall merges the usual keyword/vector
+//! hybrid results with entries reached by walking the neighbourhood of the
+//! entities named in the query, so relational questions ("which of my devices
+//! are in the kitchen?") surface facts that share no keywords with the query.
+//!
+//! Extraction is rule-based: simple copular and verb phrases ("X is in Y",
+//! "X works at Y", "X is a Y", ...) plus explicit `X -> predicate -> Y` lines.
+
+use super::namespace::{MemoryNamespace, RecallScope};
+use super::sqlite::SqliteMemory;
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord};
+use crate::config::MemoryGraphConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tokio::sync::OnceCell;
+
+const GRAPH_FILE_NAME: &str = "graph.db";
+
+/// `graph_meta` key set once existing entries have been indexed.
+const BACKFILLED_KEY: &str = "backfilled";
+
+/// Longest entity name, in words, accepted from extraction.
+const MAX_ENTITY_WORDS: usize = 5;
+const MAX_ENTITY_CHARS: usize = 64;
+
+/// Longest query n-gram looked up as an entity name.
+const MAX_QUERY_NGRAM: usize = 4;
+const MAX_QUERY_TOKENS: usize = 64;
+
+/// Relation phrases and the predicate they map to, longest first so
+/// "is located in" wins over "is in" at the same position.
+const RELATION_PHRASES: &[(&str, &str)] = &[
+    ("is a kind of", "is_a"),
+    ("is a type of", "is_a"),
+    ("is located in", "located_in"),
+    ("are located in", "located_in"),
+    ("is located at", "located_in"),
+    ("is connected to", "connected_to"),
+    ("are connected to", "connected_to"),
+    ("is married to", "married_to"),
+    ("is part of", "part_of"),
+    ("are part of", "part_of"),
+    ("belongs to", "belongs_to"),
+    ("belong to", "belongs_to"),
+    ("works at", "works_at"),
+    ("works for", "works_at"),
+    ("work at", "works_at"),
+    ("work for", "works_at"),
+    ("lives in", "lives_in"),
+    ("live in", "lives_in"),
+    ("is in", "located_in"),
+    ("are in", "located_in"),
+    ("is an", "is_a"),
+    ("is a", "is_a"),
+    ("prefers", "prefers"),
+    ("prefer", "prefers"),
+    ("likes", "likes"),
+    ("like", "likes"),
+    ("loves", "likes"),
+    ("love", "likes"),
+    ("owns", "has"),
+    ("own", "has"),
+    ("has", "has"),
+    ("have", "has"),
+    ("uses", "uses"),
+    ("use", "uses"),
+    ("are", "is_a"),
+];
+
+const LEADING_DETERMINERS: &[&str] = &[
+    "the", "a", "an", "my", "our", "your", "his", "her", "their", "its", "this", "that", "these",
+    "those", "all", "some",
+];
+
+/// Words that never name an entity on their own.
+const NON_ENTITIES: &[&str] = &[
+    "it", "they", "he", "she", "we", "you", "them", "there", "here", "which", "who", "what",
+    "this", "that", "also", "not",
+];
+
+/// One extracted fact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+}
+
+/// A stored relation together with the memory it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphRelation {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+    pub source_key: String,
+    pub namespace: MemoryNamespace,
+}
+
+/// A relation reached `hop` steps away from the starting entities.
+#[derive(Debug, Clone)]
+pub struct TraversedRelation {
+    pub hop: usize,
+    pub relation: GraphRelation,
+}
+
+/// Extract triples from free text.
+pub fn extract_triples(text: &str) -> Vec<Triple> {
+    let mut triples = Vec::new();
+    for sentence in text.split(['.', '!', '?', ';', '\n']) {
+        if let Some(triple) = explicit_triple(sentence) {
+            triples.push(triple);
+            continue;
+        }
+        extract_sentence(sentence, &mut triples);
+    }
+    let mut seen = HashSet::new();
+    triples.retain(|t| seen.insert((t.subject.clone(), t.predicate.clone(), t.object.clone())));
+    triples
+}
+
+/// `subject -> predicate -> object` or `subject | predicate | object`.
+fn explicit_triple(line: &str) -> Option<Triple> {
+    let parts: Vec<&str> = if line.contains("->") {
+        line.split("->").collect()
+    } else if line.matches('|').count() == 2 {
+        line.split('|').collect()
+    } else {
+        return None;
+    };
+    let [subject, predicate, object] = parts.as_slice() else {
+        return None;
+    };
+    let predicate = predicate
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_");
+    if predicate.is_empty() {
+        return None;
+    }
+    Some(Triple {
+        subject: normalize_entity(subject)?,
+        predicate,
+        object: normalize_entity(object)?,
+    })
+}
+
+fn extract_sentence(sentence: &str, out: &mut Vec<Triple>) {
+    let lower = sentence.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let Some((start, len, predicate)) = earliest_relation(&words) else {
+        return;
+    };
+
+    let subjects = split_entities(&words[..start]);
+    if subjects.is_empty() {
+        return;
+    }
+
+    let mut predicate = predicate;
+    let mut rest = &words[start + len..];
+    loop {
+        // "X is a lamp and is in the kitchen" continues with the same subject.
+        let continuation = rest.iter().enumerate().find_map(|(i, word)| {
+            if *word != "and" {
+                return None;
+            }
+            relation_at(&rest[i + 1..]).map(|(len, pred)| (i, len, pred))
+        });
+        let object_words = continuation.map_or(rest, |(i, _, _)| &rest[..i]);
+        for object in split_entities(object_words) {
+            for subject in &subjects {
+                if *subject != object {
+                    out.push(Triple {
+                        subject: subject.clone(),
+                        predicate: predicate.to_string(),
+                        object: object.clone(),
+                    });
+                }
+            }
+        }
+        match continuation {
+            Some((i, len, next)) => {
+                predicate = next;
+                rest = &rest[i + 1 + len..];
+            }
+            None => break,
+        }
+    }
+}
+
+/// First relation phrase in `words`: `(start, word count, predicate)`.
+fn earliest_relation(words: &[&str]) -> Option<(usize, usize, &'static str)> {
+    (1..words.len()).find_map(|start| relation_at(&words[start..]).map(|(len, p)| (start, len, p)))
+}
+
+/// Relation phrase at the very start of `words`: `(word count, predicate)`.
+fn relation_at(words: &[&str]) -> Option<(usize, &'static str)> {
+    RELATION_PHRASES.iter().find_map(|(phrase, predicate)| {
+        let phrase_words: Vec<&str> = phrase.split(' ').collect();
+        (words.len() > phrase_words.len() && words.starts_with(&phrase_words))
+            .then_some((phrase_words.len(), *predicate))
+    })
+}
+
+/// Split "the lamp, the fan and the heater" into normalized entities.
+fn split_entities(words: &[&str]) -> Vec<String> {
+    words
+        .join(" ")
+        .split([',', '&'])
+        .flat_map(|part| part.split(" and "))
+        .filter_map(normalize_entity)
+        .collect()
+}
+
+/// Lowercase, strip punctuation and determiners, singularize the head noun.
+///
+/// Returns `None` for pronouns and phrases too long to be an entity name.
+pub fn normalize_entity(raw: &str) -> Option<String> {
+    let cleaned: String = raw
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words
+        .first()
+        .is_some_and(|w| LEADING_DETERMINERS.contains(w))
+    {
+        words.remove(0);
+    }
+    match words.as_slice() {
+        [] => return None,
+        ["i" | "me"] => return Some("user".into()),
+        [single] if NON_ENTITIES.contains(single) => return None,
+        _ => {}
+    }
+    if words.len() > MAX_ENTITY_WORDS {
+        return None;
+    }
+
+    let last = words.len() - 1;
+    let head = singularize(words[last]);
+    let mut name = words[..last].join(" ");
+    if !name.is_empty() {
+        name.push(' ');
+    }
+    name.push_str(&head);
+    (name.chars().count() <= MAX_ENTITY_CHARS).then_some(name)
+}
+
+fn singularize(word: &str) -> String {
+    if word.chars().count() <= 3 {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{stem}y");
+    }
+    if word.ends_with("sses") || word.ends_with("xes") || word.ends_with("ches") {
+        return word[..word.len() - 2].to_string();
+    }
+    if word.ends_with('s')
+        && !word.ends_with("ss")
+        && !word.ends_with("us")
+        && !word.ends_with("is")
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+/// Candidate entity names mentioned in `query`: every 1–4 word n-gram.
+fn query_entity_candidates(query: &str) -> Vec<String> {
+    let cleaned: String = query
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let tokens: Vec<&str> = cleaned.split_whitespace().take(MAX_QUERY_TOKENS).collect();
+
+    let mut candidates = HashSet::new();
+    for n in 1..=MAX_QUERY_NGRAM.min(tokens.len()) {
+        for window in tokens.windows(n) {
+            if let Some(name) = normalize_entity(&window.join(" ")) {
+                candidates.insert(name);
+            }
+        }
+    }
+    candidates.into_iter().collect()
+}
+
+/// SQLite entity/relation store.
+pub struct KnowledgeGraph {
+    conn: Mutex<Connection>,
+}
+
+impl KnowledgeGraph {
+    /// Open (or create) the graph under `workspace_dir/memory/`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let dir = workspace_dir.join("memory");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create memory directory: {}", dir.display()))?;
+        let db_path = dir.join(GRAPH_FILE_NAME);
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open knowledge graph: {}", db_path.display()))?;
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        Self::init(conn)
+    }
+
+    /// In-memory graph (tests).
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS graph_entities (
+                id    INTEGER PRIMARY KEY,
+                name  TEXT NOT NULL UNIQUE
+             );
+             CREATE TABLE IF NOT EXISTS graph_relations (
+                id          INTEGER PRIMARY KEY,
+                subject_id  INTEGER NOT NULL,
+                predicate   TEXT NOT NULL,
+                object_id   INTEGER NOT NULL,
+                source_key  TEXT NOT NULL,
+                namespace   TEXT,
+                UNIQUE(subject_id, predicate, object_id, source_key)
+             );
+             CREATE INDEX IF NOT EXISTS idx_graph_relations_subject ON graph_relations(subject_id);
+             CREATE INDEX IF NOT EXISTS idx_graph_relations_object ON graph_relations(object_id);
+             CREATE INDEX IF NOT EXISTS idx_graph_relations_source ON graph_relations(source_key);
+             CREATE TABLE IF NOT EXISTS graph_meta (
+                key    TEXT PRIMARY KEY,
+                value  TEXT NOT NULL
+             );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Replace the relations extracted from memory `key`. Returns how many were stored.
+    pub fn index(&self, key: &str, content: &str, namespace: &MemoryNamespace) -> Result<usize> {
+        let triples = extract_triples(content);
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let removed = tx.execute(
+            "DELETE FROM graph_relations WHERE source_key = ?1",
+            params![key],
+        )?;
+        for triple in &triples {
+            let subject_id = Self::entity_id(&tx, &triple.subject)?;
+            let object_id = Self::entity_id(&tx, &triple.object)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO graph_relations (subject_id, predicate, object_id, source_key, namespace)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![subject_id, triple.predicate, object_id, key, namespace.to_metadata()],
+            )?;
+        }
+        if removed > 0 {
+            Self::prune_entities(&tx)?;
+        }
+        tx.commit()?;
+        Ok(triples.len())
+    }
+
+    /// Drop every relation extracted from memory `key`.
+    pub fn remove_source(&self, key: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        let removed = conn.execute(
+            "DELETE FROM graph_relations WHERE source_key = ?1",
+            params![key],
+        )?;
+        if removed > 0 {
+            Self::prune_entities(&conn)?;
+        }
+        Ok(())
+    }
+
+    /// Distinct memory keys that contributed relations.
+    pub fn source_keys(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT DISTINCT source_key FROM graph_relations")?;
+        let keys = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(keys)
+    }
+
+    /// Remove all entities and relations.
+    pub fn clear(&self) -> Result<()> {
+        self.conn.lock().execute_batch(
+            "DELETE FROM graph_relations;
+             DELETE FROM graph_entities;
+             DELETE FROM graph_meta;",
+        )?;
+        Ok(())
+    }
+
+    pub fn counts(&self) -> Result<(usize, usize)> {
+        let conn = self.conn.lock();
+        let entities: i64 =
+            conn.query_row("SELECT COUNT(*) FROM graph_entities", [], |row| row.get(0))?;
+        let relations: i64 =
+            conn.query_row("SELECT COUNT(*) FROM graph_relations", [], |row| row.get(0))?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok((entities as usize, relations as usize))
+    }
+
+    /// Ids of stored entities named in `query`.
+    pub fn entities_in(&self, query: &str) -> Result<Vec<i64>> {
+        let candidates = query_entity_candidates(query);
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; candidates.len()].join(", ");
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id FROM graph_entities WHERE name IN ({placeholders})"
+        ))?;
+        let ids = stmt
+            .query_map(params_from_iter(candidates.iter()), |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i64>>>()?;
+        Ok(ids)
+    }
+
+    /// Id of the entity called `name` (normalized like extracted entities).
+    pub fn find_entity(&self, name: &str) -> Result<Option<i64>> {
+        let Some(name) = normalize_entity(name) else {
+            return Ok(None);
+        };
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT id FROM graph_entities WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Breadth-first walk from `seeds`, following relations in both directions.
+    pub fn neighbourhood(
+        &self,
+        seeds: &[i64],
+        max_hops: usize,
+        max_relations: usize,
+    ) -> Result<Vec<TraversedRelation>> {
+        let conn = self.conn.lock();
+        let mut visited: HashSet<i64> = seeds.iter().copied().collect();
+        let mut frontier: Vec<i64> = seeds.to_vec();
+        let mut seen_relations = HashSet::new();
+        let mut traversed = Vec::new();
+
+        for hop in 1..=max_hops {
+            if frontier.is_empty() || traversed.len() >= max_relations {
+                break;
+            }
+            let placeholders = vec!["?"; frontier.len()].join(", ");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT r.id, s.name, r.predicate, o.name, r.source_key, r.namespace, r.subject_id, r.object_id
+                 FROM graph_relations r
+                 JOIN graph_entities s ON s.id = r.subject_id
+                 JOIN graph_entities o ON o.id = r.object_id
+                 WHERE r.subject_id IN ({placeholders}) OR r.object_id IN ({placeholders})
+                 ORDER BY r.id"
+            ))?;
+            let bound: Vec<i64> = frontier.iter().chain(frontier.iter()).copied().collect();
+            let rows = stmt.query_map(params_from_iter(bound.iter()), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    GraphRelation {
+                        subject: row.get(1)?,
+                        predicate: row.get(2)?,
+                        object: row.get(3)?,
+                        source_key: row.get(4)?,
+                        namespace: row
+                            .get::<_, Option<String>>(5)?
+                            .and_then(|raw| MemoryNamespace::parse(&raw).ok())
+                            .unwrap_or_else(MemoryNamespace::shared),
+                    },
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, i64>(7)?,
+                ))
+            })?;
+
+            let mut next = Vec::new();
+            for row in rows {
+                let (id, relation, subject_id, object_id) = row?;
+                if !seen_relations.insert(id) {
+                    continue;
+                }
+                for endpoint in [subject_id, object_id] {
+                    if visited.insert(endpoint) {
+                        next.push(endpoint);
+                    }
+                }
+                traversed.push(TraversedRelation { hop, relation });
+                if traversed.len() >= max_relations {
+                    break;
+                }
+            }
+            frontier = next;
+        }
+
+        Ok(traversed)
+    }
+
+    fn is_backfilled(&self) -> Result<bool> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT value FROM graph_meta WHERE key = ?1",
+                params![BACKFILLED_KEY],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    fn mark_backfilled(&self) -> Result<()> {
+        self.conn.lock().execute(
+            "INSERT OR REPLACE INTO graph_meta (key, value) VALUES (?1, '1')",
+            params![BACKFILLED_KEY],
+        )?;
+        Ok(())
+    }
+
+    fn entity_id(conn: &Connection, name: &str) -> rusqlite::Result<i64> {
+        conn.execute(
+            "INSERT OR IGNORE INTO graph_entities (name) VALUES (?1)",
+            params![name],
+        )?;
+        conn.query_row(
+            "SELECT id FROM graph_entities WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+    }
+
+    fn prune_entities(conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute(
+            "DELETE FROM graph_entities WHERE id NOT IN (
+                SELECT subject_id FROM graph_relations UNION SELECT object_id FROM graph_relations
+             )",
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+/// SQLite memory with an entity/relation graph alongside.
+pub struct GraphMemory {
+    local: SqliteMemory,
+    graph: KnowledgeGraph,
+    config: MemoryGraphConfig,
+    backfill: OnceCell<()>,
+}
+
+impl GraphMemory {
+    pub fn new(
+        workspace_dir: &Path,
+        local: SqliteMemory,
+        config: MemoryGraphConfig,
+    ) -> Result<Self> {
+        Ok(Self::with_graph(
+            local,
+            KnowledgeGraph::open(workspace_dir)?,
+            config,
+        ))
+    }
+
+    pub fn with_graph(
+        local: SqliteMemory,
+        graph: KnowledgeGraph,
+        config: MemoryGraphConfig,
+    ) -> Self {
+        Self {
+            local,
+            graph,
+            config,
+            backfill: OnceCell::new(),
+        }
+    }
+
+    pub fn graph(&self) -> &KnowledgeGraph {
+        &self.graph
+    }
+
+    /// Re-extract the whole graph from the stored entries.
+    pub async fn rebuild(&self) -> Result<usize> {
+        self.graph.clear()?;
+        let records = self.local.export_records().await?;
+        for record in &records {
+            self.index_entry(&record.entry)?;
+        }
+        self.graph.mark_backfilled()?;
+        Ok(records.len())
+    }
+
+    /// Index entries stored before the graph existed, once per workspace.
+    async fn ensure_backfilled(&self) -> Result<()> {
+        self.backfill
+            .get_or_try_init(|| async {
+                if !self.graph.is_backfilled()? {
+                    let indexed = self.rebuild().await?;
+                    tracing::info!("knowledge graph: indexed {indexed} existing memories");
+                }
+                Ok::<(), anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn index_entry(&self, entry: &MemoryEntry) -> Result<usize> {
+        self.graph
+            .index(&entry.key, &entry.content, &MemoryNamespace::of(entry))
+    }
+
+    /// Graph evidence per memory key, normalized so the strongest key is 1.0.
+    ///
+    /// Each traversed relation contributes `0.5^(hop - 1)` to its source, so
+    /// entries linked to several query entities rank above single links.
+    /// Relations outside `scope` are ignored.
+    fn graph_scores(&self, query: &str, scope: &RecallScope) -> Result<HashMap<String, f64>> {
+        let seeds = self.graph.entities_in(query)?;
+        if seeds.is_empty() || self.config.max_hops == 0 {
+            return Ok(HashMap::new());
+        }
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for traversed in
+            self.graph
+                .neighbourhood(&seeds, self.config.max_hops, self.config.max_relations)?
+        {
+            if !scope.allows_namespace(&traversed.relation.namespace) {
+                continue;
+            }
+            let hop = i32::try_from(traversed.hop).unwrap_or(i32::MAX);
+            *scores.entry(traversed.relation.source_key).or_default() += 0.5_f64.powi(hop - 1);
+        }
+        let max = scores.values().copied().fold(0.0, f64::max);
+        if max > 0.0 {
+            for score in scores.values_mut() {
+                *score /= max;
+            }
+        }
+        Ok(scores)
+    }
+
+    /// Merge graph-neighbourhood hits into `entries` from the hybrid recall.
+    ///
+    /// Scores combine as `1 - (1 - hybrid) * (1 - graph_weight * graph)`, so
+    /// either signal alone can surface an entry and both together rank highest.
+    /// Graph-only hits must pass the same session, scope and tag filters the
+    /// hybrid recall applied.
+    async fn merge_graph_hits(
+        &self,
+        mut entries: Vec<MemoryEntry>,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        scope: &RecallScope,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        let graph_scores = self.graph_scores(query, scope)?;
+        if graph_scores.is_empty() {
+            return Ok(entries);
+        }
+
+        let weight = self.config.graph_weight.clamp(0.0, 1.0);
+        let combine = |hybrid: f64, graph: f64| 1.0 - (1.0 - hybrid) * (1.0 - weight * graph);
+        let mut present: HashSet<String> = HashSet::new();
+        for entry in &mut entries {
+            let graph = graph_scores.get(&entry.key).copied().unwrap_or(0.0);
+            entry.score = Some(combine(entry.score.unwrap_or(0.0), graph));
+            present.insert(entry.key.clone());
+        }
+
+        let mut graph_only: Vec<(&String, f64)> = graph_scores
+            .iter()
+            .filter(|(key, _)| !present.contains(*key))
+            .map(|(key, score)| (key, *score))
+            .collect();
+        graph_only.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        for (key, graph) in graph_only.into_iter().take(limit) {
+            let Some(mut entry) = self.local.get(key).await? else {
+                continue;
+            };
+            if session_id.is_some_and(|sid| entry.session_id.as_deref() != Some(sid))
+                || !scope.allows(&entry)
+                || !entry.metadata.has_tags(tags)
+            {
+                continue;
+            }
+            entry.score = Some(combine(0.0, graph));
+            entries.push(entry);
+        }
+
+        entries.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl Memory for GraphMemory {
+    fn name(&self) -> &str {
+        "graph"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> Result<()> {
+        self.ensure_backfilled().await?;
+        let namespace = metadata
+            .namespace
+            .as_deref()
+            .and_then(|raw| MemoryNamespace::parse(raw).ok())
+            .unwrap_or_else(MemoryNamespace::shared);
+        self.local
+            .store_with_metadata(key, content, category, session_id, metadata)
+            .await?;
+        self.graph.index(key, content, &namespace)?;
+        Ok(())
+    }
+
+    /// Hybrid recall merged with graph-neighbourhood hits.
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.ensure_backfilled().await?;
+        let entries = self.local.recall(query, limit, session_id).await?;
+        self.merge_graph_hits(entries, query, limit, session_id, &RecallScope::All, &[])
+            .await
+    }
+
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        self.ensure_backfilled().await?;
+        let entries = self
+            .local
+            .recall_tagged(query, limit, session_id, tags)
+            .await?;
+        self.merge_graph_hits(entries, query, limit, session_id, &RecallScope::All, tags)
+            .await
+    }
+
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        self.ensure_backfilled().await?;
+        let entries = self
+            .local
+            .recall_scoped(scope, query, limit, session_id, tags)
+            .await?;
+        self.merge_graph_hits(entries, query, limit, session_id, scope, tags)
+            .await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
+        self.local.get(key).await
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.local.list(category, session_id).await
+    }
+
+    async fn forget(&self, key: &str) -> Result<bool> {
+        let removed = self.local.forget(key).await?;
+        self.graph.remove_source(key)?;
+        Ok(removed)
+    }
+
+    async fn count(&self) -> Result<usize> {
+        self.local.count().await
+    }
+
+    async fn export_records(&self) -> Result<Vec<MemoryRecord>> {
+        self.local.export_records().await
+    }
+
+    async fn import_record(&self, record: MemoryRecord) -> Result<()> {
+        let (key, content) = (record.entry.key.clone(), record.entry.content.clone());
+        let namespace = MemoryNamespace::of(&record.entry);
+        self.local.import_record(record).await?;
+        self.graph.index(&key, &content, &namespace)?;
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<usize> {
+        let purged = self.local.purge_expired().await?;
+        if purged > 0 {
+            for key in self.graph.source_keys()? {
+                if self.local.get(&key).await?.is_none() {
+                    self.graph.remove_source(&key)?;
+                }
+            }
+        }
+        Ok(purged)
+    }
+
+    async fn health_check(&self) -> bool {
+        self.local.health_check().await && self.graph.counts().is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn triple(subject: &str, predicate: &str, object: &str) -> Triple {
+        Triple {
+            subject: subject.into(),
+            predicate: predicate.into(),
+            object: object.into(),
+        }
+    }
+
+    fn graph_memory(tmp: &TempDir) -> GraphMemory {
+        GraphMemory::new(
+            tmp.path(),
+            SqliteMemory::new(tmp.path()).unwrap(),
+            MemoryGraphConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn extracts_relations_lists_and_continuations() {
+        assert_eq!(
+            extract_triples("The thermostat is located in the kitchen."),
+            vec![triple("thermostat", "located_in", "kitchen")]
+        );
+        assert_eq!(
+            extract_triples("The lamp and the speakers are in the living room"),
+            vec![
+                triple("lamp", "located_in", "living room"),
+                triple("speaker", "located_in", "living room"),
+            ]
+        );
+        assert_eq!(
+            extract_triples("The kettle is a smart device and is in the kitchen"),
+            vec![
+                triple("kettle", "is_a", "smart device"),
+                triple("kettle", "located_in", "kitchen"),
+            ]
+        );
+        assert_eq!(
+            extract_triples("I work at Acme Corp. Acme Corp -> headquartered in -> Berlin"),
+            vec![
+                triple("user", "works_at", "acme corp"),
+                triple("acme corp", "headquartered_in", "berlin"),
+            ]
+        );
+    }
+
+    #[test]
+    fn extraction_skips_pronouns_and_long_phrases() {
+        assert!(extract_triples("It is in the drawer").is_empty());
+        assert!(extract_triples("hello there").is_empty());
+        assert!(extract_triples(
+            "The thing we talked about at length yesterday afternoon is in the garage"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn normalize_entity_singularizes_head_noun() {
+        assert_eq!(normalize_entity("My Devices").as_deref(), Some("device"));
+        assert_eq!(
+            normalize_entity("the batteries").as_deref(),
+            Some("battery")
+        );
+        assert_eq!(normalize_entity("glass").as_deref(), Some("glass"));
+        assert_eq!(normalize_entity("they"), None);
+    }
+
+    #[test]
+    fn neighbourhood_walks_both_directions_and_tracks_hops() {
+        let graph = KnowledgeGraph::open_in_memory().unwrap();
+        let shared = MemoryNamespace::shared();
+        graph
+            .index("k1", "The thermostat is in the kitchen", &shared)
+            .unwrap();
+        graph
+            .index("k2", "The thermostat is a Nest device", &shared)
+            .unwrap();
+        graph.index("k3", "Bob lives in Paris", &shared).unwrap();
+
+        let seeds = graph.entities_in("what is in the kitchen?").unwrap();
+        let walked = graph.neighbourhood(&seeds, 2, 50).unwrap();
+        let sources: Vec<(usize, &str)> = walked
+            .iter()
+            .map(|t| (t.hop, t.relation.source_key.as_str()))
+            .collect();
+        assert_eq!(sources, vec![(1, "k1"), (2, "k2")]);
+
+        graph.remove_source("k2").unwrap();
+        assert_eq!(graph.counts().unwrap(), (4, 2));
+    }
+
+    #[tokio::test]
+    async fn recall_surfaces_graph_neighbours_without_shared_keywords() {
+        let tmp = TempDir::new().unwrap();
+        let mem = graph_memory(&tmp);
+        mem.store(
+            "thermostat_room",
+            "The thermostat is in the kitchen",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "thermostat_kind",
+            "The thermostat is a Nest device",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store("tea", "User drinks green tea", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let results = mem
+            .recall("which of my devices are in the kitchen", 5, None)
+            .await
+            .unwrap();
+        let keys: Vec<&str> = results.iter().map(|e| e.key.as_str()).collect();
+        assert!(keys.contains(&"thermostat_room"));
+        assert!(keys.contains(&"thermostat_kind"));
+        assert!(!keys.contains(&"tea"));
+
+        assert!(mem.forget("thermostat_kind").await.unwrap());
+        let results = mem.recall("devices", 5, None).await.unwrap();
+        assert!(results.iter().all(|e| e.key != "thermostat_kind"));
+    }
+
+    #[tokio::test]
+    async fn scoped_recall_ignores_graph_hits_from_other_namespaces() {
+        let tmp = TempDir::new().unwrap();
+        let mem = graph_memory(&tmp);
+        let in_namespace = |raw: &str| MemoryMetadata {
+            namespace: Some(raw.into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "thermostat_room",
+            "The thermostat is in the kitchen",
+            MemoryCategory::Core,
+            None,
+            in_namespace("channel:telegram"),
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "thermostat_kind",
+            "The thermostat is a Nest device",
+            MemoryCategory::Core,
+            None,
+            in_namespace("channel:discord"),
+        )
+        .await
+        .unwrap();
+
+        let scope = RecallScope::Namespace {
+            namespace: MemoryNamespace::parse("channel:telegram").unwrap(),
+            include_shared: false,
+        };
+        let results = mem
+            .recall_scoped(&scope, "which devices are in the kitchen", 5, None, &[])
+            .await
+            .unwrap();
+        let keys: Vec<&str> = results.iter().map(|e| e.key.as_str()).collect();
+        assert!(keys.contains(&"thermostat_room"));
+        assert!(!keys.contains(&"thermostat_kind"));
+
+        let unscoped = mem
+            .recall("which devices are in the kitchen", 5, None)
+            .await
+            .unwrap();
+        assert!(unscoped.iter().any(|e| e.key == "thermostat_kind"));
+    }
+
+    #[tokio::test]
+    async fn backfill_indexes_entries_stored_before_the_graph() {
+        let tmp = TempDir::new().unwrap();
+        let local = SqliteMemory::new(tmp.path()).unwrap();
+        local
+            .store(
+                "office",
+                "Alice works at Initech",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        drop(local);
+
+        let mem = graph_memory(&tmp);
+        let results = mem.recall("initech", 5, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(mem.graph().counts().unwrap(), (2, 1));
+    }
+}
@@ -17,6 +17,8 @@ struct HygieneReport {
     purged_memory_archives: u64,
     purged_session_archives: u64,
     pruned_conversation_rows: u64,
+    #[serde(default)]
+    purged_expired_memories: u64,
 }
 
 impl HygieneReport {
@@ -26,6 +28,7 @@ impl HygieneReport {
             + self.purged_memory_archives
             + self.purged_session_archives
             + self.pruned_conversation_rows
+            + self.purged_expired_memories
     }
 }
 
@@ -37,8 +40,15 @@ struct HygieneState {
 
 /// Run memory/session hygiene if the cadence window has elapsed.
 ///
+/// `purge_expired` deletes TTL-expired entries from the configured backend and
+/// returns how many were removed; it only runs when the pass is due.
+///
 /// This function is intentionally best-effort: callers should log and continue on failure.
-pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
+pub fn run_if_due(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    purge_expired: impl FnOnce() -> Result<usize>,
+) -> Result<()> {
     if !config.hygiene_enabled {
         return Ok(());
     }
@@ -59,18 +69,20 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
             workspace_dir,
             config.conversation_retention_days,
         )?,
+        purged_expired_memories: u64::try_from(purge_expired()?).unwrap_or(0),
     };
 
     write_state(workspace_dir, &report)?;
 
     if report.total_actions() > 0 {
         tracing::info!(
-            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={}",
+            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={} purged_expired_memories={}",
             report.archived_memory_files,
             report.archived_session_files,
             report.purged_memory_archives,
             report.purged_session_archives,
             report.pruned_conversation_rows,
+            report.purged_expired_memories,
         );
     }
 
@@ -197,6 +209,11 @@ fn archive_session_files(workspace_dir: &Path, archive_after_days: u32) -> Resul
             continue;
         };
 
+        // The live channel session store is pruned by its own retention policy.
+        if crate::channels::session_store::SessionStore::is_store_file(filename) {
+            continue;
+        }
+
         let is_old = if let Some(date) = date_prefix(filename) {
             date < cutoff_date
         } else {
@@ -404,7 +421,7 @@ mod tests {
         fs::write(&old_file, "old note").unwrap();
         fs::write(&today_file, "fresh note").unwrap();
 
-        run_if_due(&default_cfg(), workspace).unwrap();
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
 
         assert!(!old_file.exists(), "old daily file should be archived");
         assert!(
@@ -431,7 +448,7 @@ mod tests {
         let old_file = workspace.join("sessions").join(&old_name);
         fs::write(&old_file, "old session").unwrap();
 
-        run_if_due(&default_cfg(), workspace).unwrap();
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
 
         assert!(!old_file.exists(), "old session file should be archived");
         assert!(
@@ -444,6 +461,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn leaves_channel_session_store_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        fs::create_dir_all(workspace.join("sessions")).unwrap();
+
+        let stale = SystemTime::now() - StdDuration::from_secs(30 * 24 * 60 * 60);
+        for name in ["sessions.db", "sessions.db-wal"] {
+            let path = workspace.join("sessions").join(name);
+            fs::write(&path, "db").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(stale)
+                .unwrap();
+        }
+
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
+
+        assert!(workspace.join("sessions").join("sessions.db").exists());
+        assert!(workspace.join("sessions").join("sessions.db-wal").exists());
+    }
+
     #[test]
     fn skips_second_run_within_cadence_window() {
         let tmp = TempDir::new().unwrap();
@@ -456,7 +497,7 @@ mod tests {
         let file_a = workspace.join("memory").join(format!("{old_a}.md"));
         fs::write(&file_a, "first").unwrap();
 
-        run_if_due(&default_cfg(), workspace).unwrap();
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
         assert!(!file_a.exists(), "first old file should be archived");
 
         let old_b = (Local::now().date_naive() - Duration::days(9))
@@ -466,7 +507,7 @@ mod tests {
         fs::write(&file_b, "second").unwrap();
 
         // Should skip because cadence gate prevents a second immediate run.
-        run_if_due(&default_cfg(), workspace).unwrap();
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
         assert!(
             file_b.exists(),
             "second file should remain because run is throttled"
@@ -492,7 +533,7 @@ mod tests {
         fs::write(&old_file, "expired").unwrap();
         fs::write(&keep_file, "recent").unwrap();
 
-        run_if_due(&default_cfg(), workspace).unwrap();
+        run_if_due(&default_cfg(), workspace, || Ok(0)).unwrap();
 
         assert!(!old_file.exists(), "old archived file should be purged");
         assert!(keep_file.exists(), "recent archived file should remain");
@@ -527,7 +568,7 @@ mod tests {
         cfg.purge_after_days = 0;
         cfg.conversation_retention_days = 30;
 
-        run_if_due(&cfg, workspace).unwrap();
+        run_if_due(&cfg, workspace, || Ok(0)).unwrap();
 
         let mem2 = SqliteMemory::new(workspace).unwrap();
         assert!(
@@ -539,4 +580,25 @@ mod tests {
             "core memory should remain"
         );
     }
+
+    #[test]
+    fn purges_expired_entries_only_when_due() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+
+        let mut cfg = default_cfg();
+        cfg.archive_after_days = 0;
+        cfg.purge_after_days = 0;
+        cfg.conversation_retention_days = 0;
+
+        run_if_due(&cfg, workspace, || Ok(3)).unwrap();
+        let state: HygieneState =
+            serde_json::from_slice(&fs::read(state_path(workspace)).unwrap()).unwrap();
+        assert_eq!(state.last_report.purged_expired_memories, 3);
+
+        run_if_due(&cfg, workspace, || {
+            panic!("purge must wait for the next window")
+        })
+        .unwrap();
+    }
 }
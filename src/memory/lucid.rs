@@ -1,5 +1,6 @@
+use super::namespace::RecallScope;
 use super::sqlite::SqliteMemory;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord};
 use async_trait::async_trait;
 use chrono::Local;
 use parking_lot::Mutex;
@@ -226,6 +227,7 @@ impl LucidMemory {
                 timestamp: now.clone(),
                 session_id: None,
                 score: Some((1.0 - rank as f64 * 0.05).max(0.1)),
+                metadata: MemoryMetadata::default(),
             });
         }
 
@@ -320,6 +322,54 @@ impl Memory for LucidMemory {
         Ok(())
     }
 
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.local
+            .store_with_metadata(key, content, category.clone(), session_id, metadata)
+            .await?;
+        self.sync_to_lucid_async(key, content, &category).await;
+        Ok(())
+    }
+
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        // Lucid context carries no tags, so tag-filtered recall stays local.
+        self.local
+            .recall_tagged(query, limit, session_id, tags)
+            .await
+    }
+
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if *scope == RecallScope::All {
+            return self.recall_tagged(query, limit, session_id, tags).await;
+        }
+        // Lucid context carries no namespaces either, so scoped recall stays local.
+        self.local
+            .recall_scoped(scope, query, limit, session_id, tags)
+            .await
+    }
+
     async fn recall(
         &self,
         query: &str,
@@ -379,6 +429,18 @@ impl Memory for LucidMemory {
         self.local.count().await
     }
 
+    async fn export_records(&self) -> anyhow::Result<Vec<MemoryRecord>> {
+        self.local.export_records().await
+    }
+
+    async fn import_record(&self, record: MemoryRecord) -> anyhow::Result<()> {
+        self.local.import_record(record).await
+    }
+
+    async fn purge_expired(&self) -> anyhow::Result<usize> {
+        self.local.purge_expired().await
+    }
+
     async fn health_check(&self) -> bool {
         self.local.health_check().await
     }
@@ -1,14 +1,22 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
 use async_trait::async_trait;
 use chrono::Local;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// Marker for the inline HTML comment carrying an entry's metadata.
+const META_MARKER: &str = "<!-- zc-meta ";
+
 /// Markdown-based memory — plain files as source of truth
 ///
 /// Layout:
 ///   workspace/MEMORY.md          — curated long-term memory (core)
 ///   workspace/memory/YYYY-MM-DD.md — daily logs (append-only)
+///
+/// Tags, provenance and expiry ride along as a trailing
+/// `<!-- zc-meta {...} -->` comment so the files still render cleanly.
+/// Expired entries are hidden on read and dropped from the files by
+/// `purge_expired`.
 pub struct MarkdownMemory {
     workspace_dir: PathBuf,
 }
@@ -63,6 +71,20 @@ impl MarkdownMemory {
         Ok(())
     }
 
+    /// Split a line into its visible text and the metadata in its trailing comment.
+    fn split_metadata(line: &str) -> (&str, MemoryMetadata) {
+        let Some(body) = line.strip_suffix("-->") else {
+            return (line, MemoryMetadata::default());
+        };
+        let Some(start) = body.rfind(META_MARKER) else {
+            return (line, MemoryMetadata::default());
+        };
+        match serde_json::from_str(body[start + META_MARKER.len()..].trim()) {
+            Ok(metadata) => (body[..start].trim_end(), metadata),
+            Err(_) => (line, MemoryMetadata::default()),
+        }
+    }
+
     fn parse_entries_from_file(
         path: &Path,
         content: &str,
@@ -81,8 +103,8 @@ impl MarkdownMemory {
             })
             .enumerate()
             .map(|(i, line)| {
-                let trimmed = line.trim();
-                let clean = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+                let (text, metadata) = Self::split_metadata(line.trim());
+                let clean = text.strip_prefix("- ").unwrap_or(text);
                 MemoryEntry {
                     id: format!("{filename}:{i}"),
                     key: format!("{filename}:{i}"),
@@ -91,41 +113,47 @@ impl MarkdownMemory {
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    metadata,
                 }
             })
+            .filter(|entry| !entry.metadata.is_expired())
             .collect()
     }
 
-    async fn read_all_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
-        let mut entries = Vec::new();
-
-        // Read MEMORY.md (core)
+    /// Every markdown file backing this memory: MEMORY.md plus the daily logs.
+    async fn memory_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
         let core_path = self.core_path();
         if core_path.exists() {
-            let content = fs::read_to_string(&core_path).await?;
-            entries.extend(Self::parse_entries_from_file(
-                &core_path,
-                &content,
-                &MemoryCategory::Core,
-            ));
+            files.push(core_path);
         }
 
-        // Read daily logs
         let mem_dir = self.memory_dir();
         if mem_dir.exists() {
             let mut dir = fs::read_dir(&mem_dir).await?;
             while let Some(entry) = dir.next_entry().await? {
                 let path = entry.path();
                 if path.extension().and_then(|e| e.to_str()) == Some("md") {
-                    let content = fs::read_to_string(&path).await?;
-                    entries.extend(Self::parse_entries_from_file(
-                        &path,
-                        &content,
-                        &MemoryCategory::Daily,
-                    ));
+                    files.push(path);
                 }
             }
         }
+        Ok(files)
+    }
+
+    async fn read_all_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut entries = Vec::new();
+
+        let core_path = self.core_path();
+        for path in self.memory_files().await? {
+            let category = if path == core_path {
+                MemoryCategory::Core
+            } else {
+                MemoryCategory::Daily
+            };
+            let content = fs::read_to_string(&path).await?;
+            entries.extend(Self::parse_entries_from_file(&path, &content, &category));
+        }
 
         entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
         Ok(entries)
@@ -139,13 +167,37 @@ impl Memory for MarkdownMemory {
     }
 
     async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         _session_id: Option<&str>,
+        metadata: MemoryMetadata,
     ) -> anyhow::Result<()> {
-        let entry = format!("- **{key}**: {content}");
+        let mut entry = format!("- **{key}**: {content}");
+        if !metadata.is_empty() {
+            entry.push(' ');
+            entry.push_str(META_MARKER);
+            entry.push_str(&serde_json::to_string(&metadata)?);
+            entry.push_str(" -->");
+        }
         let path = match category {
             MemoryCategory::Core => self.core_path(),
             _ => self.daily_path(),
@@ -216,6 +268,28 @@ impl Memory for MarkdownMemory {
         Ok(false)
     }
 
+    async fn purge_expired(&self) -> anyhow::Result<usize> {
+        let mut purged = 0;
+        for path in self.memory_files().await? {
+            let content = fs::read_to_string(&path).await?;
+            let mut kept = String::with_capacity(content.len());
+            let mut removed = 0;
+            for line in content.lines() {
+                if Self::split_metadata(line.trim()).1.is_expired() {
+                    removed += 1;
+                } else {
+                    kept.push_str(line);
+                    kept.push('\n');
+                }
+            }
+            if removed > 0 {
+                fs::write(&path, kept).await?;
+                purged += removed;
+            }
+        }
+        Ok(purged)
+    }
+
     async fn count(&self) -> anyhow::Result<usize> {
         let all = self.read_all_entries().await?;
         Ok(all.len())
@@ -347,6 +421,83 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[tokio::test]
+    async fn markdown_metadata_roundtrips_and_filters_tags() {
+        let (_tmp, mem) = temp_workspace();
+        let metadata = MemoryMetadata::default().with_tags(["work"]);
+        mem.store_with_metadata("a", "Standup at 9", MemoryCategory::Core, None, metadata)
+            .await
+            .unwrap();
+        mem.store("b", "Standup notes are shared", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let raw = fs::read_to_string(mem.core_path()).await.unwrap();
+        assert!(raw.contains("<!-- zc-meta {\"tags\":[\"work\"]} -->"));
+
+        let tagged = mem
+            .recall_tagged("standup", 10, None, &["work".into()])
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].content, "**a**: Standup at 9");
+        assert_eq!(tagged[0].metadata.tags, vec!["work"]);
+    }
+
+    #[tokio::test]
+    async fn markdown_hides_expired_entries() {
+        let (_tmp, mem) = temp_workspace();
+        let expired = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("a", "old code 1234", MemoryCategory::Core, None, expired)
+            .await
+            .unwrap();
+        mem.store("b", "current code 5678", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let results = mem.recall("code", 10, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("5678"));
+        assert_eq!(mem.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn markdown_purge_expired_rewrites_files() {
+        let (_tmp, mem) = temp_workspace();
+        let expired = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "a",
+            "old code 1234",
+            MemoryCategory::Core,
+            None,
+            expired.clone(),
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata("b", "stale note", MemoryCategory::Daily, None, expired)
+            .await
+            .unwrap();
+        mem.store("c", "current code 5678", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        assert_eq!(mem.purge_expired().await.unwrap(), 2);
+
+        let core = fs::read_to_string(mem.core_path()).await.unwrap();
+        assert!(!core.contains("1234"));
+        assert!(core.contains("# Long-Term Memory"));
+        assert!(core.contains("5678"));
+        let daily = fs::read_to_string(mem.daily_path()).await.unwrap();
+        assert!(!daily.contains("stale note"));
+        assert_eq!(mem.purge_expired().await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn markdown_empty_count() {
         let (_tmp, mem) = temp_workspace();
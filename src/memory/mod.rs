@@ -1,14 +1,19 @@
+pub mod archive;
 pub mod backend;
 pub mod chunker;
 pub mod cli;
+pub mod consolidation;
 pub mod embeddings;
+pub mod graph;
 pub mod hygiene;
 pub mod lucid;
 pub mod markdown;
+pub mod namespace;
 pub mod none;
 #[cfg(feature = "memory-postgres")]
 pub mod postgres;
 pub mod qdrant;
+pub mod ranking;
 pub mod response_cache;
 pub mod snapshot;
 pub mod sqlite;
@@ -20,6 +25,7 @@ pub use backend::{
     classify_memory_backend, default_memory_backend_key, memory_backend_profile,
     selectable_memory_backends, MemoryBackendKind, MemoryBackendProfile,
 };
+pub use graph::GraphMemory;
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
 pub use none::NoneMemory;
@@ -30,23 +36,30 @@ pub use response_cache::ResponseCache;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{
+    MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord, MemorySource, StoredEmbedding,
+};
 
-use crate::config::{EmbeddingRouteConfig, MemoryConfig, StorageProviderConfig};
+use crate::config::{
+    Config, EmbeddingRouteConfig, MemoryConfig, MemoryGraphConfig, StorageProviderConfig,
+};
 use anyhow::Context;
 use std::path::Path;
 use std::sync::Arc;
 
-fn create_memory_with_builders<F, G>(
+fn create_memory_with_builders<F, G, Q>(
     backend_name: &str,
     workspace_dir: &Path,
+    graph_config: &MemoryGraphConfig,
     mut sqlite_builder: F,
     mut postgres_builder: G,
+    mut qdrant_builder: Q,
     unknown_context: &str,
 ) -> anyhow::Result<Box<dyn Memory>>
 where
     F: FnMut() -> anyhow::Result<SqliteMemory>,
     G: FnMut() -> anyhow::Result<Box<dyn Memory>>,
+    Q: FnMut() -> anyhow::Result<QdrantMemory>,
 {
     match classify_memory_backend(backend_name) {
         MemoryBackendKind::Sqlite => Ok(Box::new(sqlite_builder()?)),
@@ -54,10 +67,17 @@ where
             let local = sqlite_builder()?;
             Ok(Box::new(LucidMemory::new(workspace_dir, local)))
         }
-        MemoryBackendKind::Postgres => postgres_builder(),
-        MemoryBackendKind::Qdrant | MemoryBackendKind::Markdown => {
-            Ok(Box::new(MarkdownMemory::new(workspace_dir)))
+        MemoryBackendKind::Graph => {
+            let local = sqlite_builder()?;
+            Ok(Box::new(GraphMemory::new(
+                workspace_dir,
+                local,
+                graph_config.clone(),
+            )?))
         }
+        MemoryBackendKind::Postgres => postgres_builder(),
+        MemoryBackendKind::Qdrant => Ok(Box::new(qdrant_builder()?)),
+        MemoryBackendKind::Markdown => Ok(Box::new(MarkdownMemory::new(workspace_dir))),
         MemoryBackendKind::None => Ok(Box::new(NoneMemory::new())),
         MemoryBackendKind::Unknown => {
             tracing::warn!(
@@ -169,6 +189,105 @@ fn resolve_embedding_config(
     }
 }
 
+fn build_embedder(
+    resolved: &ResolvedEmbeddingConfig,
+) -> anyhow::Result<Arc<dyn embeddings::EmbeddingProvider>> {
+    Ok(Arc::from(embeddings::create_embedding_provider(
+        &resolved.provider,
+        resolved.api_key.as_deref(),
+        &resolved.model,
+        resolved.dimensions,
+    )?))
+}
+
+fn build_sqlite_memory(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    resolved_embedding: &ResolvedEmbeddingConfig,
+) -> anyhow::Result<SqliteMemory> {
+    let embedder = build_embedder(resolved_embedding)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mem = SqliteMemory::with_embedder(
+        workspace_dir,
+        embedder,
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+        config.embedding_cache_size,
+        config.sqlite_open_timeout_secs,
+    )?;
+    Ok(mem)
+}
+
+/// Open the workspace SQLite store with the configured embedding provider.
+///
+/// Used for maintenance such as `zeroclaw memory reindex`, which needs the
+/// concrete [`SqliteMemory`] rather than a boxed backend.
+pub fn create_sqlite_memory_with_embedder(config: &Config) -> anyhow::Result<SqliteMemory> {
+    let resolved = resolve_embedding_config(
+        &config.memory,
+        &config.embedding_routes,
+        config.api_key.as_deref(),
+    );
+    build_sqlite_memory(&config.memory, &config.workspace_dir, &resolved)
+}
+
+/// Build the configured `[memory]` embedding provider (honouring embedding routes).
+pub fn create_embedder(config: &Config) -> anyhow::Result<Arc<dyn embeddings::EmbeddingProvider>> {
+    let resolved = resolve_embedding_config(
+        &config.memory,
+        &config.embedding_routes,
+        config.api_key.as_deref(),
+    );
+    build_embedder(&resolved)
+}
+
+/// Resolve `[memory.qdrant]` settings (with `QDRANT_*` env fallbacks) into a
+/// lazily-initialized [`QdrantMemory`].
+fn build_qdrant_memory(
+    config: &MemoryConfig,
+    embedder: Arc<dyn embeddings::EmbeddingProvider>,
+) -> anyhow::Result<QdrantMemory> {
+    let url = qdrant_url(config)
+        .context("Qdrant memory backend requires url in [memory.qdrant] or QDRANT_URL env var")?;
+    let collection = std::env::var("QDRANT_COLLECTION")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| config.qdrant.collection.clone());
+    let qdrant_api_key = config
+        .qdrant
+        .api_key
+        .clone()
+        .or_else(|| std::env::var("QDRANT_API_KEY").ok())
+        .filter(|s| !s.trim().is_empty());
+    tracing::info!(
+        "📦 Qdrant memory backend configured (url: {}, collection: {})",
+        url,
+        collection
+    );
+
+    #[allow(clippy::cast_possible_truncation)]
+    let memory = QdrantMemory::new_lazy(&url, &collection, qdrant_api_key, embedder)
+        .with_hybrid_weights(config.vector_weight as f32, config.keyword_weight as f32);
+    Ok(memory)
+}
+
+/// Qdrant URL from `[memory.qdrant].url`, falling back to `QDRANT_URL`.
+pub fn qdrant_url(config: &MemoryConfig) -> Option<String> {
+    resolve_qdrant_url(config, std::env::var("QDRANT_URL").ok())
+}
+
+/// Qdrant URL from `[memory.qdrant].url`, falling back to `env_url`.
+pub(crate) fn resolve_qdrant_url(config: &MemoryConfig, env_url: Option<String>) -> Option<String> {
+    config
+        .qdrant
+        .url
+        .clone()
+        .filter(|s| !s.trim().is_empty())
+        .or(env_url)
+        .filter(|s| !s.trim().is_empty())
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
@@ -200,17 +319,12 @@ pub fn create_memory_with_storage_and_routes(
     let backend_kind = classify_memory_backend(&backend_name);
     let resolved_embedding = resolve_embedding_config(config, embedding_routes, api_key);
 
-    // Best-effort memory hygiene/retention pass (throttled by state file).
-    if let Err(e) = hygiene::run_if_due(config, workspace_dir) {
-        tracing::warn!("memory hygiene skipped: {e}");
-    }
-
     // If snapshot_on_hygiene is enabled, export core memories during hygiene.
     if config.snapshot_enabled
         && config.snapshot_on_hygiene
         && matches!(
             backend_kind,
-            MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+            MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid | MemoryBackendKind::Graph
         )
     {
         if let Err(e) = snapshot::export_snapshot(workspace_dir) {
@@ -223,7 +337,7 @@ pub fn create_memory_with_storage_and_routes(
     if config.auto_hydrate
         && matches!(
             backend_kind,
-            MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+            MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid | MemoryBackendKind::Graph
         )
         && snapshot::should_hydrate(workspace_dir)
     {
@@ -240,31 +354,6 @@ pub fn create_memory_with_storage_and_routes(
         }
     }
 
-    fn build_sqlite_memory(
-        config: &MemoryConfig,
-        workspace_dir: &Path,
-        resolved_embedding: &ResolvedEmbeddingConfig,
-    ) -> anyhow::Result<SqliteMemory> {
-        let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
-
-        #[allow(clippy::cast_possible_truncation)]
-        let mem = SqliteMemory::with_embedder(
-            workspace_dir,
-            embedder,
-            config.vector_weight as f32,
-            config.keyword_weight as f32,
-            config.embedding_cache_size,
-            config.sqlite_open_timeout_secs,
-        )?;
-        Ok(mem)
-    }
-
     #[cfg(feature = "memory-postgres")]
     fn build_postgres_memory(
         storage_provider: Option<&StorageProviderConfig>,
@@ -299,63 +388,64 @@ pub fn create_memory_with_storage_and_routes(
         );
     }
 
-    if matches!(backend_kind, MemoryBackendKind::Qdrant) {
-        let url = config
-            .qdrant
-            .url
-            .clone()
-            .filter(|s| !s.trim().is_empty())
-            .or_else(|| std::env::var("QDRANT_URL").ok())
-            .filter(|s| !s.trim().is_empty())
-            .context(
-                "Qdrant memory backend requires url in [memory.qdrant] or QDRANT_URL env var",
-            )?;
-        let collection = std::env::var("QDRANT_COLLECTION")
-            .ok()
-            .filter(|s| !s.trim().is_empty())
-            .unwrap_or_else(|| config.qdrant.collection.clone());
-        let qdrant_api_key = config
-            .qdrant
-            .api_key
-            .clone()
-            .or_else(|| std::env::var("QDRANT_API_KEY").ok())
-            .filter(|s| !s.trim().is_empty());
-        let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
-        tracing::info!(
-            "📦 Qdrant memory backend configured (url: {}, collection: {})",
-            url,
-            collection
-        );
-        return Ok(Box::new(QdrantMemory::new_lazy(
-            &url,
-            &collection,
-            qdrant_api_key,
-            embedder,
-        )));
+    let build_backend = || {
+        create_memory_with_builders(
+            &backend_name,
+            workspace_dir,
+            &config.graph,
+            || build_sqlite_memory(config, workspace_dir, &resolved_embedding),
+            || build_postgres_memory(storage_provider),
+            || build_qdrant_memory(config, build_embedder(&resolved_embedding)?),
+            "",
+        )
+    };
+
+    // Best-effort memory hygiene/retention pass (throttled by state file).
+    if let Err(e) = hygiene::run_if_due(config, workspace_dir, || {
+        purge_expired_blocking(build_backend)
+    }) {
+        tracing::warn!("memory hygiene skipped: {e}");
     }
 
-    create_memory_with_builders(
-        &backend_name,
-        workspace_dir,
-        || build_sqlite_memory(config, workspace_dir, &resolved_embedding),
-        || build_postgres_memory(storage_provider),
-        "",
-    )
+    build_backend()
+}
+
+/// Run a backend's TTL purge to completion from synchronous code.
+///
+/// The factory is called both inside and outside tokio runtimes, so the purge
+/// gets its own thread and current-thread runtime. `build` must return a fresh
+/// backend so connections bound to that short-lived runtime never leak into
+/// the instance handed back to the caller.
+fn purge_expired_blocking(
+    build: impl FnOnce() -> anyhow::Result<Box<dyn Memory>> + Send,
+) -> anyhow::Result<usize> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let memory = build()?;
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?;
+                runtime.block_on(memory.purge_expired())
+            })
+            .join()
+            .map_err(|_| anyhow::anyhow!("expired memory purge panicked"))?
+    })
 }
 
+/// Factory used by migration and `zeroclaw memory` management commands.
+///
+/// `backend` is the already-resolved backend name (see
+/// [`effective_memory_backend_name`]); the rest comes from `config`.
 pub fn create_memory_for_migration(
     backend: &str,
-    workspace_dir: &Path,
+    config: &Config,
 ) -> anyhow::Result<Box<dyn Memory>> {
+    let workspace_dir = config.workspace_dir.as_path();
+
     if matches!(classify_memory_backend(backend), MemoryBackendKind::None) {
         anyhow::bail!(
-            "memory backend 'none' disables persistence; choose sqlite, lucid, or markdown before migration"
+            "memory backend 'none' disables persistence; choose sqlite, lucid, qdrant, or markdown before migration"
         );
     }
 
@@ -371,8 +461,17 @@ pub fn create_memory_for_migration(
     create_memory_with_builders(
         backend,
         workspace_dir,
+        &config.memory.graph,
         || SqliteMemory::new(workspace_dir),
         || anyhow::bail!("postgres backend is not available in migration context"),
+        || {
+            let resolved = resolve_embedding_config(
+                &config.memory,
+                &config.embedding_routes,
+                config.api_key.as_deref(),
+            );
+            build_qdrant_memory(&config.memory, build_embedder(&resolved)?)
+        },
         " during migration",
     )
 }
@@ -420,6 +519,49 @@ mod tests {
         assert_eq!(mem.name(), "sqlite");
     }
 
+    #[tokio::test]
+    async fn factory_hygiene_purges_expired_entries_from_backend() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let expired = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "otp",
+            "one-time code 4821",
+            MemoryCategory::Core,
+            None,
+            expired,
+        )
+        .await
+        .unwrap();
+        mem.store("core_keep", "durable", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let cfg = MemoryConfig {
+            backend: "sqlite".into(),
+            ..MemoryConfig::default()
+        };
+        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        assert_eq!(
+            mem.purge_expired().await.unwrap(),
+            0,
+            "startup pass already purged"
+        );
+
+        let conn = rusqlite::Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(
+            remaining, 1,
+            "expired row should be deleted, not just hidden"
+        );
+    }
+
     #[test]
     fn assistant_autosave_key_detection_matches_legacy_patterns() {
         assert!(is_assistant_autosave_key("assistant_resp"));
@@ -451,6 +593,18 @@ mod tests {
         assert_eq!(mem.name(), "lucid");
     }
 
+    #[test]
+    fn factory_graph() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = MemoryConfig {
+            backend: "graph".into(),
+            ..MemoryConfig::default()
+        };
+        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        assert_eq!(mem.name(), "graph");
+        assert!(tmp.path().join("memory").join("graph.db").exists());
+    }
+
     #[test]
     fn factory_none_uses_noop_memory() {
         let tmp = TempDir::new().unwrap();
@@ -473,17 +627,48 @@ mod tests {
         assert_eq!(mem.name(), "markdown");
     }
 
+    #[test]
+    fn factory_qdrant_uses_native_backend() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = MemoryConfig {
+            backend: "qdrant".into(),
+            qdrant: crate::config::QdrantConfig {
+                url: Some("http://127.0.0.1:6333".into()),
+                ..crate::config::QdrantConfig::default()
+            },
+            ..MemoryConfig::default()
+        };
+        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        assert_eq!(mem.name(), "qdrant");
+    }
+
+    fn migration_config(workspace_dir: &Path) -> Config {
+        Config {
+            workspace_dir: workspace_dir.to_path_buf(),
+            ..Config::default()
+        }
+    }
+
     #[test]
     fn migration_factory_lucid() {
         let tmp = TempDir::new().unwrap();
-        let mem = create_memory_for_migration("lucid", tmp.path()).unwrap();
+        let mem = create_memory_for_migration("lucid", &migration_config(tmp.path())).unwrap();
         assert_eq!(mem.name(), "lucid");
     }
 
+    #[test]
+    fn migration_factory_qdrant_uses_native_backend() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = migration_config(tmp.path());
+        config.memory.qdrant.url = Some("http://127.0.0.1:6333".into());
+        let mem = create_memory_for_migration("qdrant", &config).unwrap();
+        assert_eq!(mem.name(), "qdrant");
+    }
+
     #[test]
     fn migration_factory_none_is_rejected() {
         let tmp = TempDir::new().unwrap();
-        let error = create_memory_for_migration("none", tmp.path())
+        let error = create_memory_for_migration("none", &migration_config(tmp.path()))
             .err()
             .expect("backend=none should be rejected for migration");
         assert!(error.to_string().contains("disables persistence"));
@@ -0,0 +1,403 @@
+//! Memory namespaces for multi-tenant daemons.
+//!
+//! Every entry belongs to one namespace: the workspace-wide `shared` scope
+//! (the default, and where all pre-namespace entries live), a whole channel
+//! (`channel:<name>`), or a single sender on a channel
+//! (`sender:<channel>:<sender>`). The namespace is recorded in the entry's
+//! metadata and prefixed onto its key, so two senders can both store
+//! `favorite_color` without overwriting each other.
+//!
+//! The channel runtime decides the namespace per message from
+//! `[memory.namespaces]` and exposes it to the `memory_*` tools through a
+//! task-local [`NamespaceContext`]; code running outside a channel message
+//! (CLI agent, gateway) sees no context and keeps unscoped behaviour.
+
+use super::traits::MemoryEntry;
+use crate::config::{MemoryIsolation, MemoryNamespaceConfig};
+use anyhow::{bail, Result};
+use std::future::Future;
+
+/// Name of the workspace-wide namespace.
+pub const SHARED_NAMESPACE: &str = "shared";
+
+/// Separator between a namespace and the caller's key in stored keys.
+const KEY_SEPARATOR: &str = "/";
+
+tokio::task_local! {
+    static CURRENT: NamespaceContext;
+}
+
+/// A memory namespace: `shared`, `channel:<name>` or `sender:<channel>:<sender>`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemoryNamespace(String);
+
+impl MemoryNamespace {
+    pub fn shared() -> Self {
+        Self(SHARED_NAMESPACE.into())
+    }
+
+    pub fn channel(channel: &str) -> Self {
+        Self(format!("channel:{}", encode_component(channel)))
+    }
+
+    pub fn sender(channel: &str, sender: &str) -> Self {
+        Self(format!(
+            "sender:{}:{}",
+            encode_component(channel),
+            encode_component(sender)
+        ))
+    }
+
+    /// Namespace for a message from `sender` on `channel` under `isolation`.
+    pub fn for_message(isolation: MemoryIsolation, channel: &str, sender: &str) -> Self {
+        match isolation {
+            MemoryIsolation::None => Self::shared(),
+            MemoryIsolation::Channel => Self::channel(channel),
+            MemoryIsolation::Sender => Self::sender(channel, sender),
+        }
+    }
+
+    /// Parse a namespace string as stored in entry metadata.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let valid = raw == SHARED_NAMESPACE
+            || raw
+                .strip_prefix("channel:")
+                .is_some_and(|rest| !rest.is_empty() && !rest.contains(':'))
+            || raw
+                .strip_prefix("sender:")
+                .and_then(|rest| rest.split_once(':'))
+                .is_some_and(|(channel, sender)| !channel.is_empty() && !sender.is_empty());
+        if !valid {
+            bail!("Invalid memory namespace '{raw}' (expected shared, channel:<name> or sender:<channel>:<id>)");
+        }
+        Ok(Self(raw.to_string()))
+    }
+
+    /// Namespace of a stored entry; entries without one are shared.
+    pub fn of(entry: &MemoryEntry) -> Self {
+        entry
+            .metadata
+            .namespace
+            .as_deref()
+            .and_then(|raw| Self::parse(raw).ok())
+            .unwrap_or_else(Self::shared)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.0 == SHARED_NAMESPACE
+    }
+
+    /// Value recorded in [`MemoryMetadata::namespace`](super::MemoryMetadata::namespace).
+    pub fn to_metadata(&self) -> Option<String> {
+        (!self.is_shared()).then(|| self.0.clone())
+    }
+
+    /// Storage key for `key` in this namespace. Shared keys are unchanged.
+    pub fn qualify_key(&self, key: &str) -> String {
+        if self.is_shared() {
+            key.to_string()
+        } else {
+            format!("{}{KEY_SEPARATOR}{key}", self.0)
+        }
+    }
+
+    /// The caller-facing key of a stored key in this namespace.
+    pub fn display_key<'a>(&self, stored_key: &'a str) -> &'a str {
+        if self.is_shared() {
+            return stored_key;
+        }
+        stored_key
+            .strip_prefix(self.0.as_str())
+            .and_then(|rest| rest.strip_prefix(KEY_SEPARATOR))
+            .unwrap_or(stored_key)
+    }
+}
+
+impl std::fmt::Display for MemoryNamespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Percent-encode the `:` and `/` separators (and `%` itself) so distinct
+/// components always map to distinct namespaces.
+fn encode_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for c in component.chars() {
+        match c {
+            '%' => encoded.push_str("%25"),
+            ':' => encoded.push_str("%3A"),
+            '/' => encoded.push_str("%2F"),
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// Which namespaces a recall may return entries from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecallScope {
+    /// Every namespace (CLI, gateway, or explicitly allowed cross-namespace recall).
+    All,
+    /// One namespace, optionally together with the shared scope.
+    Namespace {
+        namespace: MemoryNamespace,
+        include_shared: bool,
+    },
+}
+
+impl RecallScope {
+    /// Recall scope for `namespace` under the configured policy.
+    pub fn for_namespace(namespace: MemoryNamespace, policy: &MemoryNamespaceConfig) -> Self {
+        Self::Namespace {
+            namespace,
+            include_shared: policy.recall_shared,
+        }
+    }
+
+    pub fn allows(&self, entry: &MemoryEntry) -> bool {
+        self.allows_namespace(&MemoryNamespace::of(entry))
+    }
+
+    /// Filter for backends that scope recall inside their query: `None`
+    /// admits every namespace, otherwise the stored namespace value (`None`
+    /// for shared) and whether shared entries are admitted as well.
+    pub fn namespace_filter(&self) -> Option<(Option<String>, bool)> {
+        match self {
+            Self::All => None,
+            Self::Namespace {
+                namespace,
+                include_shared,
+            } => Some((namespace.to_metadata(), *include_shared)),
+        }
+    }
+
+    pub fn allows_namespace(&self, candidate: &MemoryNamespace) -> bool {
+        match self {
+            Self::All => true,
+            Self::Namespace {
+                namespace,
+                include_shared,
+            } => candidate == namespace || (*include_shared && candidate.is_shared()),
+        }
+    }
+}
+
+/// Namespace and policy for the message currently being handled.
+#[derive(Debug, Clone)]
+pub struct NamespaceContext {
+    pub namespace: MemoryNamespace,
+    pub policy: MemoryNamespaceConfig,
+}
+
+impl NamespaceContext {
+    /// Default recall scope for tools acting on behalf of this sender.
+    pub fn recall_scope(&self) -> RecallScope {
+        RecallScope::for_namespace(self.namespace.clone(), &self.policy)
+    }
+}
+
+/// Namespace a tool write with the given `scope` argument (`own` or `shared`) targets.
+///
+/// Without a namespace context every write goes to the shared namespace.
+pub fn resolve_write_scope(scope: Option<&str>) -> Result<MemoryNamespace> {
+    let context = current();
+    match (scope.unwrap_or("own"), context) {
+        ("own" | "shared", None) => Ok(MemoryNamespace::shared()),
+        ("own", Some(context)) => Ok(context.namespace),
+        ("shared", Some(context)) => {
+            if context.namespace.is_shared() || context.policy.allow_shared_writes {
+                Ok(MemoryNamespace::shared())
+            } else {
+                bail!("Writing to the shared memory namespace is disabled (memory.namespaces.allow_shared_writes)")
+            }
+        }
+        (other, _) => bail!("Unknown memory scope '{other}' (expected 'own' or 'shared')"),
+    }
+}
+
+/// Recall scope for a tool `scope` argument (`own`, `shared` or `all`).
+///
+/// Without a namespace context recall is unrestricted.
+pub fn resolve_recall_scope(scope: Option<&str>) -> Result<RecallScope> {
+    let context = current();
+    match (scope.unwrap_or("own"), context) {
+        ("own" | "all", None) => Ok(RecallScope::All),
+        ("own", Some(context)) => Ok(context.recall_scope()),
+        ("shared", None) => Ok(RecallScope::Namespace {
+            namespace: MemoryNamespace::shared(),
+            include_shared: true,
+        }),
+        ("shared", Some(context)) => {
+            if context.namespace.is_shared() || context.policy.recall_shared {
+                Ok(RecallScope::Namespace {
+                    namespace: MemoryNamespace::shared(),
+                    include_shared: true,
+                })
+            } else {
+                bail!("Recalling from the shared memory namespace is disabled (memory.namespaces.recall_shared)")
+            }
+        }
+        ("all", Some(context)) => {
+            if context.policy.allow_cross_namespace_recall {
+                Ok(RecallScope::All)
+            } else {
+                bail!("Cross-namespace recall is disabled (memory.namespaces.allow_cross_namespace_recall)")
+            }
+        }
+        (other, _) => {
+            bail!("Unknown memory scope '{other}' (expected 'own', 'shared' or 'all')")
+        }
+    }
+}
+
+/// Run `future` with `context` visible to the `memory_*` tools.
+pub async fn with_namespace<F: Future>(context: NamespaceContext, future: F) -> F::Output {
+    CURRENT.scope(context, future).await
+}
+
+/// The namespace context of the current task, if a channel runtime set one.
+pub fn current() -> Option<NamespaceContext> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, MemoryMetadata};
+
+    fn entry_in(namespace: &MemoryNamespace) -> MemoryEntry {
+        MemoryEntry {
+            id: "1".into(),
+            key: namespace.qualify_key("k"),
+            content: "v".into(),
+            category: MemoryCategory::Core,
+            timestamp: "2026-01-01T00:00:00Z".into(),
+            session_id: None,
+            score: None,
+            metadata: MemoryMetadata {
+                namespace: namespace.to_metadata(),
+                ..MemoryMetadata::default()
+            },
+        }
+    }
+
+    #[test]
+    fn namespaces_format_parse_and_qualify_keys() {
+        let ns = MemoryNamespace::sender("telegram", "alice:1");
+        assert_eq!(ns.as_str(), "sender:telegram:alice%3A1");
+        assert_eq!(MemoryNamespace::parse(ns.as_str()).unwrap(), ns);
+        assert_eq!(ns.qualify_key("color"), "sender:telegram:alice%3A1/color");
+        assert_eq!(ns.display_key("sender:telegram:alice%3A1/color"), "color");
+        assert_eq!(MemoryNamespace::shared().qualify_key("color"), "color");
+        assert!(MemoryNamespace::shared().to_metadata().is_none());
+        assert!(MemoryNamespace::parse("sender:telegram").is_err());
+        assert!(MemoryNamespace::parse("tenant:x").is_err());
+    }
+
+    #[test]
+    fn separator_encoding_keeps_senders_distinct() {
+        let ids = ["a:b", "a/b", "a_b", "a%3Ab"];
+        let namespaces: std::collections::HashSet<_> = ids
+            .iter()
+            .map(|id| MemoryNamespace::sender("matrix", id))
+            .collect();
+        assert_eq!(namespaces.len(), ids.len());
+        assert_ne!(
+            MemoryNamespace::channel("a:b"),
+            MemoryNamespace::channel("a_b")
+        );
+    }
+
+    #[test]
+    fn for_message_follows_isolation_level() {
+        assert!(MemoryNamespace::for_message(MemoryIsolation::None, "discord", "bob").is_shared());
+        assert_eq!(
+            MemoryNamespace::for_message(MemoryIsolation::Channel, "discord", "bob").as_str(),
+            "channel:discord"
+        );
+        assert_eq!(
+            MemoryNamespace::for_message(MemoryIsolation::Sender, "discord", "bob").as_str(),
+            "sender:discord:bob"
+        );
+    }
+
+    #[test]
+    fn recall_scope_isolates_senders_and_optionally_includes_shared() {
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let bob = MemoryNamespace::sender("discord", "bob");
+        let shared = MemoryNamespace::shared();
+
+        let with_shared = RecallScope::Namespace {
+            namespace: alice.clone(),
+            include_shared: true,
+        };
+        assert!(with_shared.allows(&entry_in(&alice)));
+        assert!(with_shared.allows(&entry_in(&shared)));
+        assert!(!with_shared.allows(&entry_in(&bob)));
+
+        let own_only = RecallScope::Namespace {
+            namespace: alice.clone(),
+            include_shared: false,
+        };
+        assert!(!own_only.allows(&entry_in(&shared)));
+        assert!(RecallScope::All.allows(&entry_in(&bob)));
+    }
+
+    #[tokio::test]
+    async fn tool_scopes_follow_policy() {
+        assert_eq!(resolve_recall_scope(None).unwrap(), RecallScope::All);
+        assert!(resolve_write_scope(Some("shared")).unwrap().is_shared());
+
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let context = NamespaceContext {
+            namespace: alice.clone(),
+            policy: MemoryNamespaceConfig::default(),
+        };
+        with_namespace(context, async {
+            assert_eq!(resolve_write_scope(None).unwrap(), alice);
+            assert!(resolve_write_scope(Some("shared")).is_err());
+            assert!(resolve_recall_scope(Some("all")).is_err());
+            assert!(resolve_recall_scope(Some("everything")).is_err());
+            assert_eq!(
+                resolve_recall_scope(Some("own")).unwrap(),
+                RecallScope::Namespace {
+                    namespace: alice.clone(),
+                    include_shared: true,
+                }
+            );
+        })
+        .await;
+
+        let permissive = NamespaceContext {
+            namespace: MemoryNamespace::channel("slack"),
+            policy: MemoryNamespaceConfig {
+                allow_shared_writes: true,
+                allow_cross_namespace_recall: true,
+                ..MemoryNamespaceConfig::default()
+            },
+        };
+        with_namespace(permissive, async {
+            assert!(resolve_write_scope(Some("shared")).unwrap().is_shared());
+            assert_eq!(resolve_recall_scope(Some("all")).unwrap(), RecallScope::All);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn context_is_visible_only_inside_scope() {
+        assert!(current().is_none());
+        let context = NamespaceContext {
+            namespace: MemoryNamespace::channel("slack"),
+            policy: MemoryNamespaceConfig::default(),
+        };
+        let seen = with_namespace(context, async { current().map(|c| c.namespace) }).await;
+        assert_eq!(seen, Some(MemoryNamespace::channel("slack")));
+        assert!(current().is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::namespace::RecallScope;
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -69,7 +70,9 @@ impl rustls::client::danger::ServerCertVerifier for NoCertVerifier {
 /// PostgreSQL-backed persistent memory.
 ///
 /// This backend focuses on reliable CRUD and keyword recall using SQL, without
-/// requiring extension setup (for example pgvector).
+/// requiring extension setup (for example pgvector). Tags live in a `TEXT[]`
+/// column so tag filters run in SQL; expired rows are hidden from reads and
+/// swept whenever the backend connects.
 pub struct PostgresMemory {
     client: Arc<Mutex<Client>>,
     qualified_table: String,
@@ -176,12 +179,34 @@ impl PostgresMemory {
             CREATE INDEX IF NOT EXISTS idx_memories_category ON {qualified_table}(category);
             CREATE INDEX IF NOT EXISTS idx_memories_session_id ON {qualified_table}(session_id);
             CREATE INDEX IF NOT EXISTS idx_memories_updated_at ON {qualified_table}(updated_at DESC);
+
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS metadata TEXT;
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{{}}';
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+            CREATE INDEX IF NOT EXISTS idx_memories_tags ON {qualified_table} USING GIN (tags);
+            CREATE INDEX IF NOT EXISTS idx_memories_expires_at ON {qualified_table}(expires_at);
+
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS namespace TEXT;
+            CREATE INDEX IF NOT EXISTS idx_memories_namespace ON {qualified_table}(namespace);
+            UPDATE {qualified_table} SET namespace = metadata::jsonb ->> 'namespace'
+             WHERE namespace IS NULL AND metadata LIKE '%\"namespace\"%';
             "
         ))?;
 
+        let purged = Self::purge_expired_rows(client, qualified_table)?;
+        if purged > 0 {
+            tracing::info!("PostgreSQL memory: purged {purged} expired entries");
+        }
+
         Ok(())
     }
 
+    fn purge_expired_rows(client: &mut Client, qualified_table: &str) -> Result<usize> {
+        let stmt = format!("DELETE FROM {qualified_table} WHERE expires_at <= NOW()");
+        let deleted = client.execute(&stmt, &[])?;
+        Ok(usize::try_from(deleted).unwrap_or(usize::MAX))
+    }
+
     fn category_to_str(category: &MemoryCategory) -> String {
         match category {
             MemoryCategory::Core => "core".to_string(),
@@ -202,6 +227,10 @@ impl PostgresMemory {
 
     fn row_to_entry(row: &Row) -> Result<MemoryEntry> {
         let timestamp: DateTime<Utc> = row.get(4);
+        let metadata = row
+            .get::<_, Option<String>>(6)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
 
         Ok(MemoryEntry {
             id: row.get(0),
@@ -210,50 +239,20 @@ impl PostgresMemory {
             category: Self::parse_category(&row.get::<_, String>(3)),
             timestamp: timestamp.to_rfc3339(),
             session_id: row.get(5),
-            score: row.try_get(6).ok(),
+            score: row.try_get("score").ok(),
+            metadata,
         })
     }
-}
-
-fn validate_identifier(value: &str, field_name: &str) -> Result<()> {
-    if value.is_empty() {
-        anyhow::bail!("{field_name} must not be empty");
-    }
-
-    let mut chars = value.chars();
-    let Some(first) = chars.next() else {
-        anyhow::bail!("{field_name} must not be empty");
-    };
-
-    if !(first.is_ascii_alphabetic() || first == '_') {
-        anyhow::bail!("{field_name} must start with an ASCII letter or underscore; got '{value}'");
-    }
-
-    if !chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_') {
-        anyhow::bail!(
-            "{field_name} can only contain ASCII letters, numbers, and underscores; got '{value}'"
-        );
-    }
-
-    Ok(())
-}
-
-fn quote_identifier(value: &str) -> String {
-    format!("\"{value}\"")
-}
 
-#[async_trait]
-impl Memory for PostgresMemory {
-    fn name(&self) -> &str {
-        "postgres"
-    }
-
-    async fn store(
+    /// Insert or replace by key, stamping the row with `timestamp`.
+    async fn upsert(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         session_id: Option<&str>,
+        metadata: MemoryMetadata,
+        timestamp: DateTime<Utc>,
     ) -> Result<()> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
@@ -261,47 +260,84 @@ impl Memory for PostgresMemory {
         let content = content.to_string();
         let category = Self::category_to_str(&category);
         let sid = session_id.map(str::to_string);
+        let tags = metadata.tags.clone();
+        let namespace = metadata.namespace.clone();
+        let expires_at: Option<DateTime<Utc>> = metadata
+            .expires_at
+            .as_deref()
+            .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+            .map(|at| at.with_timezone(&Utc));
+        let metadata_json = if metadata.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&metadata)?)
+        };
 
         tokio::task::spawn_blocking(move || -> Result<()> {
-            let now = Utc::now();
             let mut client = client.lock();
             let stmt = format!(
                 "
                 INSERT INTO {qualified_table}
-                    (id, key, content, category, created_at, updated_at, session_id)
+                    (id, key, content, category, created_at, updated_at, session_id,
+                     metadata, tags, expires_at, namespace)
                 VALUES
-                    ($1, $2, $3, $4, $5, $6, $7)
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
                 ON CONFLICT (key) DO UPDATE SET
                     content = EXCLUDED.content,
                     category = EXCLUDED.category,
                     updated_at = EXCLUDED.updated_at,
-                    session_id = EXCLUDED.session_id
+                    session_id = EXCLUDED.session_id,
+                    metadata = EXCLUDED.metadata,
+                    tags = EXCLUDED.tags,
+                    expires_at = EXCLUDED.expires_at,
+                    namespace = EXCLUDED.namespace
                 "
             );
 
             let id = Uuid::new_v4().to_string();
-            client.execute(&stmt, &[&id, &key, &content, &category, &now, &now, &sid])?;
+            client.execute(
+                &stmt,
+                &[
+                    &id,
+                    &key,
+                    &content,
+                    &category,
+                    &timestamp,
+                    &timestamp,
+                    &sid,
+                    &metadata_json,
+                    &tags,
+                    &expires_at,
+                    &namespace,
+                ],
+            )?;
             Ok(())
         })
         .await?
     }
 
-    async fn recall(
+    async fn recall_filtered(
         &self,
         query: &str,
         limit: usize,
         session_id: Option<&str>,
+        tags: &[String],
+        scope: &RecallScope,
     ) -> Result<Vec<MemoryEntry>> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
         let query = query.trim().to_string();
         let sid = session_id.map(str::to_string);
+        let tags = super::traits::normalize_tags(tags);
+        let filter = scope.namespace_filter();
+        let scoped = filter.is_some();
+        let (namespace, include_shared) = filter.unwrap_or_default();
 
         tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id,
+                SELECT id, key, content, category, created_at, session_id, metadata,
                        (
                          CASE WHEN key ILIKE '%' || $1 || '%' THEN 2.0 ELSE 0.0 END +
                          CASE WHEN content ILIKE '%' || $1 || '%' THEN 1.0 ELSE 0.0 END
@@ -309,6 +345,10 @@ impl Memory for PostgresMemory {
                 FROM {qualified_table}
                 WHERE ($2::TEXT IS NULL OR session_id = $2)
                   AND ($1 = '' OR key ILIKE '%' || $1 || '%' OR content ILIKE '%' || $1 || '%')
+                  AND (cardinality($4::TEXT[]) = 0 OR tags @> $4)
+                  AND (expires_at IS NULL OR expires_at > NOW())
+                  AND (NOT $5 OR namespace IS NOT DISTINCT FROM $6
+                       OR ($7 AND namespace IS NULL))
                 ORDER BY score DESC, updated_at DESC
                 LIMIT $3
                 "
@@ -317,7 +357,18 @@ impl Memory for PostgresMemory {
             #[allow(clippy::cast_possible_wrap)]
             let limit_i64 = limit as i64;
 
-            let rows = client.query(&stmt, &[&query, &sid, &limit_i64])?;
+            let rows = client.query(
+                &stmt,
+                &[
+                    &query,
+                    &sid,
+                    &limit_i64,
+                    &tags,
+                    &scoped,
+                    &namespace,
+                    &include_shared,
+                ],
+            )?;
             rows.iter()
                 .map(Self::row_to_entry)
                 .collect::<Result<Vec<MemoryEntry>>>()
@@ -325,60 +376,178 @@ impl Memory for PostgresMemory {
         .await?
     }
 
-    async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
+    async fn list_filtered(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
-        let key = key.to_string();
+        let category = category.map(Self::category_to_str);
+        let sid = session_id.map(str::to_string);
+        let tags = super::traits::normalize_tags(tags);
 
-        tokio::task::spawn_blocking(move || -> Result<Option<MemoryEntry>> {
+        tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id
+                SELECT id, key, content, category, created_at, session_id, metadata
                 FROM {qualified_table}
-                WHERE key = $1
-                LIMIT 1
+                WHERE ($1::TEXT IS NULL OR category = $1)
+                  AND ($2::TEXT IS NULL OR session_id = $2)
+                  AND (cardinality($3::TEXT[]) = 0 OR tags @> $3)
+                  AND (expires_at IS NULL OR expires_at > NOW())
+                ORDER BY updated_at DESC
                 "
             );
 
-            let row = client.query_opt(&stmt, &[&key])?;
-            row.as_ref().map(Self::row_to_entry).transpose()
+            let category_ref = category.as_deref();
+            let session_ref = sid.as_deref();
+            let rows = client.query(&stmt, &[&category_ref, &session_ref, &tags])?;
+            rows.iter()
+                .map(Self::row_to_entry)
+                .collect::<Result<Vec<MemoryEntry>>>()
         })
         .await?
     }
+}
 
-    async fn list(
+fn validate_identifier(value: &str, field_name: &str) -> Result<()> {
+    if value.is_empty() {
+        anyhow::bail!("{field_name} must not be empty");
+    }
+
+    let mut chars = value.chars();
+    let Some(first) = chars.next() else {
+        anyhow::bail!("{field_name} must not be empty");
+    };
+
+    if !(first.is_ascii_alphabetic() || first == '_') {
+        anyhow::bail!("{field_name} must start with an ASCII letter or underscore; got '{value}'");
+    }
+
+    if !chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_') {
+        anyhow::bail!(
+            "{field_name} can only contain ASCII letters, numbers, and underscores; got '{value}'"
+        );
+    }
+
+    Ok(())
+}
+
+fn quote_identifier(value: &str) -> String {
+    format!("\"{value}\"")
+}
+
+#[async_trait]
+impl Memory for PostgresMemory {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn store(
         &self,
-        category: Option<&MemoryCategory>,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> Result<()> {
+        self.upsert(key, content, category, session_id, metadata, Utc::now())
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, session_id, &[], &RecallScope::All)
+            .await
+    }
+
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, session_id, tags, &RecallScope::All)
+            .await
+    }
+
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
         session_id: Option<&str>,
+        tags: &[String],
     ) -> Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, session_id, tags, scope)
+            .await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
-        let category = category.map(Self::category_to_str);
-        let sid = session_id.map(str::to_string);
+        let key = key.to_string();
 
-        tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
+        tokio::task::spawn_blocking(move || -> Result<Option<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id
+                SELECT id, key, content, category, created_at, session_id, metadata
                 FROM {qualified_table}
-                WHERE ($1::TEXT IS NULL OR category = $1)
-                  AND ($2::TEXT IS NULL OR session_id = $2)
-                ORDER BY updated_at DESC
+                WHERE key = $1
+                  AND (expires_at IS NULL OR expires_at > NOW())
+                LIMIT 1
                 "
             );
 
-            let category_ref = category.as_deref();
-            let session_ref = sid.as_deref();
-            let rows = client.query(&stmt, &[&category_ref, &session_ref])?;
-            rows.iter()
-                .map(Self::row_to_entry)
-                .collect::<Result<Vec<MemoryEntry>>>()
+            let row = client.query_opt(&stmt, &[&key])?;
+            row.as_ref().map(Self::row_to_entry).transpose()
         })
         .await?
     }
 
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.list_filtered(category, session_id, &[]).await
+    }
+
+    async fn list_tagged(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        self.list_filtered(category, session_id, tags).await
+    }
+
     async fn forget(&self, key: &str) -> Result<bool> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
@@ -399,7 +568,9 @@ impl Memory for PostgresMemory {
 
         tokio::task::spawn_blocking(move || -> Result<usize> {
             let mut client = client.lock();
-            let stmt = format!("SELECT COUNT(*) FROM {qualified_table}");
+            let stmt = format!(
+                "SELECT COUNT(*) FROM {qualified_table} WHERE expires_at IS NULL OR expires_at > NOW()"
+            );
             let count: i64 = client.query_one(&stmt, &[])?.get(0);
             let count =
                 usize::try_from(count).context("PostgreSQL returned a negative memory count")?;
@@ -408,6 +579,31 @@ impl Memory for PostgresMemory {
         .await?
     }
 
+    async fn import_record(&self, record: MemoryRecord) -> Result<()> {
+        let entry = record.entry;
+        let timestamp = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map_or_else(|_| Utc::now(), |at| at.with_timezone(&Utc));
+        self.upsert(
+            &entry.key,
+            &entry.content,
+            entry.category,
+            entry.session_id.as_deref(),
+            entry.metadata,
+            timestamp,
+        )
+        .await
+    }
+
+    async fn purge_expired(&self) -> Result<usize> {
+        let client = self.client.clone();
+        let qualified_table = self.qualified_table.clone();
+
+        tokio::task::spawn_blocking(move || {
+            Self::purge_expired_rows(&mut client.lock(), &qualified_table)
+        })
+        .await?
+    }
+
     async fn health_check(&self) -> bool {
         let client = self.client.clone();
         tokio::task::spawn_blocking(move || client.lock().simple_query("SELECT 1").is_ok())
@@ -1,23 +1,43 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::namespace::RecallScope;
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord, StoredEmbedding,
+    TAG_FILTER_OVERFETCH,
+};
+use super::vector;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 use uuid::Uuid;
 
+/// Upper bound on points scanned by the keyword half of hybrid recall.
+const KEYWORD_SCAN_LIMIT: usize = 256;
+/// Maximum number of query terms turned into full-text conditions.
+const MAX_KEYWORD_TERMS: usize = 8;
+/// Points fetched per scroll page when exporting the whole collection.
+const EXPORT_PAGE_SIZE: usize = 256;
+
+/// Payload path of an entry's namespace.
+const NAMESPACE_FIELD: &str = "metadata.namespace";
+
 /// Qdrant vector database memory backend.
 ///
 /// Uses Qdrant's REST API for vector storage and semantic search.
 /// Requires an embedding provider for converting text to vectors.
+/// Recall blends vector similarity with a full-text keyword pass over the
+/// `content` payload via [`vector::hybrid_merge`].
 pub struct QdrantMemory {
     client: reqwest::Client,
     base_url: String,
     collection: String,
     api_key: Option<String>,
     embedder: Arc<dyn EmbeddingProvider>,
+    vector_weight: f32,
+    keyword_weight: f32,
     /// Tracks whether collection has been initialized (lazy init for sync factory).
     initialized: OnceCell<()>,
 }
@@ -64,10 +84,20 @@ impl QdrantMemory {
             collection: collection.to_string(),
             api_key,
             embedder,
+            vector_weight: 0.7,
+            keyword_weight: 0.3,
             initialized: OnceCell::new(),
         }
     }
 
+    /// Override the vector/keyword weights used when merging hybrid recall results.
+    #[must_use]
+    pub fn with_hybrid_weights(mut self, vector_weight: f32, keyword_weight: f32) -> Self {
+        self.vector_weight = vector_weight;
+        self.keyword_weight = keyword_weight;
+        self
+    }
+
     /// Ensure the collection is initialized (called lazily on first operation).
     async fn ensure_initialized(&self) -> Result<()> {
         self.initialized
@@ -157,105 +187,81 @@ impl QdrantMemory {
             dims
         );
 
-        Ok(())
-    }
-
-    fn category_to_str(category: &MemoryCategory) -> String {
-        match category {
-            MemoryCategory::Core => "core".to_string(),
-            MemoryCategory::Daily => "daily".to_string(),
-            MemoryCategory::Conversation => "conversation".to_string(),
-            MemoryCategory::Custom(name) => name.clone(),
+        // Full-text index on `content` for the keyword half of hybrid recall.
+        // Qdrant still answers text matches without it (unindexed substring
+        // scan), so a failure here is not fatal.
+        let content_schema = serde_json::json!({
+            "type": "text",
+            "tokenizer": "word",
+            "lowercase": true
+        });
+        if let Err(e) = self.ensure_payload_index("content", content_schema).await {
+            tracing::warn!("Qdrant full-text index on 'content' not created: {e}");
         }
-    }
-
-    fn parse_category(value: &str) -> MemoryCategory {
-        match value {
-            "core" => MemoryCategory::Core,
-            "daily" => MemoryCategory::Daily,
-            "conversation" => MemoryCategory::Conversation,
-            other => MemoryCategory::Custom(other.to_string()),
+        // Keyword index for namespace-scoped recall filters; same fallback.
+        if let Err(e) = self
+            .ensure_payload_index(NAMESPACE_FIELD, serde_json::json!("keyword"))
+            .await
+        {
+            tracing::warn!("Qdrant keyword index on '{NAMESPACE_FIELD}' not created: {e}");
         }
-    }
-}
 
-/// Qdrant point payload structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct MemoryPayload {
-    key: String,
-    content: String,
-    category: String,
-    timestamp: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    session_id: Option<String>,
-}
-
-/// Qdrant search result
-#[derive(Debug, Deserialize)]
-struct QdrantSearchResult {
-    result: Vec<QdrantScoredPoint>,
-}
+        Ok(())
+    }
 
-#[derive(Debug, Deserialize)]
-struct QdrantScoredPoint {
-    id: serde_json::Value,
-    score: f64,
-    payload: Option<MemoryPayload>,
-}
+    async fn ensure_payload_index(&self, field: &str, schema: serde_json::Value) -> Result<()> {
+        let index_body = serde_json::json!({
+            "field_name": field,
+            "field_schema": schema
+        });
 
-/// Qdrant scroll result
-#[derive(Debug, Deserialize)]
-struct QdrantScrollResult {
-    result: QdrantScrollPoints,
-}
+        let resp = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/collections/{}/index", self.collection),
+            )
+            .query(&[("wait", "true")])
+            .json(&index_body)
+            .send()
+            .await
+            .context("failed to create Qdrant payload index")?;
 
-#[derive(Debug, Deserialize)]
-struct QdrantScrollPoints {
-    points: Vec<QdrantPoint>,
-}
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant payload index creation failed ({status}): {text}");
+        }
 
-#[derive(Debug, Deserialize)]
-struct QdrantPoint {
-    id: serde_json::Value,
-    payload: Option<MemoryPayload>,
-}
+        Ok(())
+    }
 
-#[async_trait]
-impl Memory for QdrantMemory {
-    fn name(&self) -> &str {
-        "qdrant"
+    fn session_filter(session_id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "key": "session_id",
+            "match": { "value": session_id }
+        })
     }
 
-    async fn store(
-        &self,
-        key: &str,
-        content: &str,
-        category: MemoryCategory,
-        session_id: Option<&str>,
-    ) -> Result<()> {
+    /// Replace the point for `payload.key`, embedding the content unless a vector is supplied.
+    async fn upsert_point(&self, payload: MemoryPayload, vector: Option<Vec<f32>>) -> Result<()> {
         self.ensure_initialized().await?;
 
-        // Generate embedding for the content
-        let combined_text = format!("{}\n{}", key, content);
-        let embedding = self.embedder.embed_one(&combined_text).await?;
+        let embedding = match vector {
+            Some(vector) => vector,
+            None => {
+                let combined_text = format!("{}\n{}", payload.key, payload.content);
+                self.embedder.embed_one(&combined_text).await?
+            }
+        };
 
         if embedding.is_empty() {
             anyhow::bail!("Qdrant requires non-zero dimensional embeddings");
         }
 
         let id = Uuid::new_v4().to_string();
-        let timestamp = Utc::now().to_rfc3339();
-
-        let payload = MemoryPayload {
-            key: key.to_string(),
-            content: content.to_string(),
-            category: Self::category_to_str(&category),
-            timestamp,
-            session_id: session_id.map(str::to_string),
-        };
 
         // Delete any existing point with the same key first
-        let _ = self.forget(key).await;
+        let _ = self.forget(&payload.key).await;
 
         // Upsert point
         let upsert_body = serde_json::json!({
@@ -286,44 +292,277 @@ impl Memory for QdrantMemory {
         Ok(())
     }
 
-    async fn recall(
+    /// Page through every point in the collection.
+    async fn scroll_all(&self, with_vector: bool) -> Result<Vec<QdrantPoint>> {
+        let mut points = Vec::new();
+        let mut offset: Option<serde_json::Value> = None;
+        loop {
+            let mut scroll_body = serde_json::json!({
+                "limit": EXPORT_PAGE_SIZE,
+                "with_payload": true,
+                "with_vector": with_vector
+            });
+            if let Some(offset) = offset.take() {
+                scroll_body["offset"] = offset;
+            }
+
+            let resp = self
+                .request(
+                    reqwest::Method::POST,
+                    &format!("/collections/{}/points/scroll", self.collection),
+                )
+                .json(&scroll_body)
+                .send()
+                .await
+                .context("failed to scroll Qdrant")?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Qdrant scroll failed ({status}): {text}");
+            }
+
+            let page: QdrantScrollResult = resp.json().await?;
+            points.extend(page.result.points);
+
+            match page.result.next_page_offset {
+                Some(next) if !next.is_null() => offset = Some(next),
+                _ => break,
+            }
+        }
+        Ok(points)
+    }
+
+    /// Hybrid recall restricted to `scope` by the Qdrant filter itself.
+    async fn recall_in(
         &self,
         query: &str,
         limit: usize,
         session_id: Option<&str>,
+        scope: &RecallScope,
     ) -> Result<Vec<MemoryEntry>> {
+        let mut must = Vec::new();
+        if let Some(sid) = session_id {
+            must.push(Self::session_filter(sid));
+        }
+        must.extend(Self::namespace_condition(scope));
+
         if query.trim().is_empty() {
-            return self.list(None, session_id).await;
+            return self.list_matching(must).await;
         }
 
         self.ensure_initialized().await?;
 
-        // Generate embedding for the query
+        let candidates = limit.saturating_mul(2).max(1);
+        let terms = Self::query_terms(query);
+
+        // Generate embedding for the query; a zero-dimensional embedder
+        // degrades recall to the keyword pass only.
         let embedding = self.embedder.embed_one(query).await?;
+        let vector_entries = if embedding.is_empty() {
+            Vec::new()
+        } else {
+            self.vector_search(&embedding, candidates, &must).await?
+        };
 
-        if embedding.is_empty() {
-            // Fallback to listing if embeddings aren't available
-            return self.list(None, session_id).await;
+        let keyword_entries = match self.keyword_search(&terms, candidates, &must).await {
+            Ok(entries) => entries,
+            Err(e) if !vector_entries.is_empty() => {
+                tracing::warn!("Qdrant keyword recall skipped: {e}");
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let vector_results: Vec<(String, f32)> = vector_entries
+            .iter()
+            .map(|entry| (entry.id.clone(), entry.score.unwrap_or(0.0) as f32))
+            .collect();
+        let keyword_results: Vec<(String, f32)> = keyword_entries
+            .iter()
+            .map(|(entry, score)| (entry.id.clone(), *score))
+            .collect();
+
+        let merged = if vector_results.is_empty() {
+            keyword_results
+                .iter()
+                .take(limit)
+                .map(|(id, score)| vector::ScoredResult {
+                    id: id.clone(),
+                    vector_score: None,
+                    keyword_score: Some(*score),
+                    final_score: *score,
+                })
+                .collect::<Vec<_>>()
+        } else {
+            vector::hybrid_merge(
+                &vector_results,
+                &keyword_results,
+                self.vector_weight,
+                self.keyword_weight,
+                limit,
+            )
+        };
+
+        let mut by_id: HashMap<String, MemoryEntry> = keyword_entries
+            .into_iter()
+            .map(|(entry, _)| (entry.id.clone(), entry))
+            .collect();
+        for entry in vector_entries {
+            by_id.insert(entry.id.clone(), entry);
         }
 
-        // Build filter for session_id if provided
-        let filter = session_id.map(|sid| {
-            serde_json::json!({
-                "must": [{
-                    "key": "session_id",
-                    "match": { "value": sid }
-                }]
+        let entries = merged
+            .into_iter()
+            .filter_map(|scored| {
+                let mut entry = by_id.remove(&scored.id)?;
+                entry.score = Some(f64::from(scored.final_score));
+                Some(entry)
             })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Filter condition admitting only entries visible from `scope`.
+    fn namespace_condition(scope: &RecallScope) -> Option<serde_json::Value> {
+        let (namespace, include_shared) = scope.namespace_filter()?;
+        let shared = serde_json::json!({ "is_empty": { "key": NAMESPACE_FIELD } });
+        Some(match namespace {
+            None => shared,
+            Some(namespace) => {
+                let own = serde_json::json!({
+                    "key": NAMESPACE_FIELD,
+                    "match": { "value": namespace }
+                });
+                if include_shared {
+                    serde_json::json!({ "should": [own, shared] })
+                } else {
+                    own
+                }
+            }
+        })
+    }
+
+    /// Scroll entries matching every condition in `must`.
+    async fn list_matching(&self, must: Vec<serde_json::Value>) -> Result<Vec<MemoryEntry>> {
+        self.ensure_initialized().await?;
+
+        let mut scroll_body = serde_json::json!({
+            "limit": 1000,
+            "with_payload": true
         });
 
+        if !must.is_empty() {
+            scroll_body["filter"] = serde_json::json!({ "must": must });
+        }
+
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/scroll", self.collection),
+            )
+            .json(&scroll_body)
+            .send()
+            .await
+            .context("failed to scroll Qdrant")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant scroll failed ({status}): {text}");
+        }
+
+        let result: QdrantScrollResult = resp.json().await?;
+
+        let entries = result
+            .result
+            .points
+            .into_iter()
+            .filter_map(|point| {
+                let payload = point.payload?;
+                Self::point_to_entry(&point.id, payload, None)
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn point_to_entry(
+        id: &serde_json::Value,
+        payload: MemoryPayload,
+        score: Option<f64>,
+    ) -> Option<MemoryEntry> {
+        let id = match id {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+
+        // Expired points linger until overwritten; never surface them.
+        if payload.metadata.is_expired() {
+            return None;
+        }
+
+        Some(MemoryEntry {
+            id,
+            key: payload.key,
+            content: payload.content,
+            category: Self::parse_category(&payload.category),
+            timestamp: payload.timestamp,
+            session_id: payload.session_id,
+            score,
+            metadata: payload.metadata,
+        })
+    }
+
+    /// Lowercased, de-duplicated query terms used for keyword matching.
+    fn query_terms(query: &str) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        for term in query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+        {
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+            if terms.len() == MAX_KEYWORD_TERMS {
+                break;
+            }
+        }
+        terms
+    }
+
+    /// Fraction of query terms present in the entry's key or content.
+    #[allow(clippy::cast_precision_loss)]
+    fn keyword_score(terms: &[String], entry: &MemoryEntry) -> f32 {
+        if terms.is_empty() {
+            return 0.0;
+        }
+        let haystack = format!("{}\n{}", entry.key, entry.content).to_lowercase();
+        let matched = terms
+            .iter()
+            .filter(|term| haystack.contains(term.as_str()))
+            .count();
+        matched as f32 / terms.len() as f32
+    }
+
+    async fn vector_search(
+        &self,
+        embedding: &[f32],
+        limit: usize,
+        must: &[serde_json::Value],
+    ) -> Result<Vec<MemoryEntry>> {
         let mut search_body = serde_json::json!({
             "vector": embedding,
             "limit": limit,
             "with_payload": true
         });
 
-        if let Some(f) = filter {
-            search_body["filter"] = f;
+        if !must.is_empty() {
+            search_body["filter"] = serde_json::json!({ "must": must });
         }
 
         let resp = self
@@ -344,29 +583,222 @@ impl Memory for QdrantMemory {
 
         let result: QdrantSearchResult = resp.json().await?;
 
-        let entries = result
+        Ok(result
             .result
             .into_iter()
             .filter_map(|point| {
                 let payload = point.payload?;
-                let id = match &point.id {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => return None,
-                };
-
-                Some(MemoryEntry {
-                    id,
-                    key: payload.key,
-                    content: payload.content,
-                    category: Self::parse_category(&payload.category),
-                    timestamp: payload.timestamp,
-                    session_id: payload.session_id,
-                    score: Some(point.score),
+                Self::point_to_entry(&point.id, payload, Some(point.score))
+            })
+            .collect())
+    }
+
+    /// Full-text pass over `content`, ranked locally by term overlap.
+    async fn keyword_search(
+        &self,
+        terms: &[String],
+        limit: usize,
+        must: &[serde_json::Value],
+    ) -> Result<Vec<(MemoryEntry, f32)>> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let should: Vec<serde_json::Value> = terms
+            .iter()
+            .map(|term| {
+                serde_json::json!({
+                    "key": "content",
+                    "match": { "text": term }
                 })
             })
             .collect();
 
+        let mut filter = serde_json::json!({ "should": should });
+        if !must.is_empty() {
+            filter["must"] = serde_json::json!(must);
+        }
+
+        let scroll_body = serde_json::json!({
+            "filter": filter,
+            "limit": KEYWORD_SCAN_LIMIT,
+            "with_payload": true
+        });
+
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/scroll", self.collection),
+            )
+            .json(&scroll_body)
+            .send()
+            .await
+            .context("failed to scroll Qdrant")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant keyword scroll failed ({status}): {text}");
+        }
+
+        let result: QdrantScrollResult = resp.json().await?;
+
+        let mut scored: Vec<(MemoryEntry, f32)> = result
+            .result
+            .points
+            .into_iter()
+            .filter_map(|point| {
+                let payload = point.payload?;
+                let entry = Self::point_to_entry(&point.id, payload, None)?;
+                let score = Self::keyword_score(terms, &entry);
+                (score > 0.0).then_some((entry, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    fn category_to_str(category: &MemoryCategory) -> String {
+        match category {
+            MemoryCategory::Core => "core".to_string(),
+            MemoryCategory::Daily => "daily".to_string(),
+            MemoryCategory::Conversation => "conversation".to_string(),
+            MemoryCategory::Custom(name) => name.clone(),
+        }
+    }
+
+    fn parse_category(value: &str) -> MemoryCategory {
+        match value {
+            "core" => MemoryCategory::Core,
+            "daily" => MemoryCategory::Daily,
+            "conversation" => MemoryCategory::Conversation,
+            other => MemoryCategory::Custom(other.to_string()),
+        }
+    }
+}
+
+/// Qdrant point payload structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MemoryPayload {
+    key: String,
+    content: String,
+    category: String,
+    timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "MemoryMetadata::is_empty")]
+    metadata: MemoryMetadata,
+}
+
+/// Qdrant search result
+#[derive(Debug, Deserialize)]
+struct QdrantSearchResult {
+    result: Vec<QdrantScoredPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScoredPoint {
+    id: serde_json::Value,
+    score: f64,
+    payload: Option<MemoryPayload>,
+}
+
+/// Qdrant scroll result
+#[derive(Debug, Deserialize)]
+struct QdrantScrollResult {
+    result: QdrantScrollPoints,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScrollPoints {
+    points: Vec<QdrantPoint>,
+    #[serde(default)]
+    next_page_offset: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantPoint {
+    id: serde_json::Value,
+    payload: Option<MemoryPayload>,
+    #[serde(default)]
+    vector: Option<Vec<f32>>,
+}
+
+#[async_trait]
+impl Memory for QdrantMemory {
+    fn name(&self) -> &str {
+        "qdrant"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> Result<()> {
+        let payload = MemoryPayload {
+            key: key.to_string(),
+            content: content.to_string(),
+            category: Self::category_to_str(&category),
+            timestamp: Utc::now().to_rfc3339(),
+            session_id: session_id.map(str::to_string),
+            metadata,
+        };
+        self.upsert_point(payload, None).await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.recall_in(query, limit, session_id, &RecallScope::All)
+            .await
+    }
+
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall_in(query, limit, session_id, scope).await;
+        }
+        let mut entries = self
+            .recall_in(
+                query,
+                limit.saturating_mul(TAG_FILTER_OVERFETCH),
+                session_id,
+                scope,
+            )
+            .await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        entries.truncate(limit);
         Ok(entries)
     }
 
@@ -405,21 +837,7 @@ impl Memory for QdrantMemory {
 
         let entry = result.result.points.into_iter().next().and_then(|point| {
             let payload = point.payload?;
-            let id = match &point.id {
-                serde_json::Value::String(s) => s.clone(),
-                serde_json::Value::Number(n) => n.to_string(),
-                _ => return None,
-            };
-
-            Some(MemoryEntry {
-                id,
-                key: payload.key,
-                content: payload.content,
-                category: Self::parse_category(&payload.category),
-                timestamp: payload.timestamp,
-                session_id: payload.session_id,
-                score: None,
-            })
+            Self::point_to_entry(&point.id, payload, None)
         });
 
         Ok(entry)
@@ -430,8 +848,6 @@ impl Memory for QdrantMemory {
         category: Option<&MemoryCategory>,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
-        self.ensure_initialized().await?;
-
         // Build filter conditions
         let mut must_conditions = Vec::new();
 
@@ -443,64 +859,51 @@ impl Memory for QdrantMemory {
         }
 
         if let Some(sid) = session_id {
-            must_conditions.push(serde_json::json!({
-                "key": "session_id",
-                "match": { "value": sid }
-            }));
-        }
-
-        let mut scroll_body = serde_json::json!({
-            "limit": 1000,
-            "with_payload": true
-        });
-
-        if !must_conditions.is_empty() {
-            scroll_body["filter"] = serde_json::json!({ "must": must_conditions });
+            must_conditions.push(Self::session_filter(sid));
         }
 
-        let resp = self
-            .request(
-                reqwest::Method::POST,
-                &format!("/collections/{}/points/scroll", self.collection),
-            )
-            .json(&scroll_body)
-            .send()
-            .await
-            .context("failed to scroll Qdrant")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Qdrant scroll failed ({status}): {text}");
-        }
+        self.list_matching(must_conditions).await
+    }
 
-        let result: QdrantScrollResult = resp.json().await?;
+    async fn export_records(&self) -> Result<Vec<MemoryRecord>> {
+        self.ensure_initialized().await?;
 
-        let entries = result
-            .result
-            .points
+        let fingerprint = self.embedder.fingerprint();
+        let records = self
+            .scroll_all(true)
+            .await?
             .into_iter()
             .filter_map(|point| {
-                let payload = point.payload?;
-                let id = match &point.id {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => return None,
-                };
-
-                Some(MemoryEntry {
-                    id,
-                    key: payload.key,
-                    content: payload.content,
-                    category: Self::parse_category(&payload.category),
-                    timestamp: payload.timestamp,
-                    session_id: payload.session_id,
-                    score: None,
-                })
+                let entry = Self::point_to_entry(&point.id, point.payload?, None)?;
+                let embedding = point.vector.map(|vector| StoredEmbedding {
+                    fingerprint: fingerprint.clone(),
+                    vector,
+                });
+                Some(MemoryRecord { entry, embedding })
             })
             .collect();
 
-        Ok(entries)
+        Ok(records)
+    }
+
+    async fn import_record(&self, record: MemoryRecord) -> Result<()> {
+        let MemoryRecord { entry, embedding } = record;
+        // Vectors from another embedding space would poison similarity search
+        let vector = embedding
+            .filter(|stored| {
+                stored.fingerprint == self.embedder.fingerprint()
+                    && stored.vector.len() == self.embedder.dimensions()
+            })
+            .map(|stored| stored.vector);
+        let payload = MemoryPayload {
+            key: entry.key,
+            content: entry.content,
+            category: Self::category_to_str(&entry.category),
+            timestamp: entry.timestamp,
+            session_id: entry.session_id,
+            metadata: entry.metadata,
+        };
+        self.upsert_point(payload, vector).await
     }
 
     async fn forget(&self, key: &str) -> Result<bool> {
@@ -537,34 +940,61 @@ impl Memory for QdrantMemory {
         Ok(true)
     }
 
-    async fn count(&self) -> Result<usize> {
+    async fn purge_expired(&self) -> Result<usize> {
         self.ensure_initialized().await?;
 
+        let expired: Vec<serde_json::Value> = self
+            .scroll_all(false)
+            .await?
+            .into_iter()
+            .filter(|point| {
+                point
+                    .payload
+                    .as_ref()
+                    .is_some_and(|payload| payload.metadata.is_expired())
+            })
+            .map(|point| point.id)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
         let resp = self
             .request(
-                reqwest::Method::GET,
-                &format!("/collections/{}", self.collection),
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/delete", self.collection),
             )
+            .query(&[("wait", "true")])
+            .json(&serde_json::json!({ "points": expired }))
             .send()
             .await
-            .context("failed to get Qdrant collection info")?;
+            .context("failed to delete expired points from Qdrant")?;
 
         if !resp.status().is_success() {
             let status = resp.status();
             let text = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Qdrant collection info failed ({status}): {text}");
+            anyhow::bail!("Qdrant delete failed ({status}): {text}");
         }
 
-        let json: serde_json::Value = resp.json().await?;
+        Ok(expired.len())
+    }
 
-        let count = json
-            .get("result")
-            .and_then(|r| r.get("points_count"))
-            .and_then(|c| c.as_u64())
-            .unwrap_or(0);
+    async fn count(&self) -> Result<usize> {
+        self.ensure_initialized().await?;
 
-        let count =
-            usize::try_from(count).context("Qdrant returned a points count that exceeds usize")?;
+        // The collection's points_count includes expired points that have not
+        // been purged yet, so count what recall would actually surface.
+        let count = self
+            .scroll_all(false)
+            .await?
+            .into_iter()
+            .filter(|point| {
+                point
+                    .payload
+                    .as_ref()
+                    .is_some_and(|payload| !payload.metadata.is_expired())
+            })
+            .count();
         Ok(count)
     }
 
@@ -618,6 +1048,7 @@ mod tests {
             category: "core".into(),
             timestamp: "2026-02-20T00:00:00Z".into(),
             session_id: Some("session-1".into()),
+            metadata: MemoryMetadata::default(),
         };
 
         let json = serde_json::to_string(&payload).unwrap();
@@ -634,9 +1065,428 @@ mod tests {
             category: "core".into(),
             timestamp: "2026-02-20T00:00:00Z".into(),
             session_id: None,
+            metadata: MemoryMetadata::default(),
         };
 
         let json = serde_json::to_string(&payload).unwrap();
         assert!(!json.contains("session_id"));
     }
+
+    #[test]
+    fn query_terms_are_lowercased_and_deduplicated() {
+        assert_eq!(
+            QdrantMemory::query_terms("Rust, rust & TOKIO runtime"),
+            vec!["rust", "tokio", "runtime"]
+        );
+        assert!(QdrantMemory::query_terms("  ?! ").is_empty());
+    }
+
+    // ── In-process Qdrant REST stand-in ─────────────────────────
+
+    /// Embeds text as counts of a few fixed topic word groups.
+    struct TopicEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for TopicEmbedding {
+        fn name(&self) -> &str {
+            "topic"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let lower = text.to_lowercase();
+                    [&["coffee"][..], &["rust", "cargo"], &["garden"]]
+                        .iter()
+                        .map(|words| {
+                            let hits: usize = words.iter().map(|w| lower.matches(w).count()).sum();
+                            hits as f32 + 0.01
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeQdrant {
+        collection_created: bool,
+        indexed_fields: Vec<String>,
+        points: Vec<(String, Vec<f32>, serde_json::Value)>,
+    }
+
+    type SharedQdrant = Arc<std::sync::Mutex<FakeQdrant>>;
+
+    fn condition_matches(condition: &serde_json::Value, payload: &serde_json::Value) -> bool {
+        if condition.get("must").is_some() || condition.get("should").is_some() {
+            return filter_matches(condition, payload);
+        }
+        let lookup = |key: &str| payload.pointer(&format!("/{}", key.replace('.', "/")));
+        if let Some(key) = condition["is_empty"]["key"].as_str() {
+            return lookup(key).map_or(true, serde_json::Value::is_null);
+        }
+        let key = condition["key"].as_str().unwrap_or_default();
+        let field = lookup(key)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        if let Some(value) = condition["match"]["value"].as_str() {
+            return field == value;
+        }
+        if let Some(text) = condition["match"]["text"].as_str() {
+            return field.to_lowercase().contains(&text.to_lowercase());
+        }
+        false
+    }
+
+    fn filter_matches(filter: &serde_json::Value, payload: &serde_json::Value) -> bool {
+        let must_ok = filter["must"].as_array().map_or(true, |conds| {
+            conds.iter().all(|c| condition_matches(c, payload))
+        });
+        let should_ok = filter["should"].as_array().map_or(true, |conds| {
+            conds.iter().any(|c| condition_matches(c, payload))
+        });
+        must_ok && should_ok
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm_a * norm_b)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    async fn spawn_fake_qdrant() -> (String, SharedQdrant) {
+        use axum::extract::State;
+        use axum::http::StatusCode;
+        use axum::routing::{get, post, put};
+        use axum::{Json, Router};
+        use tokio::net::TcpListener;
+
+        let state: SharedQdrant = Arc::default();
+
+        let app = Router::new()
+            .route("/", get(|| async { Json(serde_json::json!({"title": "qdrant"})) }))
+            .route(
+                "/collections/{name}",
+                get(|State(s): State<SharedQdrant>| async move {
+                    let s = s.lock().unwrap();
+                    if s.collection_created {
+                        (
+                            StatusCode::OK,
+                            Json(serde_json::json!({"result": {"points_count": s.points.len()}})),
+                        )
+                    } else {
+                        (
+                            StatusCode::NOT_FOUND,
+                            Json(serde_json::json!({"status": {"error": "Not found"}})),
+                        )
+                    }
+                })
+                .put(|State(s): State<SharedQdrant>| async move {
+                    s.lock().unwrap().collection_created = true;
+                    Json(serde_json::json!({"result": true}))
+                }),
+            )
+            .route(
+                "/collections/{name}/index",
+                put(
+                    |State(s): State<SharedQdrant>, Json(body): Json<serde_json::Value>| async move {
+                        let field = body["field_name"].as_str().unwrap_or_default().to_string();
+                        s.lock().unwrap().indexed_fields.push(field);
+                        Json(serde_json::json!({"result": {"status": "acknowledged"}}))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points",
+                put(
+                    |State(s): State<SharedQdrant>, Json(body): Json<serde_json::Value>| async move {
+                        let mut s = s.lock().unwrap();
+                        for point in body["points"].as_array().unwrap() {
+                            let vector = point["vector"]
+                                .as_array()
+                                .unwrap()
+                                .iter()
+                                .map(|v| v.as_f64().unwrap() as f32)
+                                .collect();
+                            s.points.push((
+                                point["id"].as_str().unwrap().to_string(),
+                                vector,
+                                point["payload"].clone(),
+                            ));
+                        }
+                        Json(serde_json::json!({"result": {"status": "completed"}}))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points/search",
+                post(
+                    |State(s): State<SharedQdrant>, Json(body): Json<serde_json::Value>| async move {
+                        let query: Vec<f32> = body["vector"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|v| v.as_f64().unwrap() as f32)
+                            .collect();
+                        let limit = body["limit"].as_u64().unwrap() as usize;
+                        let s = s.lock().unwrap();
+                        let mut hits: Vec<serde_json::Value> = s
+                            .points
+                            .iter()
+                            .filter(|(_, _, payload)| filter_matches(&body["filter"], payload))
+                            .map(|(id, vector, payload)| {
+                                serde_json::json!({
+                                    "id": id,
+                                    "score": cosine(&query, vector),
+                                    "payload": payload
+                                })
+                            })
+                            .collect();
+                        hits.sort_by(|a, b| {
+                            b["score"].as_f64().partial_cmp(&a["score"].as_f64()).unwrap()
+                        });
+                        hits.truncate(limit);
+                        Json(serde_json::json!({"result": hits}))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points/scroll",
+                post(
+                    |State(s): State<SharedQdrant>, Json(body): Json<serde_json::Value>| async move {
+                        let limit = body["limit"].as_u64().unwrap() as usize;
+                        let s = s.lock().unwrap();
+                        let points: Vec<serde_json::Value> = s
+                            .points
+                            .iter()
+                            .filter(|(_, _, payload)| filter_matches(&body["filter"], payload))
+                            .take(limit)
+                            .map(|(id, _, payload)| serde_json::json!({"id": id, "payload": payload}))
+                            .collect();
+                        Json(serde_json::json!({"result": {"points": points}}))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points/delete",
+                post(
+                    |State(s): State<SharedQdrant>, Json(body): Json<serde_json::Value>| async move {
+                        let mut s = s.lock().unwrap();
+                        if let Some(ids) = body["points"].as_array() {
+                            s.points.retain(|(id, _, _)| !ids.iter().any(|i| i == id));
+                        } else {
+                            s.points.retain(|(_, _, payload)| {
+                                !filter_matches(&body["filter"], payload)
+                            });
+                        }
+                        Json(serde_json::json!({"result": {"status": "completed"}}))
+                    },
+                ),
+            )
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{addr}"), state)
+    }
+
+    #[tokio::test]
+    async fn lazy_init_creates_collection_and_content_index() {
+        let (url, state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding));
+
+        assert!(mem.health_check().await);
+        assert_eq!(mem.count().await.unwrap(), 0);
+
+        let s = state.lock().unwrap();
+        assert!(s.collection_created);
+        assert_eq!(
+            s.indexed_fields,
+            vec!["content".to_string(), NAMESPACE_FIELD.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn store_replaces_existing_key_and_round_trips() {
+        let (url, _state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding));
+
+        mem.store("drink", "likes coffee", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "drink",
+            "prefers coffee black",
+            MemoryCategory::Core,
+            Some("s1"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(mem.count().await.unwrap(), 1);
+        let entry = mem.get("drink").await.unwrap().expect("entry stored");
+        assert_eq!(entry.content, "prefers coffee black");
+        assert_eq!(entry.session_id.as_deref(), Some("s1"));
+
+        assert!(mem.forget("drink").await.unwrap());
+        assert!(mem.get("drink").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn count_skips_and_purge_deletes_expired_points() {
+        let (url, state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding));
+
+        let expired = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "otp",
+            "one-time code 4821",
+            MemoryCategory::Core,
+            None,
+            expired,
+        )
+        .await
+        .unwrap();
+        mem.store("drink", "likes coffee", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        assert_eq!(mem.count().await.unwrap(), 1);
+        assert_eq!(state.lock().unwrap().points.len(), 2);
+
+        assert_eq!(mem.purge_expired().await.unwrap(), 1);
+        assert_eq!(state.lock().unwrap().points.len(), 1);
+        assert!(mem.get("drink").await.unwrap().is_some());
+        assert_eq!(mem.purge_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn scoped_recall_filters_namespaces_in_the_query() {
+        use crate::memory::namespace::MemoryNamespace;
+
+        let (url, _state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding));
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let bob = MemoryNamespace::sender("telegram", "bob");
+        for (namespace, key, content) in [
+            (&bob, "drink", "likes coffee"),
+            (&alice, "drink", "likes coffee with milk"),
+            (
+                &MemoryNamespace::shared(),
+                "office",
+                "office coffee machine",
+            ),
+        ] {
+            mem.store_in_namespace(
+                namespace,
+                key,
+                content,
+                MemoryCategory::Core,
+                None,
+                MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let own = RecallScope::Namespace {
+            namespace: alice.clone(),
+            include_shared: false,
+        };
+        let results = mem
+            .recall_scoped(&own, "coffee", 5, None, &[])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "likes coffee with milk");
+
+        let with_shared = RecallScope::Namespace {
+            namespace: alice,
+            include_shared: true,
+        };
+        let results = mem
+            .recall_scoped(&with_shared, "coffee", 5, None, &[])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|entry| MemoryNamespace::of(entry) != bob));
+    }
+
+    #[tokio::test]
+    async fn recall_merges_vector_and_keyword_hits() {
+        let (url, _state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding))
+            .with_hybrid_weights(0.5, 0.5);
+
+        mem.store("lang", "writes rust daily", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "commute",
+            "rides cargo bikes past the garden",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "ticket",
+            "deploy id zx81 pending",
+            MemoryCategory::Daily,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Vector similarity alone ranks the cargo note second; "zx81" has no
+        // embedding signal, so the keyword pass is what lifts the ticket.
+        let results = mem.recall("rust zx81", 2, None).await.unwrap();
+        let keys: Vec<&str> = results.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["lang", "ticket"]);
+        assert!(results.iter().all(|e| e.score.is_some()));
+    }
+
+    #[tokio::test]
+    async fn recall_respects_session_filter() {
+        let (url, _state) = spawn_fake_qdrant().await;
+        let mem = QdrantMemory::new_lazy(&url, "memories", None, Arc::new(TopicEmbedding));
+
+        mem.store(
+            "a",
+            "coffee at noon",
+            MemoryCategory::Conversation,
+            Some("s1"),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "b",
+            "coffee at dawn",
+            MemoryCategory::Conversation,
+            Some("s2"),
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("coffee", 5, Some("s2")).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "b");
+    }
 }
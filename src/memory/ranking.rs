@@ -0,0 +1,359 @@
+//! Recency-aware re-ranking of recalled memories.
+//!
+//! Backends rank purely by relevance, so a fact from last year competes evenly
+//! with one from this morning and near-identical entries crowd out everything
+//! else. This pipeline runs on top of any backend's `recall` output:
+//!
+//! 1. exponential time decay by entry age (`half_life_days`, bounded below by
+//!    `decay_floor`; `core` entries do not decay),
+//! 2. a multiplicative boost for `core` entries,
+//! 3. a boost for entries from the current session or channel sender,
+//! 4. maximal marginal relevance (MMR) selection so near-duplicates do not
+//!    fill every slot.
+
+use super::namespace::RecallScope;
+use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use crate::config::MemoryRankingConfig;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+/// Who is asking, for session-affinity boosts and namespace scoping.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecallContext<'a> {
+    pub session_id: Option<&'a str>,
+    pub channel: Option<&'a str>,
+    pub sender: Option<&'a str>,
+    /// Namespaces the caller may see; `None` recalls from every namespace.
+    pub scope: Option<&'a RecallScope>,
+}
+
+impl<'a> RecallContext<'a> {
+    /// Context for a channel message from `sender` on `channel`.
+    pub fn for_sender(channel: &'a str, sender: &'a str) -> Self {
+        Self {
+            session_id: None,
+            channel: Some(channel),
+            sender: Some(sender),
+            scope: None,
+        }
+    }
+
+    /// Restrict recall to the namespaces in `scope`.
+    #[must_use]
+    pub fn with_scope(mut self, scope: &'a RecallScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn matches(&self, entry: &MemoryEntry) -> bool {
+        if let (Some(session), Some(entry_session)) = (self.session_id, &entry.session_id) {
+            if session == entry_session {
+                return true;
+            }
+        }
+        match (self.channel, self.sender, &entry.metadata.source) {
+            (Some(channel), Some(sender), Some(source)) => {
+                source.channel.as_deref() == Some(channel)
+                    && source.sender.as_deref() == Some(sender)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Recall from `memory`, drop entries below `min_relevance_score`, and re-rank
+/// the rest with `config`. Returns at most `limit` entries.
+///
+/// Overfetches by `candidate_multiplier` so decay and diversity have room to
+/// promote entries the backend ranked lower.
+pub async fn recall_ranked(
+    memory: &dyn Memory,
+    query: &str,
+    limit: usize,
+    min_relevance_score: f64,
+    config: &MemoryRankingConfig,
+    context: &RecallContext<'_>,
+) -> anyhow::Result<Vec<MemoryEntry>> {
+    let fetch = if config.enabled {
+        limit.saturating_mul(config.candidate_multiplier.max(1))
+    } else {
+        limit
+    };
+    let mut entries = match context.scope {
+        Some(scope) => memory.recall_scoped(scope, query, fetch, None, &[]).await?,
+        None => memory.recall(query, fetch, None).await?,
+    };
+    entries.retain(|entry| entry.score.map_or(true, |s| s >= min_relevance_score));
+
+    if !config.enabled {
+        entries.truncate(limit);
+        return Ok(entries);
+    }
+    Ok(rank(entries, config, context, Utc::now(), limit))
+}
+
+/// Re-rank `entries` (in backend order) and keep the best `limit`.
+///
+/// Entries without a backend score are given a rank-derived base score so
+/// keyword-only backends still benefit from decay and boosts.
+pub fn rank(
+    entries: Vec<MemoryEntry>,
+    config: &MemoryRankingConfig,
+    context: &RecallContext<'_>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<MemoryEntry> {
+    if entries.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    let total = entries.len() as f64;
+    let scored: Vec<(f64, MemoryEntry)> = entries
+        .into_iter()
+        .enumerate()
+        .map(|(position, entry)| {
+            let base = entry
+                .score
+                .unwrap_or(1.0 - 0.5 * position as f64 / total)
+                .max(0.0);
+            (base * adjustment(&entry, config, context, now), entry)
+        })
+        .collect();
+
+    select_mmr(scored, config.mmr_lambda.clamp(0.0, 1.0), limit)
+}
+
+/// Combined decay, category and affinity multiplier for one entry.
+fn adjustment(
+    entry: &MemoryEntry,
+    config: &MemoryRankingConfig,
+    context: &RecallContext<'_>,
+    now: DateTime<Utc>,
+) -> f64 {
+    let mut factor = 1.0;
+
+    if entry.category == MemoryCategory::Core {
+        factor *= config.core_boost;
+    } else if let Some(age_days) = age_days(&entry.timestamp, now) {
+        factor *= time_decay(age_days, config.half_life_days, config.decay_floor);
+    }
+
+    if context.matches(entry) {
+        factor *= config.session_boost;
+    }
+
+    factor
+}
+
+/// `floor + (1 - floor) * 0.5^(age / half_life)`; 1.0 when decay is disabled.
+pub fn time_decay(age_days: f64, half_life_days: f64, floor: f64) -> f64 {
+    if half_life_days <= 0.0 {
+        return 1.0;
+    }
+    let floor = floor.clamp(0.0, 1.0);
+    let decay = 0.5_f64.powf(age_days.max(0.0) / half_life_days);
+    floor + (1.0 - floor) * decay
+}
+
+fn age_days(timestamp: &str, now: DateTime<Utc>) -> Option<f64> {
+    let at = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let seconds = now
+        .signed_duration_since(at.with_timezone(&Utc))
+        .num_seconds();
+    #[allow(clippy::cast_precision_loss)]
+    Some(seconds.max(0) as f64 / 86_400.0)
+}
+
+/// Greedy MMR: repeatedly take the entry maximising
+/// `lambda * score - (1 - lambda) * max_similarity_to_selected`.
+fn select_mmr(mut pool: Vec<(f64, MemoryEntry)>, lambda: f64, limit: usize) -> Vec<MemoryEntry> {
+    pool.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    if lambda >= 1.0 {
+        return pool
+            .into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry)
+            .collect();
+    }
+
+    let mut pool: Vec<(f64, HashSet<String>, MemoryEntry)> = pool
+        .into_iter()
+        .map(|(score, entry)| (score, token_set(&entry.content), entry))
+        .collect();
+    let mut selected: Vec<(HashSet<String>, MemoryEntry)> = Vec::with_capacity(limit);
+
+    while selected.len() < limit && !pool.is_empty() {
+        let mut best = 0;
+        let mut best_value = f64::NEG_INFINITY;
+        for (index, (score, tokens, _)) in pool.iter().enumerate() {
+            let redundancy = selected
+                .iter()
+                .map(|(chosen, _)| jaccard(tokens, chosen))
+                .fold(0.0, f64::max);
+            let value = lambda * score - (1.0 - lambda) * redundancy;
+            if value > best_value {
+                best_value = value;
+                best = index;
+            }
+        }
+        let (_, tokens, entry) = pool.remove(best);
+        selected.push((tokens, entry));
+    }
+
+    selected.into_iter().map(|(_, entry)| entry).collect()
+}
+
+fn token_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryMetadata, MemorySource};
+    use chrono::Duration;
+
+    fn entry(key: &str, content: &str, category: MemoryCategory, age_days: i64) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category,
+            timestamp: (Utc::now() - Duration::days(age_days)).to_rfc3339(),
+            session_id: None,
+            score: Some(0.8),
+            metadata: MemoryMetadata::default(),
+        }
+    }
+
+    fn keys(entries: &[MemoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.key.as_str()).collect()
+    }
+
+    #[test]
+    fn time_decay_halves_per_half_life_above_floor() {
+        assert!((time_decay(0.0, 30.0, 0.0) - 1.0).abs() < 1e-9);
+        assert!((time_decay(30.0, 30.0, 0.0) - 0.5).abs() < 1e-9);
+        assert!((time_decay(60.0, 30.0, 0.2) - (0.2 + 0.8 * 0.25)).abs() < 1e-9);
+        assert!((time_decay(365.0, 0.0, 0.2) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recent_entries_outrank_stale_ones_but_core_does_not_decay() {
+        let config = MemoryRankingConfig {
+            mmr_lambda: 1.0,
+            ..MemoryRankingConfig::default()
+        };
+        let entries = vec![
+            entry("old_daily", "deploy on fridays", MemoryCategory::Daily, 365),
+            entry("old_core", "user name is ada", MemoryCategory::Core, 365),
+            entry(
+                "fresh_daily",
+                "release is tomorrow",
+                MemoryCategory::Daily,
+                0,
+            ),
+        ];
+
+        let ranked = rank(entries, &config, &RecallContext::default(), Utc::now(), 3);
+        assert_eq!(keys(&ranked), vec!["old_core", "fresh_daily", "old_daily"]);
+    }
+
+    #[test]
+    fn session_affinity_boosts_matching_sender() {
+        let config = MemoryRankingConfig {
+            mmr_lambda: 1.0,
+            half_life_days: 0.0,
+            ..MemoryRankingConfig::default()
+        };
+        let mut mine = entry(
+            "mine",
+            "prefers metric units",
+            MemoryCategory::Conversation,
+            1,
+        );
+        mine.metadata = MemoryMetadata::default().with_source(MemorySource {
+            channel: Some("telegram".into()),
+            sender: Some("alice".into()),
+            tool: None,
+        });
+        mine.score = Some(0.7);
+        let other = entry(
+            "other",
+            "prefers imperial units",
+            MemoryCategory::Conversation,
+            1,
+        );
+
+        let ranked = rank(
+            vec![other, mine],
+            &config,
+            &RecallContext::for_sender("telegram", "alice"),
+            Utc::now(),
+            1,
+        );
+        assert_eq!(keys(&ranked), vec!["mine"]);
+    }
+
+    #[test]
+    fn mmr_skips_near_duplicates() {
+        let config = MemoryRankingConfig {
+            half_life_days: 0.0,
+            ..MemoryRankingConfig::default()
+        };
+        let mut entries = vec![
+            entry(
+                "a",
+                "the build server runs on port 8080",
+                MemoryCategory::Daily,
+                0,
+            ),
+            entry(
+                "b",
+                "the build server runs on port 8080",
+                MemoryCategory::Daily,
+                0,
+            ),
+            entry(
+                "c",
+                "staging database lives in eu-west",
+                MemoryCategory::Daily,
+                0,
+            ),
+        ];
+        entries[2].score = Some(0.7);
+
+        let ranked = rank(entries, &config, &RecallContext::default(), Utc::now(), 2);
+        assert_eq!(keys(&ranked), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn unscored_entries_keep_backend_order_without_adjustments() {
+        let config = MemoryRankingConfig {
+            half_life_days: 0.0,
+            mmr_lambda: 1.0,
+            ..MemoryRankingConfig::default()
+        };
+        let mut entries = vec![
+            entry("first", "alpha", MemoryCategory::Daily, 0),
+            entry("second", "beta", MemoryCategory::Daily, 0),
+        ];
+        for e in &mut entries {
+            e.score = None;
+        }
+
+        let ranked = rank(entries, &config, &RecallContext::default(), Utc::now(), 5);
+        assert_eq!(keys(&ranked), vec!["first", "second"]);
+    }
+}
@@ -1,9 +1,13 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::namespace::RecallScope;
+use super::traits::{
+    format_expiry, Memory, MemoryCategory, MemoryEntry, MemoryMetadata, MemoryRecord,
+    StoredEmbedding, TAG_FILTER_OVERFETCH,
+};
 use super::vector;
 use anyhow::Context;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use parking_lot::Mutex;
 use rusqlite::{params, Connection};
 use std::fmt::Write as _;
@@ -17,6 +21,12 @@ use uuid::Uuid;
 /// Maximum allowed open timeout (seconds) to avoid unreasonable waits.
 const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 
+/// `memory_meta` key recording which embedding space stored vectors belong to.
+const EMBEDDING_FINGERPRINT_KEY: &str = "embedding_fingerprint";
+
+/// Namespace restriction for recall queries (see [`RecallScope::namespace_filter`]).
+type NamespaceFilter = Option<(Option<String>, bool)>;
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
@@ -82,6 +92,7 @@ impl SqliteMemory {
         )?;
 
         Self::init_schema(&conn)?;
+        Self::check_embedding_fingerprint(&conn, embedder.as_ref())?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -168,7 +179,13 @@ impl SqliteMemory {
                 created_at   TEXT NOT NULL,
                 accessed_at  TEXT NOT NULL
             );
-            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);
+
+            -- Store-level metadata (embedding fingerprint, ...)
+            CREATE TABLE IF NOT EXISTS memory_meta (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
         )?;
 
         // Migration: add session_id column if not present (safe to run repeatedly)
@@ -183,9 +200,134 @@ impl SqliteMemory {
             )?;
         }
 
+        // Migration: structured metadata (JSON) plus a denormalized expiry for TTL sweeps
+        let has_metadata: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("metadata");
+        if !has_metadata {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN metadata TEXT;
+                 ALTER TABLE memories ADD COLUMN expires_at TEXT;
+                 CREATE INDEX IF NOT EXISTS idx_memories_expires ON memories(expires_at);",
+            )?;
+        }
+
+        // Migration: denormalized namespace so scoped recall filters in SQL
+        let has_namespace: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("namespace");
+        if !has_namespace {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN namespace TEXT;
+                 CREATE INDEX IF NOT EXISTS idx_memories_namespace ON memories(namespace);
+                 UPDATE memories SET namespace = json_extract(metadata, '$.namespace')
+                  WHERE json_valid(metadata) AND json_extract(metadata, '$.namespace') IS NOT NULL;",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// `WHERE` fragment restricting rows to a namespace filter; binds the
+    /// three values from [`Self::namespace_params`] starting at `?{first}`.
+    fn namespace_condition(first: usize) -> String {
+        format!(
+            "(?{first} = 0 OR namespace IS ?{} OR (?{} AND namespace IS NULL))",
+            first + 1,
+            first + 2
+        )
+    }
+
+    fn namespace_params(filter: &NamespaceFilter) -> [Box<dyn rusqlite::types::ToSql>; 3] {
+        let (scoped, namespace, include_shared) = match filter {
+            Some((namespace, include_shared)) => (true, namespace.clone(), *include_shared),
+            None => (false, None, false),
+        };
+        [
+            Box::new(scoped),
+            Box::new(namespace),
+            Box::new(include_shared),
+        ]
+    }
+
+    fn metadata_from_column(raw: Option<String>) -> MemoryMetadata {
+        raw.and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn metadata_to_column(metadata: &MemoryMetadata) -> anyhow::Result<Option<String>> {
+        if metadata.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(metadata)?))
+    }
+
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<MemoryEntry> {
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: row.get(2)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: row.get(5)?,
+            score: None,
+            metadata: Self::metadata_from_column(row.get(6)?),
+        })
+    }
+
+    fn stored_embedding_fingerprint(conn: &Connection) -> anyhow::Result<Option<String>> {
+        let mut stmt = conn.prepare("SELECT value FROM memory_meta WHERE key = ?1")?;
+        let mut rows = stmt.query(params![EMBEDDING_FINGERPRINT_KEY])?;
+        Ok(match rows.next()? {
+            Some(row) => Some(row.get(0)?),
+            None => None,
+        })
+    }
+
+    fn record_embedding_fingerprint(conn: &Connection, fingerprint: &str) -> anyhow::Result<()> {
+        conn.execute(
+            "INSERT INTO memory_meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![EMBEDDING_FINGERPRINT_KEY, fingerprint],
+        )?;
+        Ok(())
+    }
+
+    /// Compare the active embedder with the one that produced stored vectors.
+    ///
+    /// Stores without a recorded fingerprint adopt the current one. On a
+    /// mismatch the content-hash keyed embedding cache is dropped, since it
+    /// would otherwise hand back vectors from the old space, and stored
+    /// vectors stay stale until [`SqliteMemory::reindex`] runs.
+    fn check_embedding_fingerprint(
+        conn: &Connection,
+        embedder: &dyn EmbeddingProvider,
+    ) -> anyhow::Result<()> {
+        if embedder.dimensions() == 0 {
+            return Ok(());
+        }
+
+        let current = embedder.fingerprint();
+        match Self::stored_embedding_fingerprint(conn)? {
+            None => Self::record_embedding_fingerprint(conn, &current)?,
+            Some(stored) if stored == current => {}
+            Some(stored) => {
+                conn.execute("DELETE FROM embedding_cache", [])?;
+                tracing::warn!(
+                    "memory embeddings were built with '{stored}' but the configured provider is '{current}'; run `zeroclaw memory reindex` to re-embed"
+                );
+            }
+        }
         Ok(())
     }
 
+    /// Fingerprint of the embedding space stored vectors currently belong to.
+    pub fn embedding_fingerprint(&self) -> anyhow::Result<Option<String>> {
+        Self::stored_embedding_fingerprint(&self.conn.lock())
+    }
+
     fn category_to_str(cat: &MemoryCategory) -> String {
         match cat {
             MemoryCategory::Core => "core".into(),
@@ -289,6 +431,7 @@ impl SqliteMemory {
         conn: &Connection,
         query: &str,
         limit: usize,
+        namespace: &NamespaceFilter,
     ) -> anyhow::Result<Vec<(String, f32)>> {
         // Escape FTS5 special chars and build query
         let fts_query: String = query
@@ -301,18 +444,26 @@ impl SqliteMemory {
             return Ok(Vec::new());
         }
 
-        let sql = "SELECT m.id, bm25(memories_fts) as score
-                   FROM memories_fts f
-                   JOIN memories m ON m.rowid = f.rowid
-                   WHERE memories_fts MATCH ?1
-                   ORDER BY score
-                   LIMIT ?2";
+        let sql = format!(
+            "SELECT m.id, bm25(memories_fts) as score
+             FROM memories_fts f
+             JOIN memories m ON m.rowid = f.rowid
+             WHERE memories_fts MATCH ?1 AND {}
+             ORDER BY score
+             LIMIT ?2",
+            Self::namespace_condition(3)
+        );
 
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
         #[allow(clippy::cast_possible_wrap)]
         let limit_i64 = limit as i64;
+        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> =
+            vec![Box::new(fts_query), Box::new(limit_i64)];
+        param_values.extend(Self::namespace_params(namespace));
+        let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+            param_values.iter().map(AsRef::as_ref).collect();
 
-        let rows = stmt.query_map(params![fts_query, limit_i64], |row| {
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
             let id: String = row.get(0)?;
             let score: f64 = row.get(1)?;
             // BM25 returns negative scores (lower = better), negate for ranking
@@ -337,6 +488,7 @@ impl SqliteMemory {
         limit: usize,
         category: Option<&str>,
         session_id: Option<&str>,
+        namespace: &NamespaceFilter,
     ) -> anyhow::Result<Vec<(String, f32)>> {
         let mut sql = "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL".to_string();
         let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
@@ -350,6 +502,11 @@ impl SqliteMemory {
         if let Some(sid) = session_id {
             let _ = write!(sql, " AND session_id = ?{idx}");
             param_values.push(Box::new(sid.to_string()));
+            idx += 1;
+        }
+        if namespace.is_some() {
+            let _ = write!(sql, " AND {}", Self::namespace_condition(idx));
+            param_values.extend(Self::namespace_params(namespace));
         }
 
         let mut stmt = conn.prepare(&sql)?;
@@ -377,7 +534,10 @@ impl SqliteMemory {
     }
 
     /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
+    ///
+    /// When the embedder's fingerprint differs from the recorded one (provider,
+    /// model, or dimensions changed) every memory is re-embedded; otherwise only
+    /// rows missing an embedding are.
     pub async fn reindex(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
@@ -390,11 +550,30 @@ impl SqliteMemory {
             .await??;
         }
 
-        // Step 2: Re-embed all memories that lack embeddings
         if self.embedder.dimensions() == 0 {
             return Ok(0);
         }
 
+        // Step 2: Drop vectors that belong to a different embedding space
+        let fingerprint = self.embedder.fingerprint();
+        {
+            let conn = self.conn.clone();
+            let fingerprint = fingerprint.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                let conn = conn.lock();
+                let stored = Self::stored_embedding_fingerprint(&conn)?;
+                if stored.as_deref() != Some(fingerprint.as_str()) {
+                    let tx = conn.unchecked_transaction()?;
+                    tx.execute("UPDATE memories SET embedding = NULL", [])?;
+                    tx.execute("DELETE FROM embedding_cache", [])?;
+                    tx.commit()?;
+                }
+                Ok(())
+            })
+            .await??;
+        }
+
+        // Step 3: Re-embed all memories that lack embeddings
         let conn = self.conn.clone();
         let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
             let conn = conn.lock();
@@ -426,61 +605,23 @@ impl SqliteMemory {
             }
         }
 
-        Ok(count)
-    }
-}
-
-#[async_trait]
-impl Memory for SqliteMemory {
-    fn name(&self) -> &str {
-        "sqlite"
-    }
-
-    async fn store(
-        &self,
-        key: &str,
-        content: &str,
-        category: MemoryCategory,
-        session_id: Option<&str>,
-    ) -> anyhow::Result<()> {
-        // Compute embedding (async, before blocking work)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
-
+        // Step 4: Stored vectors now match the active embedder
         let conn = self.conn.clone();
-        let key = key.to_string();
-        let content = content.to_string();
-        let sid = session_id.map(String::from);
-
-        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let conn = conn.lock();
-            let now = Local::now().to_rfc3339();
-            let cat = Self::category_to_str(&category);
-            let id = Uuid::new_v4().to_string();
-
-            conn.execute(
-                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                 ON CONFLICT(key) DO UPDATE SET
-                    content = excluded.content,
-                    category = excluded.category,
-                    embedding = excluded.embedding,
-                    updated_at = excluded.updated_at,
-                    session_id = excluded.session_id",
-                params![id, key, content, cat, embedding_bytes, now, now, sid],
-            )?;
-            Ok(())
+        tokio::task::spawn_blocking(move || {
+            Self::record_embedding_fingerprint(&conn.lock(), &fingerprint)
         })
-        .await?
+        .await??;
+
+        Ok(count)
     }
 
-    async fn recall(
+    /// Hybrid recall restricted to `namespace` inside every query.
+    async fn recall_in(
         &self,
         query: &str,
         limit: usize,
         session_id: Option<&str>,
+        namespace: NamespaceFilter,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
         if query.trim().is_empty() {
             return Ok(Vec::new());
@@ -500,11 +641,13 @@ impl Memory for SqliteMemory {
             let session_ref = sid.as_deref();
 
             // FTS5 BM25 keyword search
-            let keyword_results = Self::fts5_search(&conn, &query, limit * 2).unwrap_or_default();
+            let keyword_results =
+                Self::fts5_search(&conn, &query, limit * 2, &namespace).unwrap_or_default();
 
             // Vector similarity search (if embeddings available)
             let vector_results = if let Some(ref qe) = query_embedding {
-                Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                Self::vector_search(&conn, qe, limit * 2, None, session_ref, &namespace)
+                    .unwrap_or_default()
             } else {
                 Vec::new()
             };
@@ -539,7 +682,7 @@ impl Memory for SqliteMemory {
                     .collect::<Vec<_>>()
                     .join(", ");
                 let sql = format!(
-                    "SELECT id, key, content, category, created_at, session_id \
+                    "SELECT id, key, content, category, created_at, session_id, metadata \
                      FROM memories WHERE id IN ({placeholders})"
                 );
                 let mut stmt = conn.prepare(&sql)?;
@@ -557,17 +700,19 @@ impl Memory for SqliteMemory {
                         row.get::<_, String>(3)?,
                         row.get::<_, String>(4)?,
                         row.get::<_, Option<String>>(5)?,
+                        row.get::<_, Option<String>>(6)?,
                     ))
                 })?;
 
                 let mut entry_map = std::collections::HashMap::new();
                 for row in rows {
-                    let (id, key, content, cat, ts, sid) = row?;
-                    entry_map.insert(id, (key, content, cat, ts, sid));
+                    let (id, key, content, cat, ts, sid, meta) = row?;
+                    entry_map.insert(id, (key, content, cat, ts, sid, meta));
                 }
 
                 for scored in &merged {
-                    if let Some((key, content, cat, ts, sid)) = entry_map.remove(&scored.id) {
+                    if let Some((key, content, cat, ts, sid, meta)) = entry_map.remove(&scored.id)
+                    {
                         let entry = MemoryEntry {
                             id: scored.id.clone(),
                             key,
@@ -576,7 +721,11 @@ impl Memory for SqliteMemory {
                             timestamp: ts,
                             session_id: sid,
                             score: Some(f64::from(scored.final_score)),
+                            metadata: Self::metadata_from_column(meta),
                         };
+                        if entry.metadata.is_expired() {
+                            continue;
+                        }
                         if let Some(filter_sid) = session_ref {
                             if entry.session_id.as_deref() != Some(filter_sid) {
                                 continue;
@@ -607,10 +756,11 @@ impl Memory for SqliteMemory {
                         .collect();
                     let where_clause = conditions.join(" OR ");
                     let sql = format!(
-                        "SELECT id, key, content, category, created_at, session_id FROM memories
-                         WHERE {where_clause}
+                        "SELECT id, key, content, category, created_at, session_id, metadata FROM memories
+                         WHERE ({where_clause}) AND {}
                          ORDER BY updated_at DESC
                          LIMIT ?{}",
+                        Self::namespace_condition(keywords.len() * 2 + 2),
                         keywords.len() * 2 + 1
                     );
                     let mut stmt = conn.prepare(&sql)?;
@@ -621,21 +771,20 @@ impl Memory for SqliteMemory {
                     }
                     #[allow(clippy::cast_possible_wrap)]
                     param_values.push(Box::new(limit as i64));
+                    param_values.extend(Self::namespace_params(&namespace));
                     let params_ref: Vec<&dyn rusqlite::types::ToSql> =
                         param_values.iter().map(AsRef::as_ref).collect();
                     let rows = stmt.query_map(params_ref.as_slice(), |row| {
                         Ok(MemoryEntry {
-                            id: row.get(0)?,
-                            key: row.get(1)?,
-                            content: row.get(2)?,
-                            category: Self::str_to_category(&row.get::<_, String>(3)?),
-                            timestamp: row.get(4)?,
-                            session_id: row.get(5)?,
                             score: Some(1.0),
+                            ..Self::row_to_entry(row)?
                         })
                     })?;
                     for row in rows {
                         let entry = row?;
+                        if entry.metadata.is_expired() {
+                            continue;
+                        }
                         if let Some(sid) = session_ref {
                             if entry.session_id.as_deref() != Some(sid) {
                                 continue;
@@ -651,6 +800,116 @@ impl Memory for SqliteMemory {
         })
         .await?
     }
+}
+
+#[async_trait]
+impl Memory for SqliteMemory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        // Compute embedding (async, before blocking work)
+        let embedding_bytes = self
+            .get_or_compute_embedding(content)
+            .await?
+            .map(|emb| vector::vec_to_bytes(&emb));
+
+        let conn = self.conn.clone();
+        let key = key.to_string();
+        let content = content.to_string();
+        let sid = session_id.map(String::from);
+        let metadata_json = Self::metadata_to_column(&metadata)?;
+        // Canonical form keeps the string comparison in expiry sweeps correct
+        let expires_at = metadata
+            .expires_at
+            .as_deref()
+            .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+            .map(|at| format_expiry(at.with_timezone(&Utc)));
+        let namespace = metadata.namespace;
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let conn = conn.lock();
+            let now = Local::now().to_rfc3339();
+            let cat = Self::category_to_str(&category);
+            let id = Uuid::new_v4().to_string();
+
+            conn.execute(
+                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id, metadata, expires_at, namespace)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(key) DO UPDATE SET
+                    content = excluded.content,
+                    category = excluded.category,
+                    embedding = excluded.embedding,
+                    updated_at = excluded.updated_at,
+                    session_id = excluded.session_id,
+                    metadata = excluded.metadata,
+                    expires_at = excluded.expires_at,
+                    namespace = excluded.namespace",
+                params![id, key, content, cat, embedding_bytes, now, now, sid, metadata_json, expires_at, namespace],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_in(query, limit, session_id, None).await
+    }
+
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let namespace = scope.namespace_filter();
+        if tags.is_empty() {
+            return self.recall_in(query, limit, session_id, namespace).await;
+        }
+        let mut entries = self
+            .recall_in(
+                query,
+                limit.saturating_mul(TAG_FILTER_OVERFETCH),
+                session_id,
+                namespace,
+            )
+            .await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        entries.truncate(limit);
+        Ok(entries)
+    }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let conn = self.conn.clone();
@@ -659,23 +918,13 @@ impl Memory for SqliteMemory {
         tokio::task::spawn_blocking(move || -> anyhow::Result<Option<MemoryEntry>> {
             let conn = conn.lock();
             let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories WHERE key = ?1",
+                "SELECT id, key, content, category, created_at, session_id, metadata FROM memories WHERE key = ?1",
             )?;
 
-            let mut rows = stmt.query_map(params![key], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: None,
-                })
-            })?;
+            let mut rows = stmt.query_map(params![key], Self::row_to_entry)?;
 
             match rows.next() {
-                Some(Ok(entry)) => Ok(Some(entry)),
+                Some(Ok(entry)) if !entry.metadata.is_expired() => Ok(Some(entry)),
                 _ => Ok(None),
             }
         })
@@ -696,27 +945,20 @@ impl Memory for SqliteMemory {
         tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
             let conn = conn.lock();
             let session_ref = sid.as_deref();
+            let now = format_expiry(Utc::now());
             let mut results = Vec::new();
 
-            let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: None,
-                })
-            };
-
             if let Some(ref cat) = category {
                 let cat_str = Self::category_to_str(cat);
                 let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
-                     WHERE category = ?1 ORDER BY updated_at DESC LIMIT ?2",
+                    "SELECT id, key, content, category, created_at, session_id, metadata FROM memories
+                     WHERE category = ?1 AND (expires_at IS NULL OR expires_at > ?3)
+                     ORDER BY updated_at DESC LIMIT ?2",
+                )?;
+                let rows = stmt.query_map(
+                    params![cat_str, DEFAULT_LIST_LIMIT, now],
+                    Self::row_to_entry,
                 )?;
-                let rows = stmt.query_map(params![cat_str, DEFAULT_LIST_LIMIT], row_mapper)?;
                 for row in rows {
                     let entry = row?;
                     if let Some(sid) = session_ref {
@@ -728,10 +970,11 @@ impl Memory for SqliteMemory {
                 }
             } else {
                 let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
+                    "SELECT id, key, content, category, created_at, session_id, metadata FROM memories
+                     WHERE expires_at IS NULL OR expires_at > ?2
                      ORDER BY updated_at DESC LIMIT ?1",
                 )?;
-                let rows = stmt.query_map(params![DEFAULT_LIST_LIMIT], row_mapper)?;
+                let rows = stmt.query_map(params![DEFAULT_LIST_LIMIT, now], Self::row_to_entry)?;
                 for row in rows {
                     let entry = row?;
                     if let Some(sid) = session_ref {
@@ -765,14 +1008,143 @@ impl Memory for SqliteMemory {
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
             let conn = conn.lock();
-            let count: i64 =
-                conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM memories WHERE expires_at IS NULL OR expires_at > ?1",
+                params![format_expiry(Utc::now())],
+                |row| row.get(0),
+            )?;
             #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
             Ok(count as usize)
         })
         .await?
     }
 
+    async fn export_records(&self) -> anyhow::Result<Vec<MemoryRecord>> {
+        let conn = self.conn.clone();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryRecord>> {
+            let conn = conn.lock();
+            // Vectors are only portable when we know which space they belong to
+            let fingerprint = Self::stored_embedding_fingerprint(&conn)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, key, content, category, created_at, session_id, metadata, embedding
+                 FROM memories
+                 WHERE expires_at IS NULL OR expires_at > ?1
+                 ORDER BY created_at ASC",
+            )?;
+            let rows = stmt.query_map(params![format_expiry(Utc::now())], |row| {
+                Ok((Self::row_to_entry(row)?, row.get::<_, Option<Vec<u8>>>(7)?))
+            })?;
+
+            let mut records = Vec::new();
+            for row in rows {
+                let (entry, blob) = row?;
+                let embedding = match (&fingerprint, blob) {
+                    (Some(fingerprint), Some(bytes)) if !bytes.is_empty() => {
+                        Some(StoredEmbedding {
+                            fingerprint: fingerprint.clone(),
+                            vector: vector::bytes_to_vec(&bytes),
+                        })
+                    }
+                    _ => None,
+                };
+                records.push(MemoryRecord { entry, embedding });
+            }
+            Ok(records)
+        })
+        .await?
+    }
+
+    async fn import_record(&self, record: MemoryRecord) -> anyhow::Result<()> {
+        let MemoryRecord { entry, embedding } = record;
+        let conn = self.conn.clone();
+
+        // Reuse the archived vector when it lives in this store's embedding space;
+        // a store with no recorded space adopts the archive's.
+        let reusable = {
+            let conn = conn.clone();
+            let archived = embedding.as_ref().map(|e| e.fingerprint.clone());
+            tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
+                let Some(archived) = archived else {
+                    return Ok(false);
+                };
+                let conn = conn.lock();
+                match Self::stored_embedding_fingerprint(&conn)? {
+                    Some(stored) => Ok(stored == archived),
+                    None => {
+                        Self::record_embedding_fingerprint(&conn, &archived)?;
+                        Ok(true)
+                    }
+                }
+            })
+            .await??
+        };
+        let embedding_bytes = match embedding {
+            Some(stored) if reusable => Some(vector::vec_to_bytes(&stored.vector)),
+            _ => self
+                .get_or_compute_embedding(&entry.content)
+                .await?
+                .map(|emb| vector::vec_to_bytes(&emb)),
+        };
+
+        let metadata_json = Self::metadata_to_column(&entry.metadata)?;
+        let expires_at = entry
+            .metadata
+            .expires_at
+            .as_deref()
+            .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+            .map(|at| format_expiry(at.with_timezone(&Utc)));
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let conn = conn.lock();
+            let cat = Self::category_to_str(&entry.category);
+            let id = Uuid::new_v4().to_string();
+
+            conn.execute(
+                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id, metadata, expires_at, namespace)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(key) DO UPDATE SET
+                    content = excluded.content,
+                    category = excluded.category,
+                    embedding = excluded.embedding,
+                    created_at = excluded.created_at,
+                    updated_at = excluded.updated_at,
+                    session_id = excluded.session_id,
+                    metadata = excluded.metadata,
+                    expires_at = excluded.expires_at,
+                    namespace = excluded.namespace",
+                params![
+                    id,
+                    entry.key,
+                    entry.content,
+                    cat,
+                    embedding_bytes,
+                    entry.timestamp,
+                    entry.session_id,
+                    metadata_json,
+                    expires_at,
+                    entry.metadata.namespace
+                ],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn purge_expired(&self) -> anyhow::Result<usize> {
+        let conn = self.conn.clone();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+            let conn = conn.lock();
+            let deleted = conn.execute(
+                "DELETE FROM memories WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+                params![format_expiry(Utc::now())],
+            )?;
+            Ok(deleted)
+        })
+        .await?
+    }
+
     async fn health_check(&self) -> bool {
         let conn = self.conn.clone();
         tokio::task::spawn_blocking(move || conn.lock().execute_batch("SELECT 1").is_ok())
@@ -784,6 +1156,7 @@ impl Memory for SqliteMemory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::traits::MemorySource;
     use tempfile::TempDir;
 
     fn temp_sqlite() -> (TempDir, SqliteMemory) {
@@ -1586,6 +1959,70 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[tokio::test]
+    async fn reindex_reembeds_everything_when_embedder_changes() {
+        use crate::memory::embeddings::HashedNgramEmbedding;
+
+        let tmp = TempDir::new().unwrap();
+        let open = |dims: usize| {
+            SqliteMemory::with_embedder(
+                tmp.path(),
+                Arc::new(HashedNgramEmbedding::new(dims)),
+                0.7,
+                0.3,
+                100,
+                None,
+            )
+            .unwrap()
+        };
+
+        let mem = open(32);
+        mem.store("a", "rust borrow checker", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "tomato seedlings", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(mem.reindex().await.unwrap(), 0);
+        assert_eq!(
+            mem.embedding_fingerprint().unwrap().as_deref(),
+            Some("local:hashed-ngram-v1:32")
+        );
+        drop(mem);
+
+        // Reopening with different dimensions keeps the old fingerprint until reindex.
+        let mem = open(64);
+        assert_eq!(
+            mem.embedding_fingerprint().unwrap().as_deref(),
+            Some("local:hashed-ngram-v1:32")
+        );
+        assert_eq!(mem.reindex().await.unwrap(), 2);
+        assert_eq!(
+            mem.embedding_fingerprint().unwrap().as_deref(),
+            Some("local:hashed-ngram-v1:64")
+        );
+
+        let blob_len: usize = mem
+            .conn
+            .lock()
+            .query_row(
+                "SELECT length(embedding) FROM memories WHERE key = 'a'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(blob_len, 64 * 4);
+
+        let results = mem.recall("borrow checker", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "a");
+    }
+
+    #[test]
+    fn noop_embedder_does_not_record_fingerprint() {
+        let (_tmp, mem) = temp_sqlite();
+        assert!(mem.embedding_fingerprint().unwrap().is_none());
+    }
+
     // ── Edge cases: content_hash ─────────────────────────────────
 
     #[test]
@@ -1787,6 +2224,123 @@ mod tests {
 
     // ── §4.1 Concurrent write contention tests ──────────────
 
+    #[tokio::test]
+    async fn metadata_roundtrips_through_get_list_and_recall() {
+        let (_tmp, mem) = temp_sqlite();
+        let metadata = MemoryMetadata::default()
+            .with_tags(["Travel", "plans"])
+            .with_source(MemorySource {
+                channel: Some("telegram".into()),
+                sender: Some("alice".into()),
+                tool: None,
+            });
+        mem.store_with_metadata(
+            "trip",
+            "Flight to Lisbon in May",
+            MemoryCategory::Core,
+            None,
+            metadata.clone(),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "other",
+            "Lisbon has great trams",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(mem.get("trip").await.unwrap().unwrap().metadata, metadata);
+
+        let tagged = mem
+            .list_tagged(None, None, &["travel".into()])
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].key, "trip");
+
+        let recalled = mem
+            .recall_tagged("Lisbon", 10, None, &["plans".into()])
+            .await
+            .unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].key, "trip");
+        assert_eq!(mem.recall("Lisbon", 10, None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_hidden_and_purged() {
+        let (_tmp, mem) = temp_sqlite();
+        let expired = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00+02:00".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "stale",
+            "Parking in bay 7",
+            MemoryCategory::Core,
+            None,
+            expired,
+        )
+        .await
+        .unwrap();
+        let fresh = MemoryMetadata::default().with_ttl(Duration::from_secs(3600));
+        mem.store_with_metadata(
+            "fresh",
+            "Parking in bay 9",
+            MemoryCategory::Core,
+            None,
+            fresh,
+        )
+        .await
+        .unwrap();
+
+        assert!(mem.get("stale").await.unwrap().is_none());
+        assert!(mem.get("fresh").await.unwrap().is_some());
+        assert_eq!(mem.list(None, None).await.unwrap().len(), 1);
+        let recalled = mem.recall("Parking", 10, None).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].key, "fresh");
+        assert_eq!(mem.count().await.unwrap(), 1);
+
+        assert_eq!(mem.purge_expired().await.unwrap(), 1);
+        assert_eq!(mem.purge_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn very_large_ttl_never_expires() {
+        let (_tmp, mem) = temp_sqlite();
+        let distant = MemoryMetadata::default().with_ttl(Duration::from_secs(u64::MAX));
+        mem.store_with_metadata("forever", "Keep this", MemoryCategory::Core, None, distant)
+            .await
+            .unwrap();
+
+        assert_eq!(mem.purge_expired().await.unwrap(), 0);
+        let entry = mem.get("forever").await.unwrap().expect("entry kept");
+        assert_eq!(
+            entry.metadata.expires_at.as_deref(),
+            Some("9999-12-31T23:59:59Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_store_clears_previous_metadata() {
+        let (_tmp, mem) = temp_sqlite();
+        let metadata = MemoryMetadata::default().with_tags(["pinned"]);
+        mem.store_with_metadata("k", "v1", MemoryCategory::Core, None, metadata)
+            .await
+            .unwrap();
+        mem.store("k", "v2", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let entry = mem.get("k").await.unwrap().unwrap();
+        assert_eq!(entry.content, "v2");
+        assert!(entry.metadata.is_empty());
+    }
+
     #[tokio::test]
     async fn sqlite_concurrent_writes_no_data_loss() {
         let (_tmp, mem) = temp_sqlite();
@@ -1897,4 +2451,98 @@ mod tests {
 
         assert_eq!(mem.count().await.unwrap(), 1);
     }
+
+    #[tokio::test]
+    async fn scoped_recall_filters_namespaces_in_sql() {
+        use crate::memory::namespace::MemoryNamespace;
+
+        let (_tmp, mem) = temp_sqlite();
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let bob = MemoryNamespace::sender("telegram", "bob");
+        // Enough higher-ranked foreign entries to exhaust any over-fetch window
+        for i in 0..20 {
+            mem.store_in_namespace(
+                &bob,
+                &format!("color_{i}"),
+                "favorite color color color is blue",
+                MemoryCategory::Core,
+                None,
+                MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        }
+        mem.store_in_namespace(
+            &alice,
+            "color",
+            "favorite color is green, plus a long tail of unrelated words",
+            MemoryCategory::Core,
+            None,
+            MemoryMetadata::default(),
+        )
+        .await
+        .unwrap();
+        mem.store("team", "team color is red", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let own = RecallScope::Namespace {
+            namespace: alice.clone(),
+            include_shared: false,
+        };
+        let results = mem
+            .recall_scoped(&own, "color", 1, None, &[])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("green"));
+
+        let with_shared = RecallScope::Namespace {
+            namespace: alice,
+            include_shared: true,
+        };
+        let results = mem
+            .recall_scoped(&with_shared, "color", 5, None, &[])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|e| !e.content.contains("blue")));
+    }
+
+    #[tokio::test]
+    async fn namespace_column_is_backfilled_from_metadata() {
+        use crate::memory::namespace::MemoryNamespace;
+
+        let tmp = TempDir::new().unwrap();
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        {
+            let mem = SqliteMemory::new(tmp.path()).unwrap();
+            mem.store_in_namespace(
+                &alice,
+                "k",
+                "v",
+                MemoryCategory::Core,
+                None,
+                MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+            // Roll the schema back to before the namespace column existed
+            mem.conn
+                .lock()
+                .execute_batch(
+                    "DROP INDEX idx_memories_namespace;
+                     ALTER TABLE memories DROP COLUMN namespace;",
+                )
+                .unwrap();
+        }
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let namespace: Option<String> = mem
+            .conn
+            .lock()
+            .query_row("SELECT namespace FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(namespace.as_deref(), Some(alice.as_str()));
+    }
 }
@@ -1,6 +1,14 @@
+use super::namespace::{MemoryNamespace, RecallScope};
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
+/// How many extra candidates tag-filtered recall fetches per requested result.
+pub(crate) const TAG_FILTER_OVERFETCH: usize = 4;
+
+/// How many extra candidates namespace-scoped recall fetches per requested result.
+const NAMESPACE_OVERFETCH: usize = 4;
+
 /// A single memory entry
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -11,6 +19,8 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    #[serde(default)]
+    pub metadata: MemoryMetadata,
 }
 
 impl std::fmt::Debug for MemoryEntry {
@@ -22,10 +32,139 @@ impl std::fmt::Debug for MemoryEntry {
             .field("category", &self.category)
             .field("timestamp", &self.timestamp)
             .field("score", &self.score)
+            .field("metadata", &self.metadata)
             .finish_non_exhaustive()
     }
 }
 
+/// Where a memory came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySource {
+    /// Channel the memory was captured from (e.g. `telegram`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Sender identity on that channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Tool that wrote the memory (e.g. `memory_store`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+/// Structured metadata attached to a memory entry: tags, provenance and expiry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryMetadata {
+    /// Normalized (trimmed, lowercase, deduplicated) labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<MemorySource>,
+    /// RFC 3339 UTC instant after which the entry is hidden and purged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Owning namespace (`channel:<name>` or `sender:<channel>:<id>`); `None` is shared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+impl MemoryMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.source.is_none()
+            && self.expires_at.is_none()
+            && self.namespace.is_none()
+    }
+
+    #[must_use]
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tags = normalize_tags(tags);
+        self
+    }
+
+    #[must_use]
+    pub fn with_source(mut self, source: MemorySource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Expire the entry `ttl` from now, capped at the end of year 9999.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+        let expires_at = Utc::now()
+            .checked_add_signed(ttl)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.expires_at = Some(format_expiry(expires_at));
+        self
+    }
+
+    /// Whether the entry's TTL has elapsed at `now`. Unparseable expiries never expire.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+            .is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Whether the entry carries every tag in `required` (case-insensitive).
+    pub fn has_tags(&self, required: &[String]) -> bool {
+        required.iter().all(|wanted| {
+            let wanted = wanted.trim().to_lowercase();
+            wanted.is_empty() || self.tags.contains(&wanted)
+        })
+    }
+}
+
+/// A stored vector together with the embedding space it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEmbedding {
+    /// [`EmbeddingProvider::fingerprint`](super::embeddings::EmbeddingProvider::fingerprint) of the producer
+    pub fingerprint: String,
+    pub vector: Vec<f32>,
+}
+
+/// A memory entry plus its embedding, as moved by export/import
+#[derive(Debug, Clone)]
+pub struct MemoryRecord {
+    pub entry: MemoryEntry,
+    pub embedding: Option<StoredEmbedding>,
+}
+
+/// Trim, lowercase and deduplicate tags, dropping empty ones.
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.as_ref().trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Latest representable expiry, 9999-12-31T23:59:59Z. Later instants would
+/// need a wider year and break string ordering.
+const MAX_EXPIRY_TIMESTAMP: i64 = 253_402_300_799;
+
+/// Canonical expiry format. Fixed-width UTC so backends can compare expiries
+/// as strings; instants past year 9999 are clamped to its last second.
+pub fn format_expiry(at: DateTime<Utc>) -> String {
+    let latest = DateTime::from_timestamp(MAX_EXPIRY_TIMESTAMP, 0).unwrap_or(at);
+    at.min(latest).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
 /// Memory categories for organization
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -74,9 +213,107 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Store a memory entry with tags, provenance and an optional expiry.
+    ///
+    /// Backends without metadata support fall back to a plain `store`.
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        _metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.store(key, content, category, session_id).await
+    }
+
+    /// Recall memories matching a query that carry every tag in `tags`
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        let mut entries = self
+            .recall(
+                query,
+                limit.saturating_mul(TAG_FILTER_OVERFETCH),
+                session_id,
+            )
+            .await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Store an entry under `key` inside `namespace`.
+    ///
+    /// The stored key is qualified with the namespace so equal keys from
+    /// different tenants never collide; shared entries keep their plain key.
+    async fn store_in_namespace(
+        &self,
+        namespace: &MemoryNamespace,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        mut metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        metadata.namespace = namespace.to_metadata();
+        self.store_with_metadata(
+            &namespace.qualify_key(key),
+            content,
+            category,
+            session_id,
+            metadata,
+        )
+        .await
+    }
+
+    /// Recall tagged memories visible from `scope`
+    async fn recall_scoped(
+        &self,
+        scope: &RecallScope,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if *scope == RecallScope::All {
+            return self.recall_tagged(query, limit, session_id, tags).await;
+        }
+        let mut entries = self
+            .recall_tagged(
+                query,
+                limit.saturating_mul(NAMESPACE_OVERFETCH),
+                session_id,
+                tags,
+            )
+            .await?;
+        entries.retain(|entry| scope.allows(entry));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
+    /// Get the entry stored under `key` inside `namespace`
+    async fn get_in_namespace(
+        &self,
+        namespace: &MemoryNamespace,
+        key: &str,
+    ) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(self
+            .get(&namespace.qualify_key(key))
+            .await?
+            .filter(|entry| MemoryNamespace::of(entry) == *namespace))
+    }
+
     /// List all memory keys, optionally filtered by category and/or session
     async fn list(
         &self,
@@ -84,12 +321,73 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// List memories carrying every tag in `tags`, optionally filtered by category and/or session
+    async fn list_tagged(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut entries = self.list(category, session_id).await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        Ok(entries)
+    }
+
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
+    /// Remove the entry stored under `key` inside `namespace`, leaving
+    /// other namespaces untouched
+    async fn forget_in_namespace(
+        &self,
+        namespace: &MemoryNamespace,
+        key: &str,
+    ) -> anyhow::Result<bool> {
+        if self.get_in_namespace(namespace, key).await?.is_none() {
+            return Ok(false);
+        }
+        self.forget(&namespace.qualify_key(key)).await
+    }
+
     /// Count total memories
     async fn count(&self) -> anyhow::Result<usize>;
 
+    /// Every live entry with its stored embedding, without the `list` page cap.
+    ///
+    /// Backends that do not keep vectors locally export entries only.
+    async fn export_records(&self) -> anyhow::Result<Vec<MemoryRecord>> {
+        Ok(self
+            .list(None, None)
+            .await?
+            .into_iter()
+            .map(|entry| MemoryRecord {
+                entry,
+                embedding: None,
+            })
+            .collect())
+    }
+
+    /// Write an exported record back, upserting by key.
+    ///
+    /// Backends keep the original timestamp and reuse the vector when its
+    /// fingerprint matches their embedding space; the default is a plain store.
+    async fn import_record(&self, record: MemoryRecord) -> anyhow::Result<()> {
+        let MemoryRecord { entry, .. } = record;
+        self.store_with_metadata(
+            &entry.key,
+            &entry.content,
+            entry.category,
+            entry.session_id.as_deref(),
+            entry.metadata,
+        )
+        .await
+    }
+
+    /// Delete entries whose TTL has elapsed, returning how many were removed
+    async fn purge_expired(&self) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
     /// Health check
     async fn health_check(&self) -> bool;
 }
@@ -130,6 +428,13 @@ mod tests {
             timestamp: "2026-02-16T00:00:00Z".into(),
             session_id: Some("session-abc".into()),
             score: Some(0.98),
+            metadata: MemoryMetadata::default()
+                .with_tags(["Travel", "travel", " plans "])
+                .with_source(MemorySource {
+                    channel: Some("telegram".into()),
+                    sender: Some("alice".into()),
+                    tool: None,
+                }),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -141,5 +446,39 @@ mod tests {
         assert_eq!(parsed.category, MemoryCategory::Core);
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
+        assert_eq!(parsed.metadata.tags, vec!["travel", "plans"]);
+        assert_eq!(
+            parsed.metadata.source.and_then(|s| s.channel).as_deref(),
+            Some("telegram")
+        );
+    }
+
+    #[test]
+    fn memory_entry_without_metadata_deserializes() {
+        let json = r#"{"id":"1","key":"k","content":"c","category":"core","timestamp":"t","session_id":null,"score":null}"#;
+        let parsed: MemoryEntry = serde_json::from_str(json).unwrap();
+        assert!(parsed.metadata.is_empty());
+    }
+
+    #[test]
+    fn metadata_expiry_and_tag_matching() {
+        let fresh = MemoryMetadata::default().with_ttl(std::time::Duration::from_secs(3600));
+        assert!(!fresh.is_expired());
+        assert!(fresh.expires_at.as_deref().unwrap().ends_with('Z'));
+
+        let distant = MemoryMetadata::default().with_ttl(std::time::Duration::from_secs(u64::MAX));
+        assert_eq!(distant.expires_at.as_deref(), Some("9999-12-31T23:59:59Z"));
+        assert!(!distant.is_expired());
+
+        let stale = MemoryMetadata {
+            expires_at: Some("2020-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        assert!(stale.is_expired());
+
+        let tagged = MemoryMetadata::default().with_tags(["work", "urgent"]);
+        assert!(tagged.has_tags(&["WORK".into()]));
+        assert!(tagged.has_tags(&[]));
+        assert!(!tagged.has_tags(&["work".into(), "home".into()]));
     }
 }
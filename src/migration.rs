@@ -114,7 +114,7 @@ async fn migrate_openclaw_memory(
 }
 
 fn target_memory_backend(config: &Config) -> Result<Box<dyn Memory>> {
-    memory::create_memory_for_migration(&config.memory.backend, &config.workspace_dir)
+    memory::create_memory_for_migration(&config.memory.backend, config)
 }
 
 fn collect_source_entries(
@@ -27,6 +27,20 @@ impl Observer for LogObserver {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(provider = %provider, model = %model, duration_ms = ms, tokens = ?tokens_used, cost_usd = ?cost_usd, "agent.end");
             }
+            ObserverEvent::RouteDecision {
+                hint,
+                provider,
+                model,
+                reason,
+            } => {
+                info!(
+                    hint = %hint,
+                    provider = %provider,
+                    model = %model,
+                    reason = %reason,
+                    "route.decision"
+                );
+            }
             ObserverEvent::ToolCallStart { tool } => {
                 info!(tool = %tool, "tool.start");
             }
@@ -1,11 +1,8 @@
 pub mod log;
 pub mod multi;
 pub mod noop;
-<<<<<<< HEAD
 pub mod notification;
-=======
 #[cfg(feature = "observability-otel")]
->>>>>>> upstream/main
 pub mod otel;
 pub mod prometheus;
 pub mod runtime_trace;
@@ -24,7 +24,9 @@ impl SystemNotifier {
 
         // Use the first allowed user as the primary recipient for system notifications.
         let Some(primary_user) = telegram_cfg.allowed_users.first() else {
-            tracing::warn!("Telegram notifier: No allowed_users configured, nowhere to send notification");
+            tracing::warn!(
+                "Telegram notifier: No allowed_users configured, nowhere to send notification"
+            );
             return Ok(());
         };
 
@@ -47,11 +49,11 @@ impl SystemNotifier {
             .await
             .context("Failed to send system notification to Telegram")?;
 
-        if !resp.status().is_success() {
+        if resp.status().is_success() {
+            tracing::info!("System notification sent to Telegram user {}", primary_user);
+        } else {
             let err_text = resp.text().await.unwrap_or_default();
             tracing::error!("Telegram notification failed: {}", err_text);
-        } else {
-            tracing::info!("System notification sent to Telegram user {}", primary_user);
         }
 
         Ok(())
@@ -1,6 +1,7 @@
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use prometheus::{
-    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, Registry, TextEncoder,
+    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec,
+    Registry, TextEncoder,
 };
 
 /// Prometheus-backed observer — exposes metrics for scraping via `/metrics`.
@@ -26,6 +27,8 @@ pub struct PrometheusObserver {
     tokens_used: prometheus::IntGauge,
     active_sessions: GaugeVec,
     queue_depth: GaugeVec,
+    provider_circuit_state: IntGaugeVec,
+    provider_circuit_trips: IntGaugeVec,
 }
 
 impl PrometheusObserver {
@@ -128,6 +131,24 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let provider_circuit_state = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_provider_circuit_state",
+                "Provider circuit breaker state (0 = closed, 1 = half-open, 2 = open)",
+            ),
+            &["provider"],
+        )
+        .expect("valid metric");
+
+        let provider_circuit_trips = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_provider_circuit_trips",
+                "Times a provider circuit breaker has opened",
+            ),
+            &["provider"],
+        )
+        .expect("valid metric");
+
         // Register all metrics
         registry.register(Box::new(agent_starts.clone())).ok();
         registry.register(Box::new(llm_requests.clone())).ok();
@@ -145,6 +166,12 @@ impl PrometheusObserver {
         registry.register(Box::new(tokens_used.clone())).ok();
         registry.register(Box::new(active_sessions.clone())).ok();
         registry.register(Box::new(queue_depth.clone())).ok();
+        registry
+            .register(Box::new(provider_circuit_state.clone()))
+            .ok();
+        registry
+            .register(Box::new(provider_circuit_trips.clone()))
+            .ok();
 
         Self {
             registry,
@@ -162,11 +189,23 @@ impl PrometheusObserver {
             tokens_used,
             active_sessions,
             queue_depth,
+            provider_circuit_state,
+            provider_circuit_trips,
         }
     }
 
     /// Encode all registered metrics into Prometheus text exposition format.
     pub fn encode(&self) -> String {
+        // Breaker state lives in the provider layer; sample it at scrape time.
+        for (provider, circuit) in crate::providers::circuit_breaker::snapshot() {
+            self.provider_circuit_state
+                .with_label_values(&[provider.as_str()])
+                .set(circuit.state.as_gauge());
+            self.provider_circuit_trips
+                .with_label_values(&[provider.as_str()])
+                .set(i64::try_from(circuit.trips).unwrap_or(i64::MAX));
+        }
+
         let encoder = TextEncoder::new();
         let families = self.registry.gather();
         let mut buf = Vec::new();
@@ -223,7 +262,8 @@ impl Observer for PrometheusObserver {
             }
             ObserverEvent::ToolCallStart { tool: _ }
             | ObserverEvent::TurnComplete
-            | ObserverEvent::LlmRequest { .. } => {}
+            | ObserverEvent::LlmRequest { .. }
+            | ObserverEvent::RouteDecision { .. } => {}
             ObserverEvent::ToolCall {
                 tool,
                 duration,
@@ -368,6 +408,27 @@ mod tests {
         assert!(output.contains("zeroclaw_request_latency_seconds"));
     }
 
+    #[test]
+    fn encode_exports_provider_circuit_state() {
+        use crate::providers::circuit_breaker::{self, BreakerSettings};
+
+        let provider = format!("prom-circuit-{}", uuid::Uuid::new_v4());
+        let settings = BreakerSettings {
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(3600),
+            half_open_successes: 1,
+        };
+        circuit_breaker::record_failure(&provider, &settings, "connection refused");
+
+        let output = PrometheusObserver::new().encode();
+        assert!(output.contains(&format!(
+            "zeroclaw_provider_circuit_state{{provider=\"{provider}\"}} 2"
+        )));
+        assert!(output.contains(&format!(
+            "zeroclaw_provider_circuit_trips{{provider=\"{provider}\"}} 1"
+        )));
+    }
+
     #[test]
     fn counters_increment_correctly() {
         let obs = PrometheusObserver::new();
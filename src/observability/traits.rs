@@ -39,6 +39,16 @@ pub enum ObserverEvent {
         tokens_used: Option<u64>,
         cost_usd: Option<f64>,
     },
+    /// The model router picked a route for an adaptive `hint:` request.
+    ///
+    /// Emitted when the selected route for a hint changes, with a short
+    /// explanation of the latency, error-rate and price comparison.
+    RouteDecision {
+        hint: String,
+        provider: String,
+        model: String,
+        reason: String,
+    },
     /// A tool call is about to be executed.
     ToolCallStart { tool: String },
     /// A tool call has completed with a success/failure outcome.
@@ -1,13 +1,13 @@
 use crate::config::schema::{
     default_nostr_relays, DingTalkConfig, IrcConfig, LarkReceiveMode, LinqConfig,
-    NextcloudTalkConfig, NostrConfig, QQConfig, QQReceiveMode, SignalConfig, StreamMode,
-    WhatsAppConfig,
+    NextcloudTalkConfig, NostrConfig, QQConfig, QQReceiveMode, RocketChatConfig, SignalConfig,
+    StreamMode, TeamsConfig, WhatsAppConfig, XmppConfig, XmppTlsMode, ZulipConfig,
 };
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    HeartbeatConfig, HttpRequestConfig, IMessageConfig, LarkConfig, MatrixConfig, MemoryConfig,
-    ObservabilityConfig, RuntimeConfig, SecretsConfig, SlackConfig, StorageConfig, TelegramConfig,
-    WebFetchConfig, WebSearchConfig, WebhookConfig,
+    HeartbeatConfig, HttpRequestConfig, IMessageConfig, KnowledgeConfig, LarkConfig, MatrixConfig,
+    MemoryConfig, ModelRoutingConfig, ObservabilityConfig, RuntimeConfig, SecretsConfig,
+    SlackConfig, StorageConfig, TelegramConfig, WebFetchConfig, WebSearchConfig, WebhookConfig,
 };
 use crate::hardware::{self, HardwareConfig};
 use crate::memory::{
@@ -154,12 +154,14 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
         model_routes: Vec::new(),
+        model_routing: ModelRoutingConfig::default(),
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
         goal_loop: crate::config::schema::GoalLoopConfig::default(),
         channels_config,
         memory: memory_config, // User-selected memory backend
+        knowledge: KnowledgeConfig::default(),
         storage: StorageConfig::default(),
         tunnel: tunnel_config,
         gateway: crate::config::GatewayConfig::default(),
@@ -180,8 +182,10 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         hardware: hardware_config,
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
         agents_ipc: crate::config::AgentsIpcConfig::default(),
         model_support_vision: None,
+        model_context_window: None,
     };
 
     println!(
@@ -402,6 +406,10 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         auto_hydrate: true,
         sqlite_open_timeout_secs: None,
         qdrant: crate::config::QdrantConfig::default(),
+        graph: crate::config::MemoryGraphConfig::default(),
+        ranking: crate::config::MemoryRankingConfig::default(),
+        namespaces: crate::config::MemoryNamespaceConfig::default(),
+        consolidation: crate::config::MemoryConsolidationConfig::default(),
     }
 }
 
@@ -513,12 +521,14 @@ async fn run_quick_setup_with_home(
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
         model_routes: Vec::new(),
+        model_routing: ModelRoutingConfig::default(),
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
         goal_loop: crate::config::schema::GoalLoopConfig::default(),
         channels_config: ChannelsConfig::default(),
         memory: memory_config,
+        knowledge: KnowledgeConfig::default(),
         storage: StorageConfig::default(),
         tunnel: crate::config::TunnelConfig::default(),
         gateway: crate::config::GatewayConfig::default(),
@@ -539,8 +549,10 @@ async fn run_quick_setup_with_home(
         hardware: crate::config::HardwareConfig::default(),
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
         agents_ipc: crate::config::AgentsIpcConfig::default(),
         model_support_vision: None,
+        model_context_window: None,
     };
 
     config.save().await?;
@@ -3562,8 +3574,12 @@ enum ChannelMenuChoice {
     WhatsApp,
     Linq,
     Irc,
+    Xmpp,
+    Zulip,
+    RocketChat,
     Webhook,
     NextcloudTalk,
+    Teams,
     DingTalk,
     QqOfficial,
     LarkFeishu,
@@ -3581,8 +3597,12 @@ const CHANNEL_MENU_CHOICES: &[ChannelMenuChoice] = &[
     ChannelMenuChoice::WhatsApp,
     ChannelMenuChoice::Linq,
     ChannelMenuChoice::Irc,
+    ChannelMenuChoice::Xmpp,
+    ChannelMenuChoice::Zulip,
+    ChannelMenuChoice::RocketChat,
     ChannelMenuChoice::Webhook,
     ChannelMenuChoice::NextcloudTalk,
+    ChannelMenuChoice::Teams,
     ChannelMenuChoice::DingTalk,
     ChannelMenuChoice::QqOfficial,
     ChannelMenuChoice::LarkFeishu,
@@ -3679,6 +3699,30 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— IRC over TLS"
                     }
                 ),
+                ChannelMenuChoice::Xmpp => format!(
+                    "XMPP       {}",
+                    if config.xmpp.is_some() {
+                        "✅ configured"
+                    } else {
+                        "— Jabber chats + MUC rooms"
+                    }
+                ),
+                ChannelMenuChoice::Zulip => format!(
+                    "Zulip      {}",
+                    if config.zulip.is_some() {
+                        "✅ configured"
+                    } else {
+                        "— streams + topics"
+                    }
+                ),
+                ChannelMenuChoice::RocketChat => format!(
+                    "Rocket.Chat {}",
+                    if config.rocketchat.is_some() {
+                        "✅ configured"
+                    } else {
+                        "— rooms + threads"
+                    }
+                ),
                 ChannelMenuChoice::Webhook => format!(
                     "Webhook    {}",
                     if config.webhook.is_some() {
@@ -3695,6 +3739,14 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— Talk webhook + OCS API"
                     }
                 ),
+                ChannelMenuChoice::Teams => format!(
+                    "Teams      {}",
+                    if config.teams.is_some() {
+                        "✅ connected"
+                    } else {
+                        "— Azure Bot Service"
+                    }
+                ),
                 ChannelMenuChoice::DingTalk => format!(
                     "DingTalk   {}",
                     if config.dingtalk.is_some() {
@@ -4734,6 +4786,240 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     verify_tls: Some(verify_tls),
                 });
             }
+            ChannelMenuChoice::Xmpp => {
+                // ── XMPP ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("XMPP Setup").white().bold(),
+                    style("— direct client connection").dim()
+                );
+                print_bullet("Create a dedicated account for the bot on your XMPP server.");
+                print_bullet("Connects with STARTTLS on port 5222 and logs in with SASL PLAIN.");
+                println!();
+
+                let jid: String = Input::new()
+                    .with_prompt("  Bot JID (e.g. zeroclaw@example.org)")
+                    .interact_text()?;
+
+                if !jid.trim().contains('@') {
+                    println!(
+                        "  {} Skipped — JID must look like user@domain",
+                        style("→").dim()
+                    );
+                    continue;
+                }
+
+                let password: String = Input::new()
+                    .with_prompt("  Account password")
+                    .interact_text()?;
+
+                if password.trim().is_empty() {
+                    println!("  {} Skipped — password required", style("→").dim());
+                    continue;
+                }
+
+                let server: String = Input::new()
+                    .with_prompt("  Server hostname (Enter to use the JID domain)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let rooms_str: String = Input::new()
+                    .with_prompt("  Rooms to join (comma-separated: room@conference.example.org)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let rooms = rooms_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+
+                print_bullet("Allowlist bare JIDs for chats and nicknames for rooms.");
+                print_bullet("In rooms the bot only answers when its nickname is mentioned.");
+
+                let users_str: String = Input::new()
+                    .with_prompt("  Allowed JIDs/nicknames (comma-separated, or * for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let allowed_users = if users_str.trim() == "*" {
+                    vec!["*".into()]
+                } else {
+                    users_str
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                };
+
+                let verify_tls: bool = Confirm::new()
+                    .with_prompt("  Verify TLS certificate?")
+                    .default(true)
+                    .interact()?;
+
+                println!(
+                    "  {} XMPP configured as {}",
+                    style("✅").green().bold(),
+                    style(jid.trim()).cyan()
+                );
+
+                config.xmpp = Some(XmppConfig {
+                    jid: jid.trim().to_string(),
+                    password: password.trim().to_string(),
+                    server: if server.trim().is_empty() {
+                        None
+                    } else {
+                        Some(server.trim().to_string())
+                    },
+                    port: None,
+                    tls: XmppTlsMode::Starttls,
+                    verify_tls: Some(verify_tls),
+                    resource: "zeroclaw".into(),
+                    nickname: None,
+                    rooms,
+                    allowed_users,
+                    group_reply: None,
+                });
+            }
+            ChannelMenuChoice::Zulip => {
+                // ── Zulip ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("Zulip Setup").white().bold(),
+                    style("— bot account via the events API").dim()
+                );
+                print_bullet("Create a Generic bot under Settings → Personal → Bots.");
+                print_bullet("Copy the bot email and API key from the bot's zuliprc.");
+                println!();
+
+                let site_url: String = Input::new()
+                    .with_prompt("  Zulip URL (e.g. https://example.zulipchat.com)")
+                    .interact_text()?;
+
+                let bot_email: String = Input::new().with_prompt("  Bot email").interact_text()?;
+
+                let api_key: String = Input::new().with_prompt("  API key").interact_text()?;
+
+                if site_url.trim().is_empty()
+                    || bot_email.trim().is_empty()
+                    || api_key.trim().is_empty()
+                {
+                    println!(
+                        "  {} Skipped — URL, bot email and API key are required",
+                        style("→").dim()
+                    );
+                    continue;
+                }
+
+                print_bullet("Allowlist sender emails or user IDs.");
+                print_bullet("In streams the bot only answers when it is @-mentioned.");
+
+                let users_str: String = Input::new()
+                    .with_prompt("  Allowed emails/user IDs (comma-separated, or * for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let allowed_users = if users_str.trim() == "*" {
+                    vec!["*".into()]
+                } else {
+                    users_str
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                };
+
+                println!(
+                    "  {} Zulip configured as {}",
+                    style("✅").green().bold(),
+                    style(bot_email.trim()).cyan()
+                );
+
+                config.zulip = Some(ZulipConfig {
+                    site_url: site_url.trim().trim_end_matches('/').to_string(),
+                    bot_email: bot_email.trim().to_string(),
+                    api_key: api_key.trim().to_string(),
+                    allowed_users,
+                    group_reply: None,
+                    stream_mode: StreamMode::Off,
+                    draft_update_interval_ms: 1000,
+                });
+            }
+            ChannelMenuChoice::RocketChat => {
+                // ── Rocket.Chat ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("Rocket.Chat Setup").white().bold(),
+                    style("— realtime API + REST replies").dim()
+                );
+                print_bullet("Create a bot user, then a personal access token for it.");
+                print_bullet("The token page shows both the user ID and the token.");
+                println!();
+
+                let server_url: String = Input::new()
+                    .with_prompt("  Server URL (e.g. https://chat.example.com)")
+                    .interact_text()?;
+
+                let user_id: String = Input::new().with_prompt("  Bot user ID").interact_text()?;
+
+                let auth_token: String = Input::new()
+                    .with_prompt("  Personal access token")
+                    .interact_text()?;
+
+                if server_url.trim().is_empty()
+                    || user_id.trim().is_empty()
+                    || auth_token.trim().is_empty()
+                {
+                    println!(
+                        "  {} Skipped — server URL, user ID and token are required",
+                        style("→").dim()
+                    );
+                    continue;
+                }
+
+                print_bullet("Allowlist usernames or user IDs.");
+                print_bullet("In rooms the bot only answers when it is @-mentioned.");
+
+                let users_str: String = Input::new()
+                    .with_prompt("  Allowed usernames/user IDs (comma-separated, or * for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let allowed_users = if users_str.trim() == "*" {
+                    vec!["*".into()]
+                } else {
+                    users_str
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                };
+
+                let thread_replies: bool = Confirm::new()
+                    .with_prompt("  Reply to room messages in threads?")
+                    .default(true)
+                    .interact()?;
+
+                println!(
+                    "  {} Rocket.Chat configured for {}",
+                    style("✅").green().bold(),
+                    style(server_url.trim()).cyan()
+                );
+
+                config.rocketchat = Some(RocketChatConfig {
+                    server_url: server_url.trim().trim_end_matches('/').to_string(),
+                    user_id: user_id.trim().to_string(),
+                    auth_token: auth_token.trim().to_string(),
+                    allowed_users,
+                    thread_replies: Some(thread_replies),
+                    group_reply: None,
+                    stream_mode: StreamMode::Off,
+                    draft_update_interval_ms: 1000,
+                });
+            }
             ChannelMenuChoice::Webhook => {
                 // ── Webhook ──
                 println!();
@@ -4834,6 +5120,72 @@ fn setup_channels() -> Result<ChannelsConfig> {
 
                 println!("  {} Nextcloud Talk configured", style("✅").green().bold());
             }
+            ChannelMenuChoice::Teams => {
+                // ── Microsoft Teams ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("Microsoft Teams Setup").white().bold(),
+                    style("— Bot Framework webhook + connector API").dim()
+                );
+                print_bullet(
+                    "1. Create an Azure Bot resource and enable the Microsoft Teams channel.",
+                );
+                print_bullet("2. Set its messaging endpoint to: https://<your-public-url>/teams");
+                print_bullet("3. Copy the Microsoft App ID and create a client secret for it.");
+                println!();
+
+                let app_id: String = Input::new()
+                    .with_prompt("  Microsoft App ID")
+                    .interact_text()?;
+
+                if app_id.trim().is_empty() {
+                    println!("  {} Skipped — app ID required", style("→").dim());
+                    continue;
+                }
+
+                let app_password: String = Input::new()
+                    .with_prompt("  App client secret")
+                    .interact_text()?;
+
+                if app_password.trim().is_empty() {
+                    println!("  {} Skipped — client secret required", style("→").dim());
+                    continue;
+                }
+
+                let tenant_id: String = Input::new()
+                    .with_prompt("  Tenant ID (single-tenant bots only, Enter to skip)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let allowed_users_raw: String = Input::new()
+                    .with_prompt("  Allowed AAD object IDs (comma-separated, or * for all)")
+                    .default("*".into())
+                    .interact_text()?;
+
+                let allowed_users = if allowed_users_raw.trim() == "*" {
+                    vec!["*".into()]
+                } else {
+                    allowed_users_raw
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                };
+
+                config.teams = Some(TeamsConfig {
+                    app_id: app_id.trim().to_string(),
+                    app_password: app_password.trim().to_string(),
+                    tenant_id: if tenant_id.trim().is_empty() {
+                        None
+                    } else {
+                        Some(tenant_id.trim().to_string())
+                    },
+                    allowed_users,
+                });
+
+                println!("  {} Teams configured", style("✅").green().bold());
+            }
             ChannelMenuChoice::DingTalk => {
                 // ── DingTalk ──
                 println!();
@@ -7393,10 +7745,14 @@ mod tests {
     fn channel_menu_choices_include_signal_and_nextcloud_talk() {
         assert!(channel_menu_choices().contains(&ChannelMenuChoice::Signal));
         assert!(channel_menu_choices().contains(&ChannelMenuChoice::NextcloudTalk));
+        assert!(channel_menu_choices().contains(&ChannelMenuChoice::Teams));
+        assert!(channel_menu_choices().contains(&ChannelMenuChoice::Xmpp));
+        assert!(channel_menu_choices().contains(&ChannelMenuChoice::Zulip));
+        assert!(channel_menu_choices().contains(&ChannelMenuChoice::RocketChat));
     }
 
     #[test]
-    fn launchable_channels_include_signal_mattermost_qq_and_nextcloud_talk() {
+    fn launchable_channels_include_team_chats_and_xmpp() {
         let mut channels = ChannelsConfig::default();
         assert!(!has_launchable_channels(&channels));
 
@@ -7439,5 +7795,55 @@ mod tests {
             allowed_users: vec!["*".into()],
         });
         assert!(has_launchable_channels(&channels));
+
+        channels.nextcloud_talk = None;
+        channels.teams = Some(crate::config::schema::TeamsConfig {
+            app_id: "app-id".into(),
+            app_password: "secret".into(),
+            tenant_id: None,
+            allowed_users: vec!["*".into()],
+        });
+        assert!(has_launchable_channels(&channels));
+
+        channels.teams = None;
+        channels.xmpp = Some(crate::config::schema::XmppConfig {
+            jid: "bot@example.org".into(),
+            password: "secret".into(),
+            server: None,
+            port: None,
+            tls: crate::config::schema::XmppTlsMode::Starttls,
+            verify_tls: None,
+            resource: "zeroclaw".into(),
+            nickname: None,
+            rooms: vec![],
+            allowed_users: vec!["*".into()],
+            group_reply: None,
+        });
+        assert!(has_launchable_channels(&channels));
+
+        channels.xmpp = None;
+        channels.zulip = Some(crate::config::schema::ZulipConfig {
+            site_url: "https://zulip.example.com".into(),
+            bot_email: "bot@zulip.example.com".into(),
+            api_key: "key".into(),
+            allowed_users: vec!["*".into()],
+            group_reply: None,
+            stream_mode: StreamMode::Off,
+            draft_update_interval_ms: 1000,
+        });
+        assert!(has_launchable_channels(&channels));
+
+        channels.zulip = None;
+        channels.rocketchat = Some(crate::config::schema::RocketChatConfig {
+            server_url: "https://chat.example.com".into(),
+            user_id: "bot-id".into(),
+            auth_token: "token".into(),
+            allowed_users: vec!["*".into()],
+            thread_replies: None,
+            group_reply: None,
+            stream_mode: StreamMode::Off,
+            draft_update_interval_ms: 1000,
+        });
+        assert!(has_launchable_channels(&channels));
     }
 }
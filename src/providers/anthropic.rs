@@ -1,15 +1,22 @@
+use crate::providers::batch::{self, BatchOutcome, BatchRequest, BatchStatus};
+use crate::providers::quota_adapter::QuotaTracker;
+use crate::providers::quota_types::QuotaMetadata;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, ResponseFormat, StreamError, StreamEvent, StreamResult,
+    TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 pub struct AnthropicProvider {
     credential: Option<String>,
     base_url: String,
+    /// Latest rate-limit quota from response headers.
+    quota: QuotaTracker,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +58,10 @@ struct NativeChatRequest<'a> {
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -148,6 +159,28 @@ struct AnthropicUsage {
     input_tokens: Option<u64>,
     #[serde(default)]
     output_tokens: Option<u64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<u64>,
+}
+
+impl AnthropicUsage {
+    /// Anthropic reports cache reads/writes separately from `input_tokens`;
+    /// fold them into the prompt total.
+    fn into_token_usage(self) -> TokenUsage {
+        let input_tokens = self.input_tokens.map(|uncached| {
+            uncached
+                + self.cache_read_input_tokens.unwrap_or(0)
+                + self.cache_creation_input_tokens.unwrap_or(0)
+        });
+        TokenUsage {
+            input_tokens,
+            output_tokens: self.output_tokens,
+            cached_input_tokens: self.cache_read_input_tokens,
+            cache_write_tokens: self.cache_creation_input_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -164,6 +197,112 @@ struct NativeContentIn {
     input: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct NativeStreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    message: Option<NativeStreamMessage>,
+    #[serde(default)]
+    content_block: Option<NativeContentIn>,
+    #[serde(default)]
+    delta: Option<NativeStreamDelta>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+    #[serde(default)]
+    error: Option<NativeStreamError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamMessage {
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamDelta {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    partial_json: Option<String>,
+    #[serde(default)]
+    thinking: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamError {
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchSubmitRequest {
+    requests: Vec<BatchItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchItem {
+    custom_id: String,
+    params: ChatRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageBatch {
+    id: String,
+    processing_status: String,
+    #[serde(default)]
+    results_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResultLine {
+    custom_id: String,
+    result: BatchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResult {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    message: Option<NativeChatResponse>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+impl BatchResultLine {
+    fn into_outcome(self) -> BatchOutcome {
+        match (self.result.kind.as_str(), self.result.message) {
+            ("succeeded", Some(message)) => {
+                let text = message
+                    .content
+                    .into_iter()
+                    .filter(|block| block.kind == "text")
+                    .filter_map(|block| block.text)
+                    .collect::<String>();
+                let usage = message.usage.map(AnthropicUsage::into_token_usage);
+                BatchOutcome::success(self.custom_id, text, usage)
+            }
+            ("errored", _) => {
+                let message = self
+                    .result
+                    .error
+                    .as_ref()
+                    .and_then(|e| {
+                        e.pointer("/error/message")
+                            .or_else(|| e.get("message"))
+                            .and_then(serde_json::Value::as_str)
+                    })
+                    .unwrap_or("request errored")
+                    .to_string();
+                BatchOutcome::failure(self.custom_id, message)
+            }
+            (kind, _) => BatchOutcome::failure(self.custom_id, format!("request {kind}")),
+        }
+    }
+}
+
 impl AnthropicProvider {
     pub fn new(credential: Option<&str>) -> Self {
         Self::with_base_url(credential, None)
@@ -180,6 +319,7 @@ impl AnthropicProvider {
                 .filter(|k| !k.is_empty())
                 .map(ToString::to_string),
             base_url,
+            quota: QuotaTracker::default(),
         }
     }
 
@@ -413,14 +553,111 @@ impl AnthropicProvider {
             .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
     }
 
+    async fn send_messages_request(
+        &self,
+        native_request: &NativeChatRequest<'_>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
+            )
+        })?;
+
+        let req = self
+            .http_client()
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(native_request);
+
+        let response = self.apply_auth(req, credential).send().await?;
+        self.quota.observe("anthropic", response.headers());
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+        Ok(response)
+    }
+
+    /// Map one Messages API SSE `data:` payload to stream events.
+    fn parse_stream_event(data: &str) -> StreamResult<Vec<StreamEvent>> {
+        let event: NativeStreamEvent = serde_json::from_str(data).map_err(StreamError::Json)?;
+        let index = event.index.unwrap_or_default();
+        let usage_event = |usage: AnthropicUsage| StreamEvent::Usage(usage.into_token_usage());
+
+        let events = match event.kind.as_str() {
+            "message_start" => event
+                .message
+                .and_then(|message| message.usage)
+                .map(usage_event)
+                .into_iter()
+                .collect(),
+            "message_delta" => event.usage.map(usage_event).into_iter().collect(),
+            "content_block_start" => match event.content_block {
+                Some(block) if block.kind == "tool_use" => vec![StreamEvent::ToolCallDelta {
+                    index,
+                    id: block.id,
+                    name: block.name,
+                    arguments: String::new(),
+                }],
+                Some(block) => block
+                    .text
+                    .filter(|text| !text.is_empty())
+                    .map(StreamEvent::TextDelta)
+                    .into_iter()
+                    .collect(),
+                None => Vec::new(),
+            },
+            "content_block_delta" => match event.delta {
+                Some(NativeStreamDelta {
+                    text: Some(text), ..
+                }) => vec![StreamEvent::TextDelta(text)],
+                Some(NativeStreamDelta {
+                    partial_json: Some(arguments),
+                    ..
+                }) => vec![StreamEvent::ToolCallDelta {
+                    index,
+                    id: None,
+                    name: None,
+                    arguments,
+                }],
+                Some(NativeStreamDelta {
+                    thinking: Some(thinking),
+                    ..
+                }) => vec![StreamEvent::ReasoningDelta(thinking)],
+                _ => Vec::new(),
+            },
+            "error" => {
+                let message = event
+                    .error
+                    .map_or_else(|| "unknown stream error".to_string(), |e| e.message);
+                return Err(StreamError::Provider(super::sanitize_api_error(&message)));
+            }
+            _ => Vec::new(),
+        };
+        Ok(events)
+    }
+
+    /// Move the forced structured-output tool call into the reply text.
+    fn take_structured_reply(
+        mut response: ProviderChatResponse,
+        tool_name: &str,
+    ) -> ProviderChatResponse {
+        if let Some(pos) = response
+            .tool_calls
+            .iter()
+            .position(|call| call.name == tool_name)
+        {
+            let call = response.tool_calls.remove(pos);
+            response.text = Some(call.arguments);
+        }
+        response
+    }
+
     fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
-        let usage = response.usage.map(|u| TokenUsage {
-            input_tokens: u.input_tokens,
-            output_tokens: u.output_tokens,
-        });
+        let usage = response.usage.map(AnthropicUsage::into_token_usage);
 
         for block in response.content {
             match block.kind.as_str() {
@@ -464,6 +701,55 @@ impl AnthropicProvider {
     fn http_client(&self) -> Client {
         crate::config::build_runtime_proxy_client_with_timeouts("provider.anthropic", 120, 10)
     }
+
+    fn require_credential(&self) -> anyhow::Result<&str> {
+        self.credential.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
+            )
+        })
+    }
+
+    fn batch_submit_request(
+        requests: &[BatchRequest],
+        model: &str,
+        temperature: f64,
+    ) -> BatchSubmitRequest {
+        let requests = requests
+            .iter()
+            .map(|request| {
+                let (system, messages) = batch::split_system(&request.messages);
+                BatchItem {
+                    custom_id: request.custom_id.clone(),
+                    params: ChatRequest {
+                        model: model.to_string(),
+                        max_tokens: batch::BATCH_MAX_TOKENS,
+                        system,
+                        messages: messages
+                            .into_iter()
+                            .map(|m| Message {
+                                role: if m.role == "assistant" {
+                                    "assistant".to_string()
+                                } else {
+                                    "user".to_string()
+                                },
+                                content: m.content.clone(),
+                            })
+                            .collect(),
+                        temperature,
+                    },
+                }
+            })
+            .collect();
+        BatchSubmitRequest { requests }
+    }
+
+    fn parse_batch_results(body: &str) -> anyhow::Result<Vec<BatchOutcome>> {
+        Ok(batch::parse_jsonl::<BatchResultLine>(body)?
+            .into_iter()
+            .map(BatchResultLine::into_outcome)
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -502,6 +788,7 @@ impl Provider for AnthropicProvider {
         request = self.apply_auth(request, credential);
 
         let response = request.send().await?;
+        self.quota.observe("anthropic", response.headers());
 
         if !response.status().is_success() {
             return Err(super::api_error("Anthropic", response).await);
@@ -517,12 +804,6 @@ impl Provider for AnthropicProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
-            )
-        })?;
-
         let (system_prompt, mut messages) = Self::convert_messages(request.messages);
 
         // Auto-cache last message if conversation is long
@@ -530,29 +811,84 @@ impl Provider for AnthropicProvider {
             Self::apply_cache_to_last_message(&mut messages);
         }
 
+        // Anthropic has no JSON mode: structured output is a forced call to a
+        // tool whose input schema is the requested schema.
+        let format_schema = request.response_format.map(ResponseFormat::schema);
+        let (tools, tool_choice) = match (request.response_format, &format_schema) {
+            (Some(format), Some(schema)) => (
+                Some(vec![NativeToolSpec {
+                    name: format.name(),
+                    description: "Return the final answer as structured data.",
+                    input_schema: schema,
+                    cache_control: None,
+                }]),
+                Some(serde_json::json!({ "type": "tool", "name": format.name() })),
+            ),
+            _ => (Self::convert_tools(request.tools), None),
+        };
+
         let native_request = NativeChatRequest {
             model: model.to_string(),
             max_tokens: 4096,
             system: system_prompt,
             messages,
             temperature,
-            tools: Self::convert_tools(request.tools),
+            tools,
+            tool_choice,
+            stream: None,
         };
 
-        let req = self
-            .http_client()
-            .post(format!("{}/v1/messages", self.base_url))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&native_request);
+        let response = self.send_messages_request(&native_request).await?;
+        let native_response: NativeChatResponse = response.json().await?;
+        let parsed = Self::parse_native_response(native_response);
+        Ok(match request.response_format {
+            Some(format) => Self::take_structured_reply(parsed, format.name()),
+            None => parsed,
+        })
+    }
 
-        let response = self.apply_auth(req, credential).send().await?;
-        if !response.status().is_success() {
-            return Err(super::api_error("Anthropic", response).await);
+    fn quota(&self) -> Option<QuotaMetadata> {
+        self.quota.latest()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        // The forced structured-output tool call is rewritten after the fact,
+        // so structured requests are answered whole.
+        if request.response_format.is_some() {
+            let response = self.chat(request, model, temperature).await?;
+            return Ok(super::streaming::replay(response));
         }
 
-        let native_response: NativeChatResponse = response.json().await?;
-        Ok(Self::parse_native_response(native_response))
+        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
+        if Self::should_cache_conversation(request.messages) {
+            Self::apply_cache_to_last_message(&mut messages);
+        }
+
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system: system_prompt,
+            messages,
+            temperature,
+            tools: Self::convert_tools(request.tools),
+            tool_choice: None,
+            stream: Some(true),
+        };
+
+        let response = self.send_messages_request(&native_request).await?;
+        Ok(super::streaming::sse_events(
+            response,
+            Self::parse_stream_event,
+        ))
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -563,6 +899,7 @@ impl Provider for AnthropicProvider {
         ProviderCapabilities {
             native_tool_calling: true,
             vision: true,
+            structured_output: true,
         }
     }
 
@@ -609,10 +946,68 @@ impl Provider for AnthropicProvider {
             } else {
                 Some(&tool_specs)
             },
+            response_format: None,
         };
         self.chat(request, model, temperature).await
     }
 
+    fn supports_batch(&self) -> bool {
+        true
+    }
+
+    async fn submit_batch(
+        &self,
+        requests: &[BatchRequest],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let credential = self.require_credential()?;
+        let request = self
+            .http_client()
+            .post(format!("{}/v1/messages/batches", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .json(&Self::batch_submit_request(requests, model, temperature));
+        let response = self.apply_auth(request, credential).send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+        let batch: MessageBatch = response.json().await?;
+        Ok(batch.id)
+    }
+
+    async fn poll_batch(&self, batch_id: &str) -> anyhow::Result<BatchStatus> {
+        let credential = self.require_credential()?;
+        let request = self
+            .http_client()
+            .get(format!("{}/v1/messages/batches/{batch_id}", self.base_url))
+            .header("anthropic-version", "2023-06-01");
+        let response = self.apply_auth(request, credential).send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+        let batch: MessageBatch = response.json().await?;
+        if batch.processing_status != "ended" {
+            return Ok(BatchStatus::Pending);
+        }
+        let Some(results_url) = batch.results_url else {
+            return Ok(BatchStatus::Failed(format!(
+                "batch {} ended without results",
+                batch.id
+            )));
+        };
+
+        let request = self
+            .http_client()
+            .get(results_url)
+            .header("anthropic-version", "2023-06-01");
+        let response = self.apply_auth(request, credential).send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+        let body = response.text().await?;
+        Ok(BatchStatus::Completed(Self::parse_batch_results(&body)?))
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(credential) = self.credential.as_ref() {
             let mut request = self
@@ -733,6 +1128,38 @@ mod tests {
         assert!(request.headers().get("x-api-key").is_none());
     }
 
+    #[test]
+    fn batch_submit_request_moves_system_prompt_into_params() {
+        let requests = [BatchRequest {
+            custom_id: "job-1".into(),
+            messages: vec![ChatMessage::system("Be brief."), ChatMessage::user("hi")],
+        }];
+        let body = serde_json::to_value(AnthropicProvider::batch_submit_request(
+            &requests,
+            "claude-3-haiku",
+            0.3,
+        ))
+        .unwrap();
+        let item = &body["requests"][0];
+        assert_eq!(item["custom_id"], "job-1");
+        assert_eq!(item["params"]["system"], "Be brief.");
+        assert_eq!(item["params"]["messages"][0]["role"], "user");
+        assert_eq!(item["params"]["messages"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_batch_results_maps_result_types() {
+        let body = r#"{"custom_id":"a","result":{"type":"succeeded","message":{"content":[{"type":"text","text":"hi"}],"usage":{"input_tokens":5,"output_tokens":1}}}}
+{"custom_id":"b","result":{"type":"errored","error":{"type":"error","error":{"type":"invalid_request_error","message":"too long"}}}}
+{"custom_id":"c","result":{"type":"expired"}}"#;
+        let outcomes = AnthropicProvider::parse_batch_results(body).unwrap();
+        let ok = outcomes[0].result.as_ref().unwrap();
+        assert_eq!(ok.text, "hi");
+        assert_eq!(ok.usage.as_ref().unwrap().output_tokens, Some(1));
+        assert_eq!(outcomes[1].result.as_ref().unwrap_err(), "too long");
+        assert_eq!(outcomes[2].result.as_ref().unwrap_err(), "request expired");
+    }
+
     #[test]
     fn apply_auth_uses_x_api_key_for_regular_tokens() {
         let provider = AnthropicProvider::new(None);
@@ -1208,6 +1635,8 @@ mod tests {
             }],
             temperature: 0.7,
             tools: None,
+            tool_choice: None,
+            stream: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1297,6 +1726,7 @@ mod tests {
         let provider = AnthropicProvider {
             credential: Some("test-key".to_string()),
             base_url: format!("http://{addr}"),
+            quota: QuotaTracker::default(),
         };
 
         // Multi-turn conversation: system → user (Go code) → assistant (code response) → user (follow-up)
@@ -1398,6 +1828,26 @@ mod tests {
         assert_eq!(usage.output_tokens, Some(75));
     }
 
+    #[test]
+    fn native_response_folds_cache_tokens_into_input_total() {
+        let json = r#"{
+            "content": [{"type": "text", "text": "Hello"}],
+            "usage": {
+                "input_tokens": 20,
+                "output_tokens": 75,
+                "cache_read_input_tokens": 4000,
+                "cache_creation_input_tokens": 500
+            }
+        }"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let usage = AnthropicProvider::parse_native_response(resp)
+            .usage
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(4520));
+        assert_eq!(usage.cached_input_tokens, Some(4000));
+        assert_eq!(usage.cache_write_tokens, Some(500));
+    }
+
     #[test]
     fn native_response_parses_without_usage() {
         let json = r#"{"content": [{"type": "text", "text": "Hello"}]}"#;
@@ -1406,6 +1856,55 @@ mod tests {
         assert!(result.usage.is_none());
     }
 
+    #[test]
+    fn structured_reply_moves_forced_tool_input_into_text() {
+        let json = r#"{"content": [
+            {"type": "tool_use", "id": "t1", "name": "verdict", "input": {"ok": true}}
+        ]}"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let result = AnthropicProvider::take_structured_reply(
+            AnthropicProvider::parse_native_response(resp),
+            "verdict",
+        );
+        assert!(result.tool_calls.is_empty());
+        assert_eq!(result.text.as_deref(), Some(r#"{"ok":true}"#));
+    }
+
+    #[test]
+    fn stream_events_map_text_tool_input_and_usage() {
+        let payloads = [
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":25,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Let me look."}}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"shell","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"command\":"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"\"ls\"}"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":42}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+        let mut acc = crate::providers::streaming::StreamAccumulator::new();
+        for payload in payloads {
+            for event in AnthropicProvider::parse_stream_event(payload).unwrap() {
+                acc.push(event);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Let me look."));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "toolu_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        let usage = response.usage.unwrap();
+        assert_eq!(
+            (usage.input_tokens, usage.output_tokens),
+            (Some(25), Some(42))
+        );
+
+        let err = AnthropicProvider::parse_stream_event(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+
     #[test]
     fn capabilities_reports_vision_and_native_tool_calling() {
         let provider = AnthropicProvider::new(Some("test-key"));
@@ -0,0 +1,127 @@
+//! Asynchronous batch requests for providers with discounted batch endpoints.
+//!
+//! A batch is a set of independent single-turn completions submitted in one
+//! job. Providers accept it, process it within their completion window
+//! (typically up to 24 hours) and bill it at a discount. Callers submit with
+//! [`Provider::submit_batch`](super::traits::Provider::submit_batch), keep the
+//! returned id, and poll with
+//! [`Provider::poll_batch`](super::traits::Provider::poll_batch).
+
+use super::traits::{ChatMessage, TokenUsage};
+use serde::de::DeserializeOwned;
+
+/// Default completion budget for batched requests.
+pub(crate) const BATCH_MAX_TOKENS: u32 = 4096;
+
+/// One completion in a batch. `custom_id` ties the result back to the caller
+/// and must be unique within the batch (`[A-Za-z0-9_-]`, at most 64 chars).
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    pub custom_id: String,
+    pub messages: Vec<ChatMessage>,
+}
+
+/// State of a submitted batch.
+#[derive(Debug)]
+pub enum BatchStatus {
+    /// Accepted but not finished; poll again later.
+    Pending,
+    /// Finished. One outcome per request the provider returned; requests
+    /// without an outcome did not run (e.g. the batch expired).
+    Completed(Vec<BatchOutcome>),
+    /// The batch as a whole failed, was cancelled or expired without output.
+    Failed(String),
+}
+
+/// Result of a single request in a completed batch.
+#[derive(Debug)]
+pub struct BatchOutcome {
+    pub custom_id: String,
+    pub result: Result<BatchOutput, String>,
+}
+
+/// Reply text and usage of a successful batched request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOutput {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+impl BatchOutcome {
+    pub fn success(custom_id: impl Into<String>, text: String, usage: Option<TokenUsage>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            result: Ok(BatchOutput { text, usage }),
+        }
+    }
+
+    pub fn failure(custom_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            result: Err(error.into()),
+        }
+    }
+}
+
+/// Split the system prompt from the rest of a single-turn conversation.
+pub(crate) fn split_system(messages: &[ChatMessage]) -> (Option<String>, Vec<&ChatMessage>) {
+    let system = messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>();
+    let rest = messages.iter().filter(|m| m.role != "system").collect();
+    let system = (!system.is_empty()).then(|| system.join("\n\n"));
+    (system, rest)
+}
+
+/// Serialize values as JSON Lines.
+pub(crate) fn to_jsonl<T: serde::Serialize>(lines: &[T]) -> anyhow::Result<String> {
+    let mut body = String::new();
+    for line in lines {
+        body.push_str(&serde_json::to_string(line)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// Parse a JSON Lines body, skipping blank lines.
+pub(crate) fn parse_jsonl<T: DeserializeOwned>(body: &str) -> anyhow::Result<Vec<T>> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("Invalid batch result line: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_system_joins_system_messages() {
+        let messages = [
+            ChatMessage::system("a"),
+            ChatMessage::user("hi"),
+            ChatMessage::system("b"),
+        ];
+        let (system, rest) = split_system(&messages);
+        assert_eq!(system.as_deref(), Some("a\n\nb"));
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].content, "hi");
+
+        let (system, _) = split_system(&[ChatMessage::user("hi")]);
+        assert!(system.is_none());
+    }
+
+    #[test]
+    fn jsonl_round_trips_and_skips_blank_lines() {
+        let body = to_jsonl(&[serde_json::json!({"a": 1}), serde_json::json!({"a": 2})]).unwrap();
+        assert_eq!(body, "{\"a\":1}\n{\"a\":2}\n");
+        let parsed: Vec<serde_json::Value> = parse_jsonl(&format!("{body}\n  \n")).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parse_jsonl::<serde_json::Value>("{oops").is_err());
+    }
+}
@@ -4,10 +4,11 @@
 //! via environment variables. SigV4 signing is implemented manually
 //! using hmac/sha2 crates — no AWS SDK dependency.
 
+use crate::providers::batch::{self, BatchOutcome, BatchRequest, BatchStatus};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, StreamChunk, StreamError, StreamOptions, StreamResult,
-    TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
+    Provider, ProviderCapabilities, StreamChunk, StreamError, StreamEvent, StreamOptions,
+    StreamResult, TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -23,10 +24,15 @@ const ENDPOINT_PREFIX: &str = "bedrock-runtime";
 const SIGNING_SERVICE: &str = "bedrock";
 const DEFAULT_REGION: &str = "us-east-1";
 const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// S3 location (`s3://bucket/prefix`) for batch inference input and output.
+const BATCH_S3_URI_ENV: &str = "BEDROCK_BATCH_S3_URI";
+/// IAM service role Bedrock assumes to read and write the batch S3 location.
+const BATCH_ROLE_ARN_ENV: &str = "BEDROCK_BATCH_ROLE_ARN";
 
 // ── AWS Credentials ─────────────────────────────────────────────
 
 /// Resolved AWS credentials for SigV4 signing.
+#[derive(Clone)]
 struct AwsCredentials {
     access_key_id: String,
     secret_access_key: String,
@@ -147,6 +153,14 @@ impl AwsCredentials {
     }
 }
 
+/// Regional runtime endpoint for `AWS_REGION` (or `AWS_DEFAULT_REGION`).
+pub(crate) fn runtime_endpoint_from_env() -> String {
+    let region = env_optional("AWS_REGION")
+        .or_else(|| env_optional("AWS_DEFAULT_REGION"))
+        .unwrap_or_else(|| DEFAULT_REGION.to_string());
+    format!("https://{ENDPOINT_PREFIX}.{region}.amazonaws.com")
+}
+
 fn env_required(name: &str) -> anyhow::Result<String> {
     std::env::var(name)
         .ok()
@@ -195,6 +209,30 @@ fn build_authorization_header(
     headers: &[(String, String)],
     payload: &[u8],
     timestamp: &chrono::DateTime<chrono::Utc>,
+) -> String {
+    build_authorization_header_for_service(
+        credentials,
+        SIGNING_SERVICE,
+        method,
+        canonical_uri,
+        query_string,
+        headers,
+        payload,
+        timestamp,
+    )
+}
+
+/// [`build_authorization_header`] for an arbitrary AWS service (e.g. `s3`).
+#[allow(clippy::too_many_arguments)]
+fn build_authorization_header_for_service(
+    credentials: &AwsCredentials,
+    service: &str,
+    method: &str,
+    canonical_uri: &str,
+    query_string: &str,
+    headers: &[(String, String)],
+    payload: &[u8],
+    timestamp: &chrono::DateTime<chrono::Utc>,
 ) -> String {
     let date_stamp = timestamp.format("%Y%m%d").to_string();
     let amz_date = timestamp.format("%Y%m%dT%H%M%SZ").to_string();
@@ -219,10 +257,7 @@ fn build_authorization_header(
         "{method}\n{canonical_uri}\n{query_string}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
     );
 
-    let credential_scope = format!(
-        "{date_stamp}/{}/{SIGNING_SERVICE}/aws4_request",
-        credentials.region
-    );
+    let credential_scope = format!("{date_stamp}/{}/{service}/aws4_request", credentials.region);
 
     let string_to_sign = format!(
         "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
@@ -233,7 +268,7 @@ fn build_authorization_header(
         &credentials.secret_access_key,
         &date_stamp,
         &credentials.region,
-        SIGNING_SERVICE,
+        service,
     );
 
     let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
@@ -244,6 +279,186 @@ fn build_authorization_header(
     )
 }
 
+/// Sign a request for `service` and return the headers to send with it,
+/// `authorization` included. S3 additionally requires the payload hash header.
+fn signed_headers(
+    credentials: &AwsCredentials,
+    service: &str,
+    method: &str,
+    host: &str,
+    canonical_uri: &str,
+    payload: &[u8],
+    content_type: Option<&str>,
+) -> Vec<(String, String)> {
+    let now = chrono::Utc::now();
+    let mut headers = vec![
+        ("host".to_string(), host.to_string()),
+        (
+            "x-amz-date".to_string(),
+            now.format("%Y%m%dT%H%M%SZ").to_string(),
+        ),
+    ];
+    if let Some(content_type) = content_type {
+        headers.push(("content-type".to_string(), content_type.to_string()));
+    }
+    if service == "s3" {
+        headers.push(("x-amz-content-sha256".to_string(), sha256_hex(payload)));
+    }
+    if let Some(ref token) = credentials.session_token {
+        headers.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    headers.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let authorization = build_authorization_header_for_service(
+        credentials,
+        service,
+        method,
+        canonical_uri,
+        "",
+        &headers,
+        payload,
+        &now,
+    );
+    headers.retain(|(name, _)| name != "host");
+    headers.push(("authorization".to_string(), authorization));
+    headers
+}
+
+// ── Batch Inference ─────────────────────────────────────────────
+
+/// Split `s3://bucket/prefix` into bucket and prefix (without slashes at
+/// either end).
+fn parse_s3_uri(uri: &str) -> anyhow::Result<(String, String)> {
+    let rest = uri
+        .trim()
+        .strip_prefix("s3://")
+        .ok_or_else(|| anyhow::anyhow!("S3 URI must start with s3://: {uri}"))?;
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    anyhow::ensure!(!bucket.is_empty(), "S3 URI has no bucket: {uri}");
+    Ok((bucket.to_string(), prefix.trim_matches('/').to_string()))
+}
+
+/// Batch records carry the model's native InvokeModel body, not Converse.
+fn batch_model_input(
+    request: &BatchRequest,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<serde_json::Value> {
+    let (system, messages) = batch::split_system(&request.messages);
+    let role = |m: &ChatMessage| {
+        if m.role == "assistant" {
+            "assistant"
+        } else {
+            "user"
+        }
+    };
+    if model.contains("anthropic.") {
+        let mut input = serde_json::json!({
+            "anthropic_version": "bedrock-2023-05-31",
+            "max_tokens": batch::BATCH_MAX_TOKENS,
+            "temperature": temperature,
+            "messages": messages
+                .iter()
+                .map(|m| serde_json::json!({ "role": role(m), "content": m.content }))
+                .collect::<Vec<_>>(),
+        });
+        if let Some(system) = system {
+            input["system"] = serde_json::Value::String(system);
+        }
+        Ok(input)
+    } else if model.contains("amazon.nova") {
+        let mut input = serde_json::json!({
+            "schemaVersion": "messages-v1",
+            "inferenceConfig": {
+                "maxTokens": batch::BATCH_MAX_TOKENS,
+                "temperature": temperature,
+            },
+            "messages": messages
+                .iter()
+                .map(|m| serde_json::json!({ "role": role(m), "content": [{ "text": m.content }] }))
+                .collect::<Vec<_>>(),
+        });
+        if let Some(system) = system {
+            input["system"] = serde_json::json!([{ "text": system }]);
+        }
+        Ok(input)
+    } else {
+        anyhow::bail!(
+            "Bedrock batch inference supports Anthropic Claude and Amazon Nova models, not '{model}'"
+        )
+    }
+}
+
+/// Map one line of a batch job's `.jsonl.out` file to an outcome.
+fn parse_batch_output_line(line: &serde_json::Value) -> BatchOutcome {
+    let record_id = line
+        .get("recordId")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    if let Some(error) = line.get("error") {
+        let message = error
+            .get("errorMessage")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("record failed");
+        return BatchOutcome::failure(record_id, message);
+    }
+    let Some(output) = line.get("modelOutput") else {
+        return BatchOutcome::failure(record_id, "record has no model output");
+    };
+
+    let text_blocks = output
+        .get("content")
+        .or_else(|| output.pointer("/output/message/content"))
+        .and_then(serde_json::Value::as_array);
+    let text = text_blocks
+        .into_iter()
+        .flatten()
+        .filter_map(|block| block.get("text").and_then(serde_json::Value::as_str))
+        .collect::<String>();
+    let usage = output.get("usage").map(|usage| {
+        let count = |snake: &str, camel: &str| {
+            usage
+                .get(snake)
+                .or_else(|| usage.get(camel))
+                .and_then(serde_json::Value::as_u64)
+        };
+        TokenUsage {
+            input_tokens: count("input_tokens", "inputTokens"),
+            output_tokens: count("output_tokens", "outputTokens"),
+            ..TokenUsage::default()
+        }
+    });
+    BatchOutcome::success(record_id, text, usage)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InvocationJob {
+    status: String,
+    #[serde(default)]
+    message: Option<String>,
+    input_data_config: InputDataConfig,
+    output_data_config: OutputDataConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputDataConfig {
+    #[serde(rename = "s3InputDataConfig")]
+    s3: S3Location,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputDataConfig {
+    #[serde(rename = "s3OutputDataConfig")]
+    s3: S3Location,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S3Location {
+    s3_uri: String,
+}
+
 // ── Converse API Types (Request) ────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -373,7 +588,15 @@ struct InferenceConfig {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ToolConfig {
-    tools: Vec<ToolDefinition>,
+    tools: Vec<ToolEntry>,
+}
+
+/// Tool list entries: either `{"toolSpec": {...}}` or `{"cachePoint": {...}}`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ToolEntry {
+    Spec(ToolDefinition),
+    CachePoint(CachePointWrapper),
 }
 
 #[derive(Debug, Serialize)]
@@ -416,6 +639,28 @@ struct BedrockUsage {
     input_tokens: Option<u64>,
     #[serde(default)]
     output_tokens: Option<u64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_write_input_tokens: Option<u64>,
+}
+
+impl BedrockUsage {
+    /// Converse reports cache reads/writes separately from `inputTokens`;
+    /// fold them into the prompt total.
+    fn into_token_usage(self) -> TokenUsage {
+        let input_tokens = self.input_tokens.map(|uncached| {
+            uncached
+                + self.cache_read_input_tokens.unwrap_or(0)
+                + self.cache_write_input_tokens.unwrap_or(0)
+        });
+        TokenUsage {
+            input_tokens,
+            output_tokens: self.output_tokens,
+            cached_input_tokens: self.cache_read_input_tokens,
+            cache_write_tokens: self.cache_write_input_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -455,18 +700,55 @@ struct ResponseToolUseWrapper {
 
 pub struct BedrockProvider {
     credentials: Option<AwsCredentials>,
+    /// Credentials used instead of env/IMDS resolution (see `with_credentials`).
+    fixed_credentials: Option<AwsCredentials>,
+    /// Runtime endpoint override (see `with_base_url`).
+    base_url: Option<String>,
 }
 
 impl BedrockProvider {
     pub fn new() -> Self {
         Self {
             credentials: AwsCredentials::from_env().ok(),
+            fixed_credentials: None,
+            base_url: None,
         }
     }
 
     pub async fn new_async() -> Self {
         let credentials = AwsCredentials::resolve().await.ok();
-        Self { credentials }
+        Self {
+            credentials,
+            fixed_credentials: None,
+            base_url: None,
+        }
+    }
+
+    /// Send Converse requests to `base_url` (scheme, host and port) instead of
+    /// the regional endpoint. Requests are still signed for the regional host,
+    /// so the override suits forwarding proxies and test servers.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Sign with these credentials instead of resolving them from the
+    /// environment or instance metadata.
+    pub fn with_credentials(
+        mut self,
+        access_key_id: &str,
+        secret_access_key: &str,
+        region: &str,
+    ) -> Self {
+        let credentials = AwsCredentials {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: None,
+            region: region.to_string(),
+        };
+        self.credentials = Some(credentials.clone());
+        self.fixed_credentials = Some(credentials);
+        self
     }
 
     fn http_client(&self) -> Client {
@@ -490,6 +772,16 @@ impl BedrockProvider {
         format!("https://{ENDPOINT_PREFIX}.{region}.amazonaws.com/model/{model_id}/converse-stream")
     }
 
+    /// Converse (or, with `stream`, ConverseStream) URL, honoring `with_base_url`.
+    fn converse_url(&self, region: &str, model_id: &str, stream: bool) -> String {
+        match (&self.base_url, stream) {
+            (Some(base), false) => format!("{base}/model/{model_id}/converse"),
+            (Some(base), true) => format!("{base}/model/{model_id}/converse-stream"),
+            (None, false) => Self::endpoint_url(region, model_id),
+            (None, true) => Self::stream_endpoint_url(region, model_id),
+        }
+    }
+
     /// Build the canonical URI for SigV4 signing. Must URI-encode the path
     /// per SigV4 spec: colons become `%3A`. AWS verifies the signature against
     /// the encoded form even though the wire request uses raw colons.
@@ -516,12 +808,58 @@ impl BedrockProvider {
 
     /// Resolve credentials: use cached if available, otherwise fetch from IMDS.
     async fn resolve_credentials(&self) -> anyhow::Result<AwsCredentials> {
+        if let Some(creds) = &self.fixed_credentials {
+            return Ok(creds.clone());
+        }
         if let Ok(creds) = AwsCredentials::from_env() {
             return Ok(creds);
         }
         AwsCredentials::from_imds().await
     }
 
+    /// Send a SigV4-signed request to `https://{host}{path}`. `path` must
+    /// already be in canonical (URI-encoded) form.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_signed(
+        &self,
+        credentials: &AwsCredentials,
+        service: &str,
+        method: reqwest::Method,
+        host: &str,
+        path: &str,
+        payload: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let headers = signed_headers(
+            credentials,
+            service,
+            method.as_str(),
+            host,
+            path,
+            &payload,
+            content_type,
+        );
+        let mut request = self
+            .http_client()
+            .request(method, format!("https://{host}{path}"));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request.body(payload).send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Bedrock", response).await);
+        }
+        Ok(response)
+    }
+
+    fn control_plane_host(region: &str) -> String {
+        format!("bedrock.{region}.amazonaws.com")
+    }
+
+    fn s3_host(bucket: &str, region: &str) -> String {
+        format!("{bucket}.s3.{region}.amazonaws.com")
+    }
+
     // ── Cache heuristics (same thresholds as AnthropicProvider) ──
 
     /// Cache system prompts larger than ~1024 tokens (3KB of text).
@@ -529,6 +867,11 @@ impl BedrockProvider {
         text.len() > 3072
     }
 
+    /// Only Anthropic models on Bedrock accept a cache point in the tool list.
+    fn supports_tool_cache(model: &str) -> bool {
+        model.contains("anthropic.claude")
+    }
+
     /// Cache conversations with more than 4 messages (excluding system).
     fn should_cache_conversation(messages: &[ChatMessage]) -> bool {
         messages.iter().filter(|m| m.role != "system").count() > 4
@@ -822,16 +1165,18 @@ impl BedrockProvider {
         if items.is_empty() {
             return None;
         }
-        let tool_defs: Vec<ToolDefinition> = items
+        let tool_defs: Vec<ToolEntry> = items
             .iter()
-            .map(|tool| ToolDefinition {
-                tool_spec: ToolSpecDef {
-                    name: tool.name.clone(),
-                    description: tool.description.clone(),
-                    input_schema: InputSchema {
-                        json: tool.parameters.clone(),
+            .map(|tool| {
+                ToolEntry::Spec(ToolDefinition {
+                    tool_spec: ToolSpecDef {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        input_schema: InputSchema {
+                            json: tool.parameters.clone(),
+                        },
                     },
-                },
+                })
             })
             .collect();
         Some(ToolConfig { tools: tool_defs })
@@ -843,10 +1188,7 @@ impl BedrockProvider {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
-        let usage = response.usage.map(|u| TokenUsage {
-            input_tokens: u.input_tokens,
-            output_tokens: u.output_tokens,
-        });
+        let usage = response.usage.map(BedrockUsage::into_token_usage);
 
         if let Some(output) = response.output {
             if let Some(message) = output.message {
@@ -925,7 +1267,7 @@ impl BedrockProvider {
                 }
             }
         }
-        let url = Self::endpoint_url(&credentials.region, model);
+        let url = self.converse_url(&credentials.region, model, false);
         let canonical_uri = Self::canonical_uri(model);
         let now = chrono::Utc::now();
         let host = credentials.host();
@@ -972,6 +1314,59 @@ impl BedrockProvider {
         Ok(converse_response)
     }
 
+    /// Build a Converse request, adding cache points for large prompts and,
+    /// where the model supports it, after the tool definitions.
+    fn build_converse_request(
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> ConverseRequest {
+        let (system_blocks, mut converse_messages) = Self::convert_messages(request.messages);
+
+        // Apply cachePoint to system if large.
+        let system = system_blocks.map(|mut blocks| {
+            let has_large_system = blocks
+                .iter()
+                .any(|b| matches!(b, SystemBlock::Text(tb) if Self::should_cache_system(&tb.text)));
+            if has_large_system {
+                blocks.push(SystemBlock::CachePoint(CachePointWrapper {
+                    cache_point: CachePoint::default_cache(),
+                }));
+            }
+            blocks
+        });
+
+        // Apply cachePoint to last message if conversation is long.
+        if Self::should_cache_conversation(request.messages) {
+            if let Some(last_msg) = converse_messages.last_mut() {
+                last_msg
+                    .content
+                    .push(ContentBlock::CachePointBlock(CachePointWrapper {
+                        cache_point: CachePoint::default_cache(),
+                    }));
+            }
+        }
+
+        let mut tool_config = Self::convert_tools_to_converse(request.tools);
+        if Self::supports_tool_cache(model) {
+            if let Some(config) = tool_config.as_mut() {
+                config.tools.push(ToolEntry::CachePoint(CachePointWrapper {
+                    cache_point: CachePoint::default_cache(),
+                }));
+            }
+        }
+
+        ConverseRequest {
+            system,
+            messages: converse_messages,
+            inference_config: Some(InferenceConfig {
+                max_tokens: DEFAULT_MAX_TOKENS,
+                temperature,
+            }),
+            tool_config,
+        }
+    }
+
     /// Send a signed request to the ConverseStream endpoint and return the raw
     /// response for event-stream parsing.
     async fn send_converse_stream_request(
@@ -981,7 +1376,7 @@ impl BedrockProvider {
         request_body: &ConverseRequest,
     ) -> anyhow::Result<reqwest::Response> {
         let payload = serde_json::to_vec(request_body)?;
-        let url = Self::stream_endpoint_url(&credentials.region, model);
+        let url = self.converse_url(&credentials.region, model, true);
         let canonical_uri = Self::stream_canonical_uri(model);
         let now = chrono::Utc::now();
         let host = credentials.host();
@@ -1118,8 +1513,8 @@ fn parse_event_stream_message(buf: &[u8]) -> Option<(String, Vec<u8>, usize)> {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ContentBlockDelta {
-    #[allow(dead_code)]
-    content_block_index: Option<u32>,
+    #[serde(default)]
+    content_block_index: Option<usize>,
     delta: DeltaContent,
 }
 
@@ -1128,6 +1523,147 @@ struct ContentBlockDelta {
 struct DeltaContent {
     #[serde(default)]
     text: Option<String>,
+    #[serde(default)]
+    tool_use: Option<ToolUseDelta>,
+    #[serde(default)]
+    reasoning_content: Option<ReasoningDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolUseDelta {
+    #[serde(default)]
+    input: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReasoningDelta {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentBlockStart {
+    #[serde(default)]
+    content_block_index: Option<usize>,
+    #[serde(default)]
+    start: Option<BlockStart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockStart {
+    #[serde(default)]
+    tool_use: Option<ToolUseStart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolUseStart {
+    tool_use_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMetadata {
+    #[serde(default)]
+    usage: Option<BedrockUsage>,
+}
+
+/// Map one decoded converse-stream message to stream events.
+fn parse_converse_stream_event(event_type: &str, payload: &[u8]) -> StreamResult<Vec<StreamEvent>> {
+    let events = match event_type {
+        "contentBlockStart" => {
+            let start: ContentBlockStart =
+                serde_json::from_slice(payload).map_err(StreamError::Json)?;
+            start
+                .start
+                .and_then(|s| s.tool_use)
+                .map(|tool| StreamEvent::ToolCallDelta {
+                    index: start.content_block_index.unwrap_or_default(),
+                    id: Some(tool.tool_use_id),
+                    name: Some(tool.name),
+                    arguments: String::new(),
+                })
+                .into_iter()
+                .collect()
+        }
+        "contentBlockDelta" => {
+            let block: ContentBlockDelta =
+                serde_json::from_slice(payload).map_err(StreamError::Json)?;
+            let index = block.content_block_index.unwrap_or_default();
+            let delta = block.delta;
+            if let Some(tool) = delta.tool_use {
+                vec![StreamEvent::ToolCallDelta {
+                    index,
+                    id: None,
+                    name: None,
+                    arguments: tool.input,
+                }]
+            } else if let Some(text) = delta.reasoning_content.and_then(|r| r.text) {
+                vec![StreamEvent::ReasoningDelta(text)]
+            } else {
+                delta
+                    .text
+                    .filter(|t| !t.is_empty())
+                    .map(StreamEvent::TextDelta)
+                    .into_iter()
+                    .collect()
+            }
+        }
+        "metadata" => {
+            let metadata: StreamMetadata =
+                serde_json::from_slice(payload).map_err(StreamError::Json)?;
+            metadata
+                .usage
+                .map(|u| StreamEvent::Usage(u.into_token_usage()))
+                .into_iter()
+                .collect()
+        }
+        other if other.contains("Exception") || other.contains("Error") => {
+            let msg = String::from_utf8_lossy(payload);
+            return Err(StreamError::Provider(format!(
+                "Bedrock stream error ({other}): {}",
+                super::sanitize_api_error(&msg)
+            )));
+        }
+        _ => Vec::new(),
+    };
+    Ok(events)
+}
+
+/// Decode a converse-stream response body into stream events.
+fn converse_stream_events(
+    response: reqwest::Response,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+    stream::unfold(
+        (response.bytes_stream().boxed(), Vec::new(), false),
+        |(mut body, mut buffer, done)| async move {
+            loop {
+                if let Some((event_type, payload, consumed)) = parse_event_stream_message(&buffer) {
+                    buffer.drain(..consumed);
+                    let events = parse_converse_stream_event(&event_type, &payload);
+                    return Some((events, (body, buffer, done)));
+                }
+                if done {
+                    return None;
+                }
+                match body.next().await {
+                    Some(Ok(bytes)) => buffer.extend_from_slice(&bytes),
+                    Some(Err(e)) => return Some((Err(StreamError::Http(e)), (body, buffer, true))),
+                    None => return None,
+                }
+            }
+        },
+    )
+    .flat_map(|events| {
+        let events: Vec<StreamResult<StreamEvent>> = match events {
+            Ok(events) => events.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        stream::iter(events)
+    })
+    .boxed()
 }
 
 /// Convert a Bedrock converse-stream byte response into a stream of `StreamChunk`s.
@@ -1212,6 +1748,7 @@ impl Provider for BedrockProvider {
         ProviderCapabilities {
             native_tool_calling: true,
             vision: true,
+            structured_output: false,
         }
     }
 
@@ -1284,45 +1821,20 @@ impl Provider for BedrockProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credentials = self.resolve_credentials().await?;
-
-        let (system_blocks, mut converse_messages) = Self::convert_messages(request.messages);
-
-        // Apply cachePoint to system if large.
-        let system = system_blocks.map(|mut blocks| {
-            let has_large_system = blocks
-                .iter()
-                .any(|b| matches!(b, SystemBlock::Text(tb) if Self::should_cache_system(&tb.text)));
-            if has_large_system {
-                blocks.push(SystemBlock::CachePoint(CachePointWrapper {
-                    cache_point: CachePoint::default_cache(),
-                }));
-            }
-            blocks
-        });
-
-        // Apply cachePoint to last message if conversation is long.
-        if Self::should_cache_conversation(request.messages) {
-            if let Some(last_msg) = converse_messages.last_mut() {
-                last_msg
-                    .content
-                    .push(ContentBlock::CachePointBlock(CachePointWrapper {
-                        cache_point: CachePoint::default_cache(),
-                    }));
-            }
+        if let Some(format) = request.response_format {
+            return super::structured::prompt_guided_chat(
+                self,
+                request,
+                format,
+                model,
+                temperature,
+            )
+            .await;
         }
 
-        let tool_config = Self::convert_tools_to_converse(request.tools);
+        let credentials = self.resolve_credentials().await?;
 
-        let converse_request = ConverseRequest {
-            system,
-            messages: converse_messages,
-            inference_config: Some(InferenceConfig {
-                max_tokens: DEFAULT_MAX_TOKENS,
-                temperature,
-            }),
-            tool_config,
-        };
+        let converse_request = Self::build_converse_request(request, model, temperature);
 
         let response = self
             .send_converse_request(&credentials, model, &converse_request)
@@ -1335,6 +1847,25 @@ impl Provider for BedrockProvider {
         true
     }
 
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        if request.response_format.is_some() {
+            let response = self.chat(request, model, temperature).await?;
+            return Ok(super::streaming::replay(response));
+        }
+
+        let credentials = self.resolve_credentials().await?;
+        let converse_request = Self::build_converse_request(request, model, temperature);
+        let response = self
+            .send_converse_stream_request(&credentials, model, &converse_request)
+            .await?;
+        Ok(converse_stream_events(response))
+    }
+
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -1381,12 +1912,8 @@ impl Provider for BedrockProvider {
         };
 
         // Clone what we need for the async block
-        let credentials = AwsCredentials {
-            access_key_id: credentials.access_key_id.clone(),
-            secret_access_key: credentials.secret_access_key.clone(),
-            session_token: credentials.session_token.clone(),
-            region: credentials.region.clone(),
-        };
+        let credentials = credentials.clone();
+        let url = self.converse_url(&credentials.region, model, true);
         let model = model.to_string();
         let count_tokens = options.count_tokens;
         let client = self.http_client();
@@ -1408,7 +1935,6 @@ impl Provider for BedrockProvider {
                 }
             };
 
-            let url = BedrockProvider::stream_endpoint_url(&credentials.region, &model);
             let canonical_uri = BedrockProvider::stream_canonical_uri(&model);
             let now = chrono::Utc::now();
             let host = credentials.host();
@@ -1528,6 +2054,144 @@ impl Provider for BedrockProvider {
         .boxed()
     }
 
+    fn supports_batch(&self) -> bool {
+        env_optional(BATCH_S3_URI_ENV).is_some() && env_optional(BATCH_ROLE_ARN_ENV).is_some()
+    }
+
+    async fn submit_batch(
+        &self,
+        requests: &[BatchRequest],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let credentials = self.resolve_credentials().await?;
+        let (bucket, prefix) = parse_s3_uri(&env_required(BATCH_S3_URI_ENV)?)?;
+        let role_arn = env_required(BATCH_ROLE_ARN_ENV)?;
+
+        let records = requests
+            .iter()
+            .map(|request| {
+                Ok(serde_json::json!({
+                    "recordId": request.custom_id,
+                    "modelInput": batch_model_input(request, model, temperature)?,
+                }))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let job_name = format!("zeroclaw-{}", uuid::Uuid::new_v4().simple());
+        let job_dir = if prefix.is_empty() {
+            job_name.clone()
+        } else {
+            format!("{prefix}/{job_name}")
+        };
+        let input_key = format!("{job_dir}/input.jsonl");
+        self.send_signed(
+            &credentials,
+            "s3",
+            reqwest::Method::PUT,
+            &Self::s3_host(&bucket, &credentials.region),
+            &format!("/{input_key}"),
+            batch::to_jsonl(&records)?.into_bytes(),
+            None,
+        )
+        .await?;
+
+        let body = serde_json::json!({
+            "jobName": job_name,
+            "roleArn": role_arn,
+            "modelId": model,
+            "inputDataConfig": {
+                "s3InputDataConfig": { "s3Uri": format!("s3://{bucket}/{input_key}") }
+            },
+            "outputDataConfig": {
+                "s3OutputDataConfig": { "s3Uri": format!("s3://{bucket}/{job_dir}/output/") }
+            },
+        });
+        let response = self
+            .send_signed(
+                &credentials,
+                SIGNING_SERVICE,
+                reqwest::Method::POST,
+                &Self::control_plane_host(&credentials.region),
+                "/model-invocation-job",
+                serde_json::to_vec(&body)?,
+                Some("application/json"),
+            )
+            .await?;
+        let created: serde_json::Value = response.json().await?;
+        let job_arn = created
+            .get("jobArn")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Bedrock did not return a batch job ARN"))?;
+        // The trailing job ID is a valid identifier on its own and needs no
+        // escaping in the request path.
+        Ok(job_arn.rsplit('/').next().unwrap_or(job_arn).to_string())
+    }
+
+    async fn poll_batch(&self, batch_id: &str) -> anyhow::Result<BatchStatus> {
+        let credentials = self.resolve_credentials().await?;
+        let response = self
+            .send_signed(
+                &credentials,
+                SIGNING_SERVICE,
+                reqwest::Method::GET,
+                &Self::control_plane_host(&credentials.region),
+                &format!("/model-invocation-job/{batch_id}"),
+                Vec::new(),
+                None,
+            )
+            .await?;
+        let job: InvocationJob = response.json().await?;
+        match job.status.as_str() {
+            "Completed" | "PartiallyCompleted" | "Stopped" | "Expired" => {}
+            "Failed" => {
+                return Ok(BatchStatus::Failed(
+                    job.message
+                        .unwrap_or_else(|| "batch inference job failed".to_string()),
+                ))
+            }
+            _ => return Ok(BatchStatus::Pending),
+        }
+
+        // Results land at `<output uri>/<job id>/<input file name>.out`.
+        let input_name = job
+            .input_data_config
+            .s3
+            .s3_uri
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+        let (bucket, prefix) = parse_s3_uri(&job.output_data_config.s3.s3_uri)?;
+        let output_key = [prefix.as_str(), batch_id, &format!("{input_name}.out")]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+        let response = match self
+            .send_signed(
+                &credentials,
+                "s3",
+                reqwest::Method::GET,
+                &Self::s3_host(&bucket, &credentials.region),
+                &format!("/{output_key}"),
+                Vec::new(),
+                None,
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) if job.status != "Completed" => {
+                return Ok(BatchStatus::Failed(format!("batch {}: {e}", job.status)))
+            }
+            Err(e) => return Err(e),
+        };
+        let body = response.text().await?;
+        let lines = batch::parse_jsonl::<serde_json::Value>(&body)?;
+        Ok(BatchStatus::Completed(
+            lines.iter().map(parse_batch_output_line).collect(),
+        ))
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(ref creds) = self.credentials {
             let url = format!("https://{ENDPOINT_PREFIX}.{}.amazonaws.com/", creds.region);
@@ -1674,6 +2338,98 @@ mod tests {
 
     // ── Credential tests ────────────────────────────────────────
 
+    #[test]
+    fn signed_headers_add_payload_hash_for_s3_only() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: TEST_VECTOR_SECRET.to_string(),
+            session_token: None,
+            region: "us-east-1".to_string(),
+        };
+        let s3 = signed_headers(&credentials, "s3", "PUT", "b.s3", "/k", b"data", None);
+        assert!(s3.iter().any(|(k, _)| k == "x-amz-content-sha256"));
+        assert!(s3.iter().all(|(k, _)| k != "host"));
+        let auth = &s3.iter().find(|(k, _)| k == "authorization").unwrap().1;
+        assert!(auth.contains("/us-east-1/s3/aws4_request"));
+
+        let bedrock = signed_headers(
+            &credentials,
+            SIGNING_SERVICE,
+            "GET",
+            "bedrock.us-east-1.amazonaws.com",
+            "/model-invocation-job/abc",
+            b"",
+            None,
+        );
+        assert!(bedrock.iter().all(|(k, _)| k != "x-amz-content-sha256"));
+    }
+
+    #[test]
+    fn parse_s3_uri_splits_bucket_and_prefix() {
+        assert_eq!(
+            parse_s3_uri("s3://bucket/a/b/").unwrap(),
+            ("bucket".to_string(), "a/b".to_string())
+        );
+        assert_eq!(
+            parse_s3_uri("s3://bucket").unwrap(),
+            ("bucket".to_string(), String::new())
+        );
+        assert!(parse_s3_uri("https://bucket").is_err());
+    }
+
+    #[test]
+    fn batch_model_input_uses_native_model_format() {
+        let request = BatchRequest {
+            custom_id: "job-1".into(),
+            messages: vec![ChatMessage::system("sys"), ChatMessage::user("hi")],
+        };
+        let claude =
+            batch_model_input(&request, "anthropic.claude-3-haiku-20240307-v1:0", 0.2).unwrap();
+        assert_eq!(claude["anthropic_version"], "bedrock-2023-05-31");
+        assert_eq!(claude["system"], "sys");
+        assert_eq!(claude["messages"][0]["content"], "hi");
+
+        let nova = batch_model_input(&request, "us.amazon.nova-lite-v1:0", 0.2).unwrap();
+        assert_eq!(nova["system"][0]["text"], "sys");
+        assert_eq!(nova["messages"][0]["content"][0]["text"], "hi");
+
+        assert!(batch_model_input(&request, "meta.llama3-70b-instruct-v1:0", 0.2).is_err());
+    }
+
+    #[test]
+    fn parse_batch_output_line_reads_claude_nova_and_errors() {
+        let claude = serde_json::json!({
+            "recordId": "a",
+            "modelOutput": {
+                "content": [{"type": "text", "text": "hello"}],
+                "usage": {"input_tokens": 3, "output_tokens": 1}
+            }
+        });
+        let outcome = parse_batch_output_line(&claude);
+        let output = outcome.result.unwrap();
+        assert_eq!(output.text, "hello");
+        assert_eq!(output.usage.unwrap().input_tokens, Some(3));
+
+        let nova = serde_json::json!({
+            "recordId": "b",
+            "modelOutput": {
+                "output": {"message": {"content": [{"text": "hey"}]}},
+                "usage": {"inputTokens": 4, "outputTokens": 2}
+            }
+        });
+        let output = parse_batch_output_line(&nova).result.unwrap();
+        assert_eq!(output.text, "hey");
+        assert_eq!(output.usage.unwrap().output_tokens, Some(2));
+
+        let failed = serde_json::json!({
+            "recordId": "c",
+            "error": {"errorCode": 400, "errorMessage": "bad input"}
+        });
+        let outcome = parse_batch_output_line(&failed);
+        assert_eq!(outcome.custom_id, "c");
+        assert_eq!(outcome.result.unwrap_err(), "bad input");
+    }
+
     #[test]
     fn credentials_host_formats_correctly() {
         let creds = AwsCredentials {
@@ -1695,7 +2451,11 @@ mod tests {
 
     #[tokio::test]
     async fn chat_fails_without_credentials() {
-        let provider = BedrockProvider { credentials: None };
+        let provider = BedrockProvider {
+            credentials: None,
+            fixed_credentials: None,
+            base_url: None,
+        };
         let result = provider
             .chat_with_system(None, "hello", "anthropic.claude-sonnet-4-6", 0.7)
             .await;
@@ -1859,7 +2619,49 @@ mod tests {
         assert!(config.is_some());
         let config = config.unwrap();
         assert_eq!(config.tools.len(), 1);
-        assert_eq!(config.tools[0].tool_spec.name, "shell");
+        assert!(matches!(&config.tools[0], ToolEntry::Spec(def) if def.tool_spec.name == "shell"));
+    }
+
+    #[test]
+    fn converse_request_adds_tool_cache_point_for_claude_only() {
+        let tools = vec![ToolSpec {
+            name: "shell".to_string(),
+            description: "Run commands".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let messages = [ChatMessage::user("hi")];
+        let request = || ProviderChatRequest {
+            messages: &messages,
+            tools: Some(&tools),
+            response_format: None,
+        };
+
+        let claude = BedrockProvider::build_converse_request(
+            request(),
+            "anthropic.claude-sonnet-4-20250514-v1:0",
+            0.7,
+        );
+        let json = serde_json::to_value(&claude).unwrap();
+        assert_eq!(
+            json["toolConfig"]["tools"][1]["cachePoint"]["type"],
+            "default"
+        );
+
+        let nova = BedrockProvider::build_converse_request(request(), "amazon.nova-pro-v1:0", 0.7);
+        let json = serde_json::to_value(&nova).unwrap();
+        assert_eq!(json["toolConfig"]["tools"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn converse_usage_folds_cache_tokens_into_input_total() {
+        let usage: BedrockUsage = serde_json::from_str(
+            r#"{"inputTokens":10,"outputTokens":5,"cacheReadInputTokens":2000,"cacheWriteInputTokens":300}"#,
+        )
+        .unwrap();
+        let usage = usage.into_token_usage();
+        assert_eq!(usage.input_tokens, Some(2310));
+        assert_eq!(usage.cached_input_tokens, Some(2000));
+        assert_eq!(usage.cache_write_tokens, Some(300));
     }
 
     #[test]
@@ -1991,14 +2793,22 @@ mod tests {
 
     #[tokio::test]
     async fn warmup_without_credentials_is_noop() {
-        let provider = BedrockProvider { credentials: None };
+        let provider = BedrockProvider {
+            credentials: None,
+            fixed_credentials: None,
+            base_url: None,
+        };
         let result = provider.warmup().await;
         assert!(result.is_ok());
     }
 
     #[test]
     fn capabilities_reports_native_tool_calling() {
-        let provider = BedrockProvider { credentials: None };
+        let provider = BedrockProvider {
+            credentials: None,
+            fixed_credentials: None,
+            base_url: None,
+        };
         let caps = provider.capabilities();
         assert!(caps.native_tool_calling);
     }
@@ -2052,7 +2862,11 @@ mod tests {
 
     #[test]
     fn supports_streaming_returns_true() {
-        let provider = BedrockProvider { credentials: None };
+        let provider = BedrockProvider {
+            credentials: None,
+            fixed_credentials: None,
+            base_url: None,
+        };
         assert!(provider.supports_streaming());
     }
 
@@ -2208,6 +3022,43 @@ mod tests {
         assert_eq!(delta.delta.text.as_deref(), Some("Hello"));
     }
 
+    #[test]
+    fn converse_stream_events_map_tool_use_and_usage() {
+        let mut acc = crate::providers::streaming::StreamAccumulator::new();
+        let frames: [(&str, &[u8]); 5] = [
+            (
+                "contentBlockDelta",
+                br#"{"contentBlockIndex":0,"delta":{"text":"Checking"}}"#,
+            ),
+            (
+                "contentBlockStart",
+                br#"{"contentBlockIndex":1,"start":{"toolUse":{"toolUseId":"tu_1","name":"shell"}}}"#,
+            ),
+            (
+                "contentBlockDelta",
+                br#"{"contentBlockIndex":1,"delta":{"toolUse":{"input":"{\"command\":\"ls\"}"}}}"#,
+            ),
+            ("messageStop", br#"{"stopReason":"tool_use"}"#),
+            (
+                "metadata",
+                br#"{"usage":{"inputTokens":11,"outputTokens":6,"totalTokens":17}}"#,
+            ),
+        ];
+        for (event_type, payload) in frames {
+            for event in parse_converse_stream_event(event_type, payload).unwrap() {
+                acc.push(event);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Checking"));
+        assert_eq!(response.tool_calls[0].id, "tu_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.usage.unwrap().output_tokens, Some(6));
+
+        let err = parse_converse_stream_event("throttlingException", b"{}").unwrap_err();
+        assert!(err.to_string().contains("throttlingException"));
+    }
+
     #[test]
     fn parse_event_stream_message_stop() {
         let payload = br#"{"stopReason":"end_turn"}"#;
@@ -0,0 +1,485 @@
+//! Record/replay HTTP harness for provider conformance tests.
+//!
+//! A [`CassetteServer`] listens on localhost and stands in for a provider API.
+//! In replay mode it answers from a [`Cassette`] stored in the repository; in
+//! record mode it forwards each request to the real upstream and keeps the
+//! exchange so it can be saved as a new cassette.
+//!
+//! Only the method, path (without query string), JSON request body and the
+//! response are kept. Headers, and with them credentials, are never recorded.
+//!
+//! Replay matching is sequential per conformance case: a request is answered by
+//! the next unused interaction of the current case, provided method and path
+//! match and every field of the recorded request body is present in the actual
+//! body. Hand-written cassettes can therefore list only the fields that matter.
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Recorded provider traffic for one conformance run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Provider implementation the cassette targets (see
+    /// [`super::conformance::REPLAY_PROVIDERS`]).
+    pub provider: String,
+    /// Model the suite was run with.
+    pub model: String,
+    /// Path prefix of the upstream base URL (e.g. `/v1`).
+    #[serde(default)]
+    pub base_path: String,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse cassette {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut raw = serde_json::to_string_pretty(self)?;
+        raw.push('\n');
+        std::fs::write(path, raw)
+            .with_context(|| format!("Failed to write cassette {}", path.display()))
+    }
+}
+
+/// One request/response exchange.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Conformance case that issued the request.
+    pub case: String,
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Fields the request body must contain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// JSON bodies are stored as JSON; anything else (SSE, NDJSON, plain
+    /// text) as a string.
+    pub body: Value,
+    /// `body` is the base64 encoding of a binary payload (e.g. an AWS event
+    /// stream).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+enum Mode {
+    Replay,
+    Record {
+        upstream: String,
+        client: reqwest::Client,
+    },
+}
+
+struct Tape {
+    mode: Mode,
+    case: String,
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+    mismatches: Vec<String>,
+}
+
+/// Local stand-in for a provider API, in replay or record mode.
+pub struct CassetteServer {
+    tape: Arc<Mutex<Tape>>,
+    base_url: String,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl CassetteServer {
+    /// Serve the interactions of `cassette`.
+    pub async fn replay(cassette: &Cassette) -> Result<Self> {
+        let tape = Tape {
+            mode: Mode::Replay,
+            case: String::new(),
+            used: vec![false; cassette.interactions.len()],
+            interactions: cassette.interactions.clone(),
+            mismatches: Vec::new(),
+        };
+        Self::start(tape, &cassette.base_path).await
+    }
+
+    /// Forward requests to `upstream_base_url` and record every exchange.
+    pub async fn record(upstream_base_url: &str) -> Result<Self> {
+        let upstream = reqwest::Url::parse(upstream_base_url)
+            .with_context(|| format!("Invalid upstream URL: {upstream_base_url}"))?;
+        let base_path = upstream.path().trim_end_matches('/').to_string();
+        let origin = upstream.origin().ascii_serialization();
+        let tape = Tape {
+            mode: Mode::Record {
+                upstream: origin,
+                client: reqwest::Client::builder()
+                    .timeout(std::time::Duration::from_secs(120))
+                    .build()?,
+            },
+            case: String::new(),
+            interactions: Vec::new(),
+            used: Vec::new(),
+            mismatches: Vec::new(),
+        };
+        Self::start(tape, &base_path).await
+    }
+
+    async fn start(tape: Tape, base_path: &str) -> Result<Self> {
+        let tape = Arc::new(Mutex::new(tape));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let app = Router::new().fallback(handle).with_state(Arc::clone(&tape));
+        let server = tokio::spawn(async move {
+            if let Err(err) = axum::serve(listener, app).await {
+                tracing::warn!("Cassette server stopped: {err}");
+            }
+        });
+        Ok(Self {
+            tape,
+            base_url: format!("http://{addr}{base_path}"),
+            server,
+        })
+    }
+
+    /// Base URL to configure the provider under test with.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Tag subsequent requests with a conformance case.
+    pub fn begin_case(&self, case: &str) {
+        case.clone_into(&mut self.tape.lock().case);
+    }
+
+    /// In replay mode, fail if a request did not match or an interaction was
+    /// never requested.
+    pub fn verify(&self) -> Result<()> {
+        let tape = self.tape.lock();
+        let mut problems = tape.mismatches.clone();
+        for (interaction, used) in tape.interactions.iter().zip(&tape.used) {
+            if !used {
+                problems.push(format!(
+                    "[{}] {} {} was never requested",
+                    interaction.case, interaction.request.method, interaction.request.path
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Cassette replay failed:\n  {}", problems.join("\n  "))
+        }
+    }
+
+    /// Exchanges captured so far (record mode).
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.tape.lock().interactions.clone()
+    }
+}
+
+impl Drop for CassetteServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(
+    State(tape): State<Arc<Mutex<Tape>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path().to_string();
+    let request_body = serde_json::from_slice::<Value>(&body).ok();
+
+    let (upstream, client) = {
+        let mut tape = tape.lock();
+        match &tape.mode {
+            Mode::Record { upstream, client } => (upstream.clone(), client.clone()),
+            Mode::Replay => {
+                return replay_response(&mut tape, &method, &path, request_body.as_ref());
+            }
+        }
+    };
+
+    let path_and_query = uri.path_and_query().map_or(path.as_str(), |pq| pq.as_str());
+    let mut forward = client.request(method.clone(), format!("{upstream}{path_and_query}"));
+    for (name, value) in &headers {
+        if name != header::HOST && name != header::CONTENT_LENGTH && name != header::ACCEPT_ENCODING
+        {
+            forward = forward.header(name, value);
+        }
+    }
+    let upstream_response = match forward.body(body).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            let message = super::sanitize_api_error(&err.to_string());
+            return (StatusCode::BAD_GATEWAY, message).into_response();
+        }
+    };
+
+    let status = upstream_response.status().as_u16();
+    let content_type = upstream_response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string);
+    let raw = upstream_response.bytes().await.unwrap_or_default();
+    let (response_body, base64) = match serde_json::from_slice::<Value>(&raw) {
+        Ok(json) => (json, false),
+        Err(_) => match std::str::from_utf8(&raw) {
+            Ok(text) => (Value::String(text.to_string()), false),
+            Err(_) => (Value::String(BASE64.encode(&raw)), true),
+        },
+    };
+
+    let mut tape = tape.lock();
+    let case = tape.case.clone();
+    let recorded = RecordedResponse {
+        status,
+        content_type,
+        body: response_body,
+        base64,
+    };
+    let response = to_http_response(&recorded);
+    tape.interactions.push(Interaction {
+        case,
+        request: RecordedRequest {
+            method: method.to_string(),
+            path,
+            body: request_body,
+        },
+        response: recorded,
+    });
+    response
+}
+
+fn replay_response(tape: &mut Tape, method: &Method, path: &str, body: Option<&Value>) -> Response {
+    let next = tape
+        .interactions
+        .iter()
+        .zip(&tape.used)
+        .position(|(interaction, used)| !used && interaction.case == tape.case);
+    let Some(index) = next else {
+        return mismatch(
+            tape,
+            format!(
+                "[{}] unexpected {method} {path}: no interactions left",
+                tape.case
+            ),
+        );
+    };
+
+    let expected = &tape.interactions[index].request;
+    if !expected.method.eq_ignore_ascii_case(method.as_str()) || expected.path != path {
+        let message = format!(
+            "[{}] expected {} {}, got {method} {path}",
+            tape.case, expected.method, expected.path
+        );
+        return mismatch(tape, message);
+    }
+    if let Some(expected_body) = &expected.body {
+        if !body.is_some_and(|actual| json_contains(actual, expected_body)) {
+            let message = format!(
+                "[{}] {method} {path} body does not contain the recorded fields",
+                tape.case
+            );
+            return mismatch(tape, message);
+        }
+    }
+
+    tape.used[index] = true;
+    to_http_response(&tape.interactions[index].response)
+}
+
+fn mismatch(tape: &mut Tape, message: String) -> Response {
+    let body = serde_json::json!({ "error": { "message": message } });
+    tape.mismatches.push(message);
+    (StatusCode::NOT_IMPLEMENTED, axum::Json(body)).into_response()
+}
+
+fn to_http_response(recorded: &RecordedResponse) -> Response {
+    let status = StatusCode::from_u16(recorded.status).unwrap_or(StatusCode::OK);
+    let (default_type, body) = match &recorded.body {
+        Value::String(encoded) if recorded.base64 => (
+            "application/octet-stream",
+            BASE64.decode(encoded).unwrap_or_default(),
+        ),
+        Value::String(text) => ("text/plain", text.clone().into_bytes()),
+        other => ("application/json", other.to_string().into_bytes()),
+    };
+    let content_type = recorded.content_type.as_deref().unwrap_or(default_type);
+    (
+        status,
+        [(header::CONTENT_TYPE, content_type.to_string())],
+        body,
+    )
+        .into_response()
+}
+
+/// Whether `actual` contains every field of `expected`. Objects match when
+/// each expected key matches; arrays must have the same length and match
+/// element-wise.
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| json_contains(actual, value))
+        }),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(actual, expected)| json_contains(actual, expected))
+        }
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cassette(interactions: Vec<Interaction>) -> Cassette {
+        Cassette {
+            provider: "openai".into(),
+            model: "gpt-test".into(),
+            base_path: "/v1".into(),
+            interactions,
+        }
+    }
+
+    fn interaction(case: &str, body: Option<Value>) -> Interaction {
+        Interaction {
+            case: case.into(),
+            request: RecordedRequest {
+                method: "POST".into(),
+                path: "/v1/chat/completions".into(),
+                body,
+            },
+            response: RecordedResponse {
+                status: 200,
+                content_type: None,
+                body: serde_json::json!({ "ok": true }),
+                base64: false,
+            },
+        }
+    }
+
+    #[test]
+    fn json_contains_matches_subsets() {
+        let actual = serde_json::json!({
+            "model": "m",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "temperature": 0.7
+        });
+        assert!(json_contains(
+            &actual,
+            &serde_json::json!({ "messages": [{ "role": "user" }] })
+        ));
+        assert!(!json_contains(
+            &actual,
+            &serde_json::json!({ "model": "x" })
+        ));
+        assert!(!json_contains(
+            &actual,
+            &serde_json::json!({ "messages": [] })
+        ));
+    }
+
+    #[tokio::test]
+    async fn replay_serves_case_interactions_and_reports_leftovers() {
+        let server = CassetteServer::replay(&cassette(vec![
+            interaction("chat", Some(serde_json::json!({ "model": "gpt-test" }))),
+            interaction("error", None),
+        ]))
+        .await
+        .unwrap();
+        assert!(server.base_url().ends_with("/v1"));
+
+        server.begin_case("chat");
+        let response = reqwest::Client::new()
+            .post(format!("{}/chat/completions", server.base_url()))
+            .json(&serde_json::json!({ "model": "gpt-test", "stream": false }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.json::<Value>().await.unwrap()["ok"], true);
+
+        let err = server.verify().unwrap_err().to_string();
+        assert!(err.contains("[error] POST /v1/chat/completions was never requested"));
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_mismatched_body() {
+        let server = CassetteServer::replay(&cassette(vec![interaction(
+            "chat",
+            Some(serde_json::json!({ "model": "gpt-test" })),
+        )]))
+        .await
+        .unwrap();
+        server.begin_case("chat");
+        let response = reqwest::Client::new()
+            .post(format!("{}/chat/completions", server.base_url()))
+            .json(&serde_json::json!({ "model": "other" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 501);
+        assert!(server
+            .verify()
+            .unwrap_err()
+            .to_string()
+            .contains("body does not contain the recorded fields"));
+    }
+
+    #[tokio::test]
+    async fn replay_decodes_base64_bodies() {
+        let mut binary = interaction("streaming", None);
+        binary.response.body = Value::String(BASE64.encode([0_u8, 159, 146, 150]));
+        binary.response.base64 = true;
+        let server = CassetteServer::replay(&cassette(vec![binary]))
+            .await
+            .unwrap();
+        server.begin_case("streaming");
+        let response = reqwest::Client::new()
+            .post(format!("{}/chat/completions", server.base_url()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.bytes().await.unwrap().as_ref(),
+            &[0_u8, 159, 146, 150]
+        );
+        server.verify().unwrap();
+    }
+}
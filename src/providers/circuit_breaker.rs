@@ -0,0 +1,415 @@
+//! Per-provider circuit breakers shared by every [`ReliableProvider`](super::reliable::ReliableProvider).
+//!
+//! Breakers are keyed by provider name in a process-wide registry, so routed
+//! and fallback chains that reach the same provider see the same health. When
+//! persistence is initialized, state is written to
+//! `<workspace>/state/provider_circuits.json` and reloaded on the next start.
+//!
+//! State machine:
+//! - `closed`: calls flow; consecutive failures are counted.
+//! - `open`: reached `failure_threshold`; the provider is tried only after
+//!   healthy ones until `cooldown` elapses.
+//! - `half_open`: cooldown elapsed; one call at a time acts as a probe while
+//!   other calls prefer healthy providers. A failed probe reopens the breaker,
+//!   `half_open_successes` successful probes close it.
+
+use crate::config::ReliabilityConfig;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const STATE_FILE: &str = "provider_circuits.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Numeric encoding for metrics: 0 = closed, 1 = half-open, 2 = open.
+    pub fn as_gauge(self) -> i64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
+/// Breaker thresholds, derived from `[reliability]`.
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerSettings {
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+    pub half_open_successes: u32,
+}
+
+impl BreakerSettings {
+    /// Returns `None` when breakers are disabled (`circuit_failure_threshold = 0`).
+    pub fn from_config(reliability: &ReliabilityConfig) -> Option<Self> {
+        (reliability.circuit_failure_threshold > 0).then(|| Self {
+            failure_threshold: reliability.circuit_failure_threshold,
+            cooldown: Duration::from_secs(reliability.circuit_cooldown_secs),
+            half_open_successes: reliability.circuit_half_open_successes.max(1),
+        })
+    }
+}
+
+/// Persisted and reported state of one provider's breaker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub half_open_successes: u32,
+    /// Unix seconds when the breaker last opened.
+    #[serde(default)]
+    pub opened_at: Option<u64>,
+    /// Number of times the breaker has opened.
+    #[serde(default)]
+    pub trips: u64,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Unix seconds when the half-open probe in flight started. Not persisted:
+    /// a probe does not survive a restart.
+    #[serde(skip)]
+    pub probe_started_at: Option<u64>,
+}
+
+impl Default for CircuitSnapshot {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            half_open_successes: 0,
+            opened_at: None,
+            trips: 0,
+            last_error: None,
+            probe_started_at: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    circuits: BTreeMap<String, CircuitSnapshot>,
+    path: Option<PathBuf>,
+}
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+/// Held while the state file is written, so concurrent writes land in order
+/// without holding the registry lock during file I/O.
+static PERSIST_LOCK: Mutex<()> = Mutex::new(());
+
+fn registry() -> &'static Mutex<Registry> {
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Path of the persisted breaker state inside a workspace.
+pub fn state_file_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(STATE_FILE)
+}
+
+/// Read persisted breaker state without touching the live registry.
+pub fn load_persisted(workspace_dir: &Path) -> anyhow::Result<BTreeMap<String, CircuitSnapshot>> {
+    let path = state_file_path(workspace_dir);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let raw = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&raw)?)
+}
+
+/// Enable persistence under `workspace_dir` and restore any saved state.
+///
+/// Providers already tracked in this process keep their live state.
+pub fn init_persistence(workspace_dir: &Path) {
+    let restored = load_persisted(workspace_dir).unwrap_or_else(|err| {
+        tracing::warn!("Ignoring unreadable provider circuit state: {err}");
+        BTreeMap::new()
+    });
+    let mut registry = registry().lock();
+    for (provider, snapshot) in restored {
+        registry.circuits.entry(provider).or_insert(snapshot);
+    }
+    registry.path = Some(state_file_path(workspace_dir));
+}
+
+/// Save the current registry state, if persistence is enabled. Call without
+/// holding the registry lock: it is only taken to copy the state.
+fn persist() {
+    let _write = PERSIST_LOCK.lock();
+    let (path, circuits) = {
+        let registry = registry().lock();
+        let Some(path) = registry.path.clone() else {
+            return;
+        };
+        (path, registry.circuits.clone())
+    };
+    if let Err(err) = write_state(&path, &circuits) {
+        tracing::warn!("Failed to persist provider circuit state: {err}");
+    }
+}
+
+fn write_state(path: &Path, circuits: &BTreeMap<String, CircuitSnapshot>) -> anyhow::Result<()> {
+    let bytes = serde_json::to_vec_pretty(circuits)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Whether the provider should be tried ahead of others: its breaker is
+/// closed, or open with the cooldown elapsed, or half-open with no probe in
+/// flight. Does not change state.
+pub fn allows(provider: &str, settings: &BreakerSettings) -> bool {
+    let registry = registry().lock();
+    registry
+        .circuits
+        .get(provider)
+        .is_none_or(|circuit| match circuit.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => !probe_in_flight(circuit, settings),
+            CircuitState::Open => cooldown_elapsed(circuit, settings),
+        })
+}
+
+fn cooldown_elapsed(circuit: &CircuitSnapshot, settings: &BreakerSettings) -> bool {
+    let opened_at = circuit.opened_at.unwrap_or(0);
+    now_unix().saturating_sub(opened_at) >= settings.cooldown.as_secs()
+}
+
+/// A probe that has not reported back within the cooldown is presumed lost
+/// (cancelled, or ended without a recorded outcome).
+fn probe_in_flight(circuit: &CircuitSnapshot, settings: &BreakerSettings) -> bool {
+    circuit
+        .probe_started_at
+        .is_some_and(|started| now_unix().saturating_sub(started) < settings.cooldown.as_secs())
+}
+
+/// Note that a call to the provider is about to be made.
+///
+/// An open breaker whose cooldown has elapsed moves to half-open, and a call
+/// to a half-open breaker with no probe in flight becomes the probe. Other
+/// calls to a half-open provider are only made as a last resort, since
+/// [`allows`] ranks it behind healthy providers while a probe is running.
+pub fn begin_call(provider: &str, settings: &BreakerSettings) {
+    let mut registry = registry().lock();
+    let Some(circuit) = registry.circuits.get_mut(provider) else {
+        return;
+    };
+    match circuit.state {
+        CircuitState::Open if cooldown_elapsed(circuit, settings) => {
+            circuit.state = CircuitState::HalfOpen;
+            circuit.half_open_successes = 0;
+            circuit.probe_started_at = Some(now_unix());
+            tracing::info!(provider, "Circuit half-open, probing provider");
+        }
+        CircuitState::HalfOpen if !probe_in_flight(circuit, settings) => {
+            circuit.probe_started_at = Some(now_unix());
+            return;
+        }
+        _ => return,
+    }
+    drop(registry);
+    persist();
+}
+
+/// End a probe whose outcome says nothing about provider health (such as a
+/// rejected request), so the next call can probe instead.
+pub fn end_probe(provider: &str) {
+    if let Some(circuit) = registry().lock().circuits.get_mut(provider) {
+        circuit.probe_started_at = None;
+    }
+}
+
+/// Record a successful call.
+pub fn record_success(provider: &str, settings: &BreakerSettings) {
+    let mut registry = registry().lock();
+    let Some(circuit) = registry.circuits.get_mut(provider) else {
+        return;
+    };
+    let previous = circuit.state;
+    circuit.consecutive_failures = 0;
+    circuit.probe_started_at = None;
+    if circuit.state == CircuitState::HalfOpen {
+        circuit.half_open_successes += 1;
+        if circuit.half_open_successes < settings.half_open_successes {
+            return;
+        }
+    }
+    circuit.state = CircuitState::Closed;
+    circuit.half_open_successes = 0;
+    circuit.last_error = None;
+    if previous != CircuitState::Closed {
+        tracing::info!(provider, "Circuit closed, provider recovered");
+        drop(registry);
+        persist();
+    }
+}
+
+/// Record a failed call that points at provider health (not a bad request).
+pub fn record_failure(provider: &str, settings: &BreakerSettings, error: &str) {
+    let mut registry = registry().lock();
+    let circuit = registry.circuits.entry(provider.to_string()).or_default();
+    circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+    circuit.last_error = Some(error.chars().take(200).collect());
+    circuit.probe_started_at = None;
+
+    let trip = match circuit.state {
+        CircuitState::HalfOpen => true,
+        CircuitState::Closed => circuit.consecutive_failures >= settings.failure_threshold,
+        CircuitState::Open => false,
+    };
+    if trip {
+        circuit.state = CircuitState::Open;
+        circuit.opened_at = Some(now_unix());
+        circuit.half_open_successes = 0;
+        circuit.trips += 1;
+        tracing::warn!(
+            provider,
+            failures = circuit.consecutive_failures,
+            cooldown_secs = settings.cooldown.as_secs(),
+            "Circuit opened, deprioritizing provider"
+        );
+        drop(registry);
+        persist();
+    }
+}
+
+/// Current state of every tracked provider breaker.
+pub fn snapshot() -> BTreeMap<String, CircuitSnapshot> {
+    registry().lock().circuits.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> BreakerSettings {
+        BreakerSettings {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(0),
+            half_open_successes: 2,
+        }
+    }
+
+    fn unique_provider(prefix: &str) -> String {
+        format!("{prefix}-{}", uuid::Uuid::new_v4())
+    }
+
+    #[test]
+    fn breaker_opens_after_threshold_and_recovers_through_half_open() {
+        let provider = unique_provider("breaker-cycle");
+        let mut settings = settings();
+        settings.cooldown = Duration::from_secs(3600);
+
+        record_failure(&provider, &settings, "connection refused");
+        assert!(allows(&provider, &settings));
+        record_failure(&provider, &settings, "connection refused");
+        assert_eq!(snapshot()[&provider].state, CircuitState::Open);
+        assert_eq!(snapshot()[&provider].trips, 1);
+        assert!(!allows(&provider, &settings));
+
+        // Cooldown elapsed: checking alone leaves the breaker open; the next
+        // call becomes a half-open probe.
+        settings.cooldown = Duration::from_secs(0);
+        assert!(allows(&provider, &settings));
+        assert_eq!(snapshot()[&provider].state, CircuitState::Open);
+        begin_call(&provider, &settings);
+        assert_eq!(snapshot()[&provider].state, CircuitState::HalfOpen);
+
+        record_success(&provider, &settings);
+        assert_eq!(snapshot()[&provider].state, CircuitState::HalfOpen);
+        record_success(&provider, &settings);
+        let recovered = &snapshot()[&provider];
+        assert_eq!(recovered.state, CircuitState::Closed);
+        assert!(recovered.last_error.is_none());
+    }
+
+    #[test]
+    fn half_open_failure_reopens_immediately() {
+        let provider = unique_provider("breaker-reopen");
+        let settings = settings();
+
+        record_failure(&provider, &settings, "503");
+        record_failure(&provider, &settings, "503");
+        assert!(allows(&provider, &settings));
+        begin_call(&provider, &settings);
+        record_failure(&provider, &settings, "503");
+
+        let circuit = &snapshot()[&provider];
+        assert_eq!(circuit.state, CircuitState::Open);
+        assert_eq!(circuit.trips, 2);
+    }
+
+    #[test]
+    fn half_open_breaker_lets_one_probe_through_at_a_time() {
+        let provider = unique_provider("breaker-probe");
+        let mut settings = settings();
+
+        record_failure(&provider, &settings, "503");
+        record_failure(&provider, &settings, "503");
+        begin_call(&provider, &settings);
+        assert_eq!(snapshot()[&provider].state, CircuitState::HalfOpen);
+
+        // A probe is in flight: further calls go to healthy providers first.
+        settings.cooldown = Duration::from_secs(3600);
+        assert!(!allows(&provider, &settings));
+
+        record_success(&provider, &settings);
+        assert_eq!(snapshot()[&provider].state, CircuitState::HalfOpen);
+        assert!(allows(&provider, &settings));
+        begin_call(&provider, &settings);
+        assert!(!allows(&provider, &settings));
+
+        // A probe that says nothing about health frees the slot.
+        end_probe(&provider);
+        assert!(allows(&provider, &settings));
+    }
+
+    #[test]
+    fn persisted_state_round_trips() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut circuits = BTreeMap::new();
+        circuits.insert(
+            "openrouter".to_string(),
+            CircuitSnapshot {
+                state: CircuitState::Open,
+                consecutive_failures: 5,
+                opened_at: Some(now_unix()),
+                trips: 3,
+                ..CircuitSnapshot::default()
+            },
+        );
+        write_state(&state_file_path(tmp.path()), &circuits).unwrap();
+
+        let loaded = load_persisted(tmp.path()).unwrap();
+        assert_eq!(loaded["openrouter"].state, CircuitState::Open);
+        assert_eq!(loaded["openrouter"].trips, 3);
+    }
+
+    #[test]
+    fn zero_threshold_disables_breakers() {
+        let reliability = ReliabilityConfig {
+            circuit_failure_threshold: 0,
+            ..ReliabilityConfig::default()
+        };
+        assert!(BreakerSettings::from_config(&reliability).is_none());
+        assert!(BreakerSettings::from_config(&ReliabilityConfig::default()).is_some());
+    }
+}
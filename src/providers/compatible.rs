@@ -3,9 +3,11 @@
 //! This module provides a single implementation that works for all of them.
 
 use crate::multimodal;
+use crate::providers::quota_adapter::QuotaTracker;
+use crate::providers::quota_types::QuotaMetadata;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
+    Provider, StreamChunk, StreamError, StreamEvent, StreamOptions, StreamResult, TokenUsage,
     ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
@@ -51,6 +53,8 @@ pub struct OpenAiCompatibleProvider {
     api_mode: CompatibleApiMode,
     /// Optional max token cap propagated to outbound requests.
     max_tokens_override: Option<u32>,
+    /// Latest rate-limit quota from response headers.
+    quota: QuotaTracker,
 }
 
 /// How the provider expects the API key to be sent.
@@ -254,6 +258,7 @@ impl OpenAiCompatibleProvider {
             native_tool_calling: !merge_system_into_user,
             api_mode,
             max_tokens_override: max_tokens_override.filter(|value| *value > 0),
+            quota: QuotaTracker::default(),
         }
     }
 
@@ -443,6 +448,25 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
+}
+
+impl UsageInfo {
+    fn into_token_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+            cached_input_tokens: self.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            cache_write_tokens: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -1297,6 +1321,7 @@ impl OpenAiCompatibleProvider {
             .apply_auth_header(self.http_client().post(&url).json(&request), credential)
             .send()
             .await?;
+        self.quota.observe(&self.name, response.headers());
 
         if !response.status().is_success() {
             let error = response.text().await?;
@@ -1612,6 +1637,7 @@ impl Provider for OpenAiCompatibleProvider {
             // prompt-guided tool calling for those providers.
             native_tool_calling: self.native_tool_calling,
             vision: self.supports_vision,
+            structured_output: false,
         }
     }
 
@@ -1705,6 +1731,7 @@ impl Provider for OpenAiCompatibleProvider {
                 return Err(chat_error.into());
             }
         };
+        self.quota.observe(&self.name, response.headers());
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1822,6 +1849,7 @@ impl Provider for OpenAiCompatibleProvider {
                 return Err(chat_error.into());
             }
         };
+        self.quota.observe(&self.name, response.headers());
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1949,6 +1977,7 @@ impl Provider for OpenAiCompatibleProvider {
                 });
             }
         };
+        self.quota.observe(&self.name, response.headers());
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1967,10 +1996,7 @@ impl Provider for OpenAiCompatibleProvider {
 
         let body = response.text().await?;
         let chat_response = parse_chat_response_body(&self.name, &body)?;
-        let usage = chat_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let usage = chat_response.usage.map(UsageInfo::into_token_usage);
         let choice = chat_response
             .choices
             .into_iter()
@@ -2010,6 +2036,17 @@ impl Provider for OpenAiCompatibleProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
+        if let Some(format) = request.response_format {
+            return super::structured::prompt_guided_chat(
+                self,
+                request,
+                format,
+                model,
+                temperature,
+            )
+            .await;
+        }
+
         let credential = self.credential.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
@@ -2080,6 +2117,7 @@ impl Provider for OpenAiCompatibleProvider {
                 return Err(chat_error.into());
             }
         };
+        self.quota.observe(&self.name, response.headers());
 
         if !response.status().is_success() {
             let status = response.status();
@@ -2121,10 +2159,7 @@ impl Provider for OpenAiCompatibleProvider {
         }
 
         let native_response: ApiChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let usage = native_response.usage.map(UsageInfo::into_token_usage);
         let message = native_response
             .choices
             .into_iter()
@@ -2141,10 +2176,86 @@ impl Provider for OpenAiCompatibleProvider {
         self.native_tool_calling
     }
 
+    fn quota(&self) -> Option<QuotaMetadata> {
+        self.quota.latest()
+    }
+
     fn supports_streaming(&self) -> bool {
         true
     }
 
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        // Structured output, the Responses API and the fallbacks in `chat`
+        // are answered whole.
+        if request.response_format.is_some() || self.should_use_responses_mode() {
+            let response = self.chat(request, model, temperature).await?;
+            return Ok(super::streaming::replay(response));
+        }
+
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
+                self.name
+            )
+        })?;
+
+        let tools = Self::convert_tool_specs(request.tools);
+        let effective_messages = if self.merge_system_into_user {
+            Self::flatten_system_messages(request.messages)
+        } else {
+            request.messages.to_vec()
+        };
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages_for_native(
+                &effective_messages,
+                !self.merge_system_into_user,
+            ),
+            temperature,
+            max_tokens: self.effective_max_tokens(),
+            stream: Some(true),
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+        };
+
+        let url = self.chat_completions_url();
+        let response = self
+            .apply_auth_header(
+                self.http_client()
+                    .post(&url)
+                    .header("Accept", "text/event-stream")
+                    .json(&native_request),
+                credential,
+            )
+            .send()
+            .await?;
+        self.quota.observe(&self.name, response.headers());
+
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            let sanitized = super::sanitize_api_error(&error);
+            if Self::is_native_tool_schema_unsupported(status, &sanitized)
+                || (status == reqwest::StatusCode::NOT_FOUND && self.supports_responses_fallback)
+            {
+                // `chat` knows how to recover from these.
+                let response = self.chat(request, model, temperature).await?;
+                return Ok(super::streaming::replay(response));
+            }
+            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+        }
+
+        Ok(super::streaming::sse_events(
+            response,
+            super::streaming::parse_openai_chunk,
+        ))
+    }
+
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -0,0 +1,533 @@
+//! Provider contract suite for [`Provider`] implementations.
+//!
+//! The same cases run in two places:
+//! - `tests/provider_conformance.rs` replays hand-written cassettes
+//!   (`tests/fixtures/cassettes/<provider>.json`) through a
+//!   [`CassetteServer`], checking each wire implementation in
+//!   [`REPLAY_PROVIDERS`] offline against its documented wire format. The
+//!   cassettes are not captured traffic, so they pin our reading of each API
+//!   rather than the API itself. [`EXCLUDED_PROVIDERS`] have no cassette.
+//! - `zeroclaw providers verify <name>` runs them live against the
+//!   configured provider, and with `--record` writes a captured cassette
+//!   that can replace the hand-written one.
+//!
+//! Cases cover plain chat, native tool calls, vision input, streaming and
+//! error reporting. Capabilities a provider does not declare are skipped.
+
+use super::cassette::{Cassette, CassetteServer};
+use super::streaming::StreamAccumulator;
+use super::traits::{ChatMessage, ChatRequest, Provider, StreamEvent};
+use super::{anthropic, bedrock, gemini, ollama, openai, openrouter, ProviderRuntimeOptions};
+use crate::config::Config;
+use crate::tools::ToolSpec;
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use std::path::Path;
+use std::time::Duration;
+
+/// Conformance cases, in the order they run.
+pub const CASES: [&str; 5] = ["chat", "tool_call", "vision", "streaming", "error"];
+
+/// Provider implementations that can be pointed at a cassette server.
+/// `compatible` stands for every OpenAI-compatible provider
+/// (`OpenAiCompatibleProvider`), which share one wire format.
+pub const REPLAY_PROVIDERS: [&str; 7] = [
+    "openai",
+    "anthropic",
+    "ollama",
+    "openrouter",
+    "gemini",
+    "bedrock",
+    "compatible",
+];
+
+/// Provider implementations without a cassette, with the reason. They can
+/// still be checked live with `zeroclaw providers verify`.
+pub const EXCLUDED_PROVIDERS: [(&str, &str); 5] = [
+    (
+        "copilot",
+        "authenticates through GitHub's device flow and token exchange at fixed URLs",
+    ),
+    (
+        "openai_codex",
+        "needs a ChatGPT OAuth profile to build; not pointed at a replay server",
+    ),
+    (
+        "glm",
+        "fixed API base URL, and every request carries a freshly signed JWT",
+    ),
+    ("telnyx", "fixed API base URL"),
+    (
+        "embedded",
+        "runs in-process, with no HTTP traffic to replay",
+    ),
+];
+
+/// Model name the `error` case expects the provider to reject.
+pub const MISSING_MODEL: &str = "zeroclaw-conformance-missing-model";
+
+const CASE_TIMEOUT: Duration = Duration::from_secs(120);
+const TEMPERATURE: f64 = 0.0;
+const SYSTEM_PROMPT: &str = "You are a conformance test. Follow instructions exactly.";
+/// 8x8 solid red PNG.
+const RED_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAgAAAAICAIAAABLbSncAAAAEUlEQVR42mP4z8CAFTEMLQkAKP8/wc53yE8AAAAASUVORK5CYII=";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseStatus {
+    Passed,
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseOutcome {
+    pub case: &'static str,
+    pub status: CaseStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    pub outcomes: Vec<CaseOutcome>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        !self
+            .outcomes
+            .iter()
+            .any(|outcome| matches!(outcome.status, CaseStatus::Failed(_)))
+    }
+
+    /// `case: reason` for every failed case.
+    pub fn failures(&self) -> Vec<String> {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.status {
+                CaseStatus::Failed(reason) => Some(format!("{}: {reason}", outcome.case)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Run every case against `provider`.
+pub async fn run_suite(provider: &dyn Provider, model: &str) -> ConformanceReport {
+    run_suite_with(provider, model, |_| {}).await
+}
+
+/// Run every case, calling `on_case` before each one (used to tag cassette
+/// interactions).
+pub async fn run_suite_with(
+    provider: &dyn Provider,
+    model: &str,
+    mut on_case: impl FnMut(&str),
+) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    for case in CASES {
+        on_case(case);
+        let status = match tokio::time::timeout(CASE_TIMEOUT, run_case(case, provider, model)).await
+        {
+            Ok(Ok(status)) => status,
+            Ok(Err(err)) => CaseStatus::Failed(format!("{err:#}")),
+            Err(_) => CaseStatus::Failed(format!("timed out after {}s", CASE_TIMEOUT.as_secs())),
+        };
+        report.outcomes.push(CaseOutcome { case, status });
+    }
+    report
+}
+
+async fn run_case(case: &str, provider: &dyn Provider, model: &str) -> Result<CaseStatus> {
+    match case {
+        "chat" => chat_case(provider, model).await,
+        "tool_call" => tool_call_case(provider, model).await,
+        "vision" => vision_case(provider, model).await,
+        "streaming" => streaming_case(provider, model).await,
+        "error" => error_case(provider).await,
+        other => anyhow::bail!("unknown conformance case: {other}"),
+    }
+}
+
+fn conversation(prompt: &str) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system(SYSTEM_PROMPT),
+        ChatMessage::user(prompt),
+    ]
+}
+
+fn request(messages: &[ChatMessage]) -> ChatRequest<'_> {
+    ChatRequest {
+        messages,
+        tools: None,
+        response_format: None,
+    }
+}
+
+async fn chat_case(provider: &dyn Provider, model: &str) -> Result<CaseStatus> {
+    let messages = conversation("Reply with the single word: pong");
+    let response = provider
+        .chat(request(&messages), model, TEMPERATURE)
+        .await?;
+    let text = response.text.unwrap_or_default();
+    anyhow::ensure!(
+        text.to_ascii_lowercase().contains("pong"),
+        "expected a reply containing \"pong\", got {text:?}"
+    );
+    Ok(CaseStatus::Passed)
+}
+
+async fn tool_call_case(provider: &dyn Provider, model: &str) -> Result<CaseStatus> {
+    if !provider.supports_native_tools() {
+        return Ok(CaseStatus::Skipped("no native tool calling".into()));
+    }
+    let tools = [ToolSpec {
+        name: "get_weather".into(),
+        description: "Get the current weather for a city.".into(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        }),
+    }];
+    let messages = conversation("What is the weather in Paris? Use the get_weather tool.");
+    let chat_request = ChatRequest {
+        tools: Some(&tools),
+        ..request(&messages)
+    };
+    let response = provider.chat(chat_request, model, TEMPERATURE).await?;
+    let call = response
+        .tool_calls
+        .iter()
+        .find(|call| call.name == "get_weather")
+        .with_context(|| {
+            format!(
+                "expected a get_weather tool call, got {} call(s) and text {:?}",
+                response.tool_calls.len(),
+                response.text
+            )
+        })?;
+    let arguments: serde_json::Value = serde_json::from_str(&call.arguments)
+        .with_context(|| format!("tool arguments are not JSON: {}", call.arguments))?;
+    anyhow::ensure!(
+        arguments
+            .get("city")
+            .is_some_and(serde_json::Value::is_string),
+        "expected a string \"city\" argument, got {arguments}"
+    );
+    Ok(CaseStatus::Passed)
+}
+
+async fn vision_case(provider: &dyn Provider, model: &str) -> Result<CaseStatus> {
+    if !provider.supports_vision() {
+        return Ok(CaseStatus::Skipped("no vision support".into()));
+    }
+    let messages = conversation(&format!(
+        "What color is this image? Answer with one word.\n\n[IMAGE:data:image/png;base64,{RED_PNG}]"
+    ));
+    let response = provider
+        .chat(request(&messages), model, TEMPERATURE)
+        .await?;
+    let text = response.text.unwrap_or_default();
+    anyhow::ensure!(
+        text.to_ascii_lowercase().contains("red"),
+        "expected the image to be described as red, got {text:?}"
+    );
+    Ok(CaseStatus::Passed)
+}
+
+async fn streaming_case(provider: &dyn Provider, model: &str) -> Result<CaseStatus> {
+    let messages = conversation("Count from 1 to 5, separated by spaces.");
+    let mut events = provider
+        .stream_chat(request(&messages), model, TEMPERATURE)
+        .await?;
+    let mut accumulator = StreamAccumulator::new();
+    let mut text_deltas = 0;
+    while let Some(event) = events.next().await {
+        let event = event.context("stream yielded an error")?;
+        if matches!(event, StreamEvent::TextDelta(_)) {
+            text_deltas += 1;
+        }
+        accumulator.push(event);
+    }
+    let text = accumulator.finish().text.unwrap_or_default();
+    anyhow::ensure!(text_deltas > 0, "stream produced no text deltas");
+    anyhow::ensure!(
+        text.contains('1') && text.contains('5'),
+        "expected the streamed reply to count to 5, got {text:?}"
+    );
+    Ok(CaseStatus::Passed)
+}
+
+async fn error_case(provider: &dyn Provider) -> Result<CaseStatus> {
+    let messages = conversation("Reply with the single word: pong");
+    match provider
+        .chat(request(&messages), MISSING_MODEL, TEMPERATURE)
+        .await
+    {
+        Ok(response) => anyhow::bail!(
+            "expected an error for model {MISSING_MODEL}, got a reply: {:?}",
+            response.text
+        ),
+        Err(err) => {
+            anyhow::ensure!(
+                !err.to_string().trim().is_empty(),
+                "provider returned an empty error message"
+            );
+            Ok(CaseStatus::Passed)
+        }
+    }
+}
+
+/// Build the `provider` implementation against `base_url`.
+///
+/// `provider` is one of [`REPLAY_PROVIDERS`].
+pub fn provider_at(
+    provider: &str,
+    base_url: &str,
+    credential: Option<&str>,
+) -> Result<Box<dyn Provider>> {
+    Ok(match provider {
+        "openai" => Box::new(openai::OpenAiProvider::with_base_url(
+            Some(base_url),
+            credential,
+        )),
+        "anthropic" => Box::new(anthropic::AnthropicProvider::with_base_url(
+            credential,
+            Some(base_url),
+        )),
+        "ollama" => Box::new(ollama::OllamaProvider::new(Some(base_url), credential)),
+        "openrouter" => {
+            Box::new(openrouter::OpenRouterProvider::new(credential).with_base_url(base_url))
+        }
+        "gemini" => Box::new(gemini::GeminiProvider::new(credential).with_base_url(base_url)),
+        // Replays sign with the credential as a placeholder key pair;
+        // recordings (no credential) use the regular AWS credential chain.
+        "bedrock" => {
+            let provider = bedrock::BedrockProvider::new().with_base_url(base_url);
+            Box::new(match credential {
+                Some(key) => provider.with_credentials(key, key, "us-east-1"),
+                None => provider,
+            })
+        }
+        "compatible" => {
+            super::create_provider_with_url(&format!("custom:{base_url}"), credential, None)?
+        }
+        other => anyhow::bail!(
+            "No replay target for provider {other}; expected one of: {}",
+            REPLAY_PROVIDERS.join(", ")
+        ),
+    })
+}
+
+/// Replay target and real upstream base URL used when recording `name`.
+fn recording_target(config: &Config, name: &str) -> Option<(&'static str, String)> {
+    if let Some(url) = name.strip_prefix("anthropic-custom:") {
+        return Some(("anthropic", url.to_string()));
+    }
+    if let Some(url) = name.strip_prefix("custom:") {
+        return Some(("compatible", url.to_string()));
+    }
+    let configured_url = config
+        .default_provider
+        .as_deref()
+        .is_some_and(|default| default == name)
+        .then(|| config.api_url.clone())
+        .flatten();
+    let (target, default_url) = match name {
+        "openai" => ("openai", "https://api.openai.com/v1"),
+        "anthropic" => ("anthropic", "https://api.anthropic.com"),
+        "ollama" => ("ollama", "http://localhost:11434"),
+        "openrouter" => ("openrouter", "https://openrouter.ai/api/v1"),
+        "gemini" | "google" | "google-gemini" => {
+            ("gemini", "https://generativelanguage.googleapis.com/v1beta")
+        }
+        "bedrock" | "aws-bedrock" => {
+            return Some(("bedrock", bedrock::runtime_endpoint_from_env()))
+        }
+        _ => return None,
+    };
+    Some((
+        target,
+        configured_url.unwrap_or_else(|| default_url.to_string()),
+    ))
+}
+
+fn print_report(name: &str, model: &str, report: &ConformanceReport) {
+    println!("Provider conformance: {name} (model: {model})\n");
+    for outcome in &report.outcomes {
+        let (icon, detail) = match &outcome.status {
+            CaseStatus::Passed => ("✅", String::new()),
+            CaseStatus::Skipped(reason) => ("⏭️", format!(" — skipped: {reason}")),
+            CaseStatus::Failed(reason) => ("❌", format!(" — {reason}")),
+        };
+        println!("  {icon} {:<10}{detail}", outcome.case);
+    }
+    println!();
+}
+
+/// `zeroclaw providers verify`: run the suite live against `name`, optionally
+/// recording a cassette to `record`.
+pub async fn verify(
+    config: &Config,
+    name: &str,
+    model: Option<&str>,
+    record: Option<&Path>,
+) -> Result<()> {
+    let is_default = config.default_provider.as_deref() == Some(name);
+    let model = model
+        .or_else(|| {
+            is_default
+                .then_some(config.default_model.as_deref())
+                .flatten()
+        })
+        .with_context(|| format!("Pass --model to choose the model to verify {name} with"))?
+        .to_string();
+    let api_key = if is_default {
+        config.api_key.as_deref()
+    } else {
+        None
+    };
+
+    let (report, cassette) = if let Some(path) = record {
+        let (target, upstream) = recording_target(config, name).with_context(|| {
+            format!(
+                "Recording is not supported for {name}; record OpenAI-compatible providers with \
+                 `custom:<base-url>` or Anthropic-compatible ones with `anthropic-custom:<base-url>`"
+            )
+        })?;
+        let server = CassetteServer::record(&upstream).await?;
+        let credential = super::resolve_provider_credential(name, api_key);
+        let provider = provider_at(target, server.base_url(), credential.as_deref())?;
+        let report =
+            run_suite_with(provider.as_ref(), &model, |case| server.begin_case(case)).await;
+        let base_path = reqwest::Url::parse(&upstream)?
+            .path()
+            .trim_end_matches('/')
+            .to_string();
+        let cassette = Cassette {
+            provider: target.to_string(),
+            model: model.clone(),
+            base_path,
+            interactions: server.interactions(),
+        };
+        (report, Some((cassette, path)))
+    } else {
+        let options = ProviderRuntimeOptions {
+            auth_profile_override: None,
+            provider_api_url: config.api_url.clone(),
+            zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+            secrets_encrypt: config.secrets.encrypt,
+            reasoning_enabled: config.runtime.reasoning_enabled,
+            reasoning_level: config.effective_provider_reasoning_level(),
+            custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
+            max_tokens_override: None,
+            model_support_vision: config.model_support_vision,
+            model_context_window: config.model_context_window,
+        };
+        let api_url = if is_default {
+            config.api_url.as_deref()
+        } else {
+            None
+        };
+        let provider =
+            super::create_provider_with_url_and_options(name, api_key, api_url, &options)?;
+        (run_suite(provider.as_ref(), &model).await, None)
+    };
+
+    print_report(name, &model, &report);
+    if let Some((cassette, path)) = cassette {
+        cassette.save(path)?;
+        println!(
+            "Recorded {} interaction(s) to {}",
+            cassette.interactions.len(),
+            path.display()
+        );
+    }
+    if !report.passed() {
+        anyhow::bail!(
+            "{name} failed {} conformance case(s)",
+            report.failures().len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::{ChatResponse, ToolCall};
+    use async_trait::async_trait;
+
+    /// Answers every case correctly without touching the network.
+    struct ScriptedProvider;
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("pong".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            anyhow::ensure!(model != MISSING_MODEL, "model not found");
+            let prompt = &request.messages.last().unwrap().content;
+            let mut response = ChatResponse {
+                text: Some(if prompt.contains("Count") {
+                    "1 2 3 4 5".into()
+                } else {
+                    "pong".into()
+                }),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            };
+            if request.tools.is_some() {
+                response.tool_calls.push(ToolCall {
+                    id: "call_1".into(),
+                    name: "get_weather".into(),
+                    arguments: r#"{"city":"Paris"}"#.into(),
+                });
+            }
+            Ok(response)
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn suite_passes_and_skips_undeclared_capabilities() {
+        let report = run_suite(&ScriptedProvider, "scripted").await;
+        assert!(report.passed(), "{:?}", report.failures());
+        let statuses: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|outcome| (outcome.case, outcome.status.clone()))
+            .collect();
+        assert_eq!(statuses[1], ("tool_call", CaseStatus::Passed));
+        assert_eq!(
+            statuses[2],
+            ("vision", CaseStatus::Skipped("no vision support".into()))
+        );
+    }
+
+    #[test]
+    fn provider_at_rejects_unknown_targets() {
+        for target in REPLAY_PROVIDERS {
+            assert!(provider_at(target, "http://127.0.0.1:9/v1", Some("k")).is_ok());
+        }
+        for (excluded, _) in EXCLUDED_PROVIDERS {
+            assert!(provider_at(excluded, "http://127.0.0.1:9", None).is_err());
+        }
+    }
+}
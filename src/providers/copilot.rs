@@ -144,6 +144,25 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
+}
+
+impl UsageInfo {
+    fn into_token_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+            cached_input_tokens: self.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            cache_write_tokens: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -350,10 +369,7 @@ impl CopilotProvider {
         }
 
         let api_response: ApiChatResponse = response.json().await?;
-        let usage = api_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let usage = api_response.usage.map(UsageInfo::into_token_usage);
         let choice = api_response
             .choices
             .into_iter()
@@ -645,6 +661,17 @@ impl Provider for CopilotProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
+        if let Some(format) = request.response_format {
+            return super::structured::prompt_guided_chat(
+                self,
+                request,
+                format,
+                model,
+                temperature,
+            )
+            .await;
+        }
+
         self.send_chat_request(
             Self::convert_messages(request.messages),
             request.tools,
@@ -0,0 +1,712 @@
+//! In-process CPU inference for GGUF models (`provider-embedded` feature).
+//!
+//! The model name is the path of a `.gguf` file, absolute or relative to
+//! `api_url`. A HuggingFace `tokenizer.json` must sit next to it, either as
+//! `<model-stem>.tokenizer.json` or `tokenizer.json`. Supported architectures
+//! are `llama` (including Mistral-family GGUFs), `qwen2` and `phi3`; the chat
+//! template is chosen from the tokenizer's special tokens.
+//!
+//! Models are loaded on first use and kept in memory. Generation runs on the
+//! blocking thread pool, one request per model at a time. Tool calls are
+//! prompt-guided; there is no vision or native structured output.
+
+use crate::providers::model_limits::{self, ModelLimits};
+use crate::providers::tokenizer::{TokenCounter, TokenizerFamily};
+use crate::providers::traits::{
+    build_tool_instructions_text, ChatMessage, ChatRequest, ChatResponse, Provider,
+    ProviderCapabilities, StreamEvent, StreamResult, TokenUsage,
+};
+use crate::tools::ToolSpec;
+use anyhow::Context;
+use async_trait::async_trait;
+use candle_core::quantized::gguf_file;
+use candle_core::{DType, Device, Tensor};
+use candle_transformers::generation::LogitsProcessor;
+use candle_transformers::models::{quantized_llama, quantized_phi3, quantized_qwen2};
+use futures_util::stream;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokenizers::Tokenizer;
+
+const DEFAULT_MAX_TOKENS: usize = 1024;
+const REPEAT_PENALTY: f32 = 1.1;
+const REPEAT_LAST_N: usize = 64;
+const SAMPLING_SEED: u64 = 299_792_458;
+
+/// Prompt format, detected from the tokenizer vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChatTemplate {
+    ChatMl,
+    Llama3,
+    Phi3,
+    Llama2,
+}
+
+impl ChatTemplate {
+    fn detect(tokenizer: &Tokenizer) -> Self {
+        let has = |token: &str| tokenizer.token_to_id(token).is_some();
+        if has("<|im_start|>") {
+            Self::ChatMl
+        } else if has("<|start_header_id|>") {
+            Self::Llama3
+        } else if has("<|assistant|>") && has("<|end|>") {
+            Self::Phi3
+        } else {
+            Self::Llama2
+        }
+    }
+
+    fn stop_tokens(self) -> &'static [&'static str] {
+        match self {
+            Self::ChatMl => &["<|im_end|>", "<|endoftext|>"],
+            Self::Llama3 => &["<|eot_id|>", "<|end_of_text|>"],
+            Self::Phi3 => &["<|end|>", "<|endoftext|>"],
+            Self::Llama2 => &["</s>"],
+        }
+    }
+
+    /// Render the conversation, ending with an open assistant turn. Tool
+    /// results are presented as user turns.
+    fn render(self, messages: &[ChatMessage]) -> String {
+        let role = |message: &ChatMessage| match message.role.as_str() {
+            "system" => "system",
+            "assistant" => "assistant",
+            _ => "user",
+        };
+        let mut prompt = String::new();
+        match self {
+            Self::ChatMl => {
+                for message in messages {
+                    let _ = write!(
+                        prompt,
+                        "<|im_start|>{}\n{}<|im_end|>\n",
+                        role(message),
+                        message.content
+                    );
+                }
+                prompt.push_str("<|im_start|>assistant\n");
+            }
+            Self::Llama3 => {
+                prompt.push_str("<|begin_of_text|>");
+                for message in messages {
+                    let _ = write!(
+                        prompt,
+                        "<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>",
+                        role(message),
+                        message.content
+                    );
+                }
+                prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+            }
+            Self::Phi3 => {
+                for message in messages {
+                    let _ = write!(
+                        prompt,
+                        "<|{}|>\n{}<|end|>\n",
+                        role(message),
+                        message.content
+                    );
+                }
+                prompt.push_str("<|assistant|>\n");
+            }
+            Self::Llama2 => {
+                // No system role: fold system text into the next user turn.
+                let mut pending_system = String::new();
+                for message in messages {
+                    match role(message) {
+                        "system" => {
+                            pending_system.push_str(&message.content);
+                            pending_system.push_str("\n\n");
+                        }
+                        "assistant" => {
+                            let _ = write!(prompt, " {}</s>", message.content);
+                        }
+                        _ => {
+                            let _ = write!(
+                                prompt,
+                                "<s>[INST] {}{} [/INST]",
+                                std::mem::take(&mut pending_system),
+                                message.content
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        prompt
+    }
+}
+
+enum Weights {
+    Llama(quantized_llama::ModelWeights),
+    Qwen2(quantized_qwen2::ModelWeights),
+    Phi3(quantized_phi3::ModelWeights),
+}
+
+impl Weights {
+    fn forward(&mut self, input: &Tensor, index_pos: usize) -> candle_core::Result<Tensor> {
+        match self {
+            Self::Llama(model) => model.forward(input, index_pos),
+            Self::Qwen2(model) => model.forward(input, index_pos),
+            Self::Phi3(model) => model.forward(input, index_pos),
+        }
+    }
+}
+
+struct LoadedModel {
+    tokenizer: Tokenizer,
+    template: ChatTemplate,
+    stop_tokens: Vec<u32>,
+    context_length: usize,
+    weights: Mutex<Weights>,
+}
+
+impl LoadedModel {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let tokenizer_path = tokenizer_path_for(path)?;
+        let tokenizer = Tokenizer::from_file(&tokenizer_path).map_err(|err| {
+            anyhow::anyhow!(
+                "Failed to load tokenizer {}: {err}",
+                tokenizer_path.display()
+            )
+        })?;
+
+        let mut file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open model {}", path.display()))?;
+        let content = gguf_file::Content::read(&mut file)
+            .with_context(|| format!("{} is not a valid GGUF file", path.display()))?;
+        let metadata_u32 = |key: &str| {
+            content
+                .metadata
+                .get(key)
+                .and_then(|value| value.to_u32().ok())
+        };
+        let architecture = content
+            .metadata
+            .get("general.architecture")
+            .and_then(|value| value.to_string().ok())
+            .cloned()
+            .unwrap_or_default();
+        let context_length = metadata_u32(&format!("{architecture}.context_length"))
+            .map_or(quantized_llama::MAX_SEQ_LEN, |len| len as usize);
+        let eos_token = metadata_u32("tokenizer.ggml.eos_token_id");
+
+        let device = Device::Cpu;
+        let (weights, context_length) = match architecture.as_str() {
+            "llama" => (
+                Weights::Llama(quantized_llama::ModelWeights::from_gguf(
+                    content, &mut file, &device,
+                )?),
+                // candle precomputes rotary embeddings for this many positions.
+                context_length.min(quantized_llama::MAX_SEQ_LEN),
+            ),
+            "qwen2" => (
+                Weights::Qwen2(quantized_qwen2::ModelWeights::from_gguf(
+                    content, &mut file, &device,
+                )?),
+                context_length,
+            ),
+            "phi3" => (
+                Weights::Phi3(quantized_phi3::ModelWeights::from_gguf(
+                    false, content, &mut file, &device,
+                )?),
+                context_length,
+            ),
+            other => anyhow::bail!(
+                "Unsupported GGUF architecture '{other}' in {}; supported: llama, qwen2, phi3",
+                path.display()
+            ),
+        };
+
+        let template = ChatTemplate::detect(&tokenizer);
+        let mut stop_tokens: Vec<u32> = template
+            .stop_tokens()
+            .iter()
+            .filter_map(|token| tokenizer.token_to_id(token))
+            .collect();
+        stop_tokens.extend(eos_token);
+        tracing::info!(
+            model = %path.display(),
+            architecture,
+            ?template,
+            context_length,
+            "Loaded embedded GGUF model"
+        );
+
+        Ok(Self {
+            tokenizer,
+            template,
+            stop_tokens,
+            context_length,
+            weights: Mutex::new(weights),
+        })
+    }
+
+    fn encode(&self, messages: &[ChatMessage]) -> anyhow::Result<Vec<u32>> {
+        let prompt = self.template.render(messages);
+        // Templates spell out their own BOS/special tokens.
+        let encoding = self
+            .tokenizer
+            .encode(prompt, false)
+            .map_err(|err| anyhow::anyhow!("Failed to tokenize prompt: {err}"))?;
+        let tokens = encoding.get_ids().to_vec();
+        anyhow::ensure!(
+            tokens.len() < self.context_length,
+            "Prompt is {} tokens but the model context is {}",
+            tokens.len(),
+            self.context_length
+        );
+        Ok(tokens)
+    }
+
+    /// Sample up to `max_tokens` tokens after `prompt`, passing decoded text to
+    /// `emit` as it becomes available. Stops early when `emit` returns false.
+    /// Returns the number of generated tokens.
+    fn generate(
+        &self,
+        prompt: &[u32],
+        max_tokens: usize,
+        temperature: f64,
+        mut emit: impl FnMut(&str) -> bool,
+    ) -> anyhow::Result<u64> {
+        let mut weights = self.weights.lock();
+        let mut sampler = LogitsProcessor::new(
+            SAMPLING_SEED,
+            (temperature > 0.0).then_some(temperature),
+            None,
+        );
+        let max_tokens = max_tokens.min(self.context_length - prompt.len());
+        let mut tokens = prompt.to_vec();
+        let mut decoder = TextDecoder::new(prompt.len());
+        let mut generated = 0;
+
+        for step in 0..max_tokens {
+            let (input, index_pos) = if step == 0 {
+                (prompt, 0)
+            } else {
+                (&tokens[tokens.len() - 1..], tokens.len() - 1)
+            };
+            let input = Tensor::new(input, &Device::Cpu)?.unsqueeze(0)?;
+            let logits = weights
+                .forward(&input, index_pos)?
+                .squeeze(0)?
+                .to_dtype(DType::F32)?;
+            let recent = &tokens[tokens.len().saturating_sub(REPEAT_LAST_N)..];
+            let logits =
+                candle_transformers::utils::apply_repeat_penalty(&logits, REPEAT_PENALTY, recent)?;
+            let next = sampler.sample(&logits)?;
+            if self.stop_tokens.contains(&next) {
+                break;
+            }
+            tokens.push(next);
+            generated += 1;
+            if let Some(text) = decoder.next(&self.tokenizer, &tokens)? {
+                if !emit(&text) {
+                    break;
+                }
+            }
+        }
+        Ok(generated)
+    }
+}
+
+/// Exact token counts from a loaded model's tokenizer and chat template.
+struct GgufTokenCounter(Arc<LoadedModel>);
+
+impl TokenCounter for GgufTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        self.0.tokenizer.encode(text, false).map_or_else(
+            |_| TokenizerFamily::OpenWeights.counter().count(text),
+            |encoding| encoding.len(),
+        )
+    }
+
+    fn is_exact(&self) -> bool {
+        true
+    }
+
+    fn count_messages(&self, messages: &[ChatMessage]) -> usize {
+        self.count(&self.0.template.render(messages))
+    }
+
+    fn count_tools(&self, tools: &[ToolSpec]) -> usize {
+        if tools.is_empty() {
+            0
+        } else {
+            self.count(&build_tool_instructions_text(tools))
+        }
+    }
+}
+
+/// Incremental detokenizer: emits text once it decodes to whole characters,
+/// so multi-token words and UTF-8 sequences are not split.
+struct TextDecoder {
+    prev_index: usize,
+    current_index: usize,
+}
+
+impl TextDecoder {
+    fn new(start: usize) -> Self {
+        Self {
+            prev_index: start,
+            current_index: start,
+        }
+    }
+
+    fn next(&mut self, tokenizer: &Tokenizer, tokens: &[u32]) -> anyhow::Result<Option<String>> {
+        let decode = |ids: &[u32]| {
+            tokenizer
+                .decode(ids, true)
+                .map_err(|err| anyhow::anyhow!("Failed to decode tokens: {err}"))
+        };
+        let prev_text = decode(&tokens[self.prev_index..self.current_index])?;
+        let text = decode(&tokens[self.prev_index..])?;
+        if text.len() > prev_text.len() && !text.ends_with('\u{FFFD}') {
+            let delta = text.get(prev_text.len()..).map(ToString::to_string);
+            self.prev_index = self.current_index;
+            self.current_index = tokens.len();
+            Ok(delta)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn tokenizer_path_for(model: &Path) -> anyhow::Result<PathBuf> {
+    let dir = model.parent().unwrap_or_else(|| Path::new("."));
+    let stem = model
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    [
+        dir.join(format!("{stem}.tokenizer.json")),
+        dir.join("tokenizer.json"),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+    .with_context(|| {
+        format!(
+            "No tokenizer found for {}: place {stem}.tokenizer.json or tokenizer.json next to the model",
+            model.display()
+        )
+    })
+}
+
+/// Conversation with prompt-guided tool instructions appended to the system
+/// prompt (or prepended as one).
+fn with_tool_instructions(
+    messages: &[ChatMessage],
+    tools: Option<&[ToolSpec]>,
+) -> Vec<ChatMessage> {
+    let mut messages = messages.to_vec();
+    let Some(tools) = tools.filter(|tools| !tools.is_empty()) else {
+        return messages;
+    };
+    let instructions = build_tool_instructions_text(tools);
+    if let Some(system) = messages.iter_mut().find(|m| m.role == "system") {
+        if !system.content.is_empty() {
+            system.content.push_str("\n\n");
+        }
+        system.content.push_str(&instructions);
+    } else {
+        messages.insert(0, ChatMessage::system(instructions));
+    }
+    messages
+}
+
+/// In-process GGUF inference provider.
+pub struct EmbeddedProvider {
+    model_dir: Option<PathBuf>,
+    max_tokens: usize,
+    models: Arc<Mutex<HashMap<PathBuf, Arc<LoadedModel>>>>,
+}
+
+impl EmbeddedProvider {
+    /// `model_dir` resolves relative model paths; `max_tokens` caps each
+    /// reply (default 1024).
+    pub fn new(model_dir: Option<&str>, max_tokens: Option<u32>) -> Self {
+        Self {
+            model_dir: model_dir
+                .map(str::trim)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            max_tokens: max_tokens
+                .filter(|max| *max > 0)
+                .map_or(DEFAULT_MAX_TOKENS, |max| max as usize),
+            models: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn model_path(&self, model: &str) -> PathBuf {
+        let path = PathBuf::from(model);
+        match &self.model_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        }
+    }
+
+    /// The model at `model`, if it has been loaded already.
+    fn loaded(&self, model: &str) -> Option<Arc<LoadedModel>> {
+        self.models.lock().get(&self.model_path(model)).cloned()
+    }
+
+    /// Load (or reuse) the model and tokenize the conversation.
+    async fn prepare(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> anyhow::Result<(Arc<LoadedModel>, Vec<u32>)> {
+        let path = self.model_path(model);
+        let models = Arc::clone(&self.models);
+        tokio::task::spawn_blocking(move || {
+            let loaded = {
+                let mut models = models.lock();
+                if let Some(loaded) = models.get(&path) {
+                    Arc::clone(loaded)
+                } else {
+                    let loaded = Arc::new(LoadedModel::load(&path)?);
+                    models.insert(path, Arc::clone(&loaded));
+                    loaded
+                }
+            };
+            let prompt = loaded.encode(&messages)?;
+            Ok((loaded, prompt))
+        })
+        .await?
+    }
+
+    async fn complete(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (loaded, prompt) = self.prepare(messages, model).await?;
+        let max_tokens = self.max_tokens;
+        tokio::task::spawn_blocking(move || {
+            let mut text = String::new();
+            let generated = loaded.generate(&prompt, max_tokens, temperature, |delta| {
+                text.push_str(delta);
+                true
+            })?;
+            Ok(ChatResponse {
+                text: Some(text.trim().to_string()),
+                tool_calls: Vec::new(),
+                usage: Some(TokenUsage {
+                    input_tokens: Some(prompt.len() as u64),
+                    output_tokens: Some(generated),
+                    ..TokenUsage::default()
+                }),
+                reasoning_content: None,
+            })
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Provider for EmbeddedProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: false,
+            vision: false,
+            structured_output: false,
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system) = system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(message));
+        let response = self.complete(messages, model, temperature).await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let response = self.complete(messages.to_vec(), model, temperature).await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        if let Some(format) = request.response_format {
+            return super::structured::prompt_guided_chat(
+                self,
+                request,
+                format,
+                model,
+                temperature,
+            )
+            .await;
+        }
+        let messages = with_tool_instructions(request.messages, request.tools);
+        self.complete(messages, model, temperature).await
+    }
+
+    /// Exact once the model is loaded; estimated before the first request.
+    fn token_counter(&self, model: &str) -> Box<dyn TokenCounter> {
+        match self.loaded(model) {
+            Some(loaded) => Box::new(GgufTokenCounter(loaded)),
+            None => Box::new(TokenizerFamily::OpenWeights.counter()),
+        }
+    }
+
+    fn model_limits(&self, model: &str) -> ModelLimits {
+        let context_window = self.loaded(model).map_or_else(
+            || {
+                model_limits::lookup(model)
+                    .map_or(quantized_llama::MAX_SEQ_LEN, |limits| limits.context_window)
+            },
+            |loaded| loaded.context_length,
+        );
+        ModelLimits::new(context_window, self.max_tokens.min(context_window / 2))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        if request.response_format.is_some() {
+            let response = self.chat(request, model, temperature).await?;
+            return Ok(super::streaming::replay(response));
+        }
+        let messages = with_tool_instructions(request.messages, request.tools);
+        let (loaded, prompt) = self.prepare(messages, model).await?;
+        let max_tokens = self.max_tokens;
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::task::spawn_blocking(move || {
+            let result = loaded.generate(&prompt, max_tokens, temperature, |delta| {
+                tx.send(Ok(StreamEvent::TextDelta(delta.to_string())))
+                    .is_ok()
+            });
+            let event = match result {
+                Ok(generated) => Ok(StreamEvent::Usage(TokenUsage {
+                    input_tokens: Some(prompt.len() as u64),
+                    output_tokens: Some(generated),
+                    ..TokenUsage::default()
+                })),
+                Err(err) => Err(super::traits::StreamError::Provider(err.to_string())),
+            };
+            let _ = tx.send(event);
+        });
+
+        Ok(Box::pin(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Hi"),
+            ChatMessage::assistant("Hello!"),
+            ChatMessage::user("Bye"),
+        ]
+    }
+
+    #[test]
+    fn chatml_template_ends_with_open_assistant_turn() {
+        let prompt = ChatTemplate::ChatMl.render(&conversation());
+        assert!(prompt.starts_with("<|im_start|>system\nBe brief.<|im_end|>\n"));
+        assert!(prompt.contains("<|im_start|>assistant\nHello!<|im_end|>\n"));
+        assert!(prompt.ends_with("<|im_start|>user\nBye<|im_end|>\n<|im_start|>assistant\n"));
+    }
+
+    #[test]
+    fn llama2_template_folds_system_into_first_user_turn() {
+        let prompt = ChatTemplate::Llama2.render(&conversation());
+        assert_eq!(
+            prompt,
+            "<s>[INST] Be brief.\n\nHi [/INST] Hello!</s><s>[INST] Bye [/INST]"
+        );
+    }
+
+    #[test]
+    fn tool_instructions_are_added_to_system_prompt() {
+        let tools = [ToolSpec {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({ "type": "object" }),
+        }];
+        let messages = with_tool_instructions(&conversation(), Some(&tools));
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0]
+            .content
+            .starts_with("Be brief.\n\n## Tool Use Protocol"));
+        assert!(messages[0].content.contains("**shell**"));
+    }
+
+    #[test]
+    fn relative_models_resolve_against_model_dir() {
+        let provider = EmbeddedProvider::new(Some("/opt/models"), None);
+        assert_eq!(
+            provider.model_path("qwen.gguf"),
+            PathBuf::from("/opt/models/qwen.gguf")
+        );
+        assert_eq!(
+            provider.model_path("/tmp/llama.gguf"),
+            PathBuf::from("/tmp/llama.gguf")
+        );
+        let caps = provider.capabilities();
+        assert!(!caps.native_tool_calling && !caps.vision && !caps.structured_output);
+    }
+
+    #[test]
+    fn limits_before_loading_fall_back_to_registry_or_candle_cap() {
+        let provider = EmbeddedProvider::new(None, Some(512));
+        let limits = provider.model_limits("qwen2.5-7b-instruct-q4_k_m.gguf");
+        assert_eq!(limits.context_window, 32_768);
+        assert_eq!(limits.max_output_tokens, 512);
+        assert_eq!(
+            provider.model_limits("/models/custom.gguf").context_window,
+            quantized_llama::MAX_SEQ_LEN
+        );
+        assert!(!provider.token_counter("/models/custom.gguf").is_exact());
+    }
+
+    #[tokio::test]
+    async fn missing_tokenizer_is_reported_before_loading_weights() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let model = tmp.path().join("tiny.gguf");
+        std::fs::write(&model, b"GGUF").unwrap();
+        let provider = EmbeddedProvider::new(None, None);
+        let err = provider
+            .chat_with_system(None, "hi", model.to_str().unwrap(), 0.0)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("No tokenizer found"), "{err}");
+    }
+}
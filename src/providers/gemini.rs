@@ -5,14 +5,21 @@
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
 use crate::auth::AuthService;
-use crate::providers::traits::{ChatMessage, ChatResponse, Provider, TokenUsage};
+use crate::providers::traits::{
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, ResponseFormat, StreamError,
+    StreamEvent, StreamResult, TokenUsage,
+};
 use async_trait::async_trait;
 use base64::Engine;
 use directories::UserDirs;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Gemini provider supporting multiple authentication methods.
 pub struct GeminiProvider {
@@ -24,6 +31,19 @@ pub struct GeminiProvider {
     auth_service: Option<AuthService>,
     /// Override profile name for managed auth.
     auth_profile_override: Option<String>,
+    /// `cachedContents` resources created for large system instructions,
+    /// keyed by [`GeminiProvider::context_cache_key`].
+    context_caches: Arc<parking_lot::Mutex<HashMap<String, CachedContext>>>,
+    /// Base URL of the public API (API-key auth).
+    api_base: String,
+}
+
+/// A Gemini context cache holding a system instruction.
+struct CachedContext {
+    /// `cachedContents` resource name, or `None` when creating it failed and
+    /// the instruction is sent inline until `expires_at`.
+    name: Option<String>,
+    expires_at: Instant,
 }
 
 /// Mutable OAuth token state — supports runtime refresh for long-lived processes.
@@ -87,6 +107,9 @@ struct GenerateContentRequest {
     contents: Vec<Content>,
     #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
     system_instruction: Option<Content>,
+    /// Name of a `cachedContents` resource that replaces `systemInstruction`.
+    #[serde(rename = "cachedContent", skip_serializing_if = "Option::is_none")]
+    cached_content: Option<String>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
 }
@@ -144,6 +167,10 @@ struct GenerationConfig {
     temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -162,6 +189,19 @@ struct GeminiUsageMetadata {
     prompt_token_count: Option<u64>,
     #[serde(default, rename = "candidatesTokenCount")]
     candidates_token_count: Option<u64>,
+    #[serde(default, rename = "cachedContentTokenCount")]
+    cached_content_token_count: Option<u64>,
+}
+
+impl GeminiUsageMetadata {
+    fn into_token_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.prompt_token_count,
+            output_tokens: self.candidates_token_count,
+            cached_input_tokens: self.cached_content_token_count,
+            cache_write_tokens: None,
+        }
+    }
 }
 
 /// Response envelope for the internal cloudcode-pa API.
@@ -284,6 +324,19 @@ const LOAD_CODE_ASSIST_ENDPOINT: &str =
 /// Public API endpoint for API key users.
 const PUBLIC_API_ENDPOINT: &str = "https://generativelanguage.googleapis.com/v1beta";
 
+/// System instructions at least this long (~4K tokens, above every model's
+/// minimum cacheable size) are moved into an explicit context cache.
+const CONTEXT_CACHE_MIN_CHARS: usize = 16_384;
+
+/// Lifetime requested for context caches.
+const CONTEXT_CACHE_TTL_SECS: u64 = 3600;
+
+/// Stop reusing a context cache this long before it expires server-side.
+const CONTEXT_CACHE_EXPIRY_MARGIN_SECS: u64 = 300;
+
+/// After a failed context cache creation, wait this long before retrying it.
+const CONTEXT_CACHE_FAILURE_BACKOFF_SECS: u64 = 600;
+
 // ══════════════════════════════════════════════════════════════════════════════
 // TOKEN REFRESH
 // ══════════════════════════════════════════════════════════════════════════════
@@ -450,6 +503,8 @@ impl GeminiProvider {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None,
             auth_profile_override: None,
+            context_caches: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            api_base: PUBLIC_API_ENDPOINT.to_string(),
         }
     }
 
@@ -523,9 +578,18 @@ impl GeminiProvider {
                 None
             },
             auth_profile_override: profile_override,
+            context_caches: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            api_base: PUBLIC_API_ENDPOINT.to_string(),
         }
     }
 
+    /// Send API-key requests to `base_url` instead of the public
+    /// `generativelanguage.googleapis.com/v1beta` endpoint.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.api_base = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
     fn normalize_non_empty(value: &str) -> Option<String> {
         let trimmed = value.trim();
         if trimmed.is_empty() {
@@ -776,7 +840,7 @@ impl GeminiProvider {
     /// not the public API. Sending them to the public endpoint results in
     /// "400 Bad Request: API key not valid" errors.
     /// See: https://github.com/google-gemini/gemini-cli/issues/19200
-    fn build_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+    fn build_generate_content_url(api_base: &str, model: &str, auth: &GeminiAuth) -> String {
         match auth {
             GeminiAuth::OAuthToken(_) | GeminiAuth::ManagedOAuth => {
                 // OAuth tokens are scoped for the internal Code Assist API.
@@ -785,7 +849,7 @@ impl GeminiProvider {
             }
             _ => {
                 let model_name = Self::format_model_name(model);
-                let base_url = format!("{PUBLIC_API_ENDPOINT}/{model_name}:generateContent");
+                let base_url = format!("{api_base}/{model_name}:generateContent");
 
                 if auth.is_api_key() {
                     format!("{base_url}?key={}", auth.api_key_credential())
@@ -796,10 +860,127 @@ impl GeminiProvider {
         }
     }
 
+    fn build_stream_generate_content_url(api_base: &str, model: &str, auth: &GeminiAuth) -> String {
+        let url = Self::build_generate_content_url(api_base, model, auth).replacen(
+            ":generateContent",
+            ":streamGenerateContent",
+            1,
+        );
+        if url.contains('?') {
+            url.replacen('?', "?alt=sse&", 1)
+        } else {
+            format!("{url}?alt=sse")
+        }
+    }
+
     fn http_client(&self) -> Client {
         crate::config::build_runtime_proxy_client_with_timeouts("provider.gemini", 120, 10)
     }
 
+    /// Cache key for a system instruction, or `None` when it is too small
+    /// to be worth a context cache.
+    fn context_cache_key(model: &str, system_instruction: &Content) -> Option<String> {
+        let text_len: usize = system_instruction
+            .parts
+            .iter()
+            .map(|part| part.text.len())
+            .sum();
+        if text_len < CONTEXT_CACHE_MIN_CHARS {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for part in &system_instruction.parts {
+            part.text.hash(&mut hasher);
+        }
+        Some(format!(
+            "{}:{:016x}",
+            Self::format_model_name(model),
+            hasher.finish()
+        ))
+    }
+
+    /// Return the `cachedContents` resource holding `system_instruction`,
+    /// creating it on first use. Failures fall back to sending the
+    /// instruction inline and are remembered for
+    /// [`CONTEXT_CACHE_FAILURE_BACKOFF_SECS`] before the create is retried.
+    async fn cached_system_instruction(
+        &self,
+        auth: &GeminiAuth,
+        model: &str,
+        system_instruction: &Content,
+    ) -> Option<String> {
+        let key = Self::context_cache_key(model, system_instruction)?;
+        {
+            let mut caches = self.context_caches.lock();
+            match caches.get(&key) {
+                Some(cached) if cached.expires_at > Instant::now() => {
+                    return cached.name.clone();
+                }
+                Some(_) => {
+                    caches.remove(&key);
+                }
+                None => {}
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct CachedContentResponse {
+            name: String,
+        }
+
+        let url = format!(
+            "{}/cachedContents?key={}",
+            self.api_base,
+            auth.api_key_credential()
+        );
+        let body = serde_json::json!({
+            "model": Self::format_model_name(model),
+            "systemInstruction": system_instruction,
+            "ttl": format!("{CONTEXT_CACHE_TTL_SECS}s"),
+        });
+        let created = async {
+            let response = self.http_client().post(&url).json(&body).send().await?;
+            if !response.status().is_success() {
+                return Err(super::api_error("Gemini", response).await);
+            }
+            Ok(response.json::<CachedContentResponse>().await?)
+        }
+        .await;
+
+        match created {
+            Ok(cached) => {
+                let expires_at = Instant::now()
+                    + Duration::from_secs(
+                        CONTEXT_CACHE_TTL_SECS - CONTEXT_CACHE_EXPIRY_MARGIN_SECS,
+                    );
+                self.context_caches.lock().insert(
+                    key,
+                    CachedContext {
+                        name: Some(cached.name.clone()),
+                        expires_at,
+                    },
+                );
+                Some(cached.name)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    model,
+                    "Gemini context cache creation failed, sending system instruction inline \
+                     for the next {CONTEXT_CACHE_FAILURE_BACKOFF_SECS}s: {e}"
+                );
+                self.context_caches.lock().insert(
+                    key,
+                    CachedContext {
+                        name: None,
+                        expires_at: Instant::now()
+                            + Duration::from_secs(CONTEXT_CACHE_FAILURE_BACKOFF_SECS),
+                    },
+                );
+                None
+            }
+        }
+    }
+
     /// Resolve the GCP project ID for OAuth by calling the loadCodeAssist endpoint.
     /// Caches the result for subsequent calls.
     async fn resolve_oauth_project(&self, token: &str) -> anyhow::Result<String> {
@@ -933,13 +1114,110 @@ impl GeminiProvider {
 }
 
 impl GeminiProvider {
+    /// Reduce a JSON Schema to the OpenAPI subset `responseSchema` accepts;
+    /// Gemini rejects the request outright on unknown keywords.
+    fn gemini_response_schema(schema: &serde_json::Value) -> serde_json::Value {
+        const KEPT: &[&str] = &[
+            "type",
+            "format",
+            "description",
+            "nullable",
+            "enum",
+            "required",
+            "minItems",
+            "maxItems",
+            "minimum",
+            "maximum",
+        ];
+        let Some(object) = schema.as_object() else {
+            return schema.clone();
+        };
+        let mut out = serde_json::Map::new();
+        for (key, value) in object {
+            match key.as_str() {
+                "properties" => {
+                    if let Some(props) = value.as_object() {
+                        let props = props
+                            .iter()
+                            .map(|(name, prop)| (name.clone(), Self::gemini_response_schema(prop)))
+                            .collect();
+                        out.insert(key.clone(), serde_json::Value::Object(props));
+                    }
+                }
+                "items" => {
+                    out.insert(key.clone(), Self::gemini_response_schema(value));
+                }
+                "anyOf" => {
+                    if let Some(variants) = value.as_array() {
+                        let variants = variants.iter().map(Self::gemini_response_schema).collect();
+                        out.insert(key.clone(), serde_json::Value::Array(variants));
+                    }
+                }
+                "const" => {
+                    out.insert("enum".to_string(), serde_json::json!([value]));
+                }
+                _ if KEPT.contains(&key.as_str()) => {
+                    out.insert(key.clone(), value.clone());
+                }
+                _ => {}
+            }
+        }
+        serde_json::Value::Object(out)
+    }
+
     async fn send_generate_content(
         &self,
         contents: Vec<Content>,
         system_instruction: Option<Content>,
         model: &str,
         temperature: f64,
+        response_format: Option<&ResponseFormat>,
     ) -> anyhow::Result<(String, Option<TokenUsage>)> {
+        let response = self
+            .send_generate_content_request(
+                contents,
+                system_instruction,
+                model,
+                temperature,
+                response_format,
+                false,
+            )
+            .await?;
+
+        let result: GenerateContentResponse = response.json().await?;
+        if let Some(err) = &result.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+        let result = result.into_effective_response();
+        if let Some(err) = result.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+
+        let usage = result
+            .usage_metadata
+            .map(GeminiUsageMetadata::into_token_usage);
+
+        let text = result
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .and_then(|c| c.content)
+            .and_then(|c| c.effective_text())
+            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))?;
+
+        Ok((text, usage))
+    }
+
+    /// Send a `generateContent` (or, with `stream`, SSE `streamGenerateContent`)
+    /// request, handling OAuth rotation and `generationConfig` fallbacks.
+    async fn send_generate_content_request(
+        &self,
+        contents: Vec<Content>,
+        system_instruction: Option<Content>,
+        model: &str,
+        temperature: f64,
+        response_format: Option<&ResponseFormat>,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
         let auth = self.auth.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "Gemini API key not found. Options:\n\
@@ -982,16 +1260,39 @@ impl GeminiProvider {
             _ => (None, None),
         };
 
+        // Large, stable system instructions are served from a context cache
+        // (API-key auth only; the internal OAuth endpoint has no cache API).
+        let cached_content = match system_instruction.as_ref() {
+            Some(instruction) if auth.is_api_key() => {
+                self.cached_system_instruction(auth, model, instruction)
+                    .await
+            }
+            _ => None,
+        };
+        let system_instruction = system_instruction.filter(|_| cached_content.is_none());
+
         let request = GenerateContentRequest {
             contents,
             system_instruction,
+            cached_content,
             generation_config: GenerationConfig {
                 temperature,
                 max_output_tokens: 8192,
+                response_mime_type: response_format.map(|_| "application/json".to_string()),
+                response_schema: response_format.and_then(|format| match format {
+                    ResponseFormat::JsonObject => None,
+                    ResponseFormat::JsonSchema { schema, .. } => {
+                        Some(Self::gemini_response_schema(schema))
+                    }
+                }),
             },
         };
 
-        let url = Self::build_generate_content_url(model, auth);
+        let url = if stream {
+            Self::build_stream_generate_content_url(&self.api_base, model, auth)
+        } else {
+            Self::build_generate_content_url(&self.api_base, model, auth)
+        };
 
         let mut response = self
             .build_generate_content_request(
@@ -1118,28 +1419,78 @@ impl GeminiProvider {
             anyhow::bail!("Gemini API error ({status}): {error_text}");
         }
 
-        let result: GenerateContentResponse = response.json().await?;
-        if let Some(err) = &result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
-        }
-        let result = result.into_effective_response();
-        if let Some(err) = result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
+        Ok(response)
+    }
+
+    /// Split history into Gemini `contents` and a `systemInstruction`.
+    fn convert_messages(messages: &[ChatMessage]) -> (Vec<Content>, Option<Content>) {
+        let mut system_parts: Vec<&str> = Vec::new();
+        let mut contents: Vec<Content> = Vec::new();
+
+        for msg in messages {
+            match msg.role.as_str() {
+                "system" => system_parts.push(&msg.content),
+                "user" => contents.push(Content {
+                    role: Some("user".to_string()),
+                    parts: vec![Part {
+                        text: msg.content.clone(),
+                    }],
+                }),
+                "assistant" => contents.push(Content {
+                    role: Some("model".to_string()),
+                    parts: vec![Part {
+                        text: msg.content.clone(),
+                    }],
+                }),
+                _ => {}
+            }
         }
 
-        let usage = result.usage_metadata.map(|u| TokenUsage {
-            input_tokens: u.prompt_token_count,
-            output_tokens: u.candidates_token_count,
-        });
+        let system_instruction = if system_parts.is_empty() {
+            None
+        } else {
+            Some(Content {
+                role: None,
+                parts: vec![Part {
+                    text: system_parts.join("\n\n"),
+                }],
+            })
+        };
 
-        let text = result
+        (contents, system_instruction)
+    }
+
+    /// Map one `streamGenerateContent` SSE payload to stream events.
+    fn parse_stream_chunk(data: &str) -> StreamResult<Vec<StreamEvent>> {
+        let chunk: GenerateContentResponse =
+            serde_json::from_str(data).map_err(StreamError::Json)?;
+        let chunk = chunk.into_effective_response();
+        if let Some(err) = chunk.error {
+            return Err(StreamError::Provider(super::sanitize_api_error(
+                &err.message,
+            )));
+        }
+
+        let mut events = Vec::new();
+        let parts = chunk
             .candidates
             .and_then(|c| c.into_iter().next())
             .and_then(|c| c.content)
-            .and_then(|c| c.effective_text())
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))?;
-
-        Ok((text, usage))
+            .map(|c| c.parts)
+            .unwrap_or_default();
+        for part in parts {
+            match part.text {
+                Some(text) if !text.is_empty() && part.thought => {
+                    events.push(StreamEvent::ReasoningDelta(text));
+                }
+                Some(text) if !text.is_empty() => events.push(StreamEvent::TextDelta(text)),
+                _ => {}
+            }
+        }
+        if let Some(usage) = chunk.usage_metadata {
+            events.push(StreamEvent::Usage(usage.into_token_usage()));
+        }
+        Ok(events)
     }
 }
 
@@ -1167,7 +1518,7 @@ impl Provider for GeminiProvider {
         }];
 
         let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(contents, system_instruction, model, temperature, None)
             .await?;
         Ok(text)
     }
@@ -1219,7 +1570,7 @@ impl Provider for GeminiProvider {
         };
 
         let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(contents, system_instruction, model, temperature, None)
             .await?;
         Ok(text)
     }
@@ -1230,41 +1581,16 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let mut system_parts: Vec<&str> = Vec::new();
-        let mut contents: Vec<Content> = Vec::new();
-
-        for msg in request.messages {
-            match msg.role.as_str() {
-                "system" => system_parts.push(&msg.content),
-                "user" => contents.push(Content {
-                    role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
-                }),
-                "assistant" => contents.push(Content {
-                    role: Some("model".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
-                }),
-                _ => {}
-            }
-        }
-
-        let system_instruction = if system_parts.is_empty() {
-            None
-        } else {
-            Some(Content {
-                role: None,
-                parts: vec![Part {
-                    text: system_parts.join("\n\n"),
-                }],
-            })
-        };
+        let (contents, system_instruction) = Self::convert_messages(request.messages);
 
         let (text, usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(
+                contents,
+                system_instruction,
+                model,
+                temperature,
+                request.response_format,
+            )
             .await?;
 
         Ok(ChatResponse {
@@ -1275,6 +1601,41 @@ impl Provider for GeminiProvider {
         })
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream_chat(
+        &self,
+        request: crate::providers::traits::ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let (contents, system_instruction) = Self::convert_messages(request.messages);
+        let response = self
+            .send_generate_content_request(
+                contents,
+                system_instruction,
+                model,
+                temperature,
+                request.response_format,
+                true,
+            )
+            .await?;
+        Ok(super::streaming::sse_events(
+            response,
+            Self::parse_stream_chunk,
+        ))
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: false,
+            vision: false,
+            structured_output: true,
+        }
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(auth) = self.auth.as_ref() {
             match auth {
@@ -1306,12 +1667,9 @@ impl Provider for GeminiProvider {
                 _ => {
                     // API key path — verify with public API models endpoint.
                     let url = if auth.is_api_key() {
-                        format!(
-                            "https://generativelanguage.googleapis.com/v1beta/models?key={}",
-                            auth.api_key_credential()
-                        )
+                        format!("{}/models?key={}", self.api_base, auth.api_key_credential())
                     } else {
-                        "https://generativelanguage.googleapis.com/v1beta/models".to_string()
+                        format!("{}/models", self.api_base)
                     };
 
                     self.http_client()
@@ -1350,6 +1708,8 @@ mod tests {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None,
             auth_profile_override: None,
+            context_caches: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            api_base: PUBLIC_API_ENDPOINT.to_string(),
         }
     }
 
@@ -1514,14 +1874,22 @@ mod tests {
     #[test]
     fn api_key_url_includes_key_query_param() {
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         assert!(url.contains(":generateContent?key=api-key-123"));
     }
 
     #[test]
     fn oauth_url_uses_internal_endpoint() {
         let auth = test_oauth_auth("ya29.test-token");
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         assert!(url.starts_with("https://cloudcode-pa.googleapis.com/v1internal"));
         assert!(url.ends_with(":generateContent"));
         assert!(!url.contains("generativelanguage.googleapis.com"));
@@ -1531,7 +1899,11 @@ mod tests {
     #[test]
     fn api_key_url_uses_public_endpoint() {
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         assert!(url.contains("generativelanguage.googleapis.com/v1beta"));
         assert!(url.contains("models/gemini-2.0-flash"));
     }
@@ -1540,7 +1912,11 @@ mod tests {
     fn oauth_request_uses_bearer_auth_header() {
         let provider = test_provider(Some(test_oauth_auth("ya29.mock-token")));
         let auth = test_oauth_auth("ya29.mock-token");
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
@@ -1549,9 +1925,12 @@ mod tests {
                 }],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -1581,7 +1960,11 @@ mod tests {
     fn oauth_request_wraps_payload_in_request_envelope() {
         let provider = test_provider(Some(test_oauth_auth("ya29.mock-token")));
         let auth = test_oauth_auth("ya29.mock-token");
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
@@ -1590,9 +1973,12 @@ mod tests {
                 }],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -1625,7 +2011,11 @@ mod tests {
     fn api_key_request_does_not_set_bearer_header() {
         let provider = test_provider(Some(GeminiAuth::ExplicitKey("api-key-123".into())));
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
-        let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
+        let url = GeminiProvider::build_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
@@ -1634,9 +2024,12 @@ mod tests {
                 }],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -1671,9 +2064,12 @@ mod tests {
                     text: "You are helpful".to_string(),
                 }],
             }),
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -1703,6 +2099,8 @@ mod tests {
                 generation_config: Some(GenerationConfig {
                     temperature: 0.7,
                     max_output_tokens: 8192,
+                    response_mime_type: None,
+                    response_schema: None,
                 }),
             },
         };
@@ -2123,6 +2521,8 @@ mod tests {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None, // Missing auth_service
             auth_profile_override: None,
+            context_caches: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            api_base: PUBLIC_API_ENDPOINT.to_string(),
         };
 
         let result = provider.warmup().await;
@@ -2141,4 +2541,119 @@ mod tests {
         // Should succeed without making HTTP requests
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn response_schema_keeps_only_supported_keywords() {
+        let schema = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["kind", "tags"],
+            "properties": {
+                "kind": {"const": "report"},
+                "tags": {"type": "array", "items": {"type": "string", "minLength": 1}}
+            }
+        });
+        let reduced = GeminiProvider::gemini_response_schema(&schema);
+        assert_eq!(
+            reduced,
+            serde_json::json!({
+                "type": "object",
+                "required": ["kind", "tags"],
+                "properties": {
+                    "kind": {"enum": ["report"]},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn context_cache_key_only_for_large_system_instructions() {
+        let small = Content {
+            role: None,
+            parts: vec![Part {
+                text: "Be brief.".into(),
+            }],
+        };
+        assert!(GeminiProvider::context_cache_key("gemini-2.5-pro", &small).is_none());
+
+        let large = Content {
+            role: None,
+            parts: vec![Part {
+                text: "x".repeat(CONTEXT_CACHE_MIN_CHARS),
+            }],
+        };
+        let key = GeminiProvider::context_cache_key("gemini-2.5-pro", &large).unwrap();
+        assert!(key.starts_with("models/gemini-2.5-pro:"));
+        assert_ne!(
+            GeminiProvider::context_cache_key("gemini-2.5-flash", &large),
+            Some(key)
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_context_cache_creation_is_remembered() {
+        let provider = test_provider(Some(GeminiAuth::ExplicitKey("api-key".into())))
+            .with_base_url("http://127.0.0.1:9");
+        let auth = GeminiAuth::ExplicitKey("api-key".into());
+        let large = Content {
+            role: None,
+            parts: vec![Part {
+                text: "x".repeat(CONTEXT_CACHE_MIN_CHARS),
+            }],
+        };
+
+        assert!(provider
+            .cached_system_instruction(&auth, "gemini-2.5-pro", &large)
+            .await
+            .is_none());
+
+        let key = GeminiProvider::context_cache_key("gemini-2.5-pro", &large).unwrap();
+        let caches = provider.context_caches.lock();
+        let entry = caches.get(&key).expect("failure should be cached");
+        assert!(entry.name.is_none());
+        assert!(entry.expires_at > Instant::now());
+    }
+
+    #[test]
+    fn usage_metadata_reports_cached_tokens() {
+        let json = r#"{"usageMetadata":{"promptTokenCount":5000,"candidatesTokenCount":10,"cachedContentTokenCount":4096}}"#;
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let usage = resp.usage_metadata.unwrap().into_token_usage();
+        assert_eq!(usage.input_tokens, Some(5000));
+        assert_eq!(usage.cached_input_tokens, Some(4096));
+    }
+
+    #[test]
+    fn stream_url_uses_sse_endpoint() {
+        let auth = GeminiAuth::ExplicitKey("api-key".into());
+        let url = GeminiProvider::build_stream_generate_content_url(
+            PUBLIC_API_ENDPOINT,
+            "gemini-2.0-flash",
+            &auth,
+        );
+        assert!(url.contains("models/gemini-2.0-flash:streamGenerateContent?alt=sse&key="));
+    }
+
+    #[test]
+    fn stream_chunk_separates_thoughts_and_reports_usage() {
+        let data = r#"{"candidates":[{"content":{"parts":[
+            {"text":"weighing options","thought":true},
+            {"text":"Hello"}
+        ]}}],"usageMetadata":{"promptTokenCount":5,"candidatesTokenCount":2}}"#;
+        let events = GeminiProvider::parse_stream_chunk(data).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::ReasoningDelta("weighing options".into()),
+                StreamEvent::TextDelta("Hello".into()),
+                StreamEvent::Usage(TokenUsage {
+                    input_tokens: Some(5),
+                    output_tokens: Some(2),
+                    ..TokenUsage::default()
+                }),
+            ]
+        );
+    }
 }
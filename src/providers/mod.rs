@@ -17,23 +17,35 @@
 //! in [`create_provider_with_url`]. See `AGENTS.md` §7.1 for the full change playbook.
 
 pub mod anthropic;
+pub mod batch;
 pub mod bedrock;
+pub mod cassette;
+pub mod circuit_breaker;
 pub mod compatible;
+pub mod conformance;
 pub mod copilot;
+#[cfg(feature = "provider-embedded")]
+pub mod embedded;
 pub mod gemini;
+pub mod model_limits;
 pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
+pub mod quota_adapter;
+pub mod quota_types;
 pub mod reliable;
 pub mod router;
+pub mod streaming;
+pub mod structured;
 pub mod telnyx;
+pub mod tokenizer;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
     ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ResponseFormat, StreamEvent, ToolCall, ToolResultMessage,
 };
 
 use crate::auth::AuthService;
@@ -682,6 +694,7 @@ pub struct ProviderRuntimeOptions {
     pub custom_provider_api_mode: Option<CompatibleApiMode>,
     pub max_tokens_override: Option<u32>,
     pub model_support_vision: Option<bool>,
+    pub model_context_window: Option<usize>,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -696,6 +709,7 @@ impl Default for ProviderRuntimeOptions {
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            model_context_window: None,
         }
     }
 }
@@ -754,11 +768,7 @@ pub fn scrub_secret_patterns(input: &str) -> String {
 
     for (prefix, min_len) in PREFIXES {
         let mut search_from = 0;
-        loop {
-            let Some(rel) = scrubbed[search_from..].find(prefix) else {
-                break;
-            };
-
+        while let Some(rel) = scrubbed[search_from..].find(prefix) {
             let start = search_from + rel;
             let content_start = start + prefix.len();
             let end = token_end(&scrubbed, content_start);
@@ -1004,7 +1014,7 @@ pub fn create_provider_with_url(
 
 /// Factory: create provider with optional base URL and runtime options.
 #[allow(clippy::too_many_lines)]
-fn create_provider_with_url_and_options(
+pub fn create_provider_with_url_and_options(
     name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
@@ -1267,6 +1277,21 @@ fn create_provider_with_url_and_options(
                 AuthStyle::Bearer,
             )))
         }
+        "embedded" | "gguf" => {
+            #[cfg(feature = "provider-embedded")]
+            {
+                Ok(Box::new(embedded::EmbeddedProvider::new(
+                    api_url,
+                    options.max_tokens_override,
+                )))
+            }
+            #[cfg(not(feature = "provider-embedded"))]
+            {
+                anyhow::bail!(
+                    "provider 'embedded' requested but this build was compiled without `provider-embedded`; rebuild with `--features provider-embedded`"
+                )
+            }
+        }
         "nvidia" | "nvidia-nim" | "build.nvidia.com" => Ok(Box::new(
             OpenAiCompatibleProvider::new_no_responses_fallback(
                 "NVIDIA NIM",
@@ -1371,6 +1396,43 @@ pub fn create_resilient_provider_with_options(
     reliability: &crate::config::ReliabilityConfig,
     options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
+    Ok(Box::new(build_reliable_provider(
+        primary_name,
+        api_key,
+        api_url,
+        reliability,
+        options,
+    )?))
+}
+
+/// Create the provider chain for channel replies.
+///
+/// Same as [`create_resilient_provider_with_options`], plus hedged requests
+/// when `reliability.channel_hedge_delay_ms` is set.
+pub fn create_channel_provider_with_options(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let mut reliable =
+        build_reliable_provider(primary_name, api_key, api_url, reliability, options)?;
+    if reliability.channel_hedge_delay_ms > 0 {
+        reliable = reliable.with_hedging(std::time::Duration::from_millis(
+            reliability.channel_hedge_delay_ms,
+        ));
+    }
+    Ok(Box::new(reliable))
+}
+
+fn build_reliable_provider(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<ReliableProvider> {
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
 
     let primary_provider = match primary_name {
@@ -1424,9 +1486,12 @@ pub fn create_resilient_provider_with_options(
     )
     .with_api_keys(reliability.api_keys.clone())
     .with_model_fallbacks(reliability.model_fallbacks.clone())
-    .with_vision_override(options.model_support_vision);
+    .with_vision_override(options.model_support_vision)
+    .with_context_window_override(options.model_context_window)
+    .with_circuit_breaker(circuit_breaker::BreakerSettings::from_config(reliability))
+    .with_cost_tracker(crate::cost::tracker::shared());
 
-    Ok(Box::new(reliable))
+    Ok(reliable)
 }
 
 /// Create a RouterProvider if model routes are configured, otherwise return a
@@ -1471,6 +1536,60 @@ pub fn create_routed_provider_with_options(
         );
     }
 
+    Ok(Box::new(build_router(
+        primary_name,
+        api_key,
+        api_url,
+        reliability,
+        model_routes,
+        default_model,
+        options,
+    )?))
+}
+
+/// Create a routed provider from the full config, with `[model_routing]`
+/// adaptive selection and route decisions reported to `observer`.
+pub fn create_routed_provider_for_config(
+    config: &crate::config::Config,
+    primary_name: &str,
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
+    observer: std::sync::Arc<dyn crate::observability::Observer>,
+) -> anyhow::Result<Box<dyn Provider>> {
+    if config.model_routes.is_empty() {
+        return create_resilient_provider_with_options(
+            primary_name,
+            config.api_key.as_deref(),
+            config.api_url.as_deref(),
+            &config.reliability,
+            options,
+        );
+    }
+
+    let router = build_router(
+        primary_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        default_model,
+        options,
+    )?
+    .with_adaptive_routing(&config.model_routing, config.cost.prices.clone())
+    .with_persisted_stats(&config.workspace_dir)
+    .with_observer(observer);
+    Ok(Box::new(router))
+}
+
+fn build_router(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    model_routes: &[crate::config::ModelRouteConfig],
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<router::RouterProvider> {
     // Keep a default provider for non-routed model hints.
     let default_provider = create_resilient_provider_with_options(
         primary_name,
@@ -1506,7 +1625,12 @@ pub fn create_routed_provider_with_options(
             &route_options,
         ) {
             Ok(provider) => {
-                let provider_id = format!("{}#{}", route.provider, route.hint);
+                // Adaptive routing allows several routes per hint, possibly on
+                // the same provider; keep every instance addressable.
+                let mut provider_id = format!("{}#{}", route.provider, route.hint);
+                if providers.iter().any(|(name, _)| *name == provider_id) {
+                    provider_id = format!("{provider_id}#{}", providers.len());
+                }
                 providers.push((provider_id.clone(), provider));
                 routes.push((
                     route.hint.clone(),
@@ -1526,24 +1650,10 @@ pub fn create_routed_provider_with_options(
         }
     }
 
-    // Build route table
-    let routes: Vec<(String, router::Route)> = model_routes
-        .iter()
-        .map(|r| {
-            (
-                r.hint.clone(),
-                router::Route {
-                    provider_name: r.provider.clone(),
-                    model: r.model.clone(),
-                },
-            )
-        })
-        .collect();
-
-    Ok(Box::new(
+    Ok(
         router::RouterProvider::new(providers, routes, default_model.to_string())
             .with_vision_override(options.model_support_vision),
-    ))
+    )
 }
 
 /// Information about a supported provider for display purposes.
@@ -1563,7 +1673,7 @@ pub struct ProviderInfo {
 /// This is intentionally separate from the factory match in `create_provider`
 /// (display concern vs. construction concern).
 pub fn list_providers() -> Vec<ProviderInfo> {
-    vec![
+    let mut providers = vec![
         // ── Primary providers ────────────────────────────────
         ProviderInfo {
             name: "openrouter",
@@ -1807,7 +1917,14 @@ pub fn list_providers() -> Vec<ProviderInfo> {
             aliases: &["ovh"],
             local: false,
         },
-    ]
+    ];
+    providers.extend(cfg!(feature = "provider-embedded").then_some(ProviderInfo {
+        name: "embedded",
+        display_name: "Embedded GGUF (in-process)",
+        aliases: &["gguf"],
+        local: true,
+    }));
+    providers
 }
 
 #[cfg(test)]
@@ -2372,6 +2489,21 @@ mod tests {
         assert!(p.is_ok());
     }
 
+    #[cfg(not(feature = "provider-embedded"))]
+    #[test]
+    fn factory_embedded_requires_feature() {
+        let err = create_provider("embedded", None).err().unwrap().to_string();
+        assert!(err.contains("--features provider-embedded"), "{err}");
+        assert!(!list_providers().iter().any(|p| p.name == "embedded"));
+    }
+
+    #[cfg(feature = "provider-embedded")]
+    #[test]
+    fn factory_embedded() {
+        assert!(create_provider("embedded", None).is_ok());
+        assert!(create_provider("gguf", None).is_ok());
+    }
+
     #[test]
     fn resolve_provider_credential_osaurus_env() {
         let _env_lock = env_lock();
@@ -2597,6 +2729,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         let provider = create_resilient_provider(
@@ -2636,6 +2772,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         // Primary uses a ZAI key; fallbacks (lmstudio, ollama) should NOT
@@ -2658,6 +2798,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         let provider =
@@ -2684,6 +2828,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2716,6 +2864,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -3097,6 +3249,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         // openai-codex resolves its own OAuth credential; it should not
@@ -3126,6 +3282,10 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
+            circuit_half_open_successes: 1,
+            channel_hedge_delay_ms: 0,
         };
 
         let provider = create_resilient_provider("ollama", None, None, &reliability);
@@ -0,0 +1,179 @@
+//! Built-in registry of model context windows and output limits.
+//!
+//! Used to size requests before sending them. Lookups match on the bare
+//! model name, so `anthropic/claude-sonnet-4-20250514`,
+//! `claude-sonnet-4-20250514` and Bedrock's
+//! `us.anthropic.claude-sonnet-4-20250514-v1:0` resolve to the same entry.
+//! Models missing from the table get [`ModelLimits::DEFAULT`]; set
+//! `model_context_window` in config for local or unlisted models.
+
+/// Token limits of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelLimits {
+    /// Total tokens per request (prompt plus reply).
+    pub context_window: usize,
+    /// Longest reply the model can produce.
+    pub max_output_tokens: usize,
+}
+
+impl ModelLimits {
+    /// Limits assumed for unknown models: the context most current hosted
+    /// models offer at minimum.
+    pub const DEFAULT: Self = Self::new(128_000, 8_192);
+
+    pub const fn new(context_window: usize, max_output_tokens: usize) -> Self {
+        Self {
+            context_window,
+            max_output_tokens,
+        }
+    }
+
+    /// Same limits with the context window replaced, keeping the output
+    /// limit within it.
+    pub fn with_context_window(self, context_window: usize) -> Self {
+        Self {
+            context_window,
+            max_output_tokens: self.max_output_tokens.min(context_window / 2),
+        }
+    }
+
+    /// Tokens available for the prompt after reserving room for the reply.
+    /// At most a quarter of the window is reserved so large-output models
+    /// keep most of their context for input.
+    pub fn input_budget(&self) -> usize {
+        let reserve = self.max_output_tokens.min(self.context_window / 4);
+        self.context_window.saturating_sub(reserve)
+    }
+}
+
+/// Known models, most specific prefix first. Keys are normalized: lowercase
+/// with `.`, `-`, `_` and `:` removed.
+const REGISTRY: &[(&str, ModelLimits)] = &[
+    // Anthropic
+    ("claudeopus4", ModelLimits::new(200_000, 32_000)),
+    ("claudesonnet4", ModelLimits::new(200_000, 64_000)),
+    ("claudehaiku4", ModelLimits::new(200_000, 64_000)),
+    ("claude37sonnet", ModelLimits::new(200_000, 64_000)),
+    ("claude35", ModelLimits::new(200_000, 8_192)),
+    ("claude3", ModelLimits::new(200_000, 4_096)),
+    ("claude", ModelLimits::new(200_000, 8_192)),
+    // OpenAI
+    ("gpt5", ModelLimits::new(400_000, 128_000)),
+    ("gpt41", ModelLimits::new(1_047_576, 32_768)),
+    ("gpt4o", ModelLimits::new(128_000, 16_384)),
+    ("gpt4turbo", ModelLimits::new(128_000, 4_096)),
+    ("gpt4", ModelLimits::new(8_192, 4_096)),
+    ("gpt35turbo", ModelLimits::new(16_385, 4_096)),
+    ("gptoss", ModelLimits::new(131_072, 32_768)),
+    ("o1mini", ModelLimits::new(128_000, 65_536)),
+    ("o1", ModelLimits::new(200_000, 100_000)),
+    ("o3", ModelLimits::new(200_000, 100_000)),
+    ("o4mini", ModelLimits::new(200_000, 100_000)),
+    // Google
+    ("gemini25", ModelLimits::new(1_048_576, 65_536)),
+    ("gemini20", ModelLimits::new(1_048_576, 8_192)),
+    ("gemini15pro", ModelLimits::new(2_097_152, 8_192)),
+    ("gemini15", ModelLimits::new(1_048_576, 8_192)),
+    ("gemma3", ModelLimits::new(131_072, 8_192)),
+    // Open-weight families (native context; local servers may be configured lower)
+    ("llama4", ModelLimits::new(1_048_576, 16_384)),
+    ("llama33", ModelLimits::new(131_072, 8_192)),
+    ("llama32", ModelLimits::new(131_072, 8_192)),
+    ("llama31", ModelLimits::new(131_072, 8_192)),
+    ("llama3", ModelLimits::new(8_192, 4_096)),
+    ("qwen3", ModelLimits::new(131_072, 16_384)),
+    ("qwen25", ModelLimits::new(32_768, 8_192)),
+    ("mistrallarge", ModelLimits::new(131_072, 8_192)),
+    ("mistral", ModelLimits::new(32_768, 8_192)),
+    ("mixtral", ModelLimits::new(32_768, 8_192)),
+    ("deepseek", ModelLimits::new(131_072, 8_192)),
+    ("kimik2", ModelLimits::new(131_072, 16_384)),
+    ("glm4", ModelLimits::new(131_072, 16_384)),
+];
+
+/// Bedrock region and vendor prefixes (`us.anthropic.claude-...`).
+const BEDROCK_PREFIXES: &[&str] = &[
+    "us",
+    "eu",
+    "apac",
+    "global",
+    "anthropic",
+    "amazon",
+    "meta",
+    "mistral",
+    "cohere",
+    "deepseek",
+];
+
+fn normalize(model: &str) -> String {
+    let model = model.to_ascii_lowercase();
+    let mut bare = model.rsplit('/').next().unwrap_or(&model);
+    while let Some((head, rest)) = bare.split_once('.') {
+        if BEDROCK_PREFIXES.contains(&head) {
+            bare = rest;
+        } else {
+            break;
+        }
+    }
+    bare.chars()
+        .filter(|c| !matches!(c, '.' | '-' | '_' | ':'))
+        .collect()
+}
+
+/// Registry entry for `model`, if known.
+pub fn lookup(model: &str) -> Option<ModelLimits> {
+    let key = normalize(model);
+    REGISTRY
+        .iter()
+        .find(|(prefix, _)| key.starts_with(prefix))
+        .map(|(_, limits)| *limits)
+}
+
+/// Limits for `model`, falling back to [`ModelLimits::DEFAULT`].
+pub fn limits_for(model: &str) -> ModelLimits {
+    lookup(model).unwrap_or(ModelLimits::DEFAULT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_matches_prefixed_and_bedrock_ids() {
+        let sonnet = lookup("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(lookup("anthropic/claude-sonnet-4-20250514"), Some(sonnet));
+        assert_eq!(
+            lookup("us.anthropic.claude-sonnet-4-20250514-v1:0"),
+            Some(sonnet)
+        );
+        assert_eq!(sonnet.context_window, 200_000);
+
+        assert_eq!(lookup("claude-3.5-sonnet"), lookup("claude-3-5-sonnet"));
+        assert_eq!(lookup("llama3.1:8b").unwrap().context_window, 131_072);
+        assert_eq!(lookup("llama3:8b").unwrap().context_window, 8_192);
+    }
+
+    #[test]
+    fn more_specific_entries_win() {
+        assert_eq!(lookup("gpt-4o-mini").unwrap().context_window, 128_000);
+        assert_eq!(lookup("gpt-4").unwrap().context_window, 8_192);
+        assert_eq!(lookup("openai/o1-mini").unwrap().max_output_tokens, 65_536);
+        assert_eq!(lookup("o1").unwrap().max_output_tokens, 100_000);
+    }
+
+    #[test]
+    fn unknown_models_use_default() {
+        assert!(lookup("acme/mystery-model").is_none());
+        assert_eq!(limits_for("acme/mystery-model"), ModelLimits::DEFAULT);
+    }
+
+    #[test]
+    fn input_budget_reserves_reply_room() {
+        assert_eq!(ModelLimits::new(200_000, 8_192).input_budget(), 191_808);
+        // Reserve is capped at a quarter of the window.
+        assert_eq!(ModelLimits::new(200_000, 100_000).input_budget(), 150_000);
+        let small = ModelLimits::DEFAULT.with_context_window(8_192);
+        assert_eq!(small.max_output_tokens, 4_096);
+        assert_eq!(small.input_budget(), 6_144);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::multimodal;
 use crate::providers::traits::{
-    ChatMessage, ChatResponse, Provider, ProviderCapabilities, TokenUsage, ToolCall,
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, ResponseFormat, StreamError,
+    StreamEvent, StreamResult, TokenUsage, ToolCall,
 };
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -25,6 +28,8 @@ struct ChatRequest {
     think: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -69,6 +74,21 @@ struct ApiChatResponse {
     eval_count: Option<u64>,
 }
 
+/// One line of a streaming `/api/chat` response.
+#[derive(Debug, Deserialize)]
+struct StreamChatChunk {
+    #[serde(default)]
+    message: Option<ResponseMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ResponseMessage {
     #[serde(default)]
@@ -205,6 +225,7 @@ impl OllamaProvider {
         model: &str,
         temperature: f64,
         tools: Option<&[serde_json::Value]>,
+        format: Option<&ResponseFormat>,
     ) -> ChatRequest {
         ChatRequest {
             model: model.to_string(),
@@ -213,6 +234,16 @@ impl OllamaProvider {
             options: Options { temperature },
             think: self.reasoning_enabled,
             tools: tools.map(|t| t.to_vec()),
+            format: format.map(Self::format_value),
+        }
+    }
+
+    /// Ollama takes `"json"` for free-form JSON mode and a raw JSON Schema
+    /// for constrained decoding.
+    fn format_value(format: &ResponseFormat) -> serde_json::Value {
+        match format {
+            ResponseFormat::JsonObject => serde_json::Value::String("json".to_string()),
+            ResponseFormat::JsonSchema { schema, .. } => schema.clone(),
         }
     }
 
@@ -352,8 +383,9 @@ impl OllamaProvider {
         temperature: f64,
         should_auth: bool,
         tools: Option<&[serde_json::Value]>,
+        format: Option<&ResponseFormat>,
     ) -> anyhow::Result<ApiChatResponse> {
-        let request = self.build_chat_request(messages, model, temperature, tools);
+        let request = self.build_chat_request(messages, model, temperature, tools, format);
 
         let url = format!("{}/api/chat", self.base_url);
 
@@ -367,7 +399,118 @@ impl OllamaProvider {
             request.tools.as_ref().map_or(0, |t| t.len()),
         );
 
-        let mut request_builder = self.http_client().post(&url).json(&request);
+        let response = self.post_chat(&url, &request, should_auth).await?;
+        let body = response.bytes().await?;
+        tracing::debug!("Ollama response body length: {} bytes", body.len());
+
+        let chat_response: ApiChatResponse = match serde_json::from_slice(&body) {
+            Ok(r) => r,
+            Err(e) => {
+                let raw = String::from_utf8_lossy(&body);
+                let sanitized = super::sanitize_api_error(&raw);
+                tracing::error!(
+                    "Ollama response deserialization failed: {e}. body_excerpt={}",
+                    sanitized
+                );
+                anyhow::bail!("Failed to parse Ollama response: {e}");
+            }
+        };
+
+        Ok(chat_response)
+    }
+
+    /// Native chat shared by `chat_with_tools` and structured-output requests.
+    async fn chat_native(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        format: Option<&ResponseFormat>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
+
+        let api_messages = self.convert_messages(messages);
+
+        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
+        // tools_to_openai_format() in loop_.rs — pass them through directly.
+        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
+
+        let response = self
+            .send_request(
+                api_messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                tools_opt,
+                format,
+            )
+            .await?;
+
+        let usage = if response.prompt_eval_count.is_some() || response.eval_count.is_some() {
+            Some(TokenUsage {
+                input_tokens: response.prompt_eval_count,
+                output_tokens: response.eval_count,
+                ..TokenUsage::default()
+            })
+        } else {
+            None
+        };
+
+        // Native tool calls returned by the model.
+        if !response.message.tool_calls.is_empty() {
+            let tool_calls: Vec<ToolCall> = response
+                .message
+                .tool_calls
+                .iter()
+                .map(|tc| {
+                    let (name, args) = Self::extract_tool_name_and_args(tc);
+                    ToolCall {
+                        id: tc
+                            .id
+                            .clone()
+                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                        name,
+                        arguments: serde_json::to_string(&args)
+                            .unwrap_or_else(|_| "{}".to_string()),
+                    }
+                })
+                .collect();
+            let text = Self::normalize_response_text(response.message.content);
+            return Ok(ChatResponse {
+                text,
+                tool_calls,
+                usage,
+                reasoning_content: None,
+            });
+        }
+
+        // Plain text response.
+        let content = response.message.content;
+        let text = if let Some(content) = Self::normalize_response_text(content) {
+            content
+        } else {
+            Self::fallback_text_for_empty_content(
+                &normalized_model,
+                response.message.thinking.as_deref(),
+            )
+        };
+        Ok(ChatResponse {
+            text: Some(text),
+            tool_calls: vec![],
+            usage,
+            reasoning_content: None,
+        })
+    }
+
+    /// POST a chat request, turning non-success statuses into errors.
+    async fn post_chat(
+        &self,
+        url: &str,
+        request: &ChatRequest,
+        should_auth: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let mut request_builder = self.http_client().post(url).json(request);
 
         if should_auth {
             if let Some(key) = self.api_key.as_ref() {
@@ -379,11 +522,8 @@ impl OllamaProvider {
         let status = response.status();
         tracing::debug!("Ollama response status: {}", status);
 
-        let body = response.bytes().await?;
-        tracing::debug!("Ollama response body length: {} bytes", body.len());
-
         if !status.is_success() {
-            let raw = String::from_utf8_lossy(&body);
+            let raw = response.text().await.unwrap_or_default();
             let sanitized = super::sanitize_api_error(&raw);
             tracing::error!(
                 "Ollama error response: status={} body_excerpt={}",
@@ -397,20 +537,65 @@ impl OllamaProvider {
             );
         }
 
-        let chat_response: ApiChatResponse = match serde_json::from_slice(&body) {
-            Ok(r) => r,
-            Err(e) => {
-                let raw = String::from_utf8_lossy(&body);
-                let sanitized = super::sanitize_api_error(&raw);
-                tracing::error!(
-                    "Ollama response deserialization failed: {e}. body_excerpt={}",
-                    sanitized
-                );
-                anyhow::bail!("Failed to parse Ollama response: {e}");
+        Ok(response)
+    }
+
+    /// Map one NDJSON line of a streaming `/api/chat` response to events.
+    /// Ollama sends each tool call whole, so `next_index` numbers them.
+    fn parse_stream_line(line: &str, next_index: &mut usize) -> StreamResult<Vec<StreamEvent>> {
+        if line.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let chunk: StreamChatChunk = serde_json::from_str(line).map_err(StreamError::Json)?;
+        if let Some(error) = chunk.error {
+            return Err(StreamError::Provider(super::sanitize_api_error(&error)));
+        }
+
+        let mut events = Vec::new();
+        if let Some(message) = chunk.message {
+            if let Some(thinking) = message.thinking.filter(|t| !t.is_empty()) {
+                events.push(StreamEvent::ReasoningDelta(thinking));
             }
-        };
+            if !message.content.is_empty() {
+                events.push(StreamEvent::TextDelta(message.content));
+            }
+            for tc in &message.tool_calls {
+                let (name, args) = Self::extract_tool_name_and_args(tc);
+                events.push(StreamEvent::ToolCallDelta {
+                    index: *next_index,
+                    id: tc.id.clone(),
+                    name: Some(name),
+                    arguments: args.to_string(),
+                });
+                *next_index += 1;
+            }
+        }
+        if chunk.done && (chunk.prompt_eval_count.is_some() || chunk.eval_count.is_some()) {
+            events.push(StreamEvent::Usage(TokenUsage {
+                input_tokens: chunk.prompt_eval_count,
+                output_tokens: chunk.eval_count,
+                ..TokenUsage::default()
+            }));
+        }
+        Ok(events)
+    }
 
-        Ok(chat_response)
+    /// Convert ToolSpec to the OpenAI-compatible JSON `/api/chat` accepts.
+    fn tools_to_json(specs: Option<&[ToolSpec]>) -> Vec<serde_json::Value> {
+        specs
+            .unwrap_or_default()
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": s.name,
+                        "description": s.description,
+                        "parameters": s.parameters
+                    }
+                })
+            })
+            .collect()
     }
 
     /// Convert Ollama tool calls to the JSON format expected by parse_tool_calls in loop_.rs
@@ -422,7 +607,7 @@ impl OllamaProvider {
         let formatted_calls: Vec<serde_json::Value> = tool_calls
             .iter()
             .map(|tc| {
-                let (tool_name, tool_args) = self.extract_tool_name_and_args(tc);
+                let (tool_name, tool_args) = Self::extract_tool_name_and_args(tc);
 
                 // Arguments must be a JSON string for parse_tool_calls compatibility
                 let args_str =
@@ -447,7 +632,7 @@ impl OllamaProvider {
     }
 
     /// Extract the actual tool name and arguments from potentially nested structures
-    fn extract_tool_name_and_args(&self, tc: &OllamaToolCall) -> (String, serde_json::Value) {
+    fn extract_tool_name_and_args(tc: &OllamaToolCall) -> (String, serde_json::Value) {
         let name = &tc.function.name;
         let args = &tc.function.arguments;
 
@@ -491,6 +676,7 @@ impl Provider for OllamaProvider {
         ProviderCapabilities {
             native_tool_calling: true,
             vision: true,
+            structured_output: true,
         }
     }
 
@@ -525,7 +711,14 @@ impl Provider for OllamaProvider {
         });
 
         let response = self
-            .send_request(messages, &normalized_model, temperature, should_auth, None)
+            .send_request(
+                messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                None,
+                None,
+            )
             .await?;
 
         // If model returned tool calls, format them for loop_.rs's parse_tool_calls
@@ -566,6 +759,7 @@ impl Provider for OllamaProvider {
                 temperature,
                 should_auth,
                 None,
+                None,
             )
             .await?;
 
@@ -597,77 +791,40 @@ impl Provider for OllamaProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
-
-        let api_messages = self.convert_messages(messages);
-
-        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
-        // tools_to_openai_format() in loop_.rs — pass them through directly.
-        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
-
-        let response = self
-            .send_request(
-                api_messages,
-                &normalized_model,
-                temperature,
-                should_auth,
-                tools_opt,
-            )
-            .await?;
+        self.chat_native(messages, tools, None, model, temperature)
+            .await
+    }
 
-        let usage = if response.prompt_eval_count.is_some() || response.eval_count.is_some() {
-            Some(TokenUsage {
-                input_tokens: response.prompt_eval_count,
-                output_tokens: response.eval_count,
-            })
-        } else {
-            None
-        };
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 
-        // Native tool calls returned by the model.
-        if !response.message.tool_calls.is_empty() {
-            let tool_calls: Vec<ToolCall> = response
-                .message
-                .tool_calls
-                .iter()
-                .map(|tc| {
-                    let (name, args) = self.extract_tool_name_and_args(tc);
-                    ToolCall {
-                        id: tc
-                            .id
-                            .clone()
-                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
-                        name,
-                        arguments: serde_json::to_string(&args)
-                            .unwrap_or_else(|_| "{}".to_string()),
-                    }
-                })
-                .collect();
-            let text = Self::normalize_response_text(response.message.content);
-            return Ok(ChatResponse {
-                text,
-                tool_calls,
-                usage,
-                reasoning_content: None,
-            });
-        }
+    async fn stream_chat(
+        &self,
+        request: crate::providers::traits::ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
+        let tools = Self::tools_to_json(request.tools);
+        let messages =
+            super::structured::native_messages(request.messages, request.response_format);
+        let mut chat_request = self.build_chat_request(
+            self.convert_messages(&messages),
+            &normalized_model,
+            temperature,
+            (!tools.is_empty()).then_some(tools.as_slice()),
+            request.response_format,
+        );
+        chat_request.stream = true;
 
-        // Plain text response.
-        let content = response.message.content;
-        let text = if let Some(content) = Self::normalize_response_text(content) {
-            content
-        } else {
-            Self::fallback_text_for_empty_content(
-                &normalized_model,
-                response.message.thinking.as_deref(),
-            )
-        };
-        Ok(ChatResponse {
-            text: Some(text),
-            tool_calls: vec![],
-            usage,
-            reasoning_content: None,
-        })
+        let url = format!("{}/api/chat", self.base_url);
+        let response = self.post_chat(&url, &chat_request, should_auth).await?;
+        let mut next_index = 0;
+        Ok(super::streaming::parse_lines(
+            super::streaming::body_lines(response.bytes_stream()),
+            move |line| Self::parse_stream_line(line, &mut next_index),
+        ))
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -683,26 +840,20 @@ impl Provider for OllamaProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        // Convert ToolSpec to OpenAI-compatible JSON and delegate to chat_with_tools.
-        if let Some(specs) = request.tools {
-            if !specs.is_empty() {
-                let tools: Vec<serde_json::Value> = specs
-                    .iter()
-                    .map(|s| {
-                        serde_json::json!({
-                            "type": "function",
-                            "function": {
-                                "name": s.name,
-                                "description": s.description,
-                                "parameters": s.parameters
-                            }
-                        })
-                    })
-                    .collect();
-                return self
-                    .chat_with_tools(request.messages, &tools, model, temperature)
-                    .await;
-            }
+        // Tools and response formats both need the native request path.
+        let tools = Self::tools_to_json(request.tools);
+        if request.response_format.is_some() || !tools.is_empty() {
+            let messages =
+                super::structured::native_messages(request.messages, request.response_format);
+            return self
+                .chat_native(
+                    &messages,
+                    &tools,
+                    request.response_format,
+                    model,
+                    temperature,
+                )
+                .await;
         }
 
         // No tools — fall back to plain text chat.
@@ -820,12 +971,69 @@ mod tests {
             "llama3",
             0.7,
             None,
+            None,
         );
 
         let json = serde_json::to_value(request).unwrap();
         assert!(json.get("think").is_none());
     }
 
+    #[test]
+    fn stream_lines_map_content_tool_calls_and_usage() {
+        let lines = [
+            r#"{"message":{"role":"assistant","content":"On it"},"done":false}"#,
+            "",
+            r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"tool.shell","arguments":{"command":"date"}}}]},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":""},"done":true,"prompt_eval_count":9,"eval_count":4}"#,
+        ];
+        let mut next_index = 0;
+        let mut acc = crate::providers::streaming::StreamAccumulator::new();
+        for line in lines {
+            for event in OllamaProvider::parse_stream_line(line, &mut next_index).unwrap() {
+                acc.push(event);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("On it"));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"date"}"#);
+        assert_eq!(response.usage.unwrap().input_tokens, Some(9));
+
+        let err = OllamaProvider::parse_stream_line(r#"{"error":"model not found"}"#, &mut 0)
+            .unwrap_err();
+        assert!(err.to_string().contains("model not found"));
+    }
+
+    #[test]
+    fn request_maps_response_format() {
+        let provider = OllamaProvider::new(None, None);
+        let message = || Message {
+            role: "user".to_string(),
+            content: Some("hello".to_string()),
+            images: None,
+            tool_calls: None,
+            tool_name: None,
+        };
+
+        let request = provider.build_chat_request(vec![message()], "llama3", 0.7, None, None);
+        let json = serde_json::to_value(request).unwrap();
+        assert!(json.get("format").is_none());
+
+        let json_mode = ResponseFormat::JsonObject;
+        let request =
+            provider.build_chat_request(vec![message()], "llama3", 0.7, None, Some(&json_mode));
+        let json = serde_json::to_value(request).unwrap();
+        assert_eq!(json["format"], "json");
+
+        let schema = serde_json::json!({"type": "object", "required": ["ok"]});
+        let format = ResponseFormat::json_schema("result", schema.clone());
+        let request =
+            provider.build_chat_request(vec![message()], "llama3", 0.7, None, Some(&format));
+        let json = serde_json::to_value(request).unwrap();
+        assert_eq!(json["format"], schema);
+    }
+
     #[test]
     fn request_includes_think_when_reasoning_configured() {
         let provider = OllamaProvider::new_with_reasoning(None, None, Some(false));
@@ -840,6 +1048,7 @@ mod tests {
             "llama3",
             0.7,
             None,
+            None,
         );
 
         let json = serde_json::to_value(request).unwrap();
@@ -904,7 +1113,6 @@ mod tests {
 
     #[test]
     fn extract_tool_name_handles_nested_tool_call() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -915,14 +1123,13 @@ mod tests {
                 }),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "shell");
         assert_eq!(args.get("command").unwrap(), "date");
     }
 
     #[test]
     fn extract_tool_name_handles_prefixed_name() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -930,14 +1137,13 @@ mod tests {
                 arguments: serde_json::json!({"command": "ls"}),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "shell");
         assert_eq!(args.get("command").unwrap(), "ls");
     }
 
     #[test]
     fn extract_tool_name_handles_normal_call() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -945,7 +1151,7 @@ mod tests {
                 arguments: serde_json::json!({"path": "/tmp/test"}),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "file_read");
         assert_eq!(args.get("path").unwrap(), "/tmp/test");
     }
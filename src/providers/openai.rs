@@ -1,9 +1,14 @@
+use crate::providers::batch::{self, BatchOutcome, BatchRequest, BatchStatus};
+use crate::providers::quota_adapter::QuotaTracker;
+use crate::providers::quota_types::QuotaMetadata;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, StreamEvent, StreamResult, TokenUsage,
+    ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +16,8 @@ pub struct OpenAiProvider {
     base_url: String,
     credential: Option<String>,
     max_tokens_override: Option<u32>,
+    /// Latest rate-limit quota from response headers.
+    quota: QuotaTracker,
 }
 
 #[derive(Debug, Serialize)]
@@ -67,6 +74,12 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -140,6 +153,25 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
+}
+
+impl UsageInfo {
+    fn into_token_usage(self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+            cached_input_tokens: self.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            cache_write_tokens: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -167,6 +199,92 @@ impl NativeResponseMessage {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct BatchInputLine {
+    custom_id: String,
+    method: &'static str,
+    url: &'static str,
+    body: ChatRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileObject {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchObject {
+    id: String,
+    status: String,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    error_file_id: Option<String>,
+    #[serde(default)]
+    errors: Option<BatchErrors>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchErrors {
+    #[serde(default)]
+    data: Vec<BatchErrorItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchErrorItem {
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResultLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<BatchResultResponse>,
+    #[serde(default)]
+    error: Option<BatchErrorItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResultResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+impl BatchResultLine {
+    fn into_outcome(self) -> BatchOutcome {
+        let response = match (self.response, self.error) {
+            (_, Some(error)) => return BatchOutcome::failure(self.custom_id, error.message),
+            (Some(response), None) => response,
+            (None, None) => return BatchOutcome::failure(self.custom_id, "empty batch result"),
+        };
+        if response.status_code != 200 {
+            let message = response
+                .body
+                .pointer("/error/message")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("request failed");
+            return BatchOutcome::failure(
+                self.custom_id,
+                format!("HTTP {}: {message}", response.status_code),
+            );
+        }
+        match serde_json::from_value::<NativeChatResponse>(response.body) {
+            Ok(body) => {
+                let text = body
+                    .choices
+                    .into_iter()
+                    .next()
+                    .and_then(|c| c.message.effective_content())
+                    .unwrap_or_default();
+                let usage = body.usage.map(UsageInfo::into_token_usage);
+                BatchOutcome::success(self.custom_id, text, usage)
+            }
+            Err(e) => BatchOutcome::failure(self.custom_id, format!("invalid response body: {e}")),
+        }
+    }
+}
+
 impl OpenAiProvider {
     pub fn new(credential: Option<&str>) -> Self {
         Self::with_base_url_and_max_tokens(None, credential, None)
@@ -189,6 +307,7 @@ impl OpenAiProvider {
                 .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
             credential: credential.map(ToString::to_string),
             max_tokens_override: max_tokens_override.filter(|value| *value > 0),
+            quota: QuotaTracker::default(),
         }
     }
 
@@ -304,9 +423,112 @@ impl OpenAiProvider {
         }
     }
 
+    fn native_chat_request(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> NativeChatRequest {
+        let tools = Self::convert_tools(request.tools);
+        let messages =
+            super::structured::native_messages(request.messages, request.response_format);
+        NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages(&messages),
+            temperature,
+            max_tokens: self.max_tokens_override,
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+            response_format: request
+                .response_format
+                .map(super::structured::openai_response_format),
+            stream: None,
+            stream_options: None,
+        }
+    }
+
+    async fn send_native_request(
+        &self,
+        native_request: &NativeChatRequest,
+    ) -> anyhow::Result<reqwest::Response> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
+        let response = self
+            .http_client()
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .json(native_request)
+            .send()
+            .await?;
+        self.quota.observe("openai", response.headers());
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        Ok(response)
+    }
+
     fn http_client(&self) -> Client {
         crate::config::build_runtime_proxy_client_with_timeouts("provider.openai", 120, 10)
     }
+
+    fn require_credential(&self) -> anyhow::Result<&str> {
+        self.credential.as_deref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })
+    }
+
+    fn batch_input(
+        &self,
+        requests: &[BatchRequest],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let lines = requests
+            .iter()
+            .map(|request| BatchInputLine {
+                custom_id: request.custom_id.clone(),
+                method: "POST",
+                url: "/v1/chat/completions",
+                body: ChatRequest {
+                    model: model.to_string(),
+                    messages: request
+                        .messages
+                        .iter()
+                        .map(|m| Message {
+                            role: m.role.clone(),
+                            content: m.content.clone(),
+                        })
+                        .collect(),
+                    temperature,
+                    max_tokens: self.max_tokens_override,
+                },
+            })
+            .collect::<Vec<_>>();
+        batch::to_jsonl(&lines)
+    }
+
+    fn parse_batch_results(body: &str) -> anyhow::Result<Vec<BatchOutcome>> {
+        Ok(batch::parse_jsonl::<BatchResultLine>(body)?
+            .into_iter()
+            .map(BatchResultLine::into_outcome)
+            .collect())
+    }
+
+    async fn download_file(&self, credential: &str, file_id: &str) -> anyhow::Result<String> {
+        let response = self
+            .http_client()
+            .get(format!("{}/files/{file_id}/content", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        Ok(response.text().await?)
+    }
 }
 
 #[async_trait]
@@ -350,6 +572,7 @@ impl Provider for OpenAiProvider {
             .json(&request)
             .send()
             .await?;
+        self.quota.observe("openai", response.headers());
 
         if !response.status().is_success() {
             return Err(super::api_error("OpenAI", response).await);
@@ -371,37 +594,11 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
-
-        let tools = Self::convert_tools(request.tools);
-        let native_request = NativeChatRequest {
-            model: model.to_string(),
-            messages: Self::convert_messages(request.messages),
-            temperature,
-            max_tokens: self.max_tokens_override,
-            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
-            tools,
-        };
-
-        let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
-            .json(&native_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
-        }
+        let native_request = self.native_chat_request(request, model, temperature);
+        let response = self.send_native_request(&native_request).await?;
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let usage = native_response.usage.map(UsageInfo::into_token_usage);
         let message = native_response
             .choices
             .into_iter()
@@ -413,6 +610,38 @@ impl Provider for OpenAiProvider {
         Ok(result)
     }
 
+    fn quota(&self) -> Option<QuotaMetadata> {
+        self.quota.latest()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let mut native_request = self.native_chat_request(request, model, temperature);
+        native_request.stream = Some(true);
+        native_request.stream_options = Some(serde_json::json!({ "include_usage": true }));
+        let response = self.send_native_request(&native_request).await?;
+        Ok(super::streaming::sse_events(
+            response,
+            super::streaming::parse_openai_chunk,
+        ))
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: false,
+            structured_output: true,
+        }
+    }
+
     fn supports_native_tools(&self) -> bool {
         true
     }
@@ -447,6 +676,9 @@ impl Provider for OpenAiProvider {
             max_tokens: self.max_tokens_override,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            response_format: None,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -456,16 +688,14 @@ impl Provider for OpenAiProvider {
             .json(&native_request)
             .send()
             .await?;
+        self.quota.observe("openai", response.headers());
 
         if !response.status().is_success() {
             return Err(super::api_error("OpenAI", response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let usage = native_response.usage.map(UsageInfo::into_token_usage);
         let message = native_response
             .choices
             .into_iter()
@@ -477,6 +707,101 @@ impl Provider for OpenAiProvider {
         Ok(result)
     }
 
+    fn supports_batch(&self) -> bool {
+        true
+    }
+
+    async fn submit_batch(
+        &self,
+        requests: &[BatchRequest],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let credential = self.require_credential()?;
+        let part =
+            reqwest::multipart::Part::text(self.batch_input(requests, model, temperature)?)
+                .file_name("batch.jsonl")
+                .mime_str("application/jsonl")?;
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", "batch")
+            .part("file", part);
+        let response = self
+            .http_client()
+            .post(format!("{}/files", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .multipart(form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        let file: FileObject = response.json().await?;
+
+        let response = self
+            .http_client()
+            .post(format!("{}/batches", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .json(&serde_json::json!({
+                "input_file_id": file.id,
+                "endpoint": "/v1/chat/completions",
+                "completion_window": "24h",
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        let batch: BatchObject = response.json().await?;
+        Ok(batch.id)
+    }
+
+    async fn poll_batch(&self, batch_id: &str) -> anyhow::Result<BatchStatus> {
+        let credential = self.require_credential()?;
+        let response = self
+            .http_client()
+            .get(format!("{}/batches/{batch_id}", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        let batch: BatchObject = response.json().await?;
+
+        match batch.status.as_str() {
+            "completed" | "expired" | "cancelled" => {}
+            "failed" => {
+                let reason = batch
+                    .errors
+                    .map(|errors| {
+                        errors
+                            .data
+                            .into_iter()
+                            .map(|e| e.message)
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    })
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or_else(|| "batch failed".to_string());
+                return Ok(BatchStatus::Failed(reason));
+            }
+            _ => return Ok(BatchStatus::Pending),
+        }
+
+        let mut outcomes = Vec::new();
+        for file_id in [&batch.output_file_id, &batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let body = self.download_file(credential, file_id).await?;
+            outcomes.extend(Self::parse_batch_results(&body)?);
+        }
+        if outcomes.is_empty() && batch.status != "completed" {
+            return Ok(BatchStatus::Failed(format!("batch {}", batch.status)));
+        }
+        Ok(BatchStatus::Completed(outcomes))
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(credential) = self.credential.as_ref() {
             self.http_client()
@@ -512,6 +837,38 @@ mod tests {
         assert_eq!(p.credential.as_deref(), Some(""));
     }
 
+    #[test]
+    fn batch_input_targets_chat_completions() {
+        let p = OpenAiProvider::new(Some("key"));
+        let requests = [BatchRequest {
+            custom_id: "job-1".into(),
+            messages: vec![ChatMessage::user("hello")],
+        }];
+        let body = p.batch_input(&requests, "gpt-4o-mini", 0.2).unwrap();
+        let line: serde_json::Value = serde_json::from_str(body.trim()).unwrap();
+        assert_eq!(line["custom_id"], "job-1");
+        assert_eq!(line["url"], "/v1/chat/completions");
+        assert_eq!(line["body"]["model"], "gpt-4o-mini");
+        assert_eq!(line["body"]["messages"][0]["content"], "hello");
+    }
+
+    #[test]
+    fn parse_batch_results_maps_success_and_errors() {
+        let body = r#"{"custom_id":"a","response":{"status_code":200,"body":{"choices":[{"message":{"content":"done"}}],"usage":{"prompt_tokens":10,"completion_tokens":2}}},"error":null}
+{"custom_id":"b","response":{"status_code":400,"body":{"error":{"message":"bad model"}}},"error":null}
+{"custom_id":"c","response":null,"error":{"code":"batch_expired","message":"expired"}}"#;
+        let outcomes = OpenAiProvider::parse_batch_results(body).unwrap();
+        assert_eq!(outcomes.len(), 3);
+        let ok = outcomes[0].result.as_ref().unwrap();
+        assert_eq!(ok.text, "done");
+        assert_eq!(ok.usage.as_ref().unwrap().input_tokens, Some(10));
+        assert_eq!(
+            outcomes[1].result.as_ref().unwrap_err(),
+            "HTTP 400: bad model"
+        );
+        assert_eq!(outcomes[2].result.as_ref().unwrap_err(), "expired");
+    }
+
     #[tokio::test]
     async fn chat_fails_without_key() {
         let p = OpenAiProvider::new(None);
@@ -568,6 +925,43 @@ mod tests {
         assert!(json.contains("\"temperature\":0.0"));
     }
 
+    #[test]
+    fn native_request_serializes_response_format() {
+        let format = crate::providers::ResponseFormat::json_schema(
+            "verdict",
+            serde_json::json!({"type": "object", "required": ["ok"]}),
+        );
+        let req = NativeChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: Vec::new(),
+            temperature: 0.0,
+            max_tokens: None,
+            tools: None,
+            tool_choice: None,
+            response_format: Some(crate::providers::structured::openai_response_format(
+                &format,
+            )),
+            stream: None,
+            stream_options: None,
+        };
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["response_format"]["type"], "json_schema");
+        assert_eq!(json["response_format"]["json_schema"]["name"], "verdict");
+        assert_eq!(
+            json["response_format"]["json_schema"]["schema"]["required"][0],
+            "ok"
+        );
+
+        let plain = NativeChatRequest {
+            response_format: None,
+            ..req
+        };
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("response_format")
+            .is_none());
+    }
+
     #[test]
     fn response_deserializes_single_choice() {
         let json = r#"{"choices":[{"message":{"content":"Hi!"}}]}"#;
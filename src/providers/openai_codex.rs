@@ -448,11 +448,7 @@ fn parse_sse_text(body: &str) -> anyhow::Result<Option<String>> {
         Ok(())
     };
 
-    loop {
-        let Some(idx) = buffer.find("\n\n") else {
-            break;
-        };
-
+    while let Some(idx) = buffer.find("\n\n") {
         let chunk = buffer[..idx].to_string();
         buffer = buffer[idx + 2..].to_string();
         process_chunk(&chunk)?;
@@ -651,6 +647,7 @@ impl Provider for OpenAiCodexProvider {
         ProviderCapabilities {
             native_tool_calling: false,
             vision: true,
+            structured_output: false,
         }
     }
 
@@ -1085,6 +1082,7 @@ data: [DONE]
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            model_context_window: None,
         };
         let provider =
             OpenAiCodexProvider::new(&options, None).expect("provider should initialize");
@@ -13,6 +13,39 @@ use super::quota_types::QuotaMetadata;
 use chrono::{DateTime, Utc};
 use reqwest::header::HeaderMap;
 use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static EXTRACTOR: LazyLock<UniversalQuotaExtractor> = LazyLock::new(UniversalQuotaExtractor::new);
+
+/// Quota for `provider` from response headers, or from `error` when the
+/// headers carry none, using the built-in extractors.
+pub fn extract_quota(
+    provider: &str,
+    headers: &HeaderMap,
+    error: Option<&anyhow::Error>,
+) -> Option<QuotaMetadata> {
+    EXTRACTOR.extract(provider, headers, error)
+}
+
+/// Latest quota seen in one provider's responses.
+#[derive(Debug, Default)]
+pub struct QuotaTracker {
+    latest: parking_lot::Mutex<Option<QuotaMetadata>>,
+}
+
+impl QuotaTracker {
+    /// Note the quota in `headers`, if any. Responses without quota headers
+    /// keep the previous value.
+    pub fn observe(&self, provider: &str, headers: &HeaderMap) {
+        if let Some(quota) = extract_quota(provider, headers, None) {
+            *self.latest.lock() = Some(quota);
+        }
+    }
+
+    pub fn latest(&self) -> Option<QuotaMetadata> {
+        self.latest.lock().clone()
+    }
+}
 
 /// Trait for extracting quota metadata from provider responses.
 pub trait QuotaExtractor: Send + Sync {
@@ -438,6 +471,17 @@ mod tests {
         assert!(quota.retry_after_seconds.is_some());
     }
 
+    #[test]
+    fn tracker_keeps_last_reported_quota() {
+        let tracker = QuotaTracker::default();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "3".parse().unwrap());
+        tracker.observe("openai", &headers);
+        tracker.observe("openai", &HeaderMap::new());
+
+        assert_eq!(tracker.latest().unwrap().rate_limit_remaining, Some(3));
+    }
+
     #[test]
     fn test_universal_extractor_no_match() {
         let extractor = UniversalQuotaExtractor::new();
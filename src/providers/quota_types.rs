@@ -0,0 +1,24 @@
+//! Rate-limit quota reported by providers.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Rate-limit quota read from a provider's response headers or error message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaMetadata {
+    /// Requests left in the current window.
+    pub rate_limit_remaining: Option<u64>,
+    /// When the current window resets.
+    pub rate_limit_reset_at: Option<DateTime<Utc>>,
+    /// Seconds the provider asked callers to wait.
+    pub retry_after_seconds: Option<u64>,
+    /// Requests allowed per window.
+    pub rate_limit_total: Option<u64>,
+}
+
+impl QuotaMetadata {
+    /// Whether the provider reported no requests left in the current window.
+    pub fn is_exhausted(&self) -> bool {
+        self.rate_limit_remaining == Some(0)
+    }
+}
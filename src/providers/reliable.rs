@@ -1,11 +1,19 @@
+use super::circuit_breaker::{self, BreakerSettings};
+use super::model_limits::{self, ModelLimits};
+use super::quota_types::QuotaMetadata;
+use super::tokenizer::{TokenCounter, TokenizerFamily};
 use super::traits::{
-    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
+    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamEvent, StreamOptions, StreamResult,
+    TokenUsage,
 };
 use super::Provider;
+use crate::cost::CostTracker;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 // ── Error Classification ─────────────────────────────────────────────────
@@ -88,7 +96,7 @@ fn is_context_window_exceeded(err: &anyhow::Error) -> bool {
 }
 
 /// Check if an error is a rate-limit (429) error.
-fn is_rate_limited(err: &anyhow::Error) -> bool {
+pub(super) fn is_rate_limited(err: &anyhow::Error) -> bool {
     if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
         if let Some(status) = reqwest_err.status() {
             return status.as_u16() == 429;
@@ -147,7 +155,7 @@ fn is_non_retryable_rate_limit(err: &anyhow::Error) -> bool {
 
 /// Try to extract a Retry-After value (in milliseconds) from an error message.
 /// Looks for patterns like `Retry-After: 5` or `retry_after: 2.5` in the error string.
-fn parse_retry_after_ms(err: &anyhow::Error) -> Option<u64> {
+pub(super) fn parse_retry_after_ms(err: &anyhow::Error) -> Option<u64> {
     let msg = err.to_string();
     let lower = msg.to_lowercase();
 
@@ -178,6 +186,18 @@ fn parse_retry_after_ms(err: &anyhow::Error) -> Option<u64> {
     None
 }
 
+fn merge_usage(total: &mut TokenUsage, delta: &TokenUsage) {
+    let add = |sum: &mut Option<u64>, part: Option<u64>| {
+        if let Some(part) = part {
+            *sum = Some(sum.unwrap_or(0) + part);
+        }
+    };
+    add(&mut total.input_tokens, delta.input_tokens);
+    add(&mut total.output_tokens, delta.output_tokens);
+    add(&mut total.cached_input_tokens, delta.cached_input_tokens);
+    add(&mut total.cache_write_tokens, delta.cache_write_tokens);
+}
+
 fn failure_reason(rate_limited: bool, non_retryable: bool) -> &'static str {
     if rate_limited && non_retryable {
         "rate_limited_non_retryable"
@@ -211,6 +231,134 @@ fn push_failure(
     ));
 }
 
+// ── Request Hedging ──────────────────────────────────────────────────────
+// `stream_chat`, `chat` and `chat_with_history` are hedged. A hedged request
+// starts on the primary provider. If it has not answered within the hedge
+// delay (its first stream event, or the whole reply when not streaming), the
+// same request goes to the next provider and the first success wins. Dropping the losing
+// future cancels it. When both attempts fail the call fails too: the retry
+// loop would only send the same request to the same providers again.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HedgeSide {
+    Primary,
+    Hedge,
+}
+
+impl HedgeSide {
+    fn other(self) -> Self {
+        match self {
+            Self::Primary => Self::Hedge,
+            Self::Hedge => Self::Primary,
+        }
+    }
+}
+
+struct HedgeRace<T> {
+    winner: Option<(HedgeSide, T)>,
+    /// Whether the hedge request was sent at all.
+    hedged: bool,
+    /// Side still in flight when the winner returned (and was cancelled).
+    cancelled: Option<HedgeSide>,
+    errors: Vec<(HedgeSide, anyhow::Error)>,
+}
+
+async fn race_with_hedge<T, P, H>(
+    delay: Duration,
+    primary: P,
+    start_hedge: impl FnOnce() -> H,
+) -> HedgeRace<T>
+where
+    P: Future<Output = anyhow::Result<T>>,
+    H: Future<Output = anyhow::Result<T>>,
+{
+    let mut race = HedgeRace {
+        winner: None,
+        hedged: false,
+        cancelled: None,
+        errors: Vec::new(),
+    };
+    tokio::pin!(primary);
+
+    match tokio::time::timeout(delay, &mut primary).await {
+        Ok(Ok(value)) => {
+            race.winner = Some((HedgeSide::Primary, value));
+            return race;
+        }
+        Ok(Err(e)) => {
+            race.errors.push((HedgeSide::Primary, e));
+            return race;
+        }
+        Err(_) => {}
+    }
+
+    race.hedged = true;
+    let hedge = start_hedge();
+    tokio::pin!(hedge);
+
+    let (side, first) = tokio::select! {
+        result = &mut primary => (HedgeSide::Primary, result),
+        result = &mut hedge => (HedgeSide::Hedge, result),
+    };
+    match first {
+        Ok(value) => {
+            race.winner = Some((side, value));
+            race.cancelled = Some(side.other());
+        }
+        Err(e) => {
+            race.errors.push((side, e));
+            let other = side.other();
+            let second = match other {
+                HedgeSide::Primary => (&mut primary).await,
+                HedgeSide::Hedge => (&mut hedge).await,
+            };
+            match second {
+                Ok(value) => race.winner = Some((other, value)),
+                Err(e) => race.errors.push((other, e)),
+            }
+        }
+    }
+    race
+}
+
+type EventStream = stream::BoxStream<'static, StreamResult<StreamEvent>>;
+
+/// Open a stream and wait for its first event ("first byte" for hedging).
+async fn first_stream_event(
+    provider: &dyn Provider,
+    request: ChatRequest<'_>,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<(Option<StreamResult<StreamEvent>>, EventStream)> {
+    let mut events = provider.stream_chat(request, model, temperature).await?;
+    match events.next().await {
+        Some(Err(e)) => Err(anyhow::Error::new(e)),
+        first => Ok((first, events)),
+    }
+}
+
+/// Rough usage (~4 characters per token) for responses that report none,
+/// such as text-only replies and cancelled hedge attempts.
+fn estimated_usage(prompt_chars: usize, reply_chars: usize) -> TokenUsage {
+    TokenUsage {
+        input_tokens: Some((prompt_chars / 4) as u64),
+        output_tokens: Some((reply_chars / 4) as u64),
+        ..TokenUsage::default()
+    }
+}
+
+fn estimated_prompt_usage(messages: &[ChatMessage]) -> TokenUsage {
+    estimated_usage(messages.iter().map(|m| m.content.len()).sum(), 0)
+}
+
+/// Provider name, provider and the model it would be sent.
+type HedgeTarget<'a> = (&'a str, &'a dyn Provider, &'a str);
+
+/// Hedging settings (see `with_hedging`).
+struct HedgeSettings {
+    delay: Duration,
+}
+
 // ── Resilient Provider Wrapper ────────────────────────────────────────────
 // Three-level failover strategy: model chain → provider chain → retry loop.
 //   Outer loop:  iterate model fallback chain (original model first, then
@@ -221,9 +369,11 @@ fn push_failure(
 // Loop invariant: `failures` accumulates every failed attempt so the final
 // error message gives operators a complete diagnostic trail.
 
+type ProviderEntry = (String, Box<dyn Provider>);
+
 /// Provider wrapper with retry, fallback, auth rotation, and model failover.
 pub struct ReliableProvider {
-    providers: Vec<(String, Box<dyn Provider>)>,
+    providers: Vec<ProviderEntry>,
     max_retries: u32,
     base_backoff_ms: u64,
     /// Extra API keys for rotation (index tracks round-robin position).
@@ -235,6 +385,14 @@ pub struct ReliableProvider {
     provider_model_fallbacks: HashMap<String, Vec<String>>,
     /// Vision support override from config (`None` = defer to provider).
     vision_override: Option<bool>,
+    /// Context window override from config (`None` = model registry).
+    context_window_override: Option<usize>,
+    /// Per-provider circuit breaker thresholds (`None` = breakers disabled).
+    breaker: Option<BreakerSettings>,
+    /// Hedged-request mode (`None` = disabled).
+    hedge: Option<HedgeSettings>,
+    /// Where response usage is recorded (`None` = not recorded).
+    cost_tracker: Option<Arc<CostTracker>>,
 }
 
 impl ReliableProvider {
@@ -252,6 +410,10 @@ impl ReliableProvider {
             model_fallbacks: HashMap::new(),
             provider_model_fallbacks: HashMap::new(),
             vision_override: None,
+            context_window_override: None,
+            breaker: None,
+            hedge: None,
+            cost_tracker: None,
         }
     }
 
@@ -288,6 +450,269 @@ impl ReliableProvider {
         self
     }
 
+    /// Set context window override from runtime config.
+    pub fn with_context_window_override(mut self, context_window: Option<usize>) -> Self {
+        self.context_window_override = context_window.filter(|tokens| *tokens > 0);
+        self
+    }
+
+    /// Enable per-provider circuit breakers.
+    pub fn with_circuit_breaker(mut self, settings: Option<BreakerSettings>) -> Self {
+        self.breaker = settings;
+        self
+    }
+
+    /// Enable hedged requests for `stream_chat`, `chat` and `chat_with_history`.
+    ///
+    /// When the first provider has not answered within `delay`, the request
+    /// is also sent to the next provider and the first success wins. Streams
+    /// race on their first event, and only when the primary streams natively.
+    /// Non-streaming calls race on the whole reply. Both attempts are recorded
+    /// in the cost tracker so the hedging cost is visible.
+    ///
+    /// Hedging needs a fallback provider; without one a warning is logged and
+    /// requests go through the regular retry loop.
+    pub fn with_hedging(mut self, delay: Duration) -> Self {
+        if self.providers.len() < 2 {
+            tracing::warn!("Request hedging is enabled but there is no fallback provider");
+        }
+        self.hedge = Some(HedgeSettings { delay });
+        self
+    }
+
+    /// Record the usage of every successful response in `tracker`, priced
+    /// per the model that served it. Responses without reported usage are
+    /// estimated from their length.
+    pub fn with_cost_tracker(mut self, tracker: Option<Arc<CostTracker>>) -> Self {
+        self.cost_tracker = tracker;
+        self
+    }
+
+    /// Providers in the order to try them: those whose breaker allows calls
+    /// keep their configured priority, open breakers go last as a last resort.
+    /// Indices are the configured positions, so index 0 is still the primary.
+    fn provider_order(&self) -> Vec<(usize, &ProviderEntry)> {
+        let mut order: Vec<_> = self.providers.iter().enumerate().collect();
+        if let Some(settings) = &self.breaker {
+            let allowed: HashSet<usize> = order
+                .iter()
+                .filter(|(_, (name, _))| circuit_breaker::allows(name, settings))
+                .map(|(index, _)| *index)
+                .collect();
+            order.sort_by_key(|(index, _)| !allowed.contains(index));
+        }
+        order
+    }
+
+    /// Mark the start of an actual call, which turns a cooled-down open
+    /// breaker into a half-open probe.
+    fn begin_provider_call(&self, provider_name: &str) {
+        if let Some(settings) = &self.breaker {
+            circuit_breaker::begin_call(provider_name, settings);
+        }
+    }
+
+    fn record_provider_success(&self, provider_name: &str) {
+        if let Some(settings) = &self.breaker {
+            circuit_breaker::record_success(provider_name, settings);
+        }
+    }
+
+    /// Count a failure against the provider's breaker. Non-retryable errors
+    /// (bad request, auth, context window) say nothing about provider health.
+    fn record_provider_failure(&self, provider_name: &str, non_retryable: bool, detail: &str) {
+        if non_retryable {
+            if self.breaker.is_some() {
+                circuit_breaker::end_probe(provider_name);
+            }
+            return;
+        }
+        if let Some(settings) = &self.breaker {
+            circuit_breaker::record_failure(provider_name, settings, detail);
+        }
+    }
+
+    /// The first two providers in health order with the model each would be
+    /// sent, or `None` when there is nothing to hedge against.
+    fn hedge_pair<'a>(&'a self, model: &'a str) -> Option<[HedgeTarget<'a>; 2]> {
+        let order = self.provider_order();
+        let mut candidates = order.into_iter().take(2).map(|(index, (name, provider))| {
+            let sent_model = self
+                .provider_model_chain(model, name, index == 0)
+                .first()
+                .copied()
+                .unwrap_or(model);
+            (name.as_str(), provider.as_ref(), sent_model)
+        });
+        Some([candidates.next()?, candidates.next()?])
+    }
+
+    /// Feed hedge errors into the breakers and log them. Returns one attempt
+    /// line per error for the final error message.
+    fn record_hedge_errors(
+        &self,
+        pair: &[HedgeTarget<'_>; 2],
+        errors: &[(HedgeSide, anyhow::Error)],
+    ) -> Vec<String> {
+        let mut failures = Vec::new();
+        for (side, error) in errors {
+            let (name, _, sent_model) = pair[*side as usize];
+            let non_retryable = is_non_retryable(error) || is_non_retryable_rate_limit(error);
+            let detail = compact_error_detail(error);
+            push_failure(
+                &mut failures,
+                name,
+                sent_model,
+                1,
+                1,
+                failure_reason(is_rate_limited(error), non_retryable),
+                &detail,
+            );
+            self.record_provider_failure(name, non_retryable, &detail);
+            tracing::warn!(
+                provider = name,
+                model = sent_model,
+                error = %detail,
+                "Hedged attempt failed"
+            );
+        }
+        failures
+    }
+
+    fn record_cost(&self, model: &str, usage: &TokenUsage) {
+        let Some(tracker) = &self.cost_tracker else {
+            return;
+        };
+        if let Err(e) = tracker.record_provider_usage(model, usage) {
+            tracing::warn!(model, "Failed to record provider usage: {e}");
+        }
+    }
+
+    fn record_response_cost(&self, model: &str, messages: &[ChatMessage], response: &ChatResponse) {
+        if self.cost_tracker.is_none() {
+            return;
+        }
+        let usage = response.usage.clone().unwrap_or_else(|| {
+            estimated_usage(
+                messages.iter().map(|m| m.content.len()).sum(),
+                response.text_or_empty().len(),
+            )
+        });
+        self.record_cost(model, &usage);
+    }
+
+    /// Pass `events` through, recording the streamed usage once it finishes.
+    fn record_stream_cost(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        events: EventStream,
+    ) -> EventStream {
+        let Some(tracker) = self.cost_tracker.clone() else {
+            return events;
+        };
+        let model = model.to_string();
+        let fallback_usage = estimated_prompt_usage(messages);
+        let state = (events, TokenUsage::default(), false);
+        stream::unfold(state, move |(mut events, mut usage, mut reported)| {
+            let tracker = tracker.clone();
+            let model = model.clone();
+            let fallback_usage = fallback_usage.clone();
+            async move {
+                if let Some(event) = events.next().await {
+                    if let Ok(StreamEvent::Usage(delta)) = &event {
+                        merge_usage(&mut usage, delta);
+                        reported = true;
+                    }
+                    return Some((event, (events, usage, reported)));
+                }
+                let usage = if reported { usage } else { fallback_usage };
+                if let Err(e) = tracker.record_provider_usage(&model, &usage) {
+                    tracing::warn!(model, "Failed to record provider usage: {e}");
+                }
+                None
+            }
+        })
+        .boxed()
+    }
+
+    /// Race `call` on the first two providers, sending the second only once
+    /// the first has not answered within `delay`. Returns the winner's model
+    /// and answer, or `None` to fall back to the regular retry loop, which
+    /// happens when there is nothing to hedge or the primary failed before the
+    /// hedge was sent.
+    ///
+    /// Breakers and the cancelled attempt's cost are recorded here; the
+    /// winner's cost is left to the caller.
+    async fn hedged_call<'a, T, F, Fut>(
+        &'a self,
+        delay: Duration,
+        model: &'a str,
+        messages: &[ChatMessage],
+        call: F,
+    ) -> Option<anyhow::Result<(&'a str, T)>>
+    where
+        F: Fn(&'a dyn Provider, &'a str) -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let pair = self.hedge_pair(model)?;
+        let [(primary_name, primary, primary_model), (hedge_name, hedge, hedge_model)] = pair;
+        self.begin_provider_call(primary_name);
+        let race = race_with_hedge(delay, call(primary, primary_model), || {
+            tracing::info!(
+                provider = hedge_name,
+                model = hedge_model,
+                "Primary provider slow to answer; sending hedged request"
+            );
+            self.begin_provider_call(hedge_name);
+            call(hedge, hedge_model)
+        })
+        .await;
+
+        let failures = self.record_hedge_errors(&pair, &race.errors);
+        let Some((side, value)) = race.winner else {
+            return race.hedged.then(|| {
+                Err(anyhow::anyhow!(
+                    "Both hedged attempts failed. Attempts:\n{}",
+                    failures.join("\n")
+                ))
+            });
+        };
+        let (winner_name, _, winner_model) = pair[side as usize];
+        self.record_provider_success(winner_name);
+        if let Some(loser) = race.cancelled {
+            let (_, _, loser_model) = pair[loser as usize];
+            self.record_cost(loser_model, &estimated_prompt_usage(messages));
+        }
+        Some(Ok((winner_model, value)))
+    }
+
+    /// Hedged `stream_chat`, racing on the first stream event.
+    ///
+    /// Only a primary that really streams is hedged: for the others the first
+    /// event is the whole reply, and `chat` hedging already covers them.
+    async fn hedged_stream_chat(
+        &self,
+        delay: Duration,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Option<anyhow::Result<EventStream>> {
+        let [(_, primary, _), _] = self.hedge_pair(model)?;
+        if !primary.supports_streaming() {
+            return None;
+        }
+        let hedged = self
+            .hedged_call(delay, model, request.messages, |provider, sent_model| {
+                first_stream_event(provider, request, sent_model, temperature)
+            })
+            .await?;
+        Some(hedged.map(|(sent_model, (first, rest))| {
+            let events = stream::iter(first).chain(rest).boxed();
+            self.record_stream_cost(sent_model, request.messages, events)
+        }))
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -376,13 +801,14 @@ impl Provider for ReliableProvider {
         // immediately. On non-retryable error, break to next provider. On
         // retryable error, sleep with exponential backoff and retry.
         for current_model in &models {
-            for (provider_index, (provider_name, provider)) in self.providers.iter().enumerate() {
+            for (provider_index, (provider_name, provider)) in self.provider_order() {
                 let sent_models =
                     self.provider_model_chain(current_model, provider_name, provider_index == 0);
                 for sent_model in sent_models {
                     let mut backoff_ms = self.base_backoff_ms;
 
                     for attempt in 0..=self.max_retries {
+                        self.begin_provider_call(provider_name);
                         match provider
                             .chat_with_system(system_prompt, message, sent_model, temperature)
                             .await
@@ -397,6 +823,14 @@ impl Provider for ReliableProvider {
                                         "Provider recovered (failover/retry)"
                                     );
                                 }
+                                self.record_provider_success(provider_name);
+                                self.record_cost(
+                                    sent_model,
+                                    &estimated_usage(
+                                        system_prompt.map_or(0, str::len) + message.len(),
+                                        resp.len(),
+                                    ),
+                                );
                                 return Ok(resp);
                             }
                             Err(e) => {
@@ -416,6 +850,11 @@ impl Provider for ReliableProvider {
                                     failure_reason,
                                     &error_detail,
                                 );
+                                self.record_provider_failure(
+                                    provider_name,
+                                    non_retryable,
+                                    &error_detail,
+                                );
 
                                 // Rate-limit with rotatable keys: cycle to the next API key
                                 // so the retry hits a different quota bucket.
@@ -497,17 +936,38 @@ impl Provider for ReliableProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
+        if let Some(delay) = self.hedge.as_ref().map(|h| h.delay) {
+            if let Some(result) = self
+                .hedged_call(delay, model, messages, |provider, sent_model| {
+                    provider.chat_with_history(messages, sent_model, temperature)
+                })
+                .await
+            {
+                return result.map(|(sent_model, resp)| {
+                    self.record_cost(
+                        sent_model,
+                        &estimated_usage(
+                            messages.iter().map(|m| m.content.len()).sum(),
+                            resp.len(),
+                        ),
+                    );
+                    resp
+                });
+            }
+        }
+
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
         for current_model in &models {
-            for (provider_index, (provider_name, provider)) in self.providers.iter().enumerate() {
+            for (provider_index, (provider_name, provider)) in self.provider_order() {
                 let sent_models =
                     self.provider_model_chain(current_model, provider_name, provider_index == 0);
                 for sent_model in sent_models {
                     let mut backoff_ms = self.base_backoff_ms;
 
                     for attempt in 0..=self.max_retries {
+                        self.begin_provider_call(provider_name);
                         match provider
                             .chat_with_history(messages, sent_model, temperature)
                             .await
@@ -522,6 +982,14 @@ impl Provider for ReliableProvider {
                                         "Provider recovered (failover/retry)"
                                     );
                                 }
+                                self.record_provider_success(provider_name);
+                                self.record_cost(
+                                    sent_model,
+                                    &estimated_usage(
+                                        messages.iter().map(|m| m.content.len()).sum(),
+                                        resp.len(),
+                                    ),
+                                );
                                 return Ok(resp);
                             }
                             Err(e) => {
@@ -541,6 +1009,11 @@ impl Provider for ReliableProvider {
                                     failure_reason,
                                     &error_detail,
                                 );
+                                self.record_provider_failure(
+                                    provider_name,
+                                    non_retryable,
+                                    &error_detail,
+                                );
 
                                 if rate_limited && !non_retryable_rate_limit {
                                     if let Some(new_key) = self.rotate_key() {
@@ -621,6 +1094,37 @@ impl Provider for ReliableProvider {
         })
     }
 
+    fn token_counter(&self, model: &str) -> Box<dyn TokenCounter> {
+        match self.providers.first() {
+            Some((_, provider)) => provider.token_counter(model),
+            None => Box::new(TokenizerFamily::for_model(model).counter()),
+        }
+    }
+
+    fn quota(&self) -> Option<QuotaMetadata> {
+        self.providers
+            .first()
+            .and_then(|(_, provider)| provider.quota())
+    }
+
+    fn model_limits(&self, model: &str) -> ModelLimits {
+        let limits = self.providers.first().map_or_else(
+            || model_limits::limits_for(model),
+            |(_, provider)| provider.model_limits(model),
+        );
+        self.context_window_override
+            .map_or(limits, |tokens| limits.with_context_window(tokens))
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        // Any provider in the chain may end up serving the request.
+        !self.providers.is_empty()
+            && self
+                .providers
+                .iter()
+                .all(|(_, provider)| provider.supports_structured_output())
+    }
+
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -632,13 +1136,14 @@ impl Provider for ReliableProvider {
         let mut failures = Vec::new();
 
         for current_model in &models {
-            for (provider_index, (provider_name, provider)) in self.providers.iter().enumerate() {
+            for (provider_index, (provider_name, provider)) in self.provider_order() {
                 let sent_models =
                     self.provider_model_chain(current_model, provider_name, provider_index == 0);
                 for sent_model in sent_models {
                     let mut backoff_ms = self.base_backoff_ms;
 
                     for attempt in 0..=self.max_retries {
+                        self.begin_provider_call(provider_name);
                         match provider
                             .chat_with_tools(messages, tools, sent_model, temperature)
                             .await
@@ -653,6 +1158,8 @@ impl Provider for ReliableProvider {
                                         "Provider recovered (failover/retry)"
                                     );
                                 }
+                                self.record_provider_success(provider_name);
+                                self.record_response_cost(sent_model, messages, &resp);
                                 return Ok(resp);
                             }
                             Err(e) => {
@@ -672,6 +1179,11 @@ impl Provider for ReliableProvider {
                                     failure_reason,
                                     &error_detail,
                                 );
+                                self.record_provider_failure(
+                                    provider_name,
+                                    non_retryable,
+                                    &error_detail,
+                                );
 
                                 if rate_limited && !non_retryable_rate_limit {
                                     if let Some(new_key) = self.rotate_key() {
@@ -743,20 +1255,36 @@ impl Provider for ReliableProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        if let Some(delay) = self.hedge.as_ref().map(|h| h.delay) {
+            if let Some(result) = self
+                .hedged_call(delay, model, request.messages, |provider, sent_model| {
+                    provider.chat(request, sent_model, temperature)
+                })
+                .await
+            {
+                return result.map(|(sent_model, resp)| {
+                    self.record_response_cost(sent_model, request.messages, &resp);
+                    resp
+                });
+            }
+        }
+
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
         for current_model in &models {
-            for (provider_index, (provider_name, provider)) in self.providers.iter().enumerate() {
+            for (provider_index, (provider_name, provider)) in self.provider_order() {
                 let sent_models =
                     self.provider_model_chain(current_model, provider_name, provider_index == 0);
                 for sent_model in sent_models {
                     let mut backoff_ms = self.base_backoff_ms;
 
                     for attempt in 0..=self.max_retries {
+                        self.begin_provider_call(provider_name);
                         let req = ChatRequest {
                             messages: request.messages,
                             tools: request.tools,
+                            response_format: request.response_format,
                         };
                         match provider.chat(req, sent_model, temperature).await {
                             Ok(resp) => {
@@ -769,6 +1297,8 @@ impl Provider for ReliableProvider {
                                         "Provider recovered (failover/retry)"
                                     );
                                 }
+                                self.record_provider_success(provider_name);
+                                self.record_response_cost(sent_model, request.messages, &resp);
                                 return Ok(resp);
                             }
                             Err(e) => {
@@ -788,6 +1318,11 @@ impl Provider for ReliableProvider {
                                     failure_reason,
                                     &error_detail,
                                 );
+                                self.record_provider_failure(
+                                    provider_name,
+                                    non_retryable,
+                                    &error_detail,
+                                );
 
                                 if rate_limited && !non_retryable_rate_limit {
                                     if let Some(new_key) = self.rotate_key() {
@@ -865,6 +1400,117 @@ impl Provider for ReliableProvider {
         self.providers.iter().any(|(_, p)| p.supports_streaming())
     }
 
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        if let Some(delay) = self.hedge.as_ref().map(|h| h.delay) {
+            if let Some(result) = self
+                .hedged_stream_chat(delay, request, model, temperature)
+                .await
+            {
+                return result;
+            }
+        }
+
+        // Retries and fallbacks cover opening the stream only; once events
+        // start flowing, mid-stream errors are surfaced to the caller.
+        let models = self.model_chain(model);
+        let mut failures = Vec::new();
+
+        for current_model in &models {
+            for (provider_index, (provider_name, provider)) in self.provider_order() {
+                let sent_models =
+                    self.provider_model_chain(current_model, provider_name, provider_index == 0);
+                for sent_model in sent_models {
+                    let mut backoff_ms = self.base_backoff_ms;
+
+                    for attempt in 0..=self.max_retries {
+                        self.begin_provider_call(provider_name);
+                        let req = ChatRequest {
+                            messages: request.messages,
+                            tools: request.tools,
+                            response_format: request.response_format,
+                        };
+                        match provider.stream_chat(req, sent_model, temperature).await {
+                            Ok(events) => {
+                                if attempt > 0 || sent_model != model {
+                                    tracing::info!(
+                                        provider = provider_name,
+                                        model = sent_model,
+                                        attempt,
+                                        original_model = model,
+                                        "Provider stream recovered (failover/retry)"
+                                    );
+                                }
+                                self.record_provider_success(provider_name);
+                                return Ok(self.record_stream_cost(
+                                    sent_model,
+                                    request.messages,
+                                    events,
+                                ));
+                            }
+                            Err(e) => {
+                                let non_retryable =
+                                    is_non_retryable(&e) || is_non_retryable_rate_limit(&e);
+                                let rate_limited = is_rate_limited(&e);
+                                let failure_reason = failure_reason(rate_limited, non_retryable);
+                                let error_detail = compact_error_detail(&e);
+
+                                push_failure(
+                                    &mut failures,
+                                    provider_name,
+                                    sent_model,
+                                    attempt + 1,
+                                    self.max_retries + 1,
+                                    failure_reason,
+                                    &error_detail,
+                                );
+                                self.record_provider_failure(
+                                    provider_name,
+                                    non_retryable,
+                                    &error_detail,
+                                );
+
+                                if non_retryable {
+                                    if is_context_window_exceeded(&e) {
+                                        anyhow::bail!(
+                                            "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
+                                            failures.join("\n")
+                                        );
+                                    }
+                                    break;
+                                }
+
+                                if attempt < self.max_retries {
+                                    let wait = self.compute_backoff(backoff_ms, &e);
+                                    tracing::warn!(
+                                        provider = provider_name,
+                                        model = sent_model,
+                                        attempt = attempt + 1,
+                                        backoff_ms = wait,
+                                        reason = failure_reason,
+                                        error = %error_detail,
+                                        "Provider stream failed to open, retrying"
+                                    );
+                                    tokio::time::sleep(Duration::from_millis(wait)).await;
+                                    backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "All providers/models failed. Attempts:\n{}",
+            failures.join("\n")
+        )
+    }
+
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -875,7 +1521,7 @@ impl Provider for ReliableProvider {
     ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
         // Try each provider/model combination for streaming
         // For streaming, we use the first provider that supports it and has streaming enabled
-        for (provider_index, (provider_name, provider)) in self.providers.iter().enumerate() {
+        for (provider_index, (provider_name, provider)) in self.provider_order() {
             if !provider.supports_streaming() || !options.enabled {
                 continue;
             }
@@ -1055,6 +1701,410 @@ mod tests {
         assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn open_circuit_moves_provider_behind_healthy_fallback() {
+        let primary_name = format!("breaker-primary-{}", uuid::Uuid::new_v4());
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let settings = BreakerSettings {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(3600),
+            half_open_successes: 1,
+        };
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    primary_name.clone(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: usize::MAX,
+                        response: "never",
+                        error: "503 service unavailable",
+                    }),
+                ),
+                (
+                    "fallback".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "unused",
+                    }),
+                ),
+            ],
+            1,
+            1,
+        )
+        .with_circuit_breaker(Some(settings));
+
+        // First request: primary fails both attempts and trips its breaker.
+        let first = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(first, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            circuit_breaker::snapshot()[&primary_name].state,
+            circuit_breaker::CircuitState::Open
+        );
+
+        // Second request goes straight to the fallback.
+        let second = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(second, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn untried_cooled_down_breaker_stays_open() {
+        let fallback_name = format!("breaker-fallback-{}", uuid::Uuid::new_v4());
+        let settings = BreakerSettings {
+            failure_threshold: 1,
+            cooldown: Duration::ZERO,
+            half_open_successes: 1,
+        };
+        circuit_breaker::record_failure(&fallback_name, &settings, "503");
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "healthy-primary".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::new(AtomicUsize::new(0)),
+                        fail_until_attempt: 0,
+                        response: "from primary",
+                        error: "unused",
+                    }),
+                ),
+                (
+                    fallback_name.clone(),
+                    Box::new(MockProvider {
+                        calls: Arc::new(AtomicUsize::new(0)),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "unused",
+                    }),
+                ),
+            ],
+            0,
+            1,
+        )
+        .with_circuit_breaker(Some(settings));
+
+        let reply = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(reply, "from primary");
+        assert_eq!(
+            circuit_breaker::snapshot()[&fallback_name].state,
+            circuit_breaker::CircuitState::Open
+        );
+    }
+
+    /// Answers after a fixed delay, for hedging tests.
+    struct SlowProvider {
+        calls: Arc<AtomicUsize>,
+        delay: Duration,
+        response: &'static str,
+    }
+
+    #[async_trait]
+    impl Provider for SlowProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(self.response.to_string())
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+    }
+
+    async fn stream_text(
+        provider: &ReliableProvider,
+        messages: &[ChatMessage],
+    ) -> anyhow::Result<String> {
+        let request = ChatRequest {
+            messages,
+            tools: None,
+            response_format: None,
+        };
+        let mut events = provider.stream_chat(request, "model", 0.0).await?;
+        let mut accumulator = super::super::streaming::StreamAccumulator::new();
+        while let Some(event) = events.next().await {
+            accumulator.push(event?);
+        }
+        Ok(accumulator.text().to_string())
+    }
+
+    fn hedged_pair(
+        primary_delay: Duration,
+        hedge_delay: Duration,
+        tracker: Option<Arc<CostTracker>>,
+    ) -> (ReliableProvider, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let hedge_calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(SlowProvider {
+                        calls: Arc::clone(&primary_calls),
+                        delay: primary_delay,
+                        response: "from primary",
+                    }),
+                ),
+                (
+                    "hedge".into(),
+                    Box::new(SlowProvider {
+                        calls: Arc::clone(&hedge_calls),
+                        delay: hedge_delay,
+                        response: "from hedge",
+                    }),
+                ),
+            ],
+            0,
+            1,
+        )
+        .with_hedging(Duration::from_millis(20))
+        .with_cost_tracker(tracker);
+        (provider, primary_calls, hedge_calls)
+    }
+
+    #[tokio::test]
+    async fn hedged_stream_takes_faster_fallback_and_records_both_attempts() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cost_config = crate::config::schema::CostConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let tracker = Arc::new(CostTracker::new(cost_config, tmp.path()).unwrap());
+        let (provider, primary_calls, hedge_calls) = hedged_pair(
+            Duration::from_secs(5),
+            Duration::from_millis(5),
+            Some(Arc::clone(&tracker)),
+        );
+
+        let messages = [ChatMessage::user("hello there")];
+        let started = std::time::Instant::now();
+        let text = stream_text(&provider, &messages).await.unwrap();
+
+        assert_eq!(text, "from hedge");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(hedge_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(tracker.get_summary().unwrap().request_count, 2);
+    }
+
+    /// Fails after a fixed delay, for hedging tests.
+    struct SlowFailingProvider {
+        calls: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Provider for SlowFailingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            anyhow::bail!("upstream unavailable")
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_hedge_does_not_retry_the_primary() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let hedge_calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(SlowFailingProvider {
+                        calls: Arc::clone(&primary_calls),
+                        delay: Duration::from_millis(60),
+                    }),
+                ),
+                (
+                    "hedge".into(),
+                    Box::new(SlowFailingProvider {
+                        calls: Arc::clone(&hedge_calls),
+                        delay: Duration::from_millis(5),
+                    }),
+                ),
+            ],
+            2,
+            1,
+        )
+        .with_hedging(Duration::from_millis(20));
+
+        let messages = [ChatMessage::user("hello")];
+        let err = stream_text(&provider, &messages).await.unwrap_err();
+
+        assert!(err.to_string().contains("Both hedged attempts failed"));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(hedge_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn fast_primary_does_not_fire_hedge() {
+        let (provider, primary_calls, hedge_calls) =
+            hedged_pair(Duration::ZERO, Duration::ZERO, None);
+
+        let messages = [ChatMessage::user("hello")];
+        let text = stream_text(&provider, &messages).await.unwrap();
+
+        assert_eq!(text, "from primary");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(hedge_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn hedged_chat_takes_faster_fallback_and_records_both_attempts() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cost_config = crate::config::schema::CostConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let tracker = Arc::new(CostTracker::new(cost_config, tmp.path()).unwrap());
+        let (provider, primary_calls, hedge_calls) = hedged_pair(
+            Duration::from_secs(5),
+            Duration::from_millis(5),
+            Some(Arc::clone(&tracker)),
+        );
+
+        let messages = [ChatMessage::user("write a long answer")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: None,
+        };
+        let started = std::time::Instant::now();
+        let response = provider.chat(request, "model", 0.0).await.unwrap();
+
+        assert_eq!(response.text.as_deref(), Some("from hedge"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(hedge_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(tracker.get_summary().unwrap().request_count, 2);
+    }
+
+    #[tokio::test]
+    async fn hedged_chat_with_history_keeps_primary_when_it_finishes_first() {
+        let (provider, primary_calls, hedge_calls) =
+            hedged_pair(Duration::from_millis(40), Duration::from_secs(5), None);
+
+        let messages = [ChatMessage::user("hello")];
+        let reply = provider
+            .chat_with_history(&messages, "model", 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(reply, "from primary");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(hedge_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn every_response_is_recorded_in_cost_tracker() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cost_config = crate::config::schema::CostConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let tracker = Arc::new(CostTracker::new(cost_config, tmp.path()).unwrap());
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(SlowProvider {
+                    calls: Arc::new(AtomicUsize::new(0)),
+                    delay: Duration::ZERO,
+                    response: "reply",
+                }),
+            )],
+            0,
+            1,
+        )
+        .with_cost_tracker(Some(Arc::clone(&tracker)));
+
+        let messages = [ChatMessage::user("hello there")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: None,
+        };
+        provider.chat(request, "model", 0.0).await.unwrap();
+        provider
+            .chat_with_history(&messages, "model", 0.0)
+            .await
+            .unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.request_count, 2);
+        assert!(summary.total_tokens > 0);
+    }
+
+    #[tokio::test]
+    async fn stream_chat_retries_opening_then_falls_back() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: usize::MAX,
+                        response: "never",
+                        error: "temporary",
+                    }),
+                ),
+                (
+                    "fallback".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "streamed",
+                        error: "unused",
+                    }),
+                ),
+            ],
+            1,
+            1,
+        );
+
+        let messages = [ChatMessage::user("hello")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: None,
+        };
+        let events: Vec<_> = provider
+            .stream_chat(request, "test", 0.0)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(matches!(
+            events.first(),
+            Some(Ok(StreamEvent::TextDelta(text))) if text == "streamed"
+        ));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn falls_back_after_retries_exhausted() {
         let primary_calls = Arc::new(AtomicUsize::new(0));
@@ -1838,6 +2888,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1874,6 +2925,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1945,6 +2997,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let err = provider
             .chat(request, "test", 0.0)
@@ -2061,6 +3114,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "claude-opus", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("ok from sonnet"));
@@ -2109,6 +3163,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("from fallback"));
@@ -1,7 +1,26 @@
-use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::model_limits::ModelLimits;
+use super::quota_adapter::extract_quota;
+use super::quota_types::QuotaMetadata;
+use super::reliable::{is_rate_limited, parse_retry_after_ms};
+use super::tokenizer::TokenCounter;
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, StreamEvent, StreamResult, TokenUsage,
+};
 use super::Provider;
+use crate::config::schema::{ModelPricing, ModelRoutingConfig};
+use crate::observability::{runtime_trace, Observer, ObserverEvent};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Cooldown applied to a rate-limited route when the error carries no Retry-After.
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+const STATS_FILE: &str = "route_stats.json";
 
 /// A single route: maps a task hint to a provider + model combo.
 #[derive(Debug, Clone)]
@@ -18,13 +37,388 @@ pub struct Route {
 /// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table
 ///
 /// This wraps multiple pre-created providers and selects the right one per request.
+/// With adaptive routing enabled, a hint may have several candidate routes and
+/// the router picks one per request from rolling latency, error and billing
+/// data, provider-reported quota and the `[cost.prices]` table.
 pub struct RouterProvider {
-    routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
+    routes: HashMap<String, Vec<(usize, String)>>, // hint → [(provider_index, model)]
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
     /// Vision support override from config (`None` = defer to providers).
     vision_override: Option<bool>,
+    adaptive: Option<AdaptiveRouting>,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+/// Rolling per-route outcomes and the last decision per hint.
+struct AdaptiveRouting {
+    config: ModelRoutingConfig,
+    prices: HashMap<String, ModelPricing>,
+    stats: parking_lot::Mutex<HashMap<(usize, String), RouteStats>>,
+    last_choice: parking_lot::Mutex<HashMap<String, (usize, String)>>,
+    /// Where stats are saved (see `with_persisted_stats`).
+    stats_path: Option<PathBuf>,
+    /// Serializes writes of `stats_path` so snapshots land in order.
+    persist_lock: parking_lot::Mutex<()>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RouteStats {
+    /// (latency_ms, success) for the most recent calls, oldest first.
+    samples: VecDeque<(u64, bool)>,
+    /// Unix milliseconds until which a rate-limited route sits out.
+    #[serde(default)]
+    cooldown_until: Option<u64>,
+    /// Billed USD of the most recent calls that reported usage, oldest first.
+    #[serde(default)]
+    billed_usd: VecDeque<f64>,
+    /// Latest rate-limit quota reported by the route's provider.
+    #[serde(default)]
+    quota: Option<QuotaMetadata>,
+}
+
+/// One route's stats as saved in `<workspace>/state/route_stats.json`.
+/// Routes are keyed by provider name rather than index so the file survives
+/// route table edits.
+#[derive(Serialize, Deserialize)]
+struct PersistedRoute {
+    provider: String,
+    model: String,
+    #[serde(flatten)]
+    stats: RouteStats,
+}
+
+/// What a routed call reported, beyond its latency.
+struct CallOutcome<'a> {
+    provider: &'a str,
+    error: Option<&'a anyhow::Error>,
+    usage: Option<&'a TokenUsage>,
+    quota: Option<QuotaMetadata>,
+}
+
+/// Point-in-time view of one candidate route, as used for a routing decision.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSnapshot {
+    pub provider: String,
+    pub model: String,
+    pub samples: usize,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub error_rate: f64,
+    /// Input + output price per 1M tokens from `[cost.prices]`, if listed.
+    pub price_per_million: Option<f64>,
+    /// Mean USD billed per call, from the usage the route reported and
+    /// `[cost.prices]`. `None` until a priced call reported usage.
+    pub billed_usd_per_call: Option<f64>,
+    /// Requests left in the provider's rate-limit window, if reported.
+    pub quota_remaining: Option<u64>,
+    pub cooling_down: bool,
+}
+
+impl RouteStats {
+    fn record(&mut self, latency_ms: u64, success: bool, window: usize) {
+        self.samples.push_back((latency_ms, success));
+        while self.samples.len() > window.max(1) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn record_billed(&mut self, usd: f64, window: usize) {
+        self.billed_usd.push_back(usd);
+        while self.billed_usd.len() > window.max(1) {
+            self.billed_usd.pop_front();
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn billed_usd_per_call(&self) -> Option<f64> {
+        (!self.billed_usd.is_empty())
+            .then(|| self.billed_usd.iter().sum::<f64>() / self.billed_usd.len() as f64)
+    }
+
+    fn percentile(&self, pct: usize) -> Option<u64> {
+        let mut latencies: Vec<u64> = self.samples.iter().map(|(ms, _)| *ms).collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort_unstable();
+        let rank = (latencies.len() * pct).div_ceil(100).max(1);
+        latencies.get(rank - 1).copied()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn error_rate(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let errors = self.samples.iter().filter(|(_, ok)| !ok).count();
+        errors as f64 / self.samples.len() as f64
+    }
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Path of the persisted route stats inside a workspace.
+pub fn stats_file_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(STATS_FILE)
+}
+
+/// Look up pricing for a model. Price keys may carry a provider prefix
+/// (`anthropic/claude-...`) that the route model lacks, or the reverse.
+fn pricing_for<'a>(
+    prices: &'a HashMap<String, ModelPricing>,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    let bare = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+    prices.get(model).or_else(|| {
+        prices
+            .iter()
+            .find(|(key, _)| bare(key) == bare(model))
+            .map(|(_, pricing)| pricing)
+    })
+}
+
+fn price_per_million(prices: &HashMap<String, ModelPricing>, model: &str) -> Option<f64> {
+    pricing_for(prices, model).map(|pricing| pricing.input + pricing.output)
+}
+
+/// How long an exhausted quota keeps the route out: until the reported reset,
+/// or for the reported retry delay.
+fn quota_wait(quota: &QuotaMetadata) -> Option<Duration> {
+    if !quota.is_exhausted() {
+        return None;
+    }
+    quota
+        .rate_limit_reset_at
+        .and_then(|reset| (reset - chrono::Utc::now()).to_std().ok())
+        .or_else(|| quota.retry_after_seconds.map(Duration::from_secs))
+}
+
+impl AdaptiveRouting {
+    fn snapshot(&self, provider: &str, index: usize, model: &str) -> RouteSnapshot {
+        let stats = self.stats.lock();
+        let entry = stats.get(&(index, model.to_string()));
+        RouteSnapshot {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            samples: entry.map_or(0, |s| s.samples.len()),
+            p50_ms: entry.and_then(|s| s.percentile(50)),
+            p95_ms: entry.and_then(|s| s.percentile(95)),
+            error_rate: entry.map_or(0.0, RouteStats::error_rate),
+            price_per_million: price_per_million(&self.prices, model),
+            billed_usd_per_call: entry.and_then(RouteStats::billed_usd_per_call),
+            quota_remaining: entry
+                .and_then(|s| s.quota.as_ref())
+                .and_then(|q| q.rate_limit_remaining),
+            cooling_down: entry
+                .and_then(|s| s.cooldown_until)
+                .is_some_and(|until| until > now_unix_ms()),
+        }
+    }
+
+    /// Why a route cannot serve the next request, or `None` when it is eligible.
+    /// Routes without enough samples stay eligible so they get measured.
+    fn ineligibility(&self, snap: &RouteSnapshot) -> Option<String> {
+        if snap.cooling_down {
+            let why = if snap.quota_remaining == Some(0) {
+                "quota exhausted, cooling down"
+            } else {
+                "rate limited, cooling down"
+            };
+            return Some(why.to_string());
+        }
+        if snap.samples < self.config.min_samples {
+            return None;
+        }
+        if snap.error_rate > self.config.max_error_rate {
+            return Some(format!(
+                "error rate {:.0}% > {:.0}%",
+                snap.error_rate * 100.0,
+                self.config.max_error_rate * 100.0
+            ));
+        }
+        match snap.p95_ms {
+            Some(p95) if p95 > self.config.latency_slo_ms => Some(format!(
+                "p95 {p95}ms > SLO {}ms",
+                self.config.latency_slo_ms
+            )),
+            _ => None,
+        }
+    }
+
+    /// Pick the cheapest eligible candidate. Once every eligible candidate
+    /// has billed calls, price is the mean billed cost per call; until then it
+    /// is the `[cost.prices]` rate. Unknown prices sort last and ties go to
+    /// the lower p95. When nothing is eligible, fall back to the route with the
+    /// lowest error rate, then latency.
+    fn choose(&self, snapshots: &[RouteSnapshot]) -> (usize, String) {
+        let billed = snapshots
+            .iter()
+            .filter(|snap| self.ineligibility(snap).is_none())
+            .all(|snap| snap.billed_usd_per_call.is_some());
+        let price = |snap: &RouteSnapshot| {
+            if billed {
+                snap.billed_usd_per_call
+            } else {
+                snap.price_per_million
+            }
+        };
+        let price_key = |snap: &RouteSnapshot| price(snap).unwrap_or(f64::INFINITY);
+        let mut skipped = Vec::new();
+        let mut best: Option<usize> = None;
+        for (i, snap) in snapshots.iter().enumerate() {
+            if let Some(why) = self.ineligibility(snap) {
+                skipped.push(format!("{}/{}: {why}", snap.provider, snap.model));
+                continue;
+            }
+            let better = best.is_none_or(|b| {
+                let current = &snapshots[b];
+                price_key(snap)
+                    .total_cmp(&price_key(current))
+                    .then(snap.p95_ms.unwrap_or(0).cmp(&current.p95_ms.unwrap_or(0)))
+                    .is_lt()
+            });
+            if better {
+                best = Some(i);
+            }
+        }
+
+        let (chosen, mut reason) = match best {
+            Some(i) => {
+                let snap = &snapshots[i];
+                let price = match price(snap) {
+                    Some(usd) if billed => format!("${usd:.4}/call billed"),
+                    Some(usd) => format!("${usd:.2}/1M"),
+                    None => "price unknown".to_string(),
+                };
+                let reason = if snap.samples < self.config.min_samples {
+                    format!(
+                        "cheapest eligible route ({price}), measuring ({}/{} samples)",
+                        snap.samples, self.config.min_samples
+                    )
+                } else {
+                    format!(
+                        "cheapest route within SLO ({price}, p95 {}ms, errors {:.0}%)",
+                        snap.p95_ms.unwrap_or(0),
+                        snap.error_rate * 100.0
+                    )
+                };
+                (i, reason)
+            }
+            None => {
+                let i = snapshots
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        a.error_rate
+                            .total_cmp(&b.error_rate)
+                            .then(a.p95_ms.unwrap_or(0).cmp(&b.p95_ms.unwrap_or(0)))
+                    })
+                    .map_or(0, |(i, _)| i);
+                (
+                    i,
+                    "no route meets the SLO; using the lowest error rate and latency".to_string(),
+                )
+            }
+        };
+        if !skipped.is_empty() {
+            reason.push_str("; skipped ");
+            reason.push_str(&skipped.join(", "));
+        }
+        (chosen, reason)
+    }
+
+    /// Record one call. `quota` is what the provider last reported in its
+    /// response headers; a rate-limit error is also parsed for quota.
+    fn record(&self, index: usize, model: &str, latency: Duration, outcome: CallOutcome<'_>) {
+        let CallOutcome {
+            provider,
+            error,
+            usage,
+            quota,
+        } = outcome;
+        let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        let rate_limited = error.filter(|err| is_rate_limited(err));
+        let quota = rate_limited
+            .and_then(|err| extract_quota(provider, &reqwest::header::HeaderMap::new(), Some(err)))
+            .or(quota);
+        let billed = usage
+            .zip(pricing_for(&self.prices, model))
+            .map(|(usage, pricing)| {
+                crate::cost::TokenUsage::with_cache(
+                    model,
+                    usage.input_tokens.unwrap_or(0),
+                    usage.output_tokens.unwrap_or(0),
+                    usage.cached_input_tokens.unwrap_or(0),
+                    usage.cache_write_tokens.unwrap_or(0),
+                    pricing,
+                )
+                .cost()
+            });
+
+        let mut stats = self.stats.lock();
+        let entry = stats.entry((index, model.to_string())).or_default();
+        entry.record(latency_ms, error.is_none(), self.config.window);
+        if let Some(usd) = billed {
+            entry.record_billed(usd, self.config.window);
+        }
+        let exhausted_for = quota.as_ref().and_then(quota_wait);
+        if quota.is_some() {
+            entry.quota = quota;
+        }
+        let cooldown = match rate_limited {
+            Some(err) => Some(
+                parse_retry_after_ms(err)
+                    .map(Duration::from_millis)
+                    .or(exhausted_for)
+                    .unwrap_or(RATE_LIMIT_COOLDOWN),
+            ),
+            None => exhausted_for,
+        };
+        if let Some(cooldown) = cooldown {
+            let cooldown_ms = u64::try_from(cooldown.as_millis()).unwrap_or(u64::MAX);
+            entry.cooldown_until = Some(now_unix_ms().saturating_add(cooldown_ms));
+        }
+    }
+
+    /// Write the current stats to `stats_path`, if set. The snapshot is taken
+    /// under `persist_lock` but outside the stats lock, so routing decisions
+    /// are not held up by the file write and writes cannot overtake each other.
+    fn persist(&self, providers: &[(String, Box<dyn Provider>)]) {
+        let Some(path) = &self.stats_path else {
+            return;
+        };
+        let _write = self.persist_lock.lock();
+        let routes: Vec<PersistedRoute> = self
+            .stats
+            .lock()
+            .iter()
+            .filter_map(|((index, model), stats)| {
+                Some(PersistedRoute {
+                    provider: providers.get(*index)?.0.clone(),
+                    model: model.clone(),
+                    stats: stats.clone(),
+                })
+            })
+            .collect();
+        let result = serde_json::to_vec_pretty(&routes)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, bytes)?;
+                Ok(())
+            });
+        if let Err(err) = result {
+            tracing::warn!("Failed to persist adaptive route stats: {err}");
+        }
+    }
 }
 
 impl RouterProvider {
@@ -32,6 +426,7 @@ impl RouterProvider {
     ///
     /// `providers` is a list of (name, provider) pairs. The first one is the default.
     /// `routes` maps hint names to Route structs containing provider_name and model.
+    /// Several routes may share a hint; without adaptive routing the last one wins.
     pub fn new(
         providers: Vec<(String, Box<dyn Provider>)>,
         routes: Vec<(String, Route)>,
@@ -45,23 +440,22 @@ impl RouterProvider {
             .collect();
 
         // Resolve routes to provider indices
-        let resolved_routes: HashMap<String, (usize, String)> = routes
-            .into_iter()
-            .filter_map(|(hint, route)| {
-                let index = name_to_index.get(route.provider_name.as_str()).copied();
-                match index {
-                    Some(i) => Some((hint, (i, route.model))),
-                    None => {
-                        tracing::warn!(
-                            hint = hint,
-                            provider = route.provider_name,
-                            "Route references unknown provider, skipping"
-                        );
-                        None
-                    }
+        let mut resolved_routes: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+        for (hint, route) in routes {
+            match name_to_index.get(route.provider_name.as_str()).copied() {
+                Some(i) => resolved_routes
+                    .entry(hint)
+                    .or_default()
+                    .push((i, route.model)),
+                None => {
+                    tracing::warn!(
+                        hint = hint,
+                        provider = route.provider_name,
+                        "Route references unknown provider, skipping"
+                    );
                 }
-            })
-            .collect();
+            }
+        }
 
         Self {
             routes: resolved_routes,
@@ -69,6 +463,8 @@ impl RouterProvider {
             default_index: 0,
             default_model,
             vision_override: None,
+            adaptive: None,
+            observer: None,
         }
     }
 
@@ -78,15 +474,78 @@ impl RouterProvider {
         self
     }
 
-    /// Resolve a model parameter to a (provider, actual_model) pair.
+    /// Enable adaptive selection among routes that share a hint.
+    ///
+    /// Has no effect unless `config.adaptive` is set. `prices` is the
+    /// `[cost.prices]` table used to rank routes by per-token price.
+    pub fn with_adaptive_routing(
+        mut self,
+        config: &ModelRoutingConfig,
+        prices: HashMap<String, ModelPricing>,
+    ) -> Self {
+        self.adaptive = config.adaptive.then(|| AdaptiveRouting {
+            config: config.clone(),
+            prices,
+            stats: parking_lot::Mutex::new(HashMap::new()),
+            last_choice: parking_lot::Mutex::new(HashMap::new()),
+            stats_path: None,
+            persist_lock: parking_lot::Mutex::new(()),
+        });
+        self
+    }
+
+    /// Save adaptive route stats under `workspace_dir` and restore the ones
+    /// from the last run, so a restart does not start measuring from scratch.
+    ///
+    /// Has no effect unless adaptive routing is enabled. Saved routes whose
+    /// provider is no longer configured are dropped.
+    pub fn with_persisted_stats(mut self, workspace_dir: &Path) -> Self {
+        let Some(adaptive) = self.adaptive.as_mut() else {
+            return self;
+        };
+        let path = stats_file_path(workspace_dir);
+        let restored = std::fs::read_to_string(&path)
+            .ok()
+            .map(|raw| serde_json::from_str::<Vec<PersistedRoute>>(&raw))
+            .transpose()
+            .unwrap_or_else(|err| {
+                tracing::warn!("Ignoring unreadable adaptive route stats: {err}");
+                None
+            })
+            .unwrap_or_default();
+        let mut stats = adaptive.stats.lock();
+        for route in restored {
+            if let Some(index) = self
+                .providers
+                .iter()
+                .position(|(name, _)| *name == route.provider)
+            {
+                stats.insert((index, route.model), route.stats);
+            }
+        }
+        drop(stats);
+        adaptive.stats_path = Some(path);
+        self
+    }
+
+    /// Emit route decisions to an observer.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Resolve a model parameter to a (provider_index, actual_model) pair.
     ///
     /// If the model starts with "hint:", look up the hint in the route table.
     /// Otherwise, use the default provider with the given model name.
-    /// Resolve a model parameter to a (provider_index, actual_model) pair.
     fn resolve(&self, model: &str) -> (usize, String) {
         if let Some(hint) = model.strip_prefix("hint:") {
-            if let Some((idx, resolved_model)) = self.routes.get(hint) {
-                return (*idx, resolved_model.clone());
+            if let Some(candidates) = self.routes.get(hint) {
+                match (&self.adaptive, candidates.as_slice()) {
+                    (Some(adaptive), [_, _, ..]) => return self.select(adaptive, hint, candidates),
+                    (_, [.., last]) => return last.clone(),
+                    _ => {}
+                }
             }
             tracing::warn!(
                 hint = hint,
@@ -97,6 +556,97 @@ impl RouterProvider {
         // Not a hint or hint not found — use default provider with the model as-is
         (self.default_index, model.to_string())
     }
+
+    /// Routes `model` may resolve to, without recording a routing decision.
+    fn candidates(&self, model: &str) -> Vec<(usize, String)> {
+        model
+            .strip_prefix("hint:")
+            .and_then(|hint| self.routes.get(hint))
+            .filter(|candidates| !candidates.is_empty())
+            .cloned()
+            .unwrap_or_else(|| vec![(self.default_index, model.to_string())])
+    }
+
+    /// Adaptive choice among a hint's candidates. Decisions are reported when
+    /// the chosen route for the hint changes, not on every request.
+    fn select(
+        &self,
+        adaptive: &AdaptiveRouting,
+        hint: &str,
+        candidates: &[(usize, String)],
+    ) -> (usize, String) {
+        let snapshots: Vec<RouteSnapshot> = candidates
+            .iter()
+            .map(|(idx, model)| adaptive.snapshot(&self.providers[*idx].0, *idx, model))
+            .collect();
+        let (chosen, reason) = adaptive.choose(&snapshots);
+        let route = candidates[chosen].clone();
+
+        let changed = adaptive
+            .last_choice
+            .lock()
+            .insert(hint.to_string(), route.clone())
+            != Some(route.clone());
+        if changed {
+            let snap = &snapshots[chosen];
+            tracing::info!(
+                hint,
+                provider = snap.provider.as_str(),
+                model = snap.model.as_str(),
+                reason = reason.as_str(),
+                "Adaptive router switched route"
+            );
+            if let Some(observer) = &self.observer {
+                observer.record_event(&ObserverEvent::RouteDecision {
+                    hint: hint.to_string(),
+                    provider: snap.provider.clone(),
+                    model: snap.model.clone(),
+                    reason: reason.clone(),
+                });
+            }
+            runtime_trace::record_event(
+                "route_decision",
+                None,
+                Some(&snap.provider),
+                Some(&snap.model),
+                None,
+                Some(true),
+                Some(&reason),
+                serde_json::json!({
+                    "hint": hint,
+                    "latency_slo_ms": adaptive.config.latency_slo_ms,
+                    "max_error_rate": adaptive.config.max_error_rate,
+                    "candidates": snapshots,
+                }),
+            );
+        }
+        route
+    }
+
+    /// Feed the outcome of a hint-routed call back into the adaptive stats.
+    fn record_outcome(
+        &self,
+        requested_model: &str,
+        provider_idx: usize,
+        resolved_model: &str,
+        started: Instant,
+        error: Option<&anyhow::Error>,
+        usage: Option<&TokenUsage>,
+    ) {
+        if let Some(adaptive) = &self.adaptive {
+            if requested_model.starts_with("hint:") {
+                let (provider_name, provider) = &self.providers[provider_idx];
+                let outcome = CallOutcome {
+                    provider: provider_name,
+                    error,
+                    usage,
+                    quota: provider.quota(),
+                };
+                adaptive.record(provider_idx, resolved_model, started.elapsed(), outcome);
+                adaptive.persist(&self.providers);
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -117,9 +667,19 @@ impl Provider for RouterProvider {
             "Router dispatching request"
         );
 
-        provider
+        let started = Instant::now();
+        let result = provider
             .chat_with_system(system_prompt, message, &resolved_model, temperature)
-            .await
+            .await;
+        self.record_outcome(
+            model,
+            provider_idx,
+            &resolved_model,
+            started,
+            result.as_ref().err(),
+            None,
+        );
+        result
     }
 
     async fn chat_with_history(
@@ -130,9 +690,19 @@ impl Provider for RouterProvider {
     ) -> anyhow::Result<String> {
         let (provider_idx, resolved_model) = self.resolve(model);
         let (_, provider) = &self.providers[provider_idx];
-        provider
+        let started = Instant::now();
+        let result = provider
             .chat_with_history(messages, &resolved_model, temperature)
-            .await
+            .await;
+        self.record_outcome(
+            model,
+            provider_idx,
+            &resolved_model,
+            started,
+            result.as_ref().err(),
+            None,
+        );
+        result
     }
 
     async fn chat(
@@ -143,7 +713,41 @@ impl Provider for RouterProvider {
     ) -> anyhow::Result<ChatResponse> {
         let (provider_idx, resolved_model) = self.resolve(model);
         let (_, provider) = &self.providers[provider_idx];
-        provider.chat(request, &resolved_model, temperature).await
+        let started = Instant::now();
+        let result = provider.chat(request, &resolved_model, temperature).await;
+        self.record_outcome(
+            model,
+            provider_idx,
+            &resolved_model,
+            started,
+            result.as_ref().err(),
+            result.as_ref().ok().and_then(|r| r.usage.as_ref()),
+        );
+        result
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        let (_, provider) = &self.providers[provider_idx];
+        // Only the time to open the stream is measured.
+        let started = Instant::now();
+        let result = provider
+            .stream_chat(request, &resolved_model, temperature)
+            .await;
+        self.record_outcome(
+            model,
+            provider_idx,
+            &resolved_model,
+            started,
+            result.as_ref().err(),
+            None,
+        );
+        result
     }
 
     async fn chat_with_tools(
@@ -155,9 +759,19 @@ impl Provider for RouterProvider {
     ) -> anyhow::Result<ChatResponse> {
         let (provider_idx, resolved_model) = self.resolve(model);
         let (_, provider) = &self.providers[provider_idx];
-        provider
+        let started = Instant::now();
+        let result = provider
             .chat_with_tools(messages, tools, &resolved_model, temperature)
-            .await
+            .await;
+        self.record_outcome(
+            model,
+            provider_idx,
+            &resolved_model,
+            started,
+            result.as_ref().err(),
+            result.as_ref().ok().and_then(|r| r.usage.as_ref()),
+        );
+        result
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -175,6 +789,33 @@ impl Provider for RouterProvider {
         })
     }
 
+    fn token_counter(&self, model: &str) -> Box<dyn TokenCounter> {
+        let (index, model) = self.candidates(model).swap_remove(0);
+        self.providers[index].1.token_counter(&model)
+    }
+
+    /// Adaptive routing may pick any candidate, so a hint gets the smallest
+    /// context among its routes.
+    fn model_limits(&self, model: &str) -> ModelLimits {
+        self.candidates(model)
+            .into_iter()
+            .map(|(index, model)| self.providers[index].1.model_limits(&model))
+            .min_by_key(|limits| limits.context_window)
+            .unwrap_or(ModelLimits::DEFAULT)
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        self.providers
+            .get(self.default_index)
+            .is_some_and(|(_, p)| p.supports_structured_output())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.providers
+            .get(self.default_index)
+            .is_some_and(|(_, p)| p.supports_streaming())
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
@@ -375,6 +1016,21 @@ mod tests {
         assert_eq!(model, "claude-opus");
     }
 
+    #[test]
+    fn model_limits_follow_resolved_route_model() {
+        let (router, _) = make_router(
+            vec![("fast", "ok"), ("smart", "ok")],
+            vec![("small", "smart", "gpt-4")],
+        );
+
+        assert_eq!(router.model_limits("hint:small").context_window, 8_192);
+        assert_eq!(
+            router.model_limits("claude-sonnet-4").context_window,
+            200_000
+        );
+        assert_eq!(router.token_counter("hint:small").count("1234567"), 3);
+    }
+
     #[test]
     fn skips_routes_with_unknown_provider() {
         let (router, _) = make_router(
@@ -472,4 +1128,316 @@ mod tests {
         assert_eq!(mocks[1].last_model(), "claude-opus");
         assert_eq!(mocks[0].call_count(), 0);
     }
+
+    struct RecordingObserver {
+        decisions: parking_lot::Mutex<Vec<(String, String, String)>>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::RouteDecision {
+                hint,
+                model,
+                reason,
+                ..
+            } = event
+            {
+                self.decisions
+                    .lock()
+                    .push((hint.clone(), model.clone(), reason.clone()));
+            }
+        }
+
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    fn pricing(input: f64, output: f64) -> ModelPricing {
+        ModelPricing {
+            input,
+            output,
+            cached_input: None,
+            cache_write: None,
+        }
+    }
+
+    fn make_adaptive_router() -> (RouterProvider, Arc<RecordingObserver>) {
+        let (router, _) = make_router(
+            vec![
+                ("default", "default-response"),
+                ("cheap", "cheap-response"),
+                ("pricey", "pricey-response"),
+            ],
+            vec![
+                ("fast", "cheap", "cheap-model"),
+                ("fast", "pricey", "vendor/pricey-model"),
+            ],
+        );
+        let prices = HashMap::from([
+            ("cheap-model".to_string(), pricing(0.5, 1.5)),
+            ("pricey-model".to_string(), pricing(5.0, 15.0)),
+        ]);
+        let config = ModelRoutingConfig {
+            adaptive: true,
+            latency_slo_ms: 1_000,
+            max_error_rate: 0.25,
+            window: 10,
+            min_samples: 3,
+        };
+        let observer = Arc::new(RecordingObserver {
+            decisions: parking_lot::Mutex::new(Vec::new()),
+        });
+        let router = router
+            .with_adaptive_routing(&config, prices)
+            .with_observer(Arc::clone(&observer) as Arc<dyn Observer>);
+        (router, observer)
+    }
+
+    fn outcome(error: Option<&anyhow::Error>) -> CallOutcome<'_> {
+        CallOutcome {
+            provider: "mock",
+            error,
+            usage: None,
+            quota: None,
+        }
+    }
+
+    fn seed(router: &RouterProvider, idx: usize, model: &str, latency_ms: u64, count: usize) {
+        let adaptive = router.adaptive.as_ref().unwrap();
+        for _ in 0..count {
+            adaptive.record(idx, model, Duration::from_millis(latency_ms), outcome(None));
+        }
+    }
+
+    #[test]
+    fn duplicate_hints_use_last_route_without_adaptive_routing() {
+        let (router, _) = make_router(
+            vec![("default", "ok"), ("a", "ok"), ("b", "ok")],
+            vec![("fast", "a", "model-a"), ("fast", "b", "model-b")],
+        );
+
+        assert_eq!(router.resolve("hint:fast"), (2, "model-b".to_string()));
+    }
+
+    #[test]
+    fn adaptive_routing_prefers_cheapest_route_within_slo() {
+        let (router, observer) = make_adaptive_router();
+        seed(&router, 1, "cheap-model", 200, 3);
+        seed(&router, 2, "vendor/pricey-model", 100, 3);
+
+        assert_eq!(router.resolve("hint:fast"), (1, "cheap-model".to_string()));
+        // Same choice again does not emit a second decision.
+        router.resolve("hint:fast");
+
+        let decisions = observer.decisions.lock();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].0, "fast");
+        assert_eq!(decisions[0].1, "cheap-model");
+        assert!(decisions[0]
+            .2
+            .contains("cheapest route within SLO ($2.00/1M"));
+    }
+
+    #[test]
+    fn adaptive_routing_skips_routes_over_latency_slo() {
+        let (router, observer) = make_adaptive_router();
+        seed(&router, 1, "cheap-model", 2_500, 3);
+        seed(&router, 2, "vendor/pricey-model", 300, 3);
+
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+        let decisions = observer.decisions.lock();
+        assert!(decisions[0]
+            .2
+            .ends_with("; skipped cheap/cheap-model: p95 2500ms > SLO 1000ms"));
+    }
+
+    #[test]
+    fn adaptive_routing_skips_erroring_and_rate_limited_routes() {
+        let (router, _) = make_adaptive_router();
+        let adaptive = router.adaptive.as_ref().unwrap();
+        let failure = anyhow::anyhow!("upstream 500");
+        for _ in 0..3 {
+            adaptive.record(
+                1,
+                "cheap-model",
+                Duration::from_millis(50),
+                outcome(Some(&failure)),
+            );
+        }
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+
+        let (router, _) = make_adaptive_router();
+        let adaptive = router.adaptive.as_ref().unwrap();
+        let rate_limited = anyhow::anyhow!("429 Too Many Requests, retry-after: 60");
+        adaptive.record(
+            1,
+            "cheap-model",
+            Duration::from_millis(50),
+            outcome(Some(&rate_limited)),
+        );
+        let snap = adaptive.snapshot("cheap", 1, "cheap-model");
+        assert!(snap.cooling_down);
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+    }
+
+    #[test]
+    fn adaptive_routing_skips_routes_with_exhausted_quota_until_reset() {
+        let (router, observer) = make_adaptive_router();
+        let adaptive = router.adaptive.as_ref().unwrap();
+        let exhausted = QuotaMetadata {
+            rate_limit_remaining: Some(0),
+            rate_limit_reset_at: Some(chrono::Utc::now() + chrono::Duration::minutes(5)),
+            ..QuotaMetadata::default()
+        };
+        adaptive.record(
+            1,
+            "cheap-model",
+            Duration::from_millis(50),
+            CallOutcome {
+                quota: Some(exhausted),
+                ..outcome(None)
+            },
+        );
+
+        let snap = adaptive.snapshot("cheap", 1, "cheap-model");
+        assert_eq!(snap.quota_remaining, Some(0));
+        assert!(snap.cooling_down);
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+        assert!(observer.decisions.lock()[0]
+            .2
+            .ends_with("; skipped cheap/cheap-model: quota exhausted, cooling down"));
+
+        let remaining = QuotaMetadata {
+            rate_limit_remaining: Some(40),
+            ..QuotaMetadata::default()
+        };
+        adaptive.record(
+            2,
+            "vendor/pricey-model",
+            Duration::from_millis(50),
+            CallOutcome {
+                quota: Some(remaining),
+                ..outcome(None)
+            },
+        );
+        let snap = adaptive.snapshot("pricey", 2, "vendor/pricey-model");
+        assert_eq!(snap.quota_remaining, Some(40));
+        assert!(!snap.cooling_down);
+    }
+
+    #[test]
+    fn adaptive_routing_ranks_by_billed_cost_once_every_route_has_some() {
+        let (router, observer) = make_adaptive_router();
+        let adaptive = router.adaptive.as_ref().unwrap();
+        let usage = |input, output| TokenUsage {
+            input_tokens: Some(input),
+            output_tokens: Some(output),
+            ..TokenUsage::default()
+        };
+        // The cheap model answers with so many tokens that each call costs more.
+        let verbose = usage(200_000, 100_000);
+        let terse = usage(1_000, 200);
+        adaptive.record(
+            1,
+            "cheap-model",
+            Duration::from_millis(50),
+            CallOutcome {
+                usage: Some(&verbose),
+                ..outcome(None)
+            },
+        );
+        assert_eq!(router.resolve("hint:fast"), (1, "cheap-model".to_string()));
+
+        adaptive.record(
+            2,
+            "vendor/pricey-model",
+            Duration::from_millis(50),
+            CallOutcome {
+                usage: Some(&terse),
+                ..outcome(None)
+            },
+        );
+        let snap = adaptive.snapshot("pricey", 2, "vendor/pricey-model");
+        assert!((snap.billed_usd_per_call.unwrap() - 0.008).abs() < 1e-9);
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+        assert!(observer.decisions.lock()[1]
+            .2
+            .contains("($0.0080/call billed"));
+    }
+
+    #[test]
+    fn adaptive_routing_falls_back_when_no_route_meets_slo() {
+        let (router, observer) = make_adaptive_router();
+        seed(&router, 1, "cheap-model", 4_000, 3);
+        seed(&router, 2, "vendor/pricey-model", 2_000, 3);
+
+        assert_eq!(
+            router.resolve("hint:fast"),
+            (2, "vendor/pricey-model".to_string())
+        );
+        assert!(observer.decisions.lock()[0]
+            .2
+            .starts_with("no route meets the SLO"));
+    }
+
+    #[tokio::test]
+    async fn adaptive_routing_records_outcomes_of_hint_calls_only() {
+        let (router, _) = make_adaptive_router();
+
+        router.simple_chat("hello", "hint:fast", 0.5).await.unwrap();
+        router.simple_chat("hello", "gpt-4o", 0.5).await.unwrap();
+
+        let adaptive = router.adaptive.as_ref().unwrap();
+        assert_eq!(adaptive.snapshot("cheap", 1, "cheap-model").samples, 1);
+        assert_eq!(adaptive.stats.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn adaptive_route_stats_survive_restart() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (router, _) = make_adaptive_router();
+        let router = router.with_persisted_stats(tmp.path());
+        router.simple_chat("hello", "hint:fast", 0.5).await.unwrap();
+        assert!(stats_file_path(tmp.path()).exists());
+
+        let (restarted, _) = make_adaptive_router();
+        let restarted = restarted.with_persisted_stats(tmp.path());
+        let adaptive = restarted.adaptive.as_ref().unwrap();
+        assert_eq!(adaptive.snapshot("cheap", 1, "cheap-model").samples, 1);
+    }
+
+    #[test]
+    fn route_stats_percentiles_use_rolling_window() {
+        let mut stats = RouteStats::default();
+        for ms in 1..=20 {
+            stats.record(ms * 10, ms != 20, 10);
+        }
+
+        assert_eq!(stats.samples.len(), 10);
+        assert_eq!(stats.percentile(50), Some(150));
+        assert_eq!(stats.percentile(95), Some(200));
+        assert!((stats.error_rate() - 0.1).abs() < f64::EPSILON);
+    }
 }
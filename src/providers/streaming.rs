@@ -0,0 +1,397 @@
+//! Shared plumbing for [`Provider::stream_chat`](super::Provider::stream_chat)
+//! implementations: line framing for SSE and NDJSON bodies, the OpenAI
+//! chat-completions chunk parser, and [`StreamAccumulator`] for folding
+//! [`StreamEvent`]s back into a [`ChatResponse`].
+
+use super::traits::{ChatResponse, StreamError, StreamEvent, StreamResult, TokenUsage, ToolCall};
+use futures_util::{stream, Stream, StreamExt};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Replay a complete response as stream events, for providers or requests
+/// that cannot stream natively.
+pub fn replay(response: ChatResponse) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+    let mut events = Vec::new();
+    if let Some(reasoning) = response.reasoning_content.filter(|r| !r.is_empty()) {
+        events.push(StreamEvent::ReasoningDelta(reasoning));
+    }
+    if let Some(text) = response.text.filter(|t| !t.is_empty()) {
+        events.push(StreamEvent::TextDelta(text));
+    }
+    for (index, call) in response.tool_calls.into_iter().enumerate() {
+        events.push(StreamEvent::ToolCallDelta {
+            index,
+            id: Some(call.id),
+            name: Some(call.name),
+            arguments: call.arguments,
+        });
+    }
+    if let Some(usage) = response.usage {
+        events.push(StreamEvent::Usage(usage));
+    }
+    stream::iter(events.into_iter().map(Ok)).boxed()
+}
+
+/// Split a byte stream into lines (without terminators). Bytes are buffered
+/// until a newline, so multi-byte characters split across chunks survive.
+pub fn body_lines<S, B>(body: S) -> stream::BoxStream<'static, StreamResult<String>>
+where
+    S: Stream<Item = Result<B, reqwest::Error>> + Send + 'static,
+    B: AsRef<[u8]> + 'static,
+{
+    let body = body.boxed();
+    stream::unfold(
+        (body, Vec::new(), false),
+        |(mut body, mut buffer, mut done)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line)
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string();
+                    return Some((Ok(line), (body, buffer, done)));
+                }
+                if done {
+                    if buffer.is_empty() {
+                        return None;
+                    }
+                    let line = String::from_utf8_lossy(&std::mem::take(&mut buffer))
+                        .trim_end_matches('\r')
+                        .to_string();
+                    return Some((Ok(line), (body, buffer, done)));
+                }
+                match body.next().await {
+                    Some(Ok(bytes)) => buffer.extend_from_slice(bytes.as_ref()),
+                    Some(Err(e)) => {
+                        buffer.clear();
+                        return Some((Err(StreamError::Http(e)), (body, buffer, true)));
+                    }
+                    None => done = true,
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+/// Payload of an SSE `data:` line; `None` for blank lines, comments, other
+/// fields and the `[DONE]` sentinel.
+pub fn sse_data(line: &str) -> Option<&str> {
+    let data = line.strip_prefix("data:")?.trim();
+    (!data.is_empty() && data != "[DONE]").then_some(data)
+}
+
+/// Run every line through `parse` and flatten the produced events.
+pub fn parse_lines<S, F>(
+    lines: S,
+    mut parse: F,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>>
+where
+    S: Stream<Item = StreamResult<String>> + Send + 'static,
+    F: FnMut(&str) -> StreamResult<Vec<StreamEvent>> + Send + 'static,
+{
+    lines
+        .flat_map(move |line| {
+            let events: Vec<StreamResult<StreamEvent>> = match line.and_then(|l| parse(&l)) {
+                Ok(events) => events.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(events)
+        })
+        .boxed()
+}
+
+/// Parse an SSE response body, feeding each `data:` payload to `parse`.
+pub fn sse_events<F>(
+    response: reqwest::Response,
+    mut parse: F,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>>
+where
+    F: FnMut(&str) -> StreamResult<Vec<StreamEvent>> + Send + 'static,
+{
+    parse_lines(body_lines(response.bytes_stream()), move |line| {
+        sse_data(line).map_or_else(|| Ok(Vec::new()), &mut parse)
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiStreamChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiStreamUsage>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamChoice {
+    #[serde(default)]
+    delta: Option<OpenAiStreamDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    /// OpenRouter names the reasoning field `reasoning`.
+    #[serde(default)]
+    reasoning: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<OpenAiToolCallDelta>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<OpenAiFunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiFunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u64>,
+    #[serde(default)]
+    completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<OpenAiPromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
+}
+
+/// Parse one chat-completions stream chunk (`data:` payload) as used by
+/// OpenAI, OpenRouter and OpenAI-compatible endpoints.
+pub fn parse_openai_chunk(data: &str) -> StreamResult<Vec<StreamEvent>> {
+    let chunk: OpenAiStreamChunk = serde_json::from_str(data).map_err(StreamError::Json)?;
+    if let Some(error) = chunk.error {
+        let message = error
+            .get("message")
+            .and_then(serde_json::Value::as_str)
+            .map_or_else(|| error.to_string(), str::to_string);
+        return Err(StreamError::Provider(super::sanitize_api_error(&message)));
+    }
+
+    let mut events = Vec::new();
+    for delta in chunk.choices.into_iter().filter_map(|choice| choice.delta) {
+        if let Some(reasoning) = delta.reasoning_content.or(delta.reasoning) {
+            if !reasoning.is_empty() {
+                events.push(StreamEvent::ReasoningDelta(reasoning));
+            }
+        }
+        if let Some(content) = delta.content.filter(|c| !c.is_empty()) {
+            events.push(StreamEvent::TextDelta(content));
+        }
+        for call in delta.tool_calls.unwrap_or_default() {
+            let (name, arguments) = call
+                .function
+                .map_or((None, None), |f| (f.name, f.arguments));
+            events.push(StreamEvent::ToolCallDelta {
+                index: call.index,
+                id: call.id,
+                name,
+                arguments: arguments.unwrap_or_default(),
+            });
+        }
+    }
+    if let Some(usage) = chunk.usage {
+        events.push(StreamEvent::Usage(TokenUsage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            cached_input_tokens: usage.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            cache_write_tokens: None,
+        }));
+    }
+    Ok(events)
+}
+
+/// Folds [`StreamEvent`]s into a [`ChatResponse`].
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    text: String,
+    reasoning: String,
+    tool_calls: BTreeMap<usize, ToolCall>,
+    usage: Option<TokenUsage>,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: StreamEvent) {
+        match event {
+            StreamEvent::TextDelta(delta) => self.text.push_str(&delta),
+            StreamEvent::ReasoningDelta(delta) => self.reasoning.push_str(&delta),
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                let call = self.tool_calls.entry(index).or_insert_with(|| ToolCall {
+                    id: String::new(),
+                    name: String::new(),
+                    arguments: String::new(),
+                });
+                if let Some(id) = id.filter(|_| call.id.is_empty()) {
+                    call.id = id;
+                }
+                if let Some(name) = name.filter(|_| call.name.is_empty()) {
+                    call.name = name;
+                }
+                call.arguments.push_str(&arguments);
+            }
+            StreamEvent::Usage(usage) => {
+                let previous = self.usage.take().unwrap_or_default();
+                self.usage = Some(TokenUsage {
+                    input_tokens: usage.input_tokens.or(previous.input_tokens),
+                    output_tokens: usage.output_tokens.or(previous.output_tokens),
+                    cached_input_tokens: usage.cached_input_tokens.or(previous.cached_input_tokens),
+                    cache_write_tokens: usage.cache_write_tokens.or(previous.cache_write_tokens),
+                });
+            }
+        }
+    }
+
+    /// Text received so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn finish(self) -> ChatResponse {
+        let tool_calls = self
+            .tool_calls
+            .into_values()
+            .filter(|call| !call.name.is_empty())
+            .map(|mut call| {
+                if call.id.is_empty() {
+                    call.id = uuid::Uuid::new_v4().to_string();
+                }
+                if call.arguments.trim().is_empty() {
+                    call.arguments = "{}".to_string();
+                }
+                call
+            })
+            .collect();
+        ChatResponse {
+            text: (!self.text.is_empty()).then_some(self.text),
+            tool_calls,
+            usage: self.usage,
+            reasoning_content: (!self.reasoning.is_empty()).then_some(self.reasoning),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect_lines(chunks: Vec<&'static [u8]>) -> Vec<String> {
+        let body = stream::iter(chunks.into_iter().map(Ok::<_, reqwest::Error>));
+        body_lines(body)
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>()
+            .await
+    }
+
+    #[tokio::test]
+    async fn body_lines_reassembles_split_lines_and_characters() {
+        let euro = "€".as_bytes();
+        let lines = collect_lines(vec![
+            b"data: {\"a\"",
+            b":1}\r\n\ndata: ",
+            &euro[..1],
+            &euro[1..],
+            b"\nlast",
+        ])
+        .await;
+        assert_eq!(lines, vec!["data: {\"a\":1}", "", "data: €", "last"]);
+    }
+
+    #[test]
+    fn sse_data_skips_non_data_lines() {
+        assert_eq!(sse_data("data: {\"x\":1}"), Some("{\"x\":1}"));
+        assert_eq!(sse_data("data: [DONE]"), None);
+        assert_eq!(sse_data(": keep-alive"), None);
+        assert_eq!(sse_data("event: message_start"), None);
+    }
+
+    #[test]
+    fn openai_chunks_accumulate_into_tool_calls() {
+        let chunks = [
+            r#"{"choices":[{"delta":{"content":"Checking "}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"shell","arguments":"{\"comm"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"and\":\"ls\"}"}}]}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":7}}"#,
+        ];
+        let mut acc = StreamAccumulator::new();
+        for chunk in chunks {
+            for event in parse_openai_chunk(chunk).unwrap() {
+                acc.push(event);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Checking "));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        let usage = response.usage.unwrap();
+        assert_eq!(
+            (usage.input_tokens, usage.output_tokens),
+            (Some(12), Some(7))
+        );
+    }
+
+    #[test]
+    fn openai_chunk_error_is_surfaced() {
+        let err = parse_openai_chunk(r#"{"error":{"message":"rate limited"}}"#).unwrap_err();
+        assert!(err.to_string().contains("rate limited"));
+    }
+
+    #[tokio::test]
+    async fn replay_round_trips_through_accumulator() {
+        let original = ChatResponse {
+            text: Some("done".into()),
+            tool_calls: vec![ToolCall {
+                id: "t1".into(),
+                name: "file_read".into(),
+                arguments: r#"{"path":"a"}"#.into(),
+            }],
+            usage: Some(TokenUsage {
+                input_tokens: Some(3),
+                output_tokens: Some(4),
+                ..TokenUsage::default()
+            }),
+            reasoning_content: Some("thinking".into()),
+        };
+        let mut acc = StreamAccumulator::new();
+        let mut events = replay(original);
+        while let Some(event) = events.next().await {
+            acc.push(event.unwrap());
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("done"));
+        assert_eq!(response.reasoning_content.as_deref(), Some("thinking"));
+        assert_eq!(response.tool_calls[0].id, "t1");
+        assert_eq!(response.usage.unwrap().output_tokens, Some(4));
+    }
+}
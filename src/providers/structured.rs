@@ -0,0 +1,482 @@
+//! Structured (JSON) replies.
+//!
+//! Providers with native support map [`ResponseFormat`] onto their API. For
+//! everything else [`prompt_guided_chat`] describes the format in the system
+//! prompt, validates the reply against the schema and asks the model to fix
+//! invalid replies a bounded number of times.
+
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, Provider, ResponseFormat, TokenUsage};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+
+/// Total attempts (first reply plus corrections) in the prompt-guided fallback.
+pub const MAX_STRUCTURED_ATTEMPTS: usize = 3;
+
+/// System-prompt text describing the required reply format.
+pub fn instructions(format: &ResponseFormat) -> String {
+    let mut text = String::from(
+        "## Response Format\n\nReply with a single JSON value and nothing else: no prose, no Markdown code fences.",
+    );
+    if let ResponseFormat::JsonSchema { schema, .. } = format {
+        text.push_str(" The JSON must validate against this JSON Schema:\n\n");
+        text.push_str(&serde_json::to_string(schema).unwrap_or_else(|_| "{}".into()));
+    } else {
+        text.push_str(" The value must be a JSON object.");
+    }
+    text
+}
+
+/// Append `text` to the first system message, or prepend one.
+fn inject_system_text(messages: &mut Vec<ChatMessage>, text: &str) {
+    if let Some(system) = messages.iter_mut().find(|m| m.role == "system") {
+        if !system.content.is_empty() {
+            system.content.push_str("\n\n");
+        }
+        system.content.push_str(text);
+    } else {
+        messages.insert(0, ChatMessage::system(text));
+    }
+}
+
+/// Messages for a native request. OpenAI-style `json_object` mode rejects
+/// prompts that never mention JSON, so that mode also gets the instructions.
+pub fn native_messages<'a>(
+    messages: &'a [ChatMessage],
+    format: Option<&ResponseFormat>,
+) -> Cow<'a, [ChatMessage]> {
+    match format {
+        Some(format @ ResponseFormat::JsonObject) => {
+            let mut messages = messages.to_vec();
+            inject_system_text(&mut messages, &instructions(format));
+            Cow::Owned(messages)
+        }
+        _ => Cow::Borrowed(messages),
+    }
+}
+
+/// OpenAI Chat Completions `response_format` payload (also used by OpenRouter).
+pub fn openai_response_format(format: &ResponseFormat) -> Value {
+    match format {
+        ResponseFormat::JsonObject => json!({ "type": "json_object" }),
+        ResponseFormat::JsonSchema { name, schema } => json!({
+            "type": "json_schema",
+            "json_schema": { "name": name, "schema": schema }
+        }),
+    }
+}
+
+/// Pull the JSON value out of a reply, tolerating code fences and surrounding prose.
+pub fn extract_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"));
+    if let Some(inner) = unfenced {
+        if let Ok(value) = serde_json::from_str(inner.trim()) {
+            return Some(value);
+        }
+    }
+
+    // Fall back to the outermost object/array embedded in prose.
+    for (open, close) in [('{', '}'), ('[', ']')] {
+        if let (Some(start), Some(end)) = (trimmed.find(open), trimmed.rfind(close)) {
+            if start < end {
+                if let Ok(value) = serde_json::from_str(&trimmed[start..=end]) {
+                    return Some(value);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Validate `value` against the commonly used subset of JSON Schema: `type`,
+/// `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`,
+/// `anyOf`/`oneOf`, and numeric/length bounds. Returns the first violation.
+pub fn validate(value: &Value, schema: &Value) -> Result<(), String> {
+    validate_at(value, schema, "$")
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        return Ok(());
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(value, t)) {
+            return Err(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!(
+                "{path}: must be one of {}",
+                Value::Array(options.clone())
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return Err(format!("{path}: must equal {constant}"));
+        }
+    }
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            if !variants.iter().any(|v| validate_at(value, v, path).is_ok()) {
+                return Err(format!("{path}: does not match any allowed variant"));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for field in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(field) {
+                        return Err(format!("{path}: missing required property '{field}'"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, field) in map {
+                let child = format!("{path}.{key}");
+                match properties.and_then(|p| p.get(key)) {
+                    Some(field_schema) => validate_at(field, field_schema, &child)?,
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            return Err(format!("{path}: unexpected property '{key}'"))
+                        }
+                        Some(extra @ Value::Object(_)) => validate_at(field, extra, &child)?,
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(schema, "minItems", items.len(), path, |n, b| n >= b)?;
+            check_bound(schema, "maxItems", items.len(), path, |n, b| n <= b)?;
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item, item_schema, &format!("{path}[{i}]"))?;
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            check_bound(schema, "minLength", len, path, |n, b| n >= b)?;
+            check_bound(schema, "maxLength", len, path, |n, b| n <= b)?;
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    return Err(format!("{path}: must be >= {min}"));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    return Err(format!("{path}: must be <= {max}"));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn check_bound(
+    schema: &serde_json::Map<String, Value>,
+    key: &str,
+    actual: usize,
+    path: &str,
+    ok: impl Fn(u64, u64) -> bool,
+) -> Result<(), String> {
+    match schema.get(key).and_then(Value::as_u64) {
+        Some(bound) if !ok(actual as u64, bound) => Err(format!("{path}: {key} is {bound}")),
+        _ => Ok(()),
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Parse and validate a reply for `format`.
+pub fn parse_reply(text: &str, format: &ResponseFormat) -> Result<Value, String> {
+    let value = extract_json(text).ok_or_else(|| "reply is not valid JSON".to_string())?;
+    validate(&value, &format.schema())?;
+    Ok(value)
+}
+
+/// Prompt-guided structured output for providers without native support.
+///
+/// Replies that request tool calls are returned untouched. Otherwise the reply
+/// text is replaced by the validated JSON, re-serialized compactly.
+pub async fn prompt_guided_chat<P: Provider + ?Sized>(
+    provider: &P,
+    request: ChatRequest<'_>,
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<ChatResponse> {
+    let mut messages = request.messages.to_vec();
+    inject_system_text(&mut messages, &instructions(format));
+
+    let mut usage: Option<TokenUsage> = None;
+    let mut last_error = String::new();
+    for _ in 0..MAX_STRUCTURED_ATTEMPTS {
+        let mut response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: request.tools,
+                    response_format: None,
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        usage = add_usage(usage, response.usage.take());
+        if response.has_tool_calls() {
+            response.usage = usage;
+            return Ok(response);
+        }
+
+        let text = response.text_or_empty().to_string();
+        match parse_reply(&text, format) {
+            Ok(value) => {
+                response.text = Some(value.to_string());
+                response.usage = usage;
+                return Ok(response);
+            }
+            Err(error) => {
+                tracing::debug!("structured reply rejected: {error}");
+                messages.push(ChatMessage::assistant(text));
+                messages.push(ChatMessage::user(format!(
+                    "That reply was invalid ({error}). Reply again with only the corrected JSON."
+                )));
+                last_error = error;
+            }
+        }
+    }
+    anyhow::bail!(
+        "model did not return valid structured output after {MAX_STRUCTURED_ATTEMPTS} attempts: {last_error}"
+    )
+}
+
+fn add_usage(total: Option<TokenUsage>, next: Option<TokenUsage>) -> Option<TokenUsage> {
+    let sum = |a: Option<u64>, b: Option<u64>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    match (total, next) {
+        (Some(t), Some(n)) => Some(TokenUsage {
+            input_tokens: sum(t.input_tokens, n.input_tokens),
+            output_tokens: sum(t.output_tokens, n.output_tokens),
+            cached_input_tokens: sum(t.cached_input_tokens, n.cached_input_tokens),
+            cache_write_tokens: sum(t.cache_write_tokens, n.cache_write_tokens),
+        }),
+        (t, n) => t.or(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    fn verdict_format() -> ResponseFormat {
+        ResponseFormat::json_schema(
+            "verdict",
+            json!({
+                "type": "object",
+                "properties": {
+                    "success": { "type": "boolean" },
+                    "summary": { "type": "string", "minLength": 1 }
+                },
+                "required": ["success", "summary"],
+                "additionalProperties": false
+            }),
+        )
+    }
+
+    struct ScriptedProvider {
+        replies: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&'static str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                seen: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat_with_history is overridden")
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.seen.lock().push(messages.to_vec());
+            Ok(self.replies.lock().pop().unwrap_or("").to_string())
+        }
+    }
+
+    #[test]
+    fn extract_json_handles_fences_and_prose() {
+        assert_eq!(extract_json(r#"{"a":1}"#), Some(json!({"a": 1})));
+        assert_eq!(
+            extract_json("```json\n{\"a\": 1}\n```"),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(
+            extract_json("Sure! Here you go: {\"a\": [1, 2]} Hope that helps."),
+            Some(json!({"a": [1, 2]}))
+        );
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[test]
+    fn validate_reports_schema_violations() {
+        let schema = verdict_format().schema();
+        assert!(validate(&json!({"success": true, "summary": "ok"}), &schema).is_ok());
+        assert!(validate(&json!({"success": true}), &schema)
+            .unwrap_err()
+            .contains("missing required property 'summary'"));
+        assert!(
+            validate(&json!({"success": "yes", "summary": "ok"}), &schema)
+                .unwrap_err()
+                .contains("$.success: expected boolean, got string")
+        );
+        assert!(
+            validate(&json!({"success": true, "summary": "ok", "x": 1}), &schema)
+                .unwrap_err()
+                .contains("unexpected property 'x'")
+        );
+        let list = json!({"type": "array", "items": {"enum": ["a", "b"]}, "maxItems": 2});
+        assert!(validate(&json!(["a", "b"]), &list).is_ok());
+        assert!(validate(&json!(["a", "c"]), &list)
+            .unwrap_err()
+            .contains("$[1]"));
+        assert!(validate(&json!(["a", "b", "a"]), &list).is_err());
+        assert!(validate(&json!(3), &json!({"type": "integer", "maximum": 2})).is_err());
+    }
+
+    #[tokio::test]
+    async fn fallback_injects_instructions_and_returns_compact_json() {
+        let provider =
+            ScriptedProvider::new(&["```json\n{\"success\": true, \"summary\": \"done\"}\n```"]);
+        let messages = [
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Report"),
+        ];
+        let format = verdict_format();
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                    response_format: Some(&format),
+                },
+                "model",
+                0.0,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.text.as_deref(),
+            Some(r#"{"success":true,"summary":"done"}"#)
+        );
+        let seen = provider.seen.lock();
+        assert_eq!(seen.len(), 1);
+        assert!(seen[0][0]
+            .content
+            .starts_with("Be brief.\n\n## Response Format"));
+        assert!(seen[0][0]
+            .content
+            .contains("\"required\":[\"success\",\"summary\"]"));
+    }
+
+    #[tokio::test]
+    async fn fallback_retries_with_validation_error_then_gives_up() {
+        let provider = ScriptedProvider::new(&[
+            "I think it worked.",
+            r#"{"success": "yes", "summary": "done"}"#,
+            r#"{"success": true, "summary": "done"}"#,
+        ]);
+        let messages = [ChatMessage::user("Report")];
+        let format = verdict_format();
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: Some(&format),
+        };
+        let response = provider.chat(request, "model", 0.0).await.unwrap();
+        assert!(response.text_or_empty().contains("\"success\":true"));
+
+        {
+            let seen = provider.seen.lock();
+            assert_eq!(seen.len(), 3);
+            let correction = &seen[2][seen[2].len() - 1];
+            assert_eq!(correction.role, "user");
+            assert!(correction.content.contains("expected boolean, got string"));
+        }
+
+        let stubborn = ScriptedProvider::new(&["nope", "still nope", "no"]);
+        let err = stubborn.chat(request, "model", 0.0).await.unwrap_err();
+        assert!(err.to_string().contains("after 3 attempts"));
+    }
+}
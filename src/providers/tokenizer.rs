@@ -0,0 +1,367 @@
+//! Token counting for context-window budgeting.
+//!
+//! Providers only report exact usage after a request, so request shaping
+//! needs an estimate up front. [`TokenCounter`] is that estimate; the default
+//! implementation ([`HeuristicCounter`]) approximates the BPE pre-tokenizers
+//! of each [`TokenizerFamily`] closely enough to stay within a few percent on
+//! prose and code, and providers with a real tokenizer at hand (the embedded
+//! GGUF provider) return an exact counter instead.
+
+use super::traits::ChatMessage;
+use crate::tools::ToolSpec;
+
+/// Framing tokens each chat message costs on top of its content.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Tokens priming the assistant reply at the end of a conversation.
+const REPLY_PRIMING_TOKENS: usize = 3;
+/// Framing tokens per native tool definition.
+const TOOL_OVERHEAD_TOKENS: usize = 8;
+/// Budget for one inline image; providers bill images by resolution, this is
+/// the typical cost of a downscaled screenshot or photo.
+const IMAGE_TOKENS: usize = 1_000;
+
+/// Counts tokens for one model's tokenizer.
+pub trait TokenCounter: Send + Sync {
+    /// Tokens in `text` on its own.
+    fn count(&self, text: &str) -> usize;
+
+    /// Whether counts come from the model's own tokenizer.
+    fn is_exact(&self) -> bool {
+        false
+    }
+
+    /// Tokens in a conversation, including per-message framing. Image
+    /// markers are billed at a flat rate instead of by their payload size.
+    fn count_messages(&self, messages: &[ChatMessage]) -> usize {
+        let content: usize = messages
+            .iter()
+            .map(|message| {
+                let (text, images) = crate::multimodal::parse_image_markers(&message.content);
+                MESSAGE_OVERHEAD_TOKENS
+                    + self.count(&message.role)
+                    + self.count(&text)
+                    + images.len() * IMAGE_TOKENS
+            })
+            .sum();
+        content + REPLY_PRIMING_TOKENS
+    }
+
+    /// Tokens the tool definitions add to a native tool-calling request.
+    fn count_tools(&self, tools: &[ToolSpec]) -> usize {
+        tools
+            .iter()
+            .map(|tool| {
+                TOOL_OVERHEAD_TOKENS
+                    + self.count(&tool.name)
+                    + self.count(&tool.description)
+                    + self.count(&tool.parameters.to_string())
+            })
+            .sum()
+    }
+}
+
+/// Tokenizer families with distinct enough vocabularies to count separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerFamily {
+    /// OpenAI `cl100k_base` (GPT-4, GPT-3.5).
+    Cl100k,
+    /// OpenAI `o200k_base` (GPT-4o, GPT-4.1, GPT-5, o-series).
+    O200k,
+    /// Anthropic Claude.
+    Claude,
+    /// Google Gemini and Gemma (SentencePiece).
+    Gemini,
+    /// Large-vocabulary open-weight BPE tokenizers (Llama 3, Qwen, Mistral,
+    /// DeepSeek, ...).
+    OpenWeights,
+    /// Anything else; deliberately conservative.
+    Generic,
+}
+
+impl TokenizerFamily {
+    /// Family for a model id, with or without a provider prefix.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_ascii_lowercase();
+        let bare = model.rsplit('/').next().unwrap_or(&model);
+
+        if bare.contains("claude") {
+            Self::Claude
+        } else if bare.contains("gemini") || bare.contains("gemma") {
+            Self::Gemini
+        } else if ["gpt-4o", "gpt-4.1", "gpt-5", "chatgpt", "codex", "gpt-oss"]
+            .iter()
+            .any(|prefix| bare.starts_with(prefix))
+            || is_o_series(bare)
+        {
+            Self::O200k
+        } else if bare.starts_with("gpt-4")
+            || bare.starts_with("gpt-3.5")
+            || bare.starts_with("text-embedding")
+        {
+            Self::Cl100k
+        } else if [
+            "llama",
+            "qwen",
+            "qwq",
+            "mistral",
+            "mixtral",
+            "codestral",
+            "deepseek",
+            "phi",
+            "glm",
+            "kimi",
+            "yi-",
+            "nova",
+        ]
+        .iter()
+        .any(|name| bare.contains(name))
+        {
+            Self::OpenWeights
+        } else {
+            Self::Generic
+        }
+    }
+
+    /// Heuristic counter calibrated for this family.
+    pub fn counter(self) -> HeuristicCounter {
+        HeuristicCounter { family: self }
+    }
+
+    fn profile(self) -> Profile {
+        match self {
+            Self::Cl100k => Profile {
+                word_chars: 7,
+                subword_chars: 4,
+                digits_per_token: 3,
+                cjk_centi: 120,
+                other_centi: 50,
+            },
+            Self::O200k => Profile {
+                word_chars: 8,
+                subword_chars: 4,
+                digits_per_token: 3,
+                cjk_centi: 75,
+                other_centi: 35,
+            },
+            Self::Claude => Profile {
+                word_chars: 6,
+                subword_chars: 3,
+                digits_per_token: 3,
+                cjk_centi: 110,
+                other_centi: 50,
+            },
+            Self::Gemini => Profile {
+                word_chars: 7,
+                subword_chars: 4,
+                digits_per_token: 1,
+                cjk_centi: 75,
+                other_centi: 35,
+            },
+            Self::OpenWeights => Profile {
+                word_chars: 7,
+                subword_chars: 4,
+                digits_per_token: 3,
+                cjk_centi: 90,
+                other_centi: 40,
+            },
+            Self::Generic => Profile {
+                word_chars: 4,
+                subword_chars: 4,
+                digits_per_token: 2,
+                cjk_centi: 120,
+                other_centi: 60,
+            },
+        }
+    }
+}
+
+/// `o1`, `o3-mini`, `o4-mini`, ...
+fn is_o_series(bare: &str) -> bool {
+    let mut chars = bare.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Per-family calibration. Costs below one token are in hundredths.
+#[derive(Debug, Clone, Copy)]
+struct Profile {
+    /// Letter runs up to this length are usually a single token.
+    word_chars: usize,
+    /// Characters per token in the remainder of longer words.
+    subword_chars: usize,
+    /// Digits merged into one token.
+    digits_per_token: usize,
+    /// Hundredths of a token per CJK character.
+    cjk_centi: usize,
+    /// Hundredths of a token per other non-ASCII character.
+    other_centi: usize,
+}
+
+/// Character classes the pre-tokenizer splits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+    Cjk,
+    Other,
+}
+
+fn classify(c: char) -> CharClass {
+    match c {
+        'a'..='z' | 'A'..='Z' => CharClass::Letter,
+        '0'..='9' => CharClass::Digit,
+        '\n' | '\r' => CharClass::Newline,
+        c if c.is_whitespace() => CharClass::Space,
+        c if c.is_ascii() => CharClass::Punct,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}' => CharClass::Cjk,
+        _ => CharClass::Other,
+    }
+}
+
+/// Estimates tokens from character runs, mirroring how BPE pre-tokenizers
+/// split text into words, digit groups, punctuation and whitespace.
+#[derive(Debug, Clone, Copy)]
+pub struct HeuristicCounter {
+    family: TokenizerFamily,
+}
+
+impl HeuristicCounter {
+    pub fn family(self) -> TokenizerFamily {
+        self.family
+    }
+
+    fn run_centi(profile: Profile, class: CharClass, len: usize) -> usize {
+        match class {
+            CharClass::Letter if len <= profile.word_chars => 100,
+            CharClass::Letter => {
+                100 + ((len - profile.word_chars) * 100).div_ceil(profile.subword_chars)
+            }
+            CharClass::Digit => len.div_ceil(profile.digits_per_token) * 100,
+            // A single space merges into the following word.
+            CharClass::Space if len == 1 => 0,
+            CharClass::Space | CharClass::Newline => 100,
+            CharClass::Punct => len.div_ceil(2) * 100,
+            CharClass::Cjk => len * profile.cjk_centi,
+            CharClass::Other => len * profile.other_centi,
+        }
+    }
+}
+
+impl TokenCounter for HeuristicCounter {
+    fn count(&self, text: &str) -> usize {
+        let profile = self.family.profile();
+        let mut centi = 0;
+        let mut run: Option<(CharClass, usize)> = None;
+        for class in text.chars().map(classify) {
+            run = match run {
+                Some((current, len)) if current == class => Some((current, len + 1)),
+                Some((current, len)) => {
+                    centi += Self::run_centi(profile, current, len);
+                    Some((class, 1))
+                }
+                None => Some((class, 1)),
+            };
+        }
+        if let Some((class, len)) = run {
+            centi += Self::run_centi(profile, class, len);
+        }
+        centi.div_ceil(100)
+    }
+}
+
+/// Rough provider-agnostic estimate for callers without a model at hand.
+pub fn estimate_tokens(text: &str) -> usize {
+    TokenizerFamily::Generic.counter().count(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_detection_handles_prefixes_and_aliases() {
+        let cases = [
+            (
+                "anthropic/claude-sonnet-4-20250514",
+                TokenizerFamily::Claude,
+            ),
+            (
+                "us.anthropic.claude-3-5-haiku-20241022-v1:0",
+                TokenizerFamily::Claude,
+            ),
+            ("gpt-4o-mini", TokenizerFamily::O200k),
+            ("openai/o3-mini", TokenizerFamily::O200k),
+            ("gpt-4-turbo", TokenizerFamily::Cl100k),
+            ("google/gemini-2.0-flash", TokenizerFamily::Gemini),
+            ("llama3.1:8b", TokenizerFamily::OpenWeights),
+            ("qwen/qwen3-coder", TokenizerFamily::OpenWeights),
+            ("some-unknown-model", TokenizerFamily::Generic),
+        ];
+        for (model, family) in cases {
+            assert_eq!(TokenizerFamily::for_model(model), family, "{model}");
+        }
+    }
+
+    #[test]
+    fn heuristic_counts_are_close_to_real_tokenizers() {
+        // cl100k_base: 10 tokens.
+        let text = "The quick brown fox jumps over the lazy dog.";
+        let count = TokenizerFamily::Cl100k.counter().count(text);
+        assert!((9..=12).contains(&count), "{count}");
+
+        // cl100k_base splits digits into groups of three: 1234567 -> 3 tokens.
+        assert_eq!(TokenizerFamily::Cl100k.counter().count("1234567"), 3);
+        assert_eq!(TokenizerFamily::Gemini.counter().count("1234567"), 7);
+
+        // One token per ideograph or so, not a quarter of the UTF-8 length.
+        let cjk = "今天天气很好";
+        assert_eq!(TokenizerFamily::Generic.counter().count(cjk), 8);
+        assert!(cjk.len().div_ceil(4) < 6);
+    }
+
+    #[test]
+    fn long_words_and_code_cost_more() {
+        let counter = TokenizerFamily::Claude.counter();
+        assert!(counter.count("internationalization") > counter.count("nation"));
+        assert!(counter.count("fn main() {\n    println!(\"hi\");\n}") >= 12);
+        assert_eq!(counter.count(""), 0);
+    }
+
+    #[test]
+    fn message_counts_include_framing_and_flat_image_cost() {
+        let counter = TokenizerFamily::O200k.counter();
+        let messages = [ChatMessage::system("be brief"), ChatMessage::user("hi")];
+        assert_eq!(
+            counter.count_messages(&messages),
+            2 * MESSAGE_OVERHEAD_TOKENS + 1 + 2 + 1 + 1 + REPLY_PRIMING_TOKENS
+        );
+
+        let payload = "A".repeat(100_000);
+        let with_image = [ChatMessage::user(format!(
+            "look [IMAGE:data:image/png;base64,{payload}]"
+        ))];
+        let count = counter.count_messages(&with_image);
+        assert!(count > IMAGE_TOKENS && count < IMAGE_TOKENS + 20, "{count}");
+    }
+
+    #[test]
+    fn tool_counts_include_schema() {
+        let counter = TokenizerFamily::Cl100k.counter();
+        let tool = ToolSpec {
+            name: "shell".into(),
+            description: "Run a shell command".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {"command": {"type": "string"}},
+            }),
+        };
+        let count = counter.count_tools(std::slice::from_ref(&tool));
+        assert!(count > TOOL_OVERHEAD_TOKENS + 5, "{count}");
+    }
+}
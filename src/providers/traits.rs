@@ -1,3 +1,7 @@
+use super::batch::{BatchRequest, BatchStatus};
+use super::model_limits::{self, ModelLimits};
+use super::quota_types::QuotaMetadata;
+use super::tokenizer::{self, TokenCounter, TokenizerFamily};
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -50,10 +54,18 @@ pub struct ToolCall {
 }
 
 /// Raw token counts from a single LLM API response.
-#[derive(Debug, Clone, Default)]
+///
+/// `input_tokens` counts the whole prompt, including tokens served from or
+/// written to a provider-side prompt cache; the cache fields break that total
+/// down so cost accounting can price them separately.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct TokenUsage {
     pub input_tokens: Option<u64>,
     pub output_tokens: Option<u64>,
+    /// Prompt tokens read from the provider's prompt cache.
+    pub cached_input_tokens: Option<u64>,
+    /// Prompt tokens written to the provider's prompt cache.
+    pub cache_write_tokens: Option<u64>,
 }
 
 /// An LLM response that may contain text, tool calls, or both.
@@ -84,11 +96,55 @@ impl ChatResponse {
     }
 }
 
+/// Requested shape of the assistant's reply.
+///
+/// Providers that advertise [`ProviderCapabilities::structured_output`] map
+/// this to their native mechanism. Others receive prompt instructions and the
+/// reply is validated and retried by [`super::structured::prompt_guided_chat`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// Any JSON object.
+    JsonObject,
+    /// JSON matching a JSON Schema.
+    JsonSchema {
+        /// Identifier (`[a-zA-Z0-9_-]`) for backends that require one.
+        name: String,
+        schema: serde_json::Value,
+    },
+}
+
+impl ResponseFormat {
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self::JsonSchema {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// Identifier for backends that need one (`"response"` for plain JSON objects).
+    pub fn name(&self) -> &str {
+        match self {
+            Self::JsonObject => "response",
+            Self::JsonSchema { name, .. } => name,
+        }
+    }
+
+    /// Schema a valid reply must satisfy.
+    pub fn schema(&self) -> serde_json::Value {
+        match self {
+            Self::JsonObject => serde_json::json!({ "type": "object" }),
+            Self::JsonSchema { schema, .. } => schema.clone(),
+        }
+    }
+}
+
 /// Request payload for provider chat calls.
 #[derive(Debug, Clone, Copy)]
 pub struct ChatRequest<'a> {
     pub messages: &'a [ChatMessage],
     pub tools: Option<&'a [ToolSpec]>,
+    /// Constrain the reply to JSON (see [`ResponseFormat`]).
+    pub response_format: Option<&'a ResponseFormat>,
 }
 
 /// A tool result to feed back to the LLM.
@@ -155,13 +211,36 @@ impl StreamChunk {
         }
     }
 
-    /// Estimate tokens (rough approximation: ~4 chars per token).
+    /// Estimate tokens with the provider-agnostic heuristic counter.
     pub fn with_token_estimate(mut self) -> Self {
-        self.token_count = self.delta.len().div_ceil(4);
+        self.token_count = tokenizer::estimate_tokens(&self.delta);
         self
     }
 }
 
+/// Incremental event from [`Provider::stream_chat`].
+///
+/// Fold a sequence back into a [`ChatResponse`] with
+/// [`super::streaming::StreamAccumulator`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// Assistant text delta.
+    TextDelta(String),
+    /// Reasoning/thinking delta.
+    ReasoningDelta(String),
+    /// Tool-call delta. `index` identifies the call within the reply; `id` and
+    /// `name` arrive with the first delta of a call and `arguments` is a
+    /// fragment of its JSON arguments.
+    ToolCallDelta {
+        index: usize,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: String,
+    },
+    /// Token usage. Providers may report input and output counts separately.
+    Usage(TokenUsage),
+}
+
 /// Options for streaming chat requests.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StreamOptions {
@@ -233,6 +312,12 @@ pub struct ProviderCapabilities {
     pub native_tool_calling: bool,
     /// Whether the provider supports vision / image inputs.
     pub vision: bool,
+    /// Whether the provider constrains replies to a [`ResponseFormat`] natively
+    /// (e.g. OpenAI `response_format`, Gemini `responseSchema`).
+    ///
+    /// When `false`, the format is requested in the prompt and the reply is
+    /// validated and retried.
+    pub structured_output: bool,
 }
 
 /// Provider-specific tool payload formats.
@@ -328,6 +413,19 @@ pub trait Provider: Send + Sync {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        if let Some(format) = request.response_format {
+            if !self.supports_structured_output() {
+                return super::structured::prompt_guided_chat(
+                    self,
+                    request,
+                    format,
+                    model,
+                    temperature,
+                )
+                .await;
+            }
+        }
+
         // If tools are provided but provider doesn't support native tools,
         // inject tool instructions into system prompt as fallback.
         if let Some(tools) = request.tools {
@@ -388,6 +486,11 @@ pub trait Provider: Send + Sync {
         self.capabilities().vision
     }
 
+    /// Whether provider natively constrains replies to a [`ResponseFormat`].
+    fn supports_structured_output(&self) -> bool {
+        self.capabilities().structured_output
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -413,12 +516,68 @@ pub trait Provider: Send + Sync {
         })
     }
 
+    /// Whether the provider has a discounted asynchronous batch endpoint.
+    /// Default implementation returns false.
+    fn supports_batch(&self) -> bool {
+        false
+    }
+
+    /// Submit single-turn `requests` as one batch job and return the
+    /// provider's batch id. Requests carry no tools.
+    async fn submit_batch(
+        &self,
+        _requests: &[BatchRequest],
+        _model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<String> {
+        anyhow::bail!("provider does not support batch requests")
+    }
+
+    /// Check on a batch returned by [`Provider::submit_batch`].
+    async fn poll_batch(&self, _batch_id: &str) -> anyhow::Result<BatchStatus> {
+        anyhow::bail!("provider does not support batch requests")
+    }
+
+    /// Token counter for `model`, used to size requests before sending.
+    /// Default implementation picks a heuristic counter by model family.
+    fn token_counter(&self, model: &str) -> Box<dyn TokenCounter> {
+        Box::new(TokenizerFamily::for_model(model).counter())
+    }
+
+    /// Context window and output limits of `model`.
+    /// Default implementation consults the built-in model registry.
+    fn model_limits(&self, model: &str) -> ModelLimits {
+        model_limits::limits_for(model)
+    }
+
+    /// Latest rate-limit quota reported in this provider's responses.
+    /// Default implementation reports none.
+    fn quota(&self) -> Option<QuotaMetadata> {
+        None
+    }
+
     /// Whether provider supports streaming responses.
     /// Default implementation returns false.
     fn supports_streaming(&self) -> bool {
         false
     }
 
+    /// Streaming counterpart of [`Provider::chat`], tools included.
+    ///
+    /// Resolves once the provider has accepted the request, so connection and
+    /// HTTP errors surface here; the returned stream then yields
+    /// [`StreamEvent`]s as they arrive. The default implementation runs `chat`
+    /// and replays the complete response.
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let response = self.chat(request, model, temperature).await?;
+        Ok(super::streaming::replay(response))
+    }
+
     /// Streaming chat with optional system prompt.
     /// Returns an async stream of text chunks.
     /// Default implementation falls back to non-streaming chat.
@@ -502,6 +661,7 @@ mod tests {
             ProviderCapabilities {
                 native_tool_calling: true,
                 vision: true,
+                structured_output: false,
             }
         }
 
@@ -572,6 +732,7 @@ mod tests {
             usage: Some(TokenUsage {
                 input_tokens: Some(100),
                 output_tokens: Some(50),
+                ..TokenUsage::default()
             }),
             reasoning_content: None,
         };
@@ -617,14 +778,17 @@ mod tests {
         let caps1 = ProviderCapabilities {
             native_tool_calling: true,
             vision: false,
+            structured_output: false,
         };
         let caps2 = ProviderCapabilities {
             native_tool_calling: true,
             vision: false,
+            structured_output: false,
         };
         let caps3 = ProviderCapabilities {
             native_tool_calling: false,
             vision: false,
+            structured_output: false,
         };
 
         assert_eq!(caps1, caps2);
@@ -784,6 +948,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -801,6 +966,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -901,6 +1067,7 @@ mod tests {
                 ChatMessage::system("BASE_SYSTEM_PROMPT"),
             ],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -923,6 +1090,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::system("BASE"), ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -945,6 +1113,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let err = provider.chat(request, "model", 0.7).await.unwrap_err();
@@ -0,0 +1,94 @@
+//! `zeroclaw ingest` — feed workspace documents into the knowledge index.
+
+use super::knowledge::{IngestReport, KnowledgeIndex};
+use crate::config::Config;
+use anyhow::{bail, Result};
+use console::style;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Handle `zeroclaw ingest [paths] [--watch] [--status]`.
+pub async fn handle_command(
+    config: &Config,
+    paths: Vec<PathBuf>,
+    watch: bool,
+    status: bool,
+) -> Result<()> {
+    let index = KnowledgeIndex::from_config(config)?;
+
+    if status {
+        let stats = index.stats()?;
+        println!("Knowledge index:\n");
+        println!("  Documents: {}", stats.documents);
+        println!("  Chunks:    {}", stats.chunks);
+        return Ok(());
+    }
+
+    let roots = resolve_roots(config, paths);
+    if roots.is_empty() {
+        bail!("No paths given and `[knowledge] paths` is empty; run `zeroclaw ingest <path>`");
+    }
+
+    let mut first_pass = true;
+    loop {
+        for root in &roots {
+            match index
+                .ingest_path(root, &config.workspace_dir, &config.knowledge)
+                .await
+            {
+                Ok(report) if first_pass || report.ingested > 0 || report.removed > 0 => {
+                    print_report(root, &report);
+                }
+                Ok(_) => {}
+                Err(e) if watch => tracing::warn!("knowledge ingest of {}: {e}", root.display()),
+                Err(e) => return Err(e),
+            }
+        }
+        if !watch {
+            return Ok(());
+        }
+        if first_pass {
+            println!(
+                "Watching for changes every {}s (Ctrl-C to stop)...",
+                config.knowledge.watch_interval_secs.max(1)
+            );
+            first_pass = false;
+        }
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            () = tokio::time::sleep(Duration::from_secs(config.knowledge.watch_interval_secs.max(1))) => {}
+        }
+    }
+}
+
+/// Relative paths resolve against the working directory first, then the workspace.
+fn resolve_roots(config: &Config, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let paths = if paths.is_empty() {
+        config.knowledge.paths.iter().map(PathBuf::from).collect()
+    } else {
+        paths
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            if path.is_absolute() || path.exists() {
+                path
+            } else {
+                config.workspace_dir.join(path)
+            }
+        })
+        .collect()
+}
+
+fn print_report(root: &Path, report: &IngestReport) {
+    println!(
+        "{} {}: {} ingested ({} chunks), {} unchanged, {} removed, {} skipped",
+        style("✓").green().bold(),
+        root.display(),
+        report.ingested,
+        report.chunks,
+        report.unchanged,
+        report.removed,
+        report.skipped
+    );
+}
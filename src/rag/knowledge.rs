@@ -0,0 +1,818 @@
+//! Workspace document knowledge base.
+//!
+//! Markdown, text, HTML and (with the `rag-pdf` feature) PDF files are split
+//! with [`chunker::chunk_markdown`], embedded with the `[memory]` embedding
+//! provider and stored in `<workspace>/memory/knowledge.db` together with the
+//! file and line span each chunk came from. Files are re-ingested only when
+//! their content hash changes. [`KnowledgeIndex::search`] backs the
+//! `knowledge_search` tool.
+
+use crate::config::KnowledgeConfig;
+use crate::memory::chunker;
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::memory::vector;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const KNOWLEDGE_FILE_NAME: &str = "knowledge.db";
+
+/// File extensions picked up by ingestion.
+const TEXT_EXTENSIONS: &[&str] = &["md", "markdown", "mdx", "txt", "rst"];
+const HTML_EXTENSIONS: &[&str] = &["html", "htm"];
+
+/// A chunk ready to be stored, with its 1-based line span in the extracted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentChunk {
+    pub heading: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+}
+
+/// A search result with its citation.
+#[derive(Debug, Clone)]
+pub struct KnowledgeHit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub heading: Option<String>,
+    pub content: String,
+    pub score: f32,
+}
+
+impl KnowledgeHit {
+    /// `path:start-end` (or `path:line` for single-line chunks).
+    pub fn citation(&self) -> String {
+        if self.start_line == self.end_line {
+            format!("{}:{}", self.path, self.start_line)
+        } else {
+            format!("{}:{}-{}", self.path, self.start_line, self.end_line)
+        }
+    }
+}
+
+/// Outcome of one ingestion pass.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IngestReport {
+    pub ingested: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    pub removed: usize,
+    pub chunks: usize,
+}
+
+/// Document counts for `zeroclaw ingest --status`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KnowledgeStats {
+    pub documents: usize,
+    pub chunks: usize,
+}
+
+/// Extract plain text from a supported file. `None` means the format is not
+/// supported in this build.
+pub fn extract_text(path: &Path, bytes: &[u8]) -> Option<String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)?;
+    if TEXT_EXTENSIONS.contains(&ext.as_str()) {
+        return Some(String::from_utf8_lossy(bytes).into_owned());
+    }
+    if HTML_EXTENSIONS.contains(&ext.as_str()) {
+        return Some(html_to_text(&String::from_utf8_lossy(bytes)));
+    }
+    #[cfg(feature = "rag-pdf")]
+    if ext == "pdf" {
+        return pdf_extract::extract_text_from_mem(bytes).ok();
+    }
+    None
+}
+
+/// Whether ingestion should look at this file at all.
+fn is_supported(path: &Path) -> bool {
+    let Some(ext) = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+    else {
+        return false;
+    };
+    TEXT_EXTENSIONS.contains(&ext.as_str())
+        || HTML_EXTENSIONS.contains(&ext.as_str())
+        || (cfg!(feature = "rag-pdf") && ext == "pdf")
+}
+
+#[cfg(feature = "web-fetch-html2md")]
+fn html_to_text(html: &str) -> String {
+    html2md::rewrite_html(html, false)
+}
+
+#[cfg(all(not(feature = "web-fetch-html2md"), feature = "web-fetch-plaintext"))]
+fn html_to_text(html: &str) -> String {
+    nanohtml2text::html2text(html)
+}
+
+/// Minimal tag stripper used when no HTML converter is compiled in.
+#[cfg(not(any(feature = "web-fetch-html2md", feature = "web-fetch-plaintext")))]
+fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Split extracted text into chunks and recover the line span of each one.
+pub fn chunk_document(text: &str, max_tokens: usize) -> Vec<DocumentChunk> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let mut cursor = 0;
+    let mut out = Vec::new();
+
+    for chunk in chunker::chunk_markdown(text, max_tokens) {
+        let body: Vec<&str> = chunk
+            .content
+            .lines()
+            .map(str::trim_end)
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        // Sub-chunks of a long section repeat its heading, which sits before
+        // the cursor; the first body line found after it marks the start.
+        let start = body
+            .iter()
+            .find_map(|needle| find_line(&lines, cursor, needle))
+            .unwrap_or(cursor.min(lines.len().saturating_sub(1)));
+        let end = body
+            .last()
+            .and_then(|needle| find_line(&lines, start, needle))
+            .unwrap_or(start);
+        cursor = end + 1;
+
+        out.push(DocumentChunk {
+            heading: chunk.heading.as_deref().map(str::to_string),
+            start_line: start + 1,
+            end_line: end + 1,
+            content: chunk.content,
+        });
+    }
+    out
+}
+
+fn find_line(lines: &[&str], from: usize, needle: &str) -> Option<usize> {
+    lines
+        .iter()
+        .skip(from)
+        .position(|line| *line == needle)
+        .map(|offset| from + offset)
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Recursively collect supported files, skipping hidden entries. Symlinked
+/// directories are not followed, so a link cycle cannot recurse forever;
+/// symlinked files are still picked up.
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&path, out);
+        } else if path.is_file() && is_supported(&path) {
+            out.push(path);
+        }
+    }
+}
+
+/// Path used in citations: workspace-relative when possible.
+fn citation_path(path: &Path, workspace_dir: &Path) -> String {
+    path.strip_prefix(workspace_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Chunk store backing `knowledge_search`.
+pub struct KnowledgeIndex {
+    conn: Arc<Mutex<Connection>>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    vector_weight: f32,
+    keyword_weight: f32,
+}
+
+impl KnowledgeIndex {
+    /// Whether documents have ever been ingested into this workspace.
+    pub fn exists(workspace_dir: &Path) -> bool {
+        workspace_dir
+            .join("memory")
+            .join(KNOWLEDGE_FILE_NAME)
+            .is_file()
+    }
+
+    /// Open (creating if needed) `<workspace>/memory/knowledge.db`.
+    pub fn open(
+        workspace_dir: &Path,
+        embedder: Arc<dyn EmbeddingProvider>,
+        vector_weight: f32,
+        keyword_weight: f32,
+    ) -> Result<Self> {
+        let dir = workspace_dir.join("memory");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create memory directory: {}", dir.display()))?;
+        let db_path = dir.join(KNOWLEDGE_FILE_NAME);
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open knowledge index: {}", db_path.display()))?;
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        Self::init(conn, embedder, vector_weight, keyword_weight)
+    }
+
+    /// Open the workspace index with the configured `[memory]` embedding provider and weights.
+    pub fn from_config(config: &crate::config::Config) -> Result<Self> {
+        #[allow(clippy::cast_possible_truncation)]
+        Self::open(
+            &config.workspace_dir,
+            crate::memory::create_embedder(config)?,
+            config.memory.vector_weight as f32,
+            config.memory.keyword_weight as f32,
+        )
+    }
+
+    /// In-memory index (tests).
+    pub fn open_in_memory(embedder: Arc<dyn EmbeddingProvider>) -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, embedder, 0.7, 0.3)
+    }
+
+    fn init(
+        conn: Connection,
+        embedder: Arc<dyn EmbeddingProvider>,
+        vector_weight: f32,
+        keyword_weight: f32,
+    ) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS knowledge_documents (
+                path          TEXT PRIMARY KEY,
+                root          TEXT NOT NULL,
+                content_hash  TEXT NOT NULL,
+                chunk_count   INTEGER NOT NULL,
+                ingested_at   TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_knowledge_documents_root ON knowledge_documents(root);
+             CREATE TABLE IF NOT EXISTS knowledge_chunks (
+                id          INTEGER PRIMARY KEY,
+                path        TEXT NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                heading     TEXT,
+                start_line  INTEGER NOT NULL,
+                end_line    INTEGER NOT NULL,
+                content     TEXT NOT NULL,
+                embedding   BLOB
+             );
+             CREATE INDEX IF NOT EXISTS idx_knowledge_chunks_path ON knowledge_chunks(path);
+             CREATE VIRTUAL TABLE IF NOT EXISTS knowledge_fts USING fts5(
+                content, content=knowledge_chunks, content_rowid=id
+             );
+             CREATE TRIGGER IF NOT EXISTS knowledge_chunks_ai AFTER INSERT ON knowledge_chunks BEGIN
+                INSERT INTO knowledge_fts(rowid, content) VALUES (new.id, new.content);
+             END;
+             CREATE TRIGGER IF NOT EXISTS knowledge_chunks_ad AFTER DELETE ON knowledge_chunks BEGIN
+                INSERT INTO knowledge_fts(knowledge_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+             END;
+             CREATE TABLE IF NOT EXISTS knowledge_meta (
+                key    TEXT PRIMARY KEY,
+                value  TEXT NOT NULL
+             );",
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            embedder,
+            vector_weight,
+            keyword_weight,
+        })
+    }
+
+    /// Forget stored hashes when the embedding provider changed, so the next
+    /// pass re-embeds every document.
+    fn sync_embedding_fingerprint(conn: &Connection, fingerprint: &str) -> Result<()> {
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT value FROM knowledge_meta WHERE key = 'embedding_fingerprint'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if stored.as_deref() != Some(fingerprint) {
+            if stored.is_some() {
+                conn.execute("UPDATE knowledge_documents SET content_hash = ''", [])?;
+            }
+            conn.execute(
+                "INSERT OR REPLACE INTO knowledge_meta (key, value) VALUES ('embedding_fingerprint', ?1)",
+                params![fingerprint],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Ingest a file or folder. Unchanged files are skipped by content hash and
+    /// documents that disappeared from a folder are removed from the index.
+    pub async fn ingest_path(
+        &self,
+        path: &Path,
+        workspace_dir: &Path,
+        config: &KnowledgeConfig,
+    ) -> Result<IngestReport> {
+        let (path, workspace_dir) = (path.to_path_buf(), workspace_dir.to_path_buf());
+        let (path, workspace_dir, is_dir, files) =
+            tokio::task::spawn_blocking(move || -> Result<_> {
+                if !path.exists() {
+                    anyhow::bail!("Path does not exist: {}", path.display());
+                }
+                // Compare canonical paths so citations stay workspace-relative behind symlinks.
+                let path = std::fs::canonicalize(&path)?;
+                let workspace_dir = std::fs::canonicalize(&workspace_dir).unwrap_or(workspace_dir);
+                let is_dir = path.is_dir();
+                let mut files = Vec::new();
+                if is_dir {
+                    collect_files(&path, &mut files);
+                } else {
+                    files.push(path.clone());
+                }
+                files.sort();
+                Ok((path, workspace_dir, is_dir, files))
+            })
+            .await??;
+
+        let conn = Arc::clone(&self.conn);
+        let fingerprint = self.embedder.fingerprint();
+        tokio::task::spawn_blocking(move || {
+            Self::sync_embedding_fingerprint(&conn.lock(), &fingerprint)
+        })
+        .await??;
+
+        let root = citation_path(&path, &workspace_dir);
+        let mut report = IngestReport::default();
+        let mut seen = HashSet::new();
+        for file in &files {
+            let cited = citation_path(file, &workspace_dir);
+            seen.insert(cited.clone());
+            match self.ingest_file(file, &cited, &root, config).await {
+                Ok(Some(chunks)) => {
+                    report.ingested += 1;
+                    report.chunks += chunks;
+                }
+                Ok(None) => report.unchanged += 1,
+                Err(e) => {
+                    tracing::warn!("knowledge ingest skipped {}: {e}", file.display());
+                    report.skipped += 1;
+                }
+            }
+        }
+
+        if is_dir {
+            let conn = Arc::clone(&self.conn);
+            report.removed = tokio::task::spawn_blocking(move || -> Result<usize> {
+                let mut conn = conn.lock();
+                let mut removed = 0;
+                for stale in Self::document_paths(&conn, &root)? {
+                    if !seen.contains(&stale) {
+                        Self::delete_document(&mut conn, &stale)?;
+                        removed += 1;
+                    }
+                }
+                Ok(removed)
+            })
+            .await??;
+        }
+        Ok(report)
+    }
+
+    /// Returns the number of chunks stored, or `None` when the file is unchanged.
+    /// Reading, hashing, extraction and SQLite writes run on the blocking pool;
+    /// only the embedding call stays on the async worker.
+    async fn ingest_file(
+        &self,
+        file: &Path,
+        cited: &str,
+        root: &str,
+        config: &KnowledgeConfig,
+    ) -> Result<Option<usize>> {
+        let conn = Arc::clone(&self.conn);
+        let (file, cited, root) = (file.to_path_buf(), cited.to_string(), root.to_string());
+        let max_file_bytes = config.max_file_bytes;
+        let max_tokens = config.chunk_max_tokens.max(1);
+        let prepared = tokio::task::spawn_blocking({
+            let cited = cited.clone();
+            move || -> Result<Option<(String, Vec<DocumentChunk>)>> {
+                let size = std::fs::metadata(&file)?.len();
+                if size > max_file_bytes {
+                    anyhow::bail!(
+                        "file is {size} bytes, above knowledge.max_file_bytes ({max_file_bytes})"
+                    );
+                }
+                let bytes = std::fs::read(&file)?;
+                let hash = content_hash(&bytes);
+                if Self::stored_hash(&conn.lock(), &cited)?.as_deref() == Some(hash.as_str()) {
+                    return Ok(None);
+                }
+                let text = extract_text(&file, &bytes)
+                    .ok_or_else(|| anyhow::anyhow!("unsupported file type in this build"))?;
+                Ok(Some((hash, chunk_document(&text, max_tokens))))
+            }
+        })
+        .await??;
+        let Some((hash, chunks)) = prepared else {
+            return Ok(None);
+        };
+
+        let embeddings = if self.embedder.dimensions() == 0 || chunks.is_empty() {
+            Vec::new()
+        } else {
+            let texts: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+            self.embedder.embed(&texts).await.unwrap_or_else(|e| {
+                tracing::warn!("knowledge embeddings unavailable for {cited}: {e}");
+                Vec::new()
+            })
+        };
+
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            Self::store_document(&mut conn.lock(), &cited, &root, &hash, &chunks, &embeddings)
+        })
+        .await?
+    }
+
+    fn store_document(
+        conn: &mut Connection,
+        cited: &str,
+        root: &str,
+        hash: &str,
+        chunks: &[DocumentChunk],
+        embeddings: &[Vec<f32>],
+    ) -> Result<Option<usize>> {
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM knowledge_chunks WHERE path = ?1",
+            params![cited],
+        )?;
+        for (index, chunk) in chunks.iter().enumerate() {
+            let embedding = embeddings.get(index).map(|e| vector::vec_to_bytes(e));
+            #[allow(clippy::cast_possible_wrap)]
+            tx.execute(
+                "INSERT INTO knowledge_chunks (path, chunk_index, heading, start_line, end_line, content, embedding)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    cited,
+                    index as i64,
+                    chunk.heading,
+                    chunk.start_line as i64,
+                    chunk.end_line as i64,
+                    chunk.content,
+                    embedding
+                ],
+            )?;
+        }
+        #[allow(clippy::cast_possible_wrap)]
+        tx.execute(
+            "INSERT OR REPLACE INTO knowledge_documents (path, root, content_hash, chunk_count, ingested_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                cited,
+                root,
+                hash,
+                chunks.len() as i64,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        tx.commit()?;
+        Ok(Some(chunks.len()))
+    }
+
+    fn stored_hash(conn: &Connection, path: &str) -> Result<Option<String>> {
+        Ok(conn
+            .query_row(
+                "SELECT content_hash FROM knowledge_documents WHERE path = ?1",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn document_paths(conn: &Connection, root: &str) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT path FROM knowledge_documents WHERE root = ?1")?;
+        let rows = stmt.query_map(params![root], |row| row.get(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Drop a document and its chunks.
+    pub fn remove_document(&self, path: &str) -> Result<bool> {
+        Self::delete_document(&mut self.conn.lock(), path)
+    }
+
+    fn delete_document(conn: &mut Connection, path: &str) -> Result<bool> {
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM knowledge_chunks WHERE path = ?1",
+            params![path],
+        )?;
+        let removed = tx.execute(
+            "DELETE FROM knowledge_documents WHERE path = ?1",
+            params![path],
+        )?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    pub fn stats(&self) -> Result<KnowledgeStats> {
+        let conn = self.conn.lock();
+        let documents: i64 =
+            conn.query_row("SELECT COUNT(*) FROM knowledge_documents", [], |row| {
+                row.get(0)
+            })?;
+        let chunks: i64 = conn.query_row("SELECT COUNT(*) FROM knowledge_chunks", [], |row| {
+            row.get(0)
+        })?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(KnowledgeStats {
+            documents: documents as usize,
+            chunks: chunks as usize,
+        })
+    }
+
+    /// Hybrid keyword + vector search over ingested chunks.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<KnowledgeHit>> {
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let query_embedding = if self.embedder.dimensions() == 0 {
+            None
+        } else {
+            self.embedder.embed_one(query).await.ok()
+        };
+
+        let conn = self.conn.lock();
+        let keyword_results = Self::fts_search(&conn, query, limit * 2).unwrap_or_default();
+        let vector_results = match query_embedding {
+            Some(ref qe) => Self::vector_search(&conn, qe, limit * 2)?,
+            None => Vec::new(),
+        };
+        let merged = if vector_results.is_empty() {
+            let max = keyword_results
+                .iter()
+                .map(|(_, s)| *s)
+                .fold(f32::EPSILON, f32::max);
+            keyword_results
+                .iter()
+                .take(limit)
+                .map(|(id, score)| (id.clone(), score / max))
+                .collect::<Vec<_>>()
+        } else {
+            vector::hybrid_merge(
+                &vector_results,
+                &keyword_results,
+                self.vector_weight,
+                self.keyword_weight,
+                limit,
+            )
+            .into_iter()
+            .map(|r| (r.id, r.final_score))
+            .collect()
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT path, start_line, end_line, heading, content FROM knowledge_chunks WHERE id = ?1",
+        )?;
+        let mut hits = Vec::with_capacity(merged.len());
+        for (id, score) in merged {
+            let Ok(id) = id.parse::<i64>() else { continue };
+            let hit = stmt
+                .query_row(params![id], |row| {
+                    let start: i64 = row.get(1)?;
+                    let end: i64 = row.get(2)?;
+                    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                    Ok(KnowledgeHit {
+                        path: row.get(0)?,
+                        start_line: start as usize,
+                        end_line: end as usize,
+                        heading: row.get(3)?,
+                        content: row.get(4)?,
+                        score,
+                    })
+                })
+                .optional()?;
+            hits.extend(hit);
+        }
+        Ok(hits)
+    }
+
+    fn fts_search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<(String, f32)>> {
+        let fts_query = query
+            .split_whitespace()
+            .map(|w| format!("\"{}\"", w.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = conn.prepare(
+            "SELECT rowid, bm25(knowledge_fts) AS score FROM knowledge_fts
+             WHERE knowledge_fts MATCH ?1 ORDER BY score LIMIT ?2",
+        )?;
+        #[allow(clippy::cast_possible_wrap)]
+        let rows = stmt.query_map(params![fts_query, limit as i64], |row| {
+            let id: i64 = row.get(0)?;
+            let score: f64 = row.get(1)?;
+            // BM25 is negative (lower = better)
+            #[allow(clippy::cast_possible_truncation)]
+            Ok((id.to_string(), (-score) as f32))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn vector_search(
+        conn: &Connection,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        let mut stmt =
+            conn.prepare("SELECT id, embedding FROM knowledge_chunks WHERE embedding IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        let mut scored = Vec::new();
+        for row in rows {
+            let (id, blob) = row?;
+            let sim = vector::cosine_similarity(query_embedding, &vector::bytes_to_vec(&blob));
+            if sim > 0.0 {
+                scored.push((id.to_string(), sim));
+            }
+        }
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::{HashedNgramEmbedding, NoopEmbedding};
+    use tempfile::TempDir;
+
+    const GUIDE: &str = "# Setup\n\nInstall the CLI with cargo.\n\n## Deploy\n\nDeploys run every Friday at noon.\nRollbacks use the previous tag.\n";
+
+    fn config() -> KnowledgeConfig {
+        KnowledgeConfig::default()
+    }
+
+    #[test]
+    fn chunks_carry_line_spans() {
+        let chunks = chunk_document(GUIDE, 512);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 3));
+        assert_eq!(chunks[1].heading.as_deref(), Some("## Deploy"));
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (5, 8));
+    }
+
+    #[test]
+    fn split_sections_skip_repeated_heading() {
+        let body = "Paragraph about the release train.\n\n".repeat(40);
+        let text = format!("## Releases\n\n{body}");
+        let chunks = chunk_document(&text, 40);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].start_line, 1);
+        for pair in chunks.windows(2) {
+            assert!(pair[1].start_line > pair[0].end_line);
+            assert!(pair[1].start_line > 1);
+        }
+    }
+
+    #[test]
+    fn extracts_html_and_rejects_unknown_types() {
+        let text = extract_text(
+            Path::new("page.html"),
+            b"<html><body><h1>Title</h1><p>Body text</p></body></html>",
+        )
+        .unwrap();
+        assert!(text.contains("Title"));
+        assert!(text.contains("Body text"));
+        assert!(!text.contains("<p>"));
+        assert!(extract_text(Path::new("image.png"), b"\x89PNG").is_none());
+    }
+
+    #[tokio::test]
+    async fn ingest_is_incremental_and_prunes_removed_files() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("docs");
+        std::fs::create_dir_all(docs.join(".git")).unwrap();
+        std::fs::write(docs.join("guide.md"), GUIDE).unwrap();
+        std::fs::write(
+            docs.join("notes.txt"),
+            "Office wifi password rotates monthly",
+        )
+        .unwrap();
+        std::fs::write(docs.join("logo.png"), "not text").unwrap();
+        std::fs::write(docs.join(".git").join("HEAD"), "ref").unwrap();
+
+        let index = KnowledgeIndex::open_in_memory(Arc::new(NoopEmbedding)).unwrap();
+        let first = index
+            .ingest_path(&docs, tmp.path(), &config())
+            .await
+            .unwrap();
+        assert_eq!(first.ingested, 2);
+        assert_eq!(first.chunks, 3);
+
+        let second = index
+            .ingest_path(&docs, tmp.path(), &config())
+            .await
+            .unwrap();
+        assert_eq!(second.ingested, 0);
+        assert_eq!(second.unchanged, 2);
+
+        std::fs::write(docs.join("guide.md"), format!("{GUIDE}\nNew line.\n")).unwrap();
+        std::fs::remove_file(docs.join("notes.txt")).unwrap();
+        let third = index
+            .ingest_path(&docs, tmp.path(), &config())
+            .await
+            .unwrap();
+        assert_eq!(third.ingested, 1);
+        assert_eq!(third.removed, 1);
+        assert_eq!(index.stats().unwrap().documents, 1);
+    }
+
+    #[tokio::test]
+    async fn search_returns_cited_chunks() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(docs.join("guide.md"), GUIDE).unwrap();
+
+        let index =
+            KnowledgeIndex::open_in_memory(Arc::new(HashedNgramEmbedding::new(64))).unwrap();
+        index
+            .ingest_path(&docs, tmp.path(), &config())
+            .await
+            .unwrap();
+
+        let hits = index.search("when do deploys run", 3).await.unwrap();
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].citation(), "docs/guide.md:5-8");
+        assert!(hits[0].content.contains("Friday"));
+        assert!(index.search("   ", 3).await.unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn symlinked_directories_are_not_followed() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(docs.join("guide.md"), GUIDE).unwrap();
+        std::os::unix::fs::symlink(&docs, docs.join("loop")).unwrap();
+        std::os::unix::fs::symlink(docs.join("guide.md"), docs.join("linked.md")).unwrap();
+
+        let index = KnowledgeIndex::open_in_memory(Arc::new(NoopEmbedding)).unwrap();
+        let report = index
+            .ingest_path(&docs, tmp.path(), &config())
+            .await
+            .unwrap();
+        assert_eq!(report.ingested, 2, "guide.md and the linked file only");
+        assert_eq!(index.stats().unwrap().documents, 2);
+    }
+
+    #[tokio::test]
+    async fn oversized_files_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("big.md");
+        std::fs::write(&file, "x".repeat(64)).unwrap();
+        let cfg = KnowledgeConfig {
+            max_file_bytes: 16,
+            ..KnowledgeConfig::default()
+        };
+        let index = KnowledgeIndex::open_in_memory(Arc::new(NoopEmbedding)).unwrap();
+        let report = index.ingest_path(&file, tmp.path(), &cfg).await.unwrap();
+        assert_eq!(report.skipped, 1);
+        assert_eq!(index.stats().unwrap().documents, 0);
+    }
+}
@@ -246,6 +246,16 @@ impl Tool for CronAddTool {
                     delete_after_run,
                 )
             }
+            JobType::Memory => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(
+                        "memory jobs are installed with `zeroclaw memory consolidate --schedule`"
+                            .to_string(),
+                    ),
+                });
+            }
         };
 
         match result {
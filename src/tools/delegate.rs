@@ -1071,6 +1071,7 @@ mod tests {
                 model: "model".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -1177,6 +1178,7 @@ mod tests {
                 model: "test-model".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -1212,6 +1214,7 @@ mod tests {
                 model: "test-model".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -1392,6 +1395,7 @@ mod tests {
                 model: "model".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -1461,6 +1465,7 @@ mod tests {
                 model: "model-test".to_string(),
                 system_prompt: None,
                 api_key: Some("delegate-test-credential".to_string()),
+                api_url: None,
                 temperature: Some(0.2),
                 max_depth: 2,
                 agentic: false,
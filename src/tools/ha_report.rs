@@ -39,8 +39,12 @@ impl Tool for HomeAssistantReportTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let base_url = args["base_url"].as_str().ok_or_else(|| anyhow::anyhow!("Missing base_url"))?;
-        let access_token = args["access_token"].as_str().ok_or_else(|| anyhow::anyhow!("Missing access_token"))?;
+        let base_url = args["base_url"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing base_url"))?;
+        let access_token = args["access_token"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing access_token"))?;
 
         let client = HomeAssistantClient::new(base_url.to_string(), access_token.to_string());
         match get_ha_insights(&client).await {
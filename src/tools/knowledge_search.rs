@@ -0,0 +1,143 @@
+use super::traits::{Tool, ToolResult};
+use crate::rag::knowledge::KnowledgeIndex;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+const DEFAULT_LIMIT: u64 = 5;
+const MAX_LIMIT: u64 = 20;
+
+/// Let the agent search documents ingested with `zeroclaw ingest`
+pub struct KnowledgeSearchTool {
+    index: Arc<KnowledgeIndex>,
+}
+
+impl KnowledgeSearchTool {
+    pub fn new(index: Arc<KnowledgeIndex>) -> Self {
+        Self { index }
+    }
+}
+
+#[async_trait]
+impl Tool for KnowledgeSearchTool {
+    fn name(&self) -> &str {
+        "knowledge_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search workspace documents (runbooks, notes, manuals) ingested into the knowledge base. Returns relevant passages with file:line citations; cite them when answering."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for in the ingested documents"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_LIMIT,
+                    "description": "Max passages to return (default: 5)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, MAX_LIMIT) as usize;
+
+        match self.index.search(query, limit).await {
+            Ok(hits) if hits.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No passages found matching that query.".into(),
+                error: None,
+            }),
+            Ok(hits) => {
+                let mut output = format!("Found {} passages:\n", hits.len());
+                for (i, hit) in hits.iter().enumerate() {
+                    let _ = writeln!(
+                        output,
+                        "\n[{}] {} [{:.0}%]\n{}",
+                        i + 1,
+                        hit.citation(),
+                        hit.score * 100.0,
+                        hit.content
+                    );
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Knowledge search failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KnowledgeConfig;
+    use crate::memory::embeddings::NoopEmbedding;
+    use tempfile::TempDir;
+
+    async fn seeded_index(tmp: &TempDir) -> Arc<KnowledgeIndex> {
+        let docs = tmp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(
+            docs.join("runbook.md"),
+            "# Runbook\n\n## Backups\n\nBackups run nightly at 02:00 UTC.\n",
+        )
+        .unwrap();
+        let index = KnowledgeIndex::open_in_memory(Arc::new(NoopEmbedding)).unwrap();
+        index
+            .ingest_path(&docs, tmp.path(), &KnowledgeConfig::default())
+            .await
+            .unwrap();
+        Arc::new(index)
+    }
+
+    #[tokio::test]
+    async fn returns_passages_with_citations() {
+        let tmp = TempDir::new().unwrap();
+        let tool = KnowledgeSearchTool::new(seeded_index(&tmp).await);
+        let result = tool
+            .execute(json!({"query": "when do backups run"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1 passages"));
+        assert!(result.output.contains("[1] docs/runbook.md:3-5"));
+        assert!(result.output.contains("nightly at 02:00 UTC"));
+    }
+
+    #[tokio::test]
+    async fn no_match_and_missing_query() {
+        let tmp = TempDir::new().unwrap();
+        let tool = KnowledgeSearchTool::new(seeded_index(&tmp).await);
+        let result = tool.execute(json!({"query": "kubernetes"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No passages found"));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use super::traits::{Tool, ToolResult};
+use crate::memory::namespace;
 use crate::memory::Memory;
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
@@ -35,6 +36,11 @@ impl Tool for MemoryForgetTool {
                 "key": {
                     "type": "string",
                     "description": "The key of the memory to forget"
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["own", "shared"],
+                    "description": "'own' (default) forgets from the current user's namespace; 'shared' from the shared namespace, if policy allows writes there."
                 }
             },
             "required": ["key"]
@@ -47,6 +53,18 @@ impl Tool for MemoryForgetTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'key' parameter"))?;
 
+        let namespace =
+            match namespace::resolve_write_scope(args.get("scope").and_then(|v| v.as_str())) {
+                Ok(namespace) => namespace,
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                    })
+                }
+            };
+
         if let Err(error) = self
             .security
             .enforce_tool_operation(ToolOperation::Act, "memory_forget")
@@ -58,7 +76,13 @@ impl Tool for MemoryForgetTool {
             });
         }
 
-        match self.memory.forget(key).await {
+        let result = if namespace.is_shared() && namespace::current().is_none() {
+            // Outside a channel message keys are addressed exactly as stored.
+            self.memory.forget(key).await
+        } else {
+            self.memory.forget_in_namespace(&namespace, key).await
+        };
+        match result {
             Ok(true) => Ok(ToolResult {
                 success: true,
                 output: format!("Forgot memory: {key}"),
@@ -127,6 +151,42 @@ mod tests {
         assert!(result.output.contains("No memory found"));
     }
 
+    #[tokio::test]
+    async fn forget_only_touches_own_namespace() {
+        let (_tmp, mem) = test_mem();
+        let alice = namespace::MemoryNamespace::sender("telegram", "alice");
+        let bob = namespace::MemoryNamespace::sender("telegram", "bob");
+        for ns in [&alice, &bob] {
+            mem.store_in_namespace(
+                ns,
+                "color",
+                "favorite color",
+                MemoryCategory::Core,
+                None,
+                crate::memory::MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let tool = MemoryForgetTool::new(mem.clone(), test_security());
+        let context = namespace::NamespaceContext {
+            namespace: alice.clone(),
+            policy: crate::config::MemoryNamespaceConfig::default(),
+        };
+        let result = namespace::with_namespace(context, tool.execute(json!({"key": "color"})))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Forgot"));
+
+        assert!(mem
+            .get_in_namespace(&alice, "color")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(mem.get_in_namespace(&bob, "color").await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn forget_missing_key() {
         let (_tmp, mem) = test_mem();
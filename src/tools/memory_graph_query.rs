@@ -0,0 +1,222 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::graph::KnowledgeGraph;
+use crate::memory::namespace;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+const DEFAULT_DEPTH: u64 = 1;
+const MAX_DEPTH: u64 = 3;
+const DEFAULT_LIMIT: u64 = 20;
+
+/// Let the agent walk the knowledge graph built by the `graph` memory backend
+pub struct MemoryGraphQueryTool {
+    graph: Arc<KnowledgeGraph>,
+}
+
+impl MemoryGraphQueryTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self { graph }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryGraphQueryTool {
+    fn name(&self) -> &str {
+        "memory_graph_query"
+    }
+
+    fn description(&self) -> &str {
+        "Explore relations between entities extracted from long-term memory (e.g. what is located in the kitchen, where someone works). Returns subject --predicate--> object facts around an entity, with the memory key each came from."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "entity": {
+                    "type": "string",
+                    "description": "Entity to start from (e.g. 'kitchen', 'thermostat', 'Alice')"
+                },
+                "predicate": {
+                    "type": "string",
+                    "description": "Only return relations of this type (e.g. 'located_in', 'is_a', 'works_at')"
+                },
+                "depth": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DEPTH,
+                    "description": "How many relation hops to follow (default: 1)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max relations to return (default: 20)"
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["own", "shared", "all"],
+                    "description": "'own' (default) uses facts from the current user's namespace plus shared memory; 'shared' only shared memory; 'all' every namespace, if policy allows."
+                }
+            },
+            "required": ["entity"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let entity = args
+            .get("entity")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'entity' parameter"))?;
+        let predicate = args
+            .get("predicate")
+            .and_then(|v| v.as_str())
+            .map(|p| p.trim().to_lowercase().replace(' ', "_"))
+            .filter(|p| !p.is_empty());
+
+        #[allow(clippy::cast_possible_truncation)]
+        let depth = args
+            .get("depth")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(DEFAULT_DEPTH)
+            .clamp(1, MAX_DEPTH) as usize;
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(DEFAULT_LIMIT)
+            .max(1) as usize;
+
+        let scope =
+            match namespace::resolve_recall_scope(args.get("scope").and_then(|v| v.as_str())) {
+                Ok(scope) => scope,
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                    })
+                }
+            };
+
+        let Some(entity_id) = self.graph.find_entity(entity)? else {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No entity named '{entity}' in the knowledge graph."),
+                error: None,
+            });
+        };
+
+        // Overfetch: predicate and namespace filters apply after the walk.
+        let relations: Vec<_> = self
+            .graph
+            .neighbourhood(&[entity_id], depth, limit.saturating_mul(4))?
+            .into_iter()
+            .filter(|t| scope.allows_namespace(&t.relation.namespace))
+            .filter(|t| {
+                predicate
+                    .as_deref()
+                    .map_or(true, |p| t.relation.predicate == p)
+            })
+            .take(limit)
+            .collect();
+
+        if relations.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No relations found for '{entity}'."),
+                error: None,
+            });
+        }
+
+        let mut output = format!("Found {} relations:\n", relations.len());
+        for traversed in &relations {
+            let relation = &traversed.relation;
+            let _ = writeln!(
+                output,
+                "- {} --{}--> {} (hop {}, from: {})",
+                relation.subject,
+                relation.predicate,
+                relation.object,
+                traversed.hop,
+                relation.namespace.display_key(&relation.source_key)
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::namespace::MemoryNamespace;
+
+    fn seeded_graph() -> Arc<KnowledgeGraph> {
+        let graph = KnowledgeGraph::open_in_memory().unwrap();
+        let shared = MemoryNamespace::shared();
+        graph
+            .index("room", "The thermostat is in the kitchen", &shared)
+            .unwrap();
+        graph
+            .index("kind", "The thermostat is a Nest device", &shared)
+            .unwrap();
+        graph
+            .index(
+                "sender:telegram:bob/fridge",
+                "The fridge is in the kitchen",
+                &MemoryNamespace::sender("telegram", "bob"),
+            )
+            .unwrap();
+        Arc::new(graph)
+    }
+
+    #[tokio::test]
+    async fn walks_neighbourhood_with_depth_and_predicate() {
+        let tool = MemoryGraphQueryTool::new(seeded_graph());
+
+        let result = tool.execute(json!({"entity": "Kitchen"})).await.unwrap();
+        assert!(result.success);
+        assert!(result
+            .output
+            .contains("thermostat --located_in--> kitchen (hop 1, from: room)"));
+        assert!(!result.output.contains("nest device"));
+
+        let result = tool
+            .execute(json!({"entity": "kitchen", "depth": 2, "predicate": "is_a"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1 relations"));
+        assert!(result.output.contains("thermostat --is_a--> nest device"));
+    }
+
+    #[tokio::test]
+    async fn hides_other_senders_relations() {
+        let tool = MemoryGraphQueryTool::new(seeded_graph());
+
+        let unscoped = tool.execute(json!({"entity": "kitchen"})).await.unwrap();
+        assert!(unscoped.output.contains("fridge"));
+
+        let context = namespace::NamespaceContext {
+            namespace: MemoryNamespace::sender("telegram", "alice"),
+            policy: crate::config::MemoryNamespaceConfig::default(),
+        };
+        let scoped = namespace::with_namespace(context, tool.execute(json!({"entity": "kitchen"})))
+            .await
+            .unwrap();
+        assert!(scoped.output.contains("thermostat"));
+        assert!(!scoped.output.contains("fridge"));
+    }
+
+    #[tokio::test]
+    async fn unknown_entity_and_missing_param() {
+        let tool = MemoryGraphQueryTool::new(seeded_graph());
+        let result = tool.execute(json!({"entity": "garage"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No entity named"));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
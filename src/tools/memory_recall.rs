@@ -1,4 +1,5 @@
 use super::traits::{Tool, ToolResult};
+use crate::memory::namespace::{self, MemoryNamespace, RecallScope};
 use crate::memory::Memory;
 use async_trait::async_trait;
 use serde_json::json;
@@ -37,6 +38,16 @@ impl Tool for MemoryRecallTool {
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return memories carrying all of these tags"
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["own", "shared", "all"],
+                    "description": "'own' (default) searches the current user's namespace plus shared memory; 'shared' only shared memory; 'all' every namespace, if policy allows."
                 }
             },
             "required": ["query"]
@@ -55,7 +66,35 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit, None).await {
+        let tags: Vec<String> = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let scope =
+            match namespace::resolve_recall_scope(args.get("scope").and_then(|v| v.as_str())) {
+                Ok(scope) => scope,
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                    })
+                }
+            };
+
+        match self
+            .memory
+            .recall_scoped(&scope, query, limit, None, &tags)
+            .await
+        {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
@@ -67,10 +106,22 @@ impl Tool for MemoryRecallTool {
                     let score = entry
                         .score
                         .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let tags = if entry.metadata.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" #{}", entry.metadata.tags.join(" #"))
+                    };
+                    let entry_namespace = MemoryNamespace::of(entry);
+                    // Only cross-namespace results need their owner spelled out.
+                    let key = if scope == RecallScope::All {
+                        entry.key.as_str()
+                    } else {
+                        entry_namespace.display_key(&entry.key)
+                    };
                     let _ = writeln!(
                         output,
-                        "- [{}] {}: {}{score}",
-                        entry.category, entry.key, entry.content
+                        "- [{}] {key}: {}{tags}{score}",
+                        entry.category, entry.content
                     );
                 }
                 Ok(ToolResult {
@@ -91,7 +142,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::{MemoryCategory, MemoryMetadata, SqliteMemory};
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -149,6 +200,77 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_filters_by_tags() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store_with_metadata(
+            "deploy",
+            "Deploy window is Friday",
+            MemoryCategory::Core,
+            None,
+            MemoryMetadata::default().with_tags(["ops"]),
+        )
+        .await
+        .unwrap();
+        mem.store("party", "Team party is Friday", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "Friday", "tags": ["OPS"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("Deploy window is Friday #ops"));
+    }
+
+    #[tokio::test]
+    async fn recall_is_scoped_to_sender_namespace() {
+        let (_tmp, mem) = seeded_mem();
+        let alice = MemoryNamespace::sender("telegram", "alice");
+        let bob = MemoryNamespace::sender("discord", "bob");
+        for (ns, content) in [
+            (&alice, "Alice likes green tea"),
+            (&bob, "Bob likes black tea"),
+            (&MemoryNamespace::shared(), "Office serves tea at noon"),
+        ] {
+            mem.store_in_namespace(
+                ns,
+                "tea",
+                content,
+                MemoryCategory::Core,
+                None,
+                MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let tool = MemoryRecallTool::new(mem);
+        let context = namespace::NamespaceContext {
+            namespace: alice,
+            policy: crate::config::MemoryNamespaceConfig::default(),
+        };
+        let (own, all) = namespace::with_namespace(context, async {
+            let own = tool.execute(json!({"query": "tea"})).await.unwrap();
+            let all = tool
+                .execute(json!({"query": "tea", "scope": "all"}))
+                .await
+                .unwrap();
+            (own, all)
+        })
+        .await;
+
+        assert!(own.success);
+        assert!(own.output.contains("Found 2"));
+        assert!(own.output.contains("tea: Alice likes green tea"));
+        assert!(!own.output.contains("Bob"));
+        assert!(!all.success);
+        assert!(all.error.unwrap().contains("allow_cross_namespace_recall"));
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
@@ -1,5 +1,6 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::{Memory, MemoryCategory};
+use crate::memory::namespace;
+use crate::memory::{Memory, MemoryCategory, MemoryMetadata, MemorySource};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -43,6 +44,21 @@ impl Tool for MemoryStoreTool {
                 "category": {
                     "type": "string",
                     "description": "Memory category: 'core' (permanent), 'daily' (session), 'conversation' (chat), or a custom category name. Defaults to 'core'."
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional labels for later filtering with memory_recall (e.g. ['project', 'deadline'])"
+                },
+                "ttl_seconds": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional lifetime in seconds; the memory is forgotten once it elapses. Omit for facts that should persist."
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["own", "shared"],
+                    "description": "'own' (default) stores in the current user's namespace; 'shared' stores where every user can recall it, if policy allows."
                 }
             },
             "required": ["key", "content"]
@@ -67,6 +83,44 @@ impl Tool for MemoryStoreTool {
             Some(other) => MemoryCategory::Custom(other.to_string()),
         };
 
+        let tags: Vec<&str> = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut metadata = MemoryMetadata::default()
+            .with_tags(tags)
+            .with_source(MemorySource {
+                tool: Some(self.name().to_string()),
+                ..MemorySource::default()
+            });
+        match args.get("ttl_seconds") {
+            None | Some(serde_json::Value::Null) => {}
+            Some(value) => match value.as_u64().filter(|secs| *secs > 0) {
+                Some(secs) => metadata = metadata.with_ttl(std::time::Duration::from_secs(secs)),
+                None => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("'ttl_seconds' must be a positive integer".into()),
+                    })
+                }
+            },
+        }
+
+        let namespace =
+            match namespace::resolve_write_scope(args.get("scope").and_then(|v| v.as_str())) {
+                Ok(namespace) => namespace,
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                    })
+                }
+            };
+
         if let Err(error) = self
             .security
             .enforce_tool_operation(ToolOperation::Act, "memory_store")
@@ -78,7 +132,11 @@ impl Tool for MemoryStoreTool {
             });
         }
 
-        match self.memory.store(key, content, category, None).await {
+        match self
+            .memory
+            .store_in_namespace(&namespace, key, content, category, None, metadata)
+            .await
+        {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
@@ -164,6 +222,79 @@ mod tests {
         assert_eq!(entry.category, MemoryCategory::Custom("project".into()));
     }
 
+    #[tokio::test]
+    async fn store_with_tags_and_ttl_records_metadata() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let result = tool
+            .execute(json!({
+                "key": "standup",
+                "content": "Standup moved to 10:30 today",
+                "tags": ["Work", "schedule"],
+                "ttl_seconds": 3600
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let entry = mem.get("standup").await.unwrap().unwrap();
+        assert_eq!(entry.metadata.tags, vec!["work", "schedule"]);
+        assert!(entry.metadata.expires_at.is_some());
+        assert_eq!(
+            entry.metadata.source.and_then(|s| s.tool).as_deref(),
+            Some("memory_store")
+        );
+    }
+
+    #[tokio::test]
+    async fn store_rejects_invalid_ttl() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let result = tool
+            .execute(json!({"key": "k", "content": "v", "ttl_seconds": -5}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("ttl_seconds"));
+        assert!(mem.get("k").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn store_uses_sender_namespace_and_gates_shared_writes() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let alice = namespace::MemoryNamespace::sender("telegram", "alice");
+        let context = namespace::NamespaceContext {
+            namespace: alice.clone(),
+            policy: crate::config::MemoryNamespaceConfig::default(),
+        };
+
+        let (own, shared) = namespace::with_namespace(context, async {
+            let own = tool
+                .execute(json!({"key": "color", "content": "Likes green"}))
+                .await
+                .unwrap();
+            let shared = tool
+                .execute(json!({"key": "color", "content": "Likes red", "scope": "shared"}))
+                .await
+                .unwrap();
+            (own, shared)
+        })
+        .await;
+
+        assert!(own.success);
+        assert!(!shared.success);
+        assert!(shared.error.unwrap().contains("allow_shared_writes"));
+        assert!(mem.get("color").await.unwrap().is_none());
+        let entry = mem
+            .get_in_namespace(&alice, "color")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.content, "Likes green");
+        assert_eq!(entry.metadata.namespace.as_deref(), Some(alice.as_str()));
+    }
+
     #[tokio::test]
     async fn store_missing_key() {
         let (_tmp, mem) = test_mem();
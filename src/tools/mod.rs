@@ -34,12 +34,9 @@ pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
 pub mod git_operations;
-<<<<<<< HEAD
-pub mod ha_report;
-=======
 pub mod glob_search;
+pub mod ha_report;
 #[cfg(feature = "hardware")]
->>>>>>> upstream/main
 pub mod hardware_board_info;
 #[cfg(feature = "hardware")]
 pub mod hardware_memory_map;
@@ -47,7 +44,9 @@ pub mod hardware_memory_map;
 pub mod hardware_memory_read;
 pub mod http_request;
 pub mod image_info;
+pub mod knowledge_search;
 pub mod memory_forget;
+pub mod memory_graph_query;
 pub mod memory_recall;
 pub mod memory_store;
 pub mod model_routing_config;
@@ -87,12 +86,9 @@ pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use git_operations::GitOperationsTool;
-<<<<<<< HEAD
-pub use ha_report::HomeAssistantReportTool;
-=======
 pub use glob_search::GlobSearchTool;
+pub use ha_report::HomeAssistantReportTool;
 #[cfg(feature = "hardware")]
->>>>>>> upstream/main
 pub use hardware_board_info::HardwareBoardInfoTool;
 #[cfg(feature = "hardware")]
 pub use hardware_memory_map::HardwareMemoryMapTool;
@@ -100,7 +96,9 @@ pub use hardware_memory_map::HardwareMemoryMapTool;
 pub use hardware_memory_read::HardwareMemoryReadTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
+pub use knowledge_search::KnowledgeSearchTool;
 pub use memory_forget::MemoryForgetTool;
+pub use memory_graph_query::MemoryGraphQueryTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
 pub use model_routing_config::ModelRoutingConfigTool;
@@ -290,6 +288,30 @@ pub fn all_tools_with_runtime(
         Arc::new(HomeAssistantReportTool::new()),
     ];
 
+    // Graph queries need the knowledge graph kept by the `graph` memory backend
+    let memory_backend = crate::memory::effective_memory_backend_name(
+        &root_config.memory.backend,
+        Some(&root_config.storage.provider.config),
+    );
+    if crate::memory::classify_memory_backend(&memory_backend)
+        == crate::memory::MemoryBackendKind::Graph
+    {
+        match crate::memory::graph::KnowledgeGraph::open(workspace_dir) {
+            Ok(graph) => tool_arcs.push(Arc::new(MemoryGraphQueryTool::new(Arc::new(graph)))),
+            Err(e) => tracing::warn!("memory_graph_query disabled: {e}"),
+        }
+    }
+
+    // Knowledge search only makes sense once `zeroclaw ingest` has built an index
+    if root_config.knowledge.enabled
+        && crate::rag::knowledge::KnowledgeIndex::exists(&root_config.workspace_dir)
+    {
+        match crate::rag::knowledge::KnowledgeIndex::from_config(root_config) {
+            Ok(index) => tool_arcs.push(Arc::new(KnowledgeSearchTool::new(Arc::new(index)))),
+            Err(e) => tracing::warn!("knowledge_search disabled: {e}"),
+        }
+    }
+
     if has_shell_access {
         tool_arcs.push(Arc::new(ShellTool::new_with_syscall_detector(
             security.clone(),
@@ -438,6 +460,7 @@ pub fn all_tools_with_runtime(
                 .map(|mode| mode.as_compatible_mode()),
             max_tokens_override: None,
             model_support_vision: root_config.model_support_vision,
+            model_context_window: root_config.model_context_window,
         };
         let parent_tools = Arc::new(tool_arcs.clone());
         let mut delegate_tool = DelegateTool::new_with_options(
@@ -628,6 +651,74 @@ mod tests {
         assert!(names.contains(&"proxy_config"));
     }
 
+    #[test]
+    fn all_tools_includes_graph_query_only_for_graph_backend() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mut cfg = test_config(&tmp);
+        let names_for = |cfg: &Config| {
+            let mem: Arc<dyn Memory> =
+                Arc::from(crate::memory::create_memory(&cfg.memory, tmp.path(), None).unwrap());
+            all_tools(
+                Arc::new(Config::default()),
+                &security,
+                mem,
+                None,
+                None,
+                &BrowserConfig::default(),
+                &crate::config::HttpRequestConfig::default(),
+                &crate::config::WebFetchConfig::default(),
+                tmp.path(),
+                &HashMap::new(),
+                None,
+                cfg,
+                None,
+            )
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect::<Vec<_>>()
+        };
+
+        assert!(!names_for(&cfg).contains(&"memory_graph_query".to_string()));
+        cfg.memory.backend = "graph".into();
+        assert!(names_for(&cfg).contains(&"memory_graph_query".to_string()));
+    }
+
+    #[test]
+    fn all_tools_includes_knowledge_search_after_ingest() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mut cfg = test_config(&tmp);
+        let names_for = |cfg: &Config| {
+            let mem: Arc<dyn Memory> =
+                Arc::from(crate::memory::create_memory(&cfg.memory, tmp.path(), None).unwrap());
+            all_tools(
+                Arc::new(Config::default()),
+                &security,
+                mem,
+                None,
+                None,
+                &BrowserConfig::default(),
+                &crate::config::HttpRequestConfig::default(),
+                &crate::config::WebFetchConfig::default(),
+                tmp.path(),
+                &HashMap::new(),
+                None,
+                cfg,
+                None,
+            )
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect::<Vec<_>>()
+        };
+
+        assert!(!names_for(&cfg).contains(&"knowledge_search".to_string()));
+        crate::rag::knowledge::KnowledgeIndex::from_config(&cfg).unwrap();
+        assert!(names_for(&cfg).contains(&"knowledge_search".to_string()));
+        cfg.knowledge.enabled = false;
+        assert!(!names_for(&cfg).contains(&"knowledge_search".to_string()));
+    }
+
     #[test]
     fn all_tools_includes_browser_when_enabled() {
         let tmp = TempDir::new().unwrap();
@@ -702,6 +793,7 @@ mod tests {
             &HashMap::new(),
             None,
             &cfg,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"wasm_module"));
@@ -833,6 +925,7 @@ mod tests {
                 model: "llama3".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -854,6 +947,7 @@ mod tests {
             &agents,
             Some("delegate-test-credential"),
             &cfg,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"delegate"));
@@ -919,6 +1013,7 @@ mod tests {
                 model: "llama3".to_string(),
                 system_prompt: None,
                 api_key: None,
+                api_url: None,
                 temperature: None,
                 max_depth: 3,
                 agentic: false,
@@ -940,6 +1035,7 @@ mod tests {
             &agents,
             Some("delegate-test-credential"),
             &cfg,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"delegate"));
@@ -520,6 +520,7 @@ mod tests {
                 model: "llama3".to_string(),
                 system_prompt: Some("You are a research assistant.".to_string()),
                 api_key: None,
+                api_url: None,
                 temperature: Some(0.3),
                 max_depth: 3,
                 agentic: false,
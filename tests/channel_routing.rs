@@ -25,6 +25,8 @@ fn channel_message_sender_field_holds_platform_user_id() {
         channel: "telegram".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        transcribed_voice: false,
     };
 
     assert_eq!(msg.sender, "123456789");
@@ -47,6 +49,8 @@ fn channel_message_reply_target_distinct_from_sender() {
         channel: "discord".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        transcribed_voice: false,
     };
 
     assert_ne!(
@@ -67,6 +71,8 @@ fn channel_message_fields_not_swapped() {
         channel: "test".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        attachments: Vec::new(),
+        transcribed_voice: false,
     };
 
     assert_eq!(
@@ -93,6 +99,8 @@ fn channel_message_preserves_all_fields_on_clone() {
         channel: "test_channel".into(),
         timestamp: 1700000001,
         thread_ts: None,
+        attachments: Vec::new(),
+        transcribed_voice: false,
     };
 
     let cloned = original.clone();
@@ -186,6 +194,8 @@ impl Channel for CapturingChannel {
             channel: "capturing".into(),
             timestamp: 1700000000,
             thread_ts: None,
+            attachments: Vec::new(),
+            transcribed_voice: false,
         })
         .await
         .map_err(|e| anyhow::anyhow!(e.to_string()))
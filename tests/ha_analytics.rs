@@ -1,5 +1,5 @@
+use zeroclaw::integrations::home_assistant::analytics::HomeHabitAnalyzer;
 use zeroclaw::integrations::home_assistant::HaState;
-use zeroclaw::integrations::home_assistant::analytics::{HomeHabitAnalyzer, HabitSummary};
 
 #[test]
 fn test_analyze_lights() {
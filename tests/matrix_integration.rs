@@ -1,3 +1,5 @@
+#![cfg(feature = "channel-matrix")]
+
 use zeroclaw::channels::matrix::MatrixChannel;
 use zeroclaw::channels::traits::Channel;
 
@@ -7,7 +9,10 @@ fn test_matrix_channel_construction() {
         "https://matrix.org".to_string(),
         "test_token".to_string(),
         "!room:matrix.org".to_string(),
-        vec!["@husband:matrix.org".to_string(), "@wife:matrix.org".to_string()],
+        vec![
+            "@husband:matrix.org".to_string(),
+            "@wife:matrix.org".to_string(),
+        ],
     );
     assert_eq!(channel.name(), "matrix");
 }
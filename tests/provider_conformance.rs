@@ -0,0 +1,138 @@
+//! Provider contract suite replayed from hand-written cassettes.
+//!
+//! Each cassette in `tests/fixtures/cassettes/` describes the traffic of one
+//! suite run. The cassettes are written by hand from each provider's
+//! documented request and response formats, not captured from live APIs;
+//! `zeroclaw providers verify <name> --record <file>` captures real traffic
+//! to replace one. Replaying checks that the provider implementation still
+//! sends the documented requests and handles chat, tool calls, vision,
+//! streaming and errors the same way.
+
+use std::path::PathBuf;
+use zeroclaw::providers::cassette::{Cassette, CassetteServer};
+use zeroclaw::providers::conformance::{self, CaseStatus, EXCLUDED_PROVIDERS, REPLAY_PROVIDERS};
+
+/// Provider implementations that wrap other providers rather than speak a
+/// wire format of their own.
+const WRAPPERS: [&str; 2] = ["reliable", "router"];
+
+fn cassette_path(provider: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cassettes")
+        .join(format!("{provider}.json"))
+}
+
+async fn replay(provider: &str) -> Vec<(&'static str, CaseStatus)> {
+    let cassette = Cassette::load(&cassette_path(provider)).unwrap();
+    assert_eq!(cassette.provider, provider);
+
+    let server = CassetteServer::replay(&cassette).await.unwrap();
+    let under_test =
+        conformance::provider_at(provider, server.base_url(), Some("test-key")).unwrap();
+    let report = conformance::run_suite_with(under_test.as_ref(), &cassette.model, |case| {
+        server.begin_case(case);
+    })
+    .await;
+
+    assert!(
+        report.passed(),
+        "{provider} conformance failures:\n{}",
+        report.failures().join("\n")
+    );
+    server.verify().unwrap();
+    report
+        .outcomes
+        .into_iter()
+        .map(|outcome| (outcome.case, outcome.status))
+        .collect()
+}
+
+#[test]
+fn every_replay_provider_has_a_cassette() {
+    for provider in REPLAY_PROVIDERS {
+        assert!(
+            cassette_path(provider).exists(),
+            "missing cassette for {provider}"
+        );
+    }
+}
+
+/// Every module under `src/providers/` that implements `Provider` is either
+/// replayed, listed in `EXCLUDED_PROVIDERS`, or a wrapper.
+#[test]
+fn every_provider_implementation_is_replayed_or_excluded() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/providers");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap();
+        if !source
+            .lines()
+            .any(|line| line.starts_with("impl Provider for"))
+        {
+            continue;
+        }
+        let module = path.file_stem().unwrap().to_str().unwrap();
+        assert!(
+            REPLAY_PROVIDERS.contains(&module)
+                || EXCLUDED_PROVIDERS.iter().any(|(name, _)| *name == module)
+                || WRAPPERS.contains(&module),
+            "{module} implements Provider but is neither replayed nor excluded"
+        );
+    }
+}
+
+#[tokio::test]
+async fn openai_conformance() {
+    let outcomes = replay("openai").await;
+    assert!(outcomes.contains(&("vision", CaseStatus::Skipped("no vision support".into()))));
+}
+
+#[tokio::test]
+async fn anthropic_conformance() {
+    let outcomes = replay("anthropic").await;
+    assert!(outcomes
+        .iter()
+        .all(|(_, status)| *status == CaseStatus::Passed));
+}
+
+#[tokio::test]
+async fn ollama_conformance() {
+    let outcomes = replay("ollama").await;
+    assert!(outcomes
+        .iter()
+        .all(|(_, status)| *status == CaseStatus::Passed));
+}
+
+#[tokio::test]
+async fn openrouter_conformance() {
+    let outcomes = replay("openrouter").await;
+    assert!(outcomes
+        .iter()
+        .all(|(_, status)| *status == CaseStatus::Passed));
+}
+
+#[tokio::test]
+async fn openai_compatible_conformance() {
+    let outcomes = replay("compatible").await;
+    assert!(outcomes
+        .iter()
+        .all(|(_, status)| *status == CaseStatus::Passed));
+}
+
+#[tokio::test]
+async fn gemini_conformance() {
+    let outcomes = replay("gemini").await;
+    assert!(outcomes.contains(&(
+        "tool_call",
+        CaseStatus::Skipped("no native tool calling".into())
+    )));
+    assert!(outcomes.contains(&("vision", CaseStatus::Skipped("no vision support".into()))));
+}
+
+#[tokio::test]
+async fn bedrock_conformance() {
+    let outcomes = replay("bedrock").await;
+    assert!(outcomes
+        .iter()
+        .all(|(_, status)| *status == CaseStatus::Passed));
+}